@@ -0,0 +1,36 @@
+-- Create recurring activity series and their materialized occurrences
+CREATE TABLE IF NOT EXISTS recurring_series (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pet_id INTEGER NOT NULL,
+    category VARCHAR(20) NOT NULL CHECK (category IN ('health', 'growth', 'diet', 'lifestyle', 'expense')),
+    subcategory VARCHAR(100) NOT NULL,
+    activity_data TEXT, -- JSON template copied into each generated activity
+    rrule VARCHAR(255) NOT NULL,
+    start_date DATE NOT NULL,
+    materialize_days_ahead INTEGER NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'active' CHECK (status IN ('active', 'paused', 'cancelled')),
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE
+);
+
+-- One row per occurrence that has been generated, skipped or edited ahead of time
+CREATE TABLE IF NOT EXISTS recurring_occurrences (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    series_id INTEGER NOT NULL,
+    occurrence_date DATE NOT NULL,
+    activity_id INTEGER,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('materialized', 'skipped', 'overridden')),
+    override_data TEXT, -- JSON ActivityUpdateRequest applied when the occurrence is generated
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (series_id, occurrence_date),
+    FOREIGN KEY (series_id) REFERENCES recurring_series(id) ON DELETE CASCADE,
+    FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_recurring_series_pet_id ON recurring_series(pet_id);
+CREATE INDEX IF NOT EXISTS idx_recurring_series_status ON recurring_series(status);
+CREATE INDEX IF NOT EXISTS idx_recurring_occurrences_series_id ON recurring_occurrences(series_id);
@@ -0,0 +1,11 @@
+-- Generated columns used for sorting activities
+-- activity_time: time block date normalized to UTC, falling back to created_at
+ALTER TABLE activities ADD COLUMN activity_time TEXT
+    GENERATED ALWAYS AS (COALESCE(datetime(json_extract(activity_data, '$.time.date')), datetime(created_at))) VIRTUAL;
+
+-- cost_amount: amount from the cost block, if any
+ALTER TABLE activities ADD COLUMN cost_amount REAL
+    GENERATED ALWAYS AS (json_extract(activity_data, '$.cost.amount')) VIRTUAL;
+
+CREATE INDEX IF NOT EXISTS idx_activities_pet_activity_time ON activities(pet_id, activity_time);
+CREATE INDEX IF NOT EXISTS idx_activities_pet_cost_amount ON activities(pet_id, cost_amount);
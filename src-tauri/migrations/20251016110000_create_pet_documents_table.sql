@@ -0,0 +1,22 @@
+-- Create pet documents table (adoption papers, pedigree certificates, insurance policies...)
+CREATE TABLE IF NOT EXISTS pet_documents (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pet_id INTEGER NOT NULL,
+    category VARCHAR(20) NOT NULL CHECK (category IN ('adoption', 'pedigree', 'insurance', 'medical', 'registration', 'other')),
+    title VARCHAR(200) NOT NULL,
+    original_filename VARCHAR(255) NOT NULL,
+    stored_filename VARCHAR(255) NOT NULL, -- content-addressed: <sha256>.<ext>
+    mime_type VARCHAR(100) NOT NULL,
+    file_size INTEGER NOT NULL,
+    sha256 CHAR(64) NOT NULL,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (pet_id, sha256),
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_pet_documents_pet_id ON pet_documents(pet_id);
+CREATE INDEX IF NOT EXISTS idx_pet_documents_category ON pet_documents(category);
+CREATE INDEX IF NOT EXISTS idx_pet_documents_stored_filename ON pet_documents(stored_filename);
@@ -0,0 +1,29 @@
+-- Text recognized in uploaded documents (OCR output or plain text content)
+ALTER TABLE pet_documents ADD COLUMN ocr_text TEXT;
+
+-- Create FTS (Full-Text Search) virtual table for documents
+CREATE VIRTUAL TABLE IF NOT EXISTS pet_documents_fts USING fts5(
+    title,
+    notes,
+    ocr_text,
+    content='pet_documents',
+    content_rowid='id'
+);
+
+-- Create triggers to keep FTS table synchronized with pet_documents table
+CREATE TRIGGER IF NOT EXISTS pet_documents_fts_insert AFTER INSERT ON pet_documents BEGIN
+    INSERT INTO pet_documents_fts(rowid, title, notes, ocr_text)
+    VALUES (new.id, new.title, new.notes, new.ocr_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS pet_documents_fts_delete AFTER DELETE ON pet_documents BEGIN
+    INSERT INTO pet_documents_fts(pet_documents_fts, rowid, title, notes, ocr_text)
+    VALUES ('delete', old.id, old.title, old.notes, old.ocr_text);
+END;
+
+CREATE TRIGGER IF NOT EXISTS pet_documents_fts_update AFTER UPDATE ON pet_documents BEGIN
+    INSERT INTO pet_documents_fts(pet_documents_fts, rowid, title, notes, ocr_text)
+    VALUES ('delete', old.id, old.title, old.notes, old.ocr_text);
+    INSERT INTO pet_documents_fts(rowid, title, notes, ocr_text)
+    VALUES (new.id, new.title, new.notes, new.ocr_text);
+END;
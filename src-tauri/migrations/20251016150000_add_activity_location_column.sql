@@ -0,0 +1,5 @@
+-- location_key: normalized place name from the location block, used to group visits
+ALTER TABLE activities ADD COLUMN location_key TEXT
+    GENERATED ALWAYS AS (NULLIF(lower(trim(json_extract(activity_data, '$.location.name'))), '')) VIRTUAL;
+
+CREATE INDEX IF NOT EXISTS idx_activities_pet_location_key ON activities(pet_id, location_key);
@@ -0,0 +1,14 @@
+-- Journal of stored files that must be removed after their owning rows were deleted.
+-- Entries are written in the same transaction as the row deletion and removed once
+-- the file is gone, so a failed or interrupted cleanup can be retried later.
+CREATE TABLE IF NOT EXISTS file_deletion_journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    storage VARCHAR(20) NOT NULL CHECK (storage IN ('photos')),
+    file_name TEXT NOT NULL,
+    source_activity_id INTEGER,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_file_deletion_journal_created_at ON file_deletion_journal(created_at);
@@ -0,0 +1,32 @@
+-- Stock of consumables (food, litter, medication). pet_id NULL means shared by all pets.
+CREATE TABLE IF NOT EXISTS inventory_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pet_id INTEGER,
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('food', 'litter', 'medication')),
+    name VARCHAR(100) NOT NULL,
+    brand VARCHAR(100),
+    unit VARCHAR(20) NOT NULL,
+    quantity REAL NOT NULL DEFAULT 0,
+    low_stock_threshold REAL NOT NULL DEFAULT 0,
+    -- Set once a low-stock notification was sent; cleared when restocked above the threshold
+    low_stock_notified INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE
+);
+
+-- Every stock change; activity_id links consumption to the portion/dose activity that caused it
+CREATE TABLE IF NOT EXISTS inventory_movements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    item_id INTEGER NOT NULL,
+    activity_id INTEGER,
+    delta REAL NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (item_id) REFERENCES inventory_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_inventory_items_pet_id ON inventory_items(pet_id);
+CREATE INDEX IF NOT EXISTS idx_inventory_movements_item_id ON inventory_movements(item_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_inventory_movements_activity_id ON inventory_movements(activity_id);
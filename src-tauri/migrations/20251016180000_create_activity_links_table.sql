@@ -0,0 +1,13 @@
+-- Links an expense activity to the health activity it paid for.
+-- Each pair is stored once with the expense as activity_id and read in both directions.
+CREATE TABLE IF NOT EXISTS activity_links (
+    activity_id INTEGER NOT NULL,
+    related_activity_id INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (activity_id, related_activity_id),
+    FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE CASCADE,
+    FOREIGN KEY (related_activity_id) REFERENCES activities(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_links_related ON activity_links(related_activity_id);
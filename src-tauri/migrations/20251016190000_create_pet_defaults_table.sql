@@ -0,0 +1,16 @@
+-- Values a pet's activities recently used, per subcategory and field, to prefill templates.
+-- One row per distinct value, so both the latest and the most used value can be found.
+CREATE TABLE IF NOT EXISTS pet_defaults (
+    pet_id INTEGER NOT NULL,
+    -- Lowercased, trimmed subcategory
+    subcategory VARCHAR(50) NOT NULL,
+    -- Block key (portion, timer, ...) or a derived field such as brand
+    field VARCHAR(50) NOT NULL,
+    -- JSON of the value
+    value TEXT NOT NULL,
+    use_count INTEGER NOT NULL DEFAULT 1,
+    last_used_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    PRIMARY KEY (pet_id, subcategory, field, value),
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE
+);
@@ -0,0 +1,7 @@
+-- Old names of photo files renamed to their content hash, so links saved
+-- outside the database (exports, cached URLs) still resolve to the photo.
+CREATE TABLE IF NOT EXISTS photo_redirects (
+    old_name TEXT PRIMARY KEY,
+    new_name TEXT NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,5 @@
+-- needs_review: machine-created activities (imports, recurring series, quick entries)
+-- stay out of the timeline until the user approves them
+ALTER TABLE activities ADD COLUMN needs_review BOOLEAN NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_activities_needs_review ON activities(pet_id) WHERE needs_review = 1;
@@ -0,0 +1,13 @@
+-- Per-pet overrides for notifications; pets without a row use the defaults
+CREATE TABLE IF NOT EXISTS pet_notification_preferences (
+    pet_id INTEGER PRIMARY KEY,
+    -- Don't announce this pet's reminders at all, e.g. after rehoming
+    reminders_muted BOOLEAN NOT NULL DEFAULT 0,
+    -- Local HH:MM times; reminders due in between wait until the end. May wrap past midnight.
+    quiet_hours_start VARCHAR(5),
+    quiet_hours_end VARCHAR(5),
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    CHECK ((quiet_hours_start IS NULL) = (quiet_hours_end IS NULL)),
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE
+);
@@ -0,0 +1,15 @@
+-- Growth milestones reached by a pet; the catalogue of milestones lives in the backend
+CREATE TABLE IF NOT EXISTS pet_milestones (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pet_id INTEGER NOT NULL,
+    milestone_key VARCHAR(50) NOT NULL,
+    completed_on DATE NOT NULL,
+    notes TEXT,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE,
+    UNIQUE (pet_id, milestone_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pet_milestones_pet_id ON pet_milestones(pet_id);
@@ -0,0 +1,12 @@
+-- Alternate names and nicknames of a pet, in any script; used by search and quick entry
+CREATE TABLE IF NOT EXISTS pet_aliases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pet_id INTEGER NOT NULL,
+    alias VARCHAR(100) NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE,
+    UNIQUE (pet_id, alias)
+);
+
+CREATE INDEX IF NOT EXISTS idx_pet_aliases_pet_id ON pet_aliases(pet_id);
@@ -0,0 +1,4 @@
+-- Memorial mode: a pet that passed away is archived with a memorial flag and the
+-- date it died, so its history stays browsable after it leaves the active list
+ALTER TABLE pets ADD COLUMN is_memorial BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE pets ADD COLUMN passed_away_on DATE;
@@ -0,0 +1,4 @@
+-- expires_on: renewal date of insurance policies, registrations and passports
+ALTER TABLE pet_documents ADD COLUMN expires_on DATE;
+
+CREATE INDEX IF NOT EXISTS idx_pet_documents_expires_on ON pet_documents(expires_on) WHERE expires_on IS NOT NULL;
@@ -0,0 +1,41 @@
+-- Activity counts per pet and category, kept current by triggers so pagination
+-- doesn't scan the activities table on every page
+CREATE TABLE IF NOT EXISTS activity_counts (
+    pet_id INTEGER NOT NULL,
+    category VARCHAR(20) NOT NULL,
+    total INTEGER NOT NULL DEFAULT 0,
+    needs_review INTEGER NOT NULL DEFAULT 0,
+
+    PRIMARY KEY (pet_id, category)
+);
+
+INSERT OR REPLACE INTO activity_counts (pet_id, category, total, needs_review)
+SELECT pet_id, category, COUNT(*), COALESCE(SUM(needs_review), 0)
+FROM activities
+GROUP BY pet_id, category;
+
+CREATE TRIGGER IF NOT EXISTS activity_counts_insert AFTER INSERT ON activities BEGIN
+    INSERT INTO activity_counts (pet_id, category, total, needs_review)
+    VALUES (new.pet_id, new.category, 1, new.needs_review)
+    ON CONFLICT (pet_id, category) DO UPDATE SET
+        total = total + 1,
+        needs_review = needs_review + excluded.needs_review;
+END;
+
+CREATE TRIGGER IF NOT EXISTS activity_counts_delete AFTER DELETE ON activities BEGIN
+    UPDATE activity_counts
+    SET total = total - 1, needs_review = needs_review - old.needs_review
+    WHERE pet_id = old.pet_id AND category = old.category;
+END;
+
+CREATE TRIGGER IF NOT EXISTS activity_counts_update
+AFTER UPDATE OF pet_id, category, needs_review ON activities BEGIN
+    UPDATE activity_counts
+    SET total = total - 1, needs_review = needs_review - old.needs_review
+    WHERE pet_id = old.pet_id AND category = old.category;
+    INSERT INTO activity_counts (pet_id, category, total, needs_review)
+    VALUES (new.pet_id, new.category, 1, new.needs_review)
+    ON CONFLICT (pet_id, category) DO UPDATE SET
+        total = total + 1,
+        needs_review = needs_review + excluded.needs_review;
+END;
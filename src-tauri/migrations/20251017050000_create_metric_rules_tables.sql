@@ -0,0 +1,46 @@
+-- Rate-of-change alert rules. pet_id NULL means the rule applies to every pet.
+CREATE TABLE IF NOT EXISTS metric_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    pet_id INTEGER,
+    metric VARCHAR(20) NOT NULL
+        CHECK (metric IN ('weight', 'height', 'temperature', 'food_intake', 'water_intake')),
+    window_days INTEGER NOT NULL CHECK (window_days > 0),
+    -- Percent of the baseline when percent = 1, else in the metric's unit
+    threshold REAL NOT NULL CHECK (threshold > 0),
+    percent INTEGER NOT NULL DEFAULT 1,
+    direction VARCHAR(10) NOT NULL CHECK (direction IN ('rise', 'drop', 'either')),
+    enabled INTEGER NOT NULL DEFAULT 1,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE
+);
+
+-- Changes that broke a rule; one per rule, pet and day
+CREATE TABLE IF NOT EXISTS metric_alerts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    rule_id INTEGER NOT NULL,
+    pet_id INTEGER NOT NULL,
+    activity_id INTEGER,
+    metric VARCHAR(20) NOT NULL,
+    observed_on DATE NOT NULL,
+    baseline REAL NOT NULL,
+    value REAL NOT NULL,
+    change REAL NOT NULL,
+    change_percent REAL,
+    -- Set once the alert was announced to the UI
+    notified INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    UNIQUE (rule_id, pet_id, observed_on),
+    FOREIGN KEY (rule_id) REFERENCES metric_rules(id) ON DELETE CASCADE,
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE,
+    FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_metric_rules_pet_id ON metric_rules(pet_id);
+CREATE INDEX IF NOT EXISTS idx_metric_alerts_pet_id ON metric_alerts(pet_id, observed_on);
+
+-- Weight alert every pet starts with: a 10% change within 30 days
+INSERT INTO metric_rules (pet_id, metric, window_days, threshold, percent, direction)
+VALUES (NULL, 'weight', 30, 10, 1, 'either');
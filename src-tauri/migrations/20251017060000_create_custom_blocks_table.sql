@@ -0,0 +1,12 @@
+-- User-defined block types. Instances live in activity_data as
+-- { "customBlock": <name>, "values": { <field key>: <value> } } and are checked
+-- against the definition when activities are saved.
+CREATE TABLE IF NOT EXISTS custom_blocks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name VARCHAR(40) NOT NULL UNIQUE,
+    label VARCHAR(100) NOT NULL,
+    -- JSON array of field definitions
+    fields TEXT NOT NULL DEFAULT '[]',
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,16 @@
+-- Differences found by schema validation between an activity's raw JSON and what
+-- the typed blocks and generated columns make of it. One row per activity and field.
+CREATE TABLE IF NOT EXISTS schema_discrepancies (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    activity_id INTEGER NOT NULL,
+    -- Generated column name, or "block.<key>" for a typed block
+    field TEXT NOT NULL,
+    legacy_value TEXT,
+    new_value TEXT,
+    occurrences INTEGER NOT NULL DEFAULT 1,
+    first_seen_at TIMESTAMP NOT NULL,
+    last_seen_at TIMESTAMP NOT NULL,
+
+    UNIQUE (activity_id, field),
+    FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE CASCADE
+);
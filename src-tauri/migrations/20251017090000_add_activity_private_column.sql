@@ -0,0 +1,3 @@
+-- is_private: activity kept out of everything shared (exports, webhooks, handoff
+-- reports, vaccination cards) and, if the privacy settings say so, out of search
+ALTER TABLE activities ADD COLUMN is_private BOOLEAN NOT NULL DEFAULT 0;
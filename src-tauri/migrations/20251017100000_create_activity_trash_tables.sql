@@ -0,0 +1,31 @@
+-- Activities removed by a bulk delete, kept until their batch is restored or purged.
+-- Rows keep their original id (activity ids are never reused), so a restore puts them
+-- back where links, attachments and journal entries expect them.
+CREATE TABLE IF NOT EXISTS activity_trash (
+    id INTEGER PRIMARY KEY,
+    batch_token TEXT NOT NULL,
+    pet_id INTEGER NOT NULL,
+    category VARCHAR(20) NOT NULL,
+    subcategory VARCHAR(100) NOT NULL,
+    activity_data TEXT,
+    needs_review BOOLEAN NOT NULL DEFAULT 0,
+    is_private BOOLEAN NOT NULL DEFAULT 0,
+    created_at TIMESTAMP,
+    updated_at TIMESTAMP,
+    deleted_at TIMESTAMP NOT NULL,
+
+    FOREIGN KEY (pet_id) REFERENCES pets(id) ON DELETE CASCADE
+);
+
+-- Links of trashed activities, which the activities' deletion cascaded away
+CREATE TABLE IF NOT EXISTS activity_trash_links (
+    batch_token TEXT NOT NULL,
+    activity_id INTEGER NOT NULL,
+    related_activity_id INTEGER NOT NULL,
+    created_at TIMESTAMP,
+
+    PRIMARY KEY (batch_token, activity_id, related_activity_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_trash_batch ON activity_trash(batch_token);
+CREATE INDEX IF NOT EXISTS idx_activity_trash_deleted_at ON activity_trash(deleted_at);
@@ -0,0 +1,23 @@
+-- Activities logged to complete the reminder block of another activity, so
+-- compliance reports can tell reminders that were done from those skipped.
+-- A reminder is completed by at most one activity; one activity may complete several.
+CREATE TABLE IF NOT EXISTS reminder_completions (
+    reminder_activity_id INTEGER PRIMARY KEY,
+    activity_id INTEGER NOT NULL,
+    created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+
+    FOREIGN KEY (reminder_activity_id) REFERENCES activities(id) ON DELETE CASCADE,
+    FOREIGN KEY (activity_id) REFERENCES activities(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_reminder_completions_activity ON reminder_completions(activity_id);
+
+-- Completions of trashed activities, which the activities' deletion cascaded away
+CREATE TABLE IF NOT EXISTS activity_trash_reminder_completions (
+    batch_token TEXT NOT NULL,
+    reminder_activity_id INTEGER NOT NULL,
+    activity_id INTEGER NOT NULL,
+    created_at TIMESTAMP,
+
+    PRIMARY KEY (batch_token, reminder_activity_id)
+);
@@ -0,0 +1,852 @@
+use crate::database::activity_data::{ActivityData, ActivityDataExt, BlockData};
+use crate::database::{Activity, ActivityCategory, Pet, RecurringSeries, RecurringSeriesStatus};
+use crate::errors::PetError;
+use crate::pdf::{self, Font, PageContent};
+use crate::privacy::PrivacySettings;
+use crate::recurrence::{Frequency, RecurrenceRule};
+use chrono::{Days, NaiveDate, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Length of the stay when no end date is given
+pub const DEFAULT_STAY_DAYS: u64 = 7;
+
+/// Longest stay a handoff covers
+pub const MAX_STAY_DAYS: i64 = 90;
+
+/// Days of logs before the report date that the feeding routine is read from
+const HISTORY_DAYS: u64 = 14;
+
+/// Times a meal or dose must have been logged in the history to count as routine
+const MIN_ROUTINE_LOGS: usize = 2;
+
+/// Tag in an activity's notes that marks it as something the sitter should know
+pub const CARE_TAG: &str = "#care";
+
+/// A4 page in PDF points
+const PAGE_WIDTH_PT: f32 = 595.28;
+const PAGE_HEIGHT_PT: f32 = 841.89;
+const MARGIN_PT: f32 = 56.0;
+
+/// Characters per line of body text before it wraps
+const MAX_LINE_CHARS: usize = 90;
+
+/// File format of a generated handoff
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum HandoffFormat {
+    #[default]
+    Markdown,
+    Pdf,
+}
+
+impl HandoffFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            HandoffFormat::Markdown => "md",
+            HandoffFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Someone the sitter can call. Contacts are not part of the pet profile, so the
+/// caller supplies them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmergencyContact {
+    pub name: String,
+    pub phone: String,
+    /// E.g. "Owner" or "Vet"
+    pub role: Option<String>,
+}
+
+/// Stay covered by a handoff and what to print besides the pet's own records
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct CareHandoffOptions {
+    /// First day of the stay; today when omitted
+    pub start_date: Option<NaiveDate>,
+    /// Last day of the stay; a week after the start when omitted
+    pub end_date: Option<NaiveDate>,
+    pub contacts: Vec<EmergencyContact>,
+    pub format: HandoffFormat,
+}
+
+impl CareHandoffOptions {
+    /// First and last day of the stay, checked
+    pub fn stay(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), PetError> {
+        let start = self.start_date.unwrap_or(today);
+        let end = match self.end_date {
+            Some(end) => end,
+            None => start
+                .checked_add_days(Days::new(DEFAULT_STAY_DAYS - 1))
+                .unwrap_or(start),
+        };
+        if end < start {
+            return Err(PetError::validation(
+                "end_date",
+                "The stay must end on or after its first day",
+            ));
+        }
+        if (end - start).num_days() >= MAX_STAY_DAYS {
+            return Err(PetError::validation(
+                "end_date".to_string(),
+                format!("A handoff covers at most {MAX_STAY_DAYS} days"),
+            ));
+        }
+        for contact in &self.contacts {
+            if contact.name.trim().is_empty() || contact.phone.trim().is_empty() {
+                return Err(PetError::validation(
+                    "contacts",
+                    "Each emergency contact needs a name and a phone number",
+                ));
+            }
+        }
+        Ok((start, end))
+    }
+}
+
+/// A meal the pet usually gets
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FeedingPlanEntry {
+    /// Usual time, to the half hour; None when the logs carry no time
+    pub time: Option<NaiveTime>,
+    pub subcategory: String,
+    pub amount: f32,
+    pub unit: String,
+    pub brand: Option<String>,
+    pub product: Option<String>,
+    /// Times it was logged in the two weeks before the report
+    pub times_logged: usize,
+}
+
+/// A medication to give during the stay
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MedicationEntry {
+    pub name: String,
+    /// E.g. "1 tablet"
+    pub dose: Option<String>,
+    pub time: Option<NaiveTime>,
+    /// E.g. "Daily" or "Given 6 times in the last 14 days"
+    pub schedule: String,
+    /// Days in the stay a scheduled dose falls on; empty for doses that were only logged
+    pub dates: Vec<NaiveDate>,
+}
+
+/// What goes into a handoff
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CareSheet {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub feeding_plan: Vec<FeedingPlanEntry>,
+    pub medications: Vec<MedicationEntry>,
+    /// Notes tagged `#care`, newest first, without the tag
+    pub quirks: Vec<String>,
+}
+
+/// A generated handoff document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CareHandoff {
+    pub pet_id: i64,
+    /// Path of the document in the export directory
+    pub file_path: String,
+    pub format: HandoffFormat,
+    pub contacts: Vec<EmergencyContact>,
+    pub sheet: CareSheet,
+}
+
+/// Gather the feeding routine and medications from the logs of the two weeks before
+/// `today`, the active medication series with doses in the stay, and the care notes.
+/// Private activities and categories hidden in `privacy` stay out of the handoff.
+pub fn care_sheet(
+    activities: &[Activity],
+    series: &[RecurringSeries],
+    (start_date, end_date): (NaiveDate, NaiveDate),
+    today: NaiveDate,
+    privacy: &PrivacySettings,
+) -> CareSheet {
+    let history_start = today
+        .checked_sub_days(Days::new(HISTORY_DAYS))
+        .unwrap_or(today);
+    let recent: Vec<&Activity> = activities
+        .iter()
+        .filter(|activity| !activity.needs_review && privacy.shares(activity))
+        .filter(|activity| (history_start..=today).contains(&activity.occurred_on()))
+        .collect();
+
+    let mut medications: Vec<MedicationEntry> = series
+        .iter()
+        .filter(|series| series.status == RecurringSeriesStatus::Active)
+        .filter(|series| privacy.is_shared(series.category))
+        .filter(|series| is_medication(&series.category, &series.subcategory))
+        .filter_map(|series| {
+            let rule: RecurrenceRule = series.rrule.parse().ok()?;
+            let dates = rule.occurrences_between(series.start_date, start_date, end_date);
+            if dates.is_empty() {
+                return None;
+            }
+            let data = series
+                .activity_data
+                .clone()
+                .map(ActivityData::from_legacy_json)
+                .unwrap_or_default();
+            Some(MedicationEntry {
+                name: medication_name(&data, &series.subcategory),
+                dose: dose_text(&data),
+                time: time_of_day(&data),
+                schedule: schedule_text(&rule),
+                dates,
+            })
+        })
+        .collect();
+
+    // Doses given regularly without a series, e.g. logged by hand each morning
+    let mut logged: HashMap<(String, Option<NaiveTime>), (MedicationEntry, usize)> = HashMap::new();
+    for activity in &recent {
+        if !is_medication(&activity.category, &activity.subcategory) {
+            continue;
+        }
+        let Some(data) = activity.activity_data.as_ref() else {
+            continue;
+        };
+        let name = medication_name(data, &activity.subcategory);
+        let time = time_of_day(data);
+        let entry = logged
+            .entry((name.to_lowercase(), time))
+            .or_insert_with(|| {
+                let entry = MedicationEntry {
+                    name,
+                    dose: dose_text(data),
+                    time,
+                    schedule: String::new(),
+                    dates: Vec::new(),
+                };
+                (entry, 0)
+            });
+        entry.1 += 1;
+    }
+    let unscheduled: Vec<MedicationEntry> = logged
+        .into_values()
+        .filter(|(entry, count)| {
+            *count >= MIN_ROUTINE_LOGS
+                && !medications
+                    .iter()
+                    .any(|scheduled| scheduled.name.eq_ignore_ascii_case(&entry.name))
+        })
+        .map(|(entry, count)| MedicationEntry {
+            schedule: format!("Given {count} times in the last {HISTORY_DAYS} days"),
+            ..entry
+        })
+        .collect();
+    medications.extend(unscheduled);
+    medications.sort_by(|a, b| by_time(a.time, b.time).then_with(|| a.name.cmp(&b.name)));
+
+    CareSheet {
+        start_date,
+        end_date,
+        feeding_plan: feeding_plan(&recent),
+        medications,
+        quirks: care_notes(activities, privacy),
+    }
+}
+
+/// Meals logged at least twice in the history with the same time, food and amount
+fn feeding_plan(recent: &[&Activity]) -> Vec<FeedingPlanEntry> {
+    let mut meals: HashMap<String, FeedingPlanEntry> = HashMap::new();
+    for activity in recent {
+        if activity.category != ActivityCategory::Diet
+            || activity.subcategory.eq_ignore_ascii_case("water")
+        {
+            continue;
+        }
+        let Some(data) = activity.activity_data.as_ref() else {
+            continue;
+        };
+        let Some(BlockData::Portion {
+            amount,
+            unit,
+            brand,
+            product,
+            ..
+        }) = data.get("portion")
+        else {
+            continue;
+        };
+
+        let time = time_of_day(data);
+        let clean = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let (brand, product) = (clean(brand), clean(product));
+        let key = format!(
+            "{time:?}|{}|{amount}|{}|{}|{}",
+            activity.subcategory.to_lowercase(),
+            unit.trim().to_lowercase(),
+            brand.as_deref().unwrap_or_default().to_lowercase(),
+            product.as_deref().unwrap_or_default().to_lowercase(),
+        );
+        meals
+            .entry(key)
+            .or_insert_with(|| FeedingPlanEntry {
+                time,
+                subcategory: activity.subcategory.clone(),
+                amount: *amount,
+                unit: unit.trim().to_string(),
+                brand,
+                product,
+                times_logged: 0,
+            })
+            .times_logged += 1;
+    }
+
+    let mut plan: Vec<FeedingPlanEntry> = meals
+        .into_values()
+        .filter(|meal| meal.times_logged >= MIN_ROUTINE_LOGS)
+        .collect();
+    plan.sort_by(|a, b| {
+        by_time(a.time, b.time)
+            .then_with(|| b.times_logged.cmp(&a.times_logged))
+            .then_with(|| a.subcategory.cmp(&b.subcategory))
+    });
+    plan
+}
+
+/// Notes tagged `#care` on any activity, newest first, without the tag
+fn care_notes(activities: &[Activity], privacy: &PrivacySettings) -> Vec<String> {
+    let mut tagged: Vec<(NaiveDate, i64, String)> = activities
+        .iter()
+        .filter(|activity| !activity.needs_review && privacy.shares(activity))
+        .filter_map(|activity| {
+            let Some(BlockData::Text(notes)) = activity.activity_data.as_ref()?.get("notes") else {
+                return None;
+            };
+            let at = notes
+                .as_bytes()
+                .windows(CARE_TAG.len())
+                .position(|window| window.eq_ignore_ascii_case(CARE_TAG.as_bytes()))?;
+            let note = format!("{}{}", &notes[..at], &notes[at + CARE_TAG.len()..]);
+            let note = note.split_whitespace().collect::<Vec<_>>().join(" ");
+            (!note.is_empty()).then(|| (activity.occurred_on(), activity.id, note))
+        })
+        .collect();
+    tagged.sort_by(|a, b| (b.0, b.1).cmp(&(a.0, a.1)));
+
+    let mut quirks: Vec<String> = Vec::with_capacity(tagged.len());
+    for (_, _, note) in tagged {
+        if !quirks.iter().any(|quirk| quirk.eq_ignore_ascii_case(&note)) {
+            quirks.push(note);
+        }
+    }
+    quirks
+}
+
+/// Health activities whose subcategory names a medication, as the inventory counts them
+fn is_medication(category: &ActivityCategory, subcategory: &str) -> bool {
+    *category == ActivityCategory::Health && subcategory.to_lowercase().contains("medication")
+}
+
+/// Title, else the portion's product or brand, else the subcategory
+fn medication_name(data: &ActivityData, subcategory: &str) -> String {
+    let title = match data.get("title") {
+        Some(BlockData::Text(title)) => Some(title.as_str()),
+        _ => None,
+    };
+    let (brand, product) = match data.get("portion") {
+        Some(BlockData::Portion { brand, product, .. }) => (brand.as_deref(), product.as_deref()),
+        _ => (None, None),
+    };
+    [title, product, brand]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|name| !name.is_empty())
+        .unwrap_or(subcategory)
+        .to_string()
+}
+
+fn dose_text(data: &ActivityData) -> Option<String> {
+    match data.get("portion") {
+        Some(BlockData::Portion { amount, unit, .. }) if *amount > 0.0 => {
+            Some(format!("{amount} {}", unit.trim()).trim().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Time of day from the time block, to the half hour: the entered time when there
+/// is one, else the time of the stored instant
+fn time_of_day(data: &ActivityData) -> Option<NaiveTime> {
+    let Some(BlockData::Time { date, time, .. }) = data.get("time") else {
+        return None;
+    };
+    let at = NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .ok()
+        .or_else(|| {
+            chrono::DateTime::parse_from_rfc3339(date)
+                .ok()
+                .map(|at| at.time())
+        })?;
+    let minutes = (at.hour() * 60 + at.minute() + 15) / 30 * 30 % (24 * 60);
+    NaiveTime::from_hms_opt(minutes / 60, minutes % 60, 0)
+}
+
+/// Entries with a time first, in time order
+fn by_time(a: Option<NaiveTime>, b: Option<NaiveTime>) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => std::cmp::Ordering::Equal,
+    }
+}
+
+fn schedule_text(rule: &RecurrenceRule) -> String {
+    let unit = match rule.freq {
+        Frequency::Daily => "day",
+        Frequency::Weekly => "week",
+        Frequency::Monthly => "month",
+        Frequency::Yearly => "year",
+    };
+    match (rule.interval, rule.freq) {
+        (1, Frequency::Daily) => "Daily".to_string(),
+        (1, _) => format!("Every {unit}"),
+        (interval, _) => format!("Every {interval} {unit}s"),
+    }
+}
+
+/// One line of the document, before it is laid out as Markdown or PDF
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    Title(String),
+    Subtitle(String),
+    Section(&'static str),
+    Item(String),
+    Empty(&'static str),
+    Footer(String),
+}
+
+fn document_lines(
+    pet: &Pet,
+    sheet: &CareSheet,
+    contacts: &[EmergencyContact],
+    printed_on: NaiveDate,
+) -> Vec<Line> {
+    let mut description = pet.species.to_string();
+    if let Some(breed) = pet
+        .breed
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    {
+        description.push_str(", ");
+        description.push_str(breed);
+    }
+    let mut lines = vec![
+        Line::Title(format!("Care handoff: {}", pet.name.trim())),
+        Line::Subtitle(format!(
+            "{description} \u{b7} Born {} \u{b7} Stay {} to {}",
+            pet.birth_date.format("%Y-%m-%d"),
+            sheet.start_date.format("%Y-%m-%d"),
+            sheet.end_date.format("%Y-%m-%d")
+        )),
+    ];
+
+    lines.push(Line::Section("Emergency contacts"));
+    if contacts.is_empty() {
+        lines.push(Line::Empty("None provided"));
+    }
+    for contact in contacts {
+        let role = contact
+            .role
+            .as_deref()
+            .map(str::trim)
+            .filter(|role| !role.is_empty())
+            .map(|role| format!(" ({role})"))
+            .unwrap_or_default();
+        lines.push(Line::Item(format!(
+            "{}{role}: {}",
+            contact.name.trim(),
+            contact.phone.trim()
+        )));
+    }
+
+    lines.push(Line::Section("Feeding plan"));
+    if sheet.feeding_plan.is_empty() {
+        lines.push(Line::Empty("No regular meals logged in the last two weeks"));
+    }
+    for meal in &sheet.feeding_plan {
+        let food = [meal.brand.as_deref(), meal.product.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut text = format!(
+            "{}{}: {} {}",
+            time_prefix(meal.time),
+            meal.subcategory,
+            meal.amount,
+            meal.unit
+        );
+        if !food.is_empty() {
+            text.push_str(&format!(" of {food}"));
+        }
+        lines.push(Line::Item(text));
+    }
+
+    lines.push(Line::Section("Medications"));
+    if sheet.medications.is_empty() {
+        lines.push(Line::Empty("None"));
+    }
+    for medication in &sheet.medications {
+        let mut text = format!("{}{}", time_prefix(medication.time), medication.name);
+        if let Some(dose) = &medication.dose {
+            text.push_str(&format!(", {dose}"));
+        }
+        text.push_str(&format!(" \u{b7} {}", medication.schedule));
+        match medication.dates.as_slice() {
+            [] => {}
+            [day] => text.push_str(&format!(" \u{b7} on {}", day.format("%b %-d"))),
+            days => text.push_str(&format!(
+                " \u{b7} {} doses from {} to {}",
+                days.len(),
+                days[0].format("%b %-d"),
+                days[days.len() - 1].format("%b %-d")
+            )),
+        }
+        lines.push(Line::Item(text));
+    }
+
+    lines.push(Line::Section("Quirks"));
+    if sheet.quirks.is_empty() {
+        lines.push(Line::Empty("Nothing noted"));
+    }
+    lines.extend(sheet.quirks.iter().cloned().map(Line::Item));
+
+    lines.push(Line::Footer(format!(
+        "Prepared {}",
+        printed_on.format("%Y-%m-%d")
+    )));
+    lines
+}
+
+fn time_prefix(time: Option<NaiveTime>) -> String {
+    time.map(|time| format!("{} \u{2013} ", time.format("%H:%M")))
+        .unwrap_or_default()
+}
+
+/// Render the handoff as a Markdown document
+pub fn render_markdown(
+    pet: &Pet,
+    sheet: &CareSheet,
+    contacts: &[EmergencyContact],
+    printed_on: NaiveDate,
+) -> String {
+    let mut markdown = String::new();
+    for line in document_lines(pet, sheet, contacts, printed_on) {
+        match line {
+            Line::Title(text) => markdown.push_str(&format!("# {text}\n\n")),
+            Line::Subtitle(text) => markdown.push_str(&format!("{text}\n")),
+            Line::Section(text) => markdown.push_str(&format!("\n## {text}\n\n")),
+            Line::Item(text) => markdown.push_str(&format!("- {text}\n")),
+            Line::Empty(text) => markdown.push_str(&format!("_{text}_\n")),
+            Line::Footer(text) => markdown.push_str(&format!("\n---\n\n_{text}_\n")),
+        }
+    }
+    markdown
+}
+
+/// Render the handoff as an A4 PDF, continuing on further pages as needed
+pub fn render_pdf(
+    pet: &Pet,
+    sheet: &CareSheet,
+    contacts: &[EmergencyContact],
+    printed_on: NaiveDate,
+) -> Vec<u8> {
+    let top = PAGE_HEIGHT_PT - MARGIN_PT;
+    let mut pages = vec![PageContent::default()];
+    let mut y = top;
+    let write = |pages: &mut Vec<PageContent>, y: &mut f32, font, size: f32, x, text: &str| {
+        let height = size * 1.45;
+        if *y - height < MARGIN_PT {
+            pages.push(PageContent::default());
+            *y = top;
+        }
+        *y -= height;
+        if let Some(page) = pages.last_mut() {
+            page.text(font, size, x, *y, text);
+        }
+    };
+
+    for line in document_lines(pet, sheet, contacts, printed_on) {
+        match line {
+            Line::Title(text) => {
+                write(&mut pages, &mut y, Font::Bold, 18.0, MARGIN_PT, &text);
+            }
+            Line::Subtitle(text) => {
+                write(&mut pages, &mut y, Font::Regular, 10.0, MARGIN_PT, &text);
+            }
+            Line::Section(text) => {
+                y -= 8.0;
+                write(&mut pages, &mut y, Font::Bold, 13.0, MARGIN_PT, text);
+                if let Some(page) = pages.last_mut() {
+                    page.rule(MARGIN_PT, PAGE_WIDTH_PT - MARGIN_PT, y - 3.0);
+                }
+                y -= 4.0;
+            }
+            Line::Item(text) => {
+                for (i, part) in wrap(&text, MAX_LINE_CHARS - 2).iter().enumerate() {
+                    let bullet = if i == 0 { "\u{b7} " } else { "  " };
+                    write(
+                        &mut pages,
+                        &mut y,
+                        Font::Regular,
+                        10.0,
+                        MARGIN_PT,
+                        &format!("{bullet}{part}"),
+                    );
+                }
+            }
+            Line::Empty(text) => {
+                write(&mut pages, &mut y, Font::Regular, 10.0, MARGIN_PT, text);
+            }
+            Line::Footer(text) => {
+                y -= 12.0;
+                write(&mut pages, &mut y, Font::Regular, 8.0, MARGIN_PT, &text);
+            }
+        }
+    }
+
+    pdf::document(&pages, PAGE_WIDTH_PT, PAGE_HEIGHT_PT)
+}
+
+/// Split text into lines of at most `width` characters at spaces; longer words are
+/// left whole
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{PetGender, PetSpecies, WeightUnit};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn pet() -> Pet {
+        Pet {
+            id: 1,
+            name: "Mochi".to_string(),
+            birth_date: NaiveDate::from_ymd_opt(2022, 4, 1).unwrap(),
+            species: PetSpecies::Cat,
+            gender: PetGender::Female,
+            breed: None,
+            color: None,
+            weight_kg: None,
+            photo_path: None,
+            notes: None,
+            display_order: 0,
+            is_archived: false,
+            is_memorial: false,
+            passed_away_on: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_weight: None,
+            display_unit: WeightUnit::Kg,
+        }
+    }
+
+    fn activity(
+        id: i64,
+        category: ActivityCategory,
+        subcategory: &str,
+        data: serde_json::Value,
+    ) -> Activity {
+        Activity {
+            id,
+            pet_id: 1,
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(ActivityData::from_legacy_json(data)),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_care_sheet_from_logs_and_series() {
+        let time = |day: u32, at: &str| json!({ "date": format!("2025-06-{day:02}T{at}:00.000Z"), "time": "", "timezone": "" });
+        let mut activities = Vec::new();
+        for day in 1..=5 {
+            activities.push(activity(
+                day,
+                ActivityCategory::Diet,
+                "Breakfast",
+                json!({
+                    "time": time(day as u32, "08:10"),
+                    "portion": { "amount": 80, "unit": "g", "portionType": "meal", "brand": "Orijen", "product": null }
+                }),
+            ));
+        }
+        activities.push(activity(
+            10,
+            ActivityCategory::Diet,
+            "Treat",
+            json!({
+                "time": time(3, "15:00"),
+                "portion": { "amount": 2, "unit": "pieces", "portionType": "treat", "product": null }
+            }),
+        ));
+        for day in [2, 4] {
+            activities.push(activity(
+                20 + day,
+                ActivityCategory::Health,
+                "Medication",
+                json!({
+                    "time": time(day as u32, "19:00"),
+                    "title": "Fish oil",
+                    "portion": { "amount": 1, "unit": "pump", "portionType": "dose", "product": null }
+                }),
+            ));
+        }
+        activities.push(activity(
+            30,
+            ActivityCategory::Lifestyle,
+            "Play",
+            json!({ "time": time(1, "12:00"), "notes": "Hides under the bed during storms #Care" }),
+        ));
+        activities.push(activity(
+            31,
+            ActivityCategory::Lifestyle,
+            "Play",
+            json!({ "time": time(4, "12:00"), "notes": "#care Only drinks from the fountain" }),
+        ));
+
+        let series = RecurringSeries {
+            id: 1,
+            pet_id: 1,
+            category: ActivityCategory::Health,
+            subcategory: "Medication".to_string(),
+            activity_data: Some(json!({
+                "time": { "date": "2025-06-01T09:00:00.000Z", "time": "", "timezone": "" },
+                "title": "Apoquel",
+                "portion": { "amount": 1, "unit": "tablet", "portionType": "dose", "product": null }
+            })),
+            rrule: "FREQ=DAILY;INTERVAL=2".to_string(),
+            start_date: date(2025, 6, 1),
+            materialize_days_ahead: 7,
+            status: RecurringSeriesStatus::Active,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let stay = (date(2025, 6, 7), date(2025, 6, 12));
+        let sheet = care_sheet(
+            &activities,
+            &[series.clone()],
+            stay,
+            date(2025, 6, 6),
+            &PrivacySettings::default(),
+        );
+        assert_eq!(sheet.feeding_plan.len(), 1);
+        let breakfast = &sheet.feeding_plan[0];
+        assert_eq!(breakfast.time, NaiveTime::from_hms_opt(8, 0, 0));
+        assert_eq!(breakfast.times_logged, 5);
+        assert_eq!(breakfast.brand.as_deref(), Some("Orijen"));
+
+        assert_eq!(sheet.medications.len(), 2);
+        assert_eq!(sheet.medications[0].name, "Apoquel");
+        assert_eq!(sheet.medications[0].schedule, "Every 2 days");
+        assert_eq!(
+            sheet.medications[0].dates,
+            vec![date(2025, 6, 7), date(2025, 6, 9), date(2025, 6, 11)]
+        );
+        assert_eq!(sheet.medications[1].name, "Fish oil");
+        assert_eq!(sheet.medications[1].dose.as_deref(), Some("1 pump"));
+        assert!(sheet.medications[1].dates.is_empty());
+
+        assert_eq!(
+            sheet.quirks,
+            vec![
+                "Only drinks from the fountain",
+                "Hides under the bed during storms"
+            ]
+        );
+
+        // Hidden categories are left out like private activities
+        activities[0].is_private = true;
+        let privacy = PrivacySettings {
+            hidden_categories: vec![ActivityCategory::Health, ActivityCategory::Lifestyle],
+            ..Default::default()
+        };
+        let hidden = care_sheet(&activities, &[series], stay, date(2025, 6, 6), &privacy);
+        assert!(hidden.medications.is_empty());
+        assert!(hidden.quirks.is_empty());
+        assert_eq!(hidden.feeding_plan.len(), 1);
+        assert_eq!(hidden.feeding_plan[0].times_logged, 4);
+
+        let contacts = vec![EmergencyContact {
+            name: "Alex".to_string(),
+            phone: "+1 555 0100".to_string(),
+            role: Some("Owner".to_string()),
+        }];
+        let markdown = render_markdown(&pet(), &sheet, &contacts, date(2025, 6, 6));
+        assert!(markdown.starts_with("# Care handoff: Mochi\n"));
+        assert!(markdown.contains("- Alex (Owner): +1 555 0100\n"));
+        assert!(markdown.contains("- 08:00 \u{2013} Breakfast: 80 g of Orijen\n"));
+        assert!(markdown
+            .contains("Apoquel, 1 tablet \u{b7} Every 2 days \u{b7} 3 doses from Jun 7 to Jun 11"));
+
+        let pdf = render_pdf(&pet(), &sheet, &contacts, date(2025, 6, 6));
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.contains("(Care handoff: Mochi)"));
+    }
+
+    #[test]
+    fn test_stay_defaults_and_limits() {
+        let today = date(2025, 6, 1);
+        let options = CareHandoffOptions::default();
+        assert_eq!(options.stay(today).unwrap(), (today, date(2025, 6, 7)));
+
+        let backwards = CareHandoffOptions {
+            start_date: Some(date(2025, 6, 10)),
+            end_date: Some(date(2025, 6, 9)),
+            ..Default::default()
+        };
+        assert!(backwards.stay(today).is_err());
+
+        let no_phone = CareHandoffOptions {
+            contacts: vec![EmergencyContact {
+                name: "Alex".to_string(),
+                phone: " ".to_string(),
+                role: None,
+            }],
+            ..Default::default()
+        };
+        assert!(no_phone.stay(today).is_err());
+        assert_eq!(wrap("a bb ccc", 4), vec!["a bb", "ccc"]);
+    }
+}
@@ -0,0 +1,239 @@
+use crate::database::PetSpecies;
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+
+/// A task in a checklist template
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecklistTemplateItem {
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Days after the checklist start date the task is due, if it has a due date
+    pub due_after_days: Option<u32>,
+}
+
+/// A guided care flow that can be started for a pet
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecklistTemplate {
+    pub key: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Species the template applies to; None means any species
+    pub species: Option<PetSpecies>,
+    pub items: &'static [ChecklistTemplateItem],
+}
+
+impl ChecklistTemplate {
+    /// Whether the template can be used for a pet of the given species
+    pub fn applies_to(&self, species: &PetSpecies) -> bool {
+        self.species.as_ref().is_none_or(|s| s == species)
+    }
+
+    /// Due date of each item for a checklist starting on `start_date`
+    pub fn item_due_dates(&self, start_date: NaiveDate) -> Vec<Option<NaiveDate>> {
+        self.items
+            .iter()
+            .map(|item| {
+                item.due_after_days
+                    .and_then(|days| start_date.checked_add_days(Days::new(days as u64)))
+            })
+            .collect()
+    }
+}
+
+const fn item(
+    title: &'static str,
+    description: &'static str,
+    due_after_days: Option<u32>,
+) -> ChecklistTemplateItem {
+    ChecklistTemplateItem {
+        title,
+        description,
+        due_after_days,
+    }
+}
+
+static TEMPLATES: [ChecklistTemplate; 4] = [
+    ChecklistTemplate {
+        key: "new_kitten",
+        title: "New kitten checklist",
+        description: "First weeks at home with a new kitten",
+        species: Some(PetSpecies::Cat),
+        items: &[
+            item(
+                "Set up litter box",
+                "Place it in a quiet spot away from food and water",
+                Some(0),
+            ),
+            item(
+                "Schedule first vet visit",
+                "General health check and vaccination plan",
+                Some(7),
+            ),
+            item(
+                "Start deworming",
+                "Follow the vet's deworming schedule",
+                Some(14),
+            ),
+            item(
+                "First core vaccination",
+                "FVRCP, usually from 8 weeks of age",
+                Some(21),
+            ),
+            item(
+                "Microchip and register",
+                "Record the chip number in the pet profile",
+                Some(30),
+            ),
+            item(
+                "Discuss spay/neuter timing",
+                "Ask the vet about the right age",
+                Some(60),
+            ),
+        ],
+    },
+    ChecklistTemplate {
+        key: "new_puppy",
+        title: "New puppy checklist",
+        description: "First weeks at home with a new puppy",
+        species: Some(PetSpecies::Dog),
+        items: &[
+            item(
+                "Set up crate and sleeping area",
+                "A quiet, safe place to rest",
+                Some(0),
+            ),
+            item(
+                "Schedule first vet visit",
+                "General health check and vaccination plan",
+                Some(7),
+            ),
+            item(
+                "Start deworming",
+                "Follow the vet's deworming schedule",
+                Some(14),
+            ),
+            item(
+                "First core vaccination",
+                "DHPP, usually from 8 weeks of age",
+                Some(21),
+            ),
+            item(
+                "Microchip and register",
+                "Record the chip number in the pet profile",
+                Some(30),
+            ),
+            item(
+                "Start socialization and training",
+                "Short daily sessions with new people and places",
+                Some(30),
+            ),
+        ],
+    },
+    ChecklistTemplate {
+        key: "post_surgery",
+        title: "Post-surgery care",
+        description: "Recovery routine after an operation",
+        species: None,
+        items: &[
+            item(
+                "Keep the cone on",
+                "Prevent licking or chewing the incision",
+                Some(0),
+            ),
+            item(
+                "Give prescribed medication",
+                "Follow the dosage on the discharge sheet",
+                Some(0),
+            ),
+            item(
+                "Check the incision daily",
+                "Look for redness, swelling or discharge",
+                Some(1),
+            ),
+            item(
+                "Restrict activity",
+                "No jumping or running until cleared by the vet",
+                Some(3),
+            ),
+            item(
+                "Follow-up visit",
+                "Wound check and stitch removal if needed",
+                Some(10),
+            ),
+        ],
+    },
+    ChecklistTemplate {
+        key: "travel_prep",
+        title: "Travel preparation",
+        description: "Getting ready for a trip with your pet",
+        species: None,
+        items: &[
+            item(
+                "Check vaccination requirements",
+                "Some destinations require proof of rabies vaccination",
+                None,
+            ),
+            item(
+                "Pack food and medication",
+                "Bring enough for the whole trip plus a few days",
+                None,
+            ),
+            item(
+                "Update ID tag and microchip details",
+                "Make sure contact details are current",
+                None,
+            ),
+            item(
+                "Practice with the carrier",
+                "Short trips help reduce travel stress",
+                None,
+            ),
+        ],
+    },
+];
+
+/// All built-in checklist templates
+pub fn all_templates() -> &'static [ChecklistTemplate] {
+    &TEMPLATES
+}
+
+/// Look up a template by key
+pub fn find_template(key: &str) -> Option<&'static ChecklistTemplate> {
+    TEMPLATES.iter().find(|template| template.key == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_keys_are_unique() {
+        let mut keys: Vec<_> = all_templates().iter().map(|t| t.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), all_templates().len());
+        assert!(all_templates().iter().all(|t| !t.items.is_empty()));
+    }
+
+    #[test]
+    fn test_templates_filtered_by_species() {
+        let kitten = find_template("new_kitten").unwrap();
+        assert!(kitten.applies_to(&PetSpecies::Cat));
+        assert!(!kitten.applies_to(&PetSpecies::Dog));
+        assert!(find_template("post_surgery")
+            .unwrap()
+            .applies_to(&PetSpecies::Dog));
+        assert!(find_template("unknown").is_none());
+    }
+
+    #[test]
+    fn test_item_due_dates() {
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let dates = find_template("post_surgery").unwrap().item_due_dates(start);
+        assert_eq!(dates[0], Some(start));
+        assert_eq!(dates[4], NaiveDate::from_ymd_opt(2025, 3, 11));
+
+        let dates = find_template("travel_prep").unwrap().item_due_dates(start);
+        assert!(dates.iter().all(Option::is_none));
+    }
+}
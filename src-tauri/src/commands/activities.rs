@@ -1,14 +1,36 @@
-use super::AppState;
-use crate::database::{ActivityCreateRequest, ActivityResponse, ActivityUpdateRequest};
+use super::dto::{ActivityDto, CreateActivityInput, UpdateActivityInput};
+use super::{AppState, Permission};
+use crate::database::activity_data::{ActivityData, ActivityDataExt};
+use crate::database::activity_schema::ActivityDataMigrationReport;
+use crate::database::overlaps::ActivityOverlap;
+use crate::database::portion_history::KnownPortionValue;
+use crate::database::schema_validation::{SchemaValidationReport, SchemaValidationScan};
+use crate::database::subcategory_suggestions::SubcategorySuggestion;
+use crate::database::trash::{BulkDeleteFilter, BulkDeleteResult, DEFAULT_TRASH_RETENTION_DAYS};
+use crate::database::{
+    ActivityCategory, ActivityCreateRequest, ActivityUpdateRequest, ExportActivitiesRequest, Pet,
+    SmartDefaults,
+};
 use crate::errors::ActivityError;
-use tauri::State;
+use crate::events::{self, DeletedPayload};
+use crate::note_templates::{self, NoteVariable, NOTE_VARIABLES};
+use crate::quick_entry::{self, PetNames, QuickEntryDraft, MAX_QUICK_ENTRY_LENGTH};
+use crate::receipt::{self, ReceiptExtraction, MAX_RECEIPT_TEXT_LENGTH};
+use crate::validation::{
+    self, ActivityDateLimits, ValidationOutcome, WithValidation, ACTIVITY_DATE_LIMITS_SETTING_KEY,
+};
+use tauri::{AppHandle, State};
 
 /// Create a new activity with automatic pet profile updates
 #[tauri::command]
 pub async fn create_activity(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
-    activity_data: ActivityCreateRequest,
-) -> Result<ActivityResponse, ActivityError> {
+    activity_data: CreateActivityInput,
+) -> Result<WithValidation<ActivityDto>, ActivityError> {
+    state.authorize("create_activity", Permission::Write)?;
+    let mut activity_data = ActivityCreateRequest::from(activity_data);
+
     log::info!("[CREATE_ACTIVITY] Starting activity creation");
     log::debug!("[CREATE_ACTIVITY] Request params: {{\"pet_id\": {}, \"category\": \"{}\", \"subcategory\": \"{}\", \"activity_data\": {}}}",
         activity_data.pet_id,
@@ -20,16 +42,49 @@ pub async fn create_activity(
     );
 
     // Verify pet exists
-    if let Err(e) = state.database.get_pet_by_id(activity_data.pet_id).await {
-        log::error!(
-            "[CREATE_ACTIVITY] Pet validation failed: pet_id={}, error={}",
-            activity_data.pet_id,
-            e
+    let pet = match state.database.get_pet_by_id(activity_data.pet_id).await {
+        Ok(pet) => pet,
+        Err(e) => {
+            log::error!(
+                "[CREATE_ACTIVITY] Pet validation failed: pet_id={}, error={}",
+                activity_data.pet_id,
+                e
+            );
+            return Err(ActivityError::validation(
+                "pet_id",
+                &format!("Pet not found: {e}"),
+            ));
+        }
+    };
+
+    validation::validate_exercise_blocks(activity_data.activity_data.as_ref())?;
+    validation::validate_litter_blocks(activity_data.activity_data.as_ref())?;
+    validation::validate_body_condition_blocks(activity_data.activity_data.as_ref())?;
+    validation::validate_custom_blocks(
+        activity_data.activity_data.as_ref(),
+        &state.database.get_custom_blocks().await?,
+    )?;
+    activity_date_limits(&state).await?.check(
+        activity_data.category,
+        activity_data.activity_data.as_ref(),
+        chrono::Local::now().date_naive(),
+    )?;
+
+    // Apply age-aware rules (hard errors abort, warnings are returned with the result)
+    let mut outcome = validation::validate_activity_age_rules(
+        &pet,
+        &activity_data,
+        chrono::Local::now().date_naive(),
+    )?;
+    outcome.merge(overlap_warnings(&state, &activity_data).await?);
+    expand_note_variables(&state, &pet, &mut activity_data).await?;
+    for warning in &outcome.warnings {
+        log::warn!(
+            "[CREATE_ACTIVITY] Validation warning [{}] {}: {}",
+            warning.code,
+            warning.field,
+            warning.message
         );
-        return Err(ActivityError::validation(
-            "pet_id",
-            &format!("Pet not found: {e}"),
-        ));
     }
 
     // Create activity with automatic pet profile updates
@@ -47,7 +102,14 @@ pub async fn create_activity(
             log::debug!("[CREATE_ACTIVITY] Response: {{\"id\": {}, \"pet_id\": {}, \"category\": \"{}\", \"subcategory\": \"{}\", \"created_at\": \"{}\"}}",
                 activity.id, activity.pet_id, activity.category, activity.subcategory, activity.created_at
             );
-            Ok(ActivityResponse::from(activity))
+            let response = ActivityDto::from(activity);
+            state
+                .event_bus
+                .emit(&app_handle, events::ACTIVITY_CREATED, &response);
+            state.notify_low_stock(&app_handle).await;
+            state.notify_metric_alerts(&app_handle).await;
+            state.webhook_wakeup.notify_one();
+            Ok(WithValidation::new(response, outcome))
         }
         Err(e) => {
             log::error!("[CREATE_ACTIVITY] Database error: {e}");
@@ -56,13 +118,174 @@ pub async fn create_activity(
     }
 }
 
+/// Parse a quick text entry like "fed 80g royal canin at 8am" into an activity draft.
+/// The draft is for `pet_id` unless the text names another pet by name or alias.
+/// Nothing is saved; the draft is returned for the user to confirm with `create_activity`.
+/// The draft is marked as needing review, so if it is saved unchanged it waits in the review queue.
+#[tauri::command]
+pub async fn parse_quick_entry(
+    state: State<'_, AppState>,
+    text: String,
+    pet_id: i64,
+) -> Result<QuickEntryDraft, ActivityError> {
+    log::debug!("[PARSE_QUICK_ENTRY] pet_id={pet_id}, text='{text}'");
+
+    if text.trim().is_empty() {
+        return Err(ActivityError::validation("text", "Text cannot be empty"));
+    }
+    if text.chars().count() > MAX_QUICK_ENTRY_LENGTH {
+        return Err(ActivityError::validation(
+            "text".to_string(),
+            format!("Text must be at most {MAX_QUICK_ENTRY_LENGTH} characters"),
+        ));
+    }
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        return Err(ActivityError::validation(
+            "pet_id",
+            &format!("Pet not found: {e}"),
+        ));
+    }
+
+    let mut aliases = state
+        .database
+        .get_all_pet_aliases()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    let pets: Vec<PetNames> = state
+        .database
+        .get_pets(false)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+        .into_iter()
+        .map(|pet| {
+            let mut names = vec![pet.name];
+            names.extend(aliases.remove(&pet.id).unwrap_or_default());
+            PetNames {
+                pet_id: pet.id,
+                names,
+            }
+        })
+        .collect();
+
+    let draft =
+        quick_entry::parse_quick_entry(&text, pet_id, &pets, chrono::Local::now().fixed_offset())
+            .ok_or_else(|| {
+            ActivityError::validation("text", "Could not recognize an activity in the text")
+        })?;
+
+    log::debug!(
+        "[PARSE_QUICK_ENTRY] Parsed {}/{} with confidence {}",
+        draft.request.category,
+        draft.request.subcategory,
+        draft.confidence
+    );
+    Ok(draft)
+}
+
+/// Suggest the total and purchase date of an expense from the text recognized on its
+/// receipt photo. Candidates come with a confidence for the user to pick from, and the
+/// best ones as cost and time blocks to prefill the expense. Nothing is saved.
+#[tauri::command]
+pub async fn extract_receipt_details(ocr_text: String) -> Result<ReceiptExtraction, ActivityError> {
+    log::debug!(
+        "[EXTRACT_RECEIPT_DETAILS] {} characters",
+        ocr_text.chars().count()
+    );
+
+    if ocr_text.trim().is_empty() {
+        return Err(ActivityError::validation(
+            "ocr_text",
+            "Receipt text cannot be empty",
+        ));
+    }
+    if ocr_text.chars().count() > MAX_RECEIPT_TEXT_LENGTH {
+        return Err(ActivityError::validation(
+            "ocr_text".to_string(),
+            format!("Receipt text must be at most {MAX_RECEIPT_TEXT_LENGTH} characters"),
+        ));
+    }
+
+    let extraction = receipt::extract_receipt(&ocr_text, chrono::Local::now().date_naive());
+    log::debug!(
+        "[EXTRACT_RECEIPT_DETAILS] {} amounts, {} dates",
+        extraction.amounts.len(),
+        extraction.dates.len()
+    );
+    Ok(extraction)
+}
+
+/// Get the values a pet's recent activities of `subcategory` used (portion, duration,
+/// brand), so a new activity from a template can be prefilled
+#[tauri::command]
+pub async fn get_smart_defaults(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    subcategory: String,
+) -> Result<SmartDefaults, ActivityError> {
+    log::debug!("[GET_SMART_DEFAULTS] pet_id={pet_id}, subcategory={subcategory}");
+
+    if subcategory.trim().is_empty() {
+        return Err(ActivityError::validation(
+            "subcategory",
+            "Subcategory cannot be empty",
+        ));
+    }
+    state
+        .database
+        .get_smart_defaults(pet_id, &subcategory)
+        .await
+}
+
+/// Rank the subcategories of `category` for Quick Log by how often and how recently
+/// this pet used them, then by what pets of its species use, then the built-in order
+#[tauri::command]
+pub async fn suggest_subcategories(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    category: ActivityCategory,
+) -> Result<Vec<SubcategorySuggestion>, ActivityError> {
+    log::debug!("[SUGGEST_SUBCATEGORIES] pet_id={pet_id}, category={category}");
+
+    state
+        .database
+        .suggest_subcategories(pet_id, category, chrono::Utc::now())
+        .await
+}
+
+/// Brands this pet's portion blocks have used, most frequent first, for the brand field's autocomplete
+#[tauri::command]
+pub async fn get_known_brands(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<Vec<KnownPortionValue>, ActivityError> {
+    log::debug!("[GET_KNOWN_BRANDS] pet_id={pet_id}");
+    state.database.get_known_brands(pet_id).await
+}
+
+/// Products logged with a brand, most frequent first, for the product field's autocomplete
+#[tauri::command]
+pub async fn get_known_products(
+    state: State<'_, AppState>,
+    brand: String,
+) -> Result<Vec<KnownPortionValue>, ActivityError> {
+    log::debug!("[GET_KNOWN_PRODUCTS] brand={brand}");
+    if brand.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    state.database.get_known_products(&brand).await
+}
+
 /// Update an existing activity - backward compatible version (less secure)
 #[tauri::command]
 pub async fn update_activity(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     activity_id: i64,
-    updates: ActivityUpdateRequest,
-) -> Result<ActivityResponse, ActivityError> {
+    updates: UpdateActivityInput,
+) -> Result<ActivityDto, ActivityError> {
+    state.authorize("update_activity", Permission::Write)?;
+    let updates = ActivityUpdateRequest::from(updates);
+
     log::info!("[UPDATE_ACTIVITY] Starting activity update (legacy API)");
     log::debug!("[UPDATE_ACTIVITY] Request params: {{\"activity_id\": {}, \"updates\": {{\"category\": {:?}, \"subcategory\": {:?}, \"activity_data\": {}}}}}",
         activity_id,
@@ -81,8 +304,16 @@ pub async fn update_activity(
         ));
     }
 
+    validation::validate_exercise_blocks(updates.activity_data.as_ref())?;
+    validation::validate_litter_blocks(updates.activity_data.as_ref())?;
+    validation::validate_body_condition_blocks(updates.activity_data.as_ref())?;
+    validation::validate_custom_blocks(
+        updates.activity_data.as_ref(),
+        &state.database.get_custom_blocks().await?,
+    )?;
+
     // Check if activity exists
-    let _existing_activity = match state.database.get_activity_by_id(activity_id).await {
+    let existing_activity = match state.database.get_activity_by_id(activity_id).await {
         Ok(activity) => {
             log::debug!(
                 "[UPDATE_ACTIVITY] Found existing activity: id={}, pet_id={}, category={}",
@@ -100,6 +331,12 @@ pub async fn update_activity(
         }
     };
 
+    activity_date_limits(&state).await?.check(
+        updates.category.unwrap_or(existing_activity.category),
+        updates.activity_data.as_ref(),
+        chrono::Local::now().date_naive(),
+    )?;
+
     // Update the activity
     match state.database.update_activity(activity_id, updates).await {
         Ok(updated_activity) => {
@@ -111,7 +348,13 @@ pub async fn update_activity(
             log::debug!("[UPDATE_ACTIVITY] Response: {{\"id\": {}, \"pet_id\": {}, \"category\": \"{}\", \"subcategory\": \"{}\", \"updated_at\": \"{}\"}}",
                 updated_activity.id, updated_activity.pet_id, updated_activity.category, updated_activity.subcategory, updated_activity.updated_at
             );
-            Ok(ActivityResponse::from(updated_activity))
+            let response = ActivityDto::from(updated_activity);
+            state
+                .event_bus
+                .emit(&app_handle, events::ACTIVITY_UPDATED, &response);
+            state.notify_low_stock(&app_handle).await;
+            state.notify_metric_alerts(&app_handle).await;
+            Ok(response)
         }
         Err(e) => {
             log::error!("[UPDATE_ACTIVITY] Database error: {e}");
@@ -125,7 +368,7 @@ pub async fn update_activity(
 pub async fn get_activity(
     state: State<'_, AppState>,
     activity_id: i64,
-) -> Result<ActivityResponse, ActivityError> {
+) -> Result<ActivityDto, ActivityError> {
     log::info!("[GET_ACTIVITY] Starting activity retrieval (legacy API)");
     log::debug!("[GET_ACTIVITY] Request params: {{\"activity_id\": {activity_id}}}");
 
@@ -151,7 +394,7 @@ pub async fn get_activity(
                     .map(|s| s.len())
                     .unwrap_or(0)
             );
-            Ok(ActivityResponse::from(activity))
+            Ok(ActivityDto::from(activity))
         }
         Err(e) => {
             log::error!("[GET_ACTIVITY] Database error: activity_id={activity_id}, error={e}");
@@ -165,7 +408,7 @@ pub async fn get_activity(
 pub async fn get_activities_for_pet(
     state: State<'_, AppState>,
     pet_id: i64,
-) -> Result<Vec<ActivityResponse>, ActivityError> {
+) -> Result<Vec<ActivityDto>, ActivityError> {
     log::info!("[GET_ACTIVITIES_FOR_PET] Starting activities retrieval for pet");
     log::debug!("[GET_ACTIVITIES_FOR_PET] Request params: {{\"pet_id\": {pet_id}}}");
 
@@ -192,6 +435,7 @@ pub async fn get_activities_for_pet(
         sort_desc: Some(true),
         limit: Some(100), // Default limit for frontend
         offset: Some(0),
+        exact_count: false,
     };
 
     match state.database.get_activities(request).await {
@@ -208,7 +452,7 @@ pub async fn get_activities_for_pet(
             Ok(result
                 .activities
                 .into_iter()
-                .map(ActivityResponse::from)
+                .map(ActivityDto::from)
                 .collect())
         }
         Err(e) => {
@@ -218,12 +462,71 @@ pub async fn get_activities_for_pet(
     }
 }
 
+/// List imported, recurring and quick-entry activities that wait for the user to confirm them.
+/// They are left out of the timeline until approved with `approve_activity`.
+#[tauri::command]
+pub async fn list_activities_needing_review(
+    state: State<'_, AppState>,
+    pet_id: Option<i64>,
+) -> Result<Vec<ActivityDto>, ActivityError> {
+    log::debug!("[LIST_ACTIVITIES_NEEDING_REVIEW] pet_id={pet_id:?}");
+
+    let activities = state.database.get_activities_needing_review(pet_id).await?;
+    Ok(activities.into_iter().map(ActivityDto::from).collect())
+}
+
+/// Confirm a machine-created activity so it shows up in the timeline
+#[tauri::command]
+pub async fn approve_activity(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    activity_id: i64,
+) -> Result<ActivityDto, ActivityError> {
+    state.authorize("approve_activity", Permission::Write)?;
+
+    log::info!("[APPROVE_ACTIVITY] activity_id={activity_id}");
+    let response = ActivityDto::from(state.database.approve_activity(activity_id).await?);
+    state
+        .event_bus
+        .emit(&app_handle, events::ACTIVITY_UPDATED, &response);
+    Ok(response)
+}
+
+/// Mark an activity private (e.g. a behavioral incident) or shared again. Private
+/// activities stay in the diary but are left out of exports, webhook deliveries,
+/// handoff reports and vaccination cards, and out of search if the privacy
+/// settings say so.
+#[tauri::command]
+pub async fn set_activity_private(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    activity_id: i64,
+    is_private: bool,
+) -> Result<ActivityDto, ActivityError> {
+    state.authorize("set_activity_private", Permission::Write)?;
+
+    log::info!("[SET_ACTIVITY_PRIVATE] activity_id={activity_id}, is_private={is_private}");
+    let response = ActivityDto::from(
+        state
+            .database
+            .set_activity_private(activity_id, is_private)
+            .await?,
+    );
+    state
+        .event_bus
+        .emit(&app_handle, events::ACTIVITY_UPDATED, &response);
+    Ok(response)
+}
+
 /// Delete an activity - backward compatible version (less secure)
 #[tauri::command]
 pub async fn delete_activity(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     activity_id: i64,
 ) -> Result<(), ActivityError> {
+    state.authorize("delete_activity", Permission::Write)?;
+
     log::info!("[DELETE_ACTIVITY] Starting activity deletion (legacy API)");
     log::debug!("[DELETE_ACTIVITY] Request params: {{\"activity_id\": {activity_id}}}");
 
@@ -256,13 +559,28 @@ pub async fn delete_activity(
 
     // Delete the activity
     match state.database.delete_activity(activity_id).await {
-        Ok(_) => {
+        Ok(pending_files) => {
             log::info!(
                 "[DELETE_ACTIVITY] Success: deleted activity_id={} for pet_id={}",
                 activity_id,
                 activity.pet_id
             );
+            // The row is gone; files that can't be removed now stay journaled for retry
+            let cleanup = state.process_file_deletions(pending_files).await;
+            log::debug!(
+                "[DELETE_ACTIVITY] Attachment cleanup: deleted={}, pending={}",
+                cleanup.deleted,
+                cleanup.failed
+            );
             log::debug!("[DELETE_ACTIVITY] Response: {{\"deleted\": true}}");
+            state.event_bus.emit(
+                &app_handle,
+                events::ACTIVITY_DELETED,
+                DeletedPayload {
+                    id: activity_id,
+                    pet_id: Some(activity.pet_id),
+                },
+            );
             Ok(())
         }
         Err(e) => {
@@ -271,3 +589,277 @@ pub async fn delete_activity(
         }
     }
 }
+
+/// Move every activity matching `filter` to the trash, e.g. to clean up a bad import.
+/// With `dry_run` only counts them per category; otherwise the result carries an undo
+/// token for `restore_deleted_activities`.
+#[tauri::command]
+pub async fn bulk_delete_activities(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    filter: BulkDeleteFilter,
+    dry_run: bool,
+) -> Result<BulkDeleteResult, ActivityError> {
+    if !dry_run {
+        state.authorize("bulk_delete_activities", Permission::Write)?;
+    }
+    log::info!("[BULK_DELETE_ACTIVITIES] dry_run={dry_run}, filter={filter:?}");
+
+    let result = state
+        .database
+        .bulk_delete_activities(&filter, dry_run)
+        .await?;
+    if !dry_run {
+        for &id in &result.activity_ids {
+            state.event_bus.emit(
+                &app_handle,
+                events::ACTIVITY_DELETED,
+                DeletedPayload {
+                    id,
+                    pet_id: filter.pet_id,
+                },
+            );
+        }
+    }
+    log::info!(
+        "[BULK_DELETE_ACTIVITIES] {} activities {}",
+        result.total,
+        if dry_run {
+            "matched"
+        } else {
+            "moved to the trash"
+        }
+    );
+    Ok(result)
+}
+
+/// Undo a bulk delete, putting its activities back from the trash
+#[tauri::command]
+pub async fn restore_deleted_activities(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    undo_token: String,
+) -> Result<Vec<ActivityDto>, ActivityError> {
+    state.authorize("restore_deleted_activities", Permission::Write)?;
+
+    log::info!("[RESTORE_DELETED_ACTIVITIES] token={undo_token}");
+    let restored: Vec<ActivityDto> = state
+        .database
+        .restore_activity_trash(&undo_token)
+        .await?
+        .into_iter()
+        .map(ActivityDto::from)
+        .collect();
+    for response in &restored {
+        state
+            .event_bus
+            .emit(&app_handle, events::ACTIVITY_CREATED, response);
+    }
+    log::info!("[RESTORE_DELETED_ACTIVITIES] restored {}", restored.len());
+    Ok(restored)
+}
+
+/// Delete activities trashed more than `older_than_days` ago (default 30) for good,
+/// with their attachment files. Returns how many were purged.
+#[tauri::command]
+pub async fn purge_activity_trash(
+    state: State<'_, AppState>,
+    older_than_days: Option<u32>,
+) -> Result<usize, ActivityError> {
+    state.authorize("purge_activity_trash", Permission::Write)?;
+
+    let older_than_days = older_than_days.unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+    log::info!("[PURGE_ACTIVITY_TRASH] older_than_days={older_than_days}");
+    let purge = state.database.purge_activity_trash(older_than_days).await?;
+    // The rows are gone; files that can't be removed now stay journaled for retry
+    let cleanup = state.process_file_deletions(purge.pending_files).await;
+    log::debug!(
+        "[PURGE_ACTIVITY_TRASH] purged={}, files deleted={}, pending={}",
+        purge.purged,
+        cleanup.deleted,
+        cleanup.failed
+    );
+    Ok(purge.purged)
+}
+
+/// List a pet's time-bound activities (boarding, sitter visits, medication windows)
+/// whose time ranges intersect another of the same subcategory; every pet's with None
+#[tauri::command]
+pub async fn get_activity_overlaps(
+    state: State<'_, AppState>,
+    pet_id: Option<i64>,
+) -> Result<Vec<ActivityOverlap>, ActivityError> {
+    log::debug!("[GET_ACTIVITY_OVERLAPS] pet_id={pet_id:?}");
+    state.database.get_activity_overlaps(pet_id).await
+}
+
+/// Upgrade every stored activity payload written by an older app version to the
+/// current block format, instead of upgrading each one whenever it is read
+#[tauri::command]
+pub async fn migrate_activity_data_versions(
+    state: State<'_, AppState>,
+) -> Result<ActivityDataMigrationReport, ActivityError> {
+    state.authorize("migrate_activity_data_versions", Permission::Write)?;
+
+    log::info!("[MIGRATE_ACTIVITY_DATA_VERSIONS] Upgrading stored activity data");
+    state.database.migrate_activity_data_versions().await
+}
+
+/// Turn dual-read schema validation on or off. While on, every activity read is
+/// compared with its raw JSON and differences are recorded for the report.
+#[tauri::command]
+pub async fn set_schema_validation(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<SchemaValidationReport, ActivityError> {
+    state.authorize("set_schema_validation", Permission::Write)?;
+
+    log::info!("[SET_SCHEMA_VALIDATION] enabled={enabled}");
+    state.database.set_schema_validation(enabled).await?;
+    state.database.get_schema_validation_report(None).await
+}
+
+/// Differences recorded by schema validation, most recently seen first
+#[tauri::command]
+pub async fn get_schema_validation_report(
+    state: State<'_, AppState>,
+    limit: Option<i64>,
+) -> Result<SchemaValidationReport, ActivityError> {
+    log::debug!("[GET_SCHEMA_VALIDATION_REPORT] limit={limit:?}");
+    state.database.get_schema_validation_report(limit).await
+}
+
+/// Check every stored activity against its raw JSON now, replacing the report
+#[tauri::command]
+pub async fn scan_schema_discrepancies(
+    state: State<'_, AppState>,
+) -> Result<SchemaValidationScan, ActivityError> {
+    state.authorize("scan_schema_discrepancies", Permission::Write)?;
+
+    log::info!("[SCAN_SCHEMA_DISCREPANCIES] Checking all stored activities");
+    state.database.scan_schema_discrepancies().await
+}
+
+/// List the variables a note template can use, such as `{weight}`
+#[tauri::command]
+pub async fn list_note_variables() -> Result<Vec<NoteVariable>, ActivityError> {
+    Ok(NOTE_VARIABLES.to_vec())
+}
+
+/// Fill in the variables of a note written from a template, so the stored note
+/// reads e.g. "Weight 4.2 kg, 30 days since Rabies" rather than the placeholders
+async fn expand_note_variables(
+    state: &AppState,
+    pet: &Pet,
+    request: &mut ActivityCreateRequest,
+) -> Result<(), ActivityError> {
+    let Some(notes) = request
+        .activity_data
+        .as_mut()
+        .and_then(|data| data.get_mut("notes"))
+    else {
+        return Ok(());
+    };
+    let Some(template) = notes
+        .as_str()
+        .filter(|text| note_templates::has_variables(text))
+    else {
+        return Ok(());
+    };
+
+    let activities = state
+        .database
+        .export_activities(ExportActivitiesRequest {
+            pet_id: Some(pet.id),
+            format: None,
+        })
+        .await?;
+    let unit = state
+        .database
+        .get_weight_unit()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    let values =
+        note_templates::note_variables(pet, &activities, unit, chrono::Local::now().date_naive());
+    let expanded = note_templates::expand_note_template(template, &values);
+    log::debug!("[CREATE_ACTIVITY] Expanded note template: {expanded}");
+    *notes = serde_json::Value::String(expanded);
+    Ok(())
+}
+
+/// Warn when a new activity's time range intersects an existing one of the same
+/// subcategory, e.g. two boarding stays booked over the same nights
+async fn overlap_warnings(
+    state: &AppState,
+    request: &ActivityCreateRequest,
+) -> Result<ValidationOutcome, ActivityError> {
+    let mut outcome = ValidationOutcome::new();
+    let Some((start, end)) = request
+        .activity_data
+        .as_ref()
+        .map(|json| ActivityData::from_legacy_json(json.clone()))
+        .and_then(|data| data.extract_time_range())
+    else {
+        return Ok(outcome);
+    };
+
+    let overlapping = state
+        .database
+        .find_overlapping_activities(request.pet_id, &request.subcategory, start, end, None)
+        .await?;
+    for range in overlapping {
+        outcome.warn(
+            "activity_data",
+            "time_overlap",
+            format!(
+                "Overlaps {} activity {} ({} to {})",
+                request.subcategory.trim(),
+                range.activity_id,
+                range.start.format("%Y-%m-%d %H:%M"),
+                range.end.format("%Y-%m-%d %H:%M")
+            ),
+        );
+    }
+    Ok(outcome)
+}
+
+/// Get how far in the past and future activities may be dated, per category
+#[tauri::command]
+pub async fn get_activity_date_limits(
+    state: State<'_, AppState>,
+) -> Result<ActivityDateLimits, ActivityError> {
+    activity_date_limits(&state).await
+}
+
+/// Change how far in the past and future activities may be dated.
+/// Overrides let a category reach further, e.g. to back-date old health records.
+#[tauri::command]
+pub async fn set_activity_date_limits(
+    state: State<'_, AppState>,
+    limits: ActivityDateLimits,
+) -> Result<(), ActivityError> {
+    state.authorize("set_activity_date_limits", Permission::Write)?;
+
+    limits.validate()?;
+    log::info!(
+        "[SET_ACTIVITY_DATE_LIMITS] default={:?}, overrides={}",
+        limits.default,
+        limits.overrides.len()
+    );
+    state
+        .database
+        .set_setting(ACTIVITY_DATE_LIMITS_SETTING_KEY, &limits)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+}
+
+/// Stored activity date limits, defaults when never changed
+pub(crate) async fn activity_date_limits(
+    state: &AppState,
+) -> Result<ActivityDateLimits, ActivityError> {
+    state
+        .database
+        .get_activity_date_limits()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+}
@@ -0,0 +1,114 @@
+use super::{AppState, Permission};
+use crate::database::{ActivityLink, ActivityWithRelated, ReminderCompletion, ReminderCompliance};
+use crate::errors::ActivityError;
+use tauri::State;
+
+/// Link an expense to the health activity it paid for (IDs in either order)
+#[tauri::command]
+pub async fn link_activities(
+    state: State<'_, AppState>,
+    activity_id: i64,
+    related_activity_id: i64,
+) -> Result<ActivityLink, ActivityError> {
+    state.authorize("link_activities", Permission::Write)?;
+
+    log::info!(
+        "[LINK_ACTIVITIES] activity_id={activity_id}, related_activity_id={related_activity_id}"
+    );
+    state
+        .database
+        .link_activities(activity_id, related_activity_id)
+        .await
+}
+
+/// Remove the link between two activities; returns false if they weren't linked
+#[tauri::command]
+pub async fn unlink_activities(
+    state: State<'_, AppState>,
+    activity_id: i64,
+    related_activity_id: i64,
+) -> Result<bool, ActivityError> {
+    state.authorize("unlink_activities", Permission::Write)?;
+
+    log::info!(
+        "[UNLINK_ACTIVITIES] activity_id={activity_id}, related_activity_id={related_activity_id}"
+    );
+    state
+        .database
+        .unlink_activities(activity_id, related_activity_id)
+        .await
+}
+
+/// Get an activity together with its linked expenses or health activities
+#[tauri::command]
+pub async fn get_activity_with_related(
+    state: State<'_, AppState>,
+    activity_id: i64,
+) -> Result<ActivityWithRelated, ActivityError> {
+    log::debug!("[GET_ACTIVITY_WITH_RELATED] activity_id={activity_id}");
+    state.database.get_activity_with_related(activity_id).await
+}
+
+/// Get all expense/health links of a pet
+#[tauri::command]
+pub async fn get_pet_activity_links(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<Vec<ActivityLink>, ActivityError> {
+    log::debug!("[GET_PET_ACTIVITY_LINKS] pet_id={pet_id}");
+    state.database.get_pet_activity_links(pet_id).await
+}
+
+/// Record that `activity_id` was logged to do what the reminder on
+/// `reminder_activity_id` asked, which stops the reminder from being announced
+#[tauri::command]
+pub async fn complete_reminder(
+    state: State<'_, AppState>,
+    reminder_activity_id: i64,
+    activity_id: i64,
+) -> Result<ReminderCompletion, ActivityError> {
+    state.authorize("complete_reminder", Permission::Write)?;
+
+    log::info!(
+        "[COMPLETE_REMINDER] reminder_activity_id={reminder_activity_id}, activity_id={activity_id}"
+    );
+    state
+        .database
+        .complete_reminder(reminder_activity_id, activity_id)
+        .await
+}
+
+/// Mark a reminder as not done again; returns false if it wasn't completed
+#[tauri::command]
+pub async fn uncomplete_reminder(
+    state: State<'_, AppState>,
+    reminder_activity_id: i64,
+) -> Result<bool, ActivityError> {
+    state.authorize("uncomplete_reminder", Permission::Write)?;
+
+    log::info!("[UNCOMPLETE_REMINDER] reminder_activity_id={reminder_activity_id}");
+    state
+        .database
+        .uncomplete_reminder(reminder_activity_id)
+        .await
+}
+
+/// Get a pet's reminders due in a date range and whether each was done or skipped
+#[tauri::command]
+pub async fn get_reminder_compliance(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    start_date: chrono::NaiveDate,
+    end_date: chrono::NaiveDate,
+) -> Result<ReminderCompliance, ActivityError> {
+    log::debug!("[GET_REMINDER_COMPLIANCE] pet_id={pet_id}, {start_date} to {end_date}");
+    state
+        .database
+        .get_reminder_compliance(
+            pet_id,
+            start_date,
+            end_date,
+            chrono::Local::now().date_naive(),
+        )
+        .await
+}
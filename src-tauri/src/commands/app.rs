@@ -1,51 +1,176 @@
-use super::AppState;
+use super::dto::PetDto;
+use super::{AccessLevel, AppState, Permission};
+use crate::data_dir::{self, DataDirSource, DataDirStatus, DataLocation};
+use crate::database::demo_data::{DemoData, DemoDataRemoval, DemoPhotos};
+use crate::database::footprint::{build_data_footprint, DataFootprint, PetFileReferences};
+use crate::database::storage_quota::{
+    build_cleanup_plan, build_quota_status, QuotaStatus, StorageCleanupPlan, StorageQuota,
+};
+use crate::database::{FileCleanupReport, PetDatabase};
 use crate::errors::PetError;
-use tauri::{AppHandle, Manager, State};
+use crate::events::{self, DeletedPayload};
+use crate::photo::PhotoSettings;
+use crate::startup::{self, InitCheck, InitReport, InitStatus};
+use crate::AppInitGuard;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, State, Window};
 
-/// Initialize the application database and directories
+/// Photos bundled with the app for the demo pet
+const DEMO_PROFILE_PHOTO: &[u8] = include_bytes!("../../assets/demo/profile.png");
+const DEMO_WALK_PHOTO: &[u8] = include_bytes!("../../assets/demo/walk.png");
+const DEMO_MEAL_PHOTO: &[u8] = include_bytes!("../../assets/demo/meal.png");
+
+/// Initialize the application database and directories.
+///
+/// Checks the data for damage left by a crash or an incompatible app version and
+/// repairs what it can. A report with status `needs_restore` means the user should
+/// restore a backup: the app then either runs read-only or, when the database can't
+/// be opened at all, isn't started.
+///
+/// Safe to call from every window: later calls return the first report and share
+/// its state. A start that didn't get as far as managing the state is retried.
+#[tauri::command]
+pub async fn initialize_app(
+    app_handle: AppHandle,
+    window: Window,
+    guard: State<'_, AppInitGuard>,
+) -> Result<InitReport, PetError> {
+    let mut initialized = guard.report.lock().await;
+    if let Some(report) = initialized.as_ref() {
+        log::info!(
+            "Application already initialized, window '{}' shares its state",
+            window.label()
+        );
+        return Ok(report.clone());
+    }
+
+    let report = initialize(&app_handle).await?;
+    if app_handle.try_state::<AppState>().is_some() {
+        *initialized = Some(report.clone());
+    }
+    Ok(report)
+}
+
+/// Whether `initialize_app` has managed the app state, e.g. for a window opened
+/// later to skip the start-up screen
 #[tauri::command]
-pub async fn initialize_app(app_handle: AppHandle) -> Result<String, PetError> {
+pub async fn is_app_initialized(app_handle: AppHandle) -> bool {
+    app_handle.try_state::<AppState>().is_some()
+}
+
+async fn initialize(app_handle: &AppHandle) -> Result<InitReport, PetError> {
     log::info!("=== STARTING APPLICATION INITIALIZATION ===");
 
-    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| {
+    let default_dir = app_handle.path().app_data_dir().map_err(|e| {
         log::error!("Failed to get app data directory: {e}");
         PetError::file_system(format!("Failed to get app data directory: {e}"))
     })?;
 
-    log::info!("App data directory: {}", app_data_dir.display());
-
-    // Create app data directory if it doesn't exist
-    if !app_data_dir.exists() {
-        log::info!("Creating app data directory...");
-        std::fs::create_dir_all(&app_data_dir).map_err(|e| {
-            log::error!("Failed to create app data directory: {e}");
-            PetError::file_system(format!("Failed to create app data directory: {e}"))
-        })?;
-        log::info!("App data directory created successfully");
-    } else {
-        log::info!("App data directory already exists");
-    }
+    // A root chosen on the command line or in settings replaces the platform
+    // directory; a move set up by `set_data_directory` happens here
+    let opened = data_dir::open_data_dir(
+        &default_dir,
+        data_dir::data_dir_argument(std::env::args().skip(1)),
+    )
+    .inspect_err(|e| log::error!("Failed to open data directory: {e}"))?;
+    let app_data_dir = opened.path.clone();
+    log::info!(
+        "App data directory: {} ({:?})",
+        app_data_dir.display(),
+        opened.source
+    );
 
-    let db_path = app_data_dir.join("pets.db");
+    let db_path = app_data_dir.join(data_dir::DATABASE_FILE);
     let photo_dir = app_data_dir.join("photos");
+    let document_dir = app_data_dir.join("documents");
+    let export_dir = app_data_dir.join("exports");
 
     log::info!("Database path: {}", db_path.display());
     log::info!("Photo directory: {}", photo_dir.display());
+    log::info!("Document directory: {}", document_dir.display());
 
-    // Create photo directory if it doesn't exist
-    if !photo_dir.exists() {
-        log::info!("Creating photo directory...");
-        std::fs::create_dir_all(&photo_dir).map_err(|e| {
-            log::error!("Failed to create photo directory: {e}");
-            PetError::file_system(format!("Failed to create photo directory: {e}"))
-        })?;
-        log::info!("Photo directory created successfully");
-    } else {
-        log::info!("Photo directory already exists");
+    let mut report = InitReport::new(&db_path, &photo_dir);
+    for check in opened.checks {
+        report.record(check);
     }
+    let database_existed = db_path.exists();
+    // Changes still in the write-ahead log must be recovered into the database file
+    let wal_left_over =
+        std::fs::metadata(startup::wal_path(&db_path)).is_ok_and(|metadata| metadata.len() > 0);
+
+    report.record(startup::ensure_directory(
+        "photo_directory",
+        &photo_dir,
+        database_existed,
+    )?);
+    report.record(startup::ensure_directory(
+        "document_directory",
+        &document_dir,
+        database_existed,
+    )?);
+
+    // Opening the database replays the write-ahead log and runs migrations
+    let database = match PetDatabase::new(&db_path).await {
+        Ok(database) => database,
+        Err(e) => {
+            log::error!("Failed to open database: {e:#}");
+            report.record(startup::classify_open_error(&e));
+            return Ok(report);
+        }
+    };
+    report.record(InitCheck::new(
+        "database",
+        InitStatus::Ok,
+        "Database opened and migrations are up to date",
+    ));
+
+    if wal_left_over {
+        report.record(match database.checkpoint_wal().await {
+            Ok(()) => InitCheck::new(
+                "wal_recovery",
+                InitStatus::Ok,
+                "Changes left in the write-ahead log were recovered",
+            ),
+            Err(e) => InitCheck::new(
+                "wal_recovery",
+                InitStatus::NeedsRestore,
+                format!("Changes from an unclean shutdown could not be recovered: {e}"),
+            ),
+        });
+    }
+
+    report.record(match database.quick_check().await {
+        Ok(problems) if problems.is_empty() => {
+            InitCheck::new("integrity", InitStatus::Ok, "No corruption found")
+        }
+        Ok(problems) => InitCheck::new(
+            "integrity",
+            InitStatus::NeedsRestore,
+            format!(
+                "The database is damaged ({} problems, first: {})",
+                problems.len(),
+                problems[0]
+            ),
+        ),
+        Err(e) => InitCheck::new(
+            "integrity",
+            InitStatus::NeedsRestore,
+            format!("The integrity check failed: {e}"),
+        ),
+    });
 
     // Initialize application state (clone paths for later use)
-    let app_state = AppState::new(db_path.clone(), photo_dir.clone()).await?;
+    let mut app_state =
+        AppState::with_database(database, photo_dir.clone(), document_dir, export_dir).await?;
+
+    // Damaged data stays viewable and exportable, but nothing new is written to it
+    if report.status == InitStatus::NeedsRestore {
+        report.read_only = true;
+        app_state = app_state.with_access_level(AccessLevel::ReadOnly);
+        app_handle.manage(app_state);
+        log::warn!("=== APPLICATION STARTED READ-ONLY, RESTORE NEEDED ===");
+        return Ok(report);
+    }
 
     // Test database connection
     log::info!("Testing database connection...");
@@ -56,15 +181,133 @@ pub async fn initialize_app(app_handle: AppHandle) -> Result<String, PetError> {
         "Database connection successful - Total pets: {total_pets}, Active pets: {active_pets}"
     );
 
+    // Generate recurring activities that became due while the app was closed
+    match app_state
+        .database
+        .materialize_recurring_activities(chrono::Local::now().date_naive())
+        .await
+    {
+        Ok(created) => log::info!("Generated {} due recurring activities", created.len()),
+        Err(e) => log::warn!("Failed to generate recurring activities: {e}"),
+    }
+
+    // Finish attachment file deletions interrupted by a previous crash
+    match app_state.process_pending_file_deletions().await {
+        Ok(report) if report.deleted + report.failed > 0 => log::info!(
+            "Resumed file cleanup: {} deleted, {} still pending",
+            report.deleted,
+            report.failed
+        ),
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to resume file cleanup: {e}"),
+    }
+
+    // Put jobs interrupted by closing the app back in the queue
+    match super::prepare_job_queue(&app_state).await {
+        Ok(requeued) if requeued > 0 => log::info!("Requeued {requeued} interrupted jobs"),
+        Ok(_) => {}
+        Err(e) => log::warn!("Failed to requeue interrupted jobs: {e}"),
+    }
+
+    // Start announcing due reminders to all windows
+    super::spawn_reminder_watcher(
+        app_handle.clone(),
+        app_state.database.clone(),
+        app_state.event_bus.clone(),
+    );
+
+    // Write end-of-day summaries for days that ended while the app was closed
+    super::spawn_daily_summary_job(
+        app_handle.clone(),
+        app_state.database.clone(),
+        app_state.event_bus.clone(),
+    );
+
+    // Post new activities to the automation webhook, retrying failed deliveries
+    super::spawn_webhook_dispatcher(app_state.database.clone(), app_state.webhook_wakeup.clone());
+
     // Store app state in Tauri's managed state
     app_handle.manage(app_state);
 
-    log::info!("=== APPLICATION INITIALIZATION COMPLETE ===");
-    Ok(format!(
-        "Application initialized successfully. Database: {}, Photos: {}",
-        db_path.display(),
-        photo_dir.display()
-    ))
+    // Run queued imports, exports and photo re-encoding in the background
+    super::spawn_job_worker(app_handle.clone());
+
+    log::info!(
+        "=== APPLICATION INITIALIZATION COMPLETE ({:?}) ===",
+        report.status
+    );
+    Ok(report)
+}
+
+/// Get the data directory in use and the one the next start will use
+#[tauri::command]
+pub async fn get_data_directory(app_handle: AppHandle) -> Result<DataDirStatus, PetError> {
+    let default_dir = default_data_dir(&app_handle)?;
+    let (current, source) = current_data_dir(&default_dir)?;
+    data_dir::data_dir_status(&default_dir, &current, source)
+}
+
+/// Choose where the app keeps its data, e.g. a synced folder; `path` None goes back
+/// to the platform directory. With `move_data` the database, photos and documents
+/// are moved there the next time the app starts; without it the app opens whatever
+/// data the directory holds, or starts empty. Takes effect at the next start.
+#[tauri::command]
+pub async fn set_data_directory(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    path: Option<String>,
+    move_data: bool,
+) -> Result<DataDirStatus, PetError> {
+    state.authorize("set_data_directory", Permission::Write)?;
+
+    log::info!("[SET_DATA_DIRECTORY] path={path:?}, move_data={move_data}");
+    let default_dir = default_data_dir(&app_handle)?;
+    let (current, source) = current_data_dir(&default_dir)?;
+    let target = match path.as_deref().map(str::trim) {
+        Some("") => return Err(PetError::validation("path", "Path cannot be empty")),
+        Some(path) => PathBuf::from(path),
+        None => default_dir.clone(),
+    };
+    data_dir::check_root(&target)?;
+    if move_data {
+        data_dir::check_move(&current, &target)?;
+    }
+
+    data_dir::write_location(
+        &default_dir,
+        &DataLocation {
+            data_dir: (target != default_dir).then_some(target),
+            move_from: move_data.then_some(current.clone()),
+        },
+    )?;
+    let status = data_dir::data_dir_status(&default_dir, &current, source)?;
+    log::info!(
+        "[SET_DATA_DIRECTORY] Next start uses {:?}, move={}",
+        status.pending_path,
+        status.pending_move
+    );
+    Ok(status)
+}
+
+fn default_data_dir(app_handle: &AppHandle) -> Result<PathBuf, PetError> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map_err(|e| PetError::file_system(format!("Failed to get app data directory: {e}")))
+}
+
+/// Data root in use and where it came from
+fn current_data_dir(default_dir: &Path) -> Result<(PathBuf, DataDirSource), PetError> {
+    let current = data_dir::current_data_dir()
+        .ok_or_else(|| PetError::operation_failed("The app has not been initialized"))?;
+    let source = if data_dir::data_dir_argument(std::env::args().skip(1)).is_some() {
+        DataDirSource::CommandLine
+    } else if current == default_dir {
+        DataDirSource::Default
+    } else {
+        DataDirSource::Settings
+    };
+    Ok((current, source))
 }
 
 /// Get application statistics
@@ -87,6 +330,269 @@ pub async fn get_app_statistics(state: State<'_, AppState>) -> Result<AppStatist
     })
 }
 
+/// Get row counts, database and WAL size, and photo, attachment and document storage per pet
+/// for the settings storage screen
+#[tauri::command]
+pub async fn get_data_footprint(state: State<'_, AppState>) -> Result<DataFootprint, PetError> {
+    log::debug!("[GET_DATA_FOOTPRINT] Collecting storage usage");
+
+    let (footprint, _, _) = collect_data_footprint(&state).await?;
+    log::debug!(
+        "[GET_DATA_FOOTPRINT] database={} bytes, photos={} bytes, unreferenced={} files",
+        footprint.database.database_bytes,
+        footprint.photo_storage.bytes,
+        footprint.unreferenced_photos.count
+    );
+    Ok(footprint)
+}
+
+/// Storage footprint with the pet references and stored photo sizes it was built from
+async fn collect_data_footprint(
+    state: &AppState,
+) -> Result<(DataFootprint, Vec<PetFileReferences>, Vec<(String, u64)>), PetError> {
+    let database = state
+        .database
+        .get_database_footprint()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    let pets = state
+        .database
+        .get_pet_file_references()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    let stored_photos = state
+        .photo_service
+        .run_on_workers(|service| service.list_photo_sizes())
+        .await?;
+
+    let footprint = build_data_footprint(database, &pets, &stored_photos);
+    Ok((footprint, pets, stored_photos))
+}
+
+/// Get the configured storage quota
+#[tauri::command]
+pub async fn get_storage_quota(state: State<'_, AppState>) -> Result<StorageQuota, PetError> {
+    state.database.get_storage_quota().await
+}
+
+/// Set how much disk space the app may use; `max_bytes` None removes the limit
+#[tauri::command]
+pub async fn set_storage_quota(
+    state: State<'_, AppState>,
+    quota: StorageQuota,
+) -> Result<QuotaStatus, PetError> {
+    state.authorize("set_storage_quota", Permission::Write)?;
+
+    log::info!(
+        "[SET_STORAGE_QUOTA] max_bytes={:?}, warn_percent={}",
+        quota.max_bytes,
+        quota.warn_percent
+    );
+    state.database.set_storage_quota(&quota).await?;
+    get_quota_status(state).await
+}
+
+/// Get storage used by the database, photos and documents against the quota
+#[tauri::command]
+pub async fn get_quota_status(state: State<'_, AppState>) -> Result<QuotaStatus, PetError> {
+    let quota = state.database.get_storage_quota().await?;
+    let (footprint, _, _) = collect_data_footprint(&state).await?;
+
+    let status = build_quota_status(quota, &footprint);
+    log::debug!(
+        "[GET_QUOTA_STATUS] used={} bytes, max={:?}, level={:?}",
+        status.used_bytes,
+        status.quota.max_bytes,
+        status.level
+    );
+    Ok(status)
+}
+
+/// Suggest attachments to delete or re-encode with the space each would free.
+///
+/// Without `bytes_to_free`, plans for the space needed to get back under the quota
+/// warning threshold, or lists the biggest wins when storage is within the quota.
+#[tauri::command]
+pub async fn suggest_storage_cleanup(
+    state: State<'_, AppState>,
+    bytes_to_free: Option<u64>,
+) -> Result<StorageCleanupPlan, PetError> {
+    let quota = state.database.get_storage_quota().await?;
+    let (footprint, pets, stored_photos) = collect_data_footprint(&state).await?;
+    let bytes_to_free = bytes_to_free.or_else(|| {
+        let status = build_quota_status(quota, &footprint);
+        (status.bytes_over_warning > 0).then_some(status.bytes_over_warning)
+    });
+
+    let attachments = state
+        .database
+        .get_attachment_usage()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    // Photos of trashed activities are kept for a restore
+    let trashed = state
+        .database
+        .get_trashed_attachment_files()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    let kept_photos = pets
+        .into_iter()
+        .filter_map(|pet| pet.profile_photo)
+        .chain(trashed)
+        .collect();
+    let estimate = state
+        .photo_service
+        .estimate_storage_async(PhotoSettings::low_storage())
+        .await?;
+    let reencode_ratio = if estimate.current_size > 0 {
+        estimate.estimated_size as f64 / estimate.current_size as f64
+    } else {
+        1.0
+    };
+
+    let plan = build_cleanup_plan(
+        &stored_photos,
+        &attachments,
+        &kept_photos,
+        reencode_ratio,
+        bytes_to_free,
+        chrono::Utc::now(),
+    );
+    log::debug!(
+        "[SUGGEST_STORAGE_CLEANUP] {} suggestions, projected savings={} bytes, target={:?}",
+        plan.suggestions.len(),
+        plan.projected_savings,
+        plan.bytes_to_free
+    );
+    Ok(plan)
+}
+
+/// Get the access level of the current app context so the UI can hide editing controls
+#[tauri::command]
+pub async fn get_access_level(state: State<'_, AppState>) -> Result<AccessLevel, PetError> {
+    Ok(state.access_level)
+}
+
+/// Retry attachment file deletions that failed earlier
+#[tauri::command]
+pub async fn retry_file_deletions(
+    state: State<'_, AppState>,
+) -> Result<FileCleanupReport, PetError> {
+    state.authorize("retry_file_deletions", Permission::Write)?;
+
+    log::info!("[RETRY_FILE_DELETIONS] Processing pending file deletions");
+    state.process_pending_file_deletions().await
+}
+
+/// Add an example pet with a few weeks of activities and photos, so a new user
+/// can explore the app before logging their own pets
+#[tauri::command]
+pub async fn seed_demo_data(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DemoData, PetError> {
+    state.authorize("seed_demo_data", Permission::Write)?;
+
+    log::info!("[SEED_DEMO_DATA] Seeding demo data");
+    if state.database.get_demo_data().await?.is_some() {
+        return Err(PetError::validation(
+            "demo_data",
+            "Demo data is already loaded",
+        ));
+    }
+
+    let mut stored = Vec::new();
+    for bytes in [DEMO_PROFILE_PHOTO, DEMO_WALK_PHOTO, DEMO_MEAL_PHOTO] {
+        match state
+            .photo_service
+            .store_photo_from_bytes_async(bytes.to_vec(), Some("png".to_string()), false)
+            .await
+        {
+            Ok(photo) => stored.push(photo),
+            Err(e) => {
+                log::error!("[SEED_DEMO_DATA] Failed to store demo photo: {e}");
+                delete_demo_photos(&state, &stored);
+                return Err(e);
+            }
+        }
+    }
+    let photos = DemoPhotos {
+        profile: stored[0].clone(),
+        walk: stored[1].clone(),
+        meal: stored[2].clone(),
+    };
+
+    let demo = match state
+        .database
+        .seed_demo_data(&photos, chrono::Local::now().date_naive())
+        .await
+    {
+        Ok(demo) => demo,
+        Err(e) => {
+            delete_demo_photos(&state, &stored);
+            return Err(e);
+        }
+    };
+
+    for &pet_id in &demo.pet_ids {
+        if let Ok(pet) = state.database.get_pet_by_id(pet_id).await {
+            state
+                .event_bus
+                .emit(&app_handle, events::PET_CREATED, PetDto::from(pet));
+        }
+    }
+    log::info!(
+        "[SEED_DEMO_DATA] Seeded pets={:?}, activities={}",
+        demo.pet_ids,
+        demo.activity_ids.len()
+    );
+    Ok(demo)
+}
+
+/// Remove the pets, activities and photos added by `seed_demo_data`, leaving
+/// everything the user created in place
+#[tauri::command]
+pub async fn remove_demo_data(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<DemoDataRemoval, PetError> {
+    state.authorize("remove_demo_data", Permission::Write)?;
+
+    log::info!("[REMOVE_DEMO_DATA] Removing demo data");
+    let removal = state.database.remove_demo_data().await?;
+
+    // The rows are gone; files that can't be removed now stay journaled for retry
+    let cleanup = state
+        .process_file_deletions(removal.pending_files.clone())
+        .await;
+    delete_demo_photos(&state, &removal.photos);
+
+    for &id in &removal.pets_removed {
+        state.event_bus.emit(
+            &app_handle,
+            events::PET_DELETED,
+            DeletedPayload { id, pet_id: None },
+        );
+    }
+    log::info!(
+        "[REMOVE_DEMO_DATA] Removed activities={}, pets={:?}, kept pets={:?}, files deleted={}, pending={}",
+        removal.activities_removed,
+        removal.pets_removed,
+        removal.pets_kept,
+        cleanup.deleted,
+        cleanup.failed
+    );
+    Ok(removal)
+}
+
+fn delete_demo_photos(state: &AppState, photos: &[String]) {
+    for photo in photos {
+        if let Err(e) = state.photo_service.delete_photo(photo) {
+            log::warn!("Failed to delete demo photo {photo}: {e}");
+        }
+    }
+}
+
 /// Application statistics data structure
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct AppStatistics {
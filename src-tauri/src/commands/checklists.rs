@@ -0,0 +1,105 @@
+use super::{AppState, Permission};
+use crate::checklists::{self, ChecklistTemplate};
+use crate::database::{Checklist, PetSpecies};
+use crate::errors::PetError;
+use chrono::NaiveDate;
+use tauri::State;
+
+/// List built-in checklist templates, optionally only those for a species
+#[tauri::command]
+pub async fn list_checklist_templates(
+    species: Option<PetSpecies>,
+) -> Result<Vec<ChecklistTemplate>, PetError> {
+    Ok(checklists::all_templates()
+        .iter()
+        .filter(|template| species.as_ref().is_none_or(|s| template.applies_to(s)))
+        .cloned()
+        .collect())
+}
+
+/// Start a checklist for a pet from a template
+#[tauri::command]
+pub async fn start_checklist(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    template_key: String,
+    start_date: Option<NaiveDate>,
+) -> Result<Checklist, PetError> {
+    state.authorize("start_checklist", Permission::Write)?;
+
+    log::info!("[START_CHECKLIST] pet_id={pet_id}, template={template_key}");
+
+    let template = checklists::find_template(&template_key).ok_or_else(|| {
+        PetError::validation(
+            "template_key".to_string(),
+            format!("Unknown checklist template: {template_key}"),
+        )
+    })?;
+
+    let pet = state
+        .database
+        .get_pet_by_id(pet_id)
+        .await
+        .map_err(|_| PetError::not_found(pet_id))?;
+    if !template.applies_to(&pet.species) {
+        return Err(PetError::validation(
+            "template_key".to_string(),
+            format!(
+                "Template '{template_key}' does not apply to a {}",
+                pet.species
+            ),
+        ));
+    }
+
+    let start_date = start_date.unwrap_or_else(|| chrono::Local::now().date_naive());
+    let checklist = state
+        .database
+        .create_checklist_from_template(pet_id, template, start_date)
+        .await?;
+
+    log::info!("[START_CHECKLIST] Success: checklist_id={}", checklist.id);
+    Ok(checklist)
+}
+
+/// Get a pet's checklists; completed ones are included unless `include_completed` is false
+#[tauri::command]
+pub async fn get_pet_checklists(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    include_completed: Option<bool>,
+) -> Result<Vec<Checklist>, PetError> {
+    log::debug!("[GET_PET_CHECKLISTS] pet_id={pet_id}");
+    state
+        .database
+        .get_checklists_for_pet(pet_id, include_completed.unwrap_or(true))
+        .await
+}
+
+/// Check or uncheck a checklist item
+#[tauri::command]
+pub async fn set_checklist_item_completed(
+    state: State<'_, AppState>,
+    item_id: i64,
+    completed: bool,
+    notes: Option<String>,
+) -> Result<Checklist, PetError> {
+    state.authorize("set_checklist_item_completed", Permission::Write)?;
+
+    log::info!("[SET_CHECKLIST_ITEM_COMPLETED] item_id={item_id}, completed={completed}");
+    state
+        .database
+        .set_checklist_item_completed(item_id, completed, notes)
+        .await
+}
+
+/// Delete a checklist and its progress
+#[tauri::command]
+pub async fn delete_checklist(
+    state: State<'_, AppState>,
+    checklist_id: i64,
+) -> Result<(), PetError> {
+    state.authorize("delete_checklist", Permission::Write)?;
+
+    log::info!("[DELETE_CHECKLIST] checklist_id={checklist_id}");
+    state.database.delete_checklist(checklist_id).await
+}
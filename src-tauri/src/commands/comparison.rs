@@ -0,0 +1,149 @@
+use super::{AppState, Permission};
+use crate::database::budgets::{BudgetStatus, PetBudget};
+use crate::database::comparison::{self, ComparisonMetric, PetComparison};
+use crate::database::cost_benchmarks::CostBenchmarks;
+use crate::database::expense_forecast::ExpenseForecast;
+use crate::database::heatmap::ActivityHeatmap;
+use crate::errors::ActivityError;
+use crate::periods::PeriodGranularity;
+use chrono::NaiveDate;
+use tauri::State;
+
+/// Compare 2-4 pets side by side over the last `months` months, with weekly or
+/// monthly (default) series aligned on the same periods
+#[tauri::command]
+pub async fn compare_pets(
+    state: State<'_, AppState>,
+    pet_ids: Vec<i64>,
+    metrics: Vec<ComparisonMetric>,
+    months: Option<u32>,
+    granularity: Option<PeriodGranularity>,
+) -> Result<PetComparison, ActivityError> {
+    log::info!(
+        "[COMPARE_PETS] pet_ids={pet_ids:?}, metrics={metrics:?}, months={months:?}, granularity={granularity:?}"
+    );
+
+    let weight_unit = state
+        .database
+        .get_weight_unit()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    let settings = state
+        .database
+        .get_period_settings()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    let buckets = comparison::comparison_buckets(
+        chrono::Local::now().date_naive(),
+        months,
+        granularity.unwrap_or_default(),
+        settings,
+    );
+    let comparison = state
+        .database
+        .compare_pets(&pet_ids, &metrics, &buckets, weight_unit)
+        .await?;
+
+    log::info!(
+        "[COMPARE_PETS] Success: {} series over {} periods",
+        comparison.series.len(),
+        comparison.periods.len()
+    );
+    Ok(comparison)
+}
+
+/// Historical min/median/max cost of a service (activity subcategory) for a pet,
+/// to compare a new expense against what was paid before
+#[tauri::command]
+pub async fn get_cost_benchmarks(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    subcategory: String,
+) -> Result<CostBenchmarks, ActivityError> {
+    log::debug!("[GET_COST_BENCHMARKS] pet_id={pet_id}, subcategory={subcategory}");
+    state
+        .database
+        .get_cost_benchmarks(pet_id, &subcategory)
+        .await
+}
+
+/// Project a pet's monthly costs for the next `months_ahead` months (1-24) from
+/// seasonal averages of its past expenses, with a range for budgeting
+#[tauri::command]
+pub async fn forecast_expenses(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    months_ahead: u32,
+) -> Result<ExpenseForecast, ActivityError> {
+    log::debug!("[FORECAST_EXPENSES] pet_id={pet_id}, months_ahead={months_ahead}");
+
+    let forecast = state
+        .database
+        .forecast_expenses(pet_id, months_ahead, chrono::Local::now().date_naive())
+        .await?;
+
+    log::debug!(
+        "[FORECAST_EXPENSES] Based on {} months, seasonal={}",
+        forecast.history_months,
+        forecast.seasonal
+    );
+    Ok(forecast)
+}
+
+/// A pet's spending in the month containing `month` against its monthly budget
+#[tauri::command]
+pub async fn get_budget_status(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    month: NaiveDate,
+) -> Result<BudgetStatus, ActivityError> {
+    log::debug!("[GET_BUDGET_STATUS] pet_id={pet_id}, month={month}");
+
+    let status = state.database.get_budget_status(pet_id, month).await?;
+
+    log::debug!(
+        "[GET_BUDGET_STATUS] spent={}, percent_used={:?}",
+        status.spent,
+        status.percent_used
+    );
+    Ok(status)
+}
+
+/// Get a pet's monthly budget, None when it has none
+#[tauri::command]
+pub async fn get_pet_budget(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<Option<PetBudget>, ActivityError> {
+    state.database.get_pet_budget(pet_id).await
+}
+
+/// Set a pet's monthly budget, or remove it with None. Alerts are sent when
+/// spending reaches 80% and 100% of it.
+#[tauri::command]
+pub async fn set_pet_budget(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    budget: Option<PetBudget>,
+) -> Result<Option<PetBudget>, ActivityError> {
+    state.authorize("set_pet_budget", Permission::Write)?;
+
+    log::info!("[SET_PET_BUDGET] pet_id={pet_id}, budget={budget:?}");
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        log::error!("[SET_PET_BUDGET] Pet not found: pet_id={pet_id}, error={e}");
+        return Err(ActivityError::validation("pet_id", "Pet not found"));
+    }
+
+    state.database.set_pet_budget(pet_id, budget).await
+}
+
+/// Activities logged per day of `year`, for the annual contribution-graph overview
+#[tauri::command]
+pub async fn get_activity_heatmap(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    year: i32,
+) -> Result<ActivityHeatmap, ActivityError> {
+    log::debug!("[GET_ACTIVITY_HEATMAP] pet_id={pet_id}, year={year}");
+    state.database.get_activity_heatmap(pet_id, year).await
+}
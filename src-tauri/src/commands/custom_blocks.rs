@@ -0,0 +1,54 @@
+use super::{AppState, Permission};
+use crate::database::{CreateCustomBlockRequest, CustomBlockDefinition, CustomBlockUpdateRequest};
+use crate::errors::ActivityError;
+use tauri::State;
+
+/// Define a custom block type, e.g. insulin dosing or grooming details
+#[tauri::command]
+pub async fn create_custom_block(
+    state: State<'_, AppState>,
+    request: CreateCustomBlockRequest,
+) -> Result<CustomBlockDefinition, ActivityError> {
+    state.authorize("create_custom_block", Permission::Write)?;
+
+    log::info!(
+        "[CREATE_CUSTOM_BLOCK] name={}, fields={}",
+        request.name,
+        request.fields.len()
+    );
+    state.database.create_custom_block(request).await
+}
+
+/// Change a custom block's label or fields; its name stays fixed
+#[tauri::command]
+pub async fn update_custom_block(
+    state: State<'_, AppState>,
+    block_id: i64,
+    updates: CustomBlockUpdateRequest,
+) -> Result<CustomBlockDefinition, ActivityError> {
+    state.authorize("update_custom_block", Permission::Write)?;
+
+    log::info!("[UPDATE_CUSTOM_BLOCK] block_id={block_id}");
+    state.database.update_custom_block(block_id, updates).await
+}
+
+/// Remove a custom block type that no activity uses
+#[tauri::command]
+pub async fn delete_custom_block(
+    state: State<'_, AppState>,
+    block_id: i64,
+) -> Result<(), ActivityError> {
+    state.authorize("delete_custom_block", Permission::Write)?;
+
+    log::info!("[DELETE_CUSTOM_BLOCK] block_id={block_id}");
+    state.database.delete_custom_block(block_id).await
+}
+
+/// All custom block types, for the block picker and the activity form
+#[tauri::command]
+pub async fn get_custom_blocks(
+    state: State<'_, AppState>,
+) -> Result<Vec<CustomBlockDefinition>, ActivityError> {
+    log::debug!("[GET_CUSTOM_BLOCKS] Loading custom block definitions");
+    state.database.get_custom_blocks().await
+}
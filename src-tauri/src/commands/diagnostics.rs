@@ -0,0 +1,30 @@
+use super::AppState;
+use crate::database::diagnostics::DiagnosticQueryResult;
+use crate::errors::ActivityError;
+use tauri::State;
+
+/// Run a read-only SELECT for troubleshooting, with row/time limits and sensitive columns redacted
+#[tauri::command]
+pub async fn run_diagnostic_query(
+    state: State<'_, AppState>,
+    sql: String,
+    max_rows: Option<usize>,
+) -> Result<DiagnosticQueryResult, ActivityError> {
+    log::info!("[RUN_DIAGNOSTIC_QUERY] max_rows={max_rows:?}");
+
+    match state.database.run_diagnostic_query(&sql, max_rows).await {
+        Ok(result) => {
+            log::info!(
+                "[RUN_DIAGNOSTIC_QUERY] {} rows, {} redacted columns, {} ms",
+                result.rows.len(),
+                result.redacted_columns.len(),
+                result.elapsed_ms
+            );
+            Ok(result)
+        }
+        Err(e) => {
+            log::warn!("[RUN_DIAGNOSTIC_QUERY] Rejected or failed: {e}");
+            Err(e)
+        }
+    }
+}
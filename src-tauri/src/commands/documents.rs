@@ -0,0 +1,182 @@
+use super::{AppState, Permission};
+use crate::database::{CreatePetDocumentRequest, DocumentCategory, ExpiringDocument, PetDocument};
+use crate::errors::PetError;
+use chrono::NaiveDate;
+use tauri::State;
+
+/// Furthest ahead `get_expiring_documents` looks
+const MAX_EXPIRY_LOOKAHEAD_DAYS: i64 = 365;
+
+/// Upload a document (adoption papers, pedigree, insurance policy...) to a pet's vault.
+/// `ocr_text` is text recognized by the frontend; plain text files are indexed from their content.
+/// `expires_on` is the renewal date of policies, registrations and passports.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_pet_document(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    category: DocumentCategory,
+    title: String,
+    filename: String,
+    document_bytes: Vec<u8>,
+    notes: Option<String>,
+    ocr_text: Option<String>,
+    expires_on: Option<NaiveDate>,
+) -> Result<PetDocument, PetError> {
+    state.authorize("upload_pet_document", Permission::Write)?;
+
+    log::info!(
+        "[UPLOAD_PET_DOCUMENT] pet_id={pet_id}, category={category}, filename={filename} ({} bytes)",
+        document_bytes.len()
+    );
+
+    if title.trim().is_empty() {
+        return Err(PetError::validation("title", "Title cannot be empty"));
+    }
+    if title.len() > 200 {
+        return Err(PetError::validation(
+            "title",
+            "Title cannot exceed 200 characters",
+        ));
+    }
+    if filename.trim().is_empty() {
+        return Err(PetError::validation("filename", "Filename cannot be empty"));
+    }
+
+    // Ensure the pet exists before touching the filesystem
+    state
+        .database
+        .get_pet_by_id(pet_id)
+        .await
+        .map_err(|_| PetError::not_found(pet_id))?;
+
+    let stored = state
+        .document_service
+        .store_document(&document_bytes, &filename)?;
+
+    let ocr_text = ocr_text.filter(|text| !text.trim().is_empty()).or_else(|| {
+        (stored.mime_type == "text/plain")
+            .then(|| String::from_utf8_lossy(&document_bytes).into_owned())
+    });
+
+    let result = state
+        .database
+        .create_pet_document(CreatePetDocumentRequest {
+            pet_id,
+            category,
+            title: title.trim().to_string(),
+            original_filename: filename,
+            stored_filename: stored.filename.clone(),
+            mime_type: stored.mime_type,
+            file_size: stored.file_size as i64,
+            sha256: stored.sha256,
+            notes,
+            ocr_text,
+            expires_on,
+        })
+        .await;
+
+    match result {
+        Ok(document) => {
+            log::info!(
+                "[UPLOAD_PET_DOCUMENT] Success: document_id={}, stored as {}",
+                document.id,
+                document.stored_filename
+            );
+            Ok(document)
+        }
+        Err(e) => {
+            // Remove the file again unless another record already uses the same content
+            if !state
+                .database
+                .is_document_file_referenced(&stored.filename)
+                .await
+                .unwrap_or(true)
+            {
+                let _ = state.document_service.delete_document(&stored.filename);
+            }
+            log::error!("[UPLOAD_PET_DOCUMENT] Failed: {e}");
+            Err(e)
+        }
+    }
+}
+
+/// List a pet's documents, optionally filtered by category
+#[tauri::command]
+pub async fn list_pet_documents(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    category: Option<DocumentCategory>,
+) -> Result<Vec<PetDocument>, PetError> {
+    log::debug!("[LIST_PET_DOCUMENTS] pet_id={pet_id}, category={category:?}");
+    state.database.get_pet_documents(pet_id, category).await
+}
+
+/// Delete a document and its stored file when no other record uses it
+#[tauri::command]
+pub async fn delete_pet_document(
+    state: State<'_, AppState>,
+    document_id: i64,
+) -> Result<(), PetError> {
+    state.authorize("delete_pet_document", Permission::Write)?;
+
+    log::info!("[DELETE_PET_DOCUMENT] document_id={document_id}");
+
+    let (document, still_referenced) = state.database.delete_pet_document(document_id).await?;
+
+    if !still_referenced {
+        if let Err(e) = state
+            .document_service
+            .delete_document(&document.stored_filename)
+        {
+            log::warn!(
+                "[DELETE_PET_DOCUMENT] Failed to delete file {}: {e}",
+                document.stored_filename
+            );
+        }
+    }
+
+    log::info!("[DELETE_PET_DOCUMENT] Success: document_id={document_id}");
+    Ok(())
+}
+
+/// Set or clear the date a document expires, e.g. after renewing a policy
+#[tauri::command]
+pub async fn set_pet_document_expiry(
+    state: State<'_, AppState>,
+    document_id: i64,
+    expires_on: Option<NaiveDate>,
+) -> Result<PetDocument, PetError> {
+    state.authorize("set_pet_document_expiry", Permission::Write)?;
+
+    log::info!("[SET_PET_DOCUMENT_EXPIRY] document_id={document_id}, expires_on={expires_on:?}");
+    state
+        .database
+        .set_pet_document_expiry(document_id, expires_on)
+        .await
+}
+
+/// Documents of active pets expiring within `days_ahead` days, including ones that
+/// expired recently and haven't been renewed
+#[tauri::command]
+pub async fn get_expiring_documents(
+    state: State<'_, AppState>,
+    days_ahead: i64,
+) -> Result<Vec<ExpiringDocument>, PetError> {
+    if !(0..=MAX_EXPIRY_LOOKAHEAD_DAYS).contains(&days_ahead) {
+        return Err(PetError::validation(
+            "days_ahead".to_string(),
+            format!("Days ahead must be between 0 and {MAX_EXPIRY_LOOKAHEAD_DAYS}"),
+        ));
+    }
+
+    let expiring = state
+        .database
+        .get_expiring_documents(days_ahead, chrono::Local::now().date_naive())
+        .await?;
+    log::debug!(
+        "[GET_EXPIRING_DOCUMENTS] days_ahead={days_ahead}, found={}",
+        expiring.len()
+    );
+    Ok(expiring)
+}
@@ -0,0 +1,276 @@
+//! Request and response types of the pet and activity commands.
+//!
+//! These are the frontend contract: fields are camelCase and listed explicitly, and
+//! each type converts to or from its database model here. A change to a database
+//! struct then fails to compile in this file instead of silently changing the JSON
+//! the frontend receives. Request types also accept the snake_case field names
+//! earlier frontends sent.
+
+use crate::database::activity_data::ActivityDataExt;
+use crate::database::{
+    Activity, ActivityCategory, ActivityCreateRequest, ActivityUpdateRequest, CreatePetRequest,
+    Pet, PetGender, PetSpecies, UpdatePetRequest, WeightUnit,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A pet as returned to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PetDto {
+    pub id: i64,
+    pub name: String,
+    pub birth_date: NaiveDate,
+    pub species: PetSpecies,
+    pub gender: PetGender,
+    pub breed: Option<String>,
+    pub color: Option<String>,
+    /// Canonical weight; `display_weight` is the same weight in `display_unit`
+    pub weight_kg: Option<f32>,
+    pub display_weight: Option<f32>,
+    pub display_unit: WeightUnit,
+    pub photo_path: Option<String>,
+    pub notes: Option<String>,
+    pub display_order: i64,
+    pub is_archived: bool,
+    pub is_memorial: bool,
+    pub passed_away_on: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Pet> for PetDto {
+    fn from(pet: Pet) -> Self {
+        PetDto {
+            id: pet.id,
+            name: pet.name,
+            birth_date: pet.birth_date,
+            species: pet.species,
+            gender: pet.gender,
+            breed: pet.breed,
+            color: pet.color,
+            weight_kg: pet.weight_kg,
+            display_weight: pet.display_weight,
+            display_unit: pet.display_unit,
+            photo_path: pet.photo_path,
+            notes: pet.notes,
+            display_order: pet.display_order,
+            is_archived: pet.is_archived,
+            is_memorial: pet.is_memorial,
+            passed_away_on: pet.passed_away_on,
+            created_at: pet.created_at,
+            updated_at: pet.updated_at,
+        }
+    }
+}
+
+/// Fields of a new pet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePetInput {
+    pub name: String,
+    #[serde(alias = "birth_date")]
+    pub birth_date: NaiveDate,
+    pub species: PetSpecies,
+    pub gender: PetGender,
+    #[serde(default)]
+    pub breed: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default, alias = "weight_kg")]
+    pub weight_kg: Option<f32>,
+    #[serde(default, alias = "photo_path")]
+    pub photo_path: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+impl From<CreatePetInput> for CreatePetRequest {
+    fn from(input: CreatePetInput) -> Self {
+        CreatePetRequest {
+            name: input.name,
+            birth_date: input.birth_date,
+            species: input.species,
+            gender: input.gender,
+            breed: input.breed,
+            color: input.color,
+            weight_kg: input.weight_kg,
+            photo_path: input.photo_path,
+            notes: input.notes,
+        }
+    }
+}
+
+/// Pet fields to change; absent fields keep their value
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePetInput {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default, alias = "birth_date")]
+    pub birth_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub species: Option<PetSpecies>,
+    #[serde(default)]
+    pub gender: Option<PetGender>,
+    #[serde(default)]
+    pub breed: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default, alias = "weight_kg")]
+    pub weight_kg: Option<f32>,
+    #[serde(default, alias = "photo_path")]
+    pub photo_path: Option<String>,
+    #[serde(default)]
+    pub notes: Option<String>,
+}
+
+impl From<UpdatePetInput> for UpdatePetRequest {
+    fn from(input: UpdatePetInput) -> Self {
+        UpdatePetRequest {
+            name: input.name,
+            birth_date: input.birth_date,
+            species: input.species,
+            gender: input.gender,
+            breed: input.breed,
+            color: input.color,
+            weight_kg: input.weight_kg,
+            photo_path: input.photo_path,
+            notes: input.notes,
+        }
+    }
+}
+
+/// An activity as returned to the frontend, with its data in frontend block format
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityDto {
+    pub id: i64,
+    pub pet_id: i64,
+    pub category: ActivityCategory,
+    pub subcategory: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub activity_data: Option<serde_json::Value>,
+    pub needs_review: bool,
+    pub is_private: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Activity> for ActivityDto {
+    fn from(activity: Activity) -> Self {
+        ActivityDto {
+            id: activity.id,
+            pet_id: activity.pet_id,
+            category: activity.category,
+            subcategory: activity.subcategory,
+            activity_data: activity
+                .activity_data
+                .as_ref()
+                .map(|data| data.to_frontend_blocks()),
+            needs_review: activity.needs_review,
+            is_private: activity.is_private,
+            created_at: activity.created_at,
+            updated_at: activity.updated_at,
+        }
+    }
+}
+
+/// Fields of a new activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateActivityInput {
+    #[serde(alias = "pet_id")]
+    pub pet_id: i64,
+    pub category: ActivityCategory,
+    pub subcategory: String,
+    /// Blocks keyed by block name
+    #[serde(default, alias = "activity_data")]
+    pub activity_data: Option<serde_json::Value>,
+    /// Saves the activity to the review queue, e.g. an unchanged quick-entry draft
+    #[serde(default, alias = "needs_review")]
+    pub needs_review: bool,
+}
+
+impl From<CreateActivityInput> for ActivityCreateRequest {
+    fn from(input: CreateActivityInput) -> Self {
+        ActivityCreateRequest {
+            pet_id: input.pet_id,
+            category: input.category,
+            subcategory: input.subcategory,
+            activity_data: input.activity_data,
+            needs_review: input.needs_review,
+        }
+    }
+}
+
+/// Activity fields to change; absent fields keep their value
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateActivityInput {
+    #[serde(default)]
+    pub category: Option<ActivityCategory>,
+    #[serde(default)]
+    pub subcategory: Option<String>,
+    #[serde(default, alias = "activity_data")]
+    pub activity_data: Option<serde_json::Value>,
+}
+
+impl From<UpdateActivityInput> for ActivityUpdateRequest {
+    fn from(input: UpdateActivityInput) -> Self {
+        ActivityUpdateRequest {
+            category: input.category,
+            subcategory: input.subcategory,
+            activity_data: input.activity_data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_dtos_use_camel_case_and_accept_legacy_requests() {
+        let now = Utc::now();
+        let activity = ActivityDto::from(Activity {
+            id: 7,
+            pet_id: 3,
+            category: ActivityCategory::Diet,
+            subcategory: "Breakfast".to_string(),
+            activity_data: None,
+            needs_review: true,
+            created_at: now,
+            updated_at: now,
+        });
+        let json = serde_json::to_value(&activity).unwrap();
+        assert_eq!(json["petId"], 3);
+        assert_eq!(json["needsReview"], true);
+        assert!(json.get("pet_id").is_none());
+        assert!(json.get("activityData").is_none());
+
+        let camel: CreateActivityInput = serde_json::from_value(json!({
+            "petId": 3,
+            "category": "Diet",
+            "subcategory": "Breakfast",
+            "activityData": { "notes": "Ate well" },
+        }))
+        .unwrap();
+        let snake: CreateActivityInput = serde_json::from_value(json!({
+            "pet_id": 3,
+            "category": "Diet",
+            "subcategory": "Breakfast",
+            "activity_data": { "notes": "Ate well" },
+        }))
+        .unwrap();
+        assert_eq!(camel.pet_id, snake.pet_id);
+        assert_eq!(camel.activity_data, snake.activity_data);
+
+        let pet: UpdatePetInput =
+            serde_json::from_value(json!({ "birthDate": "2021-04-02", "weight_kg": 4.2 })).unwrap();
+        let request = UpdatePetRequest::from(pet);
+        assert_eq!(request.birth_date, NaiveDate::from_ymd_opt(2021, 4, 2));
+        assert_eq!(request.weight_kg, Some(4.2));
+    }
+}
@@ -0,0 +1,361 @@
+use super::{AppState, Permission};
+use crate::database::budgets::{BudgetAlertLevel, BudgetStatus};
+use crate::database::grooming::GroomingReminder;
+use crate::database::{
+    DueReminder, ExpiringDocument, NotificationPreferences, OverdueFollowUp, PetDatabase,
+    UpdateNotificationPreferencesRequest,
+};
+use crate::errors::{ActivityError, PetError};
+use crate::events::{self, EventBus, EventReplay, SessionChanges};
+use crate::notifications::{
+    self, NotificationSettings, ReminderNotification, NOTIFICATION_SETTINGS_KEY,
+};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+/// How often the background task looks for due reminders
+const REMINDER_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Reminders older than this are not announced
+const REMINDER_LOOKBACK_DAYS: i64 = 7;
+
+/// Documents are announced this many days before they expire
+const DOCUMENT_EXPIRY_NOTICE_DAYS: i64 = 30;
+
+/// Replay events a view may have missed, e.g. after being hidden or reloaded
+#[tauri::command]
+pub async fn replay_events(
+    state: State<'_, AppState>,
+    since_seq: u64,
+) -> Result<EventReplay, ActivityError> {
+    let replay = state.event_bus.replay_since(since_seq);
+    log::debug!(
+        "[REPLAY_EVENTS] since_seq={since_seq}, returned={}, latest_seq={}, truncated={}",
+        replay.events.len(),
+        replay.latest_seq,
+        replay.truncated
+    );
+    Ok(replay)
+}
+
+/// Summarize every create, update and delete made since the app started, so the
+/// user can review what they just changed before closing the app
+#[tauri::command]
+pub async fn get_session_changes(
+    state: State<'_, AppState>,
+) -> Result<SessionChanges, ActivityError> {
+    let session = state.event_bus.session_changes();
+    log::debug!(
+        "[GET_SESSION_CHANGES] changes={}, truncated={}",
+        session.changes.len(),
+        session.truncated
+    );
+    Ok(session)
+}
+
+/// Check for due reminders immediately and emit `reminder:due` for new ones
+#[tauri::command]
+pub async fn check_due_reminders(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<DueReminder>, ActivityError> {
+    announce_due_reminders(&app_handle, &state.database, &state.event_bus).await
+}
+
+/// Get a pet's health visits with an overdue follow-up, for the notification center
+#[tauri::command]
+pub async fn get_overdue_followups(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<Vec<OverdueFollowUp>, ActivityError> {
+    let overdue = state
+        .database
+        .get_overdue_followups(pet_id, chrono::Local::now().date_naive())
+        .await?;
+    log::debug!(
+        "[GET_OVERDUE_FOLLOWUPS] pet_id={pet_id}, overdue={}",
+        overdue.len()
+    );
+    Ok(overdue)
+}
+
+/// Get a pet's notification preferences
+#[tauri::command]
+pub async fn get_notification_preferences(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<NotificationPreferences, ActivityError> {
+    log::debug!("[GET_NOTIFICATION_PREFERENCES] pet_id={pet_id}");
+    state.database.get_notification_preferences(pet_id).await
+}
+
+/// Mute a pet's reminders or give it its own quiet hours
+#[tauri::command]
+pub async fn update_notification_preferences(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    preferences: UpdateNotificationPreferencesRequest,
+) -> Result<NotificationPreferences, ActivityError> {
+    state.authorize("update_notification_preferences", Permission::Write)?;
+
+    log::info!("[UPDATE_NOTIFICATION_PREFERENCES] pet_id={pet_id}, preferences={preferences:?}");
+
+    if let Some(quiet_hours) = preferences.quiet_hours {
+        if quiet_hours.start == quiet_hours.end {
+            return Err(ActivityError::validation(
+                "quiet_hours",
+                "Quiet hours must start and end at different times",
+            ));
+        }
+    }
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        log::error!("[UPDATE_NOTIFICATION_PREFERENCES] Pet not found: pet_id={pet_id}, error={e}");
+        return Err(ActivityError::validation("pet_id", "Pet not found"));
+    }
+
+    state
+        .database
+        .update_notification_preferences(pet_id, preferences)
+        .await
+}
+
+/// Get the app-wide quiet hours and reminder batching settings
+#[tauri::command]
+pub async fn get_notification_settings(
+    state: State<'_, AppState>,
+) -> Result<NotificationSettings, ActivityError> {
+    notification_settings(&state.database).await
+}
+
+/// Set quiet hours for every pet and whether reminders due together are sent as one digest
+#[tauri::command]
+pub async fn update_notification_settings(
+    state: State<'_, AppState>,
+    settings: NotificationSettings,
+) -> Result<NotificationSettings, ActivityError> {
+    state.authorize("update_notification_settings", Permission::Write)?;
+
+    log::info!("[UPDATE_NOTIFICATION_SETTINGS] settings={settings:?}");
+    settings.validate()?;
+    state
+        .database
+        .set_setting(NOTIFICATION_SETTINGS_KEY, &settings)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    Ok(settings)
+}
+
+/// Announce each reminder that has become due once: as `reminder:due`, or as one
+/// `reminder:digest` when enough come due in the same check.
+/// Reminders of muted pets are dropped; those in the app-wide or a pet's quiet
+/// hours are queued and announced together once the quiet hours are over.
+pub async fn announce_due_reminders(
+    app_handle: &AppHandle,
+    database: &PetDatabase,
+    event_bus: &EventBus,
+) -> Result<Vec<DueReminder>, ActivityError> {
+    let due = database
+        .get_due_reminders(chrono::Utc::now(), REMINDER_LOOKBACK_DAYS)
+        .await?;
+    let preferences = database.get_all_notification_preferences().await?;
+    let settings = notification_settings(database).await?;
+
+    let schedule = notifications::schedule_reminders(
+        due,
+        &settings,
+        &preferences,
+        chrono::Local::now().time(),
+    );
+    if !schedule.queued.is_empty() {
+        log::debug!(
+            "Reminders queued for quiet hours: {}",
+            schedule.queued.len()
+        );
+    }
+    let announced: Vec<DueReminder> = schedule
+        .send
+        .into_iter()
+        .filter(|r| event_bus.mark_reminder_announced(r.activity_id, r.due_at))
+        .collect();
+
+    for notification in notifications::batch_reminders(announced.clone(), &settings) {
+        match notification {
+            ReminderNotification::Single(reminder) => {
+                log::info!(
+                    "Reminder due: activity_id={}, pet_id={}, title={}",
+                    reminder.activity_id,
+                    reminder.pet_id,
+                    reminder.title
+                );
+                event_bus.emit(app_handle, events::REMINDER_DUE, &reminder);
+            }
+            ReminderNotification::Digest(digest) => {
+                log::info!("Reminder digest: {} reminders due", digest.count);
+                event_bus.emit(app_handle, events::REMINDER_DIGEST, &digest);
+            }
+        }
+    }
+
+    Ok(announced)
+}
+
+async fn notification_settings(
+    database: &PetDatabase,
+) -> Result<NotificationSettings, ActivityError> {
+    database
+        .get_notification_settings()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+}
+
+/// Emit `document:expiring` once per document expiry date that comes within
+/// [`DOCUMENT_EXPIRY_NOTICE_DAYS`]. Documents of pets with muted reminders are skipped.
+pub async fn announce_expiring_documents(
+    app_handle: &AppHandle,
+    database: &PetDatabase,
+    event_bus: &EventBus,
+) -> Result<Vec<ExpiringDocument>, PetError> {
+    let expiring = database
+        .get_expiring_documents(
+            DOCUMENT_EXPIRY_NOTICE_DAYS,
+            chrono::Local::now().date_naive(),
+        )
+        .await?;
+    let preferences = database
+        .get_all_notification_preferences()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+    let announced: Vec<ExpiringDocument> = expiring
+        .into_iter()
+        .filter(|e| {
+            preferences
+                .get(&e.document.pet_id)
+                .is_none_or(|preferences| !preferences.reminders_muted)
+        })
+        .filter(|e| event_bus.mark_expiry_announced(e.document.id, e.expires_on))
+        .collect();
+
+    for expiring in &announced {
+        log::info!(
+            "Document expiring: document_id={}, pet_id={}, expires_on={}",
+            expiring.document.id,
+            expiring.document.pet_id,
+            expiring.expires_on
+        );
+        event_bus.emit(app_handle, events::DOCUMENT_EXPIRING, expiring);
+    }
+
+    Ok(announced)
+}
+
+/// Emit `budget:alert` when a pet's spending this month first reaches 80% and
+/// again at 100% of its budget. Pets with muted reminders are skipped.
+pub async fn announce_budget_alerts(
+    app_handle: &AppHandle,
+    database: &PetDatabase,
+    event_bus: &EventBus,
+) -> Result<Vec<BudgetStatus>, ActivityError> {
+    let statuses = database
+        .get_budget_statuses(chrono::Local::now().date_naive())
+        .await?;
+    let preferences = database.get_all_notification_preferences().await?;
+
+    let announced: Vec<BudgetStatus> = statuses
+        .into_iter()
+        .filter(|s| {
+            preferences
+                .get(&s.pet_id)
+                .is_none_or(|preferences| !preferences.reminders_muted)
+        })
+        .filter(|s| {
+            let Some(alert) = s.alert else {
+                return false;
+            };
+            // Going straight past 100% announces only that the budget is spent
+            if alert == BudgetAlertLevel::Exceeded {
+                event_bus.mark_budget_alert_announced(
+                    s.pet_id,
+                    s.month,
+                    BudgetAlertLevel::Warning.percent(),
+                );
+            }
+            event_bus.mark_budget_alert_announced(s.pet_id, s.month, alert.percent())
+        })
+        .collect();
+
+    for status in &announced {
+        log::info!(
+            "Budget alert: pet_id={}, month={}, spent={}, alert={:?}",
+            status.pet_id,
+            status.month,
+            status.spent,
+            status.alert
+        );
+        event_bus.emit(app_handle, events::BUDGET_ALERT, status);
+    }
+
+    Ok(announced)
+}
+
+/// Emit `grooming:due` once for each planned grooming task that has come due.
+/// Pets with muted reminders are skipped.
+pub async fn announce_grooming_due(
+    app_handle: &AppHandle,
+    database: &PetDatabase,
+    event_bus: &EventBus,
+) -> Result<Vec<GroomingReminder>, ActivityError> {
+    let due = database
+        .get_due_grooming(chrono::Local::now().date_naive())
+        .await?;
+    let preferences = database.get_all_notification_preferences().await?;
+
+    let announced: Vec<GroomingReminder> = due
+        .into_iter()
+        .filter(|r| {
+            preferences
+                .get(&r.pet_id)
+                .is_none_or(|preferences| !preferences.reminders_muted)
+        })
+        .filter(|r| event_bus.mark_grooming_announced(r.pet_id, r.task.task.key(), r.task.next_due))
+        .collect();
+
+    for reminder in &announced {
+        log::info!(
+            "Grooming due: pet_id={}, task={}, next_due={}",
+            reminder.pet_id,
+            reminder.task.task.key(),
+            reminder.task.next_due
+        );
+        event_bus.emit(app_handle, events::GROOMING_DUE, reminder);
+    }
+
+    Ok(announced)
+}
+
+/// Start the background task that periodically announces due reminders, expiring
+/// documents, budget alerts and due grooming
+pub fn spawn_reminder_watcher(
+    app_handle: AppHandle,
+    database: Arc<PetDatabase>,
+    event_bus: Arc<EventBus>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = announce_due_reminders(&app_handle, &database, &event_bus).await {
+                log::warn!("Reminder check failed: {e}");
+            }
+            if let Err(e) = announce_expiring_documents(&app_handle, &database, &event_bus).await {
+                log::warn!("Document expiry check failed: {e}");
+            }
+            if let Err(e) = announce_budget_alerts(&app_handle, &database, &event_bus).await {
+                log::warn!("Budget check failed: {e}");
+            }
+            if let Err(e) = announce_grooming_due(&app_handle, &database, &event_bus).await {
+                log::warn!("Grooming check failed: {e}");
+            }
+            tokio::time::sleep(REMINDER_CHECK_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,294 @@
+use super::jobs::{JobOutcome, JobRun};
+use super::{AppState, Permission};
+use crate::database::ExportActivitiesRequest;
+use crate::errors::{ActivityError, PetError};
+use crate::events;
+use crate::export::{
+    self, ExportCompleted, ExportFile, ExportOutcome, ExportProgress, ExportSettings,
+    ExportStopped, EXPORT_SETTINGS_KEY,
+};
+use crate::jobs::{JobPayload, JobStatus};
+use crate::privacy::{PrivacySettings, PRIVACY_SETTINGS_KEY};
+use std::path::PathBuf;
+use tauri::State;
+
+/// Queue an export of an anonymized CSV dataset for research sharing and return the job ID.
+/// Names, notes, photos, attachments, locations and costs are removed;
+/// timestamps, species, breed and measurements are kept. Private activities and
+/// those of categories hidden in the privacy settings are left out.
+///
+/// The export runs on the job worker: `export:progress` events report the activities
+/// written so far, and `export:completed` carries the path of the finished file.
+/// `export:failed` or `export:cancelled` is sent instead when it doesn't finish.
+/// An export interrupted by closing the app starts over on the next launch.
+#[tauri::command]
+pub async fn export_anonymized_dataset(
+    state: State<'_, AppState>,
+    pet_ids: Option<Vec<i64>>,
+) -> Result<String, ActivityError> {
+    state.authorize("export_anonymized_dataset", Permission::Write)?;
+
+    // Named without pets, which the dataset is meant to keep anonymous
+    let file_path = state
+        .export_file_path(ExportFile {
+            kind: "anonymized-dataset",
+            pet: None,
+            extension: "csv",
+            created: chrono::Local::now().naive_local(),
+        })
+        .await
+        .map_err(|e| ActivityError::invalid_data(e.to_string()))?;
+    let job = state
+        .enqueue_job(JobPayload::Export {
+            pet_ids,
+            file_path: file_path.to_string_lossy().to_string(),
+        })
+        .await?;
+    log::info!(
+        "[EXPORT_ANONYMIZED] Queued job {} writing {}",
+        job.id,
+        file_path.display()
+    );
+    Ok(job.id)
+}
+
+/// Cancel an export. Returns false if the job already finished or doesn't exist.
+#[tauri::command]
+pub async fn cancel_export(
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<bool, ActivityError> {
+    state.authorize("cancel_export", Permission::Write)?;
+
+    let cancelled = match super::jobs::request_cancel(&state, &job_id).await {
+        Ok(job) => job.status == JobStatus::Cancelled || job.cancel_requested,
+        Err(ActivityError::JobNotFound { .. }) => false,
+        Err(e) => return Err(e),
+    };
+    log::info!("[CANCEL_EXPORT] job_id={job_id}, cancelled={cancelled}");
+    Ok(cancelled)
+}
+
+/// Get the activity categories kept out of exports and webhook deliveries
+#[tauri::command]
+pub async fn get_privacy_settings(state: State<'_, AppState>) -> Result<PrivacySettings, PetError> {
+    Ok(state.database.get_privacy_settings().await?)
+}
+
+/// Choose the activity categories kept out of exports and webhook deliveries,
+/// e.g. Expense so costs never leave this machine. Deliveries already queued
+/// for the hidden categories are dropped.
+#[tauri::command]
+pub async fn update_privacy_settings(
+    state: State<'_, AppState>,
+    settings: PrivacySettings,
+) -> Result<PrivacySettings, PetError> {
+    state.authorize("update_privacy_settings", Permission::Write)?;
+
+    let settings = settings.normalized();
+    log::info!(
+        "[UPDATE_PRIVACY_SETTINGS] hidden_categories={:?}",
+        settings.hidden_categories
+    );
+    state
+        .database
+        .set_setting(PRIVACY_SETTINGS_KEY, &settings)
+        .await?;
+
+    // Deliveries queued before the change would still post the hidden categories
+    let dropped = state
+        .database
+        .clear_webhook_deliveries_for(&settings.hidden_categories)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    if dropped > 0 {
+        log::info!("[UPDATE_PRIVACY_SETTINGS] Dropped {dropped} queued webhook deliveries");
+    }
+    Ok(settings)
+}
+
+/// Get the file name pattern and folder every export uses
+#[tauri::command]
+pub async fn get_export_settings(state: State<'_, AppState>) -> Result<ExportSettings, PetError> {
+    Ok(state.database.get_export_settings().await?)
+}
+
+/// Choose how exports are named, e.g. `{pet}_{type}_{date}`, and the folder they
+/// are written to; a `directory` of None goes back to the app's exports folder.
+/// The folder is created if needed and must be writable.
+#[tauri::command]
+pub async fn update_export_settings(
+    state: State<'_, AppState>,
+    settings: ExportSettings,
+) -> Result<ExportSettings, PetError> {
+    state.authorize("update_export_settings", Permission::Write)?;
+
+    let settings = settings.validated()?;
+    log::info!(
+        "[UPDATE_EXPORT_SETTINGS] filename_pattern={}, directory={:?}",
+        settings.filename_pattern,
+        settings.directory
+    );
+    if let Some(directory) = &settings.directory {
+        std::fs::create_dir_all(directory)
+            .and_then(|()| tempfile::NamedTempFile::new_in(directory).map(drop))
+            .map_err(|e| {
+                PetError::validation(
+                    "directory",
+                    &format!("Can't write exports to {directory}: {e}"),
+                )
+            })?;
+    }
+    state
+        .database
+        .set_setting(EXPORT_SETTINGS_KEY, &settings)
+        .await?;
+    Ok(settings)
+}
+
+/// Show a file, such as a finished export, selected in the system file manager
+#[tauri::command]
+pub async fn reveal_in_folder(path: String) -> Result<(), PetError> {
+    log::debug!("[REVEAL_IN_FOLDER] path={path}");
+    let path = PathBuf::from(path);
+    if !path.exists() {
+        return Err(PetError::validation(
+            "path",
+            &format!("{} no longer exists", path.display()),
+        ));
+    }
+    tauri_plugin_opener::reveal_item_in_dir(&path).map_err(|e| {
+        PetError::operation_failed(format!(
+            "Failed to show {} in its folder: {e}",
+            path.display()
+        ))
+    })
+}
+
+/// Run a queued anonymized export, sending the `export:*` events along the way
+pub(crate) async fn run_export_job(
+    run: &JobRun<'_>,
+    pet_ids: Option<Vec<i64>>,
+    file_path: PathBuf,
+) -> Result<JobOutcome, ActivityError> {
+    let job_id = run.job.id.clone();
+    let result = run_anonymized_export(run, pet_ids, file_path.clone()).await;
+    let (app_handle, event_bus) = (run.app_handle, &run.state.event_bus);
+
+    match result {
+        Ok(ExportOutcome::Completed { rows }) => {
+            log::info!(
+                "[EXPORT_ANONYMIZED] Job {job_id} wrote {rows} rows to {}",
+                file_path.display()
+            );
+            let completed = ExportCompleted {
+                job_id,
+                file_path: file_path.to_string_lossy().to_string(),
+                rows,
+            };
+            event_bus.emit(app_handle, events::EXPORT_COMPLETED, completed.clone());
+            serde_json::to_value(completed)
+                .map(JobOutcome::Done)
+                .map_err(|e| ActivityError::invalid_data(format!("Invalid export result: {e}")))
+        }
+        Ok(ExportOutcome::Cancelled) => {
+            event_bus.emit(
+                app_handle,
+                events::EXPORT_CANCELLED,
+                ExportStopped {
+                    job_id,
+                    error: None,
+                },
+            );
+            Ok(JobOutcome::Cancelled)
+        }
+        Err(e) => {
+            event_bus.emit(
+                app_handle,
+                events::EXPORT_FAILED,
+                ExportStopped {
+                    job_id,
+                    error: Some(e.to_string()),
+                },
+            );
+            Err(e)
+        }
+    }
+}
+
+/// Load the data of an anonymized export and write it to `file_path`
+async fn run_anonymized_export(
+    run: &JobRun<'_>,
+    pet_ids: Option<Vec<i64>>,
+    file_path: PathBuf,
+) -> Result<ExportOutcome, ActivityError> {
+    let database = &run.state.database;
+    let pets: Vec<_> = database
+        .get_pets(true)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+        .into_iter()
+        .filter(|pet| pet_ids.as_ref().is_none_or(|ids| ids.contains(&pet.id)))
+        .collect();
+
+    // Private activities and categories hidden in the privacy settings never reach the file
+    let privacy = database
+        .get_privacy_settings()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    let activities: Vec<_> = database
+        .export_activities(ExportActivitiesRequest {
+            pet_id: None,
+            format: None,
+        })
+        .await?
+        .into_iter()
+        .filter(|activity| privacy.shares(activity))
+        .collect();
+
+    if run.is_cancelled() {
+        return Ok(ExportOutcome::Cancelled);
+    }
+    if let Some(dir) = file_path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to create export directory: {e}"))
+        })?;
+    }
+
+    // Writing is blocking file I/O, so keep it off the async workers; progress
+    // comes back over a channel to be saved with the job
+    let (sender, mut progress) = tokio::sync::mpsc::unbounded_channel();
+    let app_handle = run.app_handle.clone();
+    let event_bus = run.state.event_bus.clone();
+    let handle = run.handle.clone();
+    let writer = tauri::async_runtime::spawn_blocking(move || {
+        export::write_anonymized_csv(
+            &file_path,
+            &pets,
+            &activities,
+            &handle,
+            |processed, total| {
+                event_bus.notify(
+                    &app_handle,
+                    events::EXPORT_PROGRESS,
+                    ExportProgress {
+                        job_id: handle.id.clone(),
+                        processed,
+                        total,
+                    },
+                );
+                // The receiver only goes away if the job stopped waiting
+                let _ = sender.send((processed, total));
+            },
+        )
+    });
+    while let Some((processed, total)) = progress.recv().await {
+        run.report_progress(processed as i64, total as i64, None)
+            .await;
+    }
+
+    writer
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Export task failed: {e}")))?
+        .map_err(|e| ActivityError::invalid_data(format!("Failed to write export file: {e}")))
+}
@@ -0,0 +1,216 @@
+use super::{AppState, Permission};
+use crate::database::body_condition::BodyConditionHistory;
+use crate::database::exercise::ExerciseSummary;
+use crate::database::grooming::{GroomingPlan, GroomingStatus};
+use crate::database::health::HealthScore;
+use crate::database::hydration::WaterIntakeReport;
+use crate::database::litter::LitterTrends;
+use crate::errors::ActivityError;
+use chrono::NaiveDate;
+use tauri::State;
+
+/// Get the composite health score for a pet with its factor breakdown
+#[tauri::command]
+pub async fn get_health_score(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<HealthScore, ActivityError> {
+    log::info!("[GET_HEALTH_SCORE] pet_id={pet_id}");
+
+    if pet_id <= 0 {
+        return Err(ActivityError::validation(
+            "pet_id",
+            "Pet ID must be positive",
+        ));
+    }
+
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        log::error!("[GET_HEALTH_SCORE] Pet not found: pet_id={pet_id}, error={e}");
+        return Err(ActivityError::validation("pet_id", "Pet not found"));
+    }
+
+    let score = state
+        .database
+        .get_health_score(pet_id, chrono::Local::now().date_naive())
+        .await?;
+
+    log::info!(
+        "[GET_HEALTH_SCORE] Success: pet_id={pet_id}, score={}",
+        score.score
+    );
+    Ok(score)
+}
+
+/// Get a pet's daily water intake from `start_date` to `end_date` inclusive,
+/// with the days that fell short of its daily target
+#[tauri::command]
+pub async fn get_water_intake(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<WaterIntakeReport, ActivityError> {
+    log::debug!("[GET_WATER_INTAKE] pet_id={pet_id}, {start_date}..={end_date}");
+
+    let report = state
+        .database
+        .get_water_intake(pet_id, start_date, end_date)
+        .await?;
+
+    log::debug!(
+        "[GET_WATER_INTAKE] {} days below target",
+        report.days_below_target.len()
+    );
+    Ok(report)
+}
+
+/// Set a pet's daily water target in millilitres; None goes back to estimating
+/// it from the pet's weight
+#[tauri::command]
+pub async fn set_water_target(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    target_ml: Option<f32>,
+) -> Result<(), ActivityError> {
+    state.authorize("set_water_target", Permission::Write)?;
+
+    log::info!("[SET_WATER_TARGET] pet_id={pet_id}, target_ml={target_ml:?}");
+
+    if target_ml.is_some_and(|target| !target.is_finite() || target <= 0.0) {
+        return Err(ActivityError::validation(
+            "target_ml",
+            "Water target must be a positive amount",
+        ));
+    }
+    state.database.set_water_target(pet_id, target_ml).await
+}
+
+/// Get a pet's exercise time and distance per week from `start_date` to `end_date`
+/// inclusive, with the weeks that met its weekly target
+#[tauri::command]
+pub async fn get_exercise_summary(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    start_date: NaiveDate,
+    end_date: NaiveDate,
+) -> Result<ExerciseSummary, ActivityError> {
+    log::debug!("[GET_EXERCISE_SUMMARY] pet_id={pet_id}, {start_date}..={end_date}");
+
+    let summary = state
+        .database
+        .get_exercise_summary(pet_id, start_date, end_date)
+        .await?;
+
+    log::debug!(
+        "[GET_EXERCISE_SUMMARY] {} of {} weeks met the target",
+        summary.weeks_target_met,
+        summary.weeks.len()
+    );
+    Ok(summary)
+}
+
+/// Set a pet's weekly exercise target in minutes; None goes back to the
+/// species default
+#[tauri::command]
+pub async fn set_exercise_target(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    weekly_minutes: Option<f32>,
+) -> Result<(), ActivityError> {
+    state.authorize("set_exercise_target", Permission::Write)?;
+
+    log::info!("[SET_EXERCISE_TARGET] pet_id={pet_id}, weekly_minutes={weekly_minutes:?}");
+
+    if weekly_minutes.is_some_and(|target| !target.is_finite() || target <= 0.0) {
+        return Err(ActivityError::validation(
+            "weekly_minutes",
+            "Exercise target must be a positive number of minutes",
+        ));
+    }
+    state
+        .database
+        .set_exercise_target(pet_id, weekly_minutes)
+        .await
+}
+
+/// Get a pet's litter box use per day over the last `days` days (default 14), with
+/// days that deviate from its usual habits flagged
+#[tauri::command]
+pub async fn get_litter_trends(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    days: Option<u32>,
+) -> Result<LitterTrends, ActivityError> {
+    log::debug!("[GET_LITTER_TRENDS] pet_id={pet_id}, days={days:?}");
+
+    let trends = state
+        .database
+        .get_litter_trends(pet_id, chrono::Local::now().date_naive(), days)
+        .await?;
+
+    log::debug!(
+        "[GET_LITTER_TRENDS] {} logged days, {} anomalies",
+        trends.days.len(),
+        trends.anomalies.len()
+    );
+    Ok(trends)
+}
+
+/// Get a pet's most recent body condition scores (default 50), oldest first
+#[tauri::command]
+pub async fn get_body_condition_history(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    limit: Option<u32>,
+) -> Result<BodyConditionHistory, ActivityError> {
+    log::debug!("[GET_BODY_CONDITION_HISTORY] pet_id={pet_id}, limit={limit:?}");
+
+    let history = state
+        .database
+        .get_body_condition_history(pet_id, limit)
+        .await?;
+
+    log::debug!(
+        "[GET_BODY_CONDITION_HISTORY] {} entries, latest={:?}",
+        history.entries.len(),
+        history.latest.as_ref().map(|entry| entry.score)
+    );
+    Ok(history)
+}
+
+/// Get a pet's grooming tasks with when each is next due and how well the plan
+/// is kept. Pets without a plan get the usual intervals for their coat.
+#[tauri::command]
+pub async fn get_grooming_status(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<GroomingStatus, ActivityError> {
+    log::debug!("[GET_GROOMING_STATUS] pet_id={pet_id}");
+
+    let status = state
+        .database
+        .get_grooming_status(pet_id, chrono::Local::now().date_naive())
+        .await?;
+
+    log::debug!(
+        "[GET_GROOMING_STATUS] coat={:?}, {} tasks, compliance={:?}",
+        status.coat_type,
+        status.tasks.len(),
+        status.compliance
+    );
+    Ok(status)
+}
+
+/// Set a pet's grooming plan; None removes it
+#[tauri::command]
+pub async fn set_grooming_plan(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    plan: Option<GroomingPlan>,
+) -> Result<(), ActivityError> {
+    state.authorize("set_grooming_plan", Permission::Write)?;
+
+    log::info!("[SET_GROOMING_PLAN] pet_id={pet_id}, plan={plan:?}");
+
+    state.database.set_grooming_plan(pet_id, plan).await
+}
@@ -0,0 +1,301 @@
+use super::dto::ActivityDto;
+use super::jobs::{JobOutcome, JobRun};
+use super::{AppState, Permission};
+use crate::database::jobs::Job;
+use crate::database::ImportResult;
+use crate::errors::ActivityError;
+use crate::events;
+use crate::import::{self, ImportDetection, ImportFormat, ImportMappingReport, ImportedActivity};
+use crate::jobs::JobPayload;
+use crate::validation::ActivityDateLimits;
+use std::path::Path;
+use tauri::{AppHandle, State};
+
+/// Recognize which pet app exported a CSV file from its header
+#[tauri::command]
+pub async fn detect_import_format(path: String) -> Result<ImportDetection, ActivityError> {
+    let detection = import::detect_import_format(Path::new(&path))?;
+    log::info!(
+        "[DETECT_IMPORT_FORMAT] path={path}, format={:?}",
+        detection.format
+    );
+    Ok(detection)
+}
+
+/// Map an import file without saving anything, so the user can review what will be imported
+#[tauri::command]
+pub async fn preview_import(
+    path: String,
+    format: Option<ImportFormat>,
+) -> Result<ImportMappingReport, ActivityError> {
+    log::info!("[PREVIEW_IMPORT] path={path}, format={format:?}");
+    let mapping = import::map_import_file(Path::new(&path), format, local_offset())?;
+    Ok(mapping.report)
+}
+
+/// Import activities from another pet app's CSV export into a pet.
+/// With `source_pet`, only rows for that pet name in the file are imported.
+/// Rows that can't be mapped or saved are counted as failed with a reason per row.
+#[tauri::command]
+pub async fn import_activities(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    pet_id: i64,
+    format: Option<ImportFormat>,
+    source_pet: Option<String>,
+) -> Result<ImportResult, ActivityError> {
+    state.authorize("import_activities", Permission::Write)?;
+    log::info!(
+        "[IMPORT_ACTIVITIES] path={path}, pet_id={pet_id}, format={format:?}, source_pet={source_pet:?}"
+    );
+
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        return Err(ActivityError::validation(
+            "pet_id",
+            &format!("Pet not found: {e}"),
+        ));
+    }
+
+    let mapping = import::map_import_file(Path::new(&path), format, local_offset())?;
+    let mut result = import_result(&mapping.report);
+    let importer = RowImporter {
+        state: &state,
+        app_handle: &app_handle,
+        pet_id,
+        source_pet: source_pet.as_deref(),
+        date_limits: super::activity_date_limits(&state).await?,
+        today: chrono::Local::now().date_naive(),
+    };
+    for activity in mapping.activities {
+        importer.import(activity, &mut result).await;
+    }
+    state.notify_low_stock(&app_handle).await;
+    state.notify_metric_alerts(&app_handle).await;
+
+    log::info!(
+        "[IMPORT_ACTIVITIES] Imported {} activities, {} failed",
+        result.total_imported,
+        result.total_failed
+    );
+    Ok(result)
+}
+
+/// Import a walk recorded by a watch or fitness tracker from a GPX file, with its
+/// distance, duration, start point and, unless `include_route` is false, a
+/// simplified route to draw on a map
+#[tauri::command]
+pub async fn import_walk_gpx(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    pet_id: i64,
+    path: String,
+    include_route: Option<bool>,
+) -> Result<ActivityDto, ActivityError> {
+    state.authorize("import_walk_gpx", Permission::Write)?;
+    log::info!("[IMPORT_WALK_GPX] path={path}, pet_id={pet_id}");
+
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        return Err(ActivityError::validation(
+            "pet_id",
+            &format!("Pet not found: {e}"),
+        ));
+    }
+
+    let walk = import::gpx::read_gpx_walk(Path::new(&path))?;
+    log::debug!(
+        "[IMPORT_WALK_GPX] {} points, {:.2} km, {:?} min",
+        walk.point_count,
+        walk.distance_km,
+        walk.duration_minutes
+    );
+    let request = walk.into_request(pet_id, include_route.unwrap_or(true))?;
+    super::activity_date_limits(&state).await?.check(
+        request.category,
+        request.activity_data.as_ref(),
+        chrono::Local::now().date_naive(),
+    )?;
+
+    let created = ActivityDto::from(
+        state
+            .database
+            .create_activity_with_side_effects(request)
+            .await?,
+    );
+    state
+        .event_bus
+        .emit(&app_handle, events::ACTIVITY_CREATED, created.clone());
+    state.notify_metric_alerts(&app_handle).await;
+
+    log::info!("[IMPORT_WALK_GPX] Imported walk id={}", created.id);
+    Ok(created)
+}
+
+/// Queue an import to run in the background and return its job. Rows are imported
+/// as in [`import_activities`]; the job's result is the `ImportResult` once done,
+/// and an import interrupted by closing the app continues after the last saved row.
+#[tauri::command]
+pub async fn start_import_job(
+    state: State<'_, AppState>,
+    path: String,
+    pet_id: i64,
+    format: Option<ImportFormat>,
+    source_pet: Option<String>,
+) -> Result<Job, ActivityError> {
+    state.authorize("start_import_job", Permission::Write)?;
+    log::info!("[START_IMPORT_JOB] path={path}, pet_id={pet_id}, format={format:?}");
+
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        return Err(ActivityError::validation(
+            "pet_id",
+            &format!("Pet not found: {e}"),
+        ));
+    }
+    // Fail now on files that can't be read or mapped rather than in the background
+    import::map_import_file(Path::new(&path), format, local_offset())?;
+
+    state
+        .enqueue_job(JobPayload::Import {
+            path,
+            pet_id,
+            format,
+            source_pet,
+        })
+        .await
+}
+
+/// Run a queued import. The result so far is saved with the job after every row, so
+/// an import interrupted by closing the app continues after the last saved row
+/// instead of importing the earlier rows again.
+pub(crate) async fn run_import_job(
+    run: &JobRun<'_>,
+    path: &str,
+    pet_id: i64,
+    format: Option<ImportFormat>,
+    source_pet: Option<&str>,
+) -> Result<JobOutcome, ActivityError> {
+    let mapping = import::map_import_file(Path::new(path), format, local_offset())?;
+    let resumed = run
+        .job
+        .result
+        .clone()
+        .filter(|_| run.job.processed > 0)
+        .and_then(|checkpoint| serde_json::from_value::<ImportResult>(checkpoint).ok());
+    let skip = match &resumed {
+        Some(_) => run.job.processed as usize,
+        None => 0,
+    };
+    let mut result = resumed.unwrap_or_else(|| import_result(&mapping.report));
+    if skip > 0 {
+        log::info!("[IMPORT_JOB] Job {} resuming after row {skip}", run.job.id);
+    }
+
+    let importer = RowImporter {
+        state: run.state,
+        app_handle: run.app_handle,
+        pet_id,
+        source_pet,
+        date_limits: super::activity_date_limits(run.state).await?,
+        today: chrono::Local::now().date_naive(),
+    };
+    let total = mapping.activities.len() as i64;
+    let mut cancelled = false;
+    for (index, activity) in mapping.activities.into_iter().enumerate().skip(skip) {
+        if run.is_cancelled() {
+            cancelled = true;
+            break;
+        }
+        importer.import(activity, &mut result).await;
+        let checkpoint = serde_json::to_value(&result)
+            .map_err(|e| ActivityError::invalid_data(format!("Invalid import result: {e}")))?;
+        run.report_progress(index as i64 + 1, total, Some(&checkpoint))
+            .await;
+    }
+    run.state.notify_low_stock(run.app_handle).await;
+    run.state.notify_metric_alerts(run.app_handle).await;
+
+    log::info!(
+        "[IMPORT_JOB] Job {}: {} activities imported, {} failed",
+        run.job.id,
+        result.total_imported,
+        result.total_failed
+    );
+    if cancelled {
+        return Ok(JobOutcome::Cancelled);
+    }
+    serde_json::to_value(&result)
+        .map(JobOutcome::Done)
+        .map_err(|e| ActivityError::invalid_data(format!("Invalid import result: {e}")))
+}
+
+/// An import result listing the rows the mapping already skipped
+pub(crate) fn import_result(report: &ImportMappingReport) -> ImportResult {
+    let errors: Vec<String> = report
+        .skipped
+        .iter()
+        .map(|row| format!("Line {}: {}", row.line, row.reason))
+        .collect();
+    ImportResult {
+        total_imported: 0,
+        total_failed: errors.len() as i64,
+        errors,
+        rollback_data: Vec::new(),
+    }
+}
+
+/// Saves mapped rows into one pet
+pub(crate) struct RowImporter<'a> {
+    pub state: &'a AppState,
+    pub app_handle: &'a AppHandle,
+    pub pet_id: i64,
+    /// Only rows for this pet name in the file are imported
+    pub source_pet: Option<&'a str>,
+    pub date_limits: ActivityDateLimits,
+    pub today: chrono::NaiveDate,
+}
+
+impl RowImporter<'_> {
+    /// Save one row and record the outcome in `result`
+    pub async fn import(&self, activity: ImportedActivity, result: &mut ImportResult) {
+        if let (Some(wanted), Some(found)) = (self.source_pet, &activity.source_pet) {
+            if !wanted.eq_ignore_ascii_case(found) {
+                return;
+            }
+        }
+        let line = activity.line;
+        if let Err(e) =
+            self.date_limits
+                .check(activity.category, Some(&activity.activity_data), self.today)
+        {
+            result.errors.push(format!("Line {line}: {e}"));
+            result.total_failed += 1;
+            return;
+        }
+        match self
+            .state
+            .database
+            .create_activity_with_side_effects(activity.into_request(self.pet_id))
+            .await
+        {
+            Ok(created) => {
+                result.rollback_data.push(created.id);
+                result.total_imported += 1;
+                self.state.event_bus.emit(
+                    self.app_handle,
+                    events::ACTIVITY_CREATED,
+                    ActivityDto::from(created),
+                );
+            }
+            Err(e) => {
+                log::warn!("[IMPORT_ACTIVITIES] Line {line} failed: {e}");
+                result.errors.push(format!("Line {line}: {e}"));
+                result.total_failed += 1;
+            }
+        }
+    }
+}
+
+/// Offset for reading the local times in import files
+pub(crate) fn local_offset() -> chrono::FixedOffset {
+    *chrono::Local::now().offset()
+}
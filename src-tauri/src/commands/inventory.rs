@@ -0,0 +1,92 @@
+use super::{AppState, Permission};
+use crate::database::{
+    CreateInventoryItemRequest, InventoryItem, InventoryItemUpdateRequest, InventoryStatus,
+};
+use crate::errors::PetError;
+use tauri::{AppHandle, State};
+
+/// Add a food, litter or medication item to the inventory
+#[tauri::command]
+pub async fn create_inventory_item(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    request: CreateInventoryItemRequest,
+) -> Result<InventoryItem, PetError> {
+    state.authorize("create_inventory_item", Permission::Write)?;
+
+    log::info!(
+        "[CREATE_INVENTORY_ITEM] kind={}, name={}, pet_id={:?}",
+        request.kind,
+        request.name,
+        request.pet_id
+    );
+    if let Some(pet_id) = request.pet_id {
+        state
+            .database
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|_| PetError::not_found(pet_id))?;
+    }
+
+    let item = state.database.create_inventory_item(request).await?;
+    state.notify_low_stock(&app_handle).await;
+    Ok(item)
+}
+
+/// Edit an inventory item's name, brand, unit or low-stock threshold
+#[tauri::command]
+pub async fn update_inventory_item(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    item_id: i64,
+    updates: InventoryItemUpdateRequest,
+) -> Result<InventoryItem, PetError> {
+    state.authorize("update_inventory_item", Permission::Write)?;
+
+    log::info!("[UPDATE_INVENTORY_ITEM] item_id={item_id}");
+    let item = state
+        .database
+        .update_inventory_item(item_id, updates)
+        .await?;
+    state.notify_low_stock(&app_handle).await;
+    Ok(item)
+}
+
+/// Restock (positive delta) or correct (negative delta) an item's quantity
+#[tauri::command]
+pub async fn adjust_inventory(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    item_id: i64,
+    delta: f64,
+) -> Result<InventoryItem, PetError> {
+    state.authorize("adjust_inventory", Permission::Write)?;
+
+    log::info!("[ADJUST_INVENTORY] item_id={item_id}, delta={delta}");
+    let item = state.database.adjust_inventory(item_id, delta).await?;
+    state.notify_low_stock(&app_handle).await;
+    Ok(item)
+}
+
+/// Remove an item from the inventory
+#[tauri::command]
+pub async fn delete_inventory_item(
+    state: State<'_, AppState>,
+    item_id: i64,
+) -> Result<(), PetError> {
+    state.authorize("delete_inventory_item", Permission::Write)?;
+
+    log::info!("[DELETE_INVENTORY_ITEM] item_id={item_id}");
+    state.database.delete_inventory_item(item_id).await
+}
+
+/// Stock levels with low-stock flags and days-remaining estimates.
+/// With a pet, only its own items and shared ones are included.
+#[tauri::command]
+pub async fn get_inventory_status(
+    state: State<'_, AppState>,
+    pet_id: Option<i64>,
+) -> Result<Vec<InventoryStatus>, PetError> {
+    log::debug!("[GET_INVENTORY_STATUS] pet_id={pet_id:?}");
+    state.database.get_inventory_status(pet_id).await
+}
@@ -0,0 +1,214 @@
+use super::{AppState, Permission};
+use crate::database::in_background;
+use crate::database::jobs::Job;
+use crate::errors::ActivityError;
+use crate::events;
+use crate::jobs::{JobHandle, JobPayload, JobProgress, JobStatus, JOB_RETENTION_DAYS};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+/// How often the job worker looks for queued jobs when nothing woke it
+const JOB_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How a job's run ended, when it didn't fail
+pub(crate) enum JobOutcome {
+    /// Finished; the value is stored as the job's result
+    Done(serde_json::Value),
+    Cancelled,
+}
+
+/// A job being run by the worker, with what its runner needs besides the payload
+pub(crate) struct JobRun<'a> {
+    pub app_handle: &'a AppHandle,
+    pub state: &'a AppState,
+    pub job: &'a Job,
+    pub handle: JobHandle,
+}
+
+impl JobRun<'_> {
+    pub fn is_cancelled(&self) -> bool {
+        self.handle.is_cancelled()
+    }
+
+    /// Save how far the job got and send a `job:progress` event. See
+    /// [`crate::database::PetDatabase::update_job_progress`] for `checkpoint`.
+    /// Failures are logged and never stop the job.
+    pub async fn report_progress(
+        &self,
+        processed: i64,
+        total: i64,
+        checkpoint: Option<&serde_json::Value>,
+    ) {
+        if let Err(e) = self
+            .state
+            .database
+            .update_job_progress(&self.job.id, processed, total, checkpoint)
+            .await
+        {
+            log::warn!("Failed to save progress of job {}: {e}", self.job.id);
+        }
+        self.state.event_bus.notify(
+            self.app_handle,
+            events::JOB_PROGRESS,
+            JobProgress {
+                job_id: self.job.id.clone(),
+                kind: self.job.kind,
+                processed,
+                total,
+            },
+        );
+    }
+}
+
+/// List background jobs, newest first, optionally only those with `status`
+#[tauri::command]
+pub async fn list_jobs(
+    state: State<'_, AppState>,
+    status: Option<JobStatus>,
+    limit: Option<i64>,
+) -> Result<Vec<Job>, ActivityError> {
+    log::debug!("[LIST_JOBS] status={status:?}, limit={limit:?}");
+    state.database.list_jobs(status, limit).await
+}
+
+/// Cancel a background job. A queued job is cancelled at once; a running one stops
+/// at its next check and keeps what it finished, e.g. the rows already imported.
+/// Returns the job, unchanged when it had already finished.
+#[tauri::command]
+pub async fn cancel_job(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    job_id: String,
+) -> Result<Job, ActivityError> {
+    state.authorize("cancel_job", Permission::Write)?;
+
+    let job = request_cancel(&state, &job_id).await?;
+    log::info!("[CANCEL_JOB] job_id={job_id}, status={}", job.status);
+    state
+        .event_bus
+        .emit(&app_handle, events::JOB_UPDATED, job.clone());
+    Ok(job)
+}
+
+/// Cancel a job in the database and stop it if the worker is running it
+pub(crate) async fn request_cancel(state: &AppState, job_id: &str) -> Result<Job, ActivityError> {
+    let job = state.database.cancel_job(job_id).await?;
+    if job.status == JobStatus::Running {
+        state.running_jobs.cancel(job_id);
+    }
+    Ok(job)
+}
+
+/// Requeue the jobs interrupted when the app last closed and drop old finished ones
+pub async fn prepare_job_queue(state: &AppState) -> Result<u64, ActivityError> {
+    let requeued = state.database.requeue_interrupted_jobs().await?;
+    let pruned = state
+        .database
+        .prune_finished_jobs(chrono::Utc::now() - chrono::Duration::days(JOB_RETENTION_DAYS))
+        .await?;
+    if pruned > 0 {
+        log::info!("Pruned {pruned} finished jobs");
+    }
+    Ok(requeued)
+}
+
+/// Start the background task that runs queued jobs one at a time, oldest first.
+/// It runs when woken after a job is queued and every poll interval. Must be
+/// called once the app state is managed.
+pub fn spawn_job_worker(app_handle: AppHandle) {
+    // Imports and other job writes queue behind activities the user is logging
+    tauri::async_runtime::spawn(in_background(async move {
+        let state = app_handle.state::<AppState>();
+        loop {
+            match state.database.claim_next_job().await {
+                Ok(Some(job)) => {
+                    run_job(&app_handle, &state, job).await;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to claim next job: {e}"),
+            }
+            tokio::select! {
+                _ = state.job_wakeup.notified() => {}
+                _ = tokio::time::sleep(JOB_POLL_INTERVAL) => {}
+            }
+        }
+    }));
+}
+
+/// Run a claimed job and record how it ended
+async fn run_job(app_handle: &AppHandle, state: &AppState, job: Job) {
+    log::info!("[JOB] Starting {} job {}", job.kind, job.id);
+    let handle = state.running_jobs.start(&job.id);
+    // Cancelled after it was claimed but before it was registered
+    if state
+        .database
+        .get_job(&job.id)
+        .await
+        .is_ok_and(|current| current.cancel_requested)
+    {
+        handle.cancel();
+    }
+    state
+        .event_bus
+        .emit(app_handle, events::JOB_UPDATED, job.clone());
+
+    let run = JobRun {
+        app_handle,
+        state,
+        job: &job,
+        handle,
+    };
+    let outcome = match &job.payload {
+        JobPayload::Import {
+            path,
+            pet_id,
+            format,
+            source_pet,
+        } => super::import::run_import_job(&run, path, *pet_id, *format, source_pet.as_deref())
+            .await
+            .map_err(|e| e.to_string()),
+        JobPayload::Export { pet_ids, file_path } => {
+            super::export::run_export_job(&run, pet_ids.clone(), PathBuf::from(file_path))
+                .await
+                .map_err(|e| e.to_string())
+        }
+        JobPayload::PhotoReencode { settings } => {
+            super::photos::run_photo_reencode_job(&run, settings)
+                .await
+                .map_err(|e| e.to_string())
+        }
+    };
+    state.running_jobs.finish(&job.id);
+
+    let finished = match outcome {
+        Ok(JobOutcome::Done(result)) => {
+            log::info!("[JOB] {} job {} done", job.kind, job.id);
+            state
+                .database
+                .finish_job(&job.id, JobStatus::Done, Some(&result), None)
+                .await
+        }
+        Ok(JobOutcome::Cancelled) => {
+            log::info!("[JOB] {} job {} cancelled", job.kind, job.id);
+            state
+                .database
+                .finish_job(&job.id, JobStatus::Cancelled, None, None)
+                .await
+        }
+        Err(error) => {
+            log::error!("[JOB] {} job {} failed: {error}", job.kind, job.id);
+            state
+                .database
+                .finish_job(&job.id, JobStatus::Failed, None, Some(&error))
+                .await
+        }
+    };
+    match finished {
+        Ok(job) => {
+            state.event_bus.emit(app_handle, events::JOB_UPDATED, job);
+        }
+        Err(e) => log::error!("Failed to record the end of job {}: {e}", job.id),
+    }
+}
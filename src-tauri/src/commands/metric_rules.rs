@@ -0,0 +1,110 @@
+use super::{AppState, Permission};
+use crate::database::{
+    CreateMetricRuleRequest, MetricAlert, MetricKind, MetricRule, MetricRuleUpdateRequest,
+};
+use crate::errors::PetError;
+use tauri::State;
+
+/// Most alerts returned when no limit is given
+const DEFAULT_ALERT_LIMIT: i64 = 50;
+
+/// Add a rate-of-change alert rule for one pet, or for every pet without a pet ID.
+/// Absolute weight thresholds are given and returned in the preferred weight unit.
+#[tauri::command]
+pub async fn create_metric_rule(
+    state: State<'_, AppState>,
+    mut request: CreateMetricRuleRequest,
+) -> Result<MetricRule, PetError> {
+    state.authorize("create_metric_rule", Permission::Write)?;
+
+    log::info!(
+        "[CREATE_METRIC_RULE] metric={}, window_days={}, threshold={}, pet_id={:?}",
+        request.metric,
+        request.window_days,
+        request.threshold,
+        request.pet_id
+    );
+    if let Some(pet_id) = request.pet_id {
+        state
+            .database
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|_| PetError::not_found(pet_id))?;
+    }
+    let unit = state.database.get_weight_unit().await?;
+    if request.metric == MetricKind::Weight && !request.percent {
+        request.threshold = unit.to_kg(request.threshold);
+    }
+    Ok(state
+        .database
+        .create_metric_rule(request)
+        .await?
+        .with_display_unit(unit))
+}
+
+/// Edit a rule's window, threshold or direction, or turn it on or off
+#[tauri::command]
+pub async fn update_metric_rule(
+    state: State<'_, AppState>,
+    rule_id: i64,
+    mut updates: MetricRuleUpdateRequest,
+) -> Result<MetricRule, PetError> {
+    state.authorize("update_metric_rule", Permission::Write)?;
+
+    log::info!("[UPDATE_METRIC_RULE] rule_id={rule_id}");
+    let unit = state.database.get_weight_unit().await?;
+    let current = state.database.get_metric_rule(rule_id).await?;
+    if current.metric == MetricKind::Weight && !updates.percent.unwrap_or(current.percent) {
+        updates.threshold = updates.threshold.map(|threshold| unit.to_kg(threshold));
+    }
+    Ok(state
+        .database
+        .update_metric_rule(rule_id, updates)
+        .await?
+        .with_display_unit(unit))
+}
+
+/// Remove a rule and its alerts
+#[tauri::command]
+pub async fn delete_metric_rule(state: State<'_, AppState>, rule_id: i64) -> Result<(), PetError> {
+    state.authorize("delete_metric_rule", Permission::Write)?;
+
+    log::info!("[DELETE_METRIC_RULE] rule_id={rule_id}");
+    state.database.delete_metric_rule(rule_id).await
+}
+
+/// Rules that apply to a pet (its own and those for every pet), or all rules
+#[tauri::command]
+pub async fn get_metric_rules(
+    state: State<'_, AppState>,
+    pet_id: Option<i64>,
+) -> Result<Vec<MetricRule>, PetError> {
+    log::debug!("[GET_METRIC_RULES] pet_id={pet_id:?}");
+    let unit = state.database.get_weight_unit().await?;
+    Ok(state
+        .database
+        .get_metric_rules(pet_id)
+        .await?
+        .into_iter()
+        .map(|rule| rule.with_display_unit(unit))
+        .collect())
+}
+
+/// Alerts raised for a pet, or for every pet, newest first, with weights in the
+/// preferred unit
+#[tauri::command]
+pub async fn get_metric_alerts(
+    state: State<'_, AppState>,
+    pet_id: Option<i64>,
+    limit: Option<i64>,
+) -> Result<Vec<MetricAlert>, PetError> {
+    log::debug!("[GET_METRIC_ALERTS] pet_id={pet_id:?}, limit={limit:?}");
+    let unit = state.database.get_weight_unit().await?;
+    Ok(state
+        .database
+        .get_metric_alerts(pet_id, limit.unwrap_or(DEFAULT_ALERT_LIMIT).max(1))
+        .await?
+        .into_iter()
+        .map(|alert| alert.with_display_unit(unit))
+        .collect())
+}
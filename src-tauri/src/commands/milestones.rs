@@ -0,0 +1,96 @@
+use super::{AppState, Permission};
+use crate::database::MilestoneProgressReport;
+use crate::errors::PetError;
+use crate::milestones::{self, MilestoneDefinition};
+use chrono::NaiveDate;
+use tauri::State;
+
+/// List built-in growth milestones with their expected age windows
+#[tauri::command]
+pub async fn list_milestones() -> Result<Vec<MilestoneDefinition>, PetError> {
+    Ok(milestones::all_milestones().to_vec())
+}
+
+/// Get a pet's growth milestones as a timeline, ordered by expected age
+#[tauri::command]
+pub async fn get_milestone_progress(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<MilestoneProgressReport, PetError> {
+    log::debug!("[GET_MILESTONE_PROGRESS] pet_id={pet_id}");
+    state
+        .database
+        .get_milestone_progress(pet_id, chrono::Local::now().date_naive())
+        .await
+}
+
+/// Record that a pet reached a milestone, today unless `completed_on` is given
+#[tauri::command]
+pub async fn complete_milestone(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    milestone_key: String,
+    completed_on: Option<NaiveDate>,
+    notes: Option<String>,
+) -> Result<MilestoneProgressReport, PetError> {
+    state.authorize("complete_milestone", Permission::Write)?;
+
+    log::info!("[COMPLETE_MILESTONE] pet_id={pet_id}, milestone={milestone_key}");
+
+    let milestone = milestones::find_milestone(&milestone_key).ok_or_else(|| {
+        PetError::validation(
+            "milestone_key".to_string(),
+            format!("Unknown milestone: {milestone_key}"),
+        )
+    })?;
+
+    let pet = state
+        .database
+        .get_pet_by_id(pet_id)
+        .await
+        .map_err(|_| PetError::not_found(pet_id))?;
+    if milestone.window_for(&pet.species).is_none() {
+        return Err(PetError::validation(
+            "milestone_key".to_string(),
+            format!(
+                "Milestone '{milestone_key}' does not apply to a {}",
+                pet.species
+            ),
+        ));
+    }
+
+    let today = chrono::Local::now().date_naive();
+    let completed_on = completed_on.unwrap_or(today);
+    if completed_on < pet.birth_date || completed_on > today {
+        return Err(PetError::validation(
+            "completed_on",
+            "Date must be between the pet's birth date and today",
+        ));
+    }
+
+    state
+        .database
+        .record_milestone(pet_id, milestone.key, completed_on, notes)
+        .await?;
+    state.database.get_milestone_progress(pet_id, today).await
+}
+
+/// Mark a milestone as not reached yet
+#[tauri::command]
+pub async fn reopen_milestone(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    milestone_key: String,
+) -> Result<MilestoneProgressReport, PetError> {
+    state.authorize("reopen_milestone", Permission::Write)?;
+
+    log::info!("[REOPEN_MILESTONE] pet_id={pet_id}, milestone={milestone_key}");
+    state
+        .database
+        .clear_milestone(pet_id, &milestone_key)
+        .await?;
+    state
+        .database
+        .get_milestone_progress(pet_id, chrono::Local::now().date_naive())
+        .await
+}
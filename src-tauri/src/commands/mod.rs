@@ -1,34 +1,357 @@
 pub mod activities;
+pub mod activity_links;
 pub mod app;
+pub mod checklists;
+pub mod comparison;
+pub mod custom_blocks;
+pub mod diagnostics;
+pub mod documents;
+pub mod dto;
+pub mod events;
+pub mod export;
+pub mod health;
+pub mod import;
+pub mod inventory;
+pub mod jobs;
+pub mod metric_rules;
+pub mod milestones;
 pub mod pets;
 pub mod photos;
+pub mod places;
+pub mod recurring;
+pub mod search;
+pub mod summaries;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod tips;
+pub mod webhooks;
 
 // Re-export all commands for easy access
 pub use activities::*;
+pub use activity_links::*;
 pub use app::*;
+pub use checklists::*;
+pub use comparison::*;
+pub use custom_blocks::*;
+pub use diagnostics::*;
+pub use documents::*;
+pub use dto::*;
+pub use events::*;
+pub use export::*;
+pub use health::*;
+pub use import::*;
+pub use inventory::*;
+pub use jobs::*;
+pub use metric_rules::*;
+pub use milestones::*;
 pub use pets::*;
 pub use photos::*;
+pub use places::*;
+pub use recurring::*;
+pub use search::*;
+pub use summaries::*;
+pub use tips::*;
+pub use webhooks::*;
 
-use crate::database::PetDatabase;
-use crate::errors::PetError;
-use crate::photo::PhotoService;
+use crate::database::jobs::Job;
+use crate::database::{ActivityHooks, FileCleanupReport, PendingFileDeletion, PetDatabase};
+use crate::documents::DocumentService;
+use crate::errors::{AccessDenied, ActivityError, PetError};
+use crate::events::{EventBus, INVENTORY_LOW_STOCK, METRIC_ALERT};
+use crate::export::ExportFile;
+use crate::jobs::{JobPayload, RunningJobs};
+use crate::photo::{PhotoService, PhotoSettings, PHOTO_SETTINGS_KEY};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
+use tauri::AppHandle;
+use tokio::sync::Notify;
 
-/// Application state containing database and photo service
+/// Kind of access a command needs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Permission {
+    /// Reading pets, activities, documents and derived data
+    Read,
+    /// Creating, changing or deleting data and settings
+    Write,
+}
+
+/// What the current app context is allowed to do.
+/// Read-only contexts (e.g. a pet sitter on a shared device) can browse but not change data.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum AccessLevel {
+    ReadOnly,
+    #[default]
+    Full,
+}
+
+impl AccessLevel {
+    /// Whether this access level grants `permission`
+    pub fn allows(self, permission: Permission) -> bool {
+        match (self, permission) {
+            (_, Permission::Read) => true,
+            (AccessLevel::Full, Permission::Write) => true,
+            (AccessLevel::ReadOnly, Permission::Write) => false,
+        }
+    }
+}
+
+impl std::fmt::Display for AccessLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AccessLevel::ReadOnly => write!(f, "read_only"),
+            AccessLevel::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl std::str::FromStr for AccessLevel {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_only" => Ok(AccessLevel::ReadOnly),
+            "full" => Ok(AccessLevel::Full),
+            _ => Err(anyhow::anyhow!("Invalid access level: {}", s)),
+        }
+    }
+}
+
+/// Application state containing database, file services and event bus
 pub struct AppState {
     pub database: Arc<PetDatabase>,
+    /// Modules subscribed to activity created/updated/deleted events
+    pub activity_hooks: Arc<ActivityHooks>,
     pub photo_service: Arc<PhotoService>,
+    pub document_service: Arc<DocumentService>,
+    pub event_bus: Arc<EventBus>,
+    /// Jobs the job worker is running, so they can be cancelled
+    pub running_jobs: Arc<RunningJobs>,
+    /// Directory export files are written to
+    pub export_dir: PathBuf,
+    pub access_level: AccessLevel,
+    /// Wakes the webhook dispatcher when new deliveries are queued
+    pub webhook_wakeup: Arc<Notify>,
+    /// Wakes the job worker when a job is queued
+    pub job_wakeup: Arc<Notify>,
 }
 
 impl AppState {
-    pub async fn new(db_path: PathBuf, photo_dir: PathBuf) -> Result<Self, PetError> {
-        let database: Arc<PetDatabase> = Arc::new(PetDatabase::new(db_path).await?);
+    pub async fn new(
+        db_path: PathBuf,
+        photo_dir: PathBuf,
+        document_dir: PathBuf,
+        export_dir: PathBuf,
+    ) -> Result<Self, PetError> {
+        let database = PetDatabase::new(db_path).await?;
+        Self::with_database(database, photo_dir, document_dir, export_dir).await
+    }
+
+    /// Build the state around a database that is already open
+    pub async fn with_database(
+        database: PetDatabase,
+        photo_dir: PathBuf,
+        document_dir: PathBuf,
+        export_dir: PathBuf,
+    ) -> Result<Self, PetError> {
+        let database = Arc::new(database);
+        if database
+            .load_schema_validation()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?
+        {
+            log::info!("Schema validation is on: activity reads are checked against raw JSON");
+        }
         let photo_service = Arc::new(PhotoService::new(photo_dir)?);
+        if let Some(settings) = database
+            .get_setting::<PhotoSettings>(PHOTO_SETTINGS_KEY)
+            .await?
+        {
+            photo_service.update_settings(settings)?;
+        }
+        photo_service.add_redirects(
+            database
+                .get_photo_redirects()
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?,
+        );
+        let document_service = Arc::new(DocumentService::new(document_dir)?);
 
         Ok(AppState {
+            activity_hooks: database.activity_hooks(),
             database,
             photo_service,
+            document_service,
+            event_bus: Arc::new(EventBus::default()),
+            running_jobs: Arc::new(RunningJobs::default()),
+            export_dir,
+            access_level: AccessLevel::default(),
+            webhook_wakeup: Arc::new(Notify::new()),
+            job_wakeup: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Use a different access level for this state (defaults to full access)
+    pub fn with_access_level(mut self, access_level: AccessLevel) -> Self {
+        self.access_level = access_level;
+        self
+    }
+
+    /// Delete the files of journal entries, removing each entry whose file is gone.
+    /// Failures stay in the journal with the error so they can be retried later.
+    pub async fn process_file_deletions(
+        &self,
+        entries: Vec<PendingFileDeletion>,
+    ) -> FileCleanupReport {
+        let mut report = FileCleanupReport::default();
+
+        for entry in entries {
+            let outcome = match self.photo_service.delete_photo(&entry.file_name) {
+                Ok(()) => self
+                    .database
+                    .complete_file_deletion(entry.id)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+
+            match outcome {
+                Ok(()) => report.deleted += 1,
+                Err(error) => {
+                    report.failed += 1;
+                    if let Err(e) = self
+                        .database
+                        .record_file_deletion_failure(entry.id, &error)
+                        .await
+                    {
+                        log::error!(
+                            "Failed to record file deletion failure for {}: {e}",
+                            entry.file_name
+                        );
+                    }
+                }
+            }
+        }
+
+        if report.failed > 0 {
+            log::warn!(
+                "File cleanup: {} deleted, {} left in journal",
+                report.deleted,
+                report.failed
+            );
+        }
+        report
+    }
+
+    /// Retry every file deletion left in the journal
+    pub async fn process_pending_file_deletions(&self) -> Result<FileCleanupReport, PetError> {
+        let pending = self
+            .database
+            .get_pending_file_deletions()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        Ok(self.process_file_deletions(pending).await)
+    }
+
+    /// Add a job to the durable queue and wake the job worker
+    pub async fn enqueue_job(&self, payload: JobPayload) -> Result<Job, ActivityError> {
+        let job = self.database.enqueue_job(&payload).await?;
+        self.job_wakeup.notify_one();
+        Ok(job)
+    }
+
+    /// Path for a new export file, named by the export settings and placed in the
+    /// chosen export folder, which is created if needed. A name already taken gets
+    /// a number.
+    pub async fn export_file_path(&self, file: ExportFile<'_>) -> Result<PathBuf, PetError> {
+        let settings = self.database.get_export_settings().await?;
+        let dir = settings.directory_or(&self.export_dir);
+        tokio::fs::create_dir_all(&dir).await.map_err(|e| {
+            PetError::file_system(format!(
+                "Failed to create export folder {}: {e}",
+                dir.display()
+            ))
+        })?;
+        Ok(crate::export::unique_path(&dir, &settings.file_name(&file)))
+    }
+
+    /// Emit a low-stock event for each inventory item that newly crossed its threshold.
+    /// Failures are logged and never fail the calling command.
+    pub async fn notify_low_stock(&self, app: &AppHandle) {
+        match self.database.take_low_stock_alerts().await {
+            Ok(items) => {
+                for item in items {
+                    self.event_bus.emit(app, INVENTORY_LOW_STOCK, &item);
+                }
+            }
+            Err(e) => log::error!("Failed to check inventory stock levels: {e}"),
+        }
+    }
+
+    /// Emit an event for each metric alert raised since the last call.
+    /// Failures are logged and never fail the calling command.
+    pub async fn notify_metric_alerts(&self, app: &AppHandle) {
+        match self.database.take_metric_alerts().await {
+            Ok(alerts) => {
+                let unit = self.database.get_weight_unit().await.unwrap_or_default();
+                for alert in alerts {
+                    self.event_bus
+                        .emit(app, METRIC_ALERT, &alert.with_display_unit(unit));
+                }
+            }
+            Err(e) => log::error!("Failed to check metric alerts: {e}"),
+        }
+    }
+
+    /// Check that the current access level grants `permission` for `command`.
+    /// The error converts into each command's error type with `?`.
+    pub fn authorize(&self, command: &str, permission: Permission) -> Result<(), AccessDenied> {
+        if self.access_level.allows(permission) {
+            return Ok(());
+        }
+
+        log::warn!(
+            "[PERMISSION] Denied {command}: requires {permission:?}, access level is {}",
+            self.access_level
+        );
+        Err(AccessDenied {
+            command: command.to_string(),
+            message: format!("{} access cannot perform this action", self.access_level),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_level_permissions() {
+        assert!(AccessLevel::Full.allows(Permission::Read));
+        assert!(AccessLevel::Full.allows(Permission::Write));
+        assert!(AccessLevel::ReadOnly.allows(Permission::Read));
+        assert!(!AccessLevel::ReadOnly.allows(Permission::Write));
+    }
+
+    #[test]
+    fn test_access_denied_maps_to_permission_denied() {
+        use crate::errors::{ActivityError, AppError};
+
+        let denied = AccessDenied {
+            command: "delete_pet".to_string(),
+            message: "read_only access cannot perform this action".to_string(),
+        };
+        assert_eq!(
+            PetError::from(denied.clone()).error_code(),
+            "PERMISSION_DENIED"
+        );
+        assert_eq!(
+            ActivityError::from(denied).error_code(),
+            "PERMISSION_DENIED"
+        );
+        assert_eq!(
+            "read_only".parse::<AccessLevel>().unwrap(),
+            AccessLevel::ReadOnly
+        );
+    }
+}
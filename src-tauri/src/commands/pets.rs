@@ -1,24 +1,56 @@
-use super::AppState;
-use crate::database::{CreatePetRequest, Pet, UpdatePetRequest};
+use super::dto::{CreatePetInput, PetDto, UpdatePetInput};
+use super::{AppState, Permission};
+use crate::care_handoff::{self, CareHandoff, CareHandoffOptions, HandoffFormat};
+use crate::database::derived_data::DerivedDataReport;
+use crate::database::growth_chart::GrowthChart;
+use crate::database::memorial::MemorialReport;
+use crate::database::{
+    CreatePetRequest, ExportActivitiesRequest, PetMergeReport, PetMergeStrategy, UpdatePetRequest,
+    WeightHistory, WeightUnit, WEIGHT_UNIT_SETTING_KEY,
+};
 use crate::errors::PetError;
-use crate::validation;
-use tauri::State;
+use crate::events::{self, DeletedPayload};
+use crate::export::ExportFile;
+use crate::periods::{PeriodSettings, PERIOD_SETTINGS_KEY};
+use crate::pet_tag::{self, PetQrOptions, PetQrTag};
+use crate::vaccination_card::{self, VaccinationCard};
+use crate::validation::{self, ValidationOutcome, WithValidation};
+use tauri::{AppHandle, State};
 
 /// Create a new pet
 #[tauri::command]
 pub async fn create_pet(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
-    pet_data: CreatePetRequest,
-) -> Result<Pet, PetError> {
+    pet_data: CreatePetInput,
+) -> Result<WithValidation<PetDto>, PetError> {
+    state.authorize("create_pet", Permission::Write)?;
+    let pet_data = CreatePetRequest::from(pet_data);
+
     log::info!("Creating new pet: {}", pet_data.name);
 
     // Validate input data
     validation::validate_pet_create_request(&pet_data)?;
+    let outcome = validation::validate_pet_age_rules(
+        &pet_data.species,
+        pet_data.birth_date,
+        pet_data.weight_kg,
+        chrono::Local::now().date_naive(),
+    )?;
+    log_validation_warnings(&outcome);
 
-    let pet = state.database.create_pet(pet_data).await?;
+    let unit = state.database.get_weight_unit().await?;
+    let pet = PetDto::from(
+        state
+            .database
+            .create_pet(pet_data)
+            .await?
+            .with_display_unit(unit),
+    );
 
     log::info!("Pet created successfully with ID: {}", pet.id);
-    Ok(pet)
+    state.event_bus.emit(&app_handle, events::PET_CREATED, &pet);
+    Ok(WithValidation::new(pet, outcome))
 }
 
 /// Get all pets, optionally including archived ones
@@ -26,10 +58,17 @@ pub async fn create_pet(
 pub async fn get_pets(
     state: State<'_, AppState>,
     include_archived: bool,
-) -> Result<Vec<Pet>, PetError> {
+) -> Result<Vec<PetDto>, PetError> {
     log::info!("Getting pets (include_archived: {include_archived})");
 
-    let pets = state.database.get_pets(include_archived).await?;
+    let unit = state.database.get_weight_unit().await?;
+    let pets: Vec<PetDto> = state
+        .database
+        .get_pets(include_archived)
+        .await?
+        .into_iter()
+        .map(|pet| PetDto::from(pet.with_display_unit(unit)))
+        .collect();
 
     log::info!("Retrieved {} pets", pets.len());
     Ok(pets)
@@ -37,26 +76,35 @@ pub async fn get_pets(
 
 /// Get a pet by ID
 #[tauri::command]
-pub async fn get_pet_by_id(state: State<'_, AppState>, id: i64) -> Result<Pet, PetError> {
+pub async fn get_pet_by_id(state: State<'_, AppState>, id: i64) -> Result<PetDto, PetError> {
     log::info!("Getting pet with ID: {id}");
 
     if id <= 0 {
         return Err(PetError::validation("id", "Pet ID must be positive"));
     }
 
-    let pet = state.database.get_pet_by_id(id).await?;
+    let unit = state.database.get_weight_unit().await?;
+    let pet = state
+        .database
+        .get_pet_by_id(id)
+        .await?
+        .with_display_unit(unit);
 
     log::info!("Pet retrieved: {}", pet.name);
-    Ok(pet)
+    Ok(PetDto::from(pet))
 }
 
 /// Update a pet
 #[tauri::command]
 pub async fn update_pet(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     id: i64,
-    pet_data: UpdatePetRequest,
-) -> Result<Pet, PetError> {
+    pet_data: UpdatePetInput,
+) -> Result<WithValidation<PetDto>, PetError> {
+    state.authorize("update_pet", Permission::Write)?;
+    let pet_data = UpdatePetRequest::from(pet_data);
+
     log::info!("Updating pet with ID: {id}");
 
     if id <= 0 {
@@ -66,15 +114,47 @@ pub async fn update_pet(
     // Validate input data
     validation::validate_pet_update_request(&pet_data)?;
 
-    let pet = state.database.update_pet(id, pet_data).await?;
+    // Re-check age-dependent rules against the merged profile
+    let outcome = if pet_data.birth_date.is_some()
+        || pet_data.species.is_some()
+        || pet_data.weight_kg.is_some()
+    {
+        let existing = state.database.get_pet_by_id(id).await?;
+        let outcome = validation::validate_pet_age_rules(
+            pet_data.species.as_ref().unwrap_or(&existing.species),
+            pet_data.birth_date.unwrap_or(existing.birth_date),
+            pet_data.weight_kg.or(existing.weight_kg),
+            chrono::Local::now().date_naive(),
+        )?;
+        log_validation_warnings(&outcome);
+        outcome
+    } else {
+        ValidationOutcome::new()
+    };
+
+    let unit = state.database.get_weight_unit().await?;
+    let pet = PetDto::from(
+        state
+            .database
+            .update_pet(id, pet_data)
+            .await?
+            .with_display_unit(unit),
+    );
 
     log::info!("Pet updated successfully: {}", pet.name);
-    Ok(pet)
+    state.event_bus.emit(&app_handle, events::PET_UPDATED, &pet);
+    Ok(WithValidation::new(pet, outcome))
 }
 
 /// Delete a pet (soft delete by archiving)
 #[tauri::command]
-pub async fn delete_pet(state: State<'_, AppState>, id: i64) -> Result<(), PetError> {
+pub async fn delete_pet(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<(), PetError> {
+    state.authorize("delete_pet", Permission::Write)?;
+
     log::info!("Deleting pet with ID: {id}");
 
     if id <= 0 {
@@ -88,12 +168,144 @@ pub async fn delete_pet(state: State<'_, AppState>, id: i64) -> Result<(), PetEr
     state.database.delete_pet(id).await?;
 
     log::info!("Pet archived successfully");
+    state.event_bus.emit(
+        &app_handle,
+        events::PET_DELETED,
+        DeletedPayload { id, pet_id: None },
+    );
     Ok(())
 }
 
+/// Merge a pet created twice into the other one: everything recorded for `source_id`
+/// moves to `target_id`, conflicting profile fields are resolved with `strategy`
+/// (target wins by default) and the source pet is archived.
+/// With `dry_run` nothing is written and the report previews the merge.
+#[tauri::command]
+pub async fn merge_pets(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    source_id: i64,
+    target_id: i64,
+    strategy: Option<PetMergeStrategy>,
+    dry_run: Option<bool>,
+) -> Result<PetMergeReport, PetError> {
+    let dry_run = dry_run.unwrap_or(false);
+    if !dry_run {
+        state.authorize("merge_pets", Permission::Write)?;
+    }
+
+    log::info!("[MERGE_PETS] source_id={source_id}, target_id={target_id}, dry_run={dry_run}");
+
+    if source_id <= 0 || target_id <= 0 {
+        return Err(PetError::validation("id", "Pet ID must be positive"));
+    }
+    if source_id == target_id {
+        return Err(PetError::validation(
+            "target_id",
+            "Cannot merge a pet into itself",
+        ));
+    }
+    if state.database.get_pet_by_id(target_id).await?.is_archived {
+        return Err(PetError::validation(
+            "target_id",
+            "Cannot merge into an archived pet",
+        ));
+    }
+
+    let unit = state.database.get_weight_unit().await?;
+    let mut report = state
+        .database
+        .merge_pets(source_id, target_id, strategy.unwrap_or_default(), dry_run)
+        .await?;
+    report.pet = report.pet.with_display_unit(unit);
+
+    log::info!(
+        "[MERGE_PETS] Moved {} activities and {} documents, {} conflicts",
+        report.activities_moved,
+        report.documents_moved,
+        report.conflicts.len()
+    );
+    if !dry_run {
+        state.event_bus.emit(
+            &app_handle,
+            events::PET_UPDATED,
+            PetDto::from(report.pet.clone()),
+        );
+        state.event_bus.emit(
+            &app_handle,
+            events::PET_DELETED,
+            DeletedPayload {
+                id: source_id,
+                pet_id: None,
+            },
+        );
+    }
+    Ok(report)
+}
+
+/// Move a pet that passed away into memorial mode. The pet leaves the active list, its
+/// reminders are muted and its recurring series paused; all of its history is kept.
+/// Returns the life-summary report.
+#[tauri::command]
+pub async fn mark_pet_deceased(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    pet_id: i64,
+    passed_away_on: chrono::NaiveDate,
+) -> Result<MemorialReport, PetError> {
+    state.authorize("mark_pet_deceased", Permission::Write)?;
+
+    log::info!("[MARK_PET_DECEASED] pet_id={pet_id}, passed_away_on={passed_away_on}");
+
+    if pet_id <= 0 {
+        return Err(PetError::validation("pet_id", "Pet ID must be positive"));
+    }
+    if passed_away_on > chrono::Local::now().date_naive() {
+        return Err(PetError::validation(
+            "passed_away_on",
+            "Date cannot be in the future",
+        ));
+    }
+
+    let unit = state.database.get_weight_unit().await?;
+    let mut report = state
+        .database
+        .mark_pet_deceased(pet_id, passed_away_on)
+        .await?;
+    report.pet = report.pet.with_display_unit(unit);
+
+    log::info!(
+        "[MARK_PET_DECEASED] {} archived as memorial with {} activities",
+        report.pet.name,
+        report.total_activities
+    );
+    state.event_bus.emit(
+        &app_handle,
+        events::PET_UPDATED,
+        PetDto::from(report.pet.clone()),
+    );
+    Ok(report)
+}
+
+/// Get the life-summary report of a pet in memorial mode
+#[tauri::command]
+pub async fn get_memorial_report(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<MemorialReport, PetError> {
+    log::debug!("[GET_MEMORIAL_REPORT] pet_id={pet_id}");
+
+    let unit = state.database.get_weight_unit().await?;
+    let mut report = state.database.get_memorial_report(pet_id).await?;
+    report.pet = report.pet.with_display_unit(unit);
+    Ok(report)
+}
+
 /// Reorder pets by updating their display_order
 #[tauri::command]
 pub async fn reorder_pets(state: State<'_, AppState>, pet_ids: Vec<i64>) -> Result<(), PetError> {
+    state.authorize("reorder_pets", Permission::Write)?;
+
     log::info!("Reordering {} pets", pet_ids.len());
 
     // Validate input
@@ -119,3 +331,322 @@ pub async fn reorder_pets(state: State<'_, AppState>, pet_ids: Vec<i64>) -> Resu
     log::info!("Pets reordered successfully");
     Ok(())
 }
+
+/// Get a pet's alternate names and nicknames
+#[tauri::command]
+pub async fn get_pet_aliases(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<Vec<String>, PetError> {
+    log::debug!("Getting aliases for pet {pet_id}");
+    state.database.get_pet_aliases(pet_id).await
+}
+
+/// Replace a pet's alternate names and nicknames, in any script. They are found by
+/// global search and can name the pet in quick entries ("喂了豆豆 50g").
+#[tauri::command]
+pub async fn set_pet_aliases(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    aliases: Vec<String>,
+) -> Result<Vec<String>, PetError> {
+    state.authorize("set_pet_aliases", Permission::Write)?;
+
+    log::info!("Setting {} aliases for pet {pet_id}", aliases.len());
+
+    validation::validate_pet_aliases(&aliases)?;
+    state
+        .database
+        .get_pet_by_id(pet_id)
+        .await
+        .map_err(|_| PetError::not_found(pet_id))?;
+
+    state.database.set_pet_aliases(pet_id, aliases).await
+}
+
+/// Get a pet's weight history in the preferred weight unit
+#[tauri::command]
+pub async fn get_weight_history(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<WeightHistory, PetError> {
+    log::debug!("Getting weight history for pet {pet_id}");
+
+    let unit = state.database.get_weight_unit().await?;
+    Ok(state.database.get_weight_history(pet_id, unit).await?)
+}
+
+/// Get everything the Growth tab charts for a pet in one call: weight and height
+/// series, milestone markers and the typical adult size of its breed
+#[tauri::command]
+pub async fn get_growth_chart(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<GrowthChart, PetError> {
+    log::debug!("[GET_GROWTH_CHART] pet_id={pet_id}");
+
+    let unit = state.database.get_weight_unit().await?;
+    state
+        .database
+        .get_growth_chart(pet_id, unit, chrono::Local::now().date_naive())
+        .await
+}
+
+/// Get the preferred unit for presenting weights
+#[tauri::command]
+pub async fn get_weight_unit(state: State<'_, AppState>) -> Result<WeightUnit, PetError> {
+    Ok(state.database.get_weight_unit().await?)
+}
+
+/// Change the preferred unit for presenting weights
+#[tauri::command]
+pub async fn set_weight_unit(state: State<'_, AppState>, unit: WeightUnit) -> Result<(), PetError> {
+    state.authorize("set_weight_unit", Permission::Write)?;
+
+    log::info!("Setting weight unit to {unit}");
+    state
+        .database
+        .set_setting(WEIGHT_UNIT_SETTING_KEY, &unit)
+        .await?;
+    Ok(())
+}
+
+/// Get the week start and month boundaries used by weekly and monthly statistics
+#[tauri::command]
+pub async fn get_period_settings(state: State<'_, AppState>) -> Result<PeriodSettings, PetError> {
+    Ok(state.database.get_period_settings().await?)
+}
+
+/// Change the week start and month boundaries used by weekly and monthly statistics
+#[tauri::command]
+pub async fn set_period_settings(
+    state: State<'_, AppState>,
+    settings: PeriodSettings,
+) -> Result<(), PetError> {
+    state.authorize("set_period_settings", Permission::Write)?;
+
+    if let Err(e) = settings.validate() {
+        return Err(PetError::validation(
+            "month_start_day".to_string(),
+            e.to_string(),
+        ));
+    }
+    log::info!(
+        "Setting period settings: week_start={}, month_start_day={}",
+        settings.week_start,
+        settings.month_start_day
+    );
+    state
+        .database
+        .set_setting(PERIOD_SETTINGS_KEY, &settings)
+        .await?;
+    Ok(())
+}
+
+/// Log soft validation warnings returned to the frontend
+fn log_validation_warnings(outcome: &ValidationOutcome) {
+    for warning in &outcome.warnings {
+        log::warn!(
+            "Validation warning [{}] {}: {}",
+            warning.code,
+            warning.field,
+            warning.message
+        );
+    }
+}
+
+/// Generate a printable QR collar tag with emergency contact and key pet info.
+/// The image is stored with the photos so it can be shown and printed like any photo.
+#[tauri::command]
+pub async fn generate_pet_qr(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    options: PetQrOptions,
+) -> Result<PetQrTag, PetError> {
+    state.authorize("generate_pet_qr", Permission::Write)?;
+
+    log::info!("[GENERATE_PET_QR] pet_id={pet_id}");
+    options.validate()?;
+
+    let pet = state.database.get_pet_by_id(pet_id).await?;
+    let payload = pet_tag::tag_payload(&pet, &options);
+    let size_px = options.size_px();
+
+    let render_payload = payload.clone();
+    let filename = state
+        .photo_service
+        .run_on_workers(move |service| {
+            let image = pet_tag::render_qr(&render_payload, size_px)?;
+            service.store_generated_png(&image)
+        })
+        .await?;
+
+    log::info!("[GENERATE_PET_QR] Stored tag {filename} for pet_id={pet_id}");
+    Ok(PetQrTag {
+        pet_id,
+        filename,
+        payload,
+        size_px,
+    })
+}
+
+/// Generate a bank-card sized PDF listing the pet's vaccinations with the dates
+/// given and next due, for boarding kennels and groomers that want a paper copy.
+/// The PDF is written to the export directory; longer records span several cards.
+#[tauri::command]
+pub async fn generate_vaccination_card(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<VaccinationCard, PetError> {
+    log::info!("[GENERATE_VACCINATION_CARD] pet_id={pet_id}");
+
+    let pet = state.database.get_pet_by_id(pet_id).await?;
+    let activities = state
+        .database
+        .export_activities(ExportActivitiesRequest {
+            pet_id: Some(pet_id),
+            format: None,
+        })
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+    let privacy = state.database.get_privacy_settings().await?;
+    let entries = vaccination_card::vaccination_entries(&activities, &privacy);
+    if entries.is_empty() {
+        return Err(PetError::validation(
+            "pet_id",
+            "No vaccinations recorded for this pet",
+        ));
+    }
+
+    let today = chrono::Local::now();
+    let pdf = vaccination_card::render_card_pdf(&pet, &entries, today.date_naive());
+    let file_path = state
+        .export_file_path(ExportFile {
+            kind: "vaccination-card",
+            pet: Some((pet.id, &pet.name)),
+            extension: "pdf",
+            created: today.naive_local(),
+        })
+        .await?;
+    tokio::fs::write(&file_path, pdf)
+        .await
+        .map_err(|e| PetError::file_system(format!("Failed to write vaccination card: {e}")))?;
+
+    log::info!(
+        "[GENERATE_VACCINATION_CARD] Wrote {} vaccinations to {}",
+        entries.len(),
+        file_path.display()
+    );
+    Ok(VaccinationCard {
+        pet_id,
+        file_path: file_path.to_string_lossy().to_string(),
+        cards: vaccination_card::card_count(&entries),
+        entries,
+    })
+}
+
+/// Write a handoff for a pet sitter or kennel covering a stay: the usual meals from the
+/// last two weeks of logs, medications due during the stay with their times, the given
+/// emergency contacts and notes tagged `#care`. Saved as Markdown or PDF in the export
+/// directory.
+#[tauri::command]
+pub async fn generate_care_handoff(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    options: CareHandoffOptions,
+) -> Result<CareHandoff, PetError> {
+    log::info!(
+        "[GENERATE_CARE_HANDOFF] pet_id={pet_id}, start={:?}, end={:?}, format={:?}",
+        options.start_date,
+        options.end_date,
+        options.format
+    );
+
+    let today = chrono::Local::now();
+    let stay = options.stay(today.date_naive())?;
+    let pet = state.database.get_pet_by_id(pet_id).await?;
+    let activities = state
+        .database
+        .export_activities(ExportActivitiesRequest {
+            pet_id: Some(pet_id),
+            format: None,
+        })
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    let series = state
+        .database
+        .get_recurring_series_for_pet(pet_id)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+    let privacy = state.database.get_privacy_settings().await?;
+    let sheet = care_handoff::care_sheet(&activities, &series, stay, today.date_naive(), &privacy);
+    let document = match options.format {
+        HandoffFormat::Markdown => {
+            care_handoff::render_markdown(&pet, &sheet, &options.contacts, today.date_naive())
+                .into_bytes()
+        }
+        HandoffFormat::Pdf => {
+            care_handoff::render_pdf(&pet, &sheet, &options.contacts, today.date_naive())
+        }
+    };
+    let file_path = state
+        .export_file_path(ExportFile {
+            kind: "care-handoff",
+            pet: Some((pet.id, &pet.name)),
+            extension: options.format.extension(),
+            created: today.naive_local(),
+        })
+        .await?;
+    tokio::fs::write(&file_path, document)
+        .await
+        .map_err(|e| PetError::file_system(format!("Failed to write care handoff: {e}")))?;
+
+    log::info!(
+        "[GENERATE_CARE_HANDOFF] Wrote {} meals, {} medications and {} notes to {}",
+        sheet.feeding_plan.len(),
+        sheet.medications.len(),
+        sheet.quirks.len(),
+        file_path.display()
+    );
+    Ok(CareHandoff {
+        pet_id,
+        file_path: file_path.to_string_lossy().to_string(),
+        format: options.format,
+        contacts: options.contacts,
+        sheet,
+    })
+}
+
+/// Recompute what is stored about a pet but derived from its activities (profile
+/// weight, cached activity counts), e.g. after an import or editing the database by
+/// hand. Returns what was out of date; `pet:updated` is sent when the profile changed.
+#[tauri::command]
+pub async fn recalculate_pet_derived_data(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<DerivedDataReport, PetError> {
+    state.authorize("recalculate_pet_derived_data", Permission::Write)?;
+
+    log::info!("[RECALCULATE_PET_DERIVED_DATA] pet_id={pet_id}");
+    let report = state.database.recalculate_pet_derived_data(pet_id).await?;
+
+    if report.weight_kg.is_some() {
+        let unit = state.database.get_weight_unit().await?;
+        let pet = state
+            .database
+            .get_pet_by_id(pet_id)
+            .await?
+            .with_display_unit(unit);
+        state
+            .event_bus
+            .emit(&app_handle, events::PET_UPDATED, PetDto::from(pet));
+    }
+    log::info!(
+        "[RECALCULATE_PET_DERIVED_DATA] pet_id={pet_id}, changed={}",
+        report.changed()
+    );
+    Ok(report)
+}
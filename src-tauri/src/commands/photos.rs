@@ -1,19 +1,40 @@
-use super::AppState;
+use super::jobs::{JobOutcome, JobRun};
+use super::{AppState, Permission};
+use crate::data_dir;
+use crate::database::jobs::Job;
+use crate::database::ExportActivitiesRequest;
 use crate::errors::PetError;
-use crate::photo::{PhotoInfo, StorageStats};
+use crate::events;
+use crate::export::ExportFile;
+use crate::jobs::JobPayload;
+use crate::photo::{
+    PhotoCropRect, PhotoEdit, PhotoEditResult, PhotoInfo, PhotoReencodeReport, PhotoRenameReport,
+    PhotoSettings, PhotoStorageEstimate, PhotoUploadItem, PhotoUploadProgress, PhotoUploadResult,
+    StorageStats, MAX_BATCH_PHOTOS, PHOTO_SETTINGS_KEY,
+};
+use crate::photo_album::{self, PhotoAlbumExport};
+use crate::photo_classifier::{self, PhotoClassification};
+use std::collections::HashSet;
 use std::path::PathBuf;
-use tauri::State;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
 
-/// Upload a pet photo from bytes data
+/// Upload a pet photo from bytes data.
+/// `process_as_document` crops and straightens a photographed document such as a certificate.
 #[tauri::command]
 pub async fn upload_pet_photo(
     state: State<'_, AppState>,
     filename: String,
     photo_bytes: Vec<u8>,
     _thumbnail_size: Option<u32>,
+    process_as_document: Option<bool>,
 ) -> Result<String, PetError> {
+    state.authorize("upload_pet_photo", Permission::Write)?;
+
+    let process_as_document = process_as_document.unwrap_or(false);
     log::info!(
-        "Uploading pet photo: {} ({} bytes)",
+        "Uploading pet photo: {} ({} bytes, as document: {process_as_document})",
         filename,
         photo_bytes.len()
     );
@@ -31,7 +52,8 @@ pub async fn upload_pet_photo(
 
     let photo_id = state
         .photo_service
-        .store_photo_from_bytes(&photo_bytes, Some(&filename))?;
+        .store_photo_from_bytes_async(photo_bytes, Some(filename), process_as_document)
+        .await?;
 
     log::info!("Pet photo uploaded successfully: {photo_id}");
     Ok(photo_id)
@@ -44,6 +66,8 @@ pub async fn upload_pet_photo_from_path(
     file_path: String,
     _thumbnail_size: Option<u32>,
 ) -> Result<String, PetError> {
+    state.authorize("upload_pet_photo_from_path", Permission::Write)?;
+
     log::info!("Uploading pet photo from path: {file_path}");
 
     if file_path.trim().is_empty() {
@@ -58,18 +82,97 @@ pub async fn upload_pet_photo_from_path(
         return Err(PetError::validation("file_path", "File does not exist"));
     }
 
-    let photo_id = state.photo_service.store_photo(&path)?;
+    let photo_id = state.photo_service.store_photo_async(path).await?;
 
     log::info!("Pet photo uploaded successfully: {photo_id}");
     Ok(photo_id)
 }
 
+/// Upload several photos at once.
+/// Photos are processed on the photo worker pool, a progress event is emitted
+/// as each one finishes, and failures are reported per item instead of aborting the batch.
+#[tauri::command]
+pub async fn upload_photos_batch(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    items: Vec<PhotoUploadItem>,
+) -> Result<Vec<PhotoUploadResult>, PetError> {
+    state.authorize("upload_photos_batch", Permission::Write)?;
+
+    let total = items.len();
+    log::info!("Uploading batch of {total} photos");
+
+    if items.is_empty() {
+        return Err(PetError::validation("items", "No photos to upload"));
+    }
+    if total > MAX_BATCH_PHOTOS {
+        return Err(PetError::resource_limit(format!(
+            "At most {MAX_BATCH_PHOTOS} photos can be uploaded at once"
+        )));
+    }
+
+    let completed = Arc::new(AtomicUsize::new(0));
+
+    let tasks: Vec<_> = items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let completed = completed.clone();
+            let photo_service = state.photo_service.clone();
+            let event_bus = state.event_bus.clone();
+            let app_handle = app_handle.clone();
+
+            tauri::async_runtime::spawn(async move {
+                let filename = item.filename.clone();
+                let outcome = photo_service.store_upload_async(item).await;
+
+                let result = PhotoUploadResult::new(index, filename, outcome);
+                if let Some(error) = &result.error {
+                    log::warn!("Batch photo {index} ({}) failed: {error}", result.filename);
+                }
+                event_bus.notify(
+                    &app_handle,
+                    events::PHOTO_UPLOAD_PROGRESS,
+                    PhotoUploadProgress {
+                        completed: completed.fetch_add(1, Ordering::SeqCst) + 1,
+                        total,
+                        result: result.clone(),
+                    },
+                );
+                result
+            })
+        })
+        .collect();
+
+    let mut results = Vec::with_capacity(total);
+    for (index, task) in tasks.into_iter().enumerate() {
+        results.push(task.await.unwrap_or_else(|e| {
+            PhotoUploadResult::new(
+                index,
+                String::new(),
+                Err(PetError::operation_failed(format!(
+                    "Upload task failed: {e}"
+                ))),
+            )
+        }));
+    }
+
+    let failed = results.iter().filter(|r| r.error.is_some()).count();
+    log::info!(
+        "Batch photo upload finished: {} stored, {failed} failed",
+        total - failed
+    );
+    Ok(results)
+}
+
 /// Delete a pet photo
 #[tauri::command]
 pub async fn delete_pet_photo(
     state: State<'_, AppState>,
     photo_id: String,
 ) -> Result<(), PetError> {
+    state.authorize("delete_pet_photo", Permission::Write)?;
+
     log::info!("Deleting pet photo: {photo_id}");
 
     if photo_id.trim().is_empty() {
@@ -85,6 +188,228 @@ pub async fn delete_pet_photo(
     Ok(())
 }
 
+/// Rotate a stored photo clockwise by a multiple of 90 degrees (negative turns
+/// counter-clockwise). See [`crop_photo`] for how the edited photo is stored.
+#[tauri::command]
+pub async fn rotate_photo(
+    state: State<'_, AppState>,
+    photo_id: String,
+    degrees: i32,
+) -> Result<PhotoEditResult, PetError> {
+    state.authorize("rotate_photo", Permission::Write)?;
+
+    log::info!("[EDIT_PHOTO] Rotating {photo_id} by {degrees} degrees");
+    edit_photo(&state, photo_id, PhotoEdit::Rotate { degrees }).await
+}
+
+/// Crop a stored photo to a pixel rectangle. The edited photo gets a new name that
+/// pet photos and activity attachments are pointed at, the old name keeps resolving
+/// through the photos:// protocol, and the photo as first uploaded is kept as a
+/// `.orig` copy.
+#[tauri::command]
+pub async fn crop_photo(
+    state: State<'_, AppState>,
+    photo_id: String,
+    rect: PhotoCropRect,
+) -> Result<PhotoEditResult, PetError> {
+    state.authorize("crop_photo", Permission::Write)?;
+
+    log::info!("[EDIT_PHOTO] Cropping {photo_id} to {rect:?}");
+    edit_photo(&state, photo_id, PhotoEdit::Crop(rect)).await
+}
+
+async fn edit_photo(
+    state: &State<'_, AppState>,
+    photo_id: String,
+    edit: PhotoEdit,
+) -> Result<PhotoEditResult, PetError> {
+    if photo_id.trim().is_empty() {
+        return Err(PetError::validation("photo_id", "Photo ID cannot be empty"));
+    }
+
+    let rename = state
+        .photo_service
+        .run_on_workers(move |service| service.prepare_edit(&photo_id, edit))
+        .await?;
+
+    let update = match state
+        .database
+        .apply_photo_renames(std::slice::from_ref(&rename))
+        .await
+    {
+        Ok(update) => update,
+        Err(e) => {
+            log::error!("[EDIT_PHOTO] Database update failed, discarding edited copy: {e}");
+            state
+                .photo_service
+                .discard_renames(std::slice::from_ref(&rename));
+            return Err(PetError::database(e.to_string()));
+        }
+    };
+    let original = state.photo_service.complete_edit(&rename)?;
+    let dimensions = state
+        .photo_service
+        .get_photo_info(&rename.new_name)?
+        .dimensions;
+
+    log::info!(
+        "[EDIT_PHOTO] {} -> {} (original kept as {original}), pets_updated={}, activities_updated={}",
+        rename.old_name,
+        rename.new_name,
+        update.pets_updated,
+        update.activities_updated
+    );
+    Ok(PhotoEditResult {
+        photo_id: rename.new_name,
+        original,
+        dimensions,
+        pets_updated: update.pets_updated,
+        activities_updated: update.activities_updated,
+    })
+}
+
+/// Suggest which activity category a photo attached in the editor belongs to,
+/// most likely first. Uses the on-device image classifier when the app was built
+/// with it and a model is installed under `models` in the data directory.
+#[tauri::command]
+pub async fn suggest_photo_categories(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    photo_id: String,
+) -> Result<PhotoClassification, PetError> {
+    log::debug!("[SUGGEST_PHOTO_CATEGORIES] photo_id={photo_id}");
+
+    if photo_id.trim().is_empty() {
+        return Err(PetError::validation("photo_id", "Photo ID cannot be empty"));
+    }
+    let data_dir = match data_dir::current_data_dir() {
+        Some(dir) => dir,
+        None => app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| PetError::file_system(format!("Failed to get app data directory: {e}")))?,
+    };
+    let model_dir = data_dir.join("models");
+
+    let classification = state
+        .photo_service
+        .run_on_workers(move |service| {
+            let path = service.get_photo_path(&photo_id)?;
+            let img = image::open(&path)
+                .map_err(|e| PetError::invalid_input(format!("Failed to read photo: {e}")))?;
+            photo_classifier::classify_photo(&img, &model_dir)
+        })
+        .await?;
+
+    log::debug!(
+        "[SUGGEST_PHOTO_CATEGORIES] model_used={}, suggestions={:?}",
+        classification.model_used,
+        classification
+            .suggestions
+            .iter()
+            .map(|s| (s.subcategory.as_str(), s.confidence))
+            .collect::<Vec<_>>()
+    );
+    Ok(classification)
+}
+
+/// Collect a pet's profile photo and the photos attached to its activities between
+/// `start_date` and `end_date` into a ZIP in the export directory, for sharing with
+/// family. Photos are named by date and event and listed on an `index.html` contact
+/// sheet. Private activities and categories hidden in the privacy settings are left out.
+#[tauri::command]
+pub async fn export_photo_album(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    start_date: Option<chrono::NaiveDate>,
+    end_date: Option<chrono::NaiveDate>,
+) -> Result<PhotoAlbumExport, PetError> {
+    log::info!("[EXPORT_PHOTO_ALBUM] pet_id={pet_id}, start={start_date:?}, end={end_date:?}");
+
+    if let (Some(start), Some(end)) = (start_date, end_date) {
+        if end < start {
+            return Err(PetError::validation(
+                "end_date",
+                "End date must not be before start date",
+            ));
+        }
+    }
+    let pet = state.database.get_pet_by_id(pet_id).await?;
+    let privacy = state.database.get_privacy_settings().await?;
+    let activities: Vec<_> = state
+        .database
+        .export_activities(ExportActivitiesRequest {
+            pet_id: Some(pet_id),
+            format: None,
+        })
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?
+        .into_iter()
+        .filter(|activity| privacy.shares(activity))
+        .collect();
+
+    let photos = photo_album::album_photos(&pet, &activities, start_date, end_date);
+    if photos.is_empty() {
+        return Err(PetError::validation(
+            "pet_id",
+            "There are no photos of this pet in the chosen dates",
+        ));
+    }
+
+    let now = chrono::Local::now();
+    let file_path = state
+        .export_file_path(ExportFile {
+            kind: "photo-album",
+            pet: Some((pet.id, &pet.name)),
+            extension: "zip",
+            created: now.naive_local(),
+        })
+        .await?;
+    let path = file_path.clone();
+    let contents = state
+        .photo_service
+        .run_on_workers(move |service| {
+            let failed =
+                |e: std::io::Error| PetError::file_system(format!("Failed to write album: {e}"));
+            let partial = path.with_extension("zip.part");
+            let file = std::fs::File::create(&partial).map_err(failed)?;
+            let written = photo_album::write_album(
+                std::io::BufWriter::new(file),
+                &pet,
+                (start_date, end_date),
+                photos,
+                now.naive_local(),
+                |photo| {
+                    let photo_path = service.get_photo_path(photo).ok()?;
+                    std::fs::read(photo_path).ok()
+                },
+            )
+            .and_then(|(out, contents)| {
+                out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+                std::fs::rename(&partial, &path)?;
+                Ok(contents)
+            });
+            if written.is_err() {
+                let _ = std::fs::remove_file(&partial);
+            }
+            written.map_err(failed)
+        })
+        .await?;
+
+    log::info!(
+        "[EXPORT_PHOTO_ALBUM] Wrote {} photos to {}, {} missing",
+        contents.written.len(),
+        file_path.display(),
+        contents.missing.len()
+    );
+    Ok(PhotoAlbumExport {
+        pet_id,
+        file_path: file_path.to_string_lossy().to_string(),
+        photos: contents.written,
+        missing: contents.missing,
+    })
+}
+
 /// Get information about a pet photo
 #[tauri::command]
 pub async fn get_pet_photo_info(
@@ -140,3 +465,216 @@ pub async fn get_photo_storage_stats(state: State<'_, AppState>) -> Result<Stora
     );
     Ok(stats)
 }
+
+/// Get the current photo processing settings
+#[tauri::command]
+pub async fn get_photo_settings(state: State<'_, AppState>) -> Result<PhotoSettings, PetError> {
+    Ok(state.photo_service.settings())
+}
+
+/// Update photo processing settings used for new uploads
+#[tauri::command]
+pub async fn update_photo_settings(
+    state: State<'_, AppState>,
+    settings: PhotoSettings,
+) -> Result<PhotoSettings, PetError> {
+    state.authorize("update_photo_settings", Permission::Write)?;
+
+    log::info!("Updating photo settings: {settings:?}");
+
+    settings.validate()?;
+    state
+        .database
+        .set_setting(PHOTO_SETTINGS_KEY, &settings)
+        .await?;
+    state.photo_service.update_settings(settings)?;
+
+    Ok(state.photo_service.settings())
+}
+
+/// Turn low-storage mode on (smaller, lossy, unpadded photos) or back to defaults
+#[tauri::command]
+pub async fn set_low_storage_mode(
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<PhotoSettings, PetError> {
+    state.authorize("set_low_storage_mode", Permission::Write)?;
+
+    let settings = if enabled {
+        PhotoSettings::low_storage()
+    } else {
+        PhotoSettings::default()
+    };
+    update_photo_settings(state, settings).await
+}
+
+/// Estimate how much space re-processing existing photos with `settings` would save.
+/// Defaults to the low-storage preset when no settings are given.
+#[tauri::command]
+pub async fn estimate_photo_storage_savings(
+    state: State<'_, AppState>,
+    settings: Option<PhotoSettings>,
+) -> Result<PhotoStorageEstimate, PetError> {
+    let settings = settings.unwrap_or_else(PhotoSettings::low_storage);
+    log::debug!("Estimating photo storage with settings: {settings:?}");
+
+    let estimate = state.photo_service.estimate_storage_async(settings).await?;
+
+    log::debug!(
+        "Photo storage estimate - current: {} bytes, estimated: {} bytes ({} sampled)",
+        estimate.current_size,
+        estimate.estimated_size,
+        estimate.sampled_count
+    );
+    Ok(estimate)
+}
+
+/// Queue re-encoding every stored photo with `settings` (the current photo settings
+/// when none are given), e.g. to shrink existing photos after turning on low-storage
+/// mode. Photos are replaced only when that saves space; their old names keep
+/// resolving through the photos:// protocol. The job's result is a `PhotoReencodeReport`.
+#[tauri::command]
+pub async fn start_photo_reencode_job(
+    state: State<'_, AppState>,
+    settings: Option<PhotoSettings>,
+) -> Result<Job, PetError> {
+    state.authorize("start_photo_reencode_job", Permission::Write)?;
+
+    let settings = settings.unwrap_or_else(|| state.photo_service.settings());
+    settings.validate()?;
+    log::info!("[START_PHOTO_REENCODE_JOB] settings={settings:?}");
+    state
+        .enqueue_job(JobPayload::PhotoReencode { settings })
+        .await
+        .map_err(|e| PetError::database(e.to_string()))
+}
+
+/// Re-encode stored photos one at a time, pointing pets and activities at each
+/// smaller copy. A run interrupted by closing the app starts over on the next launch,
+/// skipping the photos it already shrank.
+pub(crate) async fn run_photo_reencode_job(
+    run: &JobRun<'_>,
+    settings: &PhotoSettings,
+) -> Result<JobOutcome, PetError> {
+    let state = run.state;
+    let pending: HashSet<String> = state
+        .database
+        .get_pending_file_deletions()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?
+        .into_iter()
+        .map(|entry| entry.file_name)
+        .collect();
+    let photos: Vec<(String, u64)> = state
+        .photo_service
+        .list_photo_sizes()?
+        .into_iter()
+        .filter(|(name, _)| !name.starts_with("temp_"))
+        .collect();
+    let total = photos.len() as i64;
+
+    let mut report = PhotoReencodeReport::default();
+    for (index, (name, size)) in photos.into_iter().enumerate() {
+        if run.is_cancelled() {
+            return Ok(JobOutcome::Cancelled);
+        }
+        if pending.contains(&name) {
+            report.skipped += 1;
+            run.report_progress(index as i64 + 1, total, None).await;
+            continue;
+        }
+
+        let photo = name.clone();
+        let job_settings = settings.clone();
+        let prepared = state
+            .photo_service
+            .run_on_workers(move |service| service.prepare_reencode(&photo, &job_settings))
+            .await;
+        match prepared {
+            Ok(None) => report.skipped += 1,
+            Ok(Some(rename)) => {
+                let renames = std::slice::from_ref(&rename);
+                match state.database.apply_photo_renames(renames).await {
+                    Ok(update) => {
+                        state.photo_service.complete_renames(renames);
+                        let new_size = state
+                            .photo_service
+                            .get_photo_path(&rename.new_name)
+                            .ok()
+                            .and_then(|path| std::fs::metadata(path).ok())
+                            .map_or(size, |metadata| metadata.len());
+                        report.reencoded += 1;
+                        report.bytes_saved += size.saturating_sub(new_size);
+                        report.pets_updated += update.pets_updated;
+                        report.activities_updated += update.activities_updated;
+                    }
+                    Err(e) => {
+                        log::error!("[PHOTO_REENCODE] Database update for {name} failed: {e}");
+                        state.photo_service.discard_renames(renames);
+                        report.failed.push(name);
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!("[PHOTO_REENCODE] Skipping {name}: {e}");
+                report.failed.push(name);
+            }
+        }
+        run.report_progress(index as i64 + 1, total, None).await;
+    }
+
+    log::info!("[PHOTO_REENCODE] {report:?}");
+    serde_json::to_value(report)
+        .map(JobOutcome::Done)
+        .map_err(|e| PetError::operation_failed(format!("Invalid re-encode report: {e}")))
+}
+
+/// Rename stored photos from random UUID names to content-hash names, so identical
+/// photos share one file. Pet photos and activity attachments are updated in one
+/// transaction; the old names keep resolving through the photos:// protocol.
+/// Photos with a pending deletion are left alone.
+#[tauri::command]
+pub async fn migrate_photos_to_content_names(
+    state: State<'_, AppState>,
+) -> Result<PhotoRenameReport, PetError> {
+    state.authorize("migrate_photos_to_content_names", Permission::Write)?;
+
+    let pending: HashSet<String> = state
+        .database
+        .get_pending_file_deletions()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?
+        .into_iter()
+        .map(|entry| entry.file_name)
+        .collect();
+    let skipped = pending.len();
+
+    let renames = state
+        .photo_service
+        .run_on_workers(move |service| service.prepare_content_addressed_renames(&pending))
+        .await?;
+    log::info!(
+        "[MIGRATE_PHOTOS] Copied {} photos to content-addressed names",
+        renames.len()
+    );
+
+    let update = match state.database.apply_photo_renames(&renames).await {
+        Ok(update) => update,
+        Err(e) => {
+            log::error!("[MIGRATE_PHOTOS] Database update failed, discarding copies: {e}");
+            state.photo_service.discard_renames(&renames);
+            return Err(PetError::database(e.to_string()));
+        }
+    };
+    state.photo_service.complete_renames(&renames);
+
+    let report = PhotoRenameReport {
+        renamed: renames.len(),
+        deduplicated: renames.iter().filter(|rename| !rename.created).count(),
+        skipped,
+        pets_updated: update.pets_updated,
+        activities_updated: update.activities_updated,
+    };
+    log::info!("[MIGRATE_PHOTOS] {report:?}");
+    Ok(report)
+}
@@ -0,0 +1,42 @@
+use super::AppState;
+use crate::database::{FrequentPlace, WalkArea};
+use crate::errors::ActivityError;
+use tauri::State;
+
+/// Get the places a pet visits most, with visit counts and last-visit dates
+#[tauri::command]
+pub async fn get_frequent_places(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    limit: Option<i64>,
+) -> Result<Vec<FrequentPlace>, ActivityError> {
+    log::debug!("[GET_FREQUENT_PLACES] pet_id={pet_id}");
+    state.database.get_frequent_places(pet_id, limit).await
+}
+
+/// Suggest places for a location block while creating an activity
+#[tauri::command]
+pub async fn get_place_suggestions(
+    state: State<'_, AppState>,
+    pet_id: Option<i64>,
+    query: Option<String>,
+    subcategory: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<FrequentPlace>, ActivityError> {
+    log::debug!("[GET_PLACE_SUGGESTIONS] pet_id={pet_id:?}, query={query:?}");
+    state
+        .database
+        .get_place_suggestions(pet_id, query.as_deref(), subcategory.as_deref(), limit)
+        .await
+}
+
+/// Get the areas a pet is walked in most, clustered from walk locations, for a map view
+#[tauri::command]
+pub async fn get_walk_areas(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    limit: Option<i64>,
+) -> Result<Vec<WalkArea>, ActivityError> {
+    log::debug!("[GET_WALK_AREAS] pet_id={pet_id}");
+    state.database.get_walk_areas(pet_id, limit).await
+}
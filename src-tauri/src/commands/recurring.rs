@@ -0,0 +1,213 @@
+use super::dto::ActivityDto;
+use super::{AppState, Permission};
+use crate::database::{
+    ActivityCreateRequest, ActivityUpdateRequest, CreateRecurringActivityRequest,
+    RecurringOccurrence, RecurringSeries, RecurringSeriesStatus,
+};
+use crate::errors::ActivityError;
+use crate::events;
+use chrono::NaiveDate;
+use tauri::{AppHandle, State};
+
+/// Create a recurring activity series and generate any occurrences already due
+#[tauri::command]
+pub async fn create_recurring_activity(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    template: ActivityCreateRequest,
+    rrule: String,
+    start_date: Option<NaiveDate>,
+    materialize_days_ahead: Option<i64>,
+) -> Result<RecurringSeries, ActivityError> {
+    state.authorize("create_recurring_activity", Permission::Write)?;
+
+    log::info!(
+        "[CREATE_RECURRING_ACTIVITY] pet_id={}, subcategory={}, rrule={}",
+        template.pet_id,
+        template.subcategory,
+        rrule
+    );
+
+    if let Err(e) = state.database.get_pet_by_id(template.pet_id).await {
+        log::error!(
+            "[CREATE_RECURRING_ACTIVITY] Pet not found: pet_id={}, error={e}",
+            template.pet_id
+        );
+        return Err(ActivityError::validation("pet_id", "Pet not found"));
+    }
+
+    let today = chrono::Local::now().date_naive();
+    let series = state
+        .database
+        .create_recurring_series(CreateRecurringActivityRequest {
+            template,
+            rrule,
+            start_date: start_date.unwrap_or(today),
+            materialize_days_ahead,
+        })
+        .await?;
+
+    let created = state
+        .database
+        .materialize_recurring_activities(today)
+        .await?;
+    let created = emit_created(&app_handle, &state, created).await;
+    log::info!(
+        "[CREATE_RECURRING_ACTIVITY] Success: series_id={}, generated {} activities",
+        series.id,
+        created.len()
+    );
+    Ok(series)
+}
+
+/// Get all recurring series for a pet
+#[tauri::command]
+pub async fn get_recurring_series(
+    state: State<'_, AppState>,
+    pet_id: i64,
+) -> Result<Vec<RecurringSeries>, ActivityError> {
+    log::debug!("[GET_RECURRING_SERIES] pet_id={pet_id}");
+    state.database.get_recurring_series_for_pet(pet_id).await
+}
+
+/// Get generated, skipped and edited occurrences of a series
+#[tauri::command]
+pub async fn get_recurring_occurrences(
+    state: State<'_, AppState>,
+    series_id: i64,
+) -> Result<Vec<RecurringOccurrence>, ActivityError> {
+    log::debug!("[GET_RECURRING_OCCURRENCES] series_id={series_id}");
+    state.database.get_recurring_occurrences(series_id).await
+}
+
+/// Pause a series; no new occurrences are generated until it is resumed
+#[tauri::command]
+pub async fn pause_recurring_series(
+    state: State<'_, AppState>,
+    series_id: i64,
+) -> Result<RecurringSeries, ActivityError> {
+    state.authorize("pause_recurring_series", Permission::Write)?;
+
+    log::info!("[PAUSE_RECURRING_SERIES] series_id={series_id}");
+    state
+        .database
+        .set_recurring_series_status(series_id, RecurringSeriesStatus::Paused)
+        .await
+}
+
+/// Resume a paused series and generate any occurrences that became due
+#[tauri::command]
+pub async fn resume_recurring_series(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    series_id: i64,
+) -> Result<RecurringSeries, ActivityError> {
+    state.authorize("resume_recurring_series", Permission::Write)?;
+
+    log::info!("[RESUME_RECURRING_SERIES] series_id={series_id}");
+    let series = state
+        .database
+        .set_recurring_series_status(series_id, RecurringSeriesStatus::Active)
+        .await?;
+    let created = state
+        .database
+        .materialize_recurring_activities(chrono::Local::now().date_naive())
+        .await?;
+    emit_created(&app_handle, &state, created).await;
+    Ok(series)
+}
+
+/// Cancel a series permanently; already generated activities are kept
+#[tauri::command]
+pub async fn cancel_recurring_series(
+    state: State<'_, AppState>,
+    series_id: i64,
+) -> Result<RecurringSeries, ActivityError> {
+    state.authorize("cancel_recurring_series", Permission::Write)?;
+
+    log::info!("[CANCEL_RECURRING_SERIES] series_id={series_id}");
+    state
+        .database
+        .set_recurring_series_status(series_id, RecurringSeriesStatus::Cancelled)
+        .await
+}
+
+/// Edit a single occurrence of a series
+#[tauri::command]
+pub async fn update_recurring_occurrence(
+    state: State<'_, AppState>,
+    series_id: i64,
+    occurrence_date: NaiveDate,
+    updates: ActivityUpdateRequest,
+) -> Result<RecurringOccurrence, ActivityError> {
+    state.authorize("update_recurring_occurrence", Permission::Write)?;
+
+    log::info!("[UPDATE_RECURRING_OCCURRENCE] series_id={series_id}, date={occurrence_date}");
+    state
+        .database
+        .update_recurring_occurrence(series_id, occurrence_date, updates)
+        .await
+}
+
+/// Skip a single occurrence of a series
+#[tauri::command]
+pub async fn skip_recurring_occurrence(
+    state: State<'_, AppState>,
+    series_id: i64,
+    occurrence_date: NaiveDate,
+) -> Result<RecurringOccurrence, ActivityError> {
+    state.authorize("skip_recurring_occurrence", Permission::Write)?;
+
+    log::info!("[SKIP_RECURRING_OCCURRENCE] series_id={series_id}, date={occurrence_date}");
+    let occurrence = state
+        .database
+        .skip_recurring_occurrence(series_id, occurrence_date)
+        .await?;
+
+    // Skipping may delete a generated activity; remove its attachment files
+    if let Err(e) = state.process_pending_file_deletions().await {
+        log::warn!("[SKIP_RECURRING_OCCURRENCE] Attachment cleanup failed: {e}");
+    }
+    Ok(occurrence)
+}
+
+/// Generate activities for all occurrences that are due
+#[tauri::command]
+pub async fn materialize_recurring_activities(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<ActivityDto>, ActivityError> {
+    state.authorize("materialize_recurring_activities", Permission::Write)?;
+
+    let activities = state
+        .database
+        .materialize_recurring_activities(chrono::Local::now().date_naive())
+        .await?;
+    log::info!(
+        "[MATERIALIZE_RECURRING_ACTIVITIES] generated {} activities",
+        activities.len()
+    );
+    Ok(emit_created(&app_handle, &state, activities).await)
+}
+
+/// Emit `activity:created` for generated occurrences, then any low-stock alerts they caused
+async fn emit_created(
+    app_handle: &AppHandle,
+    state: &AppState,
+    activities: Vec<crate::database::Activity>,
+) -> Vec<ActivityDto> {
+    let responses: Vec<ActivityDto> = activities
+        .into_iter()
+        .map(ActivityDto::from)
+        .inspect(|response| {
+            state
+                .event_bus
+                .emit(app_handle, events::ACTIVITY_CREATED, response);
+        })
+        .collect();
+    if !responses.is_empty() {
+        state.notify_low_stock(app_handle).await;
+        state.notify_metric_alerts(app_handle).await;
+    }
+    responses
+}
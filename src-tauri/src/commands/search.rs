@@ -0,0 +1,58 @@
+use super::{AppState, Permission};
+use crate::database::fts::FtsIndexStats;
+use crate::database::search::GlobalSearchResponse;
+use crate::database::search_query::SearchQuery;
+use crate::database::FtsTokenizer;
+use crate::errors::ActivityError;
+use tauri::State;
+
+/// Search pets, activities and documents for the app-wide search bar
+#[tauri::command]
+pub async fn global_search(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<i64>,
+) -> Result<GlobalSearchResponse, ActivityError> {
+    log::debug!("[GLOBAL_SEARCH] query='{query}', limit={limit:?}");
+
+    let response = state.database.global_search(&query, limit).await?;
+
+    log::debug!("[GLOBAL_SEARCH] {} results", response.results.len());
+    Ok(response)
+}
+
+/// Show how a search query will be read, e.g. while it is being typed
+#[tauri::command]
+pub async fn parse_search_query(query: String) -> Result<SearchQuery, ActivityError> {
+    Ok(SearchQuery::parse(&query))
+}
+
+/// Get the tokenizer the search indexes are built with
+#[tauri::command]
+pub async fn get_search_tokenizer(
+    state: State<'_, AppState>,
+) -> Result<FtsTokenizer, ActivityError> {
+    state
+        .database
+        .get_fts_tokenizer()
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+}
+
+/// Switch the search tokenizer and rebuild the search indexes with it.
+/// `trigram` makes text written without spaces, such as Chinese notes, searchable.
+#[tauri::command]
+pub async fn set_search_tokenizer(
+    state: State<'_, AppState>,
+    tokenizer: FtsTokenizer,
+) -> Result<FtsIndexStats, ActivityError> {
+    state.authorize("set_search_tokenizer", Permission::Write)?;
+
+    log::info!("[SET_SEARCH_TOKENIZER] tokenizer={tokenizer}");
+    let stats = state.database.set_fts_tokenizer(tokenizer).await?;
+    log::info!(
+        "[SET_SEARCH_TOKENIZER] Rebuilt index with {} activities",
+        stats.document_count
+    );
+    Ok(stats)
+}
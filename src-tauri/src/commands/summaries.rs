@@ -0,0 +1,127 @@
+use super::dto::ActivityDto;
+use super::{AppState, Permission};
+use crate::database::timeline_digest::{DigestLocale, DigestRange, TimelineDigest};
+use crate::database::{in_background, PetDatabase};
+use crate::errors::ActivityError;
+use crate::events::{self, DeletedPayload, EventBus};
+use chrono::NaiveDate;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+/// How often the background task looks for days that still need a summary
+const DAILY_SUMMARY_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Recompose a pet's summary for `date` after its activities changed.
+/// Returns None (and removes any old summary) when the day has no activities.
+#[tauri::command]
+pub async fn regenerate_daily_summary(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    pet_id: i64,
+    date: NaiveDate,
+) -> Result<Option<ActivityDto>, ActivityError> {
+    state.authorize("regenerate_daily_summary", Permission::Write)?;
+
+    log::info!("[REGENERATE_DAILY_SUMMARY] pet_id={pet_id}, date={date}");
+    if let Err(e) = state.database.get_pet_by_id(pet_id).await {
+        return Err(ActivityError::validation(
+            "pet_id",
+            &format!("Pet not found: {e}"),
+        ));
+    }
+
+    let previous = state.database.find_daily_summary(pet_id, date).await?;
+    let summary = state
+        .database
+        .generate_daily_summary(pet_id, date)
+        .await?
+        .map(ActivityDto::from);
+
+    match (&previous, &summary) {
+        (None, Some(response)) => {
+            state
+                .event_bus
+                .emit(&app_handle, events::ACTIVITY_CREATED, response);
+        }
+        (Some(_), Some(response)) => {
+            state
+                .event_bus
+                .emit(&app_handle, events::ACTIVITY_UPDATED, response);
+        }
+        (Some(previous), None) => {
+            state.event_bus.emit(
+                &app_handle,
+                events::ACTIVITY_DELETED,
+                DeletedPayload {
+                    id: previous.id,
+                    pet_id: Some(pet_id),
+                },
+            );
+        }
+        (None, None) => {}
+    }
+    Ok(summary)
+}
+
+/// One-line summaries of a pet's activities in `range`, newest first, with times
+/// in the device's timezone. Lets the mobile timeline skip loading full activities.
+#[tauri::command]
+pub async fn get_timeline_digest(
+    state: State<'_, AppState>,
+    pet_id: i64,
+    range: DigestRange,
+    locale: Option<DigestLocale>,
+) -> Result<TimelineDigest, ActivityError> {
+    log::debug!(
+        "[GET_TIMELINE_DIGEST] pet_id={pet_id}, {}..={}, locale={locale:?}",
+        range.start_date,
+        range.end_date
+    );
+    state
+        .database
+        .get_timeline_digest(
+            pet_id,
+            range,
+            locale.unwrap_or_default(),
+            *chrono::Local::now().offset(),
+        )
+        .await
+}
+
+/// Generate yesterday's summaries for pets that don't have one yet
+pub async fn generate_due_daily_summaries(
+    app_handle: &AppHandle,
+    database: &PetDatabase,
+    event_bus: &EventBus,
+) -> Result<usize, ActivityError> {
+    let Some(yesterday) = chrono::Local::now().date_naive().pred_opt() else {
+        return Ok(0);
+    };
+    let created = database.generate_missing_daily_summaries(yesterday).await?;
+    for activity in &created {
+        event_bus.emit(
+            app_handle,
+            events::ACTIVITY_CREATED,
+            ActivityDto::from(activity.clone()),
+        );
+    }
+    Ok(created.len())
+}
+
+/// Start the background task that writes end-of-day summaries
+pub fn spawn_daily_summary_job(
+    app_handle: AppHandle,
+    database: Arc<PetDatabase>,
+    event_bus: Arc<EventBus>,
+) {
+    // Summary writes queue behind activities the user is logging
+    tauri::async_runtime::spawn(in_background(async move {
+        loop {
+            if let Err(e) = generate_due_daily_summaries(&app_handle, &database, &event_bus).await {
+                log::warn!("Daily summary generation failed: {e}");
+            }
+            tokio::time::sleep(DAILY_SUMMARY_CHECK_INTERVAL).await;
+        }
+    }));
+}
@@ -0,0 +1,147 @@
+//! Test support: application state on an in-memory database, managed by a mock app.
+//!
+//! `TestApp` wires every subsystem the way `initialize_app` does, on an in-memory
+//! database and temporary photo, document and export directories, so command-level
+//! tests can call commands that take `State` directly:
+//! `get_pets(app.state(), false).await`. Commands that also take the real
+//! `AppHandle` to emit events can't run on the mock runtime; test their database
+//! and service calls through `app.database()` instead.
+
+use super::{AccessLevel, AppState};
+use crate::database::{PetDatabase, WeightUnit, WEIGHT_UNIT_SETTING_KEY};
+use crate::errors::PetError;
+use crate::periods::{PeriodSettings, PERIOD_SETTINGS_KEY};
+use crate::photo::{PhotoSettings, PHOTO_SETTINGS_KEY};
+use std::path::Path;
+use tauri::test::{mock_app, MockRuntime};
+use tauri::{App, Manager, State};
+use tempfile::TempDir;
+
+/// A mock Tauri app managing an `AppState` built for tests.
+/// Files the state writes go to a temporary directory removed on drop.
+pub struct TestApp {
+    app: App<MockRuntime>,
+    dir: TempDir,
+}
+
+impl TestApp {
+    /// App with full access
+    pub async fn new() -> Self {
+        Self::with_access_level(AccessLevel::Full).await
+    }
+
+    /// App whose state has the given access level, e.g. read-only
+    pub async fn with_access_level(access_level: AccessLevel) -> Self {
+        let dir = TempDir::new().expect("Failed to create temp directory");
+        let state = AppState::in_memory(dir.path())
+            .await
+            .expect("Failed to build test app state")
+            .with_access_level(access_level);
+
+        let app = mock_app();
+        app.manage(state);
+        TestApp { app, dir }
+    }
+
+    /// State to pass to commands
+    pub fn state(&self) -> State<'_, AppState> {
+        self.app.state::<AppState>()
+    }
+
+    pub fn database(&self) -> &PetDatabase {
+        &self.state().inner().database
+    }
+
+    /// Directory holding the photo, document and export directories
+    pub fn data_dir(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl AppState {
+    /// State wired like the app's, on an in-memory database with default settings
+    /// stored. Photos, documents and exports go to directories under `data_dir`.
+    pub async fn in_memory(data_dir: &Path) -> Result<Self, PetError> {
+        let database = PetDatabase::in_memory().await?;
+        database
+            .set_setting(WEIGHT_UNIT_SETTING_KEY, &WeightUnit::default())
+            .await?;
+        database
+            .set_setting(PHOTO_SETTINGS_KEY, &PhotoSettings::default())
+            .await?;
+        database
+            .set_setting(PERIOD_SETTINGS_KEY, &PeriodSettings::default())
+            .await?;
+
+        let export_dir = data_dir.join("exports");
+        std::fs::create_dir_all(&export_dir)
+            .map_err(|e| PetError::file_system(format!("Failed to create export dir: {e}")))?;
+        Self::with_database(
+            database,
+            data_dir.join("photos"),
+            data_dir.join("documents"),
+            export_dir,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{get_exercise_summary, get_pets, set_exercise_target};
+    use super::*;
+    use crate::database::{CreatePetRequest, PetGender, PetSpecies};
+    use chrono::NaiveDate;
+
+    #[tokio::test]
+    async fn test_commands_run_against_test_app() {
+        let app = TestApp::new().await;
+        let pet = app
+            .database()
+            .create_pet(CreatePetRequest {
+                name: "Mochi".to_string(),
+                birth_date: NaiveDate::from_ymd_opt(2021, 4, 2).unwrap(),
+                species: PetSpecies::Dog,
+                gender: PetGender::Female,
+                breed: None,
+                color: None,
+                weight_kg: None,
+                photo_path: None,
+                notes: None,
+            })
+            .await
+            .unwrap();
+
+        let pets = get_pets(app.state(), false).await.unwrap();
+        assert_eq!(pets.iter().map(|p| p.id).collect::<Vec<_>>(), vec![pet.id]);
+
+        set_exercise_target(app.state(), pet.id, Some(90.0))
+            .await
+            .unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let summary = get_exercise_summary(app.state(), pet.id, day, day)
+            .await
+            .unwrap();
+        assert_eq!(summary.weekly_target_minutes, 90.0);
+
+        assert_eq!(
+            app.database()
+                .get_setting::<PhotoSettings>(PHOTO_SETTINGS_KEY)
+                .await
+                .unwrap(),
+            Some(PhotoSettings::default())
+        );
+        assert!(app.data_dir().join("exports").is_dir());
+    }
+
+    #[tokio::test]
+    async fn test_read_only_test_app_rejects_writes() {
+        use crate::errors::AppError;
+
+        let app = TestApp::with_access_level(AccessLevel::ReadOnly).await;
+        let error = set_exercise_target(app.state(), 1, Some(90.0))
+            .await
+            .unwrap_err();
+        assert_eq!(error.error_code(), "PERMISSION_DENIED");
+    }
+}
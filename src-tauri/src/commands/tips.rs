@@ -0,0 +1,21 @@
+use crate::database::{ActivityCategory, PetSpecies};
+use crate::errors::PetError;
+use crate::tips::{self, CareTip};
+
+/// Care tips relevant to the activity being edited, most specific first
+#[tauri::command]
+pub async fn get_contextual_tips(
+    category: ActivityCategory,
+    subcategory: Option<String>,
+    species: Option<PetSpecies>,
+) -> Result<Vec<CareTip>, PetError> {
+    log::debug!(
+        "[GET_CONTEXTUAL_TIPS] category={category}, subcategory={subcategory:?}, species={species:?}"
+    );
+    Ok(
+        tips::contextual_tips(category, subcategory.as_deref(), species.as_ref())
+            .into_iter()
+            .cloned()
+            .collect(),
+    )
+}
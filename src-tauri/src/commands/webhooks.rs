@@ -0,0 +1,183 @@
+use super::{AppState, Permission};
+use crate::database::webhooks::WebhookQueueStatus;
+use crate::database::PetDatabase;
+use crate::errors::PetError;
+use crate::webhook::{self, WebhookEndpoint, WebhookSettings, WebhookTestResult};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::State;
+use tokio::sync::Notify;
+
+/// How often queued webhook deliveries are retried when nothing new is created
+const WEBHOOK_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Deliveries sent per round, so a long backlog doesn't hold up new activities
+const WEBHOOK_BATCH_SIZE: i64 = 20;
+
+/// Get the automation webhook settings
+#[tauri::command]
+pub async fn get_webhook_settings(state: State<'_, AppState>) -> Result<WebhookSettings, PetError> {
+    Ok(state
+        .database
+        .get_setting::<WebhookSettings>(webhook::WEBHOOK_SETTINGS_KEY)
+        .await?
+        .unwrap_or_default())
+}
+
+/// Enable, disable or point the automation webhook elsewhere.
+/// Deliveries still queued are dropped when the webhook is disabled.
+#[tauri::command]
+pub async fn update_webhook_settings(
+    state: State<'_, AppState>,
+    settings: WebhookSettings,
+) -> Result<WebhookSettings, PetError> {
+    state.authorize("update_webhook_settings", Permission::Write)?;
+
+    settings.validate()?;
+    log::info!(
+        "[UPDATE_WEBHOOK_SETTINGS] enabled={}, url={:?}",
+        settings.enabled,
+        settings.url
+    );
+    state
+        .database
+        .set_setting(webhook::WEBHOOK_SETTINGS_KEY, &settings)
+        .await?;
+
+    if !settings.enabled {
+        let dropped = state
+            .database
+            .clear_webhook_deliveries()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        if dropped > 0 {
+            log::info!("[UPDATE_WEBHOOK_SETTINGS] Dropped {dropped} queued deliveries");
+        }
+    }
+    Ok(settings)
+}
+
+/// Post a sample payload to `url`, or to the configured URL when none is given
+#[tauri::command]
+pub async fn test_webhook(
+    state: State<'_, AppState>,
+    url: Option<String>,
+) -> Result<WebhookTestResult, PetError> {
+    let url = match url {
+        Some(url) => url,
+        None => state
+            .database
+            .get_setting::<WebhookSettings>(webhook::WEBHOOK_SETTINGS_KEY)
+            .await?
+            .and_then(|settings| settings.url)
+            .ok_or_else(|| PetError::validation("url", "No webhook URL is configured"))?,
+    };
+    let endpoint = WebhookEndpoint::parse(&url)?;
+
+    log::info!("[TEST_WEBHOOK] Posting test payload to {url}");
+    let result = webhook::test_endpoint(&endpoint).await;
+    log::info!(
+        "[TEST_WEBHOOK] success={}, status={:?}, elapsed={}ms",
+        result.success,
+        result.status,
+        result.elapsed_ms
+    );
+    Ok(result)
+}
+
+/// Get the number of queued and failed webhook deliveries
+#[tauri::command]
+pub async fn get_webhook_queue_status(
+    state: State<'_, AppState>,
+) -> Result<WebhookQueueStatus, PetError> {
+    state
+        .database
+        .get_webhook_queue_status()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))
+}
+
+/// Try failed webhook deliveries again
+#[tauri::command]
+pub async fn retry_webhook_deliveries(
+    state: State<'_, AppState>,
+) -> Result<WebhookQueueStatus, PetError> {
+    state.authorize("retry_webhook_deliveries", Permission::Write)?;
+
+    let requeued = state
+        .database
+        .retry_failed_webhook_deliveries()
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    log::info!("[RETRY_WEBHOOK_DELIVERIES] Requeued {requeued} deliveries");
+    state.webhook_wakeup.notify_one();
+    get_webhook_queue_status(state).await
+}
+
+/// Post due deliveries in order and return how many the receiver accepted.
+/// A failure stops the round so the receiver sees activities in creation order.
+pub async fn deliver_due_webhooks(database: &PetDatabase) -> Result<usize, PetError> {
+    let Some(endpoint) = database
+        .get_setting::<WebhookSettings>(webhook::WEBHOOK_SETTINGS_KEY)
+        .await?
+        .and_then(|settings| settings.endpoint())
+    else {
+        return Ok(0);
+    };
+
+    let due = database
+        .get_due_webhook_deliveries(chrono::Utc::now(), WEBHOOK_BATCH_SIZE)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+    let mut delivered = 0;
+    for delivery in due {
+        let error = match webhook::post_json(&endpoint, delivery.payload.as_bytes()).await {
+            Ok(status) if (200..300).contains(&status) => None,
+            Ok(status) => Some(format!("HTTP {status}")),
+            Err(e) => Some(e),
+        };
+        match error {
+            None => {
+                database
+                    .complete_webhook_delivery(delivery.id)
+                    .await
+                    .map_err(|e| PetError::database(e.to_string()))?;
+                delivered += 1;
+            }
+            Some(error) => {
+                log::warn!(
+                    "Webhook delivery for activity_id={} failed (attempt {}): {error}",
+                    delivery.activity_id,
+                    delivery.attempts + 1
+                );
+                database
+                    .record_webhook_failure(&delivery, &error, chrono::Utc::now())
+                    .await
+                    .map_err(|e| PetError::database(e.to_string()))?;
+                break;
+            }
+        }
+    }
+    Ok(delivered)
+}
+
+/// Start the background task that posts queued activities to the webhook.
+/// It runs when woken after an activity is created and every retry interval.
+pub fn spawn_webhook_dispatcher(database: Arc<PetDatabase>, wakeup: Arc<Notify>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match deliver_due_webhooks(&database).await {
+                Ok(delivered) if delivered > 0 => {
+                    log::debug!("Delivered {delivered} webhook payloads")
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Webhook delivery failed: {e}"),
+            }
+            tokio::select! {
+                _ = wakeup.notified() => {}
+                _ = tokio::time::sleep(WEBHOOK_RETRY_INTERVAL) => {}
+            }
+        }
+    });
+}
@@ -0,0 +1,621 @@
+//! Where the app keeps its data.
+//!
+//! Data lives in the platform app data directory unless the user chose another
+//! root, such as a synced folder: with `--data-dir <path>` on the command line, or in
+//! `data_location.json` in the platform directory, which `set_data_directory`
+//! writes. A move asked for in that file is made the next time the app starts,
+//! before the database is opened, and a lock file keeps a second instance out of a
+//! directory that is in use.
+
+use crate::errors::PetError;
+use crate::startup::{InitCheck, InitStatus};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Settings file in the platform app data directory naming the chosen root
+pub const LOCATION_FILE: &str = "data_location.json";
+
+/// File locked by the instance using a data directory
+pub const LOCK_FILE: &str = "paw-diary.lock";
+
+/// Command-line argument choosing the data root for one run
+pub const DATA_DIR_ARG: &str = "--data-dir";
+
+/// Database file in the data root
+pub const DATABASE_FILE: &str = "pets.db";
+
+/// Files and directories that make up the data, moved together
+const DATA_ENTRIES: [&str; 7] = [
+    DATABASE_FILE,
+    "pets.db-wal",
+    "pets.db-shm",
+    "photos",
+    "documents",
+    "exports",
+    "models",
+];
+
+/// Lock on the directory this instance uses, kept until the app exits
+static HELD_LOCK: Mutex<Option<DataDirLock>> = Mutex::new(None);
+
+/// Contents of the location settings file
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DataLocation {
+    /// Root chosen by the user; None uses the platform directory
+    pub data_dir: Option<PathBuf>,
+    /// Root to move the data from at the next start
+    #[serde(default)]
+    pub move_from: Option<PathBuf>,
+}
+
+/// Where the data root in use came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DataDirSource {
+    Default,
+    Settings,
+    CommandLine,
+}
+
+/// Data root to use and how it was chosen
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedDataDir {
+    pub path: PathBuf,
+    pub source: DataDirSource,
+    /// Root the data should be moved from first
+    pub move_from: Option<PathBuf>,
+}
+
+/// Data root the app opened, with what happened while opening it
+#[derive(Debug)]
+pub struct OpenedDataDir {
+    pub path: PathBuf,
+    pub source: DataDirSource,
+    /// Location and move checks for the start-up report
+    pub checks: Vec<InitCheck>,
+}
+
+/// Data root in use and the one set for the next start
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DataDirStatus {
+    pub path: String,
+    pub source: DataDirSource,
+    pub default_path: String,
+    /// Root the next start will use, when it differs from the one in use
+    pub pending_path: Option<String>,
+    /// Whether the data will be moved to the pending root
+    pub pending_move: bool,
+}
+
+/// Data moved to a new root
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DataMove {
+    pub files: u64,
+    pub bytes: u64,
+}
+
+/// Exclusive lock on a data directory, released when dropped or when the process ends
+#[derive(Debug)]
+pub struct DataDirLock {
+    dir: PathBuf,
+    _file: File,
+}
+
+impl DataDirLock {
+    /// Lock `dir`, failing when another instance holds it
+    pub fn acquire(dir: &Path) -> Result<Self, PetError> {
+        let path = dir.join(LOCK_FILE);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .map_err(|e| {
+                PetError::file_system(format!("Failed to open {}: {e}", path.display()))
+            })?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                return Err(PetError::concurrent_access(format!(
+                    "Another Paw Diary instance is using {}",
+                    dir.display()
+                )))
+            }
+            Err(TryLockError::Error(e)) => {
+                return Err(PetError::file_system(format!(
+                    "Failed to lock {}: {e}",
+                    path.display()
+                )))
+            }
+        }
+        // The process id helps tell who holds a lock; the lock itself is the OS's
+        let _ = file
+            .set_len(0)
+            .and_then(|_| writeln!(file, "{}", std::process::id()));
+
+        Ok(DataDirLock {
+            dir: dir.to_path_buf(),
+            _file: file,
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// The root given with `--data-dir <path>` or `--data-dir=<path>`
+pub fn data_dir_argument<I: IntoIterator<Item = String>>(args: I) -> Option<PathBuf> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == DATA_DIR_ARG {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg
+            .strip_prefix(DATA_DIR_ARG)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(PathBuf::from(path));
+        }
+    }
+    None
+}
+
+/// Read the location settings in `default_dir`; a missing file means the default root
+pub fn read_location(default_dir: &Path) -> Result<DataLocation, PetError> {
+    let path = default_dir.join(LOCATION_FILE);
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(DataLocation::default()),
+        Err(e) => {
+            return Err(PetError::file_system(format!(
+                "Failed to read {}: {e}",
+                path.display()
+            )))
+        }
+    };
+    serde_json::from_str(&text)
+        .map_err(|e| PetError::invalid_input(format!("Invalid {LOCATION_FILE}: {e}")))
+}
+
+/// Save the location settings, replacing the file in one step
+pub fn write_location(default_dir: &Path, location: &DataLocation) -> Result<(), PetError> {
+    let failed =
+        |e: std::io::Error| PetError::file_system(format!("Failed to save the data location: {e}"));
+    fs::create_dir_all(default_dir).map_err(failed)?;
+    let json = serde_json::to_string_pretty(location)
+        .map_err(|e| PetError::operation_failed(format!("Failed to encode location: {e}")))?;
+    let path = default_dir.join(LOCATION_FILE);
+    let partial = default_dir.join(format!("{LOCATION_FILE}.part"));
+    fs::write(&partial, json)
+        .and_then(|_| fs::rename(&partial, &path))
+        .map_err(failed)
+}
+
+/// Pick the data root: the command line wins over the settings file, which wins
+/// over the platform directory. Moves only run for the root in the settings.
+pub fn resolve_data_dir(
+    default_dir: &Path,
+    argument: Option<PathBuf>,
+) -> Result<ResolvedDataDir, PetError> {
+    if let Some(path) = argument {
+        check_root(&path)?;
+        return Ok(ResolvedDataDir {
+            path,
+            source: DataDirSource::CommandLine,
+            move_from: None,
+        });
+    }
+    let location = read_location(default_dir)?;
+    Ok(match location.data_dir {
+        Some(path) => ResolvedDataDir {
+            path,
+            source: DataDirSource::Settings,
+            move_from: location.move_from,
+        },
+        None => ResolvedDataDir {
+            path: default_dir.to_path_buf(),
+            source: DataDirSource::Default,
+            move_from: location.move_from,
+        },
+    })
+}
+
+/// Check a root chosen by the user can hold the data
+pub fn check_root(path: &Path) -> Result<(), PetError> {
+    if !path.is_absolute() {
+        return Err(PetError::validation(
+            "data_dir".to_string(),
+            format!("{} is not an absolute path", path.display()),
+        ));
+    }
+    if path.exists() && !path.is_dir() {
+        return Err(PetError::validation(
+            "data_dir".to_string(),
+            format!("{} is not a directory", path.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Check data can be moved from `from` to `to`: neither may contain the other, and
+/// `to` must not hold a database already
+pub fn check_move(from: &Path, to: &Path) -> Result<(), PetError> {
+    let from = normalize(from);
+    let to = normalize(to);
+    if from == to {
+        return Err(PetError::validation(
+            "data_dir",
+            "The data is already in this directory",
+        ));
+    }
+    if from.starts_with(&to) || to.starts_with(&from) {
+        return Err(PetError::validation(
+            "data_dir",
+            "The new data directory can't be inside the current one or contain it",
+        ));
+    }
+    if to.join(DATABASE_FILE).exists() {
+        return Err(PetError::validation(
+            "data_dir".to_string(),
+            format!(
+                "{} already holds Paw Diary data; choose it without moving to use that data",
+                to.display()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Copy the data from `from` to `to`, check the copy is complete, then delete the
+/// originals. A failed copy is removed again and the originals are kept. Both roots
+/// must be locked so the database is closed while its files are copied.
+pub fn move_data(from: &Path, to: &Path) -> Result<DataMove, PetError> {
+    if !from.join(DATABASE_FILE).exists() {
+        return Ok(DataMove::default());
+    }
+    check_move(from, to)?;
+    for entry in DATA_ENTRIES {
+        let target = to.join(entry);
+        let empty_dir = fs::read_dir(&target).is_ok_and(|mut dir| dir.next().is_none());
+        if target.exists() && !empty_dir {
+            return Err(PetError::validation(
+                "data_dir".to_string(),
+                format!("{} already exists", target.display()),
+            ));
+        }
+    }
+
+    let mut copied = Vec::new();
+    let mut moved = DataMove::default();
+    let result = DATA_ENTRIES
+        .iter()
+        .filter(|entry| from.join(entry).exists())
+        .try_for_each(|entry| {
+            let (source, target) = (from.join(entry), to.join(entry));
+            copied.push(target.clone());
+            copy_tree(&source, &target)?;
+            let (source_size, target_size) = (tree_size(&source)?, tree_size(&target)?);
+            if source_size != target_size {
+                return Err(std::io::Error::other(format!(
+                    "{entry}: copied {} of {} files",
+                    target_size.files, source_size.files
+                )));
+            }
+            moved.files += source_size.files;
+            moved.bytes += source_size.bytes;
+            Ok(())
+        });
+    if let Err(e) = result {
+        for target in copied {
+            let _ = remove_tree(&target);
+        }
+        return Err(PetError::file_system(format!(
+            "Failed to copy the data to {}: {e}",
+            to.display()
+        )));
+    }
+
+    for entry in DATA_ENTRIES {
+        let source = from.join(entry);
+        if source.exists() {
+            if let Err(e) = remove_tree(&source) {
+                log::warn!(
+                    "Data was moved but {} could not be deleted: {e}",
+                    source.display()
+                );
+            }
+        }
+    }
+    Ok(moved)
+}
+
+/// Resolve and lock the data root, making a pending move first. When the move
+/// fails the data stays where it was and the settings are pointed back at it.
+pub fn open_data_dir(
+    default_dir: &Path,
+    argument: Option<PathBuf>,
+) -> Result<OpenedDataDir, PetError> {
+    let resolved = resolve_data_dir(default_dir, argument)?;
+    fs::create_dir_all(&resolved.path).map_err(|e| {
+        PetError::file_system(format!(
+            "Failed to create data directory {}: {e}",
+            resolved.path.display()
+        ))
+    })?;
+    let mut checks = vec![InitCheck::new(
+        "data_directory",
+        InitStatus::Ok,
+        format!("Using {} ({:?})", resolved.path.display(), resolved.source),
+    )];
+
+    let Some(from) = resolved.move_from else {
+        hold_lock(&resolved.path)?;
+        return Ok(OpenedDataDir {
+            path: resolved.path,
+            source: resolved.source,
+            checks,
+        });
+    };
+
+    let from_lock = DataDirLock::acquire(&from)?;
+    hold_lock(&resolved.path)?;
+    let location = match move_data(&from, &resolved.path) {
+        Ok(moved) => {
+            checks.push(InitCheck::new(
+                "data_move",
+                InitStatus::Ok,
+                format!(
+                    "Moved {} files ({} bytes) from {}",
+                    moved.files,
+                    moved.bytes,
+                    from.display()
+                ),
+            ));
+            DataLocation {
+                data_dir: (resolved.source == DataDirSource::Settings)
+                    .then(|| resolved.path.clone()),
+                move_from: None,
+            }
+        }
+        Err(e) => {
+            checks.push(InitCheck::new(
+                "data_move",
+                InitStatus::Repaired,
+                format!("{e}; the data was left in {}", from.display()),
+            ));
+            drop(from_lock);
+            hold_lock(&from)?;
+            write_location(
+                default_dir,
+                &DataLocation {
+                    data_dir: (from != default_dir).then(|| from.clone()),
+                    move_from: None,
+                },
+            )?;
+            return Ok(OpenedDataDir {
+                path: from,
+                source: resolved.source,
+                checks,
+            });
+        }
+    };
+    write_location(default_dir, &location)?;
+    Ok(OpenedDataDir {
+        path: resolved.path,
+        source: resolved.source,
+        checks,
+    })
+}
+
+/// Data root this instance has locked, if the app was initialized
+pub fn current_data_dir() -> Option<PathBuf> {
+    HELD_LOCK
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_ref()
+        .map(|lock| lock.dir().to_path_buf())
+}
+
+/// Where the data is and where it will be after the next start
+pub fn data_dir_status(
+    default_dir: &Path,
+    current: &Path,
+    source: DataDirSource,
+) -> Result<DataDirStatus, PetError> {
+    let location = read_location(default_dir)?;
+    let next = location
+        .data_dir
+        .clone()
+        .unwrap_or_else(|| default_dir.to_path_buf());
+    let pending = (source != DataDirSource::CommandLine && normalize(&next) != normalize(current))
+        .then(|| next.display().to_string());
+    Ok(DataDirStatus {
+        path: current.display().to_string(),
+        source,
+        default_path: default_dir.display().to_string(),
+        pending_move: pending.is_some() && location.move_from.is_some(),
+        pending_path: pending,
+    })
+}
+
+/// Lock `dir` for this instance, keeping the lock it already holds when the app is
+/// initialized again
+fn hold_lock(dir: &Path) -> Result<(), PetError> {
+    let mut held = HELD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if held
+        .as_ref()
+        .is_some_and(|lock| normalize(lock.dir()) == normalize(dir))
+    {
+        return Ok(());
+    }
+    *held = Some(DataDirLock::acquire(dir)?);
+    Ok(())
+}
+
+/// Absolute form of a path for comparisons, following links when it exists
+fn normalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct TreeSize {
+    files: u64,
+    bytes: u64,
+}
+
+fn tree_size(path: &Path) -> std::io::Result<TreeSize> {
+    if !path.is_dir() {
+        return Ok(TreeSize {
+            files: 1,
+            bytes: fs::metadata(path)?.len(),
+        });
+    }
+    let mut size = TreeSize::default();
+    for entry in fs::read_dir(path)? {
+        let child = tree_size(&entry?.path())?;
+        size.files += child.files;
+        size.bytes += child.bytes;
+    }
+    Ok(size)
+}
+
+fn copy_tree(source: &Path, target: &Path) -> std::io::Result<()> {
+    if !source.is_dir() {
+        fs::copy(source, target)?;
+        return File::open(target)?.sync_all();
+    }
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        copy_tree(&entry.path(), &target.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+fn remove_tree(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn library(dir: &Path) {
+        fs::create_dir_all(dir.join("photos/thumbs")).unwrap();
+        fs::write(dir.join(DATABASE_FILE), b"sqlite").unwrap();
+        fs::write(dir.join("photos/a.jpg"), vec![7u8; 2048]).unwrap();
+        fs::write(dir.join("photos/thumbs/a.jpg"), b"thumb").unwrap();
+    }
+
+    #[test]
+    fn test_command_line_wins_over_settings() {
+        let dir = tempfile::tempdir().unwrap();
+        let synced = dir.path().join("Dropbox/PawDiary");
+
+        assert_eq!(
+            data_dir_argument(args(&["--verbose", "--data-dir", "/data/pets"])),
+            Some(PathBuf::from("/data/pets"))
+        );
+        assert_eq!(
+            data_dir_argument(args(&["--data-dir=/data/pets"])),
+            Some(PathBuf::from("/data/pets"))
+        );
+        assert_eq!(data_dir_argument(args(&["--data-dir"])), None);
+
+        let resolved = resolve_data_dir(dir.path(), None).unwrap();
+        assert_eq!(resolved.source, DataDirSource::Default);
+        assert_eq!(resolved.path, dir.path());
+
+        let location = DataLocation {
+            data_dir: Some(synced.clone()),
+            move_from: None,
+        };
+        write_location(dir.path(), &location).unwrap();
+        assert_eq!(read_location(dir.path()).unwrap(), location);
+        assert_eq!(resolve_data_dir(dir.path(), None).unwrap().path, synced);
+
+        let from_args = resolve_data_dir(dir.path(), Some(dir.path().join("other"))).unwrap();
+        assert_eq!(from_args.source, DataDirSource::CommandLine);
+        assert!(resolve_data_dir(dir.path(), Some(PathBuf::from("relative"))).is_err());
+    }
+
+    #[test]
+    fn test_move_copies_checks_and_removes_originals() {
+        let dir = tempfile::tempdir().unwrap();
+        let (from, to) = (dir.path().join("old"), dir.path().join("new"));
+        library(&from);
+        fs::create_dir_all(to.join("photos")).unwrap();
+
+        let moved = move_data(&from, &to).unwrap();
+        assert_eq!(
+            moved,
+            DataMove {
+                files: 3,
+                bytes: 6 + 2048 + 5
+            }
+        );
+        assert_eq!(fs::read(to.join("photos/thumbs/a.jpg")).unwrap(), b"thumb");
+        assert!(!from.join(DATABASE_FILE).exists());
+        assert!(!from.join("photos").exists());
+
+        // Never over existing data, and never into itself
+        library(&from);
+        assert!(move_data(&from, &to).is_err());
+        assert!(from.join(DATABASE_FILE).exists());
+        assert!(check_move(&from, &from.join("nested")).is_err());
+    }
+
+    #[test]
+    fn test_failed_move_keeps_data_in_place() {
+        let dir = tempfile::tempdir().unwrap();
+        let default_dir = dir.path().join("default");
+        let blocked = dir.path().join("blocked");
+        library(&default_dir);
+        // A file where the documents directory would go is in the way
+        fs::create_dir_all(default_dir.join("documents")).unwrap();
+        fs::write(default_dir.join("documents/vet.pdf"), b"pdf").unwrap();
+        fs::create_dir_all(&blocked).unwrap();
+        fs::write(blocked.join("documents"), b"not a directory").unwrap();
+
+        write_location(
+            &default_dir,
+            &DataLocation {
+                data_dir: Some(blocked.clone()),
+                move_from: Some(default_dir.clone()),
+            },
+        )
+        .unwrap();
+        let opened = open_data_dir(&default_dir, None).unwrap();
+        assert_eq!(opened.path, default_dir);
+        assert_eq!(opened.checks[1].status, InitStatus::Repaired);
+        assert!(default_dir.join("photos/a.jpg").exists());
+        assert!(!blocked.join("photos").exists());
+        assert_eq!(
+            read_location(&default_dir).unwrap(),
+            DataLocation::default()
+        );
+    }
+
+    #[test]
+    fn test_lock_keeps_second_instance_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = DataDirLock::acquire(dir.path()).unwrap();
+        let second = DataDirLock::acquire(dir.path()).unwrap_err();
+        assert!(matches!(second, PetError::ConcurrentAccess { .. }));
+
+        drop(first);
+        assert!(DataDirLock::acquire(dir.path()).is_ok());
+    }
+}
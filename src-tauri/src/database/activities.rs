@@ -1,12 +1,15 @@
-use super::activity_data::ActivityDataExt;
+use super::activity_schema;
+use super::hooks::ActivityEvent;
 use super::models::*;
+use super::query::{SelectQuery, UpdateQuery};
+use super::schema_validation::{stored_activity_data, GeneratedColumns};
 use crate::errors::ActivityError;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use sqlx::Row;
 
 impl super::PetDatabase {
-    /// Create a new activity with automatic side effects (pet profile updates)
+    /// Create a new activity and run the activity hooks (pet profile updates, ...)
     /// This is the main entry point for activity creation with transactional integrity
     pub async fn create_activity_with_side_effects(
         &self,
@@ -19,6 +22,8 @@ impl super::PetDatabase {
             activity_data.subcategory
         );
 
+        let _turn = self.writes.acquire_for_task().await;
+
         // Start a transaction for atomic operation
         let mut tx = self.pool.begin().await.map_err(|e| {
             log::error!(
@@ -34,44 +39,10 @@ impl super::PetDatabase {
             .create_activity_in_transaction(&mut tx, activity_data.clone())
             .await?;
 
-        // Apply side effects based on activity type
-        if let Some(ref data) = activity.activity_data {
-            if data.should_update_pet_profile() {
-                log::debug!(
-                    "[DB] create_activity_with_side_effects: activity triggers pet profile update, activity_id={}",
-                    activity.id
-                );
-
-                // Update pet weight if this is a weight activity
-                if let Some(weight_kg) = data.extract_weight_kg() {
-                    log::info!(
-                        "[DB] create_activity_with_side_effects: updating pet weight to {} kg for pet_id={}",
-                        weight_kg,
-                        activity.pet_id
-                    );
-
-                    sqlx::query("UPDATE pets SET weight_kg = ?, updated_at = ? WHERE id = ?")
-                        .bind(weight_kg)
-                        .bind(chrono::Utc::now())
-                        .bind(activity.pet_id)
-                        .execute(&mut *tx)
-                        .await
-                        .map_err(|e| {
-                            log::error!(
-                                "[DB] create_activity_with_side_effects: failed to update pet weight, error={e}"
-                            );
-                            ActivityError::InvalidData {
-                                message: format!("Failed to update pet weight: {e}"),
-                            }
-                        })?;
-
-                    log::debug!(
-                        "[DB] create_activity_with_side_effects: successfully updated pet weight for pet_id={}",
-                        activity.pet_id
-                    );
-                }
-            }
-        }
+        // Let registered hooks (pet profile sync, ...) apply their side effects
+        self.hooks
+            .dispatch(&mut tx, ActivityEvent::Created(&activity))
+            .await?;
 
         // Commit the transaction
         tx.commit().await.map_err(|e| {
@@ -92,7 +63,7 @@ impl super::PetDatabase {
     }
 
     /// Create a new activity within a transaction (internal use)
-    async fn create_activity_in_transaction(
+    pub(super) async fn create_activity_in_transaction(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
         activity_data: ActivityCreateRequest,
@@ -107,10 +78,10 @@ impl super::PetDatabase {
         let now = chrono::Utc::now();
 
         // Convert frontend blocks format to ActivityData HashMap
-        let typed_activity_data = activity_data.activity_data.map(|json_value| {
-            use super::activity_data::ActivityDataExt;
-            super::ActivityData::from_legacy_json(json_value)
-        });
+        let typed_activity_data = activity_data
+            .activity_data
+            .map(|value| activity_schema::prepare_for_storage(value, now))
+            .transpose()?;
 
         // Serialize ActivityData to JSON string for database storage
         let activity_data_json = typed_activity_data.as_ref().and_then(|data| {
@@ -128,15 +99,16 @@ impl super::PetDatabase {
         let result = sqlx::query(
             r#"
             INSERT INTO activities (
-                pet_id, category, subcategory, activity_data, created_at, updated_at
+                pet_id, category, subcategory, activity_data, needs_review, created_at, updated_at
             )
-            VALUES (?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(activity_data.pet_id)
         .bind(activity_data.category.to_string())
         .bind(&activity_data.subcategory)
         .bind(activity_data_json)
+        .bind(activity_data.needs_review)
         .bind(now)
         .bind(now)
         .execute(&mut **tx)
@@ -164,7 +136,7 @@ impl super::PetDatabase {
                 message: format!("Failed to retrieve created activity: {e}"),
             })?;
 
-        self.row_to_activity(&row).await
+        self.read_activity_row(&row).await
     }
 
     /// Create a new activity (legacy method without side effects, kept for backward compatibility)
@@ -182,10 +154,10 @@ impl super::PetDatabase {
         let now = Utc::now();
 
         // Convert frontend blocks format to ActivityData HashMap
-        let typed_activity_data = activity_data.activity_data.map(|json_value| {
-            use super::activity_data::ActivityDataExt;
-            super::ActivityData::from_legacy_json(json_value)
-        });
+        let typed_activity_data = activity_data
+            .activity_data
+            .map(|value| activity_schema::prepare_for_storage(value, now))
+            .transpose()?;
 
         // Serialize ActivityData to JSON string for database storage
         let activity_data_json = typed_activity_data.as_ref().and_then(|data| {
@@ -200,18 +172,20 @@ impl super::PetDatabase {
         });
 
         // Insert the activity
+        let _turn = self.writes.acquire_for_task().await;
         let result = sqlx::query(
             r#"
             INSERT INTO activities (
-                pet_id, category, subcategory, activity_data, created_at, updated_at
+                pet_id, category, subcategory, activity_data, needs_review, created_at, updated_at
             )
-            VALUES (?, ?, ?, ?, ?, ?)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(activity_data.pet_id)
         .bind(activity_data.category.to_string())
         .bind(&activity_data.subcategory)
         .bind(activity_data_json)
+        .bind(activity_data.needs_review)
         .bind(now)
         .bind(now)
         .execute(&self.pool)
@@ -233,7 +207,7 @@ impl super::PetDatabase {
         self.get_activity_by_id(activity_id).await
     }
 
-    /// Update an existing activity
+    /// Update an existing activity and run the activity hooks
     pub async fn update_activity(
         &self,
         id: i64,
@@ -242,51 +216,66 @@ impl super::PetDatabase {
         let now = Utc::now();
 
         // Check if activity exists
-        let _ = self.get_activity_by_id(id).await?;
+        let before = self.get_activity_by_id(id).await?;
 
-        // Build dynamic update query
-        let mut updates = Vec::new();
+        // Convert frontend blocks format to ActivityData HashMap
+        let activity_data_json = activity_data
+            .activity_data
+            .map(|json_value| {
+                let typed_data =
+                    activity_schema::prepare_for_storage(json_value, before.created_at)?;
+                serde_json::to_string(&typed_data).map_err(|e| ActivityError::InvalidData {
+                    message: format!("Failed to serialize activity_data: {e}"),
+                })
+            })
+            .transpose()?;
 
-        if activity_data.subcategory.is_some() {
-            updates.push("subcategory = ?");
-        }
-        if activity_data.activity_data.is_some() {
-            updates.push("activity_data = ?");
+        let mut update = UpdateQuery::new("activities");
+        update
+            .set_if_some("subcategory", activity_data.subcategory)
+            .set_if_some("activity_data", activity_data_json);
+
+        if !update.has_changes() {
+            return Ok(before);
         }
 
-        if !updates.is_empty() {
-            let query_sql = format!(
-                "UPDATE activities SET {}, updated_at = ? WHERE id = ?",
-                updates.join(", ")
-            );
+        let _turn = self.writes.acquire_for_task().await;
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to begin transaction: {e}"))
+        })?;
 
-            let mut query = sqlx::query(&query_sql);
+        update.set("updated_at", now);
+        update
+            .where_id(id)
+            .build()
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("Database error: {e}"),
+            })?;
 
-            // Add bindings in the same order as updates
-            if let Some(subcategory) = activity_data.subcategory {
-                query = query.bind(subcategory);
-            }
-            if let Some(json_value) = activity_data.activity_data {
-                // Convert frontend blocks format to ActivityData HashMap
-                use super::activity_data::ActivityDataExt;
-                let typed_data = super::ActivityData::from_legacy_json(json_value);
-                let json_str =
-                    serde_json::to_string(&typed_data).map_err(|e| ActivityError::InvalidData {
-                        message: format!("Failed to serialize activity_data: {e}"),
-                    })?;
-                query = query.bind(json_str);
-            }
+        let row = sqlx::query("SELECT * FROM activities WHERE id = ?")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let after = self.read_activity_row(&row).await?;
+
+        self.hooks
+            .dispatch(
+                &mut tx,
+                ActivityEvent::Updated {
+                    before: &before,
+                    after: &after,
+                },
+            )
+            .await?;
 
-            query = query.bind(now).bind(id);
-            query
-                .execute(&self.pool)
-                .await
-                .map_err(|e| ActivityError::InvalidData {
-                    message: format!("Database error: {e}"),
-                })?;
-        }
+        tx.commit().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to commit transaction: {e}"))
+        })?;
 
-        self.get_activity_by_id(id).await
+        Ok(after)
     }
 
     /// Get an activity by ID
@@ -295,7 +284,7 @@ impl super::PetDatabase {
 
         let row = sqlx::query("SELECT * FROM activities WHERE id = ?")
             .bind(id)
-            .fetch_optional(&self.pool)
+            .fetch_optional(&self.read_pool)
             .await
             .map_err(|e| {
                 log::error!("[DB] get_activity_by_id: query failed for id={id}, error={e}");
@@ -324,43 +313,49 @@ impl super::PetDatabase {
         let limit = request.limit.unwrap_or(50).min(1000);
         let offset = request.offset.unwrap_or(0);
 
+        let sort_key = match request.sort_by.as_deref() {
+            Some(key) => key.parse::<ActivitySortKey>().map_err(|_| {
+                ActivityError::validation(
+                    "sort_by".to_string(),
+                    format!(
+                        "Unsupported sort key '{key}', expected one of: activity_time, created_at, updated_at, cost"
+                    ),
+                )
+            })?,
+            None => ActivitySortKey::default(),
+        };
+        let order_by = sort_key.order_by_clause(request.sort_desc.unwrap_or(true));
+
         log::debug!(
-            "[DB] get_activities: querying activities pet_id={:?}, limit={}, offset={}",
+            "[DB] get_activities: querying activities pet_id={:?}, limit={}, offset={}, order_by={}",
             request.pet_id,
             limit,
-            offset
+            offset,
+            order_by
         );
 
-        let query = if let Some(_pet_id) = request.pet_id {
-            "SELECT * FROM activities WHERE pet_id = ? ORDER BY created_at DESC LIMIT ? OFFSET ?"
-        } else {
-            "SELECT * FROM activities ORDER BY created_at DESC LIMIT ? OFFSET ?"
-        };
-
-        let rows = if let Some(pet_id) = request.pet_id {
-            sqlx::query(query)
-                .bind(pet_id)
-                .bind(limit)
-                .bind(offset)
-                .fetch_all(&self.pool)
-                .await
-        } else {
-            sqlx::query(query)
-                .bind(limit)
-                .bind(offset)
-                .fetch_all(&self.pool)
-                .await
-        }
-        .map_err(|e| {
-            log::error!(
-                "[DB] get_activities: query failed pet_id={:?}, error={}",
-                request.pet_id,
-                e
-            );
-            ActivityError::InvalidData {
-                message: format!("Database error: {e}"),
-            }
-        })?;
+        // Activities waiting for review are listed by get_activities_needing_review instead
+        let mut select = SelectQuery::new("SELECT * FROM activities");
+        select
+            .where_eq_opt("pet_id", request.pet_id)
+            .where_eq("needs_review", false)
+            .order_by(order_by)
+            .paginate(limit, offset);
+
+        let rows = select
+            .build()
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| {
+                log::error!(
+                    "[DB] get_activities: query failed pet_id={:?}, error={}",
+                    request.pet_id,
+                    e
+                );
+                ActivityError::InvalidData {
+                    message: format!("Database error: {e}"),
+                }
+            })?;
 
         log::debug!("[DB] get_activities: fetched {} raw rows", rows.len());
 
@@ -369,27 +364,19 @@ impl super::PetDatabase {
             activities.push(self.row_to_activity(&row).await?);
         }
 
-        // Simple count query
-        let total_count: i64 = if let Some(pet_id) = request.pet_id {
-            sqlx::query_scalar("SELECT COUNT(*) FROM activities WHERE pet_id = ?")
-                .bind(pet_id)
-                .fetch_one(&self.pool)
-                .await
-        } else {
-            sqlx::query_scalar("SELECT COUNT(*) FROM activities")
-                .fetch_one(&self.pool)
-                .await
-        }
-        .map_err(|e| {
-            log::error!(
-                "[DB] get_activities: count query failed pet_id={:?}, error={}",
-                request.pet_id,
-                e
-            );
-            ActivityError::InvalidData {
-                message: format!("Database error: {e}"),
-            }
-        })?;
+        let total_count = self
+            .count_activities(request.pet_id, request.exact_count)
+            .await
+            .map_err(|e| {
+                log::error!(
+                    "[DB] get_activities: count query failed pet_id={:?}, error={}",
+                    request.pet_id,
+                    e
+                );
+                ActivityError::InvalidData {
+                    message: format!("Database error: {e}"),
+                }
+            })?;
 
         let has_more = (offset + activities.len() as i64) < total_count;
 
@@ -407,40 +394,53 @@ impl super::PetDatabase {
         })
     }
 
+    /// Number of listed (reviewed) activities, for all pets or one. The count comes from
+    /// the trigger-maintained `activity_counts` table unless `exact` asks for a scan.
+    async fn count_activities(&self, pet_id: Option<i64>, exact: bool) -> sqlx::Result<i64> {
+        let mut count = if exact {
+            let mut count = SelectQuery::new("SELECT COUNT(*) FROM activities");
+            count.where_eq("needs_review", false);
+            count
+        } else {
+            SelectQuery::new("SELECT COALESCE(SUM(total - needs_review), 0) FROM activity_counts")
+        };
+        count.where_eq_opt("pet_id", pet_id);
+        count
+            .build()
+            .fetch_one(&self.read_pool)
+            .await
+            .and_then(|row| row.try_get(0))
+    }
+
     /// Search activities by text
     pub async fn search_activities(
         &self,
         request: SearchActivitiesRequest,
     ) -> Result<Vec<Activity>, ActivityError> {
         // Simple text search in activity_data JSON and subcategory
-        let query = if request.pet_id.is_some() {
-            "SELECT * FROM activities WHERE (activity_data LIKE ? OR subcategory LIKE ?) AND pet_id = ? ORDER BY created_at DESC LIMIT ?"
-        } else {
-            "SELECT * FROM activities WHERE (activity_data LIKE ? OR subcategory LIKE ?) ORDER BY created_at DESC LIMIT ?"
-        };
-
-        let search_term = format!("%{}%", request.query);
         let limit = request.limit.unwrap_or(50).min(1000);
-
-        let rows = if let Some(pet_id) = request.pet_id {
-            sqlx::query(query)
-                .bind(&search_term)
-                .bind(&search_term)
-                .bind(pet_id)
-                .bind(limit)
-                .fetch_all(&self.pool)
-                .await
-        } else {
-            sqlx::query(query)
-                .bind(&search_term)
-                .bind(&search_term)
-                .bind(limit)
-                .fetch_all(&self.pool)
-                .await
+        let privacy = self
+            .get_privacy_settings()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut select = SelectQuery::new("SELECT * FROM activities");
+        select
+            .where_contains_any(&["activity_data", "subcategory"], &request.query)
+            .where_eq_opt("pet_id", request.pet_id)
+            .where_eq("needs_review", false);
+        if !privacy.searches_private() {
+            select.where_eq("is_private", false);
         }
-        .map_err(|e| ActivityError::InvalidData {
-            message: format!("Database error: {e}"),
-        })?;
+        select.order_by("created_at DESC").limit(limit);
+
+        let rows = select
+            .build()
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("Database error: {e}"),
+            })?;
 
         let mut activities = Vec::new();
         for row in rows {
@@ -450,36 +450,60 @@ impl super::PetDatabase {
         Ok(activities)
     }
 
-    /// Delete an activity
-    pub async fn delete_activity(&self, id: i64) -> Result<(), ActivityError> {
+    /// Delete an activity and run the activity hooks, which journal its attachment files.
+    /// Returns the journal entries the caller should process after the commit.
+    pub async fn delete_activity(
+        &self,
+        id: i64,
+    ) -> Result<Vec<PendingFileDeletion>, ActivityError> {
         log::debug!("[DB] delete_activity: deleting activity id={id}");
 
-        // Check if activity exists
-        let activity = self.get_activity_by_id(id).await?;
-        log::debug!(
-            "[DB] delete_activity: confirmed activity exists id={}, pet_id={}",
-            id,
-            activity.pet_id
-        );
+        let _turn = self.writes.acquire_for_task().await;
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to begin transaction: {e}"))
+        })?;
 
-        let result = sqlx::query("DELETE FROM activities WHERE id = ?")
+        let row = sqlx::query("SELECT * FROM activities WHERE id = ?")
             .bind(id)
-            .execute(&self.pool)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let Some(row) = row else {
+            log::warn!("[DB] delete_activity: activity not found id={id}");
+            return Err(ActivityError::not_found(id));
+        };
+        let activity = self.read_activity_row(&row).await?;
+
+        sqlx::query("DELETE FROM activities WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
             .await
             .map_err(|e| {
                 log::error!("[DB] delete_activity: delete failed id={id}, error={e}");
-                ActivityError::InvalidData {
-                    message: format!("Database error: {e}"),
-                }
+                ActivityError::invalid_data(format!("Database error: {e}"))
             })?;
 
-        if result.rows_affected() == 0 {
-            log::warn!("[DB] delete_activity: no rows affected for id={id}");
-            return Err(ActivityError::NotFound { id });
-        }
+        // Hooks run in the same transaction as the row deletion,
+        // so attachment files are journaled before the row is gone for good
+        self.hooks
+            .dispatch(&mut tx, ActivityEvent::Deleted(&activity))
+            .await?;
+
+        tx.commit().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to commit transaction: {e}"))
+        })?;
 
-        log::debug!("[DB] delete_activity: successfully deleted activity id={id}");
-        Ok(())
+        let pending: Vec<_> = self
+            .get_pending_file_deletions()
+            .await?
+            .into_iter()
+            .filter(|entry| entry.source_activity_id == Some(id))
+            .collect();
+        log::debug!(
+            "[DB] delete_activity: successfully deleted activity id={id}, {} files pending",
+            pending.len()
+        );
+        Ok(pending)
     }
 
     /// Get activity statistics for a pet
@@ -496,13 +520,13 @@ impl super::PetDatabase {
             r#"
             SELECT category, COUNT(*) as count 
             FROM activities 
-            WHERE pet_id = ? AND created_at >= ?
+            WHERE pet_id = ? AND activity_time >= ?
             GROUP BY category
             "#,
         )
         .bind(pet_id)
         .bind(since_date.format("%Y-%m-%d %H:%M:%S").to_string())
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await
         .map_err(|e| ActivityError::InvalidData {
             message: format!("Database error: {e}"),
@@ -532,7 +556,7 @@ impl super::PetDatabase {
             "SELECT * FROM activities WHERE pet_id = ? ORDER BY created_at DESC LIMIT 10",
         )
         .bind(pet_id)
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await
         .map_err(|e| ActivityError::InvalidData {
             message: format!("Database error: {e}"),
@@ -561,16 +585,18 @@ impl super::PetDatabase {
 
         let rows = if let Some(pet_id) = pet_id {
             sqlx::query(
-                "SELECT * FROM activities WHERE pet_id = ? ORDER BY created_at DESC LIMIT ?",
+                "SELECT * FROM activities WHERE pet_id = ? AND needs_review = 0 ORDER BY created_at DESC LIMIT ?",
             )
             .bind(pet_id)
             .bind(limit)
-            .fetch_all(&self.pool)
+            .fetch_all(&self.read_pool)
             .await
         } else {
-            sqlx::query("SELECT * FROM activities ORDER BY created_at DESC LIMIT ?")
+            sqlx::query(
+                "SELECT * FROM activities WHERE needs_review = 0 ORDER BY created_at DESC LIMIT ?",
+            )
                 .bind(limit)
-                .fetch_all(&self.pool)
+                .fetch_all(&self.read_pool)
                 .await
         }
         .map_err(|e| ActivityError::InvalidData {
@@ -585,6 +611,85 @@ impl super::PetDatabase {
         Ok(activities)
     }
 
+    /// Machine-created activities waiting to be approved, oldest first
+    pub async fn get_activities_needing_review(
+        &self,
+        pet_id: Option<i64>,
+    ) -> Result<Vec<Activity>, ActivityError> {
+        let mut select = SelectQuery::new("SELECT * FROM activities");
+        select
+            .where_eq("needs_review", true)
+            .where_eq_opt("pet_id", pet_id)
+            .order_by("created_at ASC, id ASC");
+
+        let rows = select
+            .build()
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("Database error: {e}"),
+            })?;
+
+        let mut activities = Vec::new();
+        for row in rows {
+            activities.push(self.row_to_activity(&row).await?);
+        }
+
+        log::debug!(
+            "[DB] get_activities_needing_review: pet_id={pet_id:?}, found={}",
+            activities.len()
+        );
+        Ok(activities)
+    }
+
+    /// Confirm a machine-created activity so it shows up in the timeline
+    pub async fn approve_activity(&self, id: i64) -> Result<Activity, ActivityError> {
+        let result = sqlx::query("UPDATE activities SET needs_review = 0 WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("Database error: {e}"),
+            })?;
+        if result.rows_affected() == 0 {
+            return Err(ActivityError::NotFound { id });
+        }
+
+        log::info!("[DB] approve_activity: approved activity_id={id}");
+        self.get_activity_by_id(id).await
+    }
+
+    /// Mark an activity private, or shared again. Webhook deliveries still queued
+    /// for an activity made private are dropped. Not a content edit, so updated_at
+    /// stays as it was.
+    pub async fn set_activity_private(
+        &self,
+        id: i64,
+        is_private: bool,
+    ) -> Result<Activity, ActivityError> {
+        let result = sqlx::query("UPDATE activities SET is_private = ? WHERE id = ?")
+            .bind(is_private)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("Database error: {e}"),
+            })?;
+        if result.rows_affected() == 0 {
+            return Err(ActivityError::NotFound { id });
+        }
+        if is_private {
+            sqlx::query("DELETE FROM webhook_deliveries WHERE activity_id = ?")
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+
+        log::info!("[DB] set_activity_private: activity_id={id}, is_private={is_private}");
+        self.get_activity_by_id(id).await
+    }
+
     /// Get activities by category for a specific pet
     pub async fn get_activities_by_category(
         &self,
@@ -600,7 +705,7 @@ impl super::PetDatabase {
         .bind(pet_id)
         .bind(category.to_string())
         .bind(limit)
-        .fetch_all(&self.pool)
+        .fetch_all(&self.read_pool)
         .await
         .map_err(|e| ActivityError::InvalidData {
             message: format!("Database error: {e}"),
@@ -614,6 +719,28 @@ impl super::PetDatabase {
         Ok(activities)
     }
 
+    /// Get a pet's activities that happened on `date` (by time block, in UTC), earliest first
+    pub async fn get_activities_on_date(
+        &self,
+        pet_id: i64,
+        date: chrono::NaiveDate,
+    ) -> Result<Vec<Activity>, ActivityError> {
+        let rows = sqlx::query(
+            "SELECT * FROM activities WHERE pet_id = ? AND date(activity_time) = ? ORDER BY activity_time ASC, id ASC",
+        )
+        .bind(pet_id)
+        .bind(date.format("%Y-%m-%d").to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut activities = Vec::with_capacity(rows.len());
+        for row in rows {
+            activities.push(self.row_to_activity(&row).await?);
+        }
+        Ok(activities)
+    }
+
     /// Export activities for backup/migration
     pub async fn export_activities(
         &self,
@@ -622,11 +749,11 @@ impl super::PetDatabase {
         let rows = if let Some(pet_id) = request.pet_id {
             sqlx::query("SELECT * FROM activities WHERE pet_id = ? ORDER BY created_at ASC")
                 .bind(pet_id)
-                .fetch_all(&self.pool)
+                .fetch_all(&self.read_pool)
                 .await
         } else {
             sqlx::query("SELECT * FROM activities ORDER BY pet_id, created_at ASC")
-                .fetch_all(&self.pool)
+                .fetch_all(&self.read_pool)
                 .await
         }
         .map_err(|e| ActivityError::InvalidData {
@@ -641,8 +768,27 @@ impl super::PetDatabase {
         Ok(activities)
     }
 
-    /// Helper method to convert database row to Activity struct
-    async fn row_to_activity(
+    /// Helper method to convert database row to Activity struct.
+    /// With schema validation on, the row is also checked against the legacy read path.
+    pub(super) async fn row_to_activity(
+        &self,
+        row: &sqlx::sqlite::SqliteRow,
+    ) -> Result<Activity, ActivityError> {
+        let activity = self.read_activity_row(row).await?;
+        if self.schema_validation_enabled() {
+            self.validate_read_paths(
+                &activity,
+                stored_activity_data(row).as_ref(),
+                &GeneratedColumns::from_row(row),
+            )
+            .await;
+        }
+        Ok(activity)
+    }
+
+    /// Convert a database row to an Activity without schema validation. Used for rows
+    /// read inside a write transaction, where recording a discrepancy would wait on it.
+    pub(super) async fn read_activity_row(
         &self,
         row: &sqlx::sqlite::SqliteRow,
     ) -> Result<Activity, ActivityError> {
@@ -672,18 +818,10 @@ impl super::PetDatabase {
         // Parse activity_data with backward compatibility
         let activity_data_json: Option<String> = row.try_get("activity_data").ok();
         let activity_data = activity_data_json.and_then(|json_str| {
-            use super::activity_data::ActivityDataExt;
-            // Parse JSON string to Value first
+            // Payloads from older versions are upgraded to the current block format
             serde_json::from_str::<serde_json::Value>(&json_str)
                 .ok()
-                .map(|json_value| {
-                    // Try to parse as ActivityData HashMap, with legacy migration fallback
-                    serde_json::from_value::<super::ActivityData>(json_value.clone())
-                        .unwrap_or_else(|_| {
-                            log::debug!("[DB] Migrating legacy activity_data to HashMap format");
-                            super::ActivityData::from_legacy_json(json_value)
-                        })
-                })
+                .map(activity_schema::read_stored)
         });
 
         Ok(Activity {
@@ -702,8 +840,192 @@ impl super::PetDatabase {
                     message: format!("Invalid subcategory: {e}"),
                 })?,
             activity_data,
+            needs_review: row.try_get("needs_review").unwrap_or(false),
+            is_private: row.try_get("is_private").unwrap_or(false),
             created_at,
             updated_at,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    /// Matches of "mailman" in full-text and plain search
+    async fn found(db: &crate::database::PetDatabase) -> (usize, usize) {
+        let fts = db.fts_search_activities("mailman", None).await.unwrap();
+        let like = db
+            .search_activities(SearchActivitiesRequest {
+                pet_id: None,
+                query: "mailman".to_string(),
+                limit: None,
+            })
+            .await
+            .unwrap();
+        (fts.len(), like.len())
+    }
+
+    #[tokio::test]
+    async fn test_private_activities_can_be_hidden_from_search() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let incident = db
+            .create_activity(ActivityCreateRequest {
+                pet_id: summary.pet_ids[0],
+                category: ActivityCategory::Lifestyle,
+                subcategory: "Incident".to_string(),
+                activity_data: Some(serde_json::json!({ "notes": "Snapped at the mailman" })),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+        assert!(!incident.is_private);
+
+        let private = db.set_activity_private(incident.id, true).await.unwrap();
+        assert!(private.is_private);
+        assert_eq!(private.updated_at, incident.updated_at);
+        // Private activities stay searchable unless the settings hide them
+        assert_eq!(found(&db).await, (1, 1));
+        db.set_setting(
+            crate::privacy::PRIVACY_SETTINGS_KEY,
+            &crate::privacy::PrivacySettings {
+                hide_private_from_search: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(found(&db).await, (0, 0));
+
+        db.set_activity_private(incident.id, false).await.unwrap();
+        assert_eq!(found(&db).await, (1, 1));
+        assert!(db.set_activity_private(-1, true).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_activities_needing_review_stay_out_of_timeline() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let request = |needs_review| ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Lifestyle,
+            subcategory: "Walk".to_string(),
+            activity_data: None,
+            needs_review,
+        };
+        let confirmed = db
+            .create_activity_with_side_effects(request(false))
+            .await
+            .unwrap();
+        let imported = db
+            .create_activity_with_side_effects(request(true))
+            .await
+            .unwrap();
+        assert!(imported.needs_review);
+
+        let timeline = || GetActivitiesRequest {
+            pet_id: Some(pet_id),
+            ..Default::default()
+        };
+        let listed = db.get_activities(timeline()).await.unwrap();
+        assert_eq!(listed.total_count, 1);
+        assert_eq!(listed.activities[0].id, confirmed.id);
+
+        let queue = db
+            .get_activities_needing_review(Some(pet_id))
+            .await
+            .unwrap();
+        assert_eq!(
+            queue.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![imported.id]
+        );
+
+        assert!(!db.approve_activity(imported.id).await.unwrap().needs_review);
+        assert!(db
+            .get_activities_needing_review(None)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(db.get_activities(timeline()).await.unwrap().total_count, 2);
+        assert!(db.approve_activity(9999).await.is_err());
+    }
+
+    async fn assert_counts_agree(db: &crate::database::PetDatabase, pet_ids: &[i64]) {
+        let scopes = std::iter::once(None).chain(pet_ids.iter().copied().map(Some));
+        for pet_id in scopes {
+            let cached = db.count_activities(pet_id, false).await.unwrap();
+            let exact = db.count_activities(pet_id, true).await.unwrap();
+            assert_eq!(cached, exact, "pet_id={pet_id:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_counts_match_exact_counts() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 25,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (pet_id, other_pet_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+
+        assert_counts_agree(&db, &summary.pet_ids).await;
+        assert_eq!(db.count_activities(Some(pet_id), false).await.unwrap(), 25);
+
+        let pending = db
+            .create_activity(ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Diet,
+                subcategory: "Feeding".to_string(),
+                activity_data: None,
+                needs_review: true,
+            })
+            .await
+            .unwrap();
+        assert_counts_agree(&db, &summary.pet_ids).await;
+        db.approve_activity(pending.id).await.unwrap();
+        assert_counts_agree(&db, &summary.pet_ids).await;
+
+        db.update_activity(
+            pending.id,
+            ActivityUpdateRequest {
+                category: Some(ActivityCategory::Health),
+                subcategory: Some("Checkup".to_string()),
+                activity_data: None,
+            },
+        )
+        .await
+        .unwrap();
+        sqlx::query("UPDATE activities SET pet_id = ? WHERE id = ?")
+            .bind(other_pet_id)
+            .bind(summary.activity_ids[0])
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        db.delete_activity(summary.activity_ids[1]).await.unwrap();
+        assert_counts_agree(&db, &summary.pet_ids).await;
+        assert_eq!(db.count_activities(Some(pet_id), false).await.unwrap(), 24);
+
+        let response = db
+            .get_activities(GetActivitiesRequest {
+                pet_id: Some(other_pet_id),
+                exact_count: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.total_count, 26);
+    }
+}
@@ -0,0 +1,437 @@
+use super::activity_data::{distance_km, duration_minutes, ActivityData, BlockData};
+use crate::errors::ActivityError;
+use chrono::{DateTime, SecondsFormat, Utc};
+
+/// Units a portion block may use: weights, volumes, counts and servings
+const PORTION_UNITS: &[&str] = &[
+    "g", "kg", "mg", "oz", "lb", "lbs", "ml", "l", "cup", "cups", "tbsp", "tsp", "piece", "pieces",
+    "treat", "treats", "kibble", "scoop", "scoops", "tablet", "tablets", "pill", "pills",
+    "capsule", "capsules", "drop", "drops", "portion", "meal", "serving", "bowl", "can", "pouch",
+    "dose",
+];
+
+/// Units a duration block may use
+const DURATION_UNITS: &[&str] = &["min", "h", "s"];
+
+/// Units a distance block may use
+const DISTANCE_UNITS: &[&str] = &["km", "m", "mi", "yd", "ft"];
+
+/// Longest single exercise session accepted, in minutes
+pub const MAX_SESSION_MINUTES: f32 = 24.0 * 60.0;
+
+/// Longest single exercise session accepted, in kilometres
+pub const MAX_SESSION_KM: f32 = 200.0;
+
+/// What a litter block records
+pub const LITTER_TYPES: &[&str] = &["urine", "stool", "both"];
+
+/// Most eliminations one litter block may record, e.g. clumps found in one scoop
+pub const MAX_LITTER_COUNT: u32 = 20;
+
+/// Fecal consistency scores run from 1 (hard, dry) to 7 (watery)
+pub const LITTER_CONSISTENCY_SCORES: std::ops::RangeInclusive<u8> = 1..=7;
+
+/// Body condition scores run from 1 (emaciated) to 9 (obese)
+pub const BODY_CONDITION_SCORES: std::ops::RangeInclusive<u8> = 1..=9;
+
+/// Body condition scores of a pet at its ideal weight
+pub const IDEAL_BODY_CONDITION: std::ops::RangeInclusive<u8> = 4..=5;
+
+/// What a measurement block measures; also the block's key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeasurementKind {
+    Weight,
+    Height,
+    Temperature,
+}
+
+impl MeasurementKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MeasurementKind::Weight => "weight",
+            MeasurementKind::Height => "height",
+            MeasurementKind::Temperature => "temperature",
+        }
+    }
+
+    /// Units the measurement editor offers for this kind
+    pub fn units(self) -> &'static [&'static str] {
+        match self {
+            MeasurementKind::Weight => &["kg", "g", "lb", "lbs", "oz"],
+            MeasurementKind::Height => &["cm", "in", "m"],
+            MeasurementKind::Temperature => &["C", "F"],
+        }
+    }
+}
+
+/// Portion type the diet and health templates write for a subcategory
+pub fn portion_type_for(subcategory: &str) -> &'static str {
+    match subcategory {
+        "Treat" => "treat",
+        "Water" => "bowl",
+        _ => "meal",
+    }
+}
+
+/// Typed builder for an activity's block map.
+///
+/// Blocks come out in the shapes the frontend editor writes, so code that creates
+/// activities (quick log, importers, tests) doesn't hand-roll JSON. Units and
+/// amounts are checked as blocks are added; a rejected block leaves the builder unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct ActivityBlocksBuilder {
+    blocks: ActivityData,
+}
+
+impl ActivityBlocksBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time block at an instant, stored as a UTC ISO string like the editor's date picker
+    pub fn time(&mut self, at: DateTime<Utc>) -> &mut Self {
+        self.blocks.insert(
+            "time".to_string(),
+            BlockData::Time {
+                date: at.to_rfc3339_opts(SecondsFormat::Millis, true),
+                time: String::new(),
+                timezone: String::new(),
+            },
+        );
+        self
+    }
+
+    /// Title block
+    pub fn title(&mut self, title: &str) -> &mut Self {
+        self.text("title", title)
+    }
+
+    /// Notes block
+    pub fn notes(&mut self, notes: &str) -> &mut Self {
+        self.text("notes", notes)
+    }
+
+    fn text(&mut self, key: &str, text: &str) -> &mut Self {
+        self.blocks
+            .insert(key.to_string(), BlockData::Text(text.trim().to_string()));
+        self
+    }
+
+    /// Portion block, such as 80 g of kibble or 1 tablet
+    pub fn portion(
+        &mut self,
+        amount: f64,
+        unit: &str,
+        portion_type: &str,
+        brand: Option<&str>,
+    ) -> Result<&mut Self, ActivityError> {
+        check_amount("portion", amount)?;
+        let unit = check_unit("portion", unit, PORTION_UNITS)?;
+        self.blocks.insert(
+            "portion".to_string(),
+            BlockData::Portion {
+                amount: amount as f32,
+                unit,
+                portion_type: portion_type.to_string(),
+                brand: brand.map(str::to_string),
+                product: None,
+            },
+        );
+        Ok(self)
+    }
+
+    /// Measurement block under the kind's key, such as `weight`
+    pub fn measurement(
+        &mut self,
+        kind: MeasurementKind,
+        value: f64,
+        unit: &str,
+    ) -> Result<&mut Self, ActivityError> {
+        let key = kind.as_str();
+        // Temperatures may legitimately be zero or below
+        if kind != MeasurementKind::Temperature {
+            check_amount(key, value)?;
+        } else if !value.is_finite() {
+            return Err(ActivityError::validation(key, "Value must be a number"));
+        }
+        let unit = check_unit(key, unit, kind.units())?;
+        self.blocks.insert(
+            key.to_string(),
+            BlockData::Measurement {
+                value: value.to_string(),
+                unit,
+                measurement_type: key.to_string(),
+            },
+        );
+        Ok(self)
+    }
+
+    /// Weight measurement block
+    pub fn weight(&mut self, value: f64, unit: &str) -> Result<&mut Self, ActivityError> {
+        self.measurement(MeasurementKind::Weight, value, unit)
+    }
+
+    /// Timer block for a duration, rounded to whole minutes
+    pub fn duration_minutes(&mut self, minutes: f64) -> Result<&mut Self, ActivityError> {
+        check_amount("timer", minutes)?;
+        self.blocks.insert(
+            "timer".to_string(),
+            BlockData::Other(serde_json::json!({
+                "type": "duration",
+                "duration": minutes.round(),
+            })),
+        );
+        Ok(self)
+    }
+
+    /// Duration block for an exercise session, such as 45 min of walking
+    pub fn duration(
+        &mut self,
+        amount: f64,
+        unit: &str,
+        duration_type: &str,
+    ) -> Result<&mut Self, ActivityError> {
+        check_amount("duration", amount)?;
+        let unit = check_unit("duration", unit, DURATION_UNITS)?;
+        if duration_minutes(amount as f32, &unit).is_some_and(|min| min > MAX_SESSION_MINUTES) {
+            return Err(ActivityError::validation(
+                "duration",
+                "Duration cannot be longer than 24 hours",
+            ));
+        }
+        self.blocks.insert(
+            "duration".to_string(),
+            BlockData::Duration {
+                amount: amount as f32,
+                unit,
+                duration_type: duration_type.to_string(),
+            },
+        );
+        Ok(self)
+    }
+
+    /// Distance block for an exercise session, such as 2.5 km walked
+    pub fn distance(
+        &mut self,
+        amount: f64,
+        unit: &str,
+        distance_type: &str,
+    ) -> Result<&mut Self, ActivityError> {
+        check_amount("distance", amount)?;
+        let unit = check_unit("distance", unit, DISTANCE_UNITS)?;
+        if distance_km(amount as f32, &unit).is_some_and(|km| km > MAX_SESSION_KM) {
+            return Err(ActivityError::validation(
+                "distance",
+                &format!("Distance cannot be longer than {MAX_SESSION_KM} km"),
+            ));
+        }
+        self.blocks.insert(
+            "distance".to_string(),
+            BlockData::Distance {
+                amount: amount as f32,
+                unit,
+                distance_type: distance_type.to_string(),
+            },
+        );
+        Ok(self)
+    }
+
+    /// Litter box block, such as 3 urine clumps from one scoop or a stool with its
+    /// consistency score
+    pub fn litter(
+        &mut self,
+        litter_type: &str,
+        count: u32,
+        consistency: Option<u8>,
+    ) -> Result<&mut Self, ActivityError> {
+        let litter_type = check_litter("litter", litter_type, count, consistency)?;
+        self.blocks.insert(
+            "litter".to_string(),
+            BlockData::Litter {
+                litter_type,
+                count,
+                consistency,
+            },
+        );
+        Ok(self)
+    }
+
+    /// Body condition block with a 1-9 score
+    pub fn body_condition(&mut self, score: u8) -> Result<&mut Self, ActivityError> {
+        check_body_condition("bcs", score)?;
+        self.blocks
+            .insert("bcs".to_string(), BlockData::BodyCondition { score });
+        Ok(self)
+    }
+
+    /// Cost block; the currency is an ISO code such as `USD`
+    pub fn cost(&mut self, amount: f64, currency: &str) -> Result<&mut Self, ActivityError> {
+        check_amount("cost", amount)?;
+        let currency = currency.trim().to_uppercase();
+        if currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(ActivityError::validation(
+                "cost",
+                &format!("Unknown currency '{currency}'"),
+            ));
+        }
+        self.blocks.insert(
+            "cost".to_string(),
+            BlockData::Other(serde_json::json!({ "amount": amount, "currency": currency })),
+        );
+        Ok(self)
+    }
+
+    /// Whether a block with this key has been added
+    pub fn contains(&self, key: &str) -> bool {
+        self.blocks.contains_key(key)
+    }
+
+    pub fn build(self) -> ActivityData {
+        self.blocks
+    }
+
+    /// Block map as the JSON `ActivityCreateRequest::activity_data` carries
+    pub fn into_json(self) -> serde_json::Value {
+        // Go through text so f32 amounts keep their short decimal form (0.3, not 0.30000001)
+        serde_json::to_string(&self.blocks)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn check_amount(block: &str, amount: f64) -> Result<(), ActivityError> {
+    if amount.is_finite() && amount >= 0.0 {
+        Ok(())
+    } else {
+        Err(ActivityError::validation(
+            block,
+            "Amount must be a non-negative number",
+        ))
+    }
+}
+
+/// Check a litter block's fields, returning the lowercase litter type
+pub fn check_litter(
+    block: &str,
+    litter_type: &str,
+    count: u32,
+    consistency: Option<u8>,
+) -> Result<String, ActivityError> {
+    let litter_type = litter_type.trim().to_lowercase();
+    if !LITTER_TYPES.contains(&litter_type.as_str()) {
+        return Err(ActivityError::validation(
+            block,
+            &format!("Litter type must be one of: {}", LITTER_TYPES.join(", ")),
+        ));
+    }
+    if count == 0 || count > MAX_LITTER_COUNT {
+        return Err(ActivityError::validation(
+            block,
+            &format!("Count must be 1 to {MAX_LITTER_COUNT}"),
+        ));
+    }
+    if let Some(score) = consistency {
+        if litter_type == "urine" {
+            return Err(ActivityError::validation(
+                block,
+                "Consistency applies to stool only",
+            ));
+        }
+        if !LITTER_CONSISTENCY_SCORES.contains(&score) {
+            return Err(ActivityError::validation(
+                block,
+                "Consistency must be a score from 1 (hard) to 7 (watery)",
+            ));
+        }
+    }
+    Ok(litter_type)
+}
+
+/// Check a body condition score is on the 1-9 scale
+pub fn check_body_condition(block: &str, score: u8) -> Result<(), ActivityError> {
+    if BODY_CONDITION_SCORES.contains(&score) {
+        Ok(())
+    } else {
+        Err(ActivityError::validation(
+            block,
+            "Body condition must be a score from 1 (emaciated) to 9 (obese)",
+        ))
+    }
+}
+
+/// The allowed spelling of `unit`, matched ignoring case
+fn check_unit(block: &str, unit: &str, allowed: &[&str]) -> Result<String, ActivityError> {
+    let unit = unit.trim();
+    allowed
+        .iter()
+        .find(|allowed| allowed.eq_ignore_ascii_case(unit))
+        .map(|allowed| allowed.to_string())
+        .ok_or_else(|| ActivityError::validation(block, &format!("Unsupported unit '{unit}'")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_writes_editor_shapes() {
+        let at = DateTime::parse_from_rfc3339("2025-06-10T08:00:00+02:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(at).title("Breakfast").notes(" Ate well ");
+        blocks
+            .portion(0.3, "Cups", portion_type_for("Feeding"), Some("Orijen"))
+            .unwrap();
+        blocks.weight(4.2, "kg").unwrap();
+        blocks.cost(12.5, "eur").unwrap();
+
+        assert_eq!(
+            blocks.into_json(),
+            serde_json::json!({
+                "time": { "date": "2025-06-10T06:00:00.000Z", "time": "", "timezone": "" },
+                "title": "Breakfast",
+                "notes": "Ate well",
+                "portion": {
+                    "amount": 0.3,
+                    "unit": "cups",
+                    "portionType": "meal",
+                    "brand": "Orijen",
+                    "product": null,
+                },
+                "weight": { "value": "4.2", "unit": "kg", "measurementType": "weight" },
+                "cost": { "amount": 12.5, "currency": "EUR" },
+            })
+        );
+    }
+
+    #[test]
+    fn test_builder_rejects_bad_units_and_amounts() {
+        let mut blocks = ActivityBlocksBuilder::new();
+        assert!(blocks.weight(4.0, "cups").is_err());
+        assert!(blocks.portion(-1.0, "g", "meal", None).is_err());
+        assert!(blocks.portion(f64::NAN, "g", "meal", None).is_err());
+        assert!(blocks.cost(10.0, "dollars").is_err());
+        assert!(blocks.duration(25.0, "h", "walk").is_err());
+        assert!(blocks.distance(3.0, "cups", "walk").is_err());
+        assert!(blocks.distance(3.0, "MI", "run").is_ok());
+        assert!(blocks.litter("urine", 2, Some(4)).is_err());
+        assert!(blocks.litter("stool", 1, Some(8)).is_err());
+        assert!(blocks.litter("Stool", 1, Some(6)).is_ok());
+        assert!(blocks.body_condition(0).is_err());
+        assert!(blocks.body_condition(10).is_err());
+        assert!(blocks.body_condition(5).is_ok());
+        assert!(blocks
+            .measurement(MeasurementKind::Temperature, -2.0, "c")
+            .is_ok());
+        // Rejected blocks are not added
+        assert!(!blocks.contains("weight"));
+        assert!(!blocks.contains("portion"));
+        assert!(!blocks.contains("duration"));
+        assert!(blocks.contains("distance"));
+        assert!(blocks.contains("litter"));
+        assert!(blocks.contains("bcs"));
+        assert!(blocks.contains("temperature"));
+    }
+}
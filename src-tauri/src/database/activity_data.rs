@@ -33,6 +33,12 @@ where
 /// - Time: requires "date" field (unique identifier)
 /// - Portion: requires "portionType" field (unique identifier)
 /// - Measurement: requires "measurementType" field (unique identifier)
+/// - Hydration: requires "hydrationType" field (unique identifier)
+/// - Duration: requires "durationType" field (unique identifier)
+/// - Distance: requires "distanceType" field (unique identifier)
+/// - Litter: requires "litterType" field (unique identifier)
+/// - BodyCondition: requires "bodyConditionScore" field (unique identifier)
+/// - Custom: requires "customBlock" field (unique identifier)
 /// - Text: fallback for simple strings
 /// - Other: fallback for any JSON value
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -71,6 +77,62 @@ pub enum BlockData {
         measurement_type: String,
     },
 
+    /// Hydration block: { amount: 120, unit: "ml", hydrationType: "bowl" }
+    /// Uniquely identified by "hydrationType" field (bowl, fountain, wet_food, syringe...)
+    Hydration {
+        amount: f32,
+        unit: String,
+        #[serde(rename = "hydrationType")]
+        hydration_type: String,
+    },
+
+    /// Duration block: { amount: 45, unit: "min", durationType: "walk" }
+    /// Uniquely identified by "durationType" field (walk, run, play, swim...)
+    Duration {
+        amount: f32,
+        unit: String,
+        #[serde(rename = "durationType")]
+        duration_type: String,
+    },
+
+    /// Distance block: { amount: 2.5, unit: "km", distanceType: "walk" }
+    /// Uniquely identified by "distanceType" field (walk, run, hike, swim...)
+    Distance {
+        amount: f32,
+        unit: String,
+        #[serde(rename = "distanceType")]
+        distance_type: String,
+    },
+
+    /// Litter box visit: { litterType: "stool", count: 1, consistency: 4 }
+    /// Uniquely identified by "litterType" field (urine, stool, both).
+    /// Consistency is a 1 (hard) to 7 (watery) fecal score, for stool only.
+    Litter {
+        #[serde(rename = "litterType")]
+        litter_type: String,
+        #[serde(default = "default_litter_count")]
+        count: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        consistency: Option<u8>,
+    },
+
+    /// Body condition score: { bodyConditionScore: 5 }
+    /// Uniquely identified by "bodyConditionScore" field, on the 1 (emaciated) to
+    /// 9 (obese) scale where 4-5 is ideal
+    BodyCondition {
+        #[serde(rename = "bodyConditionScore")]
+        score: u8,
+    },
+
+    /// Instance of a user-defined block: { customBlock: "insulin", values: { units: 4 } }
+    /// Uniquely identified by "customBlock" field, the name of its definition
+    Custom {
+        #[serde(rename = "customBlock")]
+        custom_block: String,
+        #[serde(default)]
+        values: serde_json::Map<String, serde_json::Value>,
+    },
+
     /// Notes or Title block: simple string
     /// Matches any string value
     Text(String),
@@ -80,6 +142,10 @@ pub enum BlockData {
     Other(serde_json::Value),
 }
 
+fn default_litter_count() -> u32 {
+    1
+}
+
 /// Activity data structure - a map of block type to block data
 /// Frontend sends: { "time": {...}, "notes": "...", "portion": {...} }
 /// This matches the frontend blocks structure exactly
@@ -93,6 +159,36 @@ pub trait ActivityDataExt {
     /// Extract weight value in kg for pet profile updates
     fn extract_weight_kg(&self) -> Option<f32>;
 
+    /// Extract the height in cm from the height measurement block
+    fn extract_height_cm(&self) -> Option<f32>;
+
+    /// Extract the body temperature in °C from the temperature measurement block
+    fn extract_temperature_c(&self) -> Option<f32>;
+
+    /// Extract the calendar date from the time block, if present
+    fn extract_activity_date(&self) -> Option<chrono::NaiveDate>;
+
+    /// Stored photo filenames referenced by attachment blocks
+    fn attachment_files(&self) -> Vec<String>;
+
+    /// Water taken in, in millilitres, from the hydration block
+    fn extract_water_ml(&self) -> Option<f32>;
+
+    /// Exercise time in minutes, from the duration block or else a timer block
+    fn extract_duration_minutes(&self) -> Option<f32>;
+
+    /// Distance covered in kilometres, from the distance block
+    fn extract_distance_km(&self) -> Option<f32>;
+
+    /// Body condition score (1-9) from the body condition block, whatever its key
+    fn extract_body_condition_score(&self) -> Option<u8>;
+
+    /// Start and end of an activity that spans time: a timer's start and end,
+    /// or the time block's moment plus the duration. None for point-in-time activities.
+    fn extract_time_range(
+        &self,
+    ) -> Option<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)>;
+
     /// Convert to frontend-compatible format (passthrough for HashMap)
     fn to_frontend_blocks(&self) -> serde_json::Value;
 
@@ -117,6 +213,7 @@ impl ActivityDataExt for ActivityData {
                 "kg" => Some(parsed_value),
                 "g" => Some(parsed_value / 1000.0),
                 "lb" | "lbs" => Some(parsed_value * 0.453592),
+                "oz" => Some(parsed_value * 0.0283495),
                 _ => Some(parsed_value), // Assume kg if unknown
             }
         } else {
@@ -124,6 +221,129 @@ impl ActivityDataExt for ActivityData {
         }
     }
 
+    fn extract_height_cm(&self) -> Option<f32> {
+        if let Some(BlockData::Measurement { value, unit, .. }) = self.get("height") {
+            let parsed_value = value.parse::<f32>().ok()?;
+            match unit.trim().to_lowercase().as_str() {
+                "cm" | "" => Some(parsed_value),
+                "m" => Some(parsed_value * 100.0),
+                "in" => Some(parsed_value * 2.54),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    fn extract_temperature_c(&self) -> Option<f32> {
+        if let Some(BlockData::Measurement { value, unit, .. }) = self.get("temperature") {
+            let parsed_value = value.parse::<f32>().ok()?;
+            match unit.trim().trim_start_matches('°').to_lowercase().as_str() {
+                "c" | "" => Some(parsed_value),
+                "f" => Some((parsed_value - 32.0) * 5.0 / 9.0),
+                _ => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    fn extract_activity_date(&self) -> Option<chrono::NaiveDate> {
+        if let Some(BlockData::Time { date, .. }) = self.get("time") {
+            // Frontend sends ISO strings, but accept plain dates as well
+            chrono::DateTime::parse_from_rfc3339(date)
+                .map(|dt| dt.date_naive())
+                .or_else(|_| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d"))
+                .ok()
+        } else {
+            None
+        }
+    }
+
+    fn attachment_files(&self) -> Vec<String> {
+        let mut files: Vec<String> = self
+            .values()
+            .filter_map(|block| match block {
+                BlockData::Other(serde_json::Value::Array(items)) => Some(items),
+                _ => None,
+            })
+            .flatten()
+            .filter_map(|item| item.as_object())
+            .flat_map(|item| {
+                ["url", "path", "filename", "thumbnailPath"]
+                    .into_iter()
+                    .filter_map(|key| item.get(key)?.as_str())
+                    .filter_map(stored_photo_filename)
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    fn extract_water_ml(&self) -> Option<f32> {
+        match self.get("hydration") {
+            Some(BlockData::Hydration { amount, unit, .. }) => water_ml(*amount, unit),
+            _ => None,
+        }
+    }
+
+    fn extract_duration_minutes(&self) -> Option<f32> {
+        match self.get("duration") {
+            Some(BlockData::Duration { amount, unit, .. }) => duration_minutes(*amount, unit),
+            // Timer blocks store whole minutes: { type: "duration", duration: 30 }
+            _ => match self.get("timer") {
+                Some(BlockData::Other(timer)) => timer
+                    .get("duration")
+                    .and_then(|minutes| minutes.as_f64())
+                    .map(|minutes| minutes as f32),
+                _ => None,
+            },
+        }
+    }
+
+    fn extract_distance_km(&self) -> Option<f32> {
+        match self.get("distance") {
+            Some(BlockData::Distance { amount, unit, .. }) => distance_km(*amount, unit),
+            _ => None,
+        }
+    }
+
+    fn extract_body_condition_score(&self) -> Option<u8> {
+        self.values().find_map(|block| match block {
+            BlockData::BodyCondition { score } => Some(*score),
+            _ => None,
+        })
+    }
+
+    fn extract_time_range(
+        &self,
+    ) -> Option<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>)> {
+        let parse = |value: &str| {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|dt| dt.with_timezone(&chrono::Utc))
+        };
+        // Timer blocks: { type: "start_end", startTime: "...", endTime: "..." }
+        let timer_time = |key: &str| match self.get("timer") {
+            Some(BlockData::Other(timer)) => {
+                timer.get(key).and_then(|v| v.as_str()).and_then(parse)
+            }
+            _ => None,
+        };
+
+        let start = timer_time("startTime").or_else(|| match self.get("time") {
+            Some(BlockData::Time { date, .. }) => parse(date),
+            _ => None,
+        })?;
+        let end = timer_time("endTime").or_else(|| {
+            let seconds = (self.extract_duration_minutes()? * 60.0).round() as i64;
+            Some(start + chrono::Duration::seconds(seconds))
+        })?;
+        (end > start).then_some((start, end))
+    }
+
     fn to_frontend_blocks(&self) -> serde_json::Value {
         // ActivityData is already in frontend format (HashMap<String, BlockData>)
         // Just serialize it directly
@@ -142,10 +362,202 @@ impl ActivityDataExt for ActivityData {
     }
 }
 
+/// Convert a water amount to millilitres; None for units that aren't volumes
+pub fn water_ml(amount: f32, unit: &str) -> Option<f32> {
+    let factor = match unit.trim().to_lowercase().as_str() {
+        "ml" | "" => 1.0,
+        "l" => 1000.0,
+        "cl" => 10.0,
+        "oz" | "fl oz" => 29.5735,
+        "cup" | "cups" => 236.588,
+        _ => return None,
+    };
+    Some(amount * factor)
+}
+
+/// Convert a duration to minutes; None for units that aren't durations
+pub fn duration_minutes(amount: f32, unit: &str) -> Option<f32> {
+    let factor = match unit.trim().to_lowercase().as_str() {
+        "min" | "mins" | "minute" | "minutes" | "" => 1.0,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60.0,
+        "s" | "sec" | "secs" | "second" | "seconds" => 1.0 / 60.0,
+        _ => return None,
+    };
+    Some(amount * factor)
+}
+
+/// Convert a distance to kilometres; None for units that aren't lengths
+pub fn distance_km(amount: f32, unit: &str) -> Option<f32> {
+    let factor = match unit.trim().to_lowercase().as_str() {
+        "km" => 1.0,
+        "m" => 0.001,
+        "mi" | "mile" | "miles" => 1.609344,
+        "yd" | "yds" => 0.0009144,
+        "ft" => 0.0003048,
+        _ => return None,
+    };
+    Some(amount * factor)
+}
+
+/// Filename inside the photo store for an attachment reference such as
+/// `photos://localhost/<file>` or a bare stored filename
+fn stored_photo_filename(reference: &str) -> Option<String> {
+    let name = reference
+        .strip_prefix("photos://localhost/")
+        .unwrap_or(reference);
+    let is_plain_file = !name.is_empty()
+        && !name.contains(['/', '\\', ':'])
+        && !name.contains("..")
+        && name.contains('.');
+    is_plain_file.then(|| name.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_attachment_files() {
+        let json = serde_json::json!({
+            "notes": "Vet visit",
+            "attachments": [
+                {
+                    "id": "a1",
+                    "name": "xray.png",
+                    "url": "photos://localhost/3f2c.png",
+                    "thumbnail": "data:image/png;base64,AAAA"
+                },
+                { "id": "a2", "filename": "9b1d.jpg", "path": "9b1d.jpg" },
+                { "id": "a3", "url": "https://example.com/remote.jpg" },
+                { "id": "a4", "path": "../../etc/passwd" }
+            ],
+            "people": ["Dr. Smith"]
+        });
+        let activity_data: ActivityData = serde_json::from_value(json).unwrap();
+
+        assert_eq!(
+            activity_data.attachment_files(),
+            vec!["3f2c.png", "9b1d.jpg"]
+        );
+    }
+
+    #[test]
+    fn test_hydration_block() {
+        let json = serde_json::json!({
+            "hydration": { "amount": 0.25, "unit": "L", "hydrationType": "fountain" },
+            "portion": { "amount": 50, "unit": "g", "portionType": "wet_food" }
+        });
+        let activity_data: ActivityData = serde_json::from_value(json).unwrap();
+
+        assert!(matches!(
+            activity_data.get("hydration"),
+            Some(BlockData::Hydration { hydration_type, .. }) if hydration_type == "fountain"
+        ));
+        assert_eq!(activity_data.extract_water_ml(), Some(250.0));
+        assert_eq!(water_ml(2.0, "tbsp"), None);
+    }
+
+    #[test]
+    fn test_exercise_blocks() {
+        let json = serde_json::json!({
+            "duration": { "amount": 1.5, "unit": "h", "durationType": "walk" },
+            "distance": { "amount": 2, "unit": "mi", "distanceType": "walk" },
+            "timer": { "type": "duration", "duration": 20 }
+        });
+        let activity_data: ActivityData = serde_json::from_value(json).unwrap();
+
+        assert!(matches!(
+            activity_data.get("distance"),
+            Some(BlockData::Distance { distance_type, .. }) if distance_type == "walk"
+        ));
+        // The duration block wins over the timer
+        assert_eq!(activity_data.extract_duration_minutes(), Some(90.0));
+        assert!((activity_data.extract_distance_km().unwrap() - 3.2187).abs() < 0.001);
+        assert_eq!(distance_km(1.0, "cups"), None);
+
+        let timer_only: ActivityData = serde_json::from_value(serde_json::json!({
+            "timer": { "type": "duration", "duration": 20 }
+        }))
+        .unwrap();
+        assert_eq!(timer_only.extract_duration_minutes(), Some(20.0));
+        assert_eq!(timer_only.extract_distance_km(), None);
+        assert_eq!(timer_only.extract_time_range(), None);
+    }
+
+    #[test]
+    fn test_body_condition_block() {
+        let activity_data: ActivityData = serde_json::from_value(serde_json::json!({
+            "bcs": { "bodyConditionScore": 6 },
+            "notes": "A little round"
+        }))
+        .unwrap();
+        assert_eq!(
+            activity_data.get("bcs"),
+            Some(&BlockData::BodyCondition { score: 6 })
+        );
+        assert_eq!(activity_data.extract_body_condition_score(), Some(6));
+    }
+
+    #[test]
+    fn test_litter_block() {
+        let activity_data: ActivityData = serde_json::from_value(serde_json::json!({
+            "litter": { "litterType": "stool", "consistency": 5 },
+            "scoop": { "litterType": "urine", "count": 3 }
+        }))
+        .unwrap();
+
+        assert_eq!(
+            activity_data.get("litter"),
+            Some(&BlockData::Litter {
+                litter_type: "stool".to_string(),
+                count: 1,
+                consistency: Some(5),
+            })
+        );
+        assert!(matches!(
+            activity_data.get("scoop"),
+            Some(BlockData::Litter {
+                count: 3,
+                consistency: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_time_range() {
+        let at = |s: &str| chrono::DateTime::parse_from_rfc3339(s).unwrap().to_utc();
+        let timed: ActivityData = serde_json::from_value(serde_json::json!({
+            "time": { "date": "2025-03-01T09:00:00.000Z", "time": "", "timezone": "" },
+            "duration": { "amount": 2, "unit": "h", "durationType": "visit" }
+        }))
+        .unwrap();
+        assert_eq!(
+            timed.extract_time_range(),
+            Some((at("2025-03-01T09:00:00Z"), at("2025-03-01T11:00:00Z")))
+        );
+
+        let start_end: ActivityData = serde_json::from_value(serde_json::json!({
+            "time": { "date": "2025-03-01T09:00:00.000Z", "time": "", "timezone": "" },
+            "timer": {
+                "type": "start_end",
+                "startTime": "2025-03-02T08:00:00.000Z",
+                "endTime": "2025-03-05T18:00:00.000Z"
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            start_end.extract_time_range(),
+            Some((at("2025-03-02T08:00:00Z"), at("2025-03-05T18:00:00Z")))
+        );
+
+        let point_in_time: ActivityData = serde_json::from_value(serde_json::json!({
+            "time": { "date": "2025-03-01T09:00:00.000Z", "time": "", "timezone": "" }
+        }))
+        .unwrap();
+        assert_eq!(point_in_time.extract_time_range(), None);
+    }
+
     #[test]
     fn test_feeding_activity_deserialization() {
         let json = serde_json::json!({
@@ -0,0 +1,283 @@
+use super::models::*;
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+impl super::PetDatabase {
+    /// Link an expense to the health activity it paid for; the IDs may be given in
+    /// either order. Linking an already linked pair returns the existing link.
+    pub async fn link_activities(
+        &self,
+        activity_id: i64,
+        related_activity_id: i64,
+    ) -> Result<ActivityLink, ActivityError> {
+        if activity_id == related_activity_id {
+            return Err(ActivityError::validation(
+                "related_activity_id",
+                "An activity cannot be linked to itself",
+            ));
+        }
+
+        let first = self.get_activity_by_id(activity_id).await?;
+        let second = self.get_activity_by_id(related_activity_id).await?;
+        let (expense, health) = match (first.category, second.category) {
+            (ActivityCategory::Expense, ActivityCategory::Health) => (first, second),
+            (ActivityCategory::Health, ActivityCategory::Expense) => (second, first),
+            _ => {
+                return Err(ActivityError::validation(
+                    "related_activity_id",
+                    "Only an expense and a health activity can be linked",
+                ))
+            }
+        };
+        if expense.pet_id != health.pet_id {
+            return Err(ActivityError::pet_mismatch(expense.pet_id, health.id));
+        }
+
+        sqlx::query(
+            "INSERT OR IGNORE INTO activity_links (activity_id, related_activity_id, created_at) VALUES (?, ?, ?)",
+        )
+        .bind(expense.id)
+        .bind(health.id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let row = sqlx::query(
+            "SELECT * FROM activity_links WHERE activity_id = ? AND related_activity_id = ?",
+        )
+        .bind(expense.id)
+        .bind(health.id)
+        .fetch_one(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::debug!(
+            "[DB] link_activities: expense id={} -> health id={}",
+            expense.id,
+            health.id
+        );
+        Ok(row_to_activity_link(&row))
+    }
+
+    /// Remove the link between two activities, given in either order.
+    /// Returns false if they weren't linked.
+    pub async fn unlink_activities(
+        &self,
+        activity_id: i64,
+        related_activity_id: i64,
+    ) -> Result<bool, ActivityError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM activity_links
+            WHERE (activity_id = ? AND related_activity_id = ?)
+               OR (activity_id = ? AND related_activity_id = ?)
+            "#,
+        )
+        .bind(activity_id)
+        .bind(related_activity_id)
+        .bind(related_activity_id)
+        .bind(activity_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::debug!(
+            "[DB] unlink_activities: {activity_id} <-> {related_activity_id}, removed={}",
+            result.rows_affected()
+        );
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Activities linked to an activity in either direction, oldest first
+    pub async fn get_related_activities(
+        &self,
+        activity_id: i64,
+    ) -> Result<Vec<Activity>, ActivityError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id
+            FROM activity_links l
+            JOIN activities a ON a.id = CASE
+                WHEN l.activity_id = ? THEN l.related_activity_id
+                ELSE l.activity_id
+            END
+            WHERE l.activity_id = ? OR l.related_activity_id = ?
+            ORDER BY a.activity_time ASC, a.id ASC
+            "#,
+        )
+        .bind(activity_id)
+        .bind(activity_id)
+        .bind(activity_id)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut related = Vec::with_capacity(rows.len());
+        for row in rows {
+            related.push(self.get_activity_by_id(row.get("id")).await?);
+        }
+        Ok(related)
+    }
+
+    /// An activity together with the expenses or health activities linked to it
+    pub async fn get_activity_with_related(
+        &self,
+        activity_id: i64,
+    ) -> Result<ActivityWithRelated, ActivityError> {
+        let activity = self.get_activity_by_id(activity_id).await?;
+        let related = self.get_related_activities(activity_id).await?;
+
+        Ok(ActivityWithRelated {
+            activity: activity.into(),
+            related: related.into_iter().map(ActivityResponse::from).collect(),
+        })
+    }
+
+    /// All expense/health links of a pet, so a timeline can mark linked entries
+    pub async fn get_pet_activity_links(
+        &self,
+        pet_id: i64,
+    ) -> Result<Vec<ActivityLink>, ActivityError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT l.*
+            FROM activity_links l
+            JOIN activities a ON a.id = l.activity_id
+            WHERE a.pet_id = ?
+            ORDER BY l.created_at ASC
+            "#,
+        )
+        .bind(pet_id)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows.iter().map(row_to_activity_link).collect())
+    }
+}
+
+fn row_to_activity_link(row: &sqlx::sqlite::SqliteRow) -> ActivityLink {
+    ActivityLink {
+        activity_id: row.get("activity_id"),
+        related_activity_id: row.get("related_activity_id"),
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::*;
+
+    fn activity_request(
+        pet_id: i64,
+        category: ActivityCategory,
+        subcategory: &str,
+    ) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: None,
+            needs_review: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_expense_and_health_activity() {
+        let (db, _dir) = test_database().await;
+        let mut pet_ids = Vec::new();
+        for name in ["Biscuit", "Mochi"] {
+            let pet = db
+                .create_pet(CreatePetRequest {
+                    name: name.to_string(),
+                    birth_date: chrono::NaiveDate::from_ymd_opt(2021, 6, 1).unwrap(),
+                    species: PetSpecies::Dog,
+                    gender: PetGender::Male,
+                    breed: None,
+                    color: None,
+                    weight_kg: None,
+                    photo_path: None,
+                    notes: None,
+                })
+                .await
+                .unwrap();
+            pet_ids.push(pet.id);
+        }
+
+        let checkup = db
+            .create_activity_with_side_effects(activity_request(
+                pet_ids[0],
+                ActivityCategory::Health,
+                "Checkup",
+            ))
+            .await
+            .unwrap();
+        let bill = db
+            .create_activity_with_side_effects(activity_request(
+                pet_ids[0],
+                ActivityCategory::Expense,
+                "Veterinary",
+            ))
+            .await
+            .unwrap();
+        let walk = db
+            .create_activity_with_side_effects(activity_request(
+                pet_ids[0],
+                ActivityCategory::Lifestyle,
+                "Walk",
+            ))
+            .await
+            .unwrap();
+        let other_pet_bill = db
+            .create_activity_with_side_effects(activity_request(
+                pet_ids[1],
+                ActivityCategory::Expense,
+                "Veterinary",
+            ))
+            .await
+            .unwrap();
+
+        // Stored with the expense first, whatever the argument order
+        let link = db.link_activities(checkup.id, bill.id).await.unwrap();
+        assert_eq!(link.activity_id, bill.id);
+        assert_eq!(link.related_activity_id, checkup.id);
+        assert_eq!(db.link_activities(bill.id, checkup.id).await.unwrap(), link);
+
+        assert!(db.link_activities(bill.id, walk.id).await.is_err());
+        assert!(db.link_activities(bill.id, bill.id).await.is_err());
+        assert!(db
+            .link_activities(other_pet_bill.id, checkup.id)
+            .await
+            .is_err());
+
+        // Readable from both sides
+        let from_health = db.get_activity_with_related(checkup.id).await.unwrap();
+        assert_eq!(from_health.activity.id, checkup.id);
+        assert_eq!(
+            from_health.related.iter().map(|a| a.id).collect::<Vec<_>>(),
+            vec![bill.id]
+        );
+        let from_expense = db.get_related_activities(bill.id).await.unwrap();
+        assert_eq!(from_expense[0].id, checkup.id);
+        assert_eq!(
+            db.get_pet_activity_links(pet_ids[0]).await.unwrap().len(),
+            1
+        );
+        assert!(db
+            .get_pet_activity_links(pet_ids[1])
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert!(db.unlink_activities(checkup.id, bill.id).await.unwrap());
+        assert!(!db.unlink_activities(checkup.id, bill.id).await.unwrap());
+
+        // Deleting either side removes the link
+        db.link_activities(bill.id, checkup.id).await.unwrap();
+        db.delete_activity(checkup.id).await.unwrap();
+        assert!(db.get_related_activities(bill.id).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,367 @@
+//! Versioning of stored `activity_data` payloads.
+//!
+//! Every payload written carries a `schema_version` entry. Payloads written by an
+//! older version are upgraded one step at a time by the functions in [`MIGRATIONS`]
+//! when they are read or written, so readers only deal with the current block format.
+//! A block format change adds a migration and bumps [`CURRENT_SCHEMA_VERSION`].
+
+use super::activity_data::{ActivityData, ActivityDataExt, BlockData};
+use super::activity_time::{normalize_time_block, TimeNormalization};
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sqlx::Row;
+
+/// Key of the version entry in a stored payload
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Version written with new payloads. Payloads without a version are version 0.
+pub const CURRENT_SCHEMA_VERSION: u64 = 2;
+
+/// Upgrade step from one version to the next
+type Migration = fn(&mut Map<String, Value>);
+
+/// Entry `n` upgrades a version `n` payload to version `n + 1`
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] =
+    [v0_plain_dates_and_units, v1_canonical_times];
+
+/// Outcome of upgrading all stored payloads
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+pub struct ActivityDataMigrationReport {
+    pub current_version: u64,
+    /// Payloads found below the current version
+    pub scanned: usize,
+    pub upgraded: usize,
+    /// Payloads whose blank time block was filled from the creation time
+    pub times_filled: usize,
+    /// Payloads written by a newer app version, left untouched
+    pub newer_versions: usize,
+}
+
+/// Version of a payload; 0 for payloads written before versioning
+pub fn schema_version(value: &Value) -> u64 {
+    value
+        .get(SCHEMA_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// Upgrade a payload to the current version and stamp it. Returns whether it
+/// changed; a payload from a newer app version is an error and left unchanged.
+pub fn upgrade(value: &mut Value) -> Result<bool, ActivityError> {
+    let version = schema_version(value);
+    let Some(blocks) = value.as_object_mut() else {
+        return Ok(false);
+    };
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(ActivityError::validation(
+            SCHEMA_VERSION_KEY,
+            &format!(
+                "Activity data version {version} is newer than the supported version {CURRENT_SCHEMA_VERSION}"
+            ),
+        ));
+    }
+    if version == CURRENT_SCHEMA_VERSION {
+        return Ok(false);
+    }
+
+    for migration in &MIGRATIONS[version as usize..] {
+        migration(blocks);
+    }
+    blocks.insert(
+        SCHEMA_VERSION_KEY.to_string(),
+        Value::from(CURRENT_SCHEMA_VERSION),
+    );
+    Ok(true)
+}
+
+/// Typed blocks to store for a payload sent by the frontend or an importer,
+/// upgraded to and stamped with the current version. The time block is brought to
+/// its canonical form even when the payload is current, and filled from
+/// `created_at` when it is missing or blank.
+pub fn prepare_for_storage(
+    mut value: Value,
+    created_at: DateTime<Utc>,
+) -> Result<ActivityData, ActivityError> {
+    upgrade(&mut value)?;
+    if let Some(blocks) = value.as_object_mut() {
+        normalize_time_block(blocks, Some(created_at));
+    }
+    let mut data = ActivityData::from_legacy_json(value);
+    data.insert(
+        SCHEMA_VERSION_KEY.to_string(),
+        BlockData::Other(Value::from(CURRENT_SCHEMA_VERSION)),
+    );
+    Ok(data)
+}
+
+/// Typed blocks of a stored payload, upgraded in memory when it is older than
+/// the current version
+pub fn read_stored(mut value: Value) -> ActivityData {
+    if let Err(e) = upgrade(&mut value) {
+        log::warn!("[DB] Reading activity data as stored: {e}");
+    }
+    ActivityData::from_legacy_json(value)
+}
+
+/// Version 0 -> 1: time blocks with a plain `YYYY-MM-DD` date get the editor's ISO
+/// form, and measurement values and units are normalized ("5.2", "lb", no padding)
+fn v0_plain_dates_and_units(blocks: &mut Map<String, Value>) {
+    for block in blocks.values_mut() {
+        let Some(block) = block.as_object_mut() else {
+            continue;
+        };
+        if let Some(date) = block.get("date").and_then(Value::as_str) {
+            if let Ok(day) = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+                block.insert(
+                    "date".to_string(),
+                    Value::from(format!("{}T00:00:00.000Z", day.format("%Y-%m-%d"))),
+                );
+            }
+        }
+        if block.contains_key("measurementType") {
+            if let Some(value) = block.get("value").filter(|value| value.is_number()) {
+                let value = value.to_string();
+                block.insert("value".to_string(), Value::from(value));
+            }
+            if let Some(unit) = block.get("unit").and_then(Value::as_str) {
+                // Case is kept: temperatures use "C" and "F"
+                let unit = match unit.trim().to_lowercase().as_str() {
+                    "lbs" => "lb".to_string(),
+                    "kgs" => "kg".to_string(),
+                    _ => unit.trim().to_string(),
+                };
+                block.insert("unit".to_string(), Value::from(unit));
+            }
+        }
+    }
+}
+
+/// Version 1 -> 2: time block dates in UTC with milliseconds, read in the block's
+/// timezone when they carry no offset
+fn v1_canonical_times(blocks: &mut Map<String, Value>) {
+    normalize_time_block(blocks, None);
+}
+
+impl super::PetDatabase {
+    /// Upgrade every stored payload below the current version in one transaction,
+    /// filling blank time blocks from each row's creation time
+    pub async fn migrate_activity_data_versions(
+        &self,
+    ) -> Result<ActivityDataMigrationReport, ActivityError> {
+        let _turn = self.writes.acquire_for_task().await;
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to begin transaction: {e}"))
+        })?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, activity_data, created_at FROM activities
+            WHERE activity_data IS NOT NULL AND json_valid(activity_data)
+                AND json_type(activity_data) = 'object'
+                AND COALESCE(json_extract(activity_data, '$.schema_version'), 0) != ?
+            "#,
+        )
+        .bind(CURRENT_SCHEMA_VERSION as i64)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut report = ActivityDataMigrationReport {
+            current_version: CURRENT_SCHEMA_VERSION,
+            ..Default::default()
+        };
+        for row in rows {
+            let json: String = row.get("activity_data");
+            let Ok(mut data) = serde_json::from_str::<Value>(&json) else {
+                continue;
+            };
+            if schema_version(&data) > CURRENT_SCHEMA_VERSION {
+                report.newer_versions += 1;
+                continue;
+            }
+            report.scanned += 1;
+            if !upgrade(&mut data)? {
+                continue;
+            }
+            let created_at: Option<DateTime<Utc>> = row.try_get("created_at").ok();
+            if let Some(blocks) = data.as_object_mut() {
+                if normalize_time_block(blocks, created_at) == TimeNormalization::Filled {
+                    report.times_filled += 1;
+                }
+            }
+            // Not a user edit, so updated_at stays as it was
+            sqlx::query("UPDATE activities SET activity_data = ? WHERE id = ?")
+                .bind(data.to_string())
+                .bind(row.get::<i64, _>("id"))
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+            report.upgraded += 1;
+        }
+
+        tx.commit().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to commit transaction: {e}"))
+        })?;
+
+        log::info!(
+            "[DB] migrate_activity_data_versions: version={}, upgraded={}/{}, times_filled={}, newer={}",
+            report.current_version,
+            report.upgraded,
+            report.scanned,
+            report.times_filled,
+            report.newer_versions
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use crate::database::PetDatabase;
+    use serde_json::json;
+
+    async fn stored_version(db: &PetDatabase, id: i64) -> u64 {
+        let json: String = sqlx::query_scalar("SELECT activity_data FROM activities WHERE id = ?")
+            .bind(id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        schema_version(&serde_json::from_str(&json).unwrap())
+    }
+
+    #[test]
+    fn test_upgrade_unversioned_payload() {
+        let mut value = json!({
+            "time": { "date": "2025-03-01", "time": "", "timezone": "" },
+            "weight": { "value": 5.2, "unit": " LBS", "measurementType": "weight" },
+            "temperature": { "value": "38.6", "unit": "C ", "measurementType": "temperature" },
+            "notes": "Weighed at home",
+        });
+        assert_eq!(schema_version(&value), 0);
+        assert!(upgrade(&mut value).unwrap());
+        assert_eq!(schema_version(&value), CURRENT_SCHEMA_VERSION);
+        assert_eq!(value["time"]["date"], "2025-03-01T00:00:00.000Z");
+        assert_eq!(value["weight"]["value"], "5.2");
+        assert_eq!(value["weight"]["unit"], "lb");
+        assert_eq!(value["temperature"]["unit"], "C");
+        assert!(!upgrade(&mut value).unwrap());
+
+        let data = read_stored(value);
+        assert!((data.extract_weight_kg().unwrap() - 2.3587).abs() < 0.001);
+
+        let mut newer = json!({ "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+        assert!(upgrade(&mut newer).is_err());
+        assert!(prepare_for_storage(newer, Utc::now()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_migration_and_stamped_writes() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 5,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let ids = &summary.activity_ids;
+
+        assert_eq!(stored_version(&db, ids[0]).await, CURRENT_SCHEMA_VERSION);
+
+        // Rows written before versioning, one without a date, and one by a newer app
+        sqlx::query(
+            "UPDATE activities SET activity_data = json_remove(activity_data, '$.schema_version') WHERE id != ?",
+        )
+        .bind(ids[0])
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE activities SET activity_data = json_set(activity_data, '$.time.date', '') WHERE id = ?")
+            .bind(ids[3])
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE activities SET activity_data = json_set(activity_data, '$.schema_version', 99) WHERE id = ?")
+            .bind(ids[1])
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(stored_version(&db, ids[2]).await, 0);
+        // Reads see the upgraded form without writing it
+        let activity = db.get_activity_by_id(ids[2]).await.unwrap();
+        assert!(activity
+            .activity_data
+            .unwrap()
+            .contains_key(SCHEMA_VERSION_KEY));
+
+        let report = db.migrate_activity_data_versions().await.unwrap();
+        assert_eq!(
+            report,
+            ActivityDataMigrationReport {
+                current_version: CURRENT_SCHEMA_VERSION,
+                scanned: ids.len() - 2,
+                upgraded: ids.len() - 2,
+                times_filled: 1,
+                newer_versions: 1,
+            }
+        );
+        for &id in &ids[2..] {
+            assert_eq!(stored_version(&db, id).await, CURRENT_SCHEMA_VERSION);
+        }
+        assert_eq!(stored_version(&db, ids[1]).await, 99);
+        assert_eq!(
+            db.migrate_activity_data_versions().await.unwrap().upgraded,
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_writes_store_canonical_times() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+
+        let create = |time: Value| {
+            db.create_activity(ActivityCreateRequest {
+                pet_id: summary.pet_ids[0],
+                category: ActivityCategory::Diet,
+                subcategory: "Feeding".to_string(),
+                activity_data: Some(json!({ "time": time, "notes": "Breakfast" })),
+                needs_review: false,
+            })
+        };
+        let stored = |id: i64| {
+            sqlx::query_as::<_, (String, String)>(
+                "SELECT json_extract(activity_data, '$.time.date'), activity_time FROM activities WHERE id = ?",
+            )
+            .bind(id)
+            .fetch_one(&db.pool)
+        };
+
+        let local = create(json!({ "date": "2025-06-01 18:30", "time": "", "timezone": "+08:00" }))
+            .await
+            .unwrap();
+        assert_eq!(
+            stored(local.id).await.unwrap(),
+            (
+                "2025-06-01T10:30:00.000Z".to_string(),
+                "2025-06-01 10:30:00".to_string()
+            )
+        );
+
+        let blank = create(json!({ "date": "", "time": "", "timezone": "" }))
+            .await
+            .unwrap();
+        let (date, _) = stored(blank.id).await.unwrap();
+        assert_eq!(
+            date,
+            crate::database::activity_time::canonical_date(blank.created_at)
+        );
+    }
+}
@@ -0,0 +1,283 @@
+//! Canonical form of time blocks.
+//!
+//! The editor sends time block dates as UTC ISO strings, but importers and older
+//! clients send plain dates, local date-times, offsets in other spellings, or no
+//! date at all. Stored dates are always UTC with milliseconds
+//! (`2025-06-01T08:30:00.000Z`), which the `activity_time` generated column reads,
+//! so every date filter on that column sees each activity at its real instant.
+
+use chrono::{
+    DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, SecondsFormat, Utc,
+};
+use serde_json::{Map, Value};
+
+/// Key of the time block in a payload
+pub const TIME_BLOCK_KEY: &str = "time";
+
+/// Date-times without an offset, read in the time block's timezone
+const LOCAL_DATE_TIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Date-times with an offset that RFC 3339 doesn't accept, e.g. `+0800`
+const OFFSET_DATE_TIME_FORMATS: [&str; 2] = ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%d %H:%M:%S%.f%z"];
+
+/// What normalizing a payload did to its time block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeNormalization {
+    /// Already canonical, or nothing to fill it from
+    Unchanged,
+    /// Missing or blank date filled from the activity's creation time
+    Filled,
+    /// Date rewritten in the canonical form
+    Normalized,
+    /// Date in a form that can't be read; left as it was
+    Unrecognized,
+}
+
+/// Stored form of an instant: `2025-06-01T08:30:00.000Z`
+pub fn canonical_date(instant: DateTime<Utc>) -> String {
+    instant.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+/// Fixed offset named by a timezone field: `UTC`, `GMT`, `Z`, `+08:00`, `+0800`,
+/// `UTC+8` or `GMT-05:30`. Blank means UTC; region names such as `Asia/Shanghai`
+/// are None, since the app carries no timezone database.
+pub fn parse_offset(timezone: &str) -> Option<FixedOffset> {
+    let zone = timezone.trim().to_uppercase();
+    let offset = zone
+        .strip_prefix("UTC")
+        .or_else(|| zone.strip_prefix("GMT"))
+        .unwrap_or(&zone);
+    if offset.is_empty() || offset == "Z" {
+        return Some(Utc.fix());
+    }
+
+    let (sign, digits) = match (offset.strip_prefix('+'), offset.strip_prefix('-')) {
+        (Some(digits), _) => (1, digits),
+        (_, Some(digits)) => (-1, digits),
+        _ => return None,
+    };
+    let (hours, minutes) = match digits.split_once(':') {
+        Some((hours, minutes)) => (hours, minutes),
+        None if digits.len() == 4 && digits.is_ascii() => digits.split_at(2),
+        None => (digits, "0"),
+    };
+    let hours: i32 = hours.parse().ok().filter(|hours| *hours <= 14)?;
+    let minutes: i32 = minutes.parse().ok().filter(|minutes| *minutes < 60)?;
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+/// Name of an offset as stored in the timezone field: `UTC` or `+08:00`
+fn offset_name(offset: FixedOffset) -> String {
+    if offset.local_minus_utc() == 0 {
+        "UTC".to_string()
+    } else {
+        offset.to_string()
+    }
+}
+
+/// Instant of a time block, in the offset it was written in. Dates without an
+/// offset are read in the block's timezone (UTC unless it is a fixed offset); a
+/// plain date takes its time of day from the `time` field, or else midnight.
+pub fn parse_block_date(date: &str, time: &str, timezone: &str) -> Option<DateTime<FixedOffset>> {
+    let date = date.trim();
+    if let Ok(instant) = DateTime::parse_from_rfc3339(date) {
+        return Some(instant);
+    }
+    if let Some(instant) = OFFSET_DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(date, format).ok())
+    {
+        return Some(instant);
+    }
+
+    let offset = parse_offset(timezone).unwrap_or_else(|| Utc.fix());
+    if let Some(local) = LOCAL_DATE_TIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+    {
+        return local.and_local_timezone(offset).single();
+    }
+
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(date, "%Y/%m/%d"))
+        .ok()?;
+    let time = time.trim();
+    match NaiveTime::parse_from_str(time, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+    {
+        Ok(time_of_day) => day
+            .and_time(time_of_day)
+            .and_local_timezone(offset)
+            .single(),
+        // Midnight UTC, as plain dates have always been stored
+        Err(_) => Some(day.and_time(NaiveTime::MIN).and_utc().fixed_offset()),
+    }
+}
+
+/// Bring the time block of a payload to its canonical form. A missing or blank
+/// date is filled from `created_at` when given, and a blank or fixed-offset
+/// timezone is named consistently; region names are kept as they are.
+pub fn normalize_time_block(
+    blocks: &mut Map<String, Value>,
+    created_at: Option<DateTime<Utc>>,
+) -> TimeNormalization {
+    let block = match blocks.get_mut(TIME_BLOCK_KEY) {
+        Some(Value::Object(block)) => block,
+        // Some other block under the time key; not ours to rewrite
+        Some(_) => return TimeNormalization::Unchanged,
+        None => {
+            let Some(created_at) = created_at else {
+                return TimeNormalization::Unchanged;
+            };
+            blocks.insert(
+                TIME_BLOCK_KEY.to_string(),
+                serde_json::json!({
+                    "date": canonical_date(created_at),
+                    "time": "",
+                    "timezone": "UTC",
+                }),
+            );
+            return TimeNormalization::Filled;
+        }
+    };
+
+    let field = |block: &Map<String, Value>, key: &str| {
+        block
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let date = field(block, "date");
+    let timezone = field(block, "timezone");
+
+    if date.trim().is_empty() {
+        let Some(created_at) = created_at else {
+            return TimeNormalization::Unchanged;
+        };
+        block.insert("date".to_string(), Value::from(canonical_date(created_at)));
+        if timezone.trim().is_empty() {
+            block.insert("timezone".to_string(), Value::from("UTC"));
+        }
+        return TimeNormalization::Filled;
+    }
+
+    let Some(instant) = parse_block_date(&date, &field(block, "time"), &timezone) else {
+        return TimeNormalization::Unrecognized;
+    };
+    let canonical = canonical_date(instant.with_timezone(&Utc));
+    let zone = if timezone.trim().is_empty() {
+        offset_name(*instant.offset())
+    } else {
+        parse_offset(&timezone).map_or(timezone.clone(), offset_name)
+    };
+
+    if canonical == date && zone == timezone {
+        return TimeNormalization::Unchanged;
+    }
+    block.insert("date".to_string(), Value::from(canonical));
+    block.insert("timezone".to_string(), Value::from(zone));
+    TimeNormalization::Normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn normalized(time: Value) -> (TimeNormalization, Value) {
+        let created_at = DateTime::parse_from_rfc3339("2025-06-01T09:15:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut blocks = json!({ "time": time, "notes": "Fed" })
+            .as_object()
+            .cloned()
+            .unwrap();
+        let outcome = normalize_time_block(&mut blocks, Some(created_at));
+        (outcome, blocks[TIME_BLOCK_KEY].clone())
+    }
+
+    #[test]
+    fn test_time_blocks_are_normalized_to_utc() {
+        let cases = [
+            // (date, time, timezone) -> (date, timezone)
+            (
+                ("2025-06-01T10:00:00.000Z", "", "Asia/Shanghai"),
+                ("2025-06-01T10:00:00.000Z", "Asia/Shanghai"),
+            ),
+            (
+                ("2025-06-01T18:00:00+08:00", "", ""),
+                ("2025-06-01T10:00:00.000Z", "+08:00"),
+            ),
+            (
+                ("2025-06-01 18:00:00+0800", "", ""),
+                ("2025-06-01T10:00:00.000Z", "+08:00"),
+            ),
+            (
+                ("2025-06-01T18:00", "", "UTC+8"),
+                ("2025-06-01T10:00:00.000Z", "+08:00"),
+            ),
+            (
+                ("2025-06-01 10:00", "", "gmt"),
+                ("2025-06-01T10:00:00.000Z", "UTC"),
+            ),
+            (
+                ("2025-06-01", "07:30", "-05:00"),
+                ("2025-06-01T12:30:00.000Z", "-05:00"),
+            ),
+            (("2025/06/01", "", ""), ("2025-06-01T00:00:00.000Z", "UTC")),
+        ];
+        for ((date, time, timezone), (expected_date, expected_zone)) in cases {
+            let (_, block) =
+                normalized(json!({ "date": date, "time": time, "timezone": timezone }));
+            assert_eq!(block["date"], expected_date, "{date} {time} {timezone}");
+            assert_eq!(block["timezone"], expected_zone, "{date} {time} {timezone}");
+        }
+
+        let (outcome, _) = normalized(
+            json!({ "date": "2025-06-01T10:00:00.000Z", "time": "", "timezone": "UTC" }),
+        );
+        assert_eq!(outcome, TimeNormalization::Unchanged);
+        let (outcome, block) = normalized(json!({ "date": "last Tuesday", "timezone": "" }));
+        assert_eq!(outcome, TimeNormalization::Unrecognized);
+        assert_eq!(block["date"], "last Tuesday");
+    }
+
+    #[test]
+    fn test_missing_dates_are_filled_from_created_at() {
+        let (outcome, block) = normalized(json!({ "date": " ", "time": "", "timezone": "" }));
+        assert_eq!(outcome, TimeNormalization::Filled);
+        assert_eq!(block["date"], "2025-06-01T09:15:00.000Z");
+        assert_eq!(block["timezone"], "UTC");
+
+        let mut blocks = Map::new();
+        assert_eq!(
+            normalize_time_block(&mut blocks, None),
+            TimeNormalization::Unchanged
+        );
+        assert!(blocks.is_empty());
+        assert_eq!(
+            normalize_time_block(&mut blocks, Some(Utc::now())),
+            TimeNormalization::Filled
+        );
+        assert!(blocks[TIME_BLOCK_KEY]["date"]
+            .as_str()
+            .unwrap()
+            .ends_with('Z'));
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("").unwrap().local_minus_utc(), 0);
+        assert_eq!(parse_offset("Z").unwrap().local_minus_utc(), 0);
+        assert_eq!(parse_offset("+0530").unwrap().local_minus_utc(), 19800);
+        assert_eq!(parse_offset("GMT-3").unwrap().local_minus_utc(), -10800);
+        assert!(parse_offset("Asia/Shanghai").is_none());
+        assert!(parse_offset("+25:00").is_none());
+    }
+}
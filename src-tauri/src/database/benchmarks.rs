@@ -0,0 +1,340 @@
+//! Timing benchmarks for the database hot paths, on generated fixtures.
+//!
+//! They are ignored by default; run them in release mode:
+//!
+//! ```text
+//! cargo test --release --lib database::benchmarks -- --ignored --nocapture --test-threads=1
+//! ```
+//!
+//! `BENCH_ACTIVITIES_PER_PET` sets the fixture size (default 500 for each of 4 pets).
+//! With `BENCH_BASELINE=<file>` the medians are written to the file when it doesn't
+//! exist yet, and otherwise compared with it: a benchmark fails when its median is
+//! more than `BENCH_TOLERANCE` (default 0.25, i.e. 25%) slower than the baseline.
+//! Record a baseline before a query redesign and compare after it on the same machine.
+
+use super::query::SelectQuery;
+use super::test_support::{seeded_database, FixtureConfig, FixtureRng, FIXTURE_BASE_DATE};
+use super::{
+    ActivityCategory, ActivityCreateRequest, ActivitySortKey, GetActivitiesRequest, PetDatabase,
+};
+use chrono::{DateTime, Days, NaiveDate, TimeZone, Utc};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const PETS: usize = 4;
+const DEFAULT_ACTIVITIES_PER_PET: usize = 500;
+const DEFAULT_TOLERANCE: f64 = 0.25;
+
+/// Untimed runs before measuring, to warm SQLite's page cache and statement cache
+const WARMUP_RUNS: usize = 5;
+
+/// Timings of one benchmark
+#[derive(Debug)]
+struct BenchResult {
+    name: &'static str,
+    runs: usize,
+    median: Duration,
+    p95: Duration,
+    mean: Duration,
+}
+
+impl BenchResult {
+    fn from_samples(name: &'static str, mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        let runs = samples.len();
+        let total: Duration = samples.iter().sum();
+        BenchResult {
+            name,
+            runs,
+            median: samples[runs / 2],
+            p95: samples[(runs * 95 / 100).min(runs - 1)],
+            mean: total / runs as u32,
+        }
+    }
+}
+
+fn env_or<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+async fn fixture() -> (PetDatabase, TempDir, Vec<i64>) {
+    let config = FixtureConfig {
+        pets: PETS,
+        activities_per_pet: env_or("BENCH_ACTIVITIES_PER_PET", DEFAULT_ACTIVITIES_PER_PET),
+        ..Default::default()
+    };
+    let started = Instant::now();
+    let (db, dir, summary) = seeded_database(&config).await;
+    println!(
+        "fixture: {} pets, {} activities in {:.2?}",
+        summary.pet_ids.len(),
+        summary.activity_ids.len(),
+        started.elapsed()
+    );
+    (db, dir, summary.pet_ids)
+}
+
+/// Time `runs` calls of `run`, each given its run index, after a few warm-up calls
+async fn measure<F, Fut>(name: &'static str, runs: usize, mut run: F) -> BenchResult
+where
+    F: FnMut(usize) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    for i in 0..WARMUP_RUNS {
+        run(runs + i).await;
+    }
+    let mut samples = Vec::with_capacity(runs);
+    for i in 0..runs {
+        let started = Instant::now();
+        run(i).await;
+        samples.push(started.elapsed());
+    }
+    let result = BenchResult::from_samples(name, samples);
+    println!(
+        "{:<40} runs={:<5} median={:>10.2?} p95={:>10.2?} mean={:>10.2?}",
+        result.name, result.runs, result.median, result.p95, result.mean
+    );
+    result
+}
+
+/// Write the medians to or compare them with the `BENCH_BASELINE` file, if set
+fn check_baseline(results: &[BenchResult]) {
+    let Ok(path) = std::env::var("BENCH_BASELINE") else {
+        return;
+    };
+    let mut baseline: BTreeMap<String, u64> = std::fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+    let tolerance: f64 = env_or("BENCH_TOLERANCE", DEFAULT_TOLERANCE);
+
+    let mut regressions = Vec::new();
+    for result in results {
+        let median_ns = result.median.as_nanos() as u64;
+        match baseline.get(result.name) {
+            Some(&expected_ns) => {
+                let change = median_ns as f64 / expected_ns.max(1) as f64 - 1.0;
+                println!(
+                    "{:<40} {:+.1}% against baseline",
+                    result.name,
+                    change * 100.0
+                );
+                if change > tolerance {
+                    regressions.push(format!(
+                        "{}: {:.2?} against {:.2?}",
+                        result.name,
+                        result.median,
+                        Duration::from_nanos(expected_ns)
+                    ));
+                }
+            }
+            None => {
+                baseline.insert(result.name.to_string(), median_ns);
+                let json = serde_json::to_string_pretty(&baseline).expect("serialize baseline");
+                std::fs::write(&path, format!("{json}\n")).expect("write baseline");
+            }
+        }
+    }
+    assert!(
+        regressions.is_empty(),
+        "Slower than the baseline by more than {:.0}%: {}",
+        tolerance * 100.0,
+        regressions.join("; ")
+    );
+}
+
+fn base_date() -> NaiveDate {
+    let (y, m, d) = FIXTURE_BASE_DATE;
+    NaiveDate::from_ymd_opt(y, m, d).expect("valid fixture base date")
+}
+
+#[tokio::test]
+#[ignore = "benchmark; run with --ignored in release mode"]
+async fn bench_activity_insert_with_side_effects() {
+    let (db, _dir, pet_ids) = fixture().await;
+    let mut rng = FixtureRng::new(7);
+
+    // A weight measurement and a food portion exercise the profile, inventory,
+    // smart default, metric rule and webhook hooks as well as the FTS triggers
+    let growth = measure("insert_weight_with_side_effects", 200, |i| {
+        let pet_id = *rng.pick(&pet_ids);
+        let day = base_date() + Days::new(i as u64 % 365);
+        let request = ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Growth,
+            subcategory: "Weight".to_string(),
+            activity_data: Some(serde_json::json!({
+                "time": { "date": format!("{day}T09:00:00.000Z"), "time": "", "timezone": "UTC" },
+                "weight": { "value": format!("{:.2}", 4.0 + (i % 50) as f64 / 100.0), "unit": "kg", "measurementType": "weight" },
+                "notes": "Weighed after breakfast",
+            })),
+            needs_review: false,
+        };
+        let db = &db;
+        async move {
+            db.create_activity_with_side_effects(request)
+                .await
+                .expect("insert weight");
+        }
+    })
+    .await;
+
+    let diet = measure("insert_portion_with_side_effects", 200, |i| {
+        let pet_id = pet_ids[i % pet_ids.len()];
+        let day = base_date() + Days::new(i as u64 % 365);
+        let request = ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Diet,
+            subcategory: "Breakfast".to_string(),
+            activity_data: Some(serde_json::json!({
+                "time": { "date": format!("{day}T08:00:00.000Z"), "time": "", "timezone": "UTC" },
+                "portion": { "amount": 80, "unit": "g", "portionType": "meal", "brand": "Orijen" },
+            })),
+            needs_review: false,
+        };
+        let db = &db;
+        async move {
+            db.create_activity_with_side_effects(request)
+                .await
+                .expect("insert portion");
+        }
+    })
+    .await;
+
+    check_baseline(&[growth, diet]);
+}
+
+/// First timeline page of a pet's activities of `category` between `start` and `end`
+async fn filtered_timeline_page(
+    db: &PetDatabase,
+    pet_id: i64,
+    category: ActivityCategory,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) {
+    let instant = |at: DateTime<Utc>| at.format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut select = SelectQuery::new("SELECT * FROM activities");
+    select
+        .where_eq("pet_id", pet_id)
+        .where_eq("category", category.to_string())
+        .where_cmp_opt("activity_time", ">=", Some(instant(start)))
+        .where_cmp_opt("activity_time", "<", Some(instant(end)))
+        .where_eq("needs_review", false)
+        .order_by(ActivitySortKey::ActivityTime.order_by_clause(true))
+        .paginate(50, 0);
+    let rows = select
+        .build()
+        .fetch_all(&db.read_pool)
+        .await
+        .expect("filtered timeline");
+    for row in &rows {
+        db.row_to_activity(row).await.expect("filtered activity");
+    }
+}
+
+#[tokio::test]
+#[ignore = "benchmark; run with --ignored in release mode"]
+async fn bench_timeline_query_with_filters() {
+    let (db, _dir, pet_ids) = fixture().await;
+    let categories = [
+        ActivityCategory::Health,
+        ActivityCategory::Growth,
+        ActivityCategory::Diet,
+        ActivityCategory::Lifestyle,
+        ActivityCategory::Expense,
+    ];
+    let month_start = |i: usize| {
+        let day = base_date() + Days::new((i as u64 * 30) % 330);
+        Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).expect("midnight"))
+    };
+
+    let first_page = measure("timeline_first_page", 300, |i| {
+        let request = GetActivitiesRequest {
+            pet_id: Some(pet_ids[i % pet_ids.len()]),
+            sort_by: Some("activity_time".to_string()),
+            sort_desc: Some(true),
+            limit: Some(50),
+            ..Default::default()
+        };
+        let db = &db;
+        async move {
+            db.get_activities(request).await.expect("timeline page");
+        }
+    })
+    .await;
+
+    // get_activities doesn't filter by category or date, so the filtered page is
+    // queried the way the timeline would: same columns, order and page size
+    let filtered = measure("timeline_category_and_date_range", 300, |i| {
+        let pet_id = pet_ids[i % pet_ids.len()];
+        let category = categories[i % categories.len()];
+        let start = month_start(i);
+        let db = &db;
+        async move {
+            filtered_timeline_page(
+                db,
+                pet_id,
+                category,
+                start,
+                start + chrono::Duration::days(30),
+            )
+            .await;
+        }
+    })
+    .await;
+
+    let deep_page = measure("timeline_deep_offset_exact_count", 100, |i| {
+        let request = GetActivitiesRequest {
+            pet_id: Some(pet_ids[i % pet_ids.len()]),
+            sort_by: Some("cost".to_string()),
+            sort_desc: Some(true),
+            limit: Some(50),
+            offset: Some(200),
+            exact_count: true,
+            ..Default::default()
+        };
+        let db = &db;
+        async move {
+            db.get_activities(request).await.expect("deep page");
+        }
+    })
+    .await;
+
+    check_baseline(&[first_page, filtered, deep_page]);
+}
+
+#[tokio::test]
+#[ignore = "benchmark; run with --ignored in release mode"]
+async fn bench_fts_search() {
+    let (db, _dir, _pet_ids) = fixture().await;
+    let queries = ["playful", "vet", "royal canin", "walk", "tired after"];
+
+    let fts = measure("fts_search_activities", 300, |i| {
+        let query = queries[i % queries.len()];
+        let db = &db;
+        async move {
+            db.fts_search_activities(query, Some(50))
+                .await
+                .expect("fts search");
+        }
+    })
+    .await;
+
+    let global = measure("global_search", 300, |i| {
+        let query = queries[i % queries.len()];
+        let db = &db;
+        async move {
+            db.global_search(query, Some(20))
+                .await
+                .expect("global search");
+        }
+    })
+    .await;
+
+    check_baseline(&[fts, global]);
+}
@@ -0,0 +1,190 @@
+use crate::errors::ActivityError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// Growth subcategory for body condition checks
+pub const BODY_CONDITION_SUBCATEGORY: &str = "Body Condition";
+
+/// Entries `get_body_condition_history` returns when no limit is given
+pub const DEFAULT_HISTORY_LIMIT: u32 = 50;
+
+/// Most entries `get_body_condition_history` returns
+const MAX_HISTORY_LIMIT: u32 = 500;
+
+/// Where a body condition score falls on the 9-point scale
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BodyConditionClass {
+    /// 1-3: ribs, spine and hip bones easily felt or visible
+    Underweight,
+    /// 4-5: ribs felt under a slight fat cover, waist visible from above
+    Ideal,
+    /// 6-7: ribs hard to feel, waist barely visible
+    Overweight,
+    /// 8-9: ribs not felt under heavy fat, no waist
+    Obese,
+}
+
+impl BodyConditionClass {
+    pub fn from_score(score: u8) -> Self {
+        match score {
+            0..=3 => Self::Underweight,
+            4..=5 => Self::Ideal,
+            6..=7 => Self::Overweight,
+            _ => Self::Obese,
+        }
+    }
+}
+
+/// One body condition score as recorded
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BodyConditionEntry {
+    pub activity_id: i64,
+    pub date: NaiveDate,
+    pub score: u8,
+    pub class: BodyConditionClass,
+}
+
+/// A pet's body condition scores over time
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BodyConditionHistory {
+    pub pet_id: i64,
+    /// Most recent scores, oldest first
+    pub entries: Vec<BodyConditionEntry>,
+    pub latest: Option<BodyConditionEntry>,
+    /// Latest score minus the one before it
+    pub change: Option<i8>,
+}
+
+impl super::PetDatabase {
+    /// Get a pet's last `limit` body condition scores (default 50), by activity date.
+    /// Scores outside 1-9 or not whole numbers are left out.
+    pub async fn get_body_condition_history(
+        &self,
+        pet_id: i64,
+        limit: Option<u32>,
+    ) -> Result<BodyConditionHistory, ActivityError> {
+        let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+        if limit == 0 || limit > MAX_HISTORY_LIMIT {
+            return Err(ActivityError::validation(
+                "limit".to_string(),
+                format!("History covers 1 to {MAX_HISTORY_LIMIT} entries"),
+            ));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT activities.id AS activity_id,
+                date(activity_time) AS day,
+                json_extract(block.value, '$.bodyConditionScore') AS score
+            FROM activities, json_each(activities.activity_data) AS block
+            WHERE pet_id = ? AND needs_review = 0
+                AND block.type = 'object'
+                AND json_type(block.value, '$.bodyConditionScore') = 'integer'
+                AND json_extract(block.value, '$.bodyConditionScore') BETWEEN 1 AND 9
+            ORDER BY activity_time DESC, activities.id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(i64::from(limit))
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut entries: Vec<BodyConditionEntry> = rows
+            .iter()
+            .filter_map(|row| {
+                let date = row
+                    .try_get::<String, _>("day")
+                    .ok()
+                    .and_then(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())?;
+                let score = row.try_get::<i64, _>("score").ok()? as u8;
+                Some(BodyConditionEntry {
+                    activity_id: row.try_get("activity_id").ok()?,
+                    date,
+                    score,
+                    class: BodyConditionClass::from_score(score),
+                })
+            })
+            .collect();
+        entries.reverse();
+
+        let change = match entries.as_slice() {
+            [.., previous, latest] => Some(latest.score as i8 - previous.score as i8),
+            _ => None,
+        };
+        log::debug!(
+            "[DB] get_body_condition_history: pet_id={pet_id}, {} entries",
+            entries.len()
+        );
+
+        Ok(BodyConditionHistory {
+            pet_id,
+            latest: entries.last().cloned(),
+            entries,
+            change,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_body_condition_history_orders_and_classifies_scores() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let log = |date: &str, bcs: serde_json::Value| ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Growth,
+            subcategory: BODY_CONDITION_SUBCATEGORY.to_string(),
+            activity_data: Some(json!({
+                "time": { "date": format!("{date}T08:00:00.000Z"), "time": "", "timezone": "UTC" },
+                "bcs": bcs,
+            })),
+            needs_review: false,
+        };
+        for request in [
+            log("2025-05-01", json!({ "bodyConditionScore": 7 })),
+            log("2025-03-01", json!({ "bodyConditionScore": 8 })),
+            log("2025-06-01", json!({ "bodyConditionScore": 5 })),
+            // Not a usable score
+            log("2025-06-02", json!({ "bodyConditionScore": 4.5 })),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let history = db.get_body_condition_history(pet_id, None).await.unwrap();
+        let scores: Vec<u8> = history.entries.iter().map(|e| e.score).collect();
+        assert_eq!(scores, vec![8, 7, 5]);
+        assert_eq!(history.entries[0].class, BodyConditionClass::Obese);
+        assert_eq!(
+            history.latest.as_ref().unwrap().class,
+            BodyConditionClass::Ideal
+        );
+        assert_eq!(history.change, Some(-2));
+
+        let recent = db
+            .get_body_condition_history(pet_id, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(recent.entries.len(), 1);
+        assert_eq!(recent.change, None);
+        assert!(db
+            .get_body_condition_history(pet_id, Some(0))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use crate::errors::ActivityError;
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashMap;
+
+/// Settings key of the per-pet monthly budgets, a map of pet ID to budget
+pub const PET_BUDGETS_SETTING_KEY: &str = "pet_budgets";
+
+/// Share of a budget, in percent, at which a warning is sent
+pub const BUDGET_WARNING_PERCENT: f64 = 80.0;
+
+/// Monthly spending limit of a pet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PetBudget {
+    pub monthly_limit: f64,
+    /// Currency of the limit; expenses paid in other currencies aren't counted.
+    /// None counts every expense.
+    #[serde(default)]
+    pub currency: Option<String>,
+}
+
+/// How far spending has gone into a budget
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAlertLevel {
+    /// At least [`BUDGET_WARNING_PERCENT`] of the budget is spent
+    Warning,
+    /// The whole budget is spent
+    Exceeded,
+}
+
+impl BudgetAlertLevel {
+    /// Share of the budget, in percent, that triggers this level
+    pub fn percent(self) -> u32 {
+        match self {
+            BudgetAlertLevel::Warning => BUDGET_WARNING_PERCENT as u32,
+            BudgetAlertLevel::Exceeded => 100,
+        }
+    }
+}
+
+/// A pet's spending in one month against its budget
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BudgetStatus {
+    pub pet_id: i64,
+    /// First day of the month
+    pub month: NaiveDate,
+    /// None when the pet has no budget; spending is still reported
+    pub budget: Option<PetBudget>,
+    pub spent: f64,
+    /// Expenses counted in `spent`
+    pub expenses: usize,
+    pub remaining: Option<f64>,
+    pub percent_used: Option<f64>,
+    pub alert: Option<BudgetAlertLevel>,
+    /// Expenses in another currency than the budget's, left out of `spent`
+    pub other_currency_expenses: usize,
+}
+
+impl super::PetDatabase {
+    /// A pet's spending in the month containing `month` (by activity time, UTC)
+    /// against its monthly budget
+    pub async fn get_budget_status(
+        &self,
+        pet_id: i64,
+        month: NaiveDate,
+    ) -> Result<BudgetStatus, ActivityError> {
+        let budget = self.get_pet_budgets().await?.remove(&pet_id);
+        self.budget_status(pet_id, month, budget).await
+    }
+
+    /// Budget status in the month containing `month` of every pet with a budget
+    pub async fn get_budget_statuses(
+        &self,
+        month: NaiveDate,
+    ) -> Result<Vec<BudgetStatus>, ActivityError> {
+        let mut statuses = Vec::new();
+        for (pet_id, budget) in self.get_pet_budgets().await? {
+            statuses.push(self.budget_status(pet_id, month, Some(budget)).await?);
+        }
+        statuses.sort_by_key(|status| status.pet_id);
+        Ok(statuses)
+    }
+
+    /// A pet's monthly budget, None when it has none
+    pub async fn get_pet_budget(&self, pet_id: i64) -> Result<Option<PetBudget>, ActivityError> {
+        Ok(self.get_pet_budgets().await?.remove(&pet_id))
+    }
+
+    /// Set a pet's monthly budget, or remove it with None
+    pub async fn set_pet_budget(
+        &self,
+        pet_id: i64,
+        budget: Option<PetBudget>,
+    ) -> Result<Option<PetBudget>, ActivityError> {
+        let budget = budget.map(normalize_budget).transpose()?;
+        let mut budgets = self.get_pet_budgets().await?;
+        match &budget {
+            Some(budget) => budgets.insert(pet_id, budget.clone()),
+            None => budgets.remove(&pet_id),
+        };
+        self.set_setting(PET_BUDGETS_SETTING_KEY, &budgets)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(budget)
+    }
+
+    async fn get_pet_budgets(&self) -> Result<HashMap<i64, PetBudget>, ActivityError> {
+        Ok(self
+            .get_setting::<HashMap<i64, PetBudget>>(PET_BUDGETS_SETTING_KEY)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .unwrap_or_default())
+    }
+
+    async fn budget_status(
+        &self,
+        pet_id: i64,
+        month: NaiveDate,
+        budget: Option<PetBudget>,
+    ) -> Result<BudgetStatus, ActivityError> {
+        let month = month.with_day(1).expect("every month has a first day");
+        let rows = sqlx::query(
+            r#"
+            SELECT CAST(cost_amount AS REAL) AS amount,
+                json_extract(activity_data, '$.cost.currency') AS currency
+            FROM activities
+            WHERE pet_id = ? AND cost_amount > 0 AND needs_review = 0
+                AND activity_time >= ? AND activity_time < ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(month.to_string())
+        .bind((month + Months::new(1)).to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let budget_currency = budget.as_ref().and_then(|budget| budget.currency.clone());
+        let (mut spent, mut expenses, mut other_currency_expenses) = (0.0, 0, 0);
+        for row in rows {
+            let currency: Option<String> = row.try_get("currency").ok().flatten();
+            let counted = match (&budget_currency, currency) {
+                (Some(budget_currency), Some(currency)) => {
+                    currency.trim().eq_ignore_ascii_case(budget_currency)
+                }
+                _ => true,
+            };
+            if counted {
+                spent += row.get::<f64, _>("amount");
+                expenses += 1;
+            } else {
+                other_currency_expenses += 1;
+            }
+        }
+
+        let limit = budget.as_ref().map(|budget| budget.monthly_limit);
+        let percent_used = limit.map(|limit| spent / limit * 100.0);
+        log::debug!(
+            "[DB] budget_status: pet_id={pet_id}, month={month}, spent={spent}, limit={limit:?}"
+        );
+        Ok(BudgetStatus {
+            pet_id,
+            month,
+            spent,
+            expenses,
+            remaining: limit.map(|limit| limit - spent),
+            percent_used,
+            alert: percent_used.and_then(budget_alert),
+            other_currency_expenses,
+            budget,
+        })
+    }
+}
+
+/// Alert level reached at `percent_used` of a budget
+pub fn budget_alert(percent_used: f64) -> Option<BudgetAlertLevel> {
+    if percent_used >= 100.0 {
+        Some(BudgetAlertLevel::Exceeded)
+    } else if percent_used >= BUDGET_WARNING_PERCENT {
+        Some(BudgetAlertLevel::Warning)
+    } else {
+        None
+    }
+}
+
+fn normalize_budget(budget: PetBudget) -> Result<PetBudget, ActivityError> {
+    if !budget.monthly_limit.is_finite() || budget.monthly_limit <= 0.0 {
+        return Err(ActivityError::validation(
+            "monthly_limit",
+            "Monthly budget must be a positive amount",
+        ));
+    }
+    Ok(PetBudget {
+        monthly_limit: budget.monthly_limit,
+        currency: budget
+            .currency
+            .map(|currency| currency.trim().to_uppercase())
+            .filter(|currency| !currency.is_empty()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityBlocksBuilder, ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_budget_status_for_month() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let expense = |month: u32, day: u32, amount: f64, currency: &str| {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(Utc.with_ymd_and_hms(2025, month, day, 12, 0, 0).unwrap());
+            blocks.cost(amount, currency).unwrap();
+            ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Expense,
+                subcategory: "Food".to_string(),
+                activity_data: Some(blocks.into_json()),
+                needs_review: false,
+            }
+        };
+        for request in [
+            expense(3, 2, 50.0, "usd"),
+            expense(3, 20, 35.0, "USD"),
+            expense(3, 21, 20.0, "EUR"),
+            expense(4, 1, 80.0, "USD"),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let march = NaiveDate::from_ymd_opt(2025, 3, 15).unwrap();
+        let status = db.get_budget_status(pet_id, march).await.unwrap();
+        assert_eq!(status.month, NaiveDate::from_ymd_opt(2025, 3, 1).unwrap());
+        assert_eq!((status.spent, status.expenses), (105.0, 3));
+        assert_eq!((status.percent_used, status.alert), (None, None));
+
+        assert!(db
+            .set_pet_budget(
+                pet_id,
+                Some(PetBudget {
+                    monthly_limit: 0.0,
+                    currency: None
+                })
+            )
+            .await
+            .is_err());
+        let budget = db
+            .set_pet_budget(
+                pet_id,
+                Some(PetBudget {
+                    monthly_limit: 100.0,
+                    currency: Some(" usd ".to_string()),
+                }),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(budget.currency.as_deref(), Some("USD"));
+
+        let status = db.get_budget_status(pet_id, march).await.unwrap();
+        assert_eq!((status.spent, status.other_currency_expenses), (85.0, 1));
+        assert_eq!(status.remaining, Some(15.0));
+        assert_eq!(status.alert, Some(BudgetAlertLevel::Warning));
+
+        let statuses = db.get_budget_statuses(march).await.unwrap();
+        assert_eq!(statuses, vec![status]);
+
+        db.set_pet_budget(pet_id, None).await.unwrap();
+        assert!(db.get_budget_statuses(march).await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_budget_alert_levels() {
+        assert_eq!(budget_alert(79.9), None);
+        assert_eq!(budget_alert(80.0), Some(BudgetAlertLevel::Warning));
+        assert_eq!(budget_alert(100.0), Some(BudgetAlertLevel::Exceeded));
+        assert_eq!(BudgetAlertLevel::Warning.percent(), 80);
+    }
+}
@@ -0,0 +1,252 @@
+use super::models::*;
+use crate::checklists::ChecklistTemplate;
+use crate::errors::PetError;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::Row;
+
+impl super::PetDatabase {
+    /// Create a checklist for a pet from a template, with one item per template task
+    pub async fn create_checklist_from_template(
+        &self,
+        pet_id: i64,
+        template: &ChecklistTemplate,
+        start_date: NaiveDate,
+    ) -> Result<Checklist, PetError> {
+        log::debug!(
+            "[DB] create_checklist_from_template: pet_id={pet_id}, template={}, start_date={start_date}",
+            template.key
+        );
+
+        let now = Utc::now();
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        let checklist_id = sqlx::query(
+            r#"
+            INSERT INTO checklists (pet_id, template_key, title, status, start_date, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(pet_id)
+        .bind(template.key)
+        .bind(template.title)
+        .bind(ChecklistStatus::Active.to_string())
+        .bind(start_date.format("%Y-%m-%d").to_string())
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?
+        .last_insert_rowid();
+
+        let due_dates = template.item_due_dates(start_date);
+        for (position, (item, due_date)) in template.items.iter().zip(due_dates).enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO checklist_items (checklist_id, position, title, description, due_date)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(checklist_id)
+            .bind(position as i64)
+            .bind(item.title)
+            .bind(item.description)
+            .bind(due_date.map(|d| d.format("%Y-%m-%d").to_string()))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!(
+            "[DB] create_checklist_from_template: checklist_id={checklist_id}, items={}",
+            template.items.len()
+        );
+        self.get_checklist_by_id(checklist_id).await
+    }
+
+    /// Get a checklist with its items
+    pub async fn get_checklist_by_id(&self, id: i64) -> Result<Checklist, PetError> {
+        let row = sqlx::query("SELECT * FROM checklists WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?
+            .ok_or_else(|| PetError::checklist_not_found(id))?;
+
+        let items = self.get_checklist_items(id).await?;
+        self.row_to_checklist(&row, items)
+    }
+
+    /// Get a pet's checklists, newest first
+    pub async fn get_checklists_for_pet(
+        &self,
+        pet_id: i64,
+        include_completed: bool,
+    ) -> Result<Vec<Checklist>, PetError> {
+        let query = if include_completed {
+            "SELECT * FROM checklists WHERE pet_id = ? ORDER BY created_at DESC, id DESC"
+        } else {
+            "SELECT * FROM checklists WHERE pet_id = ? AND status = 'active' ORDER BY created_at DESC, id DESC"
+        };
+
+        let rows = sqlx::query(query)
+            .bind(pet_id)
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        let mut checklists = Vec::with_capacity(rows.len());
+        for row in rows {
+            let items = self.get_checklist_items(row.get("id")).await?;
+            checklists.push(self.row_to_checklist(&row, items)?);
+        }
+        Ok(checklists)
+    }
+
+    /// Mark a checklist item done or not done.
+    /// The checklist is completed when all items are done and reopened when one is unchecked.
+    pub async fn set_checklist_item_completed(
+        &self,
+        item_id: i64,
+        completed: bool,
+        notes: Option<String>,
+    ) -> Result<Checklist, PetError> {
+        let checklist_id: i64 =
+            sqlx::query_scalar("SELECT checklist_id FROM checklist_items WHERE id = ?")
+                .bind(item_id)
+                .fetch_optional(&self.read_pool)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?
+                .ok_or_else(|| PetError::validation("item_id", "Checklist item not found"))?;
+
+        let now = Utc::now();
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        sqlx::query(
+            "UPDATE checklist_items SET completed_at = ?, notes = COALESCE(?, notes) WHERE id = ?",
+        )
+        .bind(completed.then_some(now))
+        .bind(&notes)
+        .bind(item_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        let remaining: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM checklist_items WHERE checklist_id = ? AND completed_at IS NULL",
+        )
+        .bind(checklist_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        let status = if remaining == 0 {
+            ChecklistStatus::Completed
+        } else {
+            ChecklistStatus::Active
+        };
+        sqlx::query(
+            r#"
+            UPDATE checklists
+            SET status = ?, completed_at = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(status.to_string())
+        .bind((status == ChecklistStatus::Completed).then_some(now))
+        .bind(now)
+        .bind(checklist_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!(
+            "[DB] set_checklist_item_completed: item_id={item_id}, completed={completed}, checklist_status={status}"
+        );
+        self.get_checklist_by_id(checklist_id).await
+    }
+
+    /// Delete a checklist and its items
+    pub async fn delete_checklist(&self, id: i64) -> Result<(), PetError> {
+        let result = sqlx::query("DELETE FROM checklists WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(PetError::checklist_not_found(id));
+        }
+        log::info!("[DB] delete_checklist: checklist_id={id}");
+        Ok(())
+    }
+
+    async fn get_checklist_items(&self, checklist_id: i64) -> Result<Vec<ChecklistItem>, PetError> {
+        let rows = sqlx::query(
+            "SELECT * FROM checklist_items WHERE checklist_id = ? ORDER BY position ASC",
+        )
+        .bind(checklist_id)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| {
+                let due_date: Option<String> = row.get("due_date");
+                Ok(ChecklistItem {
+                    id: row.get("id"),
+                    checklist_id: row.get("checklist_id"),
+                    position: row.get("position"),
+                    title: row.get("title"),
+                    description: row.get("description"),
+                    due_date: due_date
+                        .map(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d"))
+                        .transpose()
+                        .map_err(|e| PetError::database(e.to_string()))?,
+                    completed_at: row.get::<Option<DateTime<Utc>>, _>("completed_at"),
+                    notes: row.get("notes"),
+                })
+            })
+            .collect()
+    }
+
+    fn row_to_checklist(
+        &self,
+        row: &sqlx::sqlite::SqliteRow,
+        items: Vec<ChecklistItem>,
+    ) -> Result<Checklist, PetError> {
+        let status: String = row.get("status");
+        let start_date: String = row.get("start_date");
+
+        Ok(Checklist {
+            id: row.get("id"),
+            pet_id: row.get("pet_id"),
+            template_key: row.get("template_key"),
+            title: row.get("title"),
+            status: status
+                .parse()
+                .map_err(|e: anyhow::Error| PetError::database(e.to_string()))?,
+            start_date: NaiveDate::parse_from_str(&start_date, "%Y-%m-%d")
+                .map_err(|e| PetError::database(e.to_string()))?,
+            items,
+            created_at: row.get::<DateTime<Utc>, _>("created_at"),
+            updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+            completed_at: row.get::<Option<DateTime<Utc>>, _>("completed_at"),
+        })
+    }
+}
@@ -0,0 +1,362 @@
+use super::activity_data::{ActivityDataExt, BlockData};
+use super::models::*;
+use crate::errors::ActivityError;
+use crate::periods::{PeriodBuckets, PeriodGranularity, PeriodSettings};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Number of pets that can be compared at once
+pub const MIN_COMPARED_PETS: usize = 2;
+pub const MAX_COMPARED_PETS: usize = 4;
+
+/// Default number of monthly periods in a comparison
+const DEFAULT_COMPARISON_MONTHS: u32 = 12;
+
+/// Metric that can be compared between pets
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ComparisonMetric {
+    /// Last recorded weight in each period, in the comparison's `weight_unit`
+    Weight,
+    /// Number of activities recorded in each period
+    ActivityCount,
+    /// Sum of cost block amounts in each period
+    Expenses,
+}
+
+impl std::fmt::Display for ComparisonMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ComparisonMetric::Weight => write!(f, "weight"),
+            ComparisonMetric::ActivityCount => write!(f, "activity_count"),
+            ComparisonMetric::Expenses => write!(f, "expenses"),
+        }
+    }
+}
+
+impl std::str::FromStr for ComparisonMetric {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weight" => Ok(ComparisonMetric::Weight),
+            "activity_count" => Ok(ComparisonMetric::ActivityCount),
+            "expenses" => Ok(ComparisonMetric::Expenses),
+            _ => Err(anyhow::anyhow!("Invalid comparison metric: {}", s)),
+        }
+    }
+}
+
+/// One pet's values for one metric, aligned with `PetComparison::periods`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ComparisonSeries {
+    pub pet_id: i64,
+    pub pet_name: String,
+    pub metric: ComparisonMetric,
+    /// None where the pet has no data for the period (weight only)
+    pub values: Vec<Option<f64>>,
+}
+
+/// Side-by-side time series for several pets
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PetComparison {
+    /// Period labels, oldest first: `YYYY-MM` for calendar months, the start date otherwise
+    pub periods: Vec<String>,
+    pub granularity: PeriodGranularity,
+    pub series: Vec<ComparisonSeries>,
+    /// Unit of the weight series
+    pub weight_unit: WeightUnit,
+}
+
+impl super::PetDatabase {
+    /// Compare metrics for 2-4 pets, bucketed by `buckets`
+    pub async fn compare_pets(
+        &self,
+        pet_ids: &[i64],
+        metrics: &[ComparisonMetric],
+        buckets: &PeriodBuckets,
+        weight_unit: WeightUnit,
+    ) -> Result<PetComparison, ActivityError> {
+        validate_comparison_request(pet_ids, metrics)?;
+        log::debug!(
+            "[DB] compare_pets: pet_ids={pet_ids:?}, metrics={metrics:?}, periods={}",
+            buckets.starts.len()
+        );
+
+        let mut pets = Vec::with_capacity(pet_ids.len());
+        for &pet_id in pet_ids {
+            let pet = self
+                .get_pet_by_id(pet_id)
+                .await
+                .map_err(|e| ActivityError::validation("pet_ids", &format!("{e}")))?;
+            let activities = self
+                .export_activities(ExportActivitiesRequest {
+                    pet_id: Some(pet_id),
+                    format: None,
+                })
+                .await?;
+            pets.push((pet, activities));
+        }
+
+        Ok(build_comparison(&pets, metrics, buckets, weight_unit))
+    }
+}
+
+fn validate_comparison_request(
+    pet_ids: &[i64],
+    metrics: &[ComparisonMetric],
+) -> Result<(), ActivityError> {
+    if !(MIN_COMPARED_PETS..=MAX_COMPARED_PETS).contains(&pet_ids.len()) {
+        return Err(ActivityError::validation(
+            "pet_ids".to_string(),
+            format!("Compare between {MIN_COMPARED_PETS} and {MAX_COMPARED_PETS} pets"),
+        ));
+    }
+    let mut unique = pet_ids.to_vec();
+    unique.sort_unstable();
+    unique.dedup();
+    if unique.len() != pet_ids.len() {
+        return Err(ActivityError::validation(
+            "pet_ids",
+            "Each pet can only be compared once",
+        ));
+    }
+    if metrics.is_empty() {
+        return Err(ActivityError::validation(
+            "metrics",
+            "At least one metric is required",
+        ));
+    }
+    Ok(())
+}
+
+/// Buckets for a comparison over the last `months` months (12 by default, at most 60)
+pub fn comparison_buckets(
+    today: NaiveDate,
+    months: Option<u32>,
+    granularity: PeriodGranularity,
+    settings: PeriodSettings,
+) -> PeriodBuckets {
+    let months = months.unwrap_or(DEFAULT_COMPARISON_MONTHS).clamp(1, 60);
+    PeriodBuckets::covering_months(today, months, granularity, settings)
+}
+
+/// Amount of an activity's cost block, if any
+fn cost_amount(activity: &Activity) -> Option<f64> {
+    match activity.activity_data.as_ref()?.get("cost")? {
+        BlockData::Other(cost) => match cost.get("amount")? {
+            serde_json::Value::Number(n) => n.as_f64(),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Build aligned series for every pet and metric
+fn build_comparison(
+    pets: &[(Pet, Vec<Activity>)],
+    metrics: &[ComparisonMetric],
+    buckets: &PeriodBuckets,
+    weight_unit: WeightUnit,
+) -> PetComparison {
+    let periods = &buckets.starts;
+    let mut series = Vec::with_capacity(pets.len() * metrics.len());
+
+    for metric in metrics {
+        for (pet, activities) in pets {
+            let mut values: Vec<Option<f64>> = match metric {
+                ComparisonMetric::Weight => vec![None; periods.len()],
+                _ => vec![Some(0.0); periods.len()],
+            };
+            // Latest weight per period wins
+            let mut weight_dates: Vec<Option<(NaiveDate, i64)>> = vec![None; periods.len()];
+
+            for activity in activities {
+                let date = activity.occurred_on();
+                let Some(index) = buckets.index_of(date) else {
+                    continue;
+                };
+
+                match metric {
+                    ComparisonMetric::ActivityCount => {
+                        values[index] = values[index].map(|v| v + 1.0);
+                    }
+                    ComparisonMetric::Expenses => {
+                        if let Some(amount) = cost_amount(activity) {
+                            values[index] = values[index].map(|v| v + amount);
+                        }
+                    }
+                    ComparisonMetric::Weight => {
+                        let Some(weight) = activity
+                            .activity_data
+                            .as_ref()
+                            .and_then(|d| d.extract_weight_kg())
+                        else {
+                            continue;
+                        };
+                        let key = (date, activity.id);
+                        if weight_dates[index].is_none_or(|latest| key > latest) {
+                            weight_dates[index] = Some(key);
+                            let value = weight_unit.from_kg(weight) as f64;
+                            values[index] = Some((value * 100.0).round() / 100.0);
+                        }
+                    }
+                }
+            }
+
+            series.push(ComparisonSeries {
+                pet_id: pet.id,
+                pet_name: pet.name.clone(),
+                metric: *metric,
+                values,
+            });
+        }
+    }
+
+    PetComparison {
+        periods: buckets.labels(),
+        granularity: buckets.granularity,
+        series,
+        weight_unit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn pet(id: i64, name: &str) -> Pet {
+        Pet {
+            id,
+            name: name.to_string(),
+            birth_date: NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+            species: PetSpecies::Cat,
+            gender: PetGender::Unknown,
+            breed: None,
+            color: None,
+            weight_kg: None,
+            photo_path: None,
+            notes: None,
+            display_order: 0,
+            is_archived: false,
+            is_memorial: false,
+            passed_away_on: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_weight: None,
+            display_unit: WeightUnit::Kg,
+        }
+    }
+
+    fn activity(id: i64, pet_id: i64, date: &str, extra: serde_json::Value) -> Activity {
+        let mut data = serde_json::json!({ "time": { "date": date } });
+        for (key, value) in extra.as_object().unwrap() {
+            data[key] = value.clone();
+        }
+        Activity {
+            id,
+            pet_id,
+            category: ActivityCategory::Growth,
+            subcategory: "Weight".to_string(),
+            activity_data: serde_json::from_value(data).ok(),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn weight(value: &str) -> serde_json::Value {
+        serde_json::json!({
+            "weight": { "value": value, "unit": "kg", "measurementType": "weight" }
+        })
+    }
+
+    fn months(today: NaiveDate, count: u32) -> PeriodBuckets {
+        comparison_buckets(
+            today,
+            Some(count),
+            PeriodGranularity::Month,
+            PeriodSettings::default(),
+        )
+    }
+
+    #[test]
+    fn test_comparison_buckets() {
+        let today = NaiveDate::from_ymd_opt(2025, 2, 17).unwrap();
+        assert_eq!(
+            months(today, 3).starts,
+            vec![
+                NaiveDate::from_ymd_opt(2024, 12, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 2, 1).unwrap(),
+            ]
+        );
+        let default_span = comparison_buckets(
+            today,
+            None,
+            PeriodGranularity::Month,
+            PeriodSettings::default(),
+        );
+        assert_eq!(
+            default_span.starts.len(),
+            DEFAULT_COMPARISON_MONTHS as usize
+        );
+    }
+
+    #[test]
+    fn test_build_comparison_aligns_series() {
+        let periods = months(NaiveDate::from_ymd_opt(2025, 2, 17).unwrap(), 2);
+        let pets = vec![
+            (
+                pet(1, "Mochi"),
+                vec![
+                    activity(1, 1, "2025-01-05", weight("4.0")),
+                    activity(2, 1, "2025-01-20", weight("4.2")),
+                    activity(
+                        3,
+                        1,
+                        "2025-02-01",
+                        serde_json::json!({ "cost": { "amount": 12.5, "currency": "USD" } }),
+                    ),
+                    // Outside the compared months
+                    activity(4, 1, "2024-06-01", weight("3.0")),
+                ],
+            ),
+            (
+                pet(2, "Nori"),
+                vec![activity(5, 2, "2025-02-10", weight("5.1"))],
+            ),
+        ];
+
+        let comparison = build_comparison(
+            &pets,
+            &[
+                ComparisonMetric::Weight,
+                ComparisonMetric::ActivityCount,
+                ComparisonMetric::Expenses,
+            ],
+            &periods,
+            WeightUnit::Kg,
+        );
+
+        assert_eq!(comparison.periods, vec!["2025-01", "2025-02"]);
+        assert_eq!(comparison.series.len(), 6);
+        assert_eq!(comparison.series[0].values, vec![Some(4.2), None]);
+        assert_eq!(comparison.series[1].values, vec![None, Some(5.1)]);
+        assert_eq!(comparison.series[2].values, vec![Some(2.0), Some(1.0)]);
+        assert_eq!(comparison.series[4].metric, ComparisonMetric::Expenses);
+        assert_eq!(comparison.series[4].values, vec![Some(0.0), Some(12.5)]);
+    }
+
+    #[test]
+    fn test_comparison_request_validation() {
+        let metrics = [ComparisonMetric::Weight];
+        assert!(validate_comparison_request(&[1], &metrics).is_err());
+        assert!(validate_comparison_request(&[1, 2, 3, 4, 5], &metrics).is_err());
+        assert!(validate_comparison_request(&[1, 1], &metrics).is_err());
+        assert!(validate_comparison_request(&[1, 2], &[]).is_err());
+        assert!(validate_comparison_request(&[1, 2, 3], &metrics).is_ok());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::errors::ActivityError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// A past payment for a service
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostSample {
+    pub activity_id: i64,
+    pub amount: f64,
+    pub paid_at: DateTime<Utc>,
+}
+
+/// Price history of a service in one currency
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostBenchmark {
+    /// Currency of the cost blocks; None when they didn't record one
+    pub currency: Option<String>,
+    pub count: usize,
+    pub min: f64,
+    pub median: f64,
+    pub max: f64,
+    pub mean: f64,
+    /// Most recent payment
+    pub latest: CostSample,
+}
+
+/// Historical costs of a service for a pet, one benchmark per currency (most used first)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostBenchmarks {
+    pub pet_id: i64,
+    pub subcategory: String,
+    pub benchmarks: Vec<CostBenchmark>,
+}
+
+impl super::PetDatabase {
+    /// Min/median/max of what was paid for activities of `subcategory` (case-insensitive)
+    pub async fn get_cost_benchmarks(
+        &self,
+        pet_id: i64,
+        subcategory: &str,
+    ) -> Result<CostBenchmarks, ActivityError> {
+        let subcategory = subcategory.trim();
+        if subcategory.is_empty() {
+            return Err(ActivityError::validation(
+                "subcategory",
+                "Subcategory cannot be empty",
+            ));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT id, CAST(cost_amount AS REAL) AS amount, json_extract(activity_data, '$.cost.currency') AS currency,
+                   activity_time
+            FROM activities
+            WHERE pet_id = ? AND lower(subcategory) = lower(?) AND cost_amount > 0
+            ORDER BY activity_time ASC, id ASC
+            "#,
+        )
+        .bind(pet_id)
+        .bind(subcategory)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let samples = rows
+            .iter()
+            .map(|row| {
+                let paid_at: String = row.get("activity_time");
+                let paid_at = NaiveDateTime::parse_from_str(&paid_at, "%Y-%m-%d %H:%M:%S")
+                    .map_err(|e| {
+                        ActivityError::invalid_data(format!("Invalid activity time: {e}"))
+                    })?
+                    .and_utc();
+                let currency: Option<String> = row.get("currency");
+                Ok((
+                    currency
+                        .map(|c| c.trim().to_uppercase())
+                        .filter(|c| !c.is_empty()),
+                    CostSample {
+                        activity_id: row.get("id"),
+                        amount: row.get("amount"),
+                        paid_at,
+                    },
+                ))
+            })
+            .collect::<Result<Vec<_>, ActivityError>>()?;
+
+        log::debug!(
+            "[DB] get_cost_benchmarks: pet_id={pet_id}, subcategory={subcategory}, samples={}",
+            samples.len()
+        );
+        Ok(CostBenchmarks {
+            pet_id,
+            subcategory: subcategory.to_string(),
+            benchmarks: build_cost_benchmarks(samples),
+        })
+    }
+}
+
+/// Group samples (oldest first) by currency and summarize each group
+pub fn build_cost_benchmarks(samples: Vec<(Option<String>, CostSample)>) -> Vec<CostBenchmark> {
+    let mut groups: Vec<(Option<String>, Vec<CostSample>)> = Vec::new();
+    for (currency, sample) in samples {
+        match groups.iter_mut().find(|(c, _)| *c == currency) {
+            Some((_, group)) => group.push(sample),
+            None => groups.push((currency, vec![sample])),
+        }
+    }
+
+    let mut benchmarks: Vec<CostBenchmark> = groups
+        .into_iter()
+        .filter_map(|(currency, group)| {
+            let latest = group.last()?.clone();
+            let mut amounts: Vec<f64> = group.iter().map(|s| s.amount).collect();
+            amounts.sort_by(f64::total_cmp);
+            Some(CostBenchmark {
+                currency,
+                count: amounts.len(),
+                min: amounts[0],
+                median: median(&amounts),
+                max: amounts[amounts.len() - 1],
+                mean: amounts.iter().sum::<f64>() / amounts.len() as f64,
+                latest,
+            })
+        })
+        .collect();
+    benchmarks.sort_by_key(|b| std::cmp::Reverse(b.count));
+    benchmarks
+}
+
+/// Median of sorted, non-empty values
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2.0
+    } else {
+        sorted[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::super::*;
+    use super::*;
+
+    #[test]
+    fn test_build_cost_benchmarks_groups_by_currency() {
+        let sample = |id, amount| CostSample {
+            activity_id: id,
+            amount,
+            paid_at: Utc::now(),
+        };
+        let usd = Some("USD".to_string());
+        let benchmarks = build_cost_benchmarks(vec![
+            (usd.clone(), sample(1, 180.0)),
+            (Some("EUR".to_string()), sample(2, 150.0)),
+            (usd.clone(), sample(3, 120.0)),
+            (usd.clone(), sample(4, 240.0)),
+            (usd.clone(), sample(5, 200.0)),
+        ]);
+
+        assert_eq!(benchmarks.len(), 2);
+        let usd_benchmark = &benchmarks[0];
+        assert_eq!(usd_benchmark.currency, usd);
+        assert_eq!(usd_benchmark.count, 4);
+        assert_eq!(usd_benchmark.min, 120.0);
+        assert_eq!(usd_benchmark.median, 190.0);
+        assert_eq!(usd_benchmark.max, 240.0);
+        assert_eq!(usd_benchmark.mean, 185.0);
+        assert_eq!(usd_benchmark.latest.activity_id, 5);
+        assert_eq!(benchmarks[1].median, 150.0);
+    }
+
+    #[tokio::test]
+    async fn test_get_cost_benchmarks_matches_subcategory() {
+        let (db, _dir) = test_database().await;
+        let pet = db
+            .create_pet(CreatePetRequest {
+                name: "Biscuit".to_string(),
+                birth_date: chrono::NaiveDate::from_ymd_opt(2021, 6, 1).unwrap(),
+                species: PetSpecies::Dog,
+                gender: PetGender::Male,
+                breed: None,
+                color: None,
+                weight_kg: None,
+                photo_path: None,
+                notes: None,
+            })
+            .await
+            .unwrap();
+
+        for (subcategory, date, amount) in [
+            ("Dental Cleaning", "2023-05-01", 300),
+            ("dental cleaning", "2024-05-01", 360),
+            ("Dental Cleaning", "2025-05-01", 0),
+            ("Grooming", "2025-05-02", 60),
+        ] {
+            db.create_activity_with_side_effects(ActivityCreateRequest {
+                pet_id: pet.id,
+                category: ActivityCategory::Expense,
+                subcategory: subcategory.to_string(),
+                activity_data: Some(serde_json::json!({
+                    "time": { "date": format!("{date}T10:00:00.000Z") },
+                    "cost": { "amount": amount, "currency": "usd" }
+                })),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+        }
+
+        let result = db
+            .get_cost_benchmarks(pet.id, " Dental cleaning ")
+            .await
+            .unwrap();
+        assert_eq!(result.benchmarks.len(), 1);
+        let benchmark = &result.benchmarks[0];
+        assert_eq!(benchmark.currency.as_deref(), Some("USD"));
+        assert_eq!(benchmark.count, 2);
+        assert_eq!(benchmark.median, 330.0);
+        assert_eq!(benchmark.latest.amount, 360.0);
+        assert_eq!(
+            benchmark.latest.paid_at.date_naive(),
+            chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()
+        );
+
+        assert!(db.get_cost_benchmarks(pet.id, "  ").await.is_err());
+    }
+}
@@ -0,0 +1,260 @@
+use super::models::*;
+use crate::errors::ActivityError;
+use crate::validation::validate_custom_block_definition;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+
+impl super::PetDatabase {
+    /// Define a custom block type
+    pub async fn create_custom_block(
+        &self,
+        request: CreateCustomBlockRequest,
+    ) -> Result<CustomBlockDefinition, ActivityError> {
+        validate_custom_block_definition(&request.name, &request.label, &request.fields)?;
+
+        let taken: bool =
+            sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM custom_blocks WHERE name = ?)")
+                .bind(&request.name)
+                .fetch_one(&self.read_pool)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        if taken {
+            return Err(ActivityError::validation(
+                "name".to_string(),
+                format!("A custom block named '{}' already exists", request.name),
+            ));
+        }
+
+        let now = Utc::now();
+        let id = sqlx::query(
+            r#"
+            INSERT INTO custom_blocks (name, label, fields, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&request.name)
+        .bind(request.label.trim())
+        .bind(fields_json(&request.fields)?)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+        .last_insert_rowid();
+
+        log::info!(
+            "[DB] create_custom_block: id={id}, name={}, fields={}",
+            request.name,
+            request.fields.len()
+        );
+        self.get_custom_block(id).await
+    }
+
+    /// Get a custom block definition by ID
+    pub async fn get_custom_block(&self, id: i64) -> Result<CustomBlockDefinition, ActivityError> {
+        let row = sqlx::query("SELECT * FROM custom_blocks WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .ok_or_else(|| ActivityError::custom_block_not_found(id))?;
+        row_to_custom_block(&row)
+    }
+
+    /// All custom block definitions, by label
+    pub async fn get_custom_blocks(&self) -> Result<Vec<CustomBlockDefinition>, ActivityError> {
+        let rows = sqlx::query("SELECT * FROM custom_blocks ORDER BY label COLLATE NOCASE, id")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        rows.iter().map(row_to_custom_block).collect()
+    }
+
+    /// Change a definition's label or fields. Stored instances are not rewritten;
+    /// the new fields apply when an activity is next saved.
+    pub async fn update_custom_block(
+        &self,
+        id: i64,
+        updates: CustomBlockUpdateRequest,
+    ) -> Result<CustomBlockDefinition, ActivityError> {
+        let current = self.get_custom_block(id).await?;
+        let label = updates.label.unwrap_or(current.label);
+        let fields = updates.fields.unwrap_or(current.fields);
+        validate_custom_block_definition(&current.name, &label, &fields)?;
+
+        sqlx::query("UPDATE custom_blocks SET label = ?, fields = ?, updated_at = ? WHERE id = ?")
+            .bind(label.trim())
+            .bind(fields_json(&fields)?)
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::info!("[DB] update_custom_block: id={id}, name={}", current.name);
+        self.get_custom_block(id).await
+    }
+
+    /// Delete a definition no activity uses any more
+    pub async fn delete_custom_block(&self, id: i64) -> Result<(), ActivityError> {
+        let definition = self.get_custom_block(id).await?;
+        let in_use = self.count_custom_block_uses(&definition.name).await?;
+        if in_use > 0 {
+            return Err(ActivityError::validation(
+                "id".to_string(),
+                format!(
+                    "'{}' is used by {in_use} activities; remove those blocks first",
+                    definition.label
+                ),
+            ));
+        }
+
+        sqlx::query("DELETE FROM custom_blocks WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        log::info!(
+            "[DB] delete_custom_block: id={id}, name={}",
+            definition.name
+        );
+        Ok(())
+    }
+
+    /// Number of activities with an instance of the named block
+    pub async fn count_custom_block_uses(&self, name: &str) -> Result<i64, ActivityError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(DISTINCT activities.id)
+            FROM activities, json_each(activities.activity_data) AS block
+            WHERE json_valid(activities.activity_data)
+                AND block.type = 'object'
+                AND json_extract(block.value, '$.customBlock') = ?
+            "#,
+        )
+        .bind(name)
+        .fetch_one(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+    }
+}
+
+fn fields_json(fields: &[CustomFieldDefinition]) -> Result<String, ActivityError> {
+    serde_json::to_string(fields)
+        .map_err(|e| ActivityError::invalid_data(format!("Invalid field definitions: {e}")))
+}
+
+fn row_to_custom_block(
+    row: &sqlx::sqlite::SqliteRow,
+) -> Result<CustomBlockDefinition, ActivityError> {
+    let fields: String = row.get("fields");
+    Ok(CustomBlockDefinition {
+        id: row.get("id"),
+        name: row.get("name"),
+        label: row.get("label"),
+        fields: serde_json::from_str(&fields)
+            .map_err(|e| ActivityError::invalid_data(format!("Invalid field definitions: {e}")))?,
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+        updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_custom_block_lifecycle_and_search() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+
+        let definition = db
+            .create_custom_block(CreateCustomBlockRequest {
+                name: "insulin".to_string(),
+                label: "Insulin".to_string(),
+                fields: vec![
+                    CustomFieldDefinition {
+                        key: "units".to_string(),
+                        label: "Units".to_string(),
+                        field_type: CustomFieldType::Number,
+                        required: true,
+                        unit: Some("IU".to_string()),
+                        min: Some(0.0),
+                        max: Some(40.0),
+                        max_length: None,
+                        options: Vec::new(),
+                    },
+                    CustomFieldDefinition {
+                        key: "pen".to_string(),
+                        label: "Pen".to_string(),
+                        field_type: CustomFieldType::Text,
+                        required: false,
+                        unit: None,
+                        min: None,
+                        max: None,
+                        max_length: None,
+                        options: Vec::new(),
+                    },
+                ],
+            })
+            .await
+            .unwrap();
+        assert_eq!(definition.fields.len(), 2);
+        let duplicate = CreateCustomBlockRequest {
+            name: "insulin".to_string(),
+            label: "Insulin again".to_string(),
+            fields: definition.fields.clone(),
+        };
+        assert!(db.create_custom_block(duplicate).await.is_err());
+
+        let activity = db
+            .create_activity(ActivityCreateRequest {
+                pet_id: summary.pet_ids[0],
+                category: ActivityCategory::Health,
+                subcategory: "Medication".to_string(),
+                activity_data: Some(json!({
+                    "insulin": {
+                        "customBlock": "insulin",
+                        "values": { "units": 4, "pen": "Caninsulin VetPen" }
+                    }
+                })),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            activity.activity_data.as_ref().unwrap().get("insulin"),
+            Some(super::super::activity_data::BlockData::Custom { .. })
+        ));
+
+        // Text fields of instances are searchable
+        let hits = db.fts_search_activities("vetpen", Some(10)).await.unwrap();
+        assert_eq!(hits.len(), 1);
+
+        assert_eq!(db.count_custom_block_uses("insulin").await.unwrap(), 1);
+        assert!(db.delete_custom_block(definition.id).await.is_err());
+
+        let updated = db
+            .update_custom_block(
+                definition.id,
+                CustomBlockUpdateRequest {
+                    label: Some("Insulin dose".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(updated.label, "Insulin dose");
+        assert_eq!(updated.name, "insulin");
+
+        db.delete_activity(activity.id).await.unwrap();
+        db.delete_custom_block(definition.id).await.unwrap();
+        assert!(db.get_custom_blocks().await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,375 @@
+use super::models::*;
+use super::ActivityBlocksBuilder;
+use crate::errors::{ActivityError, PetError};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Settings key recording the rows created by `seed_demo_data`
+pub const DEMO_DATA_KEY: &str = "demo_data";
+
+/// Number of days of activities the demo pet gets, ending today
+pub const DEMO_DAYS: u64 = 21;
+
+/// Rows and photos created for the demo pet, so they can be removed again
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DemoData {
+    pub pet_ids: Vec<i64>,
+    pub activity_ids: Vec<i64>,
+    /// Stored copies of the bundled demo photos
+    pub photos: Vec<String>,
+}
+
+/// Stored filenames of the bundled demo photos
+#[derive(Debug, Clone)]
+pub struct DemoPhotos {
+    pub profile: String,
+    pub walk: String,
+    pub meal: String,
+}
+
+/// What `remove_demo_data` removed
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DemoDataRemoval {
+    pub activities_removed: usize,
+    pub pets_removed: Vec<i64>,
+    /// Demo pets kept because the user logged their own activities for them
+    pub pets_kept: Vec<i64>,
+    /// Profile photos of the removed pets, for the caller to delete
+    #[serde(skip)]
+    pub photos: Vec<String>,
+    /// Attachment files journaled by the removed activities
+    #[serde(skip)]
+    pub pending_files: Vec<PendingFileDeletion>,
+}
+
+impl super::PetDatabase {
+    /// Rows created by `seed_demo_data`, None when no demo data is loaded
+    pub async fn get_demo_data(&self) -> Result<Option<DemoData>, PetError> {
+        Ok(self.get_setting::<DemoData>(DEMO_DATA_KEY).await?)
+    }
+
+    /// Create an example pet with `DEMO_DAYS` days of meals, walks, weigh-ins and
+    /// vet visits up to `today`, using the already stored demo photos. Fails when
+    /// demo data is already loaded; a failure part way removes what was created.
+    pub async fn seed_demo_data(
+        &self,
+        photos: &DemoPhotos,
+        today: NaiveDate,
+    ) -> Result<DemoData, PetError> {
+        if self.get_demo_data().await?.is_some() {
+            return Err(PetError::validation(
+                "demo_data",
+                "Demo data is already loaded",
+            ));
+        }
+
+        let mut demo = DemoData {
+            photos: vec![
+                photos.profile.clone(),
+                photos.walk.clone(),
+                photos.meal.clone(),
+            ],
+            ..Default::default()
+        };
+        if let Err(e) = self.insert_demo_rows(photos, today, &mut demo).await {
+            log::error!("[DB] seed_demo_data: seeding failed, removing partial data: {e}");
+            if let Err(cleanup) = self.remove_demo_rows(&demo).await {
+                log::error!("[DB] seed_demo_data: failed to remove partial data: {cleanup}");
+            }
+            return Err(e);
+        }
+        self.set_setting(DEMO_DATA_KEY, &demo).await?;
+
+        log::info!(
+            "[DB] seed_demo_data: pets={:?}, activities={}",
+            demo.pet_ids,
+            demo.activity_ids.len()
+        );
+        Ok(demo)
+    }
+
+    /// Remove the demo activities and pets. A demo pet the user has logged their
+    /// own activities for is kept with those activities.
+    pub async fn remove_demo_data(&self) -> Result<DemoDataRemoval, PetError> {
+        let Some(demo) = self.get_demo_data().await? else {
+            return Err(PetError::validation("demo_data", "No demo data is loaded"));
+        };
+        let removal = self.remove_demo_rows(&demo).await?;
+        sqlx::query("DELETE FROM app_settings WHERE key = ?")
+            .bind(DEMO_DATA_KEY)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!(
+            "[DB] remove_demo_data: activities={}, pets_removed={:?}, pets_kept={:?}",
+            removal.activities_removed,
+            removal.pets_removed,
+            removal.pets_kept
+        );
+        Ok(removal)
+    }
+
+    async fn insert_demo_rows(
+        &self,
+        photos: &DemoPhotos,
+        today: NaiveDate,
+        demo: &mut DemoData,
+    ) -> Result<(), PetError> {
+        let pet = self
+            .create_pet(CreatePetRequest {
+                name: "Biscuit".to_string(),
+                birth_date: today
+                    .checked_sub_days(Days::new(2 * 365 + 40))
+                    .unwrap_or(today),
+                species: PetSpecies::Dog,
+                gender: PetGender::Female,
+                breed: Some("Beagle".to_string()),
+                color: Some("Tricolor".to_string()),
+                weight_kg: Some(10.2),
+                photo_path: Some(photos.profile.clone()),
+                notes: Some("Example pet to explore the app. Remove it from Settings.".to_string()),
+            })
+            .await?;
+        demo.pet_ids.push(pet.id);
+
+        let first_day = today
+            .checked_sub_days(Days::new(DEMO_DAYS - 1))
+            .unwrap_or(today);
+        for request in demo_activities(pet.id, first_day, photos)? {
+            let activity = self
+                .create_activity_with_side_effects(request)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?;
+            demo.activity_ids.push(activity.id);
+        }
+        Ok(())
+    }
+
+    async fn remove_demo_rows(&self, demo: &DemoData) -> Result<DemoDataRemoval, PetError> {
+        let mut removal = DemoDataRemoval::default();
+        for &activity_id in &demo.activity_ids {
+            match self.delete_activity(activity_id).await {
+                Ok(pending) => {
+                    removal.activities_removed += 1;
+                    removal.pending_files.extend(pending);
+                }
+                // Already deleted by the user
+                Err(ActivityError::NotFound { .. }) => {}
+                Err(e) => return Err(PetError::database(e.to_string())),
+            }
+        }
+
+        for &pet_id in &demo.pet_ids {
+            let Ok(pet) = self.get_pet_by_id(pet_id).await else {
+                continue;
+            };
+            let deleted = sqlx::query(
+                "DELETE FROM pets WHERE id = ? AND NOT EXISTS (SELECT 1 FROM activities WHERE pet_id = ?)",
+            )
+            .bind(pet_id)
+            .bind(pet_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?
+            .rows_affected();
+            if deleted == 0 {
+                removal.pets_kept.push(pet_id);
+                continue;
+            }
+            removal.pets_removed.push(pet_id);
+            removal
+                .photos
+                .extend(pet.photo_path.filter(|photo| demo.photos.contains(photo)));
+        }
+        Ok(removal)
+    }
+}
+
+/// Activities of the demo pet from `first_day` on: two meals a day, a walk every
+/// other day, weekly weigh-ins and a few one-off health and expense entries
+fn demo_activities(
+    pet_id: i64,
+    first_day: NaiveDate,
+    photos: &DemoPhotos,
+) -> Result<Vec<ActivityCreateRequest>, PetError> {
+    let at = |day: u64, hour: u32| -> DateTime<Utc> {
+        (first_day + Days::new(day))
+            .and_hms_opt(hour, 0, 0)
+            .unwrap_or_default()
+            .and_utc()
+    };
+    let request = |category: ActivityCategory, subcategory: &str, data: serde_json::Value| {
+        ActivityCreateRequest {
+            pet_id,
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(data),
+            needs_review: false,
+        }
+    };
+    let attachment = |photo: &str, at: DateTime<Utc>| {
+        serde_json::json!([{
+            "id": format!("demo-{photo}"),
+            "filename": photo,
+            "originalName": photo,
+            "mimeType": "image/png",
+            "path": photo,
+            "uploadedAt": at.to_rfc3339(),
+        }])
+    };
+    let invalid = |e: ActivityError| PetError::validation("demo_data".to_string(), e.to_string());
+
+    let mut requests = Vec::new();
+    for day in 0..DEMO_DAYS {
+        for (hour, title, grams) in [(8, "Breakfast", 90.0), (18, "Dinner", 100.0)] {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(at(day, hour)).title(title);
+            blocks
+                .portion(grams, "g", "meal", Some("Acana"))
+                .map_err(invalid)?;
+            let mut data = blocks.into_json();
+            if day == DEMO_DAYS - 2 && hour == 18 {
+                data["notes"] = serde_json::json!("Tried the new salmon recipe");
+                data["attachment"] = attachment(&photos.meal, at(day, hour));
+            }
+            requests.push(request(ActivityCategory::Diet, title, data));
+        }
+
+        if day % 2 == 0 {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(at(day, 17)).title("Evening walk");
+            blocks
+                .duration(30.0 + (day % 4) as f64 * 5.0, "min", "walk")
+                .map_err(invalid)?;
+            blocks
+                .distance(2.0 + (day % 3) as f64 * 0.5, "km", "walk")
+                .map_err(invalid)?;
+            let mut data = blocks.into_json();
+            data["location"] = serde_json::json!({
+                "name": "Riverside Park",
+                "coordinates": { "lat": 40.8007, "lng": -73.9726 },
+            });
+            if day == 0 {
+                data["attachment"] = attachment(&photos.walk, at(day, 17));
+            }
+            requests.push(request(ActivityCategory::Lifestyle, "Walk", data));
+        }
+
+        if day % 7 == 0 {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(at(day, 9));
+            blocks
+                .weight(10.2 + (day / 7) as f64 * 0.1, "kg")
+                .map_err(invalid)?;
+            requests.push(request(
+                ActivityCategory::Growth,
+                "Weight",
+                blocks.into_json(),
+            ));
+        }
+    }
+
+    let mut vaccination = ActivityBlocksBuilder::new();
+    vaccination
+        .time(at(5, 10))
+        .title("Rabies booster")
+        .notes("No reaction, very brave");
+    vaccination.cost(45.0, "USD").map_err(invalid)?;
+    requests.push(request(
+        ActivityCategory::Health,
+        "Vaccination",
+        vaccination.into_json(),
+    ));
+
+    let mut grooming = ActivityBlocksBuilder::new();
+    grooming
+        .time(at(12, 11))
+        .title("Bath and nail trim")
+        .notes("Nails were getting long");
+    requests.push(request(
+        ActivityCategory::Lifestyle,
+        "Grooming",
+        grooming.into_json(),
+    ));
+
+    let mut toy = ActivityBlocksBuilder::new();
+    toy.time(at(15, 14)).title("Squeaky squirrel toy");
+    toy.cost(14.99, "USD").map_err(invalid)?;
+    requests.push(request(ActivityCategory::Expense, "Toys", toy.into_json()));
+
+    requests.sort_by_key(|request| {
+        request
+            .activity_data
+            .as_ref()
+            .and_then(|data| data["time"]["date"].as_str().map(str::to_string))
+    });
+    Ok(requests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_demo_data_is_removed_without_touching_user_rows() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 3,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let photos = DemoPhotos {
+            profile: "demo-profile.png".to_string(),
+            walk: "demo-walk.png".to_string(),
+            meal: "demo-meal.png".to_string(),
+        };
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        let demo = db.seed_demo_data(&photos, today).await.unwrap();
+        assert_eq!(demo.pet_ids.len(), 1);
+        // Two meals a day, a walk every other day, three weigh-ins and three one-offs
+        assert_eq!(demo.activity_ids.len(), 42 + 11 + 3 + 3);
+        assert_eq!(db.get_demo_data().await.unwrap(), Some(demo.clone()));
+        assert!(db.seed_demo_data(&photos, today).await.is_err());
+
+        let removal = db.remove_demo_data().await.unwrap();
+        assert_eq!(removal.activities_removed, demo.activity_ids.len());
+        assert_eq!(removal.pets_removed, demo.pet_ids);
+        assert_eq!(removal.photos, vec![photos.profile.clone()]);
+        let mut attachments: Vec<&str> = removal
+            .pending_files
+            .iter()
+            .map(|entry| entry.file_name.as_str())
+            .collect();
+        attachments.sort();
+        assert_eq!(attachments, vec!["demo-meal.png", "demo-walk.png"]);
+        assert!(db.get_pet_by_id(demo.pet_ids[0]).await.is_err());
+        assert!(db.get_demo_data().await.unwrap().is_none());
+
+        // The fixture pet and its activities are untouched
+        let pet_id = summary.pet_ids[0];
+        assert!(db.get_pet_by_id(pet_id).await.is_ok());
+        for &activity_id in &summary.activity_ids {
+            assert!(db.get_activity_by_id(activity_id).await.is_ok());
+        }
+
+        // A demo pet the user logged for is kept with the user's activity
+        let demo = db.seed_demo_data(&photos, today).await.unwrap();
+        let own = db
+            .create_activity(ActivityCreateRequest {
+                pet_id: demo.pet_ids[0],
+                category: ActivityCategory::Lifestyle,
+                subcategory: "Play".to_string(),
+                activity_data: None,
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+        let removal = db.remove_demo_data().await.unwrap();
+        assert_eq!(removal.pets_kept, demo.pet_ids);
+        assert!(removal.photos.is_empty());
+        assert!(db.get_activity_by_id(own.id).await.is_ok());
+    }
+}
@@ -0,0 +1,261 @@
+use super::models::WeightUnit;
+use crate::errors::PetError;
+use serde::{Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A stored value that was recalculated to something else
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DerivedChange<T> {
+    pub before: T,
+    pub after: T,
+}
+
+/// Cached activity count of one category that didn't match the activities
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivityCountChange {
+    pub category: String,
+    pub total: DerivedChange<i64>,
+    pub needs_review: DerivedChange<i64>,
+}
+
+/// What recalculating a pet's derived data changed; empty when everything was current
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DerivedDataReport {
+    pub pet_id: i64,
+    /// Profile weight, set from the latest weight measurement
+    pub weight_kg: Option<DerivedChange<Option<f32>>>,
+    /// Rows of the activity counts cache that were rewritten
+    pub activity_counts: Vec<ActivityCountChange>,
+}
+
+impl DerivedDataReport {
+    /// Whether anything was out of date
+    pub fn changed(&self) -> bool {
+        self.weight_kg.is_some() || !self.activity_counts.is_empty()
+    }
+}
+
+impl super::PetDatabase {
+    /// Recompute what is stored about a pet but derived from its activities: the
+    /// profile weight and the cached activity counts. Hooks and triggers keep them
+    /// current; this repairs them after imports or edits made outside the app.
+    /// A pet without weight measurements keeps the weight entered on its profile.
+    pub async fn recalculate_pet_derived_data(
+        &self,
+        pet_id: i64,
+    ) -> Result<DerivedDataReport, PetError> {
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|_| PetError::not_found(pet_id))?;
+        let latest_weight = self
+            .get_weight_history(pet_id, WeightUnit::Kg)
+            .await?
+            .records
+            .last()
+            .map(|record| record.weight_kg);
+
+        let _permit = self.writes.acquire_for_task().await;
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        let weight_kg = match latest_weight {
+            Some(weight) if pet.weight_kg != Some(weight) => {
+                sqlx::query("UPDATE pets SET weight_kg = ?, updated_at = ? WHERE id = ?")
+                    .bind(weight)
+                    .bind(chrono::Utc::now())
+                    .bind(pet_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| PetError::database(e.to_string()))?;
+                Some(DerivedChange {
+                    before: pet.weight_kg,
+                    after: Some(weight),
+                })
+            }
+            _ => None,
+        };
+
+        let cached = category_counts(
+            &mut tx,
+            "SELECT category, total, needs_review FROM activity_counts WHERE pet_id = ?",
+            pet_id,
+        )
+        .await?;
+        let actual = category_counts(
+            &mut tx,
+            r#"
+            SELECT category, COUNT(*) AS total, COALESCE(SUM(needs_review), 0) AS needs_review
+            FROM activities
+            WHERE pet_id = ?
+            GROUP BY category
+            "#,
+            pet_id,
+        )
+        .await?;
+
+        let categories: BTreeSet<&String> = cached.keys().chain(actual.keys()).collect();
+        let mut activity_counts = Vec::new();
+        for category in categories {
+            let before = cached.get(category).copied().unwrap_or_default();
+            let after = actual.get(category).copied().unwrap_or_default();
+            if before != after {
+                activity_counts.push(ActivityCountChange {
+                    category: category.clone(),
+                    total: DerivedChange {
+                        before: before.0,
+                        after: after.0,
+                    },
+                    needs_review: DerivedChange {
+                        before: before.1,
+                        after: after.1,
+                    },
+                });
+            }
+        }
+        if !activity_counts.is_empty() {
+            sqlx::query("DELETE FROM activity_counts WHERE pet_id = ?")
+                .bind(pet_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?;
+            for (category, (total, needs_review)) in &actual {
+                sqlx::query(
+                    "INSERT INTO activity_counts (pet_id, category, total, needs_review) VALUES (?, ?, ?, ?)",
+                )
+                .bind(pet_id)
+                .bind(category)
+                .bind(total)
+                .bind(needs_review)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        let report = DerivedDataReport {
+            pet_id,
+            weight_kg,
+            activity_counts,
+        };
+        log::info!(
+            "[DB] recalculate_pet_derived_data: pet_id={pet_id}, weight_changed={}, counts_changed={}",
+            report.weight_kg.is_some(),
+            report.activity_counts.len()
+        );
+        Ok(report)
+    }
+}
+
+/// (total, needs review) per category from a query selecting those columns
+async fn category_counts(
+    conn: &mut SqliteConnection,
+    sql: &str,
+    pet_id: i64,
+) -> Result<BTreeMap<String, (i64, i64)>, PetError> {
+    let rows = sqlx::query(sql)
+        .bind(pet_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            (
+                row.get::<String, _>("category"),
+                (row.get("total"), row.get("needs_review")),
+            )
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityBlocksBuilder, ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_recalculate_repairs_weight_and_counts() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 10,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(Utc.with_ymd_and_hms(2030, 1, 1, 9, 0, 0).unwrap());
+        blocks.weight(7.5, "kg").unwrap();
+        db.create_activity_with_side_effects(ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Growth,
+            subcategory: "Weight".to_string(),
+            activity_data: Some(blocks.into_json()),
+            needs_review: false,
+        })
+        .await
+        .unwrap();
+
+        // Nothing to do while the hooks and triggers kept up
+        let report = db.recalculate_pet_derived_data(pet_id).await.unwrap();
+        assert!(!report.changed(), "{report:?}");
+
+        // Edits made outside the app
+        sqlx::query("UPDATE pets SET weight_kg = 3.0 WHERE id = ?")
+            .bind(pet_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "UPDATE activity_counts SET total = total + 5 WHERE pet_id = ? AND category = 'growth'",
+        )
+        .bind(pet_id)
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO activity_counts (pet_id, category, total, needs_review) VALUES (?, 'bogus', 2, 0)")
+            .bind(pet_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let report = db.recalculate_pet_derived_data(pet_id).await.unwrap();
+        assert_eq!(
+            report.weight_kg,
+            Some(DerivedChange {
+                before: Some(3.0),
+                after: Some(7.5)
+            })
+        );
+        let categories: Vec<&str> = report
+            .activity_counts
+            .iter()
+            .map(|change| change.category.as_str())
+            .collect();
+        assert_eq!(categories, vec!["bogus", "growth"]);
+        assert_eq!(report.activity_counts[0].total.after, 0);
+        assert_eq!(
+            report.activity_counts[1].total.before - report.activity_counts[1].total.after,
+            5
+        );
+
+        assert_eq!(db.get_pet_by_id(pet_id).await.unwrap().weight_kg, Some(7.5));
+        assert!(!db
+            .recalculate_pet_derived_data(pet_id)
+            .await
+            .unwrap()
+            .changed());
+        assert!(db.recalculate_pet_derived_data(pet_id + 100).await.is_err());
+    }
+}
@@ -0,0 +1,506 @@
+use crate::errors::ActivityError;
+use serde::{Deserialize, Serialize};
+use sqlx::{Column, Row, TypeInfo, ValueRef};
+use std::time::{Duration, Instant};
+
+/// Rows returned by a diagnostic query unless the caller asks for fewer
+pub const DEFAULT_DIAGNOSTIC_ROWS: usize = 100;
+const MAX_DIAGNOSTIC_ROWS: usize = 1000;
+
+/// Longest a diagnostic query may run
+const DIAGNOSTIC_QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest accepted diagnostic SQL text
+const MAX_DIAGNOSTIC_SQL_LENGTH: usize = 4000;
+
+/// Value shown instead of the content of a sensitive column
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Columns holding personal free text or file locations. They are redacted in
+/// results and may not be referenced directly, so they can't leak through
+/// aliases, filters or `json_extract`.
+const SENSITIVE_COLUMNS: &[&str] = &[
+    "notes",
+    "ocr_text",
+    "photo_path",
+    "stored_filename",
+    "original_filename",
+    "sha256",
+    // Activity JSON with notes, locations and costs, also copied to the trash
+    // and into webhook and job payloads
+    "activity_data",
+    "payload",
+    "location_key",
+    "legacy_value",
+    "new_value",
+    // Setting and pet default values, e.g. the webhook URL
+    "value",
+];
+
+/// Full-text indexes of sensitive columns; a MATCH filter would reveal their content
+const SENSITIVE_TABLES: &[&str] = &["activities_fts", "pet_documents_fts"];
+
+/// Virtual machine instructions between deadline checks of a diagnostic query
+const DIAGNOSTIC_PROGRESS_OPS: i32 = 1000;
+
+/// Keywords of statements that change data or the connection
+const FORBIDDEN_KEYWORDS: &[&str] = &[
+    "insert",
+    "update",
+    "delete",
+    "replace",
+    "upsert",
+    "drop",
+    "alter",
+    "create",
+    "attach",
+    "detach",
+    "pragma",
+    "vacuum",
+    "reindex",
+    "analyze",
+    "begin",
+    "commit",
+    "rollback",
+    "savepoint",
+    "release",
+    "load_extension",
+];
+
+/// Result of a read-only diagnostic query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticQueryResult {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<serde_json::Value>>,
+    /// More rows matched than were returned
+    pub truncated: bool,
+    /// Columns whose values were replaced with [`REDACTED`]
+    pub redacted_columns: Vec<String>,
+    pub elapsed_ms: u64,
+}
+
+impl super::PetDatabase {
+    /// Run a single SELECT statement on a read-only connection with row and time limits
+    pub async fn run_diagnostic_query(
+        &self,
+        sql: &str,
+        max_rows: Option<usize>,
+    ) -> Result<DiagnosticQueryResult, ActivityError> {
+        let statement = validate_diagnostic_sql(sql)?;
+        let max_rows = max_rows
+            .unwrap_or(DEFAULT_DIAGNOSTIC_ROWS)
+            .clamp(1, MAX_DIAGNOSTIC_ROWS);
+
+        log::info!("[DB] run_diagnostic_query: max_rows={max_rows}, sql={statement}");
+
+        let mut conn = self
+            .read_pool
+            .acquire()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        // Read connections already refuse writes; the in-memory database has only one
+        let already_read_only: bool = sqlx::query_scalar("PRAGMA query_only")
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        // SQLite itself refuses writes on this connection, whatever the statement does
+        sqlx::query("PRAGMA query_only = ON")
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        // SQLite checks the deadline while the statement runs and interrupts it,
+        // so a slow query doesn't keep the connection busy after the timeout
+        let started = Instant::now();
+        let deadline = started + DIAGNOSTIC_QUERY_TIMEOUT;
+        conn.lock_handle()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .set_progress_handler(DIAGNOSTIC_PROGRESS_OPS, move || Instant::now() < deadline);
+
+        // Fetch one extra row to know whether the result was cut off
+        let limited = format!("SELECT * FROM ({statement}) LIMIT {}", max_rows + 1);
+        let outcome = sqlx::query(&limited).fetch_all(&mut *conn).await;
+
+        let mut restored = conn
+            .lock_handle()
+            .await
+            .map(|mut handle| handle.remove_progress_handler());
+        if restored.is_ok() && !already_read_only {
+            restored = sqlx::query("PRAGMA query_only = OFF")
+                .execute(&mut *conn)
+                .await
+                .map(|_| ());
+        }
+        if let Err(e) = restored {
+            // Don't hand a read-only connection back to the pool
+            conn.detach();
+            return Err(ActivityError::invalid_data(format!("Database error: {e}")));
+        }
+
+        let rows = outcome.map_err(|e| {
+            if Instant::now() >= deadline {
+                ActivityError::validation(
+                    "sql".to_string(),
+                    format!(
+                        "Query took longer than {} seconds",
+                        DIAGNOSTIC_QUERY_TIMEOUT.as_secs()
+                    ),
+                )
+            } else {
+                ActivityError::validation("sql".to_string(), format!("{e}"))
+            }
+        })?;
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+
+        let columns: Vec<String> = rows
+            .first()
+            .map(|row| row.columns().iter().map(|c| c.name().to_string()).collect())
+            .unwrap_or_default();
+        let redacted: Vec<bool> = columns.iter().map(|c| is_sensitive_column(c)).collect();
+
+        let truncated = rows.len() > max_rows;
+        let values = rows
+            .iter()
+            .take(max_rows)
+            .map(|row| {
+                (0..columns.len())
+                    .map(|i| {
+                        if redacted[i] {
+                            serde_json::Value::String(REDACTED.to_string())
+                        } else {
+                            decode_value(row, i)
+                        }
+                    })
+                    .collect()
+            })
+            .collect::<Vec<Vec<_>>>();
+
+        log::info!(
+            "[DB] run_diagnostic_query: {} rows in {elapsed_ms} ms, truncated={truncated}",
+            values.len()
+        );
+
+        Ok(DiagnosticQueryResult {
+            redacted_columns: columns
+                .iter()
+                .zip(&redacted)
+                .filter(|(_, redacted)| **redacted)
+                .map(|(column, _)| column.clone())
+                .collect(),
+            columns,
+            rows: values,
+            truncated,
+            elapsed_ms,
+        })
+    }
+}
+
+fn is_sensitive_column(name: &str) -> bool {
+    let name = name.to_lowercase();
+    SENSITIVE_COLUMNS.contains(&name.as_str())
+        || ["password", "secret", "token"]
+            .iter()
+            .any(|part| name.contains(part))
+}
+
+/// JSON value of column `index`, by the value's runtime SQLite type
+fn decode_value(row: &sqlx::sqlite::SqliteRow, index: usize) -> serde_json::Value {
+    let Ok(raw) = row.try_get_raw(index) else {
+        return serde_json::Value::Null;
+    };
+    if raw.is_null() {
+        return serde_json::Value::Null;
+    }
+    let type_name = raw.type_info().name().to_string();
+
+    match type_name.as_str() {
+        "INTEGER" => row
+            .try_get_unchecked::<i64, _>(index)
+            .map(serde_json::Value::from)
+            .unwrap_or_default(),
+        "REAL" => row
+            .try_get_unchecked::<f64, _>(index)
+            .map(serde_json::Value::from)
+            .unwrap_or_default(),
+        "BLOB" => row
+            .try_get_unchecked::<Vec<u8>, _>(index)
+            .map(|bytes| serde_json::Value::from(format!("<blob {} bytes>", bytes.len())))
+            .unwrap_or_default(),
+        _ => row
+            .try_get_unchecked::<String, _>(index)
+            .map(serde_json::Value::from)
+            .unwrap_or_default(),
+    }
+}
+
+/// Check that `sql` is a single read-only SELECT (or WITH ... SELECT) statement
+/// that doesn't reference sensitive columns. Returns the statement without a trailing `;`.
+pub fn validate_diagnostic_sql(sql: &str) -> Result<String, ActivityError> {
+    let invalid = |message: &str| ActivityError::validation("sql", message);
+
+    let statement = sql.trim().trim_end_matches(';').trim();
+    if statement.is_empty() {
+        return Err(invalid("Query cannot be empty"));
+    }
+    if statement.chars().count() > MAX_DIAGNOSTIC_SQL_LENGTH {
+        return Err(invalid("Query is too long"));
+    }
+
+    let identifiers = sql_identifiers(statement)?;
+    match identifiers.first().map(String::as_str) {
+        Some("select" | "with") => {}
+        _ => return Err(invalid("Only SELECT statements are allowed")),
+    }
+    if let Some(keyword) = identifiers
+        .iter()
+        .find(|word| FORBIDDEN_KEYWORDS.contains(&word.as_str()))
+    {
+        return Err(ActivityError::validation(
+            "sql".to_string(),
+            format!(
+                "'{}' is not allowed in diagnostic queries",
+                keyword.to_uppercase()
+            ),
+        ));
+    }
+    if let Some(table) = identifiers
+        .iter()
+        .find(|word| SENSITIVE_TABLES.contains(&word.as_str()))
+    {
+        return Err(ActivityError::validation(
+            "sql".to_string(),
+            format!("Table '{table}' indexes redacted columns and can't be queried"),
+        ));
+    }
+    if let Some(column) = identifiers.iter().find(|word| is_sensitive_column(word)) {
+        return Err(ActivityError::validation(
+            "sql".to_string(),
+            format!("Column '{column}' is redacted and can't be referenced; use SELECT * to see other columns"),
+        ));
+    }
+
+    Ok(statement.to_string())
+}
+
+/// Lowercase words and quoted identifiers of a statement, skipping string literals.
+/// Rejects comments and multiple statements.
+fn sql_identifiers(statement: &str) -> Result<Vec<String>, ActivityError> {
+    let mut identifiers = Vec::new();
+    let mut chars = statement.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                // String literal, '' is an escaped quote
+                loop {
+                    match chars.next() {
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                        }
+                        Some('\'') => break,
+                        Some(_) => {}
+                        None => {
+                            return Err(ActivityError::validation("sql", "Unterminated string"))
+                        }
+                    }
+                }
+            }
+            '"' | '`' | '[' => {
+                let close = if c == '[' { ']' } else { c };
+                let mut identifier = String::new();
+                loop {
+                    match chars.next() {
+                        Some(next) if next == close => break,
+                        Some(next) => identifier.push(next),
+                        None => {
+                            return Err(ActivityError::validation(
+                                "sql",
+                                "Unterminated quoted identifier",
+                            ))
+                        }
+                    }
+                }
+                identifiers.push(identifier.to_lowercase());
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                return Err(ActivityError::validation("sql", "Comments are not allowed"));
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                return Err(ActivityError::validation("sql", "Comments are not allowed"));
+            }
+            ';' => {
+                return Err(ActivityError::validation(
+                    "sql",
+                    "Only a single statement is allowed",
+                ));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut word = c.to_lowercase().to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_alphanumeric() || next == '_' {
+                        word.extend(next.to_lowercase());
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                identifiers.push(word);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(identifiers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[test]
+    fn test_validate_diagnostic_sql() {
+        assert_eq!(
+            validate_diagnostic_sql("  SELECT id FROM pets; ").unwrap(),
+            "SELECT id FROM pets"
+        );
+        assert!(validate_diagnostic_sql(
+            "WITH c AS (SELECT pet_id FROM activities) SELECT COUNT(*) FROM c"
+        )
+        .is_ok());
+        // Keywords inside string literals are fine
+        assert!(validate_diagnostic_sql("SELECT id FROM pets WHERE name = 'Drop''s'").is_ok());
+
+        for rejected in [
+            "",
+            "DELETE FROM pets",
+            "SELECT 1; DROP TABLE pets",
+            "WITH x AS (SELECT 1) DELETE FROM pets",
+            "SELECT id FROM pets -- comment",
+            "PRAGMA table_info(pets)",
+            "SELECT notes AS n FROM pets",
+            "SELECT id FROM pets WHERE \"photo_path\" LIKE 'a%'",
+            "SELECT load_extension('x')",
+            "SELECT activity_data FROM activities",
+            "SELECT json_extract(activity_data, '$.notes') FROM activities",
+            "SELECT value FROM app_settings WHERE key = 'webhook'",
+            "SELECT rowid FROM activities_fts WHERE activities_fts MATCH 'vet'",
+            "SELECT 'unterminated",
+        ] {
+            assert!(
+                validate_diagnostic_sql(rejected).is_err(),
+                "accepted: {rejected}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_diagnostic_query_redacts_and_limits() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 5,
+            ..Default::default()
+        };
+        let (db, _dir, _) = seeded_database(&config).await;
+
+        let result = db
+            .run_diagnostic_query("SELECT * FROM pets ORDER BY id", Some(1))
+            .await
+            .unwrap();
+        assert!(result.truncated);
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.redacted_columns, vec!["photo_path", "notes"]);
+        let notes = result.columns.iter().position(|c| c == "notes").unwrap();
+        assert_eq!(result.rows[0][notes], REDACTED);
+        let id = result.columns.iter().position(|c| c == "id").unwrap();
+        assert!(result.rows[0][id].is_i64());
+
+        let counts = db
+            .run_diagnostic_query(
+                "SELECT category, COUNT(*) AS n, AVG(pet_id) AS avg FROM activities GROUP BY category",
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!counts.truncated);
+        assert_eq!(counts.columns, vec!["category", "n", "avg"]);
+
+        assert!(db
+            .run_diagnostic_query("SELECT * FROM missing_table", None)
+            .await
+            .is_err());
+        // The pooled connection is writable again afterwards
+        db.set_setting("diagnostics_probe", &true).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_diagnostic_query_redacts_activity_data_and_settings() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 3,
+            ..Default::default()
+        };
+        let (db, _dir, _) = seeded_database(&config).await;
+        db.set_setting("webhook_url", &"https://example.com/hook?key=abc")
+            .await
+            .unwrap();
+
+        for sql in [
+            "SELECT activity_data FROM activities",
+            "SELECT json_extract(activity_data,'$.notes') FROM activities",
+            "SELECT value FROM app_settings",
+        ] {
+            assert!(
+                db.run_diagnostic_query(sql, None).await.is_err(),
+                "accepted: {sql}"
+            );
+        }
+
+        let activities = db
+            .run_diagnostic_query("SELECT * FROM activities", None)
+            .await
+            .unwrap();
+        assert!(activities
+            .redacted_columns
+            .contains(&"activity_data".to_string()));
+        let data = activities
+            .columns
+            .iter()
+            .position(|c| c == "activity_data")
+            .unwrap();
+        assert!(activities.rows.iter().all(|row| row[data] == REDACTED));
+
+        let settings = db
+            .run_diagnostic_query("SELECT * FROM app_settings", None)
+            .await
+            .unwrap();
+        assert!(settings.redacted_columns.contains(&"value".to_string()));
+        assert!(!serde_json::to_string(&settings.rows)
+            .unwrap()
+            .contains("example.com"));
+    }
+
+    #[tokio::test]
+    async fn test_run_diagnostic_query_interrupts_slow_queries() {
+        let (db, _dir, _) = seeded_database(&FixtureConfig::default()).await;
+
+        let started = Instant::now();
+        let result = db
+            .run_diagnostic_query(
+                "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n) SELECT COUNT(*) FROM n",
+                None,
+            )
+            .await;
+        assert!(result.unwrap_err().to_string().contains("longer than"));
+        assert!(started.elapsed() < DIAGNOSTIC_QUERY_TIMEOUT * 3);
+
+        // The interrupted connection went back to the pool in a usable state
+        let counts = db
+            .run_diagnostic_query("SELECT COUNT(*) AS n FROM pets", None)
+            .await
+            .unwrap();
+        assert_eq!(counts.columns, vec!["n"]);
+        db.set_setting("diagnostics_probe", &true).await.unwrap();
+    }
+}
@@ -0,0 +1,311 @@
+use super::models::*;
+use crate::errors::PetError;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::Row;
+
+/// Format document expiry dates are stored in
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Expired documents stay in the expiring list this long, until renewed or cleared
+pub const EXPIRED_DOCUMENT_GRACE_DAYS: i64 = 30;
+
+impl super::PetDatabase {
+    /// Record an uploaded document for a pet
+    pub async fn create_pet_document(
+        &self,
+        request: CreatePetDocumentRequest,
+    ) -> Result<PetDocument, PetError> {
+        log::debug!(
+            "[DB] create_pet_document: pet_id={}, category={}, sha256={}",
+            request.pet_id,
+            request.category,
+            request.sha256
+        );
+
+        let now = Utc::now();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO pet_documents (
+                pet_id, category, title, original_filename, stored_filename,
+                mime_type, file_size, sha256, notes, ocr_text, expires_on, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(request.pet_id)
+        .bind(request.category.to_string())
+        .bind(&request.title)
+        .bind(&request.original_filename)
+        .bind(&request.stored_filename)
+        .bind(&request.mime_type)
+        .bind(request.file_size)
+        .bind(&request.sha256)
+        .bind(&request.notes)
+        .bind(&request.ocr_text)
+        .bind(
+            request
+                .expires_on
+                .map(|date| date.format(DATE_FORMAT).to_string()),
+        )
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db) if db.is_unique_violation() => PetError::validation(
+                "document_bytes",
+                "This document has already been uploaded for this pet",
+            ),
+            e => {
+                log::error!("[DB] create_pet_document: insert failed, error={e}");
+                PetError::database(e.to_string())
+            }
+        })?;
+
+        self.get_pet_document_by_id(result.last_insert_rowid())
+            .await
+    }
+
+    /// Get a document by ID
+    pub async fn get_pet_document_by_id(&self, id: i64) -> Result<PetDocument, PetError> {
+        let row = sqlx::query("SELECT * FROM pet_documents WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        match row {
+            Some(row) => self.row_to_pet_document(&row),
+            None => Err(PetError::document_not_found(id)),
+        }
+    }
+
+    /// List a pet's documents, newest first, optionally filtered by category
+    pub async fn get_pet_documents(
+        &self,
+        pet_id: i64,
+        category: Option<DocumentCategory>,
+    ) -> Result<Vec<PetDocument>, PetError> {
+        let rows = match category {
+            Some(category) => sqlx::query(
+                "SELECT * FROM pet_documents WHERE pet_id = ? AND category = ? ORDER BY created_at DESC, id DESC",
+            )
+            .bind(pet_id)
+            .bind(category.to_string()),
+            None => sqlx::query(
+                "SELECT * FROM pet_documents WHERE pet_id = ? ORDER BY created_at DESC, id DESC",
+            )
+            .bind(pet_id),
+        }
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        rows.iter()
+            .map(|row| self.row_to_pet_document(row))
+            .collect()
+    }
+
+    /// Delete a document record.
+    /// Returns the deleted document and whether its stored file is still referenced by another record.
+    pub async fn delete_pet_document(&self, id: i64) -> Result<(PetDocument, bool), PetError> {
+        let document = self.get_pet_document_by_id(id).await?;
+
+        sqlx::query("DELETE FROM pet_documents WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        let still_referenced = self
+            .is_document_file_referenced(&document.stored_filename)
+            .await?;
+
+        log::info!(
+            "[DB] delete_pet_document: document_id={id}, still_referenced={still_referenced}"
+        );
+        Ok((document, still_referenced))
+    }
+
+    /// Set or clear the date a document expires
+    pub async fn set_pet_document_expiry(
+        &self,
+        id: i64,
+        expires_on: Option<NaiveDate>,
+    ) -> Result<PetDocument, PetError> {
+        let result =
+            sqlx::query("UPDATE pet_documents SET expires_on = ?, updated_at = ? WHERE id = ?")
+                .bind(expires_on.map(|date| date.format(DATE_FORMAT).to_string()))
+                .bind(Utc::now())
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(PetError::document_not_found(id));
+        }
+
+        log::info!("[DB] set_pet_document_expiry: document_id={id}, expires_on={expires_on:?}");
+        self.get_pet_document_by_id(id).await
+    }
+
+    /// Documents of active pets that expire within `days_ahead` days of `today`, or that
+    /// expired in the last [`EXPIRED_DOCUMENT_GRACE_DAYS`] days, soonest first
+    pub async fn get_expiring_documents(
+        &self,
+        days_ahead: i64,
+        today: NaiveDate,
+    ) -> Result<Vec<ExpiringDocument>, PetError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT d.*, p.name AS pet_name
+            FROM pet_documents d
+            JOIN pets p ON p.id = d.pet_id
+            WHERE p.is_archived = 0 AND d.expires_on BETWEEN ? AND ?
+            ORDER BY d.expires_on ASC, d.id ASC
+            "#,
+        )
+        .bind(
+            (today - chrono::Duration::days(EXPIRED_DOCUMENT_GRACE_DAYS))
+                .format(DATE_FORMAT)
+                .to_string(),
+        )
+        .bind(
+            (today + chrono::Duration::days(days_ahead))
+                .format(DATE_FORMAT)
+                .to_string(),
+        )
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        let mut expiring = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let document = self.row_to_pet_document(row)?;
+            let Some(expires_on) = document.expires_on else {
+                continue;
+            };
+            expiring.push(ExpiringDocument {
+                pet_name: row.get("pet_name"),
+                expires_on,
+                days_until_expiry: (expires_on - today).num_days(),
+                document,
+            });
+        }
+
+        log::debug!(
+            "[DB] get_expiring_documents: days_ahead={days_ahead}, found={}",
+            expiring.len()
+        );
+        Ok(expiring)
+    }
+
+    /// Check whether any document record points at a stored file
+    pub async fn is_document_file_referenced(
+        &self,
+        stored_filename: &str,
+    ) -> Result<bool, PetError> {
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM pet_documents WHERE stored_filename = ?")
+                .bind(stored_filename)
+                .fetch_one(&self.read_pool)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?;
+        Ok(count > 0)
+    }
+
+    fn row_to_pet_document(&self, row: &sqlx::sqlite::SqliteRow) -> Result<PetDocument, PetError> {
+        let category: String = row.get("category");
+
+        Ok(PetDocument {
+            id: row.get("id"),
+            pet_id: row.get("pet_id"),
+            category: category
+                .parse()
+                .map_err(|e: anyhow::Error| PetError::database(e.to_string()))?,
+            title: row.get("title"),
+            original_filename: row.get("original_filename"),
+            stored_filename: row.get("stored_filename"),
+            mime_type: row.get("mime_type"),
+            file_size: row.get("file_size"),
+            sha256: row.get("sha256"),
+            notes: row.get("notes"),
+            ocr_text: row.get("ocr_text"),
+            expires_on: row
+                .get::<Option<String>, _>("expires_on")
+                .and_then(|date| NaiveDate::parse_from_str(&date, DATE_FORMAT).ok()),
+            created_at: row.get::<DateTime<Utc>, _>("created_at"),
+            updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_expiring_documents() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (pet_id, archived_pet_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        let mut documents = Vec::new();
+        for (pet_id, title, expires_on) in [
+            (pet_id, "Insurance", Some("2025-06-20")),
+            (pet_id, "Registration", Some("2025-05-20")),
+            (pet_id, "Old passport", Some("2024-01-01")),
+            (pet_id, "Next year", Some("2026-06-01")),
+            (pet_id, "Adoption", None),
+            (archived_pet_id, "Insurance", Some("2025-06-10")),
+        ] {
+            let document = db
+                .create_pet_document(CreatePetDocumentRequest {
+                    pet_id,
+                    category: DocumentCategory::Insurance,
+                    title: title.to_string(),
+                    original_filename: format!("{title}.pdf"),
+                    stored_filename: format!("{title}.pdf"),
+                    mime_type: "application/pdf".to_string(),
+                    file_size: 1,
+                    sha256: title.to_string(),
+                    notes: None,
+                    ocr_text: None,
+                    expires_on: expires_on.map(|date| date.parse().unwrap()),
+                })
+                .await
+                .unwrap();
+            documents.push(document);
+        }
+        db.delete_pet(archived_pet_id).await.unwrap();
+
+        let expiring = db.get_expiring_documents(30, today).await.unwrap();
+        let listed: Vec<(&str, i64)> = expiring
+            .iter()
+            .map(|e| (e.document.title.as_str(), e.days_until_expiry))
+            .collect();
+        assert_eq!(listed, vec![("Registration", -12), ("Insurance", 19)]);
+
+        // Renewing moves the date out of the window; clearing removes it
+        db.set_pet_document_expiry(documents[0].id, Some("2026-06-20".parse().unwrap()))
+            .await
+            .unwrap();
+        let cleared = db
+            .set_pet_document_expiry(documents[1].id, None)
+            .await
+            .unwrap();
+        assert_eq!(cleared.expires_on, None);
+        assert!(db
+            .get_expiring_documents(30, today)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(db.set_pet_document_expiry(9999, None).await.is_err());
+    }
+}
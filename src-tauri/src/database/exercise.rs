@@ -0,0 +1,306 @@
+use super::activity_data::{ActivityData, ActivityDataExt};
+use super::models::PetSpecies;
+use crate::errors::ActivityError;
+use chrono::{Datelike, Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::{BTreeMap, HashMap};
+
+/// Settings key of the per-pet weekly exercise targets, a map of pet ID to minutes
+pub const EXERCISE_TARGETS_SETTING_KEY: &str = "exercise_targets";
+
+/// Longest range `get_exercise_summary` reports on
+const MAX_SUMMARY_RANGE_DAYS: u64 = 366;
+
+/// Lifestyle subcategories whose timers measure rest rather than exercise
+const REST_SUBCATEGORIES: &[&str] = &["Sleep", "Nap", "Rest"];
+
+/// Where a pet's weekly exercise target comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExerciseTargetSource {
+    /// Set by the user in settings
+    Setting,
+    /// Usual amount for the pet's species
+    Species,
+}
+
+/// Exercise logged in one week (Monday to Sunday)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseWeek {
+    pub week_start: NaiveDate,
+    /// Days of the week inside the requested range; the target is prorated by them
+    pub days_in_range: u32,
+    pub minutes: f32,
+    pub distance_km: f32,
+    pub sessions: i64,
+    pub target_met: bool,
+}
+
+/// Weekly exercise time and distance of a pet over a date range, against its target
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseSummary {
+    pub pet_id: i64,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub weekly_target_minutes: f32,
+    pub target_source: ExerciseTargetSource,
+    /// Every week overlapping the range, oldest first
+    pub weeks: Vec<ExerciseWeek>,
+    pub total_minutes: f32,
+    pub total_distance_km: f32,
+    pub weeks_target_met: usize,
+}
+
+impl super::PetDatabase {
+    /// Sum a pet's exercise per week (by activity time, UTC) from `start_date` to
+    /// `end_date` inclusive. Time comes from duration blocks, or timer blocks of
+    /// lifestyle activities other than rest; distance from distance blocks.
+    pub async fn get_exercise_summary(
+        &self,
+        pet_id: i64,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<ExerciseSummary, ActivityError> {
+        if end_date < start_date {
+            return Err(ActivityError::validation(
+                "end_date".to_string(),
+                "End date must not be before start date".to_string(),
+            ));
+        }
+        if start_date + Days::new(MAX_SUMMARY_RANGE_DAYS) <= end_date {
+            return Err(ActivityError::validation(
+                "end_date".to_string(),
+                format!("Date range is limited to {MAX_SUMMARY_RANGE_DAYS} days"),
+            ));
+        }
+
+        let (weekly_target_minutes, target_source) = self.get_exercise_target(pet_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT date(activity_time) AS day, subcategory, activity_data
+            FROM activities
+            WHERE pet_id = ? AND date(activity_time) BETWEEN ? AND ?
+                AND needs_review = 0
+                AND (json_extract(activity_data, '$.duration.durationType') IS NOT NULL
+                    OR json_extract(activity_data, '$.distance.distanceType') IS NOT NULL
+                    OR (category = 'lifestyle'
+                        AND json_extract(activity_data, '$.timer.duration') IS NOT NULL))
+            "#,
+        )
+        .bind(pet_id)
+        .bind(start_date.format("%Y-%m-%d").to_string())
+        .bind(end_date.format("%Y-%m-%d").to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        // (minutes, km, sessions) per week start
+        let mut logged: BTreeMap<NaiveDate, (f32, f32, i64)> = BTreeMap::new();
+        for row in rows {
+            let Some(day) = row
+                .try_get::<String, _>("day")
+                .ok()
+                .and_then(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())
+            else {
+                continue;
+            };
+            let Some(data) = row
+                .try_get::<Option<String>, _>("activity_data")
+                .ok()
+                .flatten()
+                .and_then(|json| serde_json::from_str::<ActivityData>(&json).ok())
+            else {
+                continue;
+            };
+
+            let subcategory: String = row.get("subcategory");
+            let timed_rest = !data.contains_key("duration")
+                && !data.contains_key("distance")
+                && REST_SUBCATEGORIES.contains(&subcategory.as_str());
+            if timed_rest {
+                continue;
+            }
+
+            let minutes = data.extract_duration_minutes().unwrap_or_default();
+            let km = data.extract_distance_km().unwrap_or_default();
+            let entry = logged.entry(week_start(day)).or_default();
+            entry.0 += minutes;
+            entry.1 += km;
+            entry.2 += 1;
+        }
+
+        let mut weeks = Vec::new();
+        let mut week = week_start(start_date);
+        while week <= end_date {
+            let first = week.max(start_date);
+            let last = (week + Days::new(6)).min(end_date);
+            let days_in_range = ((last - first).num_days() + 1) as u32;
+            let (minutes, distance_km, sessions) = logged.get(&week).copied().unwrap_or_default();
+            weeks.push(ExerciseWeek {
+                week_start: week,
+                days_in_range,
+                minutes,
+                distance_km,
+                sessions,
+                target_met: minutes >= weekly_target_minutes * days_in_range as f32 / 7.0,
+            });
+            week = week + Days::new(7);
+        }
+
+        let weeks_target_met = weeks.iter().filter(|week| week.target_met).count();
+        log::debug!(
+            "[DB] get_exercise_summary: pet_id={pet_id}, {start_date}..={end_date}, weeks={}, met={weeks_target_met}",
+            weeks.len()
+        );
+        Ok(ExerciseSummary {
+            pet_id,
+            start_date,
+            end_date,
+            weekly_target_minutes,
+            target_source,
+            total_minutes: weeks.iter().map(|week| week.minutes).sum(),
+            total_distance_km: weeks.iter().map(|week| week.distance_km).sum(),
+            weeks_target_met,
+            weeks,
+        })
+    }
+
+    /// A pet's weekly exercise target in minutes: the one set in settings,
+    /// otherwise the usual amount for its species
+    pub async fn get_exercise_target(
+        &self,
+        pet_id: i64,
+    ) -> Result<(f32, ExerciseTargetSource), ActivityError> {
+        if let Some(target) = self.get_exercise_targets().await?.get(&pet_id) {
+            return Ok((*target, ExerciseTargetSource::Setting));
+        }
+
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|e| ActivityError::validation("pet_id", &format!("Pet not found: {e}")))?;
+        Ok((
+            default_weekly_minutes(&pet.species),
+            ExerciseTargetSource::Species,
+        ))
+    }
+
+    /// Set a pet's weekly exercise target in minutes, or clear it with None to go
+    /// back to the species default
+    pub async fn set_exercise_target(
+        &self,
+        pet_id: i64,
+        weekly_minutes: Option<f32>,
+    ) -> Result<(), ActivityError> {
+        let mut targets = self.get_exercise_targets().await?;
+        match weekly_minutes {
+            Some(target) => targets.insert(pet_id, target),
+            None => targets.remove(&pet_id),
+        };
+        self.set_setting(EXERCISE_TARGETS_SETTING_KEY, &targets)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+    }
+
+    async fn get_exercise_targets(&self) -> Result<HashMap<i64, f32>, ActivityError> {
+        Ok(self
+            .get_setting::<HashMap<i64, f32>>(EXERCISE_TARGETS_SETTING_KEY)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .unwrap_or_default())
+    }
+}
+
+/// Usual weekly exercise: half an hour a day for dogs, a quarter hour of play for cats
+fn default_weekly_minutes(species: &PetSpecies) -> f32 {
+    match species {
+        PetSpecies::Dog => 210.0,
+        PetSpecies::Cat => 105.0,
+    }
+}
+
+/// Monday of the week containing `date`
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(date.weekday().num_days_from_monday() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityBlocksBuilder, ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_exercise_summary_per_week() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let entry = |subcategory: &str, day: u32, build: &dyn Fn(&mut ActivityBlocksBuilder)| {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(Utc.with_ymd_and_hms(2025, 3, day, 9, 0, 0).unwrap());
+            build(&mut blocks);
+            ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Lifestyle,
+                subcategory: subcategory.to_string(),
+                activity_data: Some(blocks.into_json()),
+                needs_review: false,
+            }
+        };
+        // 2025-03-03 and 2025-03-10 are Mondays
+        for request in [
+            entry("Walk", 3, &|b| {
+                b.duration(1.0, "h", "walk").unwrap();
+                b.distance(3.0, "km", "walk").unwrap();
+            }),
+            entry("Walk", 5, &|b| {
+                b.duration_minutes(45.0).unwrap();
+            }),
+            entry("Sleep", 6, &|b| {
+                b.duration_minutes(120.0).unwrap();
+            }),
+            entry("Run", 11, &|b| {
+                b.distance(2.0, "mi", "run").unwrap();
+            }),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+        db.set_exercise_target(pet_id, Some(140.0)).await.unwrap();
+
+        let start = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+        let report = db.get_exercise_summary(pet_id, start, end).await.unwrap();
+        assert_eq!(report.target_source, ExerciseTargetSource::Setting);
+        assert_eq!(report.weeks.len(), 2);
+
+        let first = &report.weeks[0];
+        assert_eq!((first.minutes, first.sessions), (105.0, 2));
+        assert_eq!(first.distance_km, 3.0);
+        assert!(!first.target_met);
+
+        // Three days of the second week are in range, so 60 minutes would meet it
+        let second = &report.weeks[1];
+        assert_eq!(
+            second.week_start,
+            NaiveDate::from_ymd_opt(2025, 3, 10).unwrap()
+        );
+        assert_eq!(second.days_in_range, 3);
+        assert_eq!(second.minutes, 0.0);
+        assert!((second.distance_km - 3.2187).abs() < 0.001);
+        assert!(!second.target_met);
+        assert_eq!(report.weeks_target_met, 0);
+
+        db.set_exercise_target(pet_id, None).await.unwrap();
+        let (_, source) = db.get_exercise_target(pet_id).await.unwrap();
+        assert_eq!(source, ExerciseTargetSource::Species);
+        assert!(db.get_exercise_summary(pet_id, end, start).await.is_err());
+    }
+}
@@ -0,0 +1,412 @@
+use crate::errors::ActivityError;
+use chrono::{Datelike, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashMap;
+
+/// Months of past expenses a forecast is based on
+const FORECAST_HISTORY_MONTHS: u32 = 24;
+
+/// Longest forecast that can be asked for
+pub const MAX_FORECAST_MONTHS: u32 = 24;
+
+/// Z-score of the 80% range around the expected cost
+const RANGE_Z: f64 = 1.28;
+
+/// Kind of recurring expense a forecast is split into
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseGroup {
+    Food,
+    Insurance,
+    Vet,
+    Other,
+}
+
+impl ExpenseGroup {
+    /// Group of an activity with a cost, from its category and subcategory
+    pub fn classify(category: &str, subcategory: &str) -> Self {
+        let subcategory = subcategory.to_lowercase();
+        let mentions = |words: &[&str]| words.iter().any(|word| subcategory.contains(word));
+        if mentions(&["insurance"]) {
+            ExpenseGroup::Insurance
+        } else if category.eq_ignore_ascii_case("diet") || mentions(&["food", "treat", "feeding"]) {
+            ExpenseGroup::Food
+        } else if category.eq_ignore_ascii_case("health")
+            || mentions(&["vet", "checkup", "vaccin", "dental", "medication"])
+        {
+            ExpenseGroup::Vet
+        } else {
+            ExpenseGroup::Other
+        }
+    }
+}
+
+/// Expected cost with an 80% range
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub struct ForecastRange {
+    pub expected: f64,
+    pub low: f64,
+    pub high: f64,
+}
+
+/// Forecast of one expense group in one month
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExpenseGroupForecast {
+    pub group: ExpenseGroup,
+    pub range: ForecastRange,
+}
+
+/// Forecast of one upcoming month
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExpenseForecastMonth {
+    /// First day of the month
+    pub month: NaiveDate,
+    pub total: ForecastRange,
+    pub groups: Vec<ExpenseGroupForecast>,
+}
+
+/// Projected monthly costs of a pet for budgeting
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExpenseForecast {
+    pub pet_id: i64,
+    /// Currency of the forecast, the one most past expenses were paid in
+    pub currency: Option<String>,
+    /// Complete past months the forecast is based on
+    pub history_months: usize,
+    /// Same-month averages are used once a full year of history is available
+    pub seasonal: bool,
+    pub months: Vec<ExpenseForecastMonth>,
+    /// Past expenses in other currencies, left out of the forecast
+    pub skipped_expenses: usize,
+}
+
+/// A past expense
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpenseSample {
+    /// First day of the month it was paid in
+    pub month: NaiveDate,
+    pub group: ExpenseGroup,
+    pub currency: Option<String>,
+    pub amount: f64,
+}
+
+impl super::PetDatabase {
+    /// Project a pet's costs for the `months_ahead` months after the current one from
+    /// the expenses of the last two complete years, using averages of the same calendar
+    /// month once a year of history is available
+    pub async fn forecast_expenses(
+        &self,
+        pet_id: i64,
+        months_ahead: u32,
+        today: NaiveDate,
+    ) -> Result<ExpenseForecast, ActivityError> {
+        if !(1..=MAX_FORECAST_MONTHS).contains(&months_ahead) {
+            return Err(ActivityError::validation(
+                "months_ahead".to_string(),
+                format!("Forecast must cover 1 to {MAX_FORECAST_MONTHS} months"),
+            ));
+        }
+
+        let current_month = first_of_month(today);
+        let history_start = current_month - Months::new(FORECAST_HISTORY_MONTHS);
+        let rows = sqlx::query(
+            r#"
+            SELECT category, subcategory, CAST(cost_amount AS REAL) AS amount,
+                json_extract(activity_data, '$.cost.currency') AS currency,
+                strftime('%Y-%m-01', activity_time) AS month
+            FROM activities
+            WHERE pet_id = ? AND cost_amount > 0 AND needs_review = 0
+                AND activity_time >= ? AND activity_time < ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(history_start.to_string())
+        .bind(current_month.to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let samples: Vec<ExpenseSample> = rows
+            .iter()
+            .filter_map(|row| {
+                let month: String = row.try_get("month").ok()?;
+                let currency: Option<String> = row.try_get("currency").ok().flatten();
+                Some(ExpenseSample {
+                    month: NaiveDate::parse_from_str(&month, "%Y-%m-%d").ok()?,
+                    group: ExpenseGroup::classify(
+                        &row.get::<String, _>("category"),
+                        &row.get::<String, _>("subcategory"),
+                    ),
+                    currency: currency
+                        .map(|c| c.trim().to_uppercase())
+                        .filter(|c| !c.is_empty()),
+                    amount: row.get("amount"),
+                })
+            })
+            .collect();
+
+        let forecast = build_expense_forecast(pet_id, &samples, current_month, months_ahead);
+        log::debug!(
+            "[DB] forecast_expenses: pet_id={pet_id}, months_ahead={months_ahead}, history_months={}, samples={}",
+            forecast.history_months,
+            samples.len()
+        );
+        Ok(forecast)
+    }
+}
+
+fn first_of_month(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("every month has a first day")
+}
+
+/// Forecast the months after `current_month` from expenses paid before it
+pub fn build_expense_forecast(
+    pet_id: i64,
+    samples: &[ExpenseSample],
+    current_month: NaiveDate,
+    months_ahead: u32,
+) -> ExpenseForecast {
+    // Forecast in the currency most expenses were paid in
+    let mut currency_counts: HashMap<&Option<String>, usize> = HashMap::new();
+    for sample in samples {
+        *currency_counts.entry(&sample.currency).or_default() += 1;
+    }
+    let currency = currency_counts
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+        .and_then(|(currency, _)| (*currency).clone());
+    let samples: Vec<&ExpenseSample> = samples
+        .iter()
+        .filter(|sample| sample.currency == currency)
+        .collect();
+
+    // History runs from the first month with an expense to the last complete month
+    let history: Vec<NaiveDate> = match samples.iter().map(|sample| sample.month).min() {
+        Some(first) => std::iter::successors(Some(first), |month| Some(*month + Months::new(1)))
+            .take_while(|month| *month < current_month)
+            .collect(),
+        None => Vec::new(),
+    };
+    let seasonal = history.len() >= 12;
+
+    let mut groups: Vec<ExpenseGroup> = samples.iter().map(|sample| sample.group).collect();
+    groups.sort();
+    groups.dedup();
+
+    // Monthly totals per group, zero for months without expenses
+    let totals = |group: Option<ExpenseGroup>| -> Vec<(NaiveDate, f64)> {
+        history
+            .iter()
+            .map(|&month| {
+                let total = samples
+                    .iter()
+                    .filter(|s| s.month == month && group.is_none_or(|g| s.group == g))
+                    .map(|s| s.amount)
+                    .sum();
+                (month, total)
+            })
+            .collect()
+    };
+    let group_models: Vec<(ExpenseGroup, MonthlyModel)> = groups
+        .iter()
+        .map(|&group| (group, MonthlyModel::fit(&totals(Some(group)), seasonal)))
+        .collect();
+    let total_model = MonthlyModel::fit(&totals(None), seasonal);
+
+    let months = (1..=months_ahead)
+        .map(|offset| {
+            let month = current_month + Months::new(offset);
+            ExpenseForecastMonth {
+                month,
+                total: total_model.predict(month),
+                groups: group_models
+                    .iter()
+                    .map(|(group, model)| ExpenseGroupForecast {
+                        group: *group,
+                        range: model.predict(month),
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    let skipped_expenses = currency_counts
+        .iter()
+        .filter(|(c, _)| ***c != currency)
+        .map(|(_, count)| count)
+        .sum();
+    ExpenseForecast {
+        pet_id,
+        currency,
+        history_months: history.len(),
+        seasonal,
+        months,
+        skipped_expenses,
+    }
+}
+
+/// Expected monthly total, overall or per calendar month, with the spread of past
+/// months around it
+struct MonthlyModel {
+    mean: f64,
+    /// Mean per calendar month (1-12), when seasonal
+    by_calendar_month: HashMap<u32, f64>,
+    std_dev: f64,
+}
+
+impl MonthlyModel {
+    fn fit(totals: &[(NaiveDate, f64)], seasonal: bool) -> Self {
+        let mean = if totals.is_empty() {
+            0.0
+        } else {
+            totals.iter().map(|(_, total)| total).sum::<f64>() / totals.len() as f64
+        };
+
+        let mut by_calendar_month = HashMap::new();
+        if seasonal {
+            let mut sums: HashMap<u32, (f64, usize)> = HashMap::new();
+            for (month, total) in totals {
+                let entry = sums.entry(month.month()).or_default();
+                entry.0 += total;
+                entry.1 += 1;
+            }
+            by_calendar_month = sums
+                .into_iter()
+                .map(|(month, (sum, count))| (month, sum / count as f64))
+                .collect();
+        }
+
+        let mut model = MonthlyModel {
+            mean,
+            by_calendar_month,
+            std_dev: 0.0,
+        };
+        model.std_dev = if totals.len() > 1 {
+            let squared: f64 = totals
+                .iter()
+                .map(|(month, total)| (total - model.expected(*month)).powi(2))
+                .sum();
+            (squared / (totals.len() - 1) as f64).sqrt()
+        } else {
+            // Too little history to measure the spread
+            mean / 2.0
+        };
+        model
+    }
+
+    fn expected(&self, month: NaiveDate) -> f64 {
+        self.by_calendar_month
+            .get(&month.month())
+            .copied()
+            .unwrap_or(self.mean)
+    }
+
+    fn predict(&self, month: NaiveDate) -> ForecastRange {
+        let expected = self.expected(month);
+        let margin = RANGE_Z * self.std_dev;
+        ForecastRange {
+            expected: round_cents(expected),
+            low: round_cents((expected - margin).max(0.0)),
+            high: round_cents(expected + margin),
+        }
+    }
+}
+
+fn round_cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn month(year: i32, month: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, 1).unwrap()
+    }
+
+    fn sample(month: NaiveDate, group: ExpenseGroup, amount: f64) -> ExpenseSample {
+        ExpenseSample {
+            month,
+            group,
+            currency: Some("USD".to_string()),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_expense_groups() {
+        assert_eq!(
+            ExpenseGroup::classify("expense", "Pet Insurance"),
+            ExpenseGroup::Insurance
+        );
+        assert_eq!(
+            ExpenseGroup::classify("diet", "Feeding"),
+            ExpenseGroup::Food
+        );
+        assert_eq!(
+            ExpenseGroup::classify("health", "Checkup"),
+            ExpenseGroup::Vet
+        );
+        assert_eq!(
+            ExpenseGroup::classify("lifestyle", "Grooming"),
+            ExpenseGroup::Other
+        );
+    }
+
+    #[test]
+    fn test_seasonal_forecast() {
+        let mut samples = Vec::new();
+        // Two years of monthly food and insurance, with a yearly checkup every March
+        for offset in 0..24 {
+            let paid = month(2023, 1) + Months::new(offset);
+            samples.push(sample(paid, ExpenseGroup::Food, 60.0));
+            samples.push(sample(paid, ExpenseGroup::Insurance, 30.0));
+            if paid.month() == 3 {
+                samples.push(sample(paid, ExpenseGroup::Vet, 120.0));
+            }
+        }
+        samples.push(ExpenseSample {
+            currency: Some("EUR".to_string()),
+            ..sample(month(2024, 5), ExpenseGroup::Other, 500.0)
+        });
+
+        let forecast = build_expense_forecast(1, &samples, month(2025, 1), 3);
+        assert_eq!(forecast.currency.as_deref(), Some("USD"));
+        assert_eq!(forecast.history_months, 24);
+        assert!(forecast.seasonal);
+        assert_eq!(forecast.skipped_expenses, 1);
+
+        let [february, march, april] = &forecast.months[..] else {
+            panic!("expected three months");
+        };
+        assert_eq!(february.month, month(2025, 2));
+        assert_eq!(february.total.expected, 90.0);
+        assert_eq!(march.total.expected, 210.0);
+        assert_eq!(april.total.expected, 90.0);
+        // Every year looked the same, so there is no uncertainty
+        assert_eq!(march.total.low, march.total.high);
+        let vet = march
+            .groups
+            .iter()
+            .find(|g| g.group == ExpenseGroup::Vet)
+            .unwrap();
+        assert_eq!(vet.range.expected, 120.0);
+    }
+
+    #[test]
+    fn test_short_history_uses_overall_mean() {
+        let samples = vec![
+            sample(month(2025, 3), ExpenseGroup::Food, 50.0),
+            sample(month(2025, 4), ExpenseGroup::Food, 70.0),
+        ];
+        let forecast = build_expense_forecast(1, &samples, month(2025, 6), 1);
+
+        // March to May, with nothing spent in May
+        assert_eq!(forecast.history_months, 3);
+        assert!(!forecast.seasonal);
+        let total = forecast.months[0].total;
+        assert_eq!(total.expected, 40.0);
+        assert!(total.low < 40.0 && total.high > 40.0);
+    }
+}
@@ -0,0 +1,173 @@
+use super::models::*;
+use super::query::escape_like;
+use crate::errors::ActivityError;
+use sqlx::{Row, SqliteConnection};
+
+/// Store name of journal entries for photo files
+pub const PHOTO_STORAGE: &str = "photos";
+
+impl super::PetDatabase {
+    /// Whether a stored photo is still used by a pet or by an activity other than
+    /// `except_activity_id`, including activities in the trash
+    pub(super) async fn is_photo_file_referenced(
+        conn: &mut SqliteConnection,
+        file_name: &str,
+        except_activity_id: i64,
+    ) -> Result<bool, ActivityError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM pets WHERE photo_path = ?)
+                OR EXISTS(
+                    SELECT 1 FROM activities
+                    WHERE id != ? AND activity_data LIKE ? ESCAPE '\'
+                )
+                OR EXISTS(
+                    SELECT 1 FROM activity_trash
+                    WHERE id != ? AND activity_data LIKE ? ESCAPE '\'
+                )
+            "#,
+        )
+        .bind(file_name)
+        .bind(except_activity_id)
+        .bind(format!("%{}%", escape_like(file_name)))
+        .bind(except_activity_id)
+        .bind(format!("%{}%", escape_like(file_name)))
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+    }
+
+    /// Record a photo file for deletion on the caller's transaction
+    pub(super) async fn journal_file_deletion(
+        conn: &mut SqliteConnection,
+        file_name: &str,
+        source_activity_id: i64,
+    ) -> Result<i64, ActivityError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO file_deletion_journal (storage, file_name, source_activity_id)
+            VALUES (?, ?, ?)
+            "#,
+        )
+        .bind(PHOTO_STORAGE)
+        .bind(file_name)
+        .bind(source_activity_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Files still waiting to be deleted, oldest first
+    pub async fn get_pending_file_deletions(
+        &self,
+    ) -> Result<Vec<PendingFileDeletion>, ActivityError> {
+        let rows = sqlx::query("SELECT * FROM file_deletion_journal ORDER BY id ASC")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| PendingFileDeletion {
+                id: row.get("id"),
+                storage: row.get("storage"),
+                file_name: row.get("file_name"),
+                source_activity_id: row.get("source_activity_id"),
+                attempts: row.get("attempts"),
+                last_error: row.get("last_error"),
+            })
+            .collect())
+    }
+
+    /// Remove a journal entry once its file is gone
+    pub async fn complete_file_deletion(&self, id: i64) -> Result<(), ActivityError> {
+        sqlx::query("DELETE FROM file_deletion_journal WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(())
+    }
+
+    /// Keep a journal entry for a later retry and remember why deletion failed
+    pub async fn record_file_deletion_failure(
+        &self,
+        id: i64,
+        error: &str,
+    ) -> Result<(), ActivityError> {
+        sqlx::query(
+            "UPDATE file_deletion_journal SET attempts = attempts + 1, last_error = ? WHERE id = ?",
+        )
+        .bind(error)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::warn!("[DB] record_file_deletion_failure: journal_id={id}, error={error}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_delete_activity_journals_unshared_attachments() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let attachment = |file: &str| {
+            serde_json::json!({
+                "attachment": [{ "filename": file, "path": file }]
+            })
+        };
+        let mut ids = Vec::new();
+        for data in [
+            attachment("own.jpg"),
+            attachment("shared.jpg"),
+            attachment("shared.jpg"),
+        ] {
+            let activity = db
+                .create_activity(ActivityCreateRequest {
+                    pet_id,
+                    category: ActivityCategory::Health,
+                    subcategory: "Checkup".to_string(),
+                    activity_data: Some(data),
+                    needs_review: false,
+                })
+                .await
+                .unwrap();
+            ids.push(activity.id);
+        }
+
+        let pending = db.delete_activity(ids[0]).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].file_name, "own.jpg");
+        assert_eq!(pending[0].storage, PHOTO_STORAGE);
+
+        // The file is still used by the other activity
+        assert!(db.delete_activity(ids[1]).await.unwrap().is_empty());
+
+        db.record_file_deletion_failure(pending[0].id, "busy")
+            .await
+            .unwrap();
+        let journal = db.get_pending_file_deletions().await.unwrap();
+        assert_eq!(journal.len(), 1);
+        assert_eq!(journal[0].attempts, 1);
+        assert_eq!(journal[0].last_error.as_deref(), Some("busy"));
+
+        db.complete_file_deletion(pending[0].id).await.unwrap();
+        assert!(db.get_pending_file_deletions().await.unwrap().is_empty());
+        assert!(db.delete_activity(ids[0]).await.is_err());
+    }
+}
@@ -0,0 +1,426 @@
+use super::activity_data::ActivityDataExt;
+use crate::errors::ActivityError;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::{BTreeSet, HashMap};
+
+/// Number of rows in one table
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+/// Size of the SQLite database and its tables
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DatabaseFootprint {
+    pub tables: Vec<TableRowCount>,
+    pub database_bytes: u64,
+    /// Write-ahead log not yet checkpointed into the main file
+    pub wal_bytes: u64,
+}
+
+/// Files a pet's records point at
+#[derive(Debug, Clone, PartialEq)]
+pub struct PetFileReferences {
+    pub pet_id: i64,
+    pub pet_name: String,
+    pub profile_photo: Option<String>,
+    pub attachment_files: BTreeSet<String>,
+    pub document_count: i64,
+    pub document_bytes: u64,
+}
+
+/// Number and size of a group of files
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub struct StorageBucket {
+    pub count: usize,
+    pub bytes: u64,
+}
+
+impl StorageBucket {
+    fn add(&mut self, bytes: u64) {
+        self.count += 1;
+        self.bytes += bytes;
+    }
+}
+
+/// Storage used by one pet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PetStorageUsage {
+    pub pet_id: i64,
+    pub pet_name: String,
+    /// Profile photo and activity attachments
+    pub photos: StorageBucket,
+    /// Activity attachments only
+    pub attachments: StorageBucket,
+    pub documents: StorageBucket,
+    /// Everything above
+    pub total_bytes: u64,
+}
+
+/// Everything the app stores on disk, for the settings storage screen
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DataFootprint {
+    pub database: DatabaseFootprint,
+    /// Per pet, largest first. A file shared by several pets counts for each of them.
+    pub pets: Vec<PetStorageUsage>,
+    /// All files in photo storage
+    pub photo_storage: StorageBucket,
+    /// Distinct files referenced by activity attachments
+    pub attachments: StorageBucket,
+    /// Stored photos that no pet or activity refers to
+    pub unreferenced_photos: StorageBucket,
+    pub documents: StorageBucket,
+}
+
+impl super::PetDatabase {
+    /// Row counts of the app's tables and the size of the database files
+    pub async fn get_database_footprint(&self) -> Result<DatabaseFootprint, ActivityError> {
+        let rows = sqlx::query(
+            "SELECT name, sql FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name != '_sqlx_migrations' ORDER BY name",
+        )
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let tables: Vec<(String, bool)> = rows
+            .iter()
+            .map(|row| {
+                let sql: Option<String> = row.get("sql");
+                let is_virtual = sql.is_some_and(|sql| sql.starts_with("CREATE VIRTUAL"));
+                (row.get("name"), is_virtual)
+            })
+            .collect();
+        // Full-text indexes mirror their source tables; skip them and their shadow tables
+        let virtual_tables: Vec<&str> = tables
+            .iter()
+            .filter(|(_, is_virtual)| *is_virtual)
+            .map(|(name, _)| name.as_str())
+            .collect();
+
+        let mut counts = Vec::new();
+        for (table, is_virtual) in &tables {
+            if *is_virtual
+                || virtual_tables
+                    .iter()
+                    .any(|v| table.starts_with(&format!("{v}_")))
+            {
+                continue;
+            }
+            let rows: i64 = sqlx::query_scalar(&format!(
+                "SELECT COUNT(*) FROM \"{}\"",
+                table.replace('"', "\"\"")
+            ))
+            .fetch_one(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+            counts.push(TableRowCount {
+                table: table.clone(),
+                rows,
+            });
+        }
+
+        let page_count: i64 = self.pragma_value("page_count").await?;
+        let page_size: i64 = self.pragma_value("page_size").await?;
+        let database_file: Option<String> = sqlx::query("PRAGMA database_list")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .iter()
+            .find(|row| row.get::<String, _>("name") == "main")
+            .map(|row| row.get("file"));
+        let wal_bytes = database_file
+            .filter(|file| !file.is_empty())
+            .and_then(|file| std::fs::metadata(format!("{file}-wal")).ok())
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+
+        log::debug!(
+            "[DB] get_database_footprint: {} tables, {} pages of {} bytes, wal={} bytes",
+            counts.len(),
+            page_count,
+            page_size,
+            wal_bytes
+        );
+
+        Ok(DatabaseFootprint {
+            tables: counts,
+            database_bytes: (page_count * page_size).max(0) as u64,
+            wal_bytes,
+        })
+    }
+
+    async fn pragma_value(&self, pragma: &'static str) -> Result<i64, ActivityError> {
+        sqlx::query_scalar(&format!("PRAGMA {pragma}"))
+            .fetch_one(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+    }
+
+    /// Photos, attachments and documents referenced by each pet, archived pets included
+    pub async fn get_pet_file_references(&self) -> Result<Vec<PetFileReferences>, ActivityError> {
+        let pet_rows = sqlx::query("SELECT id, name, photo_path FROM pets ORDER BY id")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let mut pets: Vec<PetFileReferences> = pet_rows
+            .iter()
+            .map(|row| PetFileReferences {
+                pet_id: row.get("id"),
+                pet_name: row.get("name"),
+                profile_photo: row
+                    .get::<Option<String>, _>("photo_path")
+                    .filter(|path| !path.trim().is_empty()),
+                attachment_files: BTreeSet::new(),
+                document_count: 0,
+                document_bytes: 0,
+            })
+            .collect();
+        let index: HashMap<i64, usize> = pets
+            .iter()
+            .enumerate()
+            .map(|(i, pet)| (pet.pet_id, i))
+            .collect();
+
+        let activity_rows = sqlx::query(
+            "SELECT pet_id, activity_data FROM activities WHERE activity_data IS NOT NULL",
+        )
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        for row in &activity_rows {
+            let Some(&i) = index.get(&row.get::<i64, _>("pet_id")) else {
+                continue;
+            };
+            let json: String = row.get("activity_data");
+            if let Ok(data) = serde_json::from_str::<super::ActivityData>(&json) {
+                pets[i].attachment_files.extend(data.attachment_files());
+            }
+        }
+
+        let document_rows = sqlx::query(
+            "SELECT pet_id, COUNT(*) AS count, COALESCE(SUM(file_size), 0) AS bytes FROM pet_documents GROUP BY pet_id",
+        )
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        for row in &document_rows {
+            if let Some(&i) = index.get(&row.get::<i64, _>("pet_id")) {
+                pets[i].document_count = row.get("count");
+                pets[i].document_bytes = row.get::<i64, _>("bytes").max(0) as u64;
+            }
+        }
+
+        Ok(pets)
+    }
+}
+
+/// Combine database sizes, pet references and the files in photo storage.
+/// `stored_photos` lists every file in photo storage with its size.
+pub fn build_data_footprint(
+    database: DatabaseFootprint,
+    pets: &[PetFileReferences],
+    stored_photos: &[(String, u64)],
+) -> DataFootprint {
+    let sizes: HashMap<&str, u64> = stored_photos
+        .iter()
+        .map(|(name, size)| (name.as_str(), *size))
+        .collect();
+    let mut photo_storage = StorageBucket::default();
+    for (_, size) in stored_photos {
+        photo_storage.add(*size);
+    }
+
+    let mut referenced: BTreeSet<&str> = BTreeSet::new();
+    let mut attachment_files: BTreeSet<&str> = BTreeSet::new();
+    let mut documents = StorageBucket::default();
+    let mut usage = Vec::with_capacity(pets.len());
+
+    for pet in pets {
+        let mut photos = StorageBucket::default();
+        let mut attachments = StorageBucket::default();
+
+        for file in &pet.attachment_files {
+            // Files that are already gone from disk take no space
+            if let Some(&size) = sizes.get(file.as_str()) {
+                attachments.add(size);
+                photos.add(size);
+            }
+            referenced.insert(file);
+            attachment_files.insert(file);
+        }
+        if let Some(photo) = &pet.profile_photo {
+            if let Some(&size) = sizes.get(photo.as_str()) {
+                if !pet.attachment_files.contains(photo) {
+                    photos.add(size);
+                }
+            }
+            referenced.insert(photo);
+        }
+
+        let pet_documents = StorageBucket {
+            count: pet.document_count.max(0) as usize,
+            bytes: pet.document_bytes,
+        };
+        documents.count += pet_documents.count;
+        documents.bytes += pet_documents.bytes;
+
+        usage.push(PetStorageUsage {
+            pet_id: pet.pet_id,
+            pet_name: pet.pet_name.clone(),
+            photos,
+            attachments,
+            documents: pet_documents,
+            total_bytes: photos.bytes + pet_documents.bytes,
+        });
+    }
+    usage.sort_by(|a, b| {
+        b.total_bytes
+            .cmp(&a.total_bytes)
+            .then_with(|| a.pet_id.cmp(&b.pet_id))
+    });
+
+    let mut attachments = StorageBucket::default();
+    for file in &attachment_files {
+        if let Some(&size) = sizes.get(file) {
+            attachments.add(size);
+        }
+    }
+    let mut unreferenced_photos = StorageBucket::default();
+    for (name, size) in stored_photos {
+        if !referenced.contains(name.as_str()) {
+            unreferenced_photos.add(*size);
+        }
+    }
+
+    DataFootprint {
+        database,
+        pets: usage,
+        photo_storage,
+        attachments,
+        unreferenced_photos,
+        documents,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    fn pet(
+        pet_id: i64,
+        profile_photo: Option<&str>,
+        attachments: &[&str],
+        document_bytes: u64,
+    ) -> PetFileReferences {
+        PetFileReferences {
+            pet_id,
+            pet_name: format!("Pet {pet_id}"),
+            profile_photo: profile_photo.map(str::to_string),
+            attachment_files: attachments.iter().map(|f| f.to_string()).collect(),
+            document_count: i64::from(document_bytes > 0),
+            document_bytes,
+        }
+    }
+
+    #[test]
+    fn test_build_data_footprint() {
+        let database = DatabaseFootprint {
+            tables: Vec::new(),
+            database_bytes: 4096,
+            wal_bytes: 0,
+        };
+        let stored = vec![
+            ("a.jpg".to_string(), 100),
+            ("b.jpg".to_string(), 200),
+            ("shared.jpg".to_string(), 50),
+            ("orphan.jpg".to_string(), 7),
+        ];
+        let pets = vec![
+            pet(1, Some("a.jpg"), &["shared.jpg", "missing.jpg"], 0),
+            pet(2, None, &["b.jpg", "shared.jpg"], 1000),
+        ];
+
+        let footprint = build_data_footprint(database, &pets, &stored);
+
+        assert_eq!(
+            footprint.photo_storage,
+            StorageBucket {
+                count: 4,
+                bytes: 357
+            }
+        );
+        assert_eq!(
+            footprint.attachments,
+            StorageBucket {
+                count: 2,
+                bytes: 250
+            }
+        );
+        assert_eq!(
+            footprint.unreferenced_photos,
+            StorageBucket { count: 1, bytes: 7 }
+        );
+        assert_eq!(
+            footprint.documents,
+            StorageBucket {
+                count: 1,
+                bytes: 1000
+            }
+        );
+
+        // Largest first
+        assert_eq!(footprint.pets[0].pet_id, 2);
+        assert_eq!(footprint.pets[0].total_bytes, 1250);
+        assert_eq!(
+            footprint.pets[1].photos,
+            StorageBucket {
+                count: 2,
+                bytes: 150
+            }
+        );
+        assert_eq!(
+            footprint.pets[1].attachments,
+            StorageBucket {
+                count: 1,
+                bytes: 50
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_database_footprint_and_references() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 20,
+            attachment_ratio: 0.5,
+            ..Default::default()
+        };
+        let (db, _dir, _) = seeded_database(&config).await;
+
+        let footprint = db.get_database_footprint().await.unwrap();
+        let rows = |table: &str| {
+            footprint
+                .tables
+                .iter()
+                .find(|t| t.table == table)
+                .map(|t| t.rows)
+        };
+        assert_eq!(rows("pets"), Some(2));
+        assert_eq!(rows("activities"), Some(40));
+        assert!(footprint.tables.iter().all(|t| !t.table.contains("_fts")));
+        assert!(footprint.database_bytes > 0);
+
+        let references = db.get_pet_file_references().await.unwrap();
+        assert_eq!(references.len(), 2);
+        assert!(references
+            .iter()
+            .flat_map(|pet| &pet.attachment_files)
+            .all(|file| file.starts_with("fixture-")));
+        assert!(references
+            .iter()
+            .any(|pet| !pet.attachment_files.is_empty()));
+    }
+}
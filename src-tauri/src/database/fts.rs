@@ -1,15 +1,32 @@
-use super::{Activity, PetDatabase};
+use super::search::FtsFilter;
+use super::search_query::SearchQuery;
+use super::{Activity, FtsTokenizer, PetDatabase, WritePriority, FTS_TOKENIZER_SETTING_KEY};
 use crate::errors::ActivityError;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use sqlx::Row;
 
+/// Columns of the activity search index
+pub const ACTIVITY_FTS_COLUMNS: &[&str] = &["subcategory", "activity_data"];
+
+/// Columns of the document search index
+pub const DOCUMENT_FTS_COLUMNS: &[&str] = &["title", "notes", "ocr_text"];
+
+/// Search indexes with their content tables and indexed columns
+const FTS_TABLES: [(&str, &str, &[&str]); 2] = [
+    ("activities_fts", "activities", ACTIVITY_FTS_COLUMNS),
+    ("pet_documents_fts", "pet_documents", DOCUMENT_FTS_COLUMNS),
+];
+
 /// Full-Text Search utilities for activities
 impl PetDatabase {
     /// Rebuild the entire FTS index from scratch
     pub async fn rebuild_fts_index(&self) -> Result<FtsIndexStats, ActivityError> {
         log::info!("Starting FTS index rebuild");
 
+        // Maintenance waits for any activity the user is logging
+        let _turn = self.writes.acquire(WritePriority::Background).await;
+
         // Start a transaction
         let mut tx = self
             .pool
@@ -19,70 +36,94 @@ impl PetDatabase {
                 message: format!("Transaction error: {e}"),
             })?;
 
-        // Clear existing FTS data
-        sqlx::query("DELETE FROM activities_fts")
+        // Re-read every row from the content table
+        sqlx::query("INSERT INTO activities_fts(activities_fts) VALUES('rebuild')")
             .execute(&mut *tx)
             .await
             .map_err(|e| ActivityError::InvalidData {
-                message: format!("FTS clear error: {e}"),
+                message: format!("FTS rebuild error: {e}"),
             })?;
 
-        // Get all activities and rebuild FTS index
-        let rows = sqlx::query("SELECT id, subcategory, activity_data FROM activities")
-            .fetch_all(&mut *tx)
+        // Optimize the FTS index
+        sqlx::query("INSERT INTO activities_fts(activities_fts) VALUES('optimize')")
+            .execute(&mut *tx)
             .await
             .map_err(|e| ActivityError::InvalidData {
-                message: format!("Activities fetch error: {e}"),
-            })?;
-
-        let mut indexed_count = 0;
-        for row in rows {
-            let id: i64 = row.try_get("id").map_err(|e| ActivityError::InvalidData {
-                message: format!("Invalid id: {e}"),
+                message: format!("FTS optimize error: {e}"),
             })?;
-            let subcategory: String =
-                row.try_get("subcategory")
-                    .map_err(|e| ActivityError::InvalidData {
-                        message: format!("Invalid subcategory: {e}"),
-                    })?;
 
-            // Extract text content from activity_data JSON for search indexing
-            let activity_data: Option<String> = row.try_get("activity_data").ok();
-            let searchable_content = activity_data.unwrap_or_else(|| "{}".to_string());
+        // Commit the transaction
+        tx.commit().await.map_err(|e| ActivityError::InvalidData {
+            message: format!("Transaction commit error: {e}"),
+        })?;
 
-            // For now, store the JSON data directly in the description field for simple text search
-            // In the future, this could be enhanced to extract specific fields like title, notes, etc.
-            sqlx::query(
-                "INSERT INTO activities_fts(rowid, title, description, subcategory, location) VALUES (?, ?, ?, ?, ?)"
-            )
-            .bind(id)
-            .bind(&subcategory) // Use subcategory as title for now
-            .bind(&searchable_content) // Store JSON data for search
-            .bind(&subcategory)
-            .bind("") // No separate location field anymore
-            .execute(&mut *tx)
-            .await
-            .map_err(|e| ActivityError::InvalidData { message: format!("FTS insert error: {e}") })?;
+        let stats = self.get_fts_index_stats().await?;
+        log::info!(
+            "FTS index rebuild completed: {} activities indexed",
+            stats.document_count
+        );
+        Ok(stats)
+    }
 
-            indexed_count += 1;
-        }
+    /// Recreate the activity and document search indexes with `tokenizer` and remember it.
+    /// Indexes are rebuilt from their tables in one transaction, so searches never see
+    /// a half-built index.
+    pub async fn set_fts_tokenizer(
+        &self,
+        tokenizer: FtsTokenizer,
+    ) -> Result<FtsIndexStats, ActivityError> {
+        log::info!("Rebuilding FTS indexes with the {tokenizer} tokenizer");
 
-        // Optimize the FTS index
-        sqlx::query("INSERT INTO activities_fts(activities_fts) VALUES('optimize')")
-            .execute(&mut *tx)
+        let _turn = self.writes.acquire(WritePriority::Background).await;
+        let mut tx = self
+            .pool
+            .begin()
             .await
             .map_err(|e| ActivityError::InvalidData {
-                message: format!("FTS optimize error: {e}"),
+                message: format!("Transaction error: {e}"),
             })?;
 
-        // Commit the transaction
+        for (table, content, columns) in FTS_TABLES {
+            // The sync triggers live on the content tables and keep working once
+            // the index exists again under the same name
+            let statements = [
+                format!("DROP TABLE IF EXISTS {table}"),
+                format!(
+                    "CREATE VIRTUAL TABLE {table} USING fts5({}, content='{content}', content_rowid='id', tokenize='{}')",
+                    columns.join(", "),
+                    tokenizer.tokenize_option()
+                ),
+                format!("INSERT INTO {table}({table}) VALUES('rebuild')"),
+            ];
+            for statement in statements {
+                sqlx::query(&statement)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| ActivityError::InvalidData {
+                        message: format!("FTS rebuild error on {table}: {e}"),
+                    })?;
+            }
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO app_settings (key, value, updated_at) VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(FTS_TOKENIZER_SETTING_KEY)
+        .bind(serde_json::to_string(&tokenizer).unwrap_or_default())
+        .bind(chrono::Utc::now())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ActivityError::InvalidData {
+            message: format!("Database error: {e}"),
+        })?;
+
         tx.commit().await.map_err(|e| ActivityError::InvalidData {
             message: format!("Transaction commit error: {e}"),
         })?;
 
-        log::info!("FTS index rebuild completed: {indexed_count} activities indexed");
-
-        // Get index statistics
         self.get_fts_index_stats().await
     }
 
@@ -96,28 +137,54 @@ impl PetDatabase {
 
         log::debug!("FTS search query: '{query}', limit: {limit}");
 
-        // Sanitize query to prevent FTS injection
-        let sanitized_query = self.sanitize_fts_query(query);
+        let tokenizer = self
+            .get_fts_tokenizer()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let filter = FtsFilter::new(&SearchQuery::parse(query), tokenizer);
+        self.fts_search_activities_matching(&filter, limit).await
+    }
 
-        let rows = sqlx::query(
+    /// Activities matching a full-text filter, best match first. Private activities
+    /// are left out when the privacy settings hide them from search.
+    pub(super) async fn fts_search_activities_matching(
+        &self,
+        filter: &FtsFilter,
+        limit: i64,
+    ) -> Result<Vec<FtsSearchResult>, ActivityError> {
+        if filter.is_empty() {
+            return Ok(Vec::new());
+        }
+        let searches_private = self
+            .get_privacy_settings()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .searches_private();
+
+        let sql = format!(
             r#"
-            SELECT 
-                a.id, a.pet_id, a.category, a.subcategory, a.activity_data, a.created_at, a.updated_at,
-                fts.rank
-            FROM activities_fts fts
-            JOIN activities a ON a.id = fts.rowid
-            WHERE activities_fts MATCH ?
-            ORDER BY fts.rank
+            SELECT a.id, {} AS rank
+            FROM activities_fts
+            JOIN activities a ON a.id = activities_fts.rowid
+            WHERE {} AND (a.is_private = 0 OR ?)
+            ORDER BY rank
             LIMIT ?
             "#,
-        )
-        .bind(&sanitized_query)
-        .bind(limit)
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| ActivityError::InvalidData {
-            message: format!("FTS search error: {e}"),
-        })?;
+            filter.rank("activities_fts", ACTIVITY_FTS_COLUMNS),
+            filter.condition("activities_fts", ACTIVITY_FTS_COLUMNS)
+        );
+        let mut select = sqlx::query(&sql);
+        for value in filter.binds(ACTIVITY_FTS_COLUMNS) {
+            select = select.bind(value);
+        }
+        let rows = select
+            .bind(searches_private)
+            .bind(limit)
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("FTS search error: {e}"),
+            })?;
 
         let mut results = Vec::new();
         for row in rows {
@@ -147,7 +214,7 @@ impl PetDatabase {
     pub async fn get_fts_index_stats(&self) -> Result<FtsIndexStats, ActivityError> {
         // Get number of indexed documents
         let doc_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM activities_fts")
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .map_err(|e| ActivityError::InvalidData {
                 message: format!("FTS count error: {e}"),
@@ -160,7 +227,7 @@ impl PetDatabase {
         let integrity_result = sqlx::query_scalar::<_, String>(
             "SELECT 'ok' FROM activities_fts WHERE activities_fts MATCH 'test' LIMIT 1",
         )
-        .fetch_optional(&self.pool)
+        .fetch_optional(&self.read_pool)
         .await;
 
         let integrity_ok = integrity_result.is_ok();
@@ -179,7 +246,7 @@ impl PetDatabase {
 
         // Check if FTS table exists and is accessible
         let fts_accessible = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM activities_fts")
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .is_ok();
 
@@ -195,14 +262,14 @@ impl PetDatabase {
 
         // Get counts from both tables
         let activities_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM activities")
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .map_err(|e| ActivityError::InvalidData {
                 message: format!("Activities count error: {e}"),
             })?;
 
         let fts_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM activities_fts")
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await
             .map_err(|e| ActivityError::InvalidData {
                 message: format!("FTS count error: {e}"),
@@ -221,7 +288,7 @@ impl PetDatabase {
         let orphaned_fts: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM activities_fts WHERE rowid NOT IN (SELECT id FROM activities)",
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&self.read_pool)
         .await
         .map_err(|e| ActivityError::InvalidData {
             message: format!("Orphaned FTS check error: {e}"),
@@ -235,7 +302,7 @@ impl PetDatabase {
         let missing_fts: i64 = sqlx::query_scalar(
             "SELECT COUNT(*) FROM activities WHERE id NOT IN (SELECT rowid FROM activities_fts)",
         )
-        .fetch_one(&self.pool)
+        .fetch_one(&self.read_pool)
         .await
         .map_err(|e| ActivityError::InvalidData {
             message: format!("Missing FTS check error: {e}"),
@@ -283,6 +350,9 @@ impl PetDatabase {
 
         let mut added_missing = 0;
 
+        // Maintenance waits for any activity the user is logging
+        let _turn = self.writes.acquire(WritePriority::Background).await;
+
         // Start a transaction
         let mut tx = self
             .pool
@@ -327,16 +397,16 @@ impl PetDatabase {
             let searchable_content = activity_data.unwrap_or_else(|| "{}".to_string());
 
             sqlx::query(
-                "INSERT INTO activities_fts(rowid, title, description, subcategory, location) VALUES (?, ?, ?, ?, ?)"
+                "INSERT INTO activities_fts(rowid, subcategory, activity_data) VALUES (?, ?, ?)",
             )
             .bind(id)
-            .bind(&subcategory) // Use subcategory as title for now
-            .bind(&searchable_content) // Store JSON data for search
             .bind(&subcategory)
-            .bind("") // No separate location field anymore
+            .bind(&searchable_content)
             .execute(&mut *tx)
             .await
-            .map_err(|e| ActivityError::InvalidData { message: format!("FTS repair insert error: {e}") })?;
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("FTS repair insert error: {e}"),
+            })?;
 
             added_missing += 1;
         }
@@ -367,22 +437,6 @@ impl PetDatabase {
             duration_ms: 0,
         })
     }
-
-    /// Sanitize FTS query to prevent injection and improve search quality
-    fn sanitize_fts_query(&self, query: &str) -> String {
-        // Remove potentially harmful characters and normalize the query
-        let cleaned = query
-            .chars()
-            .filter(|c| c.is_alphanumeric() || c.is_whitespace() || "-_*\"".contains(*c))
-            .collect::<String>();
-
-        // If the query looks like a phrase, wrap it in quotes
-        if cleaned.contains(' ') && !cleaned.contains('"') {
-            format!("\"{}\"", cleaned.trim())
-        } else {
-            cleaned.trim().to_string()
-        }
-    }
 }
 
 /// FTS search result with relevance ranking
@@ -0,0 +1,558 @@
+use super::activity_data::BlockData;
+use super::models::{Activity, ExportActivitiesRequest, Pet, PetSpecies};
+use crate::errors::ActivityError;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Settings key of the per-pet grooming plans, a map of pet ID to plan
+pub const GROOMING_PLANS_SETTING_KEY: &str = "grooming_plans";
+
+/// Longest interval a plan may set for a task
+const MAX_INTERVAL_DAYS: u32 = 365;
+
+/// A task is due soon this many days before its due date
+const DUE_SOON_DAYS: i64 = 3;
+
+/// Days of history the on-time rate looks at
+const COMPLIANCE_WINDOW_DAYS: u64 = 365;
+
+/// A task done up to this share of its interval late still counts as on time
+const ON_TIME_GRACE: f64 = 0.2;
+
+/// Grooming chores a plan schedules
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum GroomingTask {
+    Bath,
+    NailTrim,
+    CoatTrim,
+}
+
+impl GroomingTask {
+    pub const ALL: [GroomingTask; 3] = [
+        GroomingTask::Bath,
+        GroomingTask::NailTrim,
+        GroomingTask::CoatTrim,
+    ];
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            GroomingTask::Bath => "bath",
+            GroomingTask::NailTrim => "nail_trim",
+            GroomingTask::CoatTrim => "coat_trim",
+        }
+    }
+
+    /// Words in a subcategory or title that mean the task was done. A full
+    /// grooming appointment counts as a bath and a coat trim.
+    fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            GroomingTask::Bath => &["bath", "wash", "groom", "洗澡", "美容"],
+            GroomingTask::NailTrim => &["nail", "claw", "指甲"],
+            GroomingTask::CoatTrim => {
+                &["haircut", "coat", "clip", "shave", "groom", "剪毛", "美容"]
+            }
+        }
+    }
+}
+
+/// Coat types with different grooming needs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CoatType {
+    Short,
+    Medium,
+    Long,
+    Wire,
+    Curly,
+    Hairless,
+}
+
+/// Breed name fragments by coat type, checked in order so "yorkshire terrier"
+/// is long before "terrier" is wire
+const BREED_COATS: [(CoatType, &[&str]); 5] = [
+    (
+        CoatType::Hairless,
+        &["sphynx", "peterbald", "chinese crested", "xolo", "hairless"],
+    ),
+    (
+        CoatType::Curly,
+        &[
+            "poodle",
+            "doodle",
+            "bichon",
+            "rex",
+            "portuguese water",
+            "lagotto",
+        ],
+    ),
+    (
+        CoatType::Long,
+        &[
+            "persian",
+            "maine coon",
+            "ragdoll",
+            "himalayan",
+            "norwegian forest",
+            "siberian",
+            "birman",
+            "angora",
+            "golden retriever",
+            "collie",
+            "shih tzu",
+            "yorkshire",
+            "yorkie",
+            "maltese",
+            "afghan",
+            "lhasa",
+            "havanese",
+            "pomeranian",
+            "bernese",
+            "samoyed",
+            "longhair",
+        ],
+    ),
+    (
+        CoatType::Wire,
+        &["schnauzer", "terrier", "wirehaired", "griffon"],
+    ),
+    (
+        CoatType::Short,
+        &[
+            "shorthair",
+            "siamese",
+            "bengal",
+            "russian blue",
+            "abyssinian",
+            "labrador",
+            "beagle",
+            "boxer",
+            "pug",
+            "bulldog",
+            "chihuahua",
+            "dalmatian",
+            "doberman",
+            "greyhound",
+            "rottweiler",
+            "whippet",
+        ],
+    ),
+];
+
+/// Coat type of a breed, or None when the breed isn't known
+pub fn coat_type_for_breed(breed: &str) -> Option<CoatType> {
+    let breed = breed.to_lowercase();
+    BREED_COATS
+        .iter()
+        .find(|(_, names)| names.iter().any(|name| breed.contains(name)))
+        .map(|(coat, _)| *coat)
+}
+
+/// Usual days between each task for a species and coat, or None when the task
+/// isn't needed: most cats never need a bath, and short coats no trims
+pub fn default_interval_days(
+    species: &PetSpecies,
+    coat: CoatType,
+    task: GroomingTask,
+) -> Option<u32> {
+    match (species, task) {
+        (PetSpecies::Dog, GroomingTask::NailTrim) => {
+            Some(if coat == CoatType::Curly { 21 } else { 28 })
+        }
+        (PetSpecies::Cat, GroomingTask::NailTrim) => Some(14),
+        (PetSpecies::Dog, GroomingTask::Bath) => Some(match coat {
+            CoatType::Hairless => 7,
+            CoatType::Curly => 21,
+            CoatType::Long => 28,
+            CoatType::Medium | CoatType::Wire => 42,
+            CoatType::Short => 56,
+        }),
+        (PetSpecies::Cat, GroomingTask::Bath) => match coat {
+            // Skin oils build up without fur to soak them up
+            CoatType::Hairless => Some(7),
+            CoatType::Curly => Some(30),
+            CoatType::Long => Some(60),
+            _ => None,
+        },
+        (PetSpecies::Dog, GroomingTask::CoatTrim) => match coat {
+            CoatType::Curly => Some(42),
+            CoatType::Long => Some(56),
+            CoatType::Wire => Some(84),
+            _ => None,
+        },
+        (PetSpecies::Cat, GroomingTask::CoatTrim) => (coat == CoatType::Long).then_some(90),
+    }
+}
+
+/// A pet's grooming plan as saved in settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroomingPlan {
+    /// None to go by the pet's breed
+    #[serde(default)]
+    pub coat_type: Option<CoatType>,
+    /// Days between each task, replacing the coat type's; 0 leaves a task out
+    #[serde(default)]
+    pub intervals: BTreeMap<GroomingTask, u32>,
+    /// When the plan began; tasks never done are due from this day
+    pub started_on: NaiveDate,
+}
+
+impl GroomingPlan {
+    fn validate(&self) -> Result<(), ActivityError> {
+        if let Some((task, days)) = self
+            .intervals
+            .iter()
+            .find(|(_, days)| **days > MAX_INTERVAL_DAYS)
+        {
+            return Err(ActivityError::validation(
+                "intervals".to_string(),
+                format!(
+                    "The {} interval of {days} days is longer than {MAX_INTERVAL_DAYS} days",
+                    task.key()
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Where a task stands against its interval
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GroomingTaskState {
+    OnTrack,
+    DueSoon,
+    /// Due today or earlier
+    Due,
+    /// Never recorded since the plan began
+    NeverDone,
+}
+
+/// One task of a pet's grooming plan
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroomingTaskStatus {
+    pub task: GroomingTask,
+    pub interval_days: u32,
+    pub last_done: Option<NaiveDate>,
+    pub next_due: NaiveDate,
+    /// Days past the due date; negative while it is still ahead
+    pub days_overdue: i64,
+    pub state: GroomingTaskState,
+    /// Share of the last year's intervals done on time, None until done twice
+    pub on_time_rate: Option<f64>,
+}
+
+/// A pet's grooming plan and how well it is being kept
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroomingStatus {
+    pub pet_id: i64,
+    pub coat_type: CoatType,
+    /// Whether the plan was saved, rather than a suggestion from the breed
+    pub has_plan: bool,
+    pub tasks: Vec<GroomingTaskStatus>,
+    /// Average on-time rate of the tasks that have one
+    pub compliance: Option<f64>,
+}
+
+/// A planned grooming task that has come due
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GroomingReminder {
+    pub pet_id: i64,
+    #[serde(flatten)]
+    pub task: GroomingTaskStatus,
+}
+
+impl super::PetDatabase {
+    /// A pet's grooming tasks with when each was last done and is next due. Pets
+    /// without a saved plan get the usual intervals for their breed.
+    pub async fn get_grooming_status(
+        &self,
+        pet_id: i64,
+        today: NaiveDate,
+    ) -> Result<GroomingStatus, ActivityError> {
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|e| ActivityError::validation("pet_id", &format!("Pet not found: {e}")))?;
+        let plan = self.get_grooming_plans().await?.remove(&pet_id);
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id: Some(pet_id),
+                format: None,
+            })
+            .await?;
+
+        Ok(grooming_status(&pet, plan.as_ref(), &activities, today))
+    }
+
+    /// Tasks due today or earlier for every pet with a saved plan. Pets without
+    /// one only see suggested intervals and aren't reminded.
+    pub async fn get_due_grooming(
+        &self,
+        today: NaiveDate,
+    ) -> Result<Vec<GroomingReminder>, ActivityError> {
+        let plans = self.get_grooming_plans().await?;
+        let mut due = Vec::new();
+        for pet_id in plans.keys() {
+            let status = match self.get_grooming_status(*pet_id, today).await {
+                Ok(status) => status,
+                // A plan left behind by a deleted pet
+                Err(e) => {
+                    log::debug!("[DB] get_due_grooming: skipping pet_id={pet_id}: {e}");
+                    continue;
+                }
+            };
+            due.extend(
+                status
+                    .tasks
+                    .into_iter()
+                    .filter(|task| task.days_overdue >= 0)
+                    .map(|task| GroomingReminder {
+                        pet_id: *pet_id,
+                        task,
+                    }),
+            );
+        }
+        Ok(due)
+    }
+
+    /// Save a pet's grooming plan, or remove it with None
+    pub async fn set_grooming_plan(
+        &self,
+        pet_id: i64,
+        plan: Option<GroomingPlan>,
+    ) -> Result<(), ActivityError> {
+        let mut plans = self.get_grooming_plans().await?;
+        match plan {
+            Some(plan) => {
+                plan.validate()?;
+                plans.insert(pet_id, plan);
+            }
+            None => {
+                plans.remove(&pet_id);
+            }
+        }
+        self.set_setting(GROOMING_PLANS_SETTING_KEY, &plans)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+    }
+
+    async fn get_grooming_plans(&self) -> Result<HashMap<i64, GroomingPlan>, ActivityError> {
+        Ok(self
+            .get_setting::<HashMap<i64, GroomingPlan>>(GROOMING_PLANS_SETTING_KEY)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .unwrap_or_default())
+    }
+}
+
+/// Whether an activity records a grooming task, by its subcategory and title
+fn records_task(activity: &Activity, task: GroomingTask) -> bool {
+    let title = match activity.activity_data.as_ref().and_then(|d| d.get("title")) {
+        Some(BlockData::Text(title)) => title.as_str(),
+        _ => "",
+    };
+    let text = format!("{} {title}", activity.subcategory).to_lowercase();
+    task.keywords().iter().any(|keyword| text.contains(keyword))
+}
+
+/// Grooming status of a pet from its activities, under its plan or else the
+/// usual intervals for its breed starting today
+pub fn grooming_status(
+    pet: &Pet,
+    plan: Option<&GroomingPlan>,
+    activities: &[Activity],
+    today: NaiveDate,
+) -> GroomingStatus {
+    let coat_type = plan
+        .and_then(|plan| plan.coat_type)
+        .or_else(|| pet.breed.as_deref().and_then(coat_type_for_breed))
+        .unwrap_or(CoatType::Medium);
+    let started_on = plan.map_or(today, |plan| plan.started_on);
+
+    let tasks: Vec<GroomingTaskStatus> = GroomingTask::ALL
+        .iter()
+        .filter_map(|&task| {
+            let interval_days = match plan.and_then(|plan| plan.intervals.get(&task)) {
+                Some(0) => return None,
+                Some(days) => *days,
+                None => default_interval_days(&pet.species, coat_type, task)?,
+            };
+            let mut done: Vec<NaiveDate> = activities
+                .iter()
+                .filter(|a| records_task(a, task))
+                .map(|a| a.occurred_on())
+                .filter(|date| *date <= today)
+                .collect();
+            done.sort();
+            done.dedup();
+            Some(task_status(task, interval_days, &done, started_on, today))
+        })
+        .collect();
+
+    let rates: Vec<f64> = tasks.iter().filter_map(|t| t.on_time_rate).collect();
+    GroomingStatus {
+        pet_id: pet.id,
+        coat_type,
+        has_plan: plan.is_some(),
+        compliance: (!rates.is_empty()).then(|| rates.iter().sum::<f64>() / rates.len() as f64),
+        tasks,
+    }
+}
+
+/// Status of one task from the dates it was done, oldest first
+fn task_status(
+    task: GroomingTask,
+    interval_days: u32,
+    done: &[NaiveDate],
+    started_on: NaiveDate,
+    today: NaiveDate,
+) -> GroomingTaskStatus {
+    let last_done = done.last().copied();
+    let next_due = last_done.map_or(started_on, |date| {
+        date + Days::new(u64::from(interval_days))
+    });
+    let days_overdue = (today - next_due).num_days();
+    let state = match last_done {
+        None if days_overdue >= 0 => GroomingTaskState::NeverDone,
+        _ if days_overdue >= 0 => GroomingTaskState::Due,
+        _ if days_overdue >= -DUE_SOON_DAYS => GroomingTaskState::DueSoon,
+        _ => GroomingTaskState::OnTrack,
+    };
+
+    // Gaps between visits in the window, plus the open one once it runs late
+    let allowed = f64::from(interval_days) * (1.0 + ON_TIME_GRACE);
+    let window_start = today - Days::new(COMPLIANCE_WINDOW_DAYS);
+    let mut gaps: Vec<f64> = done
+        .windows(2)
+        .filter(|pair| pair[1] >= window_start)
+        .map(|pair| (pair[1] - pair[0]).num_days() as f64)
+        .collect();
+    if let Some(last) = last_done {
+        let open = (today - last).num_days() as f64;
+        if open > allowed {
+            gaps.push(open);
+        }
+    }
+    let on_time_rate = (!gaps.is_empty())
+        .then(|| gaps.iter().filter(|gap| **gap <= allowed).count() as f64 / gaps.len() as f64);
+
+    GroomingTaskStatus {
+        task,
+        interval_days,
+        last_done,
+        next_due,
+        days_overdue,
+        state,
+        on_time_rate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::activity_data::ActivityDataExt;
+    use crate::database::models::{ActivityCategory, PetGender, WeightUnit};
+    use chrono::Utc;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn pet(species: PetSpecies, breed: &str) -> Pet {
+        Pet {
+            id: 1,
+            name: "Mochi".to_string(),
+            birth_date: date("2020-01-01"),
+            species,
+            gender: PetGender::Unknown,
+            breed: Some(breed.to_string()),
+            color: None,
+            weight_kg: None,
+            photo_path: None,
+            notes: None,
+            display_order: 0,
+            is_archived: false,
+            is_memorial: false,
+            passed_away_on: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_weight: None,
+            display_unit: WeightUnit::default(),
+        }
+    }
+
+    fn groomed(subcategory: &str, on: &str) -> Activity {
+        let data = serde_json::json!({
+            "time": { "date": format!("{on}T10:00:00.000Z"), "time": "", "timezone": "UTC" }
+        });
+        Activity {
+            id: 0,
+            pet_id: 1,
+            category: ActivityCategory::Lifestyle,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(crate::database::ActivityData::from_legacy_json(data)),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_coat_types_from_breeds() {
+        assert_eq!(coat_type_for_breed("Persian"), Some(CoatType::Long));
+        assert_eq!(
+            coat_type_for_breed("Yorkshire Terrier"),
+            Some(CoatType::Long)
+        );
+        assert_eq!(coat_type_for_breed("Border Terrier"), Some(CoatType::Wire));
+        assert_eq!(coat_type_for_breed("Toy Poodle"), Some(CoatType::Curly));
+        assert_eq!(
+            coat_type_for_breed("British Shorthair"),
+            Some(CoatType::Short)
+        );
+        assert_eq!(coat_type_for_breed("Mixed"), None);
+    }
+
+    #[test]
+    fn test_long_haired_cat_plan_and_compliance() {
+        let persian = pet(PetSpecies::Cat, "Persian");
+        let activities = vec![
+            groomed("Nail Trim", "2025-05-01"),
+            groomed("Nail Trim", "2025-05-15"),
+            // A month between trims is well past the 14 day interval
+            groomed("Nail Trim", "2025-06-14"),
+            groomed("Grooming", "2025-03-20"),
+        ];
+        let status = grooming_status(&persian, None, &activities, date("2025-06-20"));
+        assert_eq!(status.coat_type, CoatType::Long);
+        assert!(!status.has_plan);
+
+        let task = |task: GroomingTask| status.tasks.iter().find(|t| t.task == task).unwrap();
+        let nails = task(GroomingTask::NailTrim);
+        assert_eq!(nails.next_due, date("2025-06-28"));
+        assert_eq!(nails.state, GroomingTaskState::OnTrack);
+        assert_eq!(nails.on_time_rate, Some(0.5));
+        // A full grooming is both a bath and a trim
+        assert_eq!(task(GroomingTask::Bath).last_done, Some(date("2025-03-20")));
+        assert_eq!(task(GroomingTask::Bath).state, GroomingTaskState::Due);
+        assert_eq!(task(GroomingTask::CoatTrim).next_due, date("2025-06-18"));
+
+        // Short-haired cats need neither baths nor trims unless the plan says so
+        let plan = GroomingPlan {
+            coat_type: Some(CoatType::Short),
+            intervals: BTreeMap::from([(GroomingTask::Bath, 90), (GroomingTask::NailTrim, 0)]),
+            started_on: date("2025-06-01"),
+        };
+        let status = grooming_status(&persian, Some(&plan), &[], date("2025-06-20"));
+        let tasks: Vec<_> = status.tasks.iter().map(|t| (t.task, t.state)).collect();
+        assert_eq!(
+            tasks,
+            vec![(GroomingTask::Bath, GroomingTaskState::NeverDone)]
+        );
+        assert_eq!(status.tasks[0].next_due, date("2025-06-01"));
+        assert_eq!(status.compliance, None);
+    }
+}
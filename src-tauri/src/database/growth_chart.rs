@@ -0,0 +1,176 @@
+use super::activity_data::ActivityDataExt;
+use super::models::*;
+use crate::errors::PetError;
+use crate::growth_reference::{self, ReferenceRange};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+/// A height measurement from a growth activity
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct HeightRecord {
+    pub activity_id: i64,
+    pub date: NaiveDate,
+    pub height_cm: f32,
+}
+
+/// Typical adult size of the pet's breed, drawn from `adult_from` on
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GrowthReferenceBand {
+    pub breed: String,
+    /// False when the pet's breed isn't known and a species default is shown
+    pub breed_matched: bool,
+    pub adult_from: NaiveDate,
+    /// Adult weight in the chart's `display_unit`
+    pub weight: ReferenceRange,
+    pub height_cm: ReferenceRange,
+}
+
+/// Everything the growth chart draws for a pet
+#[derive(Debug, Clone, Serialize)]
+pub struct GrowthChart {
+    pub pet_id: i64,
+    pub birth_date: NaiveDate,
+    pub display_unit: WeightUnit,
+    pub weight: Vec<WeightRecord>,
+    pub height: Vec<HeightRecord>,
+    /// Milestones for the pet's species, with expected windows and completion dates
+    pub milestones: Vec<MilestoneProgress>,
+    /// None when there is no reference for the pet's breed
+    pub reference: Option<GrowthReferenceBand>,
+}
+
+impl super::PetDatabase {
+    /// Weight and height series, milestone markers and breed reference band of a
+    /// pet, with milestone status as of `today`
+    pub async fn get_growth_chart(
+        &self,
+        pet_id: i64,
+        unit: WeightUnit,
+        today: NaiveDate,
+    ) -> Result<GrowthChart, PetError> {
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|_| PetError::not_found(pet_id))?;
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id: Some(pet_id),
+                format: None,
+            })
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        let weight = super::pets::weight_records(&activities, unit);
+        let mut height: Vec<HeightRecord> = activities
+            .iter()
+            .filter_map(|activity| {
+                Some(HeightRecord {
+                    activity_id: activity.id,
+                    date: activity.occurred_on(),
+                    height_cm: activity.activity_data.as_ref()?.extract_height_cm()?,
+                })
+            })
+            .collect();
+        height.sort_by_key(|record| (record.date, record.activity_id));
+
+        let milestones = self.get_milestone_progress(pet_id, today).await?.milestones;
+        let reference = growth_reference::find_breed_reference(&pet.species, pet.breed.as_deref())
+            .map(|(reference, breed_matched)| GrowthReferenceBand {
+                breed: reference.breed.to_string(),
+                breed_matched,
+                adult_from: reference.adult_from(pet.birth_date),
+                weight: ReferenceRange {
+                    min: unit.from_kg(reference.adult_weight_kg.min),
+                    max: unit.from_kg(reference.adult_weight_kg.max),
+                },
+                height_cm: reference.adult_height_cm,
+            });
+
+        log::debug!(
+            "[DB] get_growth_chart: pet_id={pet_id}, weights={}, heights={}, reference={:?}",
+            weight.len(),
+            height.len(),
+            reference.as_ref().map(|band| &band.breed)
+        );
+        Ok(GrowthChart {
+            pet_id,
+            birth_date: pet.birth_date,
+            display_unit: unit,
+            weight,
+            height,
+            milestones,
+            reference,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityBlocksBuilder, ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use crate::database::activity_blocks::MeasurementKind;
+    use chrono::{TimeZone, Utc};
+
+    #[tokio::test]
+    async fn test_growth_chart_series_and_reference() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+        let pet = db.get_pet_by_id(pet_id).await.unwrap();
+
+        for (day, kind, value, unit) in [
+            (3, MeasurementKind::Height, 12.0, "in"),
+            (2, MeasurementKind::Weight, 4.0, "kg"),
+            (1, MeasurementKind::Height, 28.0, "cm"),
+        ] {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(Utc.with_ymd_and_hms(2025, 5, day, 9, 0, 0).unwrap());
+            blocks.measurement(kind, value, unit).unwrap();
+            db.create_activity(ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Growth,
+                subcategory: "Measurement".to_string(),
+                activity_data: Some(blocks.into_json()),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+        }
+
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let chart = db
+            .get_growth_chart(pet_id, WeightUnit::Lb, today)
+            .await
+            .unwrap();
+        assert_eq!(chart.weight.len(), 1);
+        assert_eq!(chart.weight[0].value, WeightUnit::Lb.from_kg(4.0));
+        assert_eq!(chart.height.len(), 2);
+        assert_eq!(chart.height[0].height_cm, 28.0);
+        assert!((chart.height[1].height_cm - 30.48).abs() < 0.01);
+        let keys = |milestones: &[MilestoneProgress]| -> Vec<String> {
+            milestones.iter().map(|m| m.key.clone()).collect()
+        };
+        let progress = db.get_milestone_progress(pet_id, today).await.unwrap();
+        assert_eq!(keys(&chart.milestones), keys(&progress.milestones));
+
+        let expected = growth_reference::find_breed_reference(&pet.species, pet.breed.as_deref());
+        assert_eq!(chart.reference.is_some(), expected.is_some());
+        if let (Some(band), Some((reference, _))) = (&chart.reference, expected) {
+            assert_eq!(
+                band.weight.max,
+                WeightUnit::Lb.from_kg(reference.adult_weight_kg.max)
+            );
+            assert_eq!(band.adult_from, reference.adult_from(pet.birth_date));
+        }
+
+        assert!(db
+            .get_growth_chart(pet_id + 100, WeightUnit::Kg, today)
+            .await
+            .is_err());
+    }
+}
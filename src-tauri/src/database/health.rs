@@ -0,0 +1,336 @@
+use super::activity_blocks::{BODY_CONDITION_SCORES, IDEAL_BODY_CONDITION};
+use super::activity_data::ActivityDataExt;
+use super::models::*;
+use crate::errors::ActivityError;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Relative weights of each factor in the composite score; the first four sum to
+/// 1.0 and the composite is rescaled when body condition is scored too
+const WEIGHT_STABILITY_WEIGHT: f64 = 0.3;
+const ACTIVITY_FREQUENCY_WEIGHT: f64 = 0.2;
+const VACCINATION_WEIGHT: f64 = 0.3;
+const CONDITIONS_WEIGHT: f64 = 0.2;
+const BODY_CONDITION_WEIGHT: f64 = 0.2;
+
+/// Days a body condition score counts towards the health score
+const BODY_CONDITION_MAX_AGE_DAYS: i64 = 180;
+
+/// Activities in the last 30 days needed for a full frequency score
+const TARGET_MONTHLY_ACTIVITIES: f64 = 20.0;
+
+/// Health subcategories that indicate an ongoing condition
+const CONDITION_SUBCATEGORIES: [&str; 2] = ["symptom", "medication"];
+
+/// A single factor contributing to the health score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthScoreFactor {
+    pub key: String,
+    pub label: String,
+    /// Factor score from 0 to 100
+    pub score: f64,
+    /// Relative weight in the composite score
+    pub weight: f64,
+    pub details: String,
+}
+
+/// Composite health score with a transparent factor breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthScore {
+    pub pet_id: i64,
+    /// Composite score from 0 to 100
+    pub score: f64,
+    pub factors: Vec<HealthScoreFactor>,
+    pub computed_on: NaiveDate,
+}
+
+impl super::PetDatabase {
+    /// Compute the health score for a pet from its activity history
+    pub async fn get_health_score(
+        &self,
+        pet_id: i64,
+        today: NaiveDate,
+    ) -> Result<HealthScore, ActivityError> {
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id: Some(pet_id),
+                format: None,
+            })
+            .await?;
+
+        log::debug!(
+            "[DB] get_health_score: pet_id={pet_id}, scoring {} activities",
+            activities.len()
+        );
+
+        Ok(compute_health_score(pet_id, &activities, today))
+    }
+}
+
+/// Compute the composite health score from a pet's activities
+pub fn compute_health_score(pet_id: i64, activities: &[Activity], today: NaiveDate) -> HealthScore {
+    let mut factors = vec![
+        weight_stability_factor(activities, today),
+        activity_frequency_factor(activities, today),
+        vaccination_factor(activities, today),
+        conditions_factor(activities, today),
+    ];
+    // Only pets with a recent score are judged on it, so no score isn't a penalty
+    factors.extend(body_condition_factor(activities, today));
+
+    let total_weight = factors.iter().map(|f| f.weight).sum::<f64>();
+    let score = factors.iter().map(|f| f.score * f.weight).sum::<f64>() / total_weight;
+
+    HealthScore {
+        pet_id,
+        score: score.round(),
+        factors,
+        computed_on: today,
+    }
+}
+
+fn days_ago(activity: &Activity, today: NaiveDate) -> i64 {
+    (today - activity.occurred_on()).num_days()
+}
+
+/// Score weight change over the last 90 days; large swings lower the score
+fn weight_stability_factor(activities: &[Activity], today: NaiveDate) -> HealthScoreFactor {
+    let mut weights: Vec<(NaiveDate, f32)> = activities
+        .iter()
+        .filter(|a| (0..=90).contains(&days_ago(a, today)))
+        .filter_map(|a| {
+            a.activity_data
+                .as_ref()
+                .and_then(|d| d.extract_weight_kg())
+                .map(|w| (a.occurred_on(), w))
+        })
+        .collect();
+    weights.sort_by_key(|(date, _)| *date);
+
+    let (score, details) = match (weights.first(), weights.last()) {
+        (Some((_, first)), Some((_, last))) if weights.len() >= 2 && *first > 0.0 => {
+            let change = ((last - first) / first * 100.0) as f64;
+            // Full score within ±5%, dropping to 0 at ±25%
+            let score = 100.0 - ((change.abs() - 5.0).max(0.0) * 5.0);
+            (
+                score.clamp(0.0, 100.0),
+                format!(
+                    "{change:+.1}% over {} weight records in 90 days",
+                    weights.len()
+                ),
+            )
+        }
+        _ => (
+            50.0,
+            "Not enough weight records in the last 90 days".to_string(),
+        ),
+    };
+
+    HealthScoreFactor {
+        key: "weight_stability".to_string(),
+        label: "Weight stability".to_string(),
+        score,
+        weight: WEIGHT_STABILITY_WEIGHT,
+        details,
+    }
+}
+
+/// Score how regularly activities are being recorded in the last 30 days
+fn activity_frequency_factor(activities: &[Activity], today: NaiveDate) -> HealthScoreFactor {
+    let recent = activities
+        .iter()
+        .filter(|a| (0..30).contains(&days_ago(a, today)))
+        .count();
+
+    HealthScoreFactor {
+        key: "activity_frequency".to_string(),
+        label: "Activity frequency".to_string(),
+        score: (recent as f64 / TARGET_MONTHLY_ACTIVITIES * 100.0).min(100.0),
+        weight: ACTIVITY_FREQUENCY_WEIGHT,
+        details: format!("{recent} activities recorded in the last 30 days"),
+    }
+}
+
+/// Score how recently the pet was vaccinated; boosters are expected yearly
+fn vaccination_factor(activities: &[Activity], today: NaiveDate) -> HealthScoreFactor {
+    let last_vaccination = activities
+        .iter()
+        .filter(|a| {
+            a.category == ActivityCategory::Health
+                && a.subcategory.to_lowercase().contains("vaccin")
+        })
+        .map(|a| a.occurred_on())
+        .filter(|date| *date <= today)
+        .max();
+
+    let (score, details) = match last_vaccination {
+        Some(date) => {
+            let age_days = (today - date).num_days();
+            // Current for a year, then loses all credit over the following 90 days
+            let score = if age_days <= 365 {
+                100.0
+            } else {
+                (100.0 - (age_days - 365) as f64 / 90.0 * 100.0).max(0.0)
+            };
+            (
+                score,
+                format!("Last vaccination on {date} ({age_days} days ago)"),
+            )
+        }
+        None => (0.0, "No vaccination records".to_string()),
+    };
+
+    HealthScoreFactor {
+        key: "vaccination_currency".to_string(),
+        label: "Vaccination currency".to_string(),
+        score,
+        weight: VACCINATION_WEIGHT,
+        details,
+    }
+}
+
+/// Penalize symptoms and medications recorded in the last 14 days
+fn conditions_factor(activities: &[Activity], today: NaiveDate) -> HealthScoreFactor {
+    let active = activities
+        .iter()
+        .filter(|a| a.category == ActivityCategory::Health)
+        .filter(|a| CONDITION_SUBCATEGORIES.contains(&a.subcategory.to_lowercase().as_str()))
+        .filter(|a| (0..14).contains(&days_ago(a, today)))
+        .count();
+
+    HealthScoreFactor {
+        key: "active_conditions".to_string(),
+        label: "Active conditions".to_string(),
+        score: (100.0 - active as f64 * 25.0).max(0.0),
+        weight: CONDITIONS_WEIGHT,
+        details: format!("{active} symptom or medication records in the last 14 days"),
+    }
+}
+
+/// Score the latest body condition score of the last 180 days; 4-5 is ideal and
+/// each point either side costs 25
+fn body_condition_factor(activities: &[Activity], today: NaiveDate) -> Option<HealthScoreFactor> {
+    let (date, bcs) = activities
+        .iter()
+        .filter(|a| (0..=BODY_CONDITION_MAX_AGE_DAYS).contains(&days_ago(a, today)))
+        .filter_map(|a| {
+            a.activity_data
+                .as_ref()
+                .and_then(|d| d.extract_body_condition_score())
+                .filter(|score| BODY_CONDITION_SCORES.contains(score))
+                .map(|score| (a.occurred_on(), score))
+        })
+        .max_by_key(|(date, _)| *date)?;
+
+    let distance = if bcs < *IDEAL_BODY_CONDITION.start() {
+        IDEAL_BODY_CONDITION.start() - bcs
+    } else {
+        bcs.saturating_sub(*IDEAL_BODY_CONDITION.end())
+    };
+
+    Some(HealthScoreFactor {
+        key: "body_condition".to_string(),
+        label: "Body condition".to_string(),
+        score: (100.0 - f64::from(distance) * 25.0).max(0.0),
+        weight: BODY_CONDITION_WEIGHT,
+        details: format!("Body condition {bcs}/9 on {date}"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::activity_data::BlockData;
+    use crate::database::ActivityData;
+    use chrono::Utc;
+
+    fn activity(category: ActivityCategory, subcategory: &str, date: &str) -> Activity {
+        let data = serde_json::json!({
+            "time": { "date": format!("{date}T10:00:00.000Z"), "time": "", "timezone": "" }
+        });
+        Activity {
+            id: 0,
+            pet_id: 1,
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(ActivityData::from_legacy_json(data)),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_empty_history_scores_neutral_weight_only() {
+        let score = compute_health_score(1, &[], date("2025-06-01"));
+        assert_eq!(score.factors.len(), 4);
+        // weight 50*0.3 + frequency 0 + vaccination 0 + conditions 100*0.2
+        assert_eq!(score.score, 35.0);
+    }
+
+    #[test]
+    fn test_recent_vaccination_and_symptoms() {
+        let activities = vec![
+            activity(ActivityCategory::Health, "Vaccination", "2025-03-01"),
+            activity(ActivityCategory::Health, "Symptom", "2025-05-28"),
+        ];
+        let score = compute_health_score(1, &activities, date("2025-06-01"));
+
+        let vaccination = score
+            .factors
+            .iter()
+            .find(|f| f.key == "vaccination_currency")
+            .unwrap();
+        assert_eq!(vaccination.score, 100.0);
+
+        let conditions = score
+            .factors
+            .iter()
+            .find(|f| f.key == "active_conditions")
+            .unwrap();
+        assert_eq!(conditions.score, 75.0);
+    }
+
+    #[test]
+    fn test_body_condition_factor_only_when_recently_scored() {
+        let scored = |score: u8, date: &str| {
+            let mut bcs = activity(ActivityCategory::Growth, "Body Condition", date);
+            bcs.activity_data
+                .as_mut()
+                .unwrap()
+                .insert("bcs".to_string(), BlockData::BodyCondition { score });
+            bcs
+        };
+        let today = date("2025-06-01");
+
+        let stale = compute_health_score(1, &[scored(8, "2024-10-01")], today);
+        assert_eq!(stale.factors.len(), 4);
+
+        let ideal = compute_health_score(1, &[scored(5, "2025-05-01")], today);
+        let factor = ideal
+            .factors
+            .iter()
+            .find(|f| f.key == "body_condition")
+            .unwrap();
+        assert_eq!(factor.score, 100.0);
+        // (35 + 100*0.2) / 1.2
+        assert_eq!(ideal.score, 46.0);
+
+        let obese = compute_health_score(
+            1,
+            &[scored(5, "2025-01-01"), scored(8, "2025-05-01")],
+            today,
+        );
+        let factor = obese
+            .factors
+            .iter()
+            .find(|f| f.key == "body_condition")
+            .unwrap();
+        assert_eq!(factor.score, 25.0);
+    }
+}
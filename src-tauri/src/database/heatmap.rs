@@ -0,0 +1,158 @@
+use super::summaries::DAILY_SUMMARY_SUBCATEGORY;
+use crate::errors::ActivityError;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// Activities logged per day of one year, for a contribution-graph style overview
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivityHeatmap {
+    pub pet_id: i64,
+    pub year: i32,
+    /// Count per day from January 1st, 365 or 366 cells
+    pub counts: Vec<i64>,
+    /// Weekday of January 1st, 0 = Monday, to offset the first column of the grid
+    pub first_weekday: u32,
+    pub total: i64,
+    /// Days with at least one activity
+    pub active_days: usize,
+    /// Highest count of a single day, for scaling the colors
+    pub max_count: i64,
+    /// Most consecutive active days in the year
+    pub longest_streak: usize,
+}
+
+impl super::PetDatabase {
+    /// Count a pet's activities per day of `year` (by activity time, UTC) in one query.
+    /// Generated daily summaries and activities waiting for review are not counted.
+    pub async fn get_activity_heatmap(
+        &self,
+        pet_id: i64,
+        year: i32,
+    ) -> Result<ActivityHeatmap, ActivityError> {
+        let (Some(start), Some(end)) = (
+            NaiveDate::from_ymd_opt(year, 1, 1),
+            NaiveDate::from_ymd_opt(year + 1, 1, 1),
+        ) else {
+            return Err(ActivityError::validation(
+                "year".to_string(),
+                format!("Unsupported year {year}"),
+            ));
+        };
+
+        let rows = sqlx::query(
+            r#"
+            SELECT CAST(strftime('%j', activity_time) AS INTEGER) AS day_of_year,
+                COUNT(*) AS count
+            FROM activities
+            WHERE pet_id = ? AND activity_time >= ? AND activity_time < ?
+                AND needs_review = 0 AND subcategory != ?
+            GROUP BY day_of_year
+            "#,
+        )
+        .bind(pet_id)
+        .bind(start.to_string())
+        .bind(end.to_string())
+        .bind(DAILY_SUMMARY_SUBCATEGORY)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let days = (end - start).num_days() as usize;
+        let mut counts = vec![0; days];
+        for row in &rows {
+            let day: i64 = row.get("day_of_year");
+            if let Some(cell) = counts.get_mut((day - 1) as usize) {
+                *cell = row.get("count");
+            }
+        }
+
+        log::debug!(
+            "[DB] get_activity_heatmap: pet_id={pet_id}, year={year}, active_days={}",
+            rows.len()
+        );
+        Ok(ActivityHeatmap::new(
+            pet_id,
+            year,
+            start.weekday().num_days_from_monday(),
+            counts,
+        ))
+    }
+}
+
+impl ActivityHeatmap {
+    fn new(pet_id: i64, year: i32, first_weekday: u32, counts: Vec<i64>) -> Self {
+        let mut longest_streak = 0;
+        let mut streak = 0;
+        for &count in &counts {
+            streak = if count > 0 { streak + 1 } else { 0 };
+            longest_streak = longest_streak.max(streak);
+        }
+
+        ActivityHeatmap {
+            pet_id,
+            year,
+            first_weekday,
+            total: counts.iter().sum(),
+            active_days: counts.iter().filter(|&&count| count > 0).count(),
+            max_count: counts.iter().copied().max().unwrap_or(0),
+            longest_streak,
+            counts,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_activity_heatmap_counts_days() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let on = |date: &str, subcategory: &str, needs_review: bool| ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Lifestyle,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(serde_json::json!({
+                "time": { "date": format!("{date}T10:00:00.000Z"), "time": "", "timezone": "" }
+            })),
+            needs_review,
+        };
+        for request in [
+            on("2024-01-01", "Walk", false),
+            on("2024-01-02", "Walk", false),
+            on("2024-01-02", "Play", false),
+            on("2024-01-03", "Walk", false),
+            on("2024-12-31", "Walk", false),
+            on("2024-01-05", DAILY_SUMMARY_SUBCATEGORY, false),
+            on("2024-01-06", "Walk", true),
+            on("2025-01-01", "Walk", false),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let heatmap = db.get_activity_heatmap(pet_id, 2024).await.unwrap();
+        // Leap year
+        assert_eq!(heatmap.counts.len(), 366);
+        assert_eq!(&heatmap.counts[..6], &[1, 2, 1, 0, 0, 0]);
+        assert_eq!(heatmap.counts[365], 1);
+        assert_eq!(heatmap.first_weekday, 0);
+        assert_eq!(heatmap.total, 5);
+        assert_eq!(heatmap.active_days, 4);
+        assert_eq!(heatmap.max_count, 2);
+        assert_eq!(heatmap.longest_streak, 3);
+
+        let next_year = db.get_activity_heatmap(pet_id, 2025).await.unwrap();
+        assert_eq!(next_year.counts.len(), 365);
+        assert_eq!(next_year.total, 1);
+    }
+}
@@ -0,0 +1,477 @@
+use super::activity_data::ActivityDataExt;
+use super::models::*;
+use super::PetDatabase;
+use crate::errors::ActivityError;
+use futures::future::BoxFuture;
+use sqlx::SqliteConnection;
+use std::sync::{Arc, RwLock};
+
+/// Change to an activity that hooks are notified about
+#[derive(Debug, Clone, Copy)]
+pub enum ActivityEvent<'a> {
+    Created(&'a Activity),
+    Updated {
+        before: &'a Activity,
+        after: &'a Activity,
+    },
+    Deleted(&'a Activity),
+}
+
+impl ActivityEvent<'_> {
+    /// The activity as it is after the change (the removed one for deletions)
+    pub fn activity(&self) -> &Activity {
+        match self {
+            ActivityEvent::Created(activity) | ActivityEvent::Deleted(activity) => activity,
+            ActivityEvent::Updated { after, .. } => after,
+        }
+    }
+}
+
+/// A module that reacts to activity changes.
+///
+/// Hooks run inside the transaction of the change, after the activity row has been
+/// written, so an error from any hook rolls the whole change back.
+pub trait ActivityHook: Send + Sync {
+    /// Name used in logs
+    fn name(&self) -> &'static str;
+
+    fn on_activity_event<'a>(
+        &'a self,
+        conn: &'a mut SqliteConnection,
+        event: ActivityEvent<'a>,
+    ) -> BoxFuture<'a, Result<(), ActivityError>>;
+}
+
+/// Ordered list of activity hooks, shared between the database and the app state
+pub struct ActivityHooks {
+    hooks: RwLock<Vec<Arc<dyn ActivityHook>>>,
+}
+
+impl ActivityHooks {
+    /// An empty hook list
+    pub fn new() -> Self {
+        ActivityHooks {
+            hooks: RwLock::new(Vec::new()),
+        }
+    }
+
+    /// The hooks every database starts with
+    pub fn with_builtin() -> Self {
+        let hooks = Self::new();
+        hooks.register(Arc::new(PetProfileHook));
+        hooks.register(Arc::new(AttachmentCleanupHook));
+        hooks.register(Arc::new(InventoryHook));
+        hooks.register(Arc::new(SmartDefaultsHook));
+        hooks.register(Arc::new(MetricRulesHook));
+        hooks.register(Arc::new(super::webhooks::WebhookOutboxHook));
+        hooks
+    }
+
+    /// Add a hook; hooks run in registration order
+    pub fn register(&self, hook: Arc<dyn ActivityHook>) {
+        log::debug!("[DB] register activity hook: {}", hook.name());
+        self.hooks
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(hook);
+    }
+
+    /// Names of the registered hooks, in order
+    pub fn names(&self) -> Vec<&'static str> {
+        self.snapshot().iter().map(|hook| hook.name()).collect()
+    }
+
+    fn snapshot(&self) -> Vec<Arc<dyn ActivityHook>> {
+        self.hooks.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Run every hook for `event`, stopping at the first error
+    pub async fn dispatch(
+        &self,
+        conn: &mut SqliteConnection,
+        event: ActivityEvent<'_>,
+    ) -> Result<(), ActivityError> {
+        for hook in self.snapshot() {
+            hook.on_activity_event(&mut *conn, event)
+                .await
+                .inspect_err(|e| {
+                    log::error!(
+                        "[DB] activity hook {} failed for activity_id={}: {e}",
+                        hook.name(),
+                        event.activity().id
+                    );
+                })?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ActivityHooks {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps the pet profile in sync with new measurements (currently weight). A measurement
+/// only replaces the profile weight when no other one is dated later.
+pub struct PetProfileHook;
+
+impl ActivityHook for PetProfileHook {
+    fn name(&self) -> &'static str {
+        "pet_profile"
+    }
+
+    fn on_activity_event<'a>(
+        &'a self,
+        conn: &'a mut SqliteConnection,
+        event: ActivityEvent<'a>,
+    ) -> BoxFuture<'a, Result<(), ActivityError>> {
+        Box::pin(async move {
+            let ActivityEvent::Created(activity) = event else {
+                return Ok(());
+            };
+            let Some(weight_kg) = activity
+                .activity_data
+                .as_ref()
+                .filter(|data| data.should_update_pet_profile())
+                .and_then(|data| data.extract_weight_kg())
+            else {
+                return Ok(());
+            };
+
+            // Imports can add old measurements after newer ones; only the latest by
+            // activity date belongs on the profile
+            let newer_exists: bool = sqlx::query_scalar(
+                r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM activities this
+                    JOIN activities other ON other.pet_id = this.pet_id AND other.id != this.id
+                    WHERE this.id = ?
+                        AND json_extract(other.activity_data, '$.weight.value') IS NOT NULL
+                        AND other.activity_time > this.activity_time
+                )
+                "#,
+            )
+            .bind(activity.id)
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+            if newer_exists {
+                log::debug!(
+                    "[DB] pet_profile hook: keeping profile weight, pet_id={} has a newer measurement than activity_id={}",
+                    activity.pet_id,
+                    activity.id
+                );
+                return Ok(());
+            }
+
+            log::info!(
+                "[DB] pet_profile hook: updating pet weight to {} kg for pet_id={}",
+                weight_kg,
+                activity.pet_id
+            );
+            sqlx::query("UPDATE pets SET weight_kg = ?, updated_at = ? WHERE id = ?")
+                .bind(weight_kg)
+                .bind(chrono::Utc::now())
+                .bind(activity.pet_id)
+                .execute(&mut *conn)
+                .await
+                .map_err(|e| {
+                    ActivityError::invalid_data(format!("Failed to update pet weight: {e}"))
+                })?;
+            Ok(())
+        })
+    }
+}
+
+/// Journals attachment files of deleted activities that nothing else uses
+pub struct AttachmentCleanupHook;
+
+impl ActivityHook for AttachmentCleanupHook {
+    fn name(&self) -> &'static str {
+        "attachment_cleanup"
+    }
+
+    fn on_activity_event<'a>(
+        &'a self,
+        conn: &'a mut SqliteConnection,
+        event: ActivityEvent<'a>,
+    ) -> BoxFuture<'a, Result<(), ActivityError>> {
+        Box::pin(async move {
+            let ActivityEvent::Deleted(activity) = event else {
+                return Ok(());
+            };
+            let files = activity
+                .activity_data
+                .as_ref()
+                .map(|data| data.attachment_files())
+                .unwrap_or_default();
+
+            for file_name in files {
+                if PetDatabase::is_photo_file_referenced(conn, &file_name, activity.id).await? {
+                    log::debug!("[DB] attachment_cleanup hook: keeping shared file {file_name}");
+                    continue;
+                }
+                PetDatabase::journal_file_deletion(conn, &file_name, activity.id).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Draws logged food portions, litter changes and medication doses from the inventory
+pub struct InventoryHook;
+
+impl ActivityHook for InventoryHook {
+    fn name(&self) -> &'static str {
+        "inventory"
+    }
+
+    fn on_activity_event<'a>(
+        &'a self,
+        conn: &'a mut SqliteConnection,
+        event: ActivityEvent<'a>,
+    ) -> BoxFuture<'a, Result<(), ActivityError>> {
+        Box::pin(async move {
+            match event {
+                ActivityEvent::Created(activity) => {
+                    PetDatabase::apply_inventory_consumption(conn, activity).await
+                }
+                ActivityEvent::Updated { before, after } => {
+                    PetDatabase::revert_inventory_consumption(conn, before.id).await?;
+                    PetDatabase::apply_inventory_consumption(conn, after).await
+                }
+                ActivityEvent::Deleted(activity) => {
+                    PetDatabase::revert_inventory_consumption(conn, activity.id).await
+                }
+            }
+        })
+    }
+}
+
+/// Remembers portions, durations and brands of new activities as per-pet defaults
+pub struct SmartDefaultsHook;
+
+impl ActivityHook for SmartDefaultsHook {
+    fn name(&self) -> &'static str {
+        "smart_defaults"
+    }
+
+    fn on_activity_event<'a>(
+        &'a self,
+        conn: &'a mut SqliteConnection,
+        event: ActivityEvent<'a>,
+    ) -> BoxFuture<'a, Result<(), ActivityError>> {
+        Box::pin(async move {
+            let ActivityEvent::Created(activity) = event else {
+                return Ok(());
+            };
+            PetDatabase::record_smart_defaults(conn, activity).await
+        })
+    }
+}
+
+/// Checks new and edited measurements and intake against the metric rules
+pub struct MetricRulesHook;
+
+impl ActivityHook for MetricRulesHook {
+    fn name(&self) -> &'static str {
+        "metric_rules"
+    }
+
+    fn on_activity_event<'a>(
+        &'a self,
+        conn: &'a mut SqliteConnection,
+        event: ActivityEvent<'a>,
+    ) -> BoxFuture<'a, Result<(), ActivityError>> {
+        Box::pin(async move {
+            match event {
+                ActivityEvent::Created(activity)
+                | ActivityEvent::Updated {
+                    after: activity, ..
+                } => PetDatabase::evaluate_metric_rules(conn, activity).await,
+                ActivityEvent::Deleted(_) => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::super::ActivityBlocksBuilder;
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records events and optionally fails on creation
+    #[derive(Default)]
+    struct RecordingHook {
+        events: Mutex<Vec<String>>,
+        fail_on_create: bool,
+    }
+
+    impl ActivityHook for RecordingHook {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        fn on_activity_event<'a>(
+            &'a self,
+            _conn: &'a mut SqliteConnection,
+            event: ActivityEvent<'a>,
+        ) -> BoxFuture<'a, Result<(), ActivityError>> {
+            Box::pin(async move {
+                let label = match event {
+                    ActivityEvent::Created(a) => format!("created:{}", a.subcategory),
+                    ActivityEvent::Updated { before, after } => {
+                        format!("updated:{}->{}", before.subcategory, after.subcategory)
+                    }
+                    ActivityEvent::Deleted(a) => format!("deleted:{}", a.subcategory),
+                };
+                self.events.lock().unwrap().push(label);
+                if self.fail_on_create && matches!(event, ActivityEvent::Created(_)) {
+                    return Err(ActivityError::invalid_data("hook failed"));
+                }
+                Ok(())
+            })
+        }
+    }
+
+    async fn create_pet(db: &PetDatabase) -> Pet {
+        db.create_pet(CreatePetRequest {
+            name: "Mochi".to_string(),
+            birth_date: chrono::NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+            species: PetSpecies::Cat,
+            gender: PetGender::Female,
+            breed: None,
+            color: None,
+            weight_kg: Some(4.0),
+            photo_path: None,
+            notes: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    fn weight_request(pet_id: i64, subcategory: &str) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Growth,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(serde_json::json!({
+                "weight": { "value": "4.6", "unit": "kg", "measurementType": "weight" }
+            })),
+            needs_review: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hooks_receive_lifecycle_events() {
+        let (db, _dir) = test_database().await;
+        let pet = create_pet(&db).await;
+        let hook = Arc::new(RecordingHook::default());
+        db.activity_hooks().register(hook.clone());
+        assert_eq!(
+            db.activity_hooks().names(),
+            vec![
+                "pet_profile",
+                "attachment_cleanup",
+                "inventory",
+                "smart_defaults",
+                "metric_rules",
+                "webhook_outbox",
+                "recording"
+            ]
+        );
+
+        let activity = db
+            .create_activity_with_side_effects(weight_request(pet.id, "Weight"))
+            .await
+            .unwrap();
+        assert_eq!(db.get_pet_by_id(pet.id).await.unwrap().weight_kg, Some(4.6));
+
+        db.update_activity(
+            activity.id,
+            ActivityUpdateRequest {
+                subcategory: Some("Weigh-in".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        db.delete_activity(activity.id).await.unwrap();
+
+        assert_eq!(
+            *hook.events.lock().unwrap(),
+            vec![
+                "created:Weight",
+                "updated:Weight->Weigh-in",
+                "deleted:Weigh-in"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_profile_weight_follows_latest_measurement_date() {
+        let (db, _dir) = test_database().await;
+        let pet = create_pet(&db).await;
+        let weigh_in = |date: &str, kg: f64| {
+            let at = chrono::DateTime::parse_from_rfc3339(&format!("{date}T09:00:00Z"))
+                .unwrap()
+                .with_timezone(&chrono::Utc);
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(at).weight(kg, "kg").unwrap();
+            ActivityCreateRequest {
+                pet_id: pet.id,
+                category: ActivityCategory::Growth,
+                subcategory: "Weight".to_string(),
+                activity_data: Some(blocks.into_json()),
+                needs_review: false,
+            }
+        };
+
+        // Imported out of order: the newest measurement comes first
+        for (date, kg) in [
+            ("2025-05-01", 5.2),
+            ("2024-01-10", 3.1),
+            ("2025-02-01", 4.8),
+        ] {
+            db.create_activity_with_side_effects(weigh_in(date, kg))
+                .await
+                .unwrap();
+        }
+        assert_eq!(db.get_pet_by_id(pet.id).await.unwrap().weight_kg, Some(5.2));
+
+        // A later measurement still replaces it
+        db.create_activity_with_side_effects(weigh_in("2025-06-01", 5.4))
+            .await
+            .unwrap();
+        assert_eq!(db.get_pet_by_id(pet.id).await.unwrap().weight_kg, Some(5.4));
+    }
+
+    #[tokio::test]
+    async fn test_failing_hook_rolls_back_creation() {
+        let (db, _dir) = test_database().await;
+        let pet = create_pet(&db).await;
+        db.activity_hooks().register(Arc::new(RecordingHook {
+            fail_on_create: true,
+            ..Default::default()
+        }));
+
+        assert!(db
+            .create_activity_with_side_effects(weight_request(pet.id, "Weight"))
+            .await
+            .is_err());
+
+        // Neither the activity nor the weight side effect was kept
+        let activities = db
+            .get_activities(GetActivitiesRequest {
+                pet_id: Some(pet.id),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert!(activities.activities.is_empty());
+        assert_eq!(db.get_pet_by_id(pet.id).await.unwrap().weight_kg, Some(4.0));
+    }
+}
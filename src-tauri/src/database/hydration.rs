@@ -0,0 +1,262 @@
+use super::activity_data::water_ml;
+use crate::errors::ActivityError;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::{BTreeMap, HashMap};
+
+/// Settings key of the per-pet daily water targets, a map of pet ID to millilitres
+pub const WATER_TARGETS_SETTING_KEY: &str = "water_targets";
+
+/// Daily water need per kilogram of body weight, used when no target is set
+pub const DEFAULT_WATER_ML_PER_KG: f32 = 50.0;
+
+/// Longest range `get_water_intake` reports on
+const MAX_INTAKE_RANGE_DAYS: u64 = 366;
+
+/// Where a pet's daily water target comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WaterTargetSource {
+    /// Set by the user in settings
+    Setting,
+    /// Estimated from the pet's weight
+    Weight,
+    /// No target: no setting and no known weight
+    None,
+}
+
+/// Water taken in on one day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WaterIntakeDay {
+    pub date: NaiveDate,
+    pub total_ml: f32,
+    /// Hydration entries logged that day
+    pub entries: i64,
+    /// Logged intake fell short of the daily target. Days without entries are
+    /// never flagged, since nothing logged doesn't mean nothing drunk.
+    pub below_target: bool,
+}
+
+/// Daily water intake of a pet over a date range, compared with its daily target
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WaterIntakeReport {
+    pub pet_id: i64,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub daily_target_ml: Option<f32>,
+    pub target_source: WaterTargetSource,
+    /// Every day of the range, oldest first
+    pub days: Vec<WaterIntakeDay>,
+    pub days_below_target: Vec<NaiveDate>,
+    /// Average over the days with entries
+    pub average_ml: Option<f32>,
+}
+
+impl super::PetDatabase {
+    /// Sum a pet's hydration blocks per day (by activity time, UTC) from `start_date`
+    /// to `end_date` inclusive, flagging days below its daily water target
+    pub async fn get_water_intake(
+        &self,
+        pet_id: i64,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<WaterIntakeReport, ActivityError> {
+        if end_date < start_date {
+            return Err(ActivityError::validation(
+                "end_date".to_string(),
+                "End date must not be before start date".to_string(),
+            ));
+        }
+        if start_date + Days::new(MAX_INTAKE_RANGE_DAYS) <= end_date {
+            return Err(ActivityError::validation(
+                "end_date".to_string(),
+                format!("Date range is limited to {MAX_INTAKE_RANGE_DAYS} days"),
+            ));
+        }
+
+        let (daily_target_ml, target_source) = self.get_water_target(pet_id).await?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT date(activity_time) AS day,
+                CAST(json_extract(activity_data, '$.hydration.amount') AS REAL) AS amount,
+                json_extract(activity_data, '$.hydration.unit') AS unit
+            FROM activities
+            WHERE pet_id = ? AND date(activity_time) BETWEEN ? AND ?
+                AND needs_review = 0
+                AND json_extract(activity_data, '$.hydration.hydrationType') IS NOT NULL
+            "#,
+        )
+        .bind(pet_id)
+        .bind(start_date.format("%Y-%m-%d").to_string())
+        .bind(end_date.format("%Y-%m-%d").to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut logged: BTreeMap<NaiveDate, (f32, i64)> = BTreeMap::new();
+        for row in rows {
+            let Some(day) = row
+                .try_get::<String, _>("day")
+                .ok()
+                .and_then(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())
+            else {
+                continue;
+            };
+            let amount: Option<f64> = row.try_get("amount").ok().flatten();
+            let unit: Option<String> = row.try_get("unit").ok().flatten();
+            let Some(ml) =
+                amount.and_then(|amount| water_ml(amount as f32, &unit.unwrap_or_default()))
+            else {
+                continue;
+            };
+            let entry = logged.entry(day).or_default();
+            entry.0 += ml;
+            entry.1 += 1;
+        }
+
+        let days: Vec<WaterIntakeDay> = start_date
+            .iter_days()
+            .take_while(|date| *date <= end_date)
+            .map(|date| {
+                let (total_ml, entries) = logged.get(&date).copied().unwrap_or_default();
+                WaterIntakeDay {
+                    date,
+                    total_ml,
+                    entries,
+                    below_target: entries > 0
+                        && daily_target_ml.is_some_and(|target| total_ml < target),
+                }
+            })
+            .collect();
+
+        let days_below_target = days
+            .iter()
+            .filter(|day| day.below_target)
+            .map(|day| day.date)
+            .collect();
+        let average_ml = (!logged.is_empty())
+            .then(|| logged.values().map(|(ml, _)| ml).sum::<f32>() / logged.len() as f32);
+
+        log::debug!(
+            "[DB] get_water_intake: pet_id={pet_id}, {start_date}..={end_date}, logged_days={}",
+            logged.len()
+        );
+        Ok(WaterIntakeReport {
+            pet_id,
+            start_date,
+            end_date,
+            daily_target_ml,
+            target_source,
+            days,
+            days_below_target,
+            average_ml,
+        })
+    }
+
+    /// A pet's daily water target: the one set in settings, otherwise
+    /// estimated from its weight
+    pub async fn get_water_target(
+        &self,
+        pet_id: i64,
+    ) -> Result<(Option<f32>, WaterTargetSource), ActivityError> {
+        if let Some(target) = self.get_water_targets().await?.get(&pet_id) {
+            return Ok((Some(*target), WaterTargetSource::Setting));
+        }
+
+        let weight_kg: Option<f32> = sqlx::query_scalar("SELECT weight_kg FROM pets WHERE id = ?")
+            .bind(pet_id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .flatten();
+        Ok(match weight_kg.filter(|kg| *kg > 0.0) {
+            Some(kg) => (
+                Some(kg * DEFAULT_WATER_ML_PER_KG),
+                WaterTargetSource::Weight,
+            ),
+            None => (None, WaterTargetSource::None),
+        })
+    }
+
+    /// Set a pet's daily water target in millilitres, or clear it with None to go
+    /// back to the weight-based estimate
+    pub async fn set_water_target(
+        &self,
+        pet_id: i64,
+        target_ml: Option<f32>,
+    ) -> Result<(), ActivityError> {
+        let mut targets = self.get_water_targets().await?;
+        match target_ml {
+            Some(target) => targets.insert(pet_id, target),
+            None => targets.remove(&pet_id),
+        };
+        self.set_setting(WATER_TARGETS_SETTING_KEY, &targets)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))
+    }
+
+    async fn get_water_targets(&self) -> Result<HashMap<i64, f32>, ActivityError> {
+        Ok(self
+            .get_setting::<HashMap<i64, f32>>(WATER_TARGETS_SETTING_KEY)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_water_intake_against_target() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let drink = |date: &str, amount: f32, unit: &str| ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Diet,
+            subcategory: "Water".to_string(),
+            activity_data: Some(serde_json::json!({
+                "time": { "date": format!("{date}T08:00:00.000Z"), "time": "", "timezone": "" },
+                "hydration": { "amount": amount, "unit": unit, "hydrationType": "bowl" }
+            })),
+            needs_review: false,
+        };
+        for request in [
+            drink("2025-03-01", 100.0, "ml"),
+            drink("2025-03-01", 0.1, "l"),
+            drink("2025-03-02", 80.0, "ml"),
+            drink("2025-03-05", 500.0, "ml"),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let start = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+
+        let (_, source) = db.get_water_target(pet_id).await.unwrap();
+        assert_eq!(source, WaterTargetSource::Weight);
+
+        db.set_water_target(pet_id, Some(150.0)).await.unwrap();
+        let report = db.get_water_intake(pet_id, start, end).await.unwrap();
+        assert_eq!(report.target_source, WaterTargetSource::Setting);
+        assert_eq!(report.days.len(), 3);
+        assert_eq!(report.days[0].total_ml, 200.0);
+        assert_eq!(report.days[0].entries, 2);
+        // Day 3 has nothing logged, so it isn't flagged
+        assert_eq!(report.days_below_target, vec![start + Days::new(1)]);
+        assert_eq!(report.average_ml, Some(140.0));
+
+        assert!(db.get_water_intake(pet_id, end, start).await.is_err());
+    }
+}
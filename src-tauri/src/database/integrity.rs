@@ -0,0 +1,28 @@
+use anyhow::Result;
+use sqlx::Row;
+
+impl super::PetDatabase {
+    /// Problems found by SQLite's quick integrity check; empty when the file is sound
+    pub async fn quick_check(&self) -> Result<Vec<String>> {
+        let rows = sqlx::query("PRAGMA quick_check")
+            .fetch_all(&self.read_pool)
+            .await?;
+        let problems: Vec<String> = rows
+            .iter()
+            .map(|row| row.get::<String, _>(0))
+            .filter(|message| message != "ok")
+            .collect();
+
+        log::debug!("[DB] quick_check: {} problems", problems.len());
+        Ok(problems)
+    }
+
+    /// Copy committed pages from the write-ahead log into the database file and
+    /// truncate the log
+    pub async fn checkpoint_wal(&self) -> Result<()> {
+        sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,713 @@
+use super::activity_data::BlockData;
+use super::models::*;
+use crate::errors::{ActivityError, PetError};
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{Row, SqliteConnection};
+
+/// Number of days of consumption used to estimate daily usage
+const USAGE_WINDOW_DAYS: i64 = 30;
+
+/// Stock consumed by a single activity, before it is matched to an item
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consumption {
+    pub kind: InventoryKind,
+    pub amount: f64,
+    pub unit: String,
+    /// Brand, product and title texts used to find the stocked item
+    pub labels: Vec<String>,
+}
+
+impl super::PetDatabase {
+    /// Add an item to the inventory
+    pub async fn create_inventory_item(
+        &self,
+        request: CreateInventoryItemRequest,
+    ) -> Result<InventoryItem, PetError> {
+        log::debug!(
+            "[DB] create_inventory_item: kind={}, name={}, pet_id={:?}",
+            request.kind,
+            request.name,
+            request.pet_id
+        );
+        validate_item_fields(&request.name, &request.unit, request.low_stock_threshold)?;
+        if !request.quantity.is_finite() || request.quantity < 0.0 {
+            return Err(PetError::validation(
+                "quantity",
+                "Quantity must be a non-negative number",
+            ));
+        }
+
+        let now = Utc::now();
+        let id = sqlx::query(
+            r#"
+            INSERT INTO inventory_items
+                (pet_id, kind, name, brand, unit, quantity, low_stock_threshold, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(request.pet_id)
+        .bind(request.kind.to_string())
+        .bind(request.name.trim())
+        .bind(request.brand.as_deref().map(str::trim).filter(|b| !b.is_empty()))
+        .bind(request.unit.trim())
+        .bind(request.quantity)
+        .bind(request.low_stock_threshold)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?
+        .last_insert_rowid();
+
+        log::info!("[DB] create_inventory_item: item_id={id}");
+        self.get_inventory_item(id).await
+    }
+
+    /// Get an inventory item by ID
+    pub async fn get_inventory_item(&self, id: i64) -> Result<InventoryItem, PetError> {
+        let row = sqlx::query("SELECT * FROM inventory_items WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?
+            .ok_or_else(|| PetError::inventory_item_not_found(id))?;
+        row_to_inventory_item(&row)
+    }
+
+    /// Get inventory items; with a pet, its own items plus shared ones
+    pub async fn get_inventory_items(
+        &self,
+        pet_id: Option<i64>,
+    ) -> Result<Vec<InventoryItem>, PetError> {
+        let rows = match pet_id {
+            Some(pet_id) => sqlx::query(
+                "SELECT * FROM inventory_items WHERE pet_id IS NULL OR pet_id = ? ORDER BY kind, name",
+            )
+            .bind(pet_id)
+            .fetch_all(&self.read_pool)
+            .await,
+            None => {
+                sqlx::query("SELECT * FROM inventory_items ORDER BY kind, name")
+                    .fetch_all(&self.read_pool)
+                    .await
+            }
+        }
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        rows.iter().map(row_to_inventory_item).collect()
+    }
+
+    /// Edit an item's description or threshold
+    pub async fn update_inventory_item(
+        &self,
+        id: i64,
+        updates: InventoryItemUpdateRequest,
+    ) -> Result<InventoryItem, PetError> {
+        let current = self.get_inventory_item(id).await?;
+        let name = updates.name.unwrap_or(current.name);
+        let unit = updates.unit.unwrap_or(current.unit);
+        let threshold = updates
+            .low_stock_threshold
+            .unwrap_or(current.low_stock_threshold);
+        validate_item_fields(&name, &unit, threshold)?;
+        let brand = match updates.brand {
+            Some(brand) => Some(brand.trim().to_string()).filter(|b| !b.is_empty()),
+            None => current.brand,
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE inventory_items
+            SET name = ?, brand = ?, unit = ?, low_stock_threshold = ?, updated_at = ?,
+                low_stock_notified = CASE WHEN quantity > ? THEN 0 ELSE low_stock_notified END
+            WHERE id = ?
+            "#,
+        )
+        .bind(name.trim())
+        .bind(brand)
+        .bind(unit.trim())
+        .bind(threshold)
+        .bind(Utc::now())
+        .bind(threshold)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!("[DB] update_inventory_item: item_id={id}");
+        self.get_inventory_item(id).await
+    }
+
+    /// Change the stock by `delta` (positive to restock, negative for manual corrections)
+    pub async fn adjust_inventory(&self, id: i64, delta: f64) -> Result<InventoryItem, PetError> {
+        if !delta.is_finite() || delta == 0.0 {
+            return Err(PetError::validation(
+                "delta",
+                "Adjustment must be a non-zero number",
+            ));
+        }
+        self.get_inventory_item(id).await?;
+
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        let applied = change_quantity(&mut conn, id, delta, None)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        // The in-memory database has a single connection shared with readers
+        drop(conn);
+
+        log::info!("[DB] adjust_inventory: item_id={id}, delta={applied}");
+        self.get_inventory_item(id).await
+    }
+
+    /// Remove an item and its movement history
+    pub async fn delete_inventory_item(&self, id: i64) -> Result<(), PetError> {
+        let result = sqlx::query("DELETE FROM inventory_items WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(PetError::inventory_item_not_found(id));
+        }
+        log::info!("[DB] delete_inventory_item: item_id={id}");
+        Ok(())
+    }
+
+    /// Stock levels with usage estimates; with a pet, its own items plus shared ones
+    pub async fn get_inventory_status(
+        &self,
+        pet_id: Option<i64>,
+    ) -> Result<Vec<InventoryStatus>, PetError> {
+        let items = self.get_inventory_items(pet_id).await?;
+        let since = Utc::now() - Duration::days(USAGE_WINDOW_DAYS);
+
+        let usage_rows = sqlx::query(
+            r#"
+            SELECT item_id, -SUM(delta) AS consumed
+            FROM inventory_movements
+            WHERE activity_id IS NOT NULL AND delta < 0 AND created_at >= ?
+            GROUP BY item_id
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+        let consumed: std::collections::HashMap<i64, f64> = usage_rows
+            .iter()
+            .map(|row| (row.get("item_id"), row.get("consumed")))
+            .collect();
+
+        let now = Utc::now();
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let daily_usage = consumed
+                    .get(&item.id)
+                    .map(|total| daily_rate(*total, item.created_at, now));
+                inventory_status(item, daily_usage)
+            })
+            .collect())
+    }
+
+    /// Items that dropped to their low-stock threshold since the last call.
+    /// Each item is returned once until it is restocked above the threshold.
+    pub async fn take_low_stock_alerts(&self) -> Result<Vec<InventoryItem>, PetError> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE inventory_items
+            SET low_stock_notified = 1
+            WHERE low_stock_notified = 0 AND low_stock_threshold > 0 AND quantity <= low_stock_threshold
+            RETURNING *
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        if !rows.is_empty() {
+            log::info!("[DB] take_low_stock_alerts: {} item(s) low", rows.len());
+        }
+        rows.iter().map(row_to_inventory_item).collect()
+    }
+
+    /// Decrement the stocked item matching an activity's portion or dose, on the caller's transaction
+    pub(super) async fn apply_inventory_consumption(
+        conn: &mut SqliteConnection,
+        activity: &Activity,
+    ) -> Result<(), ActivityError> {
+        let Some(consumption) = consumption_from_activity(activity) else {
+            return Ok(());
+        };
+
+        let rows = sqlx::query(
+            "SELECT * FROM inventory_items WHERE kind = ? AND (pet_id IS NULL OR pet_id = ?)",
+        )
+        .bind(consumption.kind.to_string())
+        .bind(activity.pet_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let items = rows
+            .iter()
+            .map(row_to_inventory_item)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ActivityError::invalid_data(e.to_string()))?;
+
+        let Some(item) = match_inventory_item(&consumption, activity.pet_id, &items) else {
+            log::debug!(
+                "[DB] apply_inventory_consumption: no {} item for activity_id={}",
+                consumption.kind,
+                activity.id
+            );
+            return Ok(());
+        };
+        let Some(amount) = convert_amount(consumption.amount, &consumption.unit, &item.unit) else {
+            log::debug!(
+                "[DB] apply_inventory_consumption: cannot convert {} to {} for item_id={}",
+                consumption.unit,
+                item.unit,
+                item.id
+            );
+            return Ok(());
+        };
+
+        let applied = change_quantity(conn, item.id, -amount, Some(activity.id))
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        log::debug!(
+            "[DB] apply_inventory_consumption: item_id={}, delta={applied}, activity_id={}",
+            item.id,
+            activity.id
+        );
+        Ok(())
+    }
+
+    /// Give back the stock an activity consumed, on the caller's transaction
+    pub(super) async fn revert_inventory_consumption(
+        conn: &mut SqliteConnection,
+        activity_id: i64,
+    ) -> Result<(), ActivityError> {
+        let movements: Vec<(i64, f64)> =
+            sqlx::query_as("SELECT item_id, delta FROM inventory_movements WHERE activity_id = ?")
+                .bind(activity_id)
+                .fetch_all(&mut *conn)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        if movements.is_empty() {
+            return Ok(());
+        }
+
+        for (item_id, delta) in movements {
+            sqlx::query(
+                r#"
+                UPDATE inventory_items
+                SET quantity = quantity - ?, updated_at = ?,
+                    low_stock_notified = CASE WHEN quantity - ? > low_stock_threshold THEN 0 ELSE low_stock_notified END
+                WHERE id = ?
+                "#,
+            )
+            .bind(delta)
+            .bind(Utc::now())
+            .bind(delta)
+            .bind(item_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+        sqlx::query("DELETE FROM inventory_movements WHERE activity_id = ?")
+            .bind(activity_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(())
+    }
+}
+
+/// Apply `delta` to an item's stock without going below zero and record the movement.
+/// Returns the delta actually applied.
+async fn change_quantity(
+    conn: &mut SqliteConnection,
+    item_id: i64,
+    delta: f64,
+    activity_id: Option<i64>,
+) -> Result<f64, sqlx::Error> {
+    let quantity: f64 = sqlx::query_scalar("SELECT quantity FROM inventory_items WHERE id = ?")
+        .bind(item_id)
+        .fetch_one(&mut *conn)
+        .await?;
+    let applied = delta.max(-quantity.max(0.0));
+    if applied == 0.0 {
+        return Ok(0.0);
+    }
+
+    sqlx::query(
+        r#"
+        UPDATE inventory_items
+        SET quantity = quantity + ?, updated_at = ?,
+            low_stock_notified = CASE WHEN quantity + ? > low_stock_threshold THEN 0 ELSE low_stock_notified END
+        WHERE id = ?
+        "#,
+    )
+    .bind(applied)
+    .bind(Utc::now())
+    .bind(applied)
+    .bind(item_id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query("INSERT INTO inventory_movements (item_id, activity_id, delta, created_at) VALUES (?, ?, ?, ?)")
+        .bind(item_id)
+        .bind(activity_id)
+        .bind(applied)
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await?;
+    Ok(applied)
+}
+
+fn validate_item_fields(name: &str, unit: &str, threshold: f64) -> Result<(), PetError> {
+    if name.trim().is_empty() {
+        return Err(PetError::validation("name", "Name cannot be empty"));
+    }
+    if unit.trim().is_empty() {
+        return Err(PetError::validation("unit", "Unit cannot be empty"));
+    }
+    if !threshold.is_finite() || threshold < 0.0 {
+        return Err(PetError::validation(
+            "low_stock_threshold",
+            "Threshold must be a non-negative number",
+        ));
+    }
+    Ok(())
+}
+
+fn row_to_inventory_item(row: &sqlx::sqlite::SqliteRow) -> Result<InventoryItem, PetError> {
+    let kind: String = row.get("kind");
+    Ok(InventoryItem {
+        id: row.get("id"),
+        pet_id: row.get("pet_id"),
+        kind: kind
+            .parse()
+            .map_err(|e: anyhow::Error| PetError::database(e.to_string()))?,
+        name: row.get("name"),
+        brand: row.get("brand"),
+        unit: row.get("unit"),
+        quantity: row.get("quantity"),
+        low_stock_threshold: row.get("low_stock_threshold"),
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+        updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+    })
+}
+
+/// Average consumption per day, over the usage window or the item's lifetime if shorter
+fn daily_rate(consumed: f64, created_at: DateTime<Utc>, now: DateTime<Utc>) -> f64 {
+    let days = (now - created_at).num_days().clamp(1, USAGE_WINDOW_DAYS);
+    consumed / days as f64
+}
+
+fn inventory_status(item: InventoryItem, daily_usage: Option<f64>) -> InventoryStatus {
+    let daily_usage = daily_usage.filter(|rate| *rate > 0.0);
+    InventoryStatus {
+        is_low: item.quantity <= item.low_stock_threshold,
+        days_remaining: daily_usage.map(|rate| item.quantity / rate),
+        daily_usage,
+        item,
+    }
+}
+
+/// What an activity consumes: Diet portions use food, "litter" activities use litter
+/// and Health medication doses use medication
+pub fn consumption_from_activity(activity: &Activity) -> Option<Consumption> {
+    let data = activity.activity_data.as_ref()?;
+    let Some(BlockData::Portion {
+        amount,
+        unit,
+        brand,
+        product,
+        ..
+    }) = data.get("portion")
+    else {
+        return None;
+    };
+
+    let subcategory = activity.subcategory.to_lowercase();
+    let kind = if subcategory.contains("litter") {
+        InventoryKind::Litter
+    } else if activity.category == ActivityCategory::Health && subcategory.contains("medication") {
+        InventoryKind::Medication
+    } else if activity.category == ActivityCategory::Diet && subcategory != "water" {
+        InventoryKind::Food
+    } else {
+        return None;
+    };
+
+    let title = match data.get("title") {
+        Some(BlockData::Text(title)) => Some(title),
+        _ => None,
+    };
+    let labels = [brand.as_ref(), product.as_ref(), title]
+        .into_iter()
+        .flatten()
+        .map(|label| label.trim().to_lowercase())
+        .filter(|label| !label.is_empty())
+        .collect();
+
+    (*amount > 0.0).then(|| Consumption {
+        kind,
+        amount: f64::from(*amount),
+        unit: unit.clone(),
+        labels,
+    })
+}
+
+/// Pick the stocked item an activity draws from.
+/// With labels, the item's name or brand must appear in one of them; without labels the
+/// only item of that kind is used. The pet's own items win over shared ones.
+pub fn match_inventory_item<'a>(
+    consumption: &Consumption,
+    pet_id: i64,
+    items: &'a [InventoryItem],
+) -> Option<&'a InventoryItem> {
+    let candidates: Vec<&InventoryItem> = items
+        .iter()
+        .filter(|item| item.kind == consumption.kind)
+        .filter(|item| item.pet_id.is_none_or(|id| id == pet_id))
+        .filter(|item| {
+            consumption.labels.is_empty()
+                || consumption.labels.iter().any(|label| {
+                    label.contains(&item.name.to_lowercase())
+                        || item
+                            .brand
+                            .as_ref()
+                            .is_some_and(|brand| label.contains(&brand.to_lowercase()))
+                })
+        })
+        .collect();
+
+    let own: Vec<&InventoryItem> = candidates
+        .iter()
+        .copied()
+        .filter(|item| item.pet_id == Some(pet_id))
+        .collect();
+    let pool = if own.is_empty() { candidates } else { own };
+    match pool.as_slice() {
+        [only] => Some(only),
+        [first, ..] if !consumption.labels.is_empty() => Some(first),
+        _ => None,
+    }
+}
+
+/// Convert between mass or volume units; other units must match exactly (ignoring plurals)
+pub fn convert_amount(amount: f64, from: &str, to: &str) -> Option<f64> {
+    fn normalize(unit: &str) -> String {
+        let unit = unit.trim().to_lowercase();
+        match unit.strip_suffix('s') {
+            Some(singular) if singular.len() > 1 => singular.to_string(),
+            _ => unit,
+        }
+    }
+    fn scale(unit: &str) -> Option<(&'static str, f64)> {
+        match unit {
+            "mg" => Some(("mass", 0.001)),
+            "g" | "gram" => Some(("mass", 1.0)),
+            "kg" => Some(("mass", 1000.0)),
+            "oz" => Some(("mass", 28.3495)),
+            "lb" => Some(("mass", 453.592)),
+            "ml" => Some(("volume", 1.0)),
+            "l" | "liter" | "litre" => Some(("volume", 1000.0)),
+            _ => None,
+        }
+    }
+
+    let (from, to) = (normalize(from), normalize(to));
+    if from == to {
+        return Some(amount);
+    }
+    match (scale(&from), scale(&to)) {
+        (Some((from_dim, from_scale)), Some((to_dim, to_scale))) if from_dim == to_dim => {
+            Some(amount * from_scale / to_scale)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::super::PetDatabase;
+    use super::*;
+
+    async fn create_pet(db: &PetDatabase) -> Pet {
+        db.create_pet(CreatePetRequest {
+            name: "Mochi".to_string(),
+            birth_date: chrono::NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+            species: PetSpecies::Cat,
+            gender: PetGender::Female,
+            breed: None,
+            color: None,
+            weight_kg: Some(4.0),
+            photo_path: None,
+            notes: None,
+        })
+        .await
+        .unwrap()
+    }
+
+    fn feeding(pet_id: i64, amount: f64, unit: &str, brand: &str) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Diet,
+            subcategory: "Feeding".to_string(),
+            activity_data: Some(serde_json::json!({
+                "portion": { "amount": amount, "unit": unit, "portionType": "meal", "brand": brand }
+            })),
+            needs_review: false,
+        }
+    }
+
+    #[test]
+    fn test_convert_amount() {
+        assert_eq!(convert_amount(80.0, "g", "kg"), Some(0.08));
+        assert_eq!(convert_amount(1.5, "L", "ml"), Some(1500.0));
+        assert_eq!(convert_amount(2.0, "tablets", "tablet"), Some(2.0));
+        assert_eq!(convert_amount(2.0, "g", "ml"), None);
+        assert_eq!(convert_amount(1.0, "cup", "g"), None);
+    }
+
+    #[test]
+    fn test_match_prefers_labelled_and_own_items() {
+        let item = |id, pet_id, name: &str, brand: Option<&str>| InventoryItem {
+            id,
+            pet_id,
+            kind: InventoryKind::Food,
+            name: name.to_string(),
+            brand: brand.map(str::to_string),
+            unit: "kg".to_string(),
+            quantity: 2.0,
+            low_stock_threshold: 0.5,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let items = vec![
+            item(1, None, "Dry food", Some("Royal Canin")),
+            item(2, Some(7), "Kitten dry food", Some("Royal Canin")),
+            item(3, None, "Wet food", Some("Orijen")),
+        ];
+        let consumption = |labels: &[&str]| Consumption {
+            kind: InventoryKind::Food,
+            amount: 80.0,
+            unit: "g".to_string(),
+            labels: labels.iter().map(|l| l.to_string()).collect(),
+        };
+
+        let matched = |labels: &[&str], pet_id| {
+            match_inventory_item(&consumption(labels), pet_id, &items).map(|i| i.id)
+        };
+        assert_eq!(matched(&["royal canin"], 7), Some(2));
+        assert_eq!(matched(&["royal canin"], 8), Some(1));
+        assert_eq!(matched(&["orijen"], 7), Some(3));
+        assert_eq!(matched(&["acana"], 7), None);
+        // Without labels only an unambiguous item is used
+        assert_eq!(matched(&[], 8), None);
+        assert_eq!(matched(&[], 7), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_feeding_decrements_and_raises_one_alert() {
+        let (db, _dir) = test_database().await;
+        let pet = create_pet(&db).await;
+        let item = db
+            .create_inventory_item(CreateInventoryItemRequest {
+                pet_id: None,
+                kind: InventoryKind::Food,
+                name: "Dry food".to_string(),
+                brand: Some("Royal Canin".to_string()),
+                unit: "kg".to_string(),
+                quantity: 0.3,
+                low_stock_threshold: 0.2,
+            })
+            .await
+            .unwrap();
+
+        let first = db
+            .create_activity_with_side_effects(feeding(pet.id, 80.0, "g", "Royal Canin"))
+            .await
+            .unwrap();
+        let quantity = db.get_inventory_item(item.id).await.unwrap().quantity;
+        assert!((quantity - 0.22).abs() < 1e-9);
+        assert!(db.take_low_stock_alerts().await.unwrap().is_empty());
+
+        // Unrelated brands don't touch the stock
+        db.create_activity_with_side_effects(feeding(pet.id, 80.0, "g", "Orijen"))
+            .await
+            .unwrap();
+        db.create_activity_with_side_effects(feeding(pet.id, 80.0, "g", "Royal Canin"))
+            .await
+            .unwrap();
+        let alerts = db.take_low_stock_alerts().await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].id, item.id);
+        assert!(db.take_low_stock_alerts().await.unwrap().is_empty());
+
+        let status = db.get_inventory_status(Some(pet.id)).await.unwrap();
+        assert!(status[0].is_low);
+        assert!((status[0].daily_usage.unwrap() - 0.16).abs() < 1e-9);
+
+        // Editing the portion replaces the old consumption; deleting gives it back
+        db.update_activity(
+            first.id,
+            ActivityUpdateRequest {
+                activity_data: Some(serde_json::json!({
+                    "portion": { "amount": 40, "unit": "g", "portionType": "meal", "brand": "Royal Canin" }
+                })),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let quantity = db.get_inventory_item(item.id).await.unwrap().quantity;
+        assert!((quantity - 0.18).abs() < 1e-9);
+
+        db.delete_activity(first.id).await.unwrap();
+        let quantity = db.get_inventory_item(item.id).await.unwrap().quantity;
+        assert!((quantity - 0.22).abs() < 1e-9);
+
+        // Restocking above the threshold re-arms the alert
+        db.adjust_inventory(item.id, 2.0).await.unwrap();
+        db.adjust_inventory(item.id, -2.1).await.unwrap();
+        assert_eq!(db.take_low_stock_alerts().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_adjust_inventory_on_single_connection_database() {
+        let db = PetDatabase::in_memory().await.unwrap();
+        let item = db
+            .create_inventory_item(CreateInventoryItemRequest {
+                pet_id: None,
+                kind: InventoryKind::Litter,
+                name: "Clumping litter".to_string(),
+                brand: None,
+                unit: "kg".to_string(),
+                quantity: 5.0,
+                low_stock_threshold: 1.0,
+            })
+            .await
+            .unwrap();
+
+        // Would wait for the pool's only connection if it were still held
+        let adjusted = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            db.adjust_inventory(item.id, 2.0),
+        )
+        .await
+        .expect("adjust_inventory blocked on the shared connection")
+        .unwrap();
+        assert_eq!(adjusted.quantity, 7.0);
+    }
+}
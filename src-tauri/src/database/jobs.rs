@@ -0,0 +1,383 @@
+use crate::errors::ActivityError;
+use crate::jobs::{JobKind, JobPayload, JobStatus};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// Jobs listed when no limit is given
+const DEFAULT_JOB_LIST_LIMIT: i64 = 50;
+
+/// Most jobs listed at once
+const MAX_JOB_LIST_LIMIT: i64 = 200;
+
+/// A background job and how far it got
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub payload: JobPayload,
+    pub processed: i64,
+    pub total: i64,
+    /// Outcome of a finished job; for a running import, what it did so far
+    pub result: Option<serde_json::Value>,
+    pub error: Option<String>,
+    /// Set while a running job is being asked to stop
+    pub cancel_requested: bool,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl super::PetDatabase {
+    /// Add a job to the end of the queue
+    pub async fn enqueue_job(&self, payload: &JobPayload) -> Result<Job, ActivityError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            INSERT INTO jobs (id, kind, status, payload, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&id)
+        .bind(payload.kind().to_string())
+        .bind(JobStatus::Queued.to_string())
+        .bind(payload_json(payload)?)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::info!("[DB] enqueue_job: id={id}, kind={}", payload.kind());
+        self.get_job(&id).await
+    }
+
+    /// Get a job by ID
+    pub async fn get_job(&self, id: &str) -> Result<Job, ActivityError> {
+        let row = sqlx::query("SELECT * FROM jobs WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .ok_or_else(|| ActivityError::job_not_found(id))?;
+        row_to_job(&row)
+    }
+
+    /// Jobs newest first, optionally only those in `status`
+    pub async fn list_jobs(
+        &self,
+        status: Option<JobStatus>,
+        limit: Option<i64>,
+    ) -> Result<Vec<Job>, ActivityError> {
+        let limit = limit
+            .unwrap_or(DEFAULT_JOB_LIST_LIMIT)
+            .clamp(1, MAX_JOB_LIST_LIMIT);
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM jobs
+            WHERE ? IS NULL OR status = ?
+            ORDER BY created_at DESC, rowid DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(status.map(|s| s.to_string()))
+        .bind(status.map(|s| s.to_string()))
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        rows.iter().map(row_to_job).collect()
+    }
+
+    /// Mark the oldest queued job as running and return it, or None when the queue is empty
+    pub async fn claim_next_job(&self) -> Result<Option<Job>, ActivityError> {
+        let now = Utc::now();
+        let row = sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = ?, started_at = COALESCE(started_at, ?), updated_at = ?
+            WHERE id = (
+                SELECT id FROM jobs WHERE status = ? ORDER BY created_at, rowid LIMIT 1
+            )
+            RETURNING *
+            "#,
+        )
+        .bind(JobStatus::Running.to_string())
+        .bind(now)
+        .bind(now)
+        .bind(JobStatus::Queued.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        row.as_ref().map(row_to_job).transpose()
+    }
+
+    /// Record how far a running job got. A `checkpoint` replaces the stored result,
+    /// so work done before a restart isn't repeated or lost.
+    pub async fn update_job_progress(
+        &self,
+        id: &str,
+        processed: i64,
+        total: i64,
+        checkpoint: Option<&serde_json::Value>,
+    ) -> Result<(), ActivityError> {
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET processed = ?, total = ?, result = COALESCE(?, result), updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(processed)
+        .bind(total)
+        .bind(checkpoint.map(|value| value.to_string()))
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(())
+    }
+
+    /// Move a running job to a final status
+    pub async fn finish_job(
+        &self,
+        id: &str,
+        status: JobStatus,
+        result: Option<&serde_json::Value>,
+        error: Option<&str>,
+    ) -> Result<Job, ActivityError> {
+        debug_assert!(status.is_finished());
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            UPDATE jobs
+            SET status = ?, result = COALESCE(?, result), error = ?, finished_at = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(status.to_string())
+        .bind(result.map(|value| value.to_string()))
+        .bind(error)
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::info!("[DB] finish_job: id={id}, status={status}");
+        self.get_job(id).await
+    }
+
+    /// Cancel a queued job, or ask a running one to stop at its next check.
+    /// Returns the job, unchanged when it had already finished.
+    pub async fn cancel_job(&self, id: &str) -> Result<Job, ActivityError> {
+        let now = Utc::now();
+        let cancelled = sqlx::query(
+            "UPDATE jobs SET status = ?, finished_at = ?, updated_at = ? WHERE id = ? AND status = ?",
+        )
+        .bind(JobStatus::Cancelled.to_string())
+        .bind(now)
+        .bind(now)
+        .bind(id)
+        .bind(JobStatus::Queued.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+        .rows_affected();
+        if cancelled == 0 {
+            sqlx::query(
+                "UPDATE jobs SET cancel_requested = 1, updated_at = ? WHERE id = ? AND status = ?",
+            )
+            .bind(now)
+            .bind(id)
+            .bind(JobStatus::Running.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+        self.get_job(id).await
+    }
+
+    /// Requeue the jobs a previous session was running when the app closed, so the
+    /// worker picks them up again. Jobs that were asked to stop are cancelled instead.
+    /// Returns the number of jobs requeued.
+    pub async fn requeue_interrupted_jobs(&self) -> Result<u64, ActivityError> {
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            UPDATE jobs SET status = ?, finished_at = ?, updated_at = ?
+            WHERE status = ? AND cancel_requested = 1
+            "#,
+        )
+        .bind(JobStatus::Cancelled.to_string())
+        .bind(now)
+        .bind(now)
+        .bind(JobStatus::Running.to_string())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let requeued = sqlx::query("UPDATE jobs SET status = ?, updated_at = ? WHERE status = ?")
+            .bind(JobStatus::Queued.to_string())
+            .bind(now)
+            .bind(JobStatus::Running.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .rows_affected();
+        if requeued > 0 {
+            log::info!("[DB] requeue_interrupted_jobs: {requeued} jobs requeued");
+        }
+        Ok(requeued)
+    }
+
+    /// Delete jobs that finished before `before`. Returns the number deleted.
+    pub async fn prune_finished_jobs(&self, before: DateTime<Utc>) -> Result<u64, ActivityError> {
+        let result = sqlx::query("DELETE FROM jobs WHERE status IN (?, ?, ?) AND finished_at < ?")
+            .bind(JobStatus::Done.to_string())
+            .bind(JobStatus::Failed.to_string())
+            .bind(JobStatus::Cancelled.to_string())
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(result.rows_affected())
+    }
+}
+
+fn payload_json(payload: &JobPayload) -> Result<String, ActivityError> {
+    serde_json::to_string(payload)
+        .map_err(|e| ActivityError::invalid_data(format!("Invalid job payload: {e}")))
+}
+
+fn row_to_job(row: &sqlx::sqlite::SqliteRow) -> Result<Job, ActivityError> {
+    let kind: String = row.get("kind");
+    let status: String = row.get("status");
+    let payload: String = row.get("payload");
+    let result: Option<String> = row.get("result");
+    Ok(Job {
+        id: row.get("id"),
+        kind: kind
+            .parse()
+            .map_err(|e| ActivityError::invalid_data(format!("{e}")))?,
+        status: status
+            .parse()
+            .map_err(|e| ActivityError::invalid_data(format!("{e}")))?,
+        payload: serde_json::from_str(&payload)
+            .map_err(|e| ActivityError::invalid_data(format!("Invalid job payload: {e}")))?,
+        processed: row.get("processed"),
+        total: row.get("total"),
+        result: result.and_then(|json| serde_json::from_str(&json).ok()),
+        error: row.get("error"),
+        cancel_requested: row.get("cancel_requested"),
+        created_at: row.get("created_at"),
+        started_at: row.get("started_at"),
+        finished_at: row.get("finished_at"),
+        updated_at: row.get("updated_at"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    fn export(file: &str) -> JobPayload {
+        JobPayload::Export {
+            pet_ids: None,
+            file_path: file.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jobs_run_in_order_and_survive_restart() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, _summary) = seeded_database(&config).await;
+
+        let first = db.enqueue_job(&export("first.csv")).await.unwrap();
+        let second = db.enqueue_job(&export("second.csv")).await.unwrap();
+        let third = db.enqueue_job(&export("third.csv")).await.unwrap();
+        assert_eq!(first.status, JobStatus::Queued);
+
+        let claimed = db.claim_next_job().await.unwrap().unwrap();
+        assert_eq!(claimed.id, first.id);
+        assert_eq!(claimed.status, JobStatus::Running);
+        db.update_job_progress(&first.id, 250, 1000, None)
+            .await
+            .unwrap();
+
+        // Queued jobs are cancelled at once, running ones are asked to stop
+        assert_eq!(
+            db.cancel_job(&third.id).await.unwrap().status,
+            JobStatus::Cancelled
+        );
+        let running = db.cancel_job(&first.id).await.unwrap();
+        assert_eq!(running.status, JobStatus::Running);
+        assert!(running.cancel_requested);
+
+        // The app closed: the job asked to stop is cancelled, nothing else was running
+        assert_eq!(db.requeue_interrupted_jobs().await.unwrap(), 0);
+        assert_eq!(
+            db.get_job(&first.id).await.unwrap().status,
+            JobStatus::Cancelled
+        );
+
+        // A job interrupted mid-way is picked up again with its progress
+        let claimed = db.claim_next_job().await.unwrap().unwrap();
+        assert_eq!(claimed.id, second.id);
+        let checkpoint = serde_json::json!({ "imported": 3 });
+        db.update_job_progress(&second.id, 3, 10, Some(&checkpoint))
+            .await
+            .unwrap();
+        assert_eq!(db.requeue_interrupted_jobs().await.unwrap(), 1);
+        let resumed = db.claim_next_job().await.unwrap().unwrap();
+        assert_eq!(resumed.id, second.id);
+        assert_eq!((resumed.processed, resumed.total), (3, 10));
+        assert_eq!(resumed.result, Some(checkpoint));
+        assert!(db.claim_next_job().await.unwrap().is_none());
+
+        let done = db
+            .finish_job(
+                &second.id,
+                JobStatus::Done,
+                Some(&serde_json::json!({ "rows": 10 })),
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(done.status, JobStatus::Done);
+        assert!(done.finished_at.is_some());
+        assert_eq!(
+            db.cancel_job(&second.id).await.unwrap().status,
+            JobStatus::Done
+        );
+
+        let listed = db.list_jobs(None, None).await.unwrap();
+        assert_eq!(listed.len(), 3);
+        assert_eq!(listed[0].id, third.id);
+        let cancelled = db
+            .list_jobs(Some(JobStatus::Cancelled), None)
+            .await
+            .unwrap();
+        assert_eq!(cancelled.len(), 2);
+        assert!(matches!(
+            db.get_job("missing").await,
+            Err(ActivityError::JobNotFound { .. })
+        ));
+
+        let pruned = db
+            .prune_finished_jobs(Utc::now() + chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+        assert_eq!(pruned, 3);
+    }
+}
@@ -0,0 +1,337 @@
+use crate::errors::ActivityError;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::BTreeMap;
+
+/// Health subcategory for litter box visits
+pub const LITTER_SUBCATEGORY: &str = "Litter";
+
+/// Days `get_litter_trends` reports on when no range is given
+pub const DEFAULT_TREND_DAYS: u32 = 14;
+
+/// Longest range `get_litter_trends` reports on
+const MAX_TREND_DAYS: u32 = 90;
+
+/// Days before the range whose logs make up the baseline
+const BASELINE_DAYS: u64 = 28;
+
+/// Logged days the baseline needs before frequency is judged against it
+const MIN_BASELINE_DAYS: usize = 5;
+
+/// A day's count is flagged above this multiple of the baseline...
+const HIGH_FREQUENCY_FACTOR: f32 = 1.5;
+
+/// ...when it is also at least this many visits above it
+const HIGH_FREQUENCY_MARGIN: f32 = 2.0;
+
+/// A day's urination is flagged below this fraction of the baseline
+const LOW_FREQUENCY_FACTOR: f32 = 0.5;
+
+/// Average stool scores at or beyond these are flagged
+const LOOSE_STOOL_SCORE: f32 = 6.0;
+const HARD_STOOL_SCORE: f32 = 2.0;
+
+/// Litter box use on one day
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LitterDay {
+    pub date: NaiveDate,
+    pub urinations: u32,
+    pub stools: u32,
+    /// Average consistency score of the stools scored that day
+    pub average_consistency: Option<f32>,
+}
+
+/// What deviates from a pet's usual litter habits
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LitterAnomalyKind {
+    /// Urinating much more often than usual (diabetes, kidney disease, cystitis)
+    FrequentUrination,
+    /// Urinating much less often than usual; in male cats a possible blockage
+    ReducedUrination,
+    FrequentStools,
+    LooseStool,
+    HardStool,
+}
+
+/// A day whose litter habits stand out
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LitterAnomaly {
+    pub date: NaiveDate,
+    pub kind: LitterAnomalyKind,
+    pub message: String,
+}
+
+/// A pet's litter box use over a date range, against its usual habits
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LitterTrends {
+    pub pet_id: i64,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    /// Days of the range with litter logs, oldest first
+    pub days: Vec<LitterDay>,
+    /// Averages over the logged days of the range
+    pub average_urinations: Option<f32>,
+    pub average_stools: Option<f32>,
+    /// Averages over the logged days of the 4 weeks before the range; None until
+    /// enough days are logged to tell what is usual
+    pub baseline_urinations: Option<f32>,
+    pub baseline_stools: Option<f32>,
+    pub anomalies: Vec<LitterAnomaly>,
+}
+
+impl super::PetDatabase {
+    /// Count a pet's litter blocks per day (by activity time, UTC) over the `days`
+    /// days up to `end_date`, and flag days that deviate from the 4 weeks before.
+    /// Days without logs are left out, since nothing logged doesn't mean no visits.
+    pub async fn get_litter_trends(
+        &self,
+        pet_id: i64,
+        end_date: NaiveDate,
+        days: Option<u32>,
+    ) -> Result<LitterTrends, ActivityError> {
+        let days = days.unwrap_or(DEFAULT_TREND_DAYS);
+        if days == 0 || days > MAX_TREND_DAYS {
+            return Err(ActivityError::validation(
+                "days".to_string(),
+                format!("Trends cover 1 to {MAX_TREND_DAYS} days"),
+            ));
+        }
+        let start_date = end_date - Days::new(u64::from(days) - 1);
+        let baseline_start = start_date - Days::new(BASELINE_DAYS);
+
+        let rows = sqlx::query(
+            r#"
+            SELECT date(activity_time) AS day,
+                lower(json_extract(block.value, '$.litterType')) AS litter_type,
+                json_extract(block.value, '$.count') AS count,
+                json_extract(block.value, '$.consistency') AS consistency
+            FROM activities, json_each(activities.activity_data) AS block
+            WHERE pet_id = ? AND date(activity_time) BETWEEN ? AND ?
+                AND needs_review = 0
+                AND block.type = 'object'
+                AND json_extract(block.value, '$.litterType') IS NOT NULL
+            "#,
+        )
+        .bind(pet_id)
+        .bind(baseline_start.format("%Y-%m-%d").to_string())
+        .bind(end_date.format("%Y-%m-%d").to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        // (urinations, stools, consistency sum, scored stools) per day
+        let mut logged: BTreeMap<NaiveDate, (u32, u32, f32, u32)> = BTreeMap::new();
+        for row in rows {
+            let Some(day) = row
+                .try_get::<String, _>("day")
+                .ok()
+                .and_then(|day| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok())
+            else {
+                continue;
+            };
+            let litter_type: String = row.try_get("litter_type").unwrap_or_default();
+            let count = row
+                .try_get::<Option<i64>, _>("count")
+                .ok()
+                .flatten()
+                .map_or(1, |count| count.clamp(1, i64::from(u32::MAX)) as u32);
+            let consistency: Option<f64> = row.try_get("consistency").ok().flatten();
+
+            let entry = logged.entry(day).or_default();
+            if matches!(litter_type.as_str(), "urine" | "both") {
+                entry.0 += count;
+            }
+            if matches!(litter_type.as_str(), "stool" | "both") {
+                entry.1 += count;
+                if let Some(score) = consistency {
+                    entry.2 += score as f32;
+                    entry.3 += 1;
+                }
+            }
+        }
+
+        let (baseline, range): (Vec<_>, Vec<_>) =
+            logged.into_iter().partition(|(date, _)| *date < start_date);
+        let days: Vec<LitterDay> = range
+            .into_iter()
+            .map(
+                |(date, (urinations, stools, score_sum, scored))| LitterDay {
+                    date,
+                    urinations,
+                    stools,
+                    average_consistency: (scored > 0).then(|| score_sum / scored as f32),
+                },
+            )
+            .collect();
+
+        let enough_baseline = baseline.len() >= MIN_BASELINE_DAYS;
+        let baseline_urinations =
+            enough_baseline.then(|| mean(baseline.iter().map(|(_, day)| day.0)));
+        let baseline_stools = enough_baseline.then(|| mean(baseline.iter().map(|(_, day)| day.1)));
+        let anomalies = days
+            .iter()
+            .flat_map(|day| litter_anomalies(day, baseline_urinations, baseline_stools))
+            .collect();
+
+        log::debug!(
+            "[DB] get_litter_trends: pet_id={pet_id}, {start_date}..={end_date}, logged_days={}, baseline_days={}",
+            days.len(),
+            baseline.len()
+        );
+        Ok(LitterTrends {
+            pet_id,
+            start_date,
+            end_date,
+            average_urinations: (!days.is_empty()).then(|| mean(days.iter().map(|d| d.urinations))),
+            average_stools: (!days.is_empty()).then(|| mean(days.iter().map(|d| d.stools))),
+            days,
+            baseline_urinations,
+            baseline_stools,
+            anomalies,
+        })
+    }
+}
+
+fn mean(counts: impl ExactSizeIterator<Item = u32>) -> f32 {
+    let len = counts.len().max(1) as f32;
+    counts.sum::<u32>() as f32 / len
+}
+
+/// Flags for one logged day. Frequency is judged only against a baseline; stool
+/// consistency is flagged by its score alone.
+fn litter_anomalies(
+    day: &LitterDay,
+    baseline_urinations: Option<f32>,
+    baseline_stools: Option<f32>,
+) -> Vec<LitterAnomaly> {
+    let flag = |kind, message: String| LitterAnomaly {
+        date: day.date,
+        kind,
+        message,
+    };
+    let frequent = |count: u32, usual: f32| {
+        count as f32 > usual * HIGH_FREQUENCY_FACTOR
+            && count as f32 - usual >= HIGH_FREQUENCY_MARGIN
+    };
+
+    let mut anomalies = Vec::new();
+    if let Some(usual) = baseline_urinations {
+        if frequent(day.urinations, usual) {
+            anomalies.push(flag(
+                LitterAnomalyKind::FrequentUrination,
+                format!("Urinated {} times, usually {usual:.1}", day.urinations),
+            ));
+        } else if usual >= 1.0 && (day.urinations as f32) < usual * LOW_FREQUENCY_FACTOR {
+            anomalies.push(flag(
+                LitterAnomalyKind::ReducedUrination,
+                format!("Urinated {} times, usually {usual:.1}", day.urinations),
+            ));
+        }
+    }
+    if let Some(usual) = baseline_stools {
+        if frequent(day.stools, usual) {
+            anomalies.push(flag(
+                LitterAnomalyKind::FrequentStools,
+                format!("{} stools, usually {usual:.1}", day.stools),
+            ));
+        }
+    }
+    match day.average_consistency {
+        Some(score) if score >= LOOSE_STOOL_SCORE => anomalies.push(flag(
+            LitterAnomalyKind::LooseStool,
+            format!("Loose stool (consistency {score:.1} of 7)"),
+        )),
+        Some(score) if score <= HARD_STOOL_SCORE => anomalies.push(flag(
+            LitterAnomalyKind::HardStool,
+            format!("Hard stool (consistency {score:.1} of 7)"),
+        )),
+        _ => {}
+    }
+    anomalies
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_litter_trends_flag_deviations_from_baseline() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+        let end = NaiveDate::from_ymd_opt(2025, 6, 30).unwrap();
+
+        let log = |date: NaiveDate, litter: serde_json::Value| ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Health,
+            subcategory: LITTER_SUBCATEGORY.to_string(),
+            activity_data: Some(json!({
+                "time": { "date": format!("{date}T08:00:00.000Z"), "time": "", "timezone": "UTC" },
+                "litter": litter,
+            })),
+            needs_review: false,
+        };
+        // A usual week: 2 urinations and a normal stool a day
+        for offset in 14..21 {
+            let date = end - Days::new(offset);
+            for request in [
+                log(date, json!({ "litterType": "urine", "count": 2 })),
+                log(date, json!({ "litterType": "stool", "consistency": 4 })),
+            ] {
+                db.create_activity(request).await.unwrap();
+            }
+        }
+        let ordinary = end - Days::new(3);
+        let frequent = end - Days::new(2);
+        let loose = end - Days::new(1);
+        for request in [
+            log(
+                ordinary,
+                json!({ "litterType": "both", "count": 2, "consistency": 3 }),
+            ),
+            log(frequent, json!({ "litterType": "urine", "count": 6 })),
+            log(loose, json!({ "litterType": "both", "consistency": 7 })),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let trends = db.get_litter_trends(pet_id, end, None).await.unwrap();
+        assert_eq!(trends.start_date, end - Days::new(13));
+        assert_eq!(trends.baseline_urinations, Some(2.0));
+        assert_eq!(trends.baseline_stools, Some(1.0));
+        assert_eq!(trends.days.len(), 3);
+        assert_eq!(trends.days[0].urinations, 2);
+        assert_eq!(trends.days[0].average_consistency, Some(3.0));
+
+        let flags: Vec<(NaiveDate, LitterAnomalyKind)> = trends
+            .anomalies
+            .iter()
+            .map(|anomaly| (anomaly.date, anomaly.kind))
+            .collect();
+        assert_eq!(
+            flags,
+            vec![
+                (frequent, LitterAnomalyKind::FrequentUrination),
+                (loose, LitterAnomalyKind::LooseStool),
+            ]
+        );
+
+        // Without a baseline only stool consistency is judged
+        let short = db
+            .get_litter_trends(pet_id, end - Days::new(14), Some(7))
+            .await
+            .unwrap();
+        assert_eq!(short.baseline_urinations, None);
+        assert!(short.anomalies.is_empty());
+        assert!(db.get_litter_trends(pet_id, end, Some(0)).await.is_err());
+    }
+}
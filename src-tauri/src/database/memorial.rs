@@ -0,0 +1,312 @@
+use super::activity_data::ActivityDataExt;
+use super::models::*;
+use super::summaries::DAILY_SUMMARY_SUBCATEGORY;
+use crate::errors::PetError;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashSet;
+
+/// Number of most-logged activities listed in a memorial report
+const FAVORITE_ACTIVITY_COUNT: i64 = 5;
+
+/// An activity the pet did often, by number of entries
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FavoriteActivity {
+    pub category: ActivityCategory,
+    pub subcategory: String,
+    pub count: i64,
+}
+
+/// Life summary of a pet that passed away
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemorialReport {
+    pub pet: Pet,
+    pub passed_away_on: NaiveDate,
+    /// Age reached in whole years and the months past the last birthday
+    pub age_years: u32,
+    pub age_months: u32,
+    pub age_days: i64,
+    pub total_activities: i64,
+    pub first_activity_on: Option<NaiveDate>,
+    pub last_activity_on: Option<NaiveDate>,
+    /// Milestones the pet reached, in expected order
+    pub milestones: Vec<MilestoneProgress>,
+    /// Distinct photos attached to activities, plus the profile photo
+    pub photo_count: usize,
+    pub favorite_activities: Vec<FavoriteActivity>,
+    pub generated_at: DateTime<Utc>,
+}
+
+impl super::PetDatabase {
+    /// Move a pet that passed away into memorial mode: it is archived with the memorial
+    /// flag and the date, its reminders are muted and its active recurring series are
+    /// paused. Its history stays in place; returns the life-summary report.
+    pub async fn mark_pet_deceased(
+        &self,
+        pet_id: i64,
+        passed_away_on: NaiveDate,
+    ) -> Result<MemorialReport, PetError> {
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|_| PetError::not_found(pet_id))?;
+        if passed_away_on < pet.birth_date {
+            return Err(PetError::validation(
+                "passed_away_on",
+                "Date cannot be before the pet's birth date",
+            ));
+        }
+
+        let now = Utc::now();
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            UPDATE pets SET is_archived = 1, is_memorial = 1, passed_away_on = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(passed_away_on.format("%Y-%m-%d").to_string())
+        .bind(now)
+        .bind(pet_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        // Muting goes through the notification preferences, so it can be undone there
+        sqlx::query(
+            r#"
+            INSERT INTO pet_notification_preferences (pet_id, reminders_muted, updated_at)
+            VALUES (?, 1, ?)
+            ON CONFLICT (pet_id) DO UPDATE SET
+                reminders_muted = 1,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(pet_id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        let paused = sqlx::query(
+            "UPDATE recurring_series SET status = ?, updated_at = ? WHERE pet_id = ? AND status = ?",
+        )
+        .bind(RecurringSeriesStatus::Paused.to_string())
+        .bind(now)
+        .bind(pet_id)
+        .bind(RecurringSeriesStatus::Active.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?
+        .rows_affected();
+
+        tx.commit()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!(
+            "[DB] mark_pet_deceased: pet_id={pet_id}, passed_away_on={passed_away_on}, paused_series={paused}"
+        );
+        self.get_memorial_report(pet_id).await
+    }
+
+    /// Life summary of a pet in memorial mode: age, milestones, photos and the
+    /// activities it was logged doing most
+    pub async fn get_memorial_report(&self, pet_id: i64) -> Result<MemorialReport, PetError> {
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|_| PetError::not_found(pet_id))?;
+        let passed_away_on = match (pet.is_memorial, pet.passed_away_on) {
+            (true, Some(date)) => date,
+            _ => {
+                return Err(PetError::validation(
+                    "pet_id",
+                    "Pet is not in memorial mode",
+                ))
+            }
+        };
+
+        let totals = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS total, MIN(activity_time) AS first_at, MAX(activity_time) AS last_at
+            FROM activities
+            WHERE pet_id = ? AND needs_review = 0 AND subcategory != ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(DAILY_SUMMARY_SUBCATEGORY)
+        .fetch_one(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+        let activity_date = |column: &str| {
+            totals
+                .get::<Option<String>, _>(column)
+                .and_then(|time| time.get(..10).map(str::to_string))
+                .and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok())
+        };
+
+        let favorite_rows = sqlx::query(
+            r#"
+            SELECT category, subcategory, COUNT(*) AS uses
+            FROM activities
+            WHERE pet_id = ? AND needs_review = 0 AND subcategory != ?
+            GROUP BY category, subcategory
+            ORDER BY uses DESC, MAX(activity_time) DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(DAILY_SUMMARY_SUBCATEGORY)
+        .bind(FAVORITE_ACTIVITY_COUNT)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+        let favorite_activities = favorite_rows
+            .iter()
+            .filter_map(|row| {
+                Some(FavoriteActivity {
+                    category: row.get::<String, _>("category").parse().ok()?,
+                    subcategory: row.get("subcategory"),
+                    count: row.get("uses"),
+                })
+            })
+            .collect();
+
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id: Some(pet_id),
+                format: None,
+            })
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        let photos: HashSet<String> = activities
+            .iter()
+            .filter_map(|activity| activity.activity_data.as_ref())
+            .flat_map(|data| data.attachment_files())
+            .chain(pet.photo_path.clone())
+            .collect();
+
+        let milestones = self
+            .get_milestone_progress(pet_id, passed_away_on)
+            .await?
+            .milestones
+            .into_iter()
+            .filter(|milestone| milestone.status == MilestoneStatus::Completed)
+            .collect();
+
+        let (months, age_months) = age_in_months(pet.birth_date, passed_away_on);
+        Ok(MemorialReport {
+            passed_away_on,
+            age_years: months / 12,
+            age_months,
+            age_days: (passed_away_on - pet.birth_date).num_days(),
+            total_activities: totals.get("total"),
+            first_activity_on: activity_date("first_at"),
+            last_activity_on: activity_date("last_at"),
+            milestones,
+            photo_count: photos.len(),
+            favorite_activities,
+            generated_at: Utc::now(),
+            pet,
+        })
+    }
+}
+
+/// Whole months from `birth_date` to `on`, and the months past the last full year
+fn age_in_months(birth_date: NaiveDate, on: NaiveDate) -> (u32, u32) {
+    let mut months =
+        (on.year() - birth_date.year()) * 12 + on.month() as i32 - birth_date.month() as i32;
+    if on.day() < birth_date.day() {
+        months -= 1;
+    }
+    let months = months.max(0) as u32;
+    (months, months % 12)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[test]
+    fn test_age_in_months() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(
+            age_in_months(date(2015, 3, 20), date(2025, 3, 19)),
+            (119, 11)
+        );
+        assert_eq!(
+            age_in_months(date(2015, 3, 20), date(2025, 3, 20)),
+            (120, 0)
+        );
+        assert_eq!(age_in_months(date(2024, 1, 31), date(2024, 1, 31)), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_mark_pet_deceased() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 30,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (pet_id, other_pet_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+        let pet = db.get_pet_by_id(pet_id).await.unwrap();
+
+        assert!(db.get_memorial_report(pet_id).await.is_err());
+        assert!(db
+            .mark_pet_deceased(pet_id, pet.birth_date - chrono::Days::new(1))
+            .await
+            .is_err());
+
+        let passed_away_on = NaiveDate::from_ymd_opt(2025, 12, 31).unwrap();
+        let report = db.mark_pet_deceased(pet_id, passed_away_on).await.unwrap();
+        assert!(report.pet.is_archived && report.pet.is_memorial);
+        assert_eq!(report.pet.passed_away_on, Some(passed_away_on));
+        assert_eq!(report.total_activities, 30);
+        assert!(report.first_activity_on <= report.last_activity_on);
+        assert!(report.first_activity_on.is_some());
+        assert!(!report.favorite_activities.is_empty());
+        assert!(report.favorite_activities.len() <= FAVORITE_ACTIVITY_COUNT as usize);
+        assert!(report
+            .favorite_activities
+            .windows(2)
+            .all(|pair| pair[0].count >= pair[1].count));
+
+        // Out of the active list and muted, but the history is still there
+        let active: Vec<i64> = db
+            .get_pets(false)
+            .await
+            .unwrap()
+            .iter()
+            .map(|pet| pet.id)
+            .collect();
+        assert_eq!(active, vec![other_pet_id]);
+        assert!(
+            db.get_notification_preferences(pet_id)
+                .await
+                .unwrap()
+                .reminders_muted
+        );
+        assert!(
+            !db.get_notification_preferences(other_pet_id)
+                .await
+                .unwrap()
+                .reminders_muted
+        );
+        assert_eq!(
+            db.get_memorial_report(pet_id)
+                .await
+                .unwrap()
+                .total_activities,
+            30
+        );
+    }
+}
@@ -0,0 +1,691 @@
+use super::activity_data::{ActivityData, ActivityDataExt, BlockData};
+use super::inventory::convert_amount;
+use super::models::*;
+use crate::errors::{ActivityError, PetError};
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc};
+use sqlx::{Row, SqliteConnection};
+use std::collections::BTreeMap;
+
+/// Longest window a rule may look back over
+pub const MAX_WINDOW_DAYS: i64 = 365;
+
+/// Days with logged intake needed before a daily total is compared with their average
+const MIN_BASELINE_DAYS: usize = 3;
+
+/// Format of the `activity_time` column
+const ACTIVITY_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// A change from a baseline that broke a rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleBreach {
+    pub baseline: f64,
+    pub value: f64,
+    pub change: f64,
+    pub change_percent: Option<f64>,
+}
+
+impl super::PetDatabase {
+    /// Add a metric rule
+    pub async fn create_metric_rule(
+        &self,
+        request: CreateMetricRuleRequest,
+    ) -> Result<MetricRule, PetError> {
+        validate_rule_fields(request.window_days, request.threshold)?;
+
+        let now = Utc::now();
+        let id = sqlx::query(
+            r#"
+            INSERT INTO metric_rules
+                (pet_id, metric, window_days, threshold, percent, direction, enabled, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, 1, ?, ?)
+            "#,
+        )
+        .bind(request.pet_id)
+        .bind(request.metric.to_string())
+        .bind(request.window_days)
+        .bind(request.threshold)
+        .bind(request.percent)
+        .bind(request.direction.to_string())
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?
+        .last_insert_rowid();
+
+        log::info!(
+            "[DB] create_metric_rule: rule_id={id}, metric={}, pet_id={:?}",
+            request.metric,
+            request.pet_id
+        );
+        self.get_metric_rule(id).await
+    }
+
+    /// Get a metric rule by ID
+    pub async fn get_metric_rule(&self, id: i64) -> Result<MetricRule, PetError> {
+        let row = sqlx::query("SELECT * FROM metric_rules WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?
+            .ok_or_else(|| PetError::metric_rule_not_found(id))?;
+        row_to_metric_rule(&row)
+    }
+
+    /// Get metric rules; with a pet, its own rules plus those for every pet
+    pub async fn get_metric_rules(&self, pet_id: Option<i64>) -> Result<Vec<MetricRule>, PetError> {
+        let rows = match pet_id {
+            Some(pet_id) => sqlx::query(
+                "SELECT * FROM metric_rules WHERE pet_id IS NULL OR pet_id = ? ORDER BY metric, id",
+            )
+            .bind(pet_id)
+            .fetch_all(&self.read_pool)
+            .await,
+            None => {
+                sqlx::query("SELECT * FROM metric_rules ORDER BY metric, id")
+                    .fetch_all(&self.read_pool)
+                    .await
+            }
+        }
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        rows.iter().map(row_to_metric_rule).collect()
+    }
+
+    /// Change a rule's window, threshold or direction, or turn it on or off
+    pub async fn update_metric_rule(
+        &self,
+        id: i64,
+        updates: MetricRuleUpdateRequest,
+    ) -> Result<MetricRule, PetError> {
+        let current = self.get_metric_rule(id).await?;
+        let window_days = updates.window_days.unwrap_or(current.window_days);
+        let threshold = updates.threshold.unwrap_or(current.threshold);
+        validate_rule_fields(window_days, threshold)?;
+
+        sqlx::query(
+            r#"
+            UPDATE metric_rules
+            SET window_days = ?, threshold = ?, percent = ?, direction = ?, enabled = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(window_days)
+        .bind(threshold)
+        .bind(updates.percent.unwrap_or(current.percent))
+        .bind(updates.direction.unwrap_or(current.direction).to_string())
+        .bind(updates.enabled.unwrap_or(current.enabled))
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!("[DB] update_metric_rule: rule_id={id}");
+        self.get_metric_rule(id).await
+    }
+
+    /// Delete a metric rule and the alerts it raised
+    pub async fn delete_metric_rule(&self, id: i64) -> Result<(), PetError> {
+        let result = sqlx::query("DELETE FROM metric_rules WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(PetError::metric_rule_not_found(id));
+        }
+        log::info!("[DB] delete_metric_rule: rule_id={id}");
+        Ok(())
+    }
+
+    /// Alerts raised for a pet, or for every pet, newest first
+    pub async fn get_metric_alerts(
+        &self,
+        pet_id: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<MetricAlert>, PetError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM metric_alerts
+            WHERE ? IS NULL OR pet_id = ?
+            ORDER BY observed_on DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(pet_id)
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        rows.iter().map(row_to_metric_alert).collect()
+    }
+
+    /// Alerts raised since the last call. Each alert is returned once.
+    pub async fn take_metric_alerts(&self) -> Result<Vec<MetricAlert>, PetError> {
+        let rows =
+            sqlx::query("UPDATE metric_alerts SET notified = 1 WHERE notified = 0 RETURNING *")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?;
+
+        if !rows.is_empty() {
+            log::info!("[DB] take_metric_alerts: {} alert(s)", rows.len());
+        }
+        rows.iter().map(row_to_metric_alert).collect()
+    }
+
+    /// Check the enabled rules for the metrics an activity records, on the caller's
+    /// transaction. Readings are compared with the earliest one in the rule's window;
+    /// daily totals are checked for the day before the activity, the last complete day.
+    pub(super) async fn evaluate_metric_rules(
+        conn: &mut SqliteConnection,
+        activity: &Activity,
+    ) -> Result<(), ActivityError> {
+        let Some(data) = activity
+            .activity_data
+            .as_ref()
+            .filter(|_| !activity.needs_review)
+        else {
+            return Ok(());
+        };
+        let values: Vec<(MetricKind, f64)> = MetricKind::ALL
+            .into_iter()
+            .filter_map(|metric| Some((metric, metric_value(metric, &activity.category, data)?)))
+            .collect();
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        let rows = sqlx::query(
+            "SELECT * FROM metric_rules WHERE enabled = 1 AND (pet_id IS NULL OR pet_id = ?)",
+        )
+        .bind(activity.pet_id)
+        .fetch_all(&mut *conn)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let rules = rows
+            .iter()
+            .map(row_to_metric_rule)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| ActivityError::invalid_data(e.to_string()))?;
+
+        let activity_time: String =
+            sqlx::query_scalar("SELECT activity_time FROM activities WHERE id = ?")
+                .bind(activity.id)
+                .fetch_one(&mut *conn)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let at = NaiveDateTime::parse_from_str(&activity_time, ACTIVITY_TIME_FORMAT)
+            .map_err(|e| ActivityError::invalid_data(format!("Invalid activity time: {e}")))?;
+
+        for rule in &rules {
+            let Some(&(_, value)) = values.iter().find(|(metric, _)| *metric == rule.metric) else {
+                continue;
+            };
+            let window_start = at - chrono::Duration::days(rule.window_days);
+            let readings =
+                metric_readings(conn, activity.pet_id, rule.metric, window_start.date(), at)
+                    .await?;
+
+            let (observed_on, activity_id, breach) = if rule.metric.is_daily_total() {
+                let Some(day) = at.date().checked_sub_days(Days::new(1)) else {
+                    continue;
+                };
+                (day, None, check_daily_total(rule, &readings, day))
+            } else {
+                let baseline = readings
+                    .iter()
+                    .find(|reading| reading.id != activity.id && reading.at >= window_start);
+                (
+                    at.date(),
+                    Some(activity.id),
+                    baseline.and_then(|baseline| check_change(rule, baseline.value, value)),
+                )
+            };
+            let Some(breach) = breach else {
+                continue;
+            };
+
+            let inserted = sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO metric_alerts
+                    (rule_id, pet_id, activity_id, metric, observed_on, baseline, value, change, change_percent, created_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(rule.id)
+            .bind(activity.pet_id)
+            .bind(activity_id)
+            .bind(rule.metric.to_string())
+            .bind(observed_on)
+            .bind(breach.baseline)
+            .bind(breach.value)
+            .bind(breach.change)
+            .bind(breach.change_percent)
+            .bind(Utc::now())
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .rows_affected();
+            if inserted > 0 {
+                log::info!(
+                    "[DB] metric rule {} broken for pet_id={}: {} {} -> {} on {observed_on}",
+                    rule.id,
+                    activity.pet_id,
+                    rule.metric,
+                    breach.baseline,
+                    breach.value
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A logged value of a metric
+#[derive(Debug, Clone)]
+struct MetricReading {
+    id: i64,
+    at: NaiveDateTime,
+    value: f64,
+}
+
+/// Values of `metric` logged for a pet from `from` up to `until`, oldest first
+async fn metric_readings(
+    conn: &mut SqliteConnection,
+    pet_id: i64,
+    metric: MetricKind,
+    from: NaiveDate,
+    until: NaiveDateTime,
+) -> Result<Vec<MetricReading>, ActivityError> {
+    let rows = sqlx::query(
+        r#"
+        SELECT id, category, activity_time, activity_data FROM activities
+        WHERE pet_id = ? AND needs_review = 0 AND activity_data IS NOT NULL
+            AND activity_time >= ? AND activity_time <= ?
+        ORDER BY activity_time ASC, id ASC
+        "#,
+    )
+    .bind(pet_id)
+    .bind(from.format("%Y-%m-%d").to_string())
+    .bind(until.format(ACTIVITY_TIME_FORMAT).to_string())
+    .fetch_all(&mut *conn)
+    .await
+    .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+    Ok(rows
+        .iter()
+        .filter_map(|row| {
+            let category = row.get::<String, _>("category").parse().ok()?;
+            let at = NaiveDateTime::parse_from_str(
+                &row.get::<String, _>("activity_time"),
+                ACTIVITY_TIME_FORMAT,
+            )
+            .ok()?;
+            let data: ActivityData =
+                serde_json::from_str(&row.get::<String, _>("activity_data")).ok()?;
+            Some(MetricReading {
+                id: row.get("id"),
+                at,
+                value: metric_value(metric, &category, &data)?,
+            })
+        })
+        .collect())
+}
+
+/// Value of `metric` an activity records, in the metric's unit
+pub fn metric_value(
+    metric: MetricKind,
+    category: &ActivityCategory,
+    data: &ActivityData,
+) -> Option<f64> {
+    let value = match metric {
+        MetricKind::Weight => data.extract_weight_kg(),
+        MetricKind::Height => data.extract_height_cm(),
+        MetricKind::Temperature => data.extract_temperature_c(),
+        MetricKind::WaterIntake => data.extract_water_ml(),
+        MetricKind::FoodIntake => {
+            if *category != ActivityCategory::Diet {
+                return None;
+            }
+            return match data.get("portion") {
+                Some(BlockData::Portion { amount, unit, .. }) => {
+                    convert_amount(*amount as f64, unit, "g")
+                }
+                _ => None,
+            };
+        }
+    };
+    value.map(f64::from)
+}
+
+/// Check a change from `baseline` to `value` against a rule
+pub fn check_change(rule: &MetricRule, baseline: f64, value: f64) -> Option<RuleBreach> {
+    let change = value - baseline;
+    let change_percent = (baseline.abs() > f64::EPSILON).then(|| change / baseline * 100.0);
+    let magnitude = if rule.percent {
+        change_percent?
+    } else {
+        change
+    };
+    let breached = match rule.direction {
+        ChangeDirection::Rise => magnitude >= rule.threshold,
+        ChangeDirection::Drop => -magnitude >= rule.threshold,
+        ChangeDirection::Either => magnitude.abs() >= rule.threshold,
+    };
+    breached.then_some(RuleBreach {
+        baseline,
+        value,
+        change,
+        change_percent,
+    })
+}
+
+/// Compare the total of `day` with the average of the earlier days in the readings
+/// that have anything logged. Days without logs are skipped rather than counted as zero.
+fn check_daily_total(
+    rule: &MetricRule,
+    readings: &[MetricReading],
+    day: NaiveDate,
+) -> Option<RuleBreach> {
+    let mut totals: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for reading in readings {
+        *totals.entry(reading.at.date()).or_default() += reading.value;
+    }
+    let value = *totals.get(&day)?;
+    let earlier: Vec<f64> = totals.range(..day).map(|(_, total)| *total).collect();
+    if earlier.len() < MIN_BASELINE_DAYS {
+        return None;
+    }
+    let baseline = earlier.iter().sum::<f64>() / earlier.len() as f64;
+    check_change(rule, baseline, value)
+}
+
+fn validate_rule_fields(window_days: i64, threshold: f64) -> Result<(), PetError> {
+    if !(1..=MAX_WINDOW_DAYS).contains(&window_days) {
+        return Err(PetError::validation(
+            "window_days".to_string(),
+            format!("Window must be between 1 and {MAX_WINDOW_DAYS} days"),
+        ));
+    }
+    if !threshold.is_finite() || threshold <= 0.0 {
+        return Err(PetError::validation(
+            "threshold",
+            "Threshold must be a positive number",
+        ));
+    }
+    Ok(())
+}
+
+fn row_to_metric_rule(row: &sqlx::sqlite::SqliteRow) -> Result<MetricRule, PetError> {
+    let metric: String = row.get("metric");
+    let direction: String = row.get("direction");
+    Ok(MetricRule {
+        id: row.get("id"),
+        pet_id: row.get("pet_id"),
+        metric: metric
+            .parse()
+            .map_err(|e: anyhow::Error| PetError::database(e.to_string()))?,
+        window_days: row.get("window_days"),
+        threshold: row.get("threshold"),
+        percent: row.get("percent"),
+        direction: direction
+            .parse()
+            .map_err(|e: anyhow::Error| PetError::database(e.to_string()))?,
+        enabled: row.get("enabled"),
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+        updated_at: row.get::<DateTime<Utc>, _>("updated_at"),
+        display_unit: WeightUnit::Kg,
+    })
+}
+
+fn row_to_metric_alert(row: &sqlx::sqlite::SqliteRow) -> Result<MetricAlert, PetError> {
+    let metric: String = row.get("metric");
+    Ok(MetricAlert {
+        id: row.get("id"),
+        rule_id: row.get("rule_id"),
+        pet_id: row.get("pet_id"),
+        activity_id: row.get("activity_id"),
+        metric: metric
+            .parse()
+            .map_err(|e: anyhow::Error| PetError::database(e.to_string()))?,
+        observed_on: row.get("observed_on"),
+        baseline: row.get("baseline"),
+        value: row.get("value"),
+        change: row.get("change"),
+        change_percent: row.get("change_percent"),
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+        display_unit: WeightUnit::Kg,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::activity_blocks::MeasurementKind;
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::ActivityBlocksBuilder;
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_check_change_directions() {
+        let mut rule = MetricRule {
+            id: 1,
+            pet_id: None,
+            metric: MetricKind::Temperature,
+            window_days: 1,
+            threshold: 1.0,
+            percent: false,
+            direction: ChangeDirection::Rise,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_unit: WeightUnit::Kg,
+        };
+        assert!(check_change(&rule, 38.5, 39.6).is_some());
+        assert!(check_change(&rule, 38.5, 39.0).is_none());
+        assert!(check_change(&rule, 39.6, 38.5).is_none());
+
+        rule.direction = ChangeDirection::Drop;
+        rule.percent = true;
+        rule.threshold = 30.0;
+        let breach = check_change(&rule, 200.0, 120.0).unwrap();
+        assert_eq!(breach.change, -80.0);
+        assert_eq!(breach.change_percent, Some(-40.0));
+        assert!(check_change(&rule, 200.0, 150.0).is_none());
+        // A percent change of a zero baseline is undefined
+        assert!(check_change(&rule, 0.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn test_weight_rules_and_alerts_in_display_unit() {
+        let rule = MetricRule {
+            id: 1,
+            pet_id: None,
+            metric: MetricKind::Weight,
+            window_days: 30,
+            threshold: 0.5,
+            percent: false,
+            direction: ChangeDirection::Drop,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_unit: WeightUnit::Kg,
+        };
+        let shown = rule.clone().with_display_unit(WeightUnit::Lb);
+        assert_eq!(shown.threshold, 1.1);
+        assert_eq!(shown.display_unit, WeightUnit::Lb);
+        assert!((WeightUnit::Lb.to_kg(shown.threshold) - 0.5).abs() < 0.01);
+        // Percent thresholds have no unit
+        let percent = MetricRule {
+            percent: true,
+            threshold: 10.0,
+            ..rule
+        };
+        assert_eq!(percent.with_display_unit(WeightUnit::Lb).threshold, 10.0);
+
+        let alert = MetricAlert {
+            id: 1,
+            rule_id: 1,
+            pet_id: 1,
+            activity_id: Some(3),
+            metric: MetricKind::Weight,
+            observed_on: NaiveDate::from_ymd_opt(2025, 3, 1).unwrap(),
+            baseline: 10.0,
+            value: 9.0,
+            change: -1.0,
+            change_percent: Some(-10.0),
+            created_at: Utc::now(),
+            display_unit: WeightUnit::Kg,
+        }
+        .with_display_unit(WeightUnit::Lb);
+        assert_eq!(
+            (alert.baseline, alert.value, alert.change),
+            (22.05, 19.84, -2.2)
+        );
+        assert_eq!(alert.change_percent, Some(-10.0));
+        assert_eq!(alert.display_unit, WeightUnit::Lb);
+    }
+
+    #[tokio::test]
+    async fn test_rules_raise_alerts_incrementally() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let temperature_rule = db
+            .create_metric_rule(CreateMetricRuleRequest {
+                pet_id: Some(pet_id),
+                metric: MetricKind::Temperature,
+                window_days: 1,
+                threshold: 1.0,
+                percent: false,
+                direction: ChangeDirection::Rise,
+            })
+            .await
+            .unwrap();
+        let food_rule = db
+            .create_metric_rule(CreateMetricRuleRequest {
+                pet_id: None,
+                metric: MetricKind::FoodIntake,
+                window_days: 7,
+                threshold: 30.0,
+                percent: true,
+                direction: ChangeDirection::Drop,
+            })
+            .await
+            .unwrap();
+        assert!(db
+            .create_metric_rule(CreateMetricRuleRequest {
+                pet_id: None,
+                metric: MetricKind::Weight,
+                window_days: 0,
+                threshold: 5.0,
+                percent: true,
+                direction: ChangeDirection::Either,
+            })
+            .await
+            .is_err());
+
+        let log = |category: ActivityCategory, blocks: ActivityBlocksBuilder| {
+            let db = &db;
+            async move {
+                db.create_activity_with_side_effects(ActivityCreateRequest {
+                    pet_id,
+                    category,
+                    subcategory: "Log".to_string(),
+                    activity_data: Some(blocks.into_json()),
+                    needs_review: false,
+                })
+                .await
+                .unwrap()
+            }
+        };
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2025, 4, day, hour, 0, 0).unwrap();
+
+        for (hour, celsius) in [(8, 38.4), (20, 39.6)] {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(at(1, hour));
+            blocks
+                .measurement(MeasurementKind::Temperature, celsius, "C")
+                .unwrap();
+            log(ActivityCategory::Health, blocks).await;
+        }
+        let alerts = db.take_metric_alerts().await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule_id, temperature_rule.id);
+        assert!((alerts[0].change - 1.2).abs() < 0.01);
+        assert!(db.take_metric_alerts().await.unwrap().is_empty());
+
+        // 200 g a day, then 100 g on the 6th; the drop shows once the 7th is logged
+        for (day, grams) in [
+            (2, 200.0),
+            (3, 200.0),
+            (4, 200.0),
+            (5, 200.0),
+            (6, 100.0),
+            (7, 200.0),
+        ] {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(at(day, 8));
+            blocks.portion(grams, "g", "meal", None).unwrap();
+            log(ActivityCategory::Diet, blocks).await;
+        }
+        let alerts = db.take_metric_alerts().await.unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].rule_id, food_rule.id);
+        assert_eq!(
+            alerts[0].observed_on,
+            NaiveDate::from_ymd_opt(2025, 4, 6).unwrap()
+        );
+        assert_eq!(alerts[0].change_percent, Some(-50.0));
+
+        // The built-in weight rule, and turning a rule off
+        for (day, kg) in [(1, 4.0), (20, 4.5)] {
+            let mut blocks = ActivityBlocksBuilder::new();
+            blocks.time(at(day, 9)).weight(kg, "kg").unwrap();
+            log(ActivityCategory::Growth, blocks).await;
+        }
+        let alerts = db.get_metric_alerts(Some(pet_id), 10).await.unwrap();
+        assert_eq!(alerts.len(), 3);
+        assert_eq!(alerts[0].metric, MetricKind::Weight);
+
+        db.update_metric_rule(
+            temperature_rule.id,
+            MetricRuleUpdateRequest {
+                enabled: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(at(21, 8));
+        blocks
+            .measurement(MeasurementKind::Temperature, 41.0, "C")
+            .unwrap();
+        log(ActivityCategory::Health, blocks).await;
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(at(21, 20));
+        blocks
+            .measurement(MeasurementKind::Temperature, 42.5, "C")
+            .unwrap();
+        log(ActivityCategory::Health, blocks).await;
+        assert!(db
+            .take_metric_alerts()
+            .await
+            .unwrap()
+            .iter()
+            .all(|alert| alert.metric == MetricKind::Weight));
+
+        db.delete_metric_rule(food_rule.id).await.unwrap();
+        assert!(db.delete_metric_rule(food_rule.id).await.is_err());
+    }
+}
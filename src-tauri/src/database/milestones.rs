@@ -0,0 +1,198 @@
+use super::models::*;
+use crate::errors::PetError;
+use crate::milestones;
+use chrono::{NaiveDate, Utc};
+use sqlx::Row;
+use std::collections::HashMap;
+
+impl super::PetDatabase {
+    /// Record the date a pet reached a milestone, replacing an earlier record
+    pub async fn record_milestone(
+        &self,
+        pet_id: i64,
+        milestone_key: &str,
+        completed_on: NaiveDate,
+        notes: Option<String>,
+    ) -> Result<(), PetError> {
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            INSERT INTO pet_milestones (pet_id, milestone_key, completed_on, notes, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (pet_id, milestone_key) DO UPDATE SET
+                completed_on = excluded.completed_on,
+                notes = COALESCE(excluded.notes, notes),
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(pet_id)
+        .bind(milestone_key)
+        .bind(completed_on.format("%Y-%m-%d").to_string())
+        .bind(&notes)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!(
+            "[DB] record_milestone: pet_id={pet_id}, milestone={milestone_key}, completed_on={completed_on}"
+        );
+        Ok(())
+    }
+
+    /// Forget that a pet reached a milestone
+    pub async fn clear_milestone(&self, pet_id: i64, milestone_key: &str) -> Result<(), PetError> {
+        sqlx::query("DELETE FROM pet_milestones WHERE pet_id = ? AND milestone_key = ?")
+            .bind(pet_id)
+            .bind(milestone_key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!("[DB] clear_milestone: pet_id={pet_id}, milestone={milestone_key}");
+        Ok(())
+    }
+
+    /// The milestones that apply to a pet's species, ordered by expected age,
+    /// with their status on `today`
+    pub async fn get_milestone_progress(
+        &self,
+        pet_id: i64,
+        today: NaiveDate,
+    ) -> Result<MilestoneProgressReport, PetError> {
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|_| PetError::not_found(pet_id))?;
+
+        let rows = sqlx::query(
+            "SELECT milestone_key, completed_on, notes FROM pet_milestones WHERE pet_id = ?",
+        )
+        .bind(pet_id)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| PetError::database(e.to_string()))?;
+        let mut recorded: HashMap<String, (Option<NaiveDate>, Option<String>)> = rows
+            .iter()
+            .map(|row| {
+                let completed_on = row
+                    .try_get::<String, _>("completed_on")
+                    .ok()
+                    .and_then(|date| NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok());
+                (
+                    row.get("milestone_key"),
+                    (completed_on, row.try_get("notes").ok().flatten()),
+                )
+            })
+            .collect();
+
+        let mut progress: Vec<MilestoneProgress> = milestones::all_milestones()
+            .iter()
+            .filter_map(|milestone| {
+                let window = milestone.window_for(&pet.species)?;
+                let (expected_from, expected_until) = window.dates(pet.birth_date);
+                let (completed_on, notes) = recorded.remove(milestone.key).unwrap_or((None, None));
+                Some(MilestoneProgress {
+                    key: milestone.key.to_string(),
+                    title: milestone.title.to_string(),
+                    description: milestone.description.to_string(),
+                    expected_from,
+                    expected_until,
+                    status: window.status(pet.birth_date, completed_on, today),
+                    completed_on,
+                    notes,
+                })
+            })
+            .collect();
+        progress.sort_by_key(|milestone| (milestone.expected_from, milestone.expected_until));
+
+        let completed = progress
+            .iter()
+            .filter(|milestone| milestone.status == MilestoneStatus::Completed)
+            .count();
+        Ok(MilestoneProgressReport {
+            pet_id,
+            completed,
+            total: progress.len(),
+            milestones: progress,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_milestone_progress_timeline() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+        let pet = db.get_pet_by_id(pet_id).await.unwrap();
+
+        let report = db
+            .get_milestone_progress(pet_id, pet.birth_date)
+            .await
+            .unwrap();
+        assert_eq!(report.completed, 0);
+        assert!(report
+            .milestones
+            .iter()
+            .all(|m| m.status == MilestoneStatus::Upcoming));
+        assert!(report
+            .milestones
+            .windows(2)
+            .all(|pair| pair[0].expected_from <= pair[1].expected_from));
+        let first = &report.milestones[0];
+        assert_eq!(first.key, "first_vaccine");
+
+        let vaccinated_on = first.expected_from;
+        db.record_milestone(
+            pet_id,
+            "first_vaccine",
+            vaccinated_on,
+            Some("Clinic".into()),
+        )
+        .await
+        .unwrap();
+        // Re-recording moves the date and keeps earlier notes
+        db.record_milestone(
+            pet_id,
+            "first_vaccine",
+            vaccinated_on.succ_opt().unwrap(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let two_years_later = pet.birth_date + chrono::Days::new(730);
+        let report = db
+            .get_milestone_progress(pet_id, two_years_later)
+            .await
+            .unwrap();
+        assert_eq!(report.completed, 1);
+        assert_eq!(report.milestones[0].status, MilestoneStatus::Completed);
+        assert_eq!(report.milestones[0].completed_on, vaccinated_on.succ_opt());
+        assert_eq!(report.milestones[0].notes.as_deref(), Some("Clinic"));
+        assert!(report.milestones[1..]
+            .iter()
+            .all(|m| m.status == MilestoneStatus::Overdue));
+
+        db.clear_milestone(pet_id, "first_vaccine").await.unwrap();
+        let report = db
+            .get_milestone_progress(pet_id, two_years_later)
+            .await
+            .unwrap();
+        assert_eq!(report.completed, 0);
+        assert!(db
+            .get_milestone_progress(-1, two_years_later)
+            .await
+            .is_err());
+    }
+}
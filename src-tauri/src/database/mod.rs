@@ -1,19 +1,96 @@
 pub mod activities;
+pub mod activity_blocks;
 pub mod activity_data;
+pub mod activity_links;
+pub mod activity_schema;
+pub mod activity_time;
+#[cfg(test)]
+mod benchmarks;
+pub mod body_condition;
+pub mod budgets;
+pub mod checklists;
+pub mod comparison;
+pub mod cost_benchmarks;
+pub mod custom_blocks;
+pub mod demo_data;
+pub mod derived_data;
+pub mod diagnostics;
+pub mod documents;
+pub mod exercise;
+pub mod expense_forecast;
+pub mod file_journal;
+pub mod footprint;
 pub mod fts;
+pub mod grooming;
+pub mod growth_chart;
+pub mod health;
+pub mod heatmap;
+pub mod hooks;
+pub mod hydration;
+pub mod integrity;
+pub mod inventory;
+pub mod jobs;
+pub mod litter;
+pub mod memorial;
+pub mod metric_rules;
+pub mod milestones;
 pub mod models;
+pub mod notification_preferences;
+pub mod overlaps;
+pub mod pet_aliases;
+pub mod pet_merge;
 pub mod pets;
+pub mod photo_renames;
+pub mod places;
+pub mod portion_history;
+pub mod query;
+pub mod recurring;
+pub mod reminder_completions;
+pub mod reminders;
+pub mod schema_validation;
+pub mod search;
+pub mod search_query;
+pub mod settings;
+pub mod smart_defaults;
+pub mod storage_quota;
+pub mod subcategory_suggestions;
+pub mod summaries;
+#[cfg(test)]
+pub mod test_support;
+pub mod timeline_digest;
+pub mod trash;
+pub mod webhooks;
+pub mod write_queue;
 
+pub use activity_blocks::ActivityBlocksBuilder;
 pub use activity_data::ActivityData;
+pub use hooks::{ActivityEvent, ActivityHook, ActivityHooks};
 pub use models::*;
+pub use write_queue::{in_background, WritePriority};
 
 use anyhow::Result;
-use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqliteSynchronous};
-use std::{path::Path, str::FromStr};
+use sqlx::sqlite::{
+    SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions, SqliteSynchronous,
+};
+use std::{path::Path, str::FromStr, sync::atomic::AtomicBool, sync::Arc};
+
+/// Connections reading alongside the writer; WAL lets them run while it writes
+const READ_POOL_CONNECTIONS: u32 = 4;
 
 /// Main database instance that combines all modules
 pub struct PetDatabase {
+    /// The one connection that writes. SQLite allows a single writer at a time,
+    /// so a second writing connection would only wait on the lock and fail with
+    /// "database is locked" once the busy timeout runs out.
     pub pool: SqlitePool,
+    /// Connections for queries that only read, such as the timeline and search,
+    /// so they keep going during an import. They can't write.
+    pub read_pool: SqlitePool,
+    hooks: Arc<ActivityHooks>,
+    /// Orders activity writes and maintenance jobs, interactive ones first
+    writes: write_queue::WriteScheduler,
+    /// Check activity reads against the legacy JSON path, see [`schema_validation`]
+    schema_validation: AtomicBool,
 }
 
 impl PetDatabase {
@@ -27,12 +104,57 @@ impl PetDatabase {
             .journal_mode(SqliteJournalMode::Wal)
             .synchronous(SqliteSynchronous::Normal);
 
-        let pool = SqlitePool::connect_with(options).await?;
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options.clone())
+            .await?;
 
         // Run migrations
         sqlx::migrate!("./migrations").run(&pool).await?;
 
-        Ok(PetDatabase { pool })
+        // Opened after the migrations, which create the file and switch it to WAL
+        let read_pool = SqlitePoolOptions::new()
+            .max_connections(READ_POOL_CONNECTIONS)
+            .connect_with(options.pragma("query_only", "ON"))
+            .await?;
+
+        Ok(PetDatabase {
+            pool,
+            read_pool,
+            hooks: Arc::new(ActivityHooks::with_builtin()),
+            writes: Default::default(),
+            schema_validation: AtomicBool::new(false),
+        })
+    }
+
+    /// Hooks notified about activity changes; register more at startup
+    pub fn activity_hooks(&self) -> Arc<ActivityHooks> {
+        self.hooks.clone()
+    }
+
+    /// Open a private in-memory database with all migrations applied. The pool keeps
+    /// a single connection open for good, since the data only lives as long as it does;
+    /// reads share it.
+    #[cfg(any(test, feature = "test-support"))]
+    pub async fn in_memory() -> Result<Self> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?;
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
+
+        sqlx::migrate!("./migrations").run(&pool).await?;
+
+        Ok(PetDatabase {
+            read_pool: pool.clone(),
+            pool,
+            hooks: Arc::new(ActivityHooks::with_builtin()),
+            writes: Default::default(),
+            schema_validation: AtomicBool::new(false),
+        })
     }
 
     /// Create a new database instance for testing
@@ -41,3 +163,40 @@ impl PetDatabase {
         Self::new(database_path).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_run_beside_an_open_write() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = PetDatabase::new(dir.path().join("pets.db")).await.unwrap();
+
+        let mut tx = db.pool.begin().await.unwrap();
+        sqlx::query("INSERT INTO app_settings (key, value) VALUES ('pending', '1')")
+            .execute(&mut *tx)
+            .await
+            .unwrap();
+
+        // The reader sees the last commit without waiting for the writer
+        let pending: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM app_settings WHERE key = 'pending'")
+                .fetch_one(&db.read_pool)
+                .await
+                .unwrap();
+        assert_eq!(pending, 0);
+        tx.commit().await.unwrap();
+        let pending: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM app_settings WHERE key = 'pending'")
+                .fetch_one(&db.read_pool)
+                .await
+                .unwrap();
+        assert_eq!(pending, 1);
+
+        assert!(sqlx::query("DELETE FROM app_settings")
+            .execute(&db.read_pool)
+            .await
+            .is_err());
+    }
+}
@@ -18,8 +18,161 @@ pub struct Pet {
     pub notes: Option<String>,
     pub display_order: i64,
     pub is_archived: bool,
+    /// Archived because the pet passed away
+    #[serde(default)]
+    pub is_memorial: bool,
+    #[serde(default)]
+    pub passed_away_on: Option<chrono::NaiveDate>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// Weight converted to `display_unit`; `weight_kg` stays the canonical value
+    #[serde(default)]
+    pub display_weight: Option<f32>,
+    #[serde(default)]
+    pub display_unit: WeightUnit,
+}
+
+impl Pet {
+    /// Express the display weight in `unit`
+    pub fn with_display_unit(mut self, unit: WeightUnit) -> Self {
+        self.display_weight = self.weight_kg.map(|kg| unit.from_kg(kg));
+        self.display_unit = unit;
+        self
+    }
+}
+
+/// Settings key for the preferred weight unit
+pub const WEIGHT_UNIT_SETTING_KEY: &str = "weight_unit";
+
+/// Kilograms in one pound
+pub const KG_PER_LB: f32 = 0.453592;
+
+/// Unit used to present weights to the user
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum WeightUnit {
+    #[default]
+    Kg,
+    Lb,
+}
+
+impl WeightUnit {
+    /// Convert a weight in kilograms to this unit, rounded to 2 decimals
+    pub fn from_kg(self, kg: f32) -> f32 {
+        let value = match self {
+            WeightUnit::Kg => kg,
+            WeightUnit::Lb => kg / KG_PER_LB,
+        };
+        (value * 100.0).round() / 100.0
+    }
+
+    /// [`WeightUnit::from_kg`] for values kept as `f64`, such as metric rule thresholds
+    pub fn from_kg_f64(self, kg: f64) -> f64 {
+        let value = match self {
+            WeightUnit::Kg => kg,
+            WeightUnit::Lb => kg / KG_PER_LB as f64,
+        };
+        (value * 100.0).round() / 100.0
+    }
+
+    /// Convert a weight in this unit to kilograms
+    pub fn to_kg(self, value: f64) -> f64 {
+        match self {
+            WeightUnit::Kg => value,
+            WeightUnit::Lb => value * KG_PER_LB as f64,
+        }
+    }
+}
+
+impl std::fmt::Display for WeightUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeightUnit::Kg => write!(f, "kg"),
+            WeightUnit::Lb => write!(f, "lb"),
+        }
+    }
+}
+
+impl std::str::FromStr for WeightUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "kg" => Ok(WeightUnit::Kg),
+            "lb" | "lbs" => Ok(WeightUnit::Lb),
+            _ => Err(anyhow::anyhow!("Invalid weight unit: {}", s)),
+        }
+    }
+}
+
+/// Settings key of the tokenizer the full-text search indexes are built with
+pub const FTS_TOKENIZER_SETTING_KEY: &str = "fts_tokenizer";
+
+/// How full-text search splits text into searchable terms
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum FtsTokenizer {
+    /// Words separated by spaces and punctuation, ignoring case and diacritics
+    #[default]
+    Unicode61,
+    /// Every three-character sequence, so text written without spaces (Chinese,
+    /// Japanese) matches anywhere inside a sentence
+    Trigram,
+}
+
+impl FtsTokenizer {
+    /// Value of the FTS5 `tokenize` option
+    pub fn tokenize_option(self) -> &'static str {
+        match self {
+            FtsTokenizer::Unicode61 => "unicode61 remove_diacritics 2",
+            FtsTokenizer::Trigram => "trigram",
+        }
+    }
+
+    /// Shortest term the index can match; shorter terms need a scan
+    pub fn min_term_chars(self) -> usize {
+        match self {
+            FtsTokenizer::Unicode61 => 1,
+            FtsTokenizer::Trigram => 3,
+        }
+    }
+}
+
+impl std::fmt::Display for FtsTokenizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FtsTokenizer::Unicode61 => write!(f, "unicode61"),
+            FtsTokenizer::Trigram => write!(f, "trigram"),
+        }
+    }
+}
+
+impl std::str::FromStr for FtsTokenizer {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "unicode61" => Ok(FtsTokenizer::Unicode61),
+            "trigram" => Ok(FtsTokenizer::Trigram),
+            _ => Err(anyhow::anyhow!("Invalid FTS tokenizer: {}", s)),
+        }
+    }
+}
+
+/// A weight measurement taken from a growth activity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightRecord {
+    pub activity_id: i64,
+    pub date: chrono::NaiveDate,
+    pub weight_kg: f32,
+    /// Weight converted to the history's `display_unit`
+    pub value: f32,
+}
+
+/// A pet's weight measurements in date order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightHistory {
+    pub pet_id: i64,
+    pub display_unit: WeightUnit,
+    pub records: Vec<WeightRecord>,
 }
 
 /// Pet species enum
@@ -118,10 +271,26 @@ pub struct Activity {
     pub subcategory: String,
     #[serde(default)]
     pub activity_data: Option<super::ActivityData>,
+    /// Machine-created and waiting for the user to approve it
+    #[serde(default)]
+    pub needs_review: bool,
+    /// Never shared: left out of exports, webhooks and reports written for others
+    #[serde(default)]
+    pub is_private: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Activity {
+    /// Date the activity happened, from its time block or falling back to creation time
+    pub fn occurred_on(&self) -> chrono::NaiveDate {
+        self.activity_data
+            .as_ref()
+            .and_then(|data| data.extract_activity_date())
+            .unwrap_or_else(|| self.created_at.date_naive())
+    }
+}
+
 /// Response structure for Activity with frontend-compatible blocks
 /// Automatically converts ActivityData to frontend block format
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -132,6 +301,8 @@ pub struct ActivityResponse {
     pub subcategory: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub activity_data: Option<serde_json::Value>,
+    pub needs_review: bool,
+    pub is_private: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -149,6 +320,8 @@ impl From<Activity> for ActivityResponse {
             category: activity.category,
             subcategory: activity.subcategory,
             activity_data,
+            needs_review: activity.needs_review,
+            is_private: activity.is_private,
             created_at: activity.created_at,
             updated_at: activity.updated_at,
         }
@@ -244,6 +417,10 @@ pub struct ActivityCreateRequest {
     pub subcategory: String,
     #[serde(default)]
     pub activity_data: Option<serde_json::Value>,
+    /// Set for activities created by importers, recurring series or quick-entry parsing,
+    /// which stay out of the timeline until approved
+    #[serde(default)]
+    pub needs_review: bool,
 }
 
 /// Request structure for updating an activity
@@ -312,10 +489,71 @@ pub struct GetActivitiesRequest {
     pub category: Option<ActivityCategory>,
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
-    pub sort_by: Option<String>, // "created_at", "updated_at"
+    pub sort_by: Option<String>, // "activity_time", "created_at", "updated_at", "cost"
     pub sort_desc: Option<bool>,
     pub limit: Option<i64>,
     pub offset: Option<i64>,
+    /// Count with a full scan instead of the per-pet count cache
+    #[serde(default)]
+    pub exact_count: bool,
+}
+
+/// Allowed sort keys for activity queries
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum ActivitySortKey {
+    /// Time block date, falling back to creation time
+    ActivityTime,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Cost,
+}
+
+impl ActivitySortKey {
+    /// Build an ORDER BY clause with stable secondary ordering.
+    /// Only the cost can be missing, and activities without one are placed last;
+    /// the activity time falls back to the creation time, which is always set.
+    pub fn order_by_clause(&self, desc: bool) -> &'static str {
+        match (self, desc) {
+            (ActivitySortKey::ActivityTime, true) => "activity_time DESC, created_at DESC, id DESC",
+            (ActivitySortKey::ActivityTime, false) => "activity_time ASC, created_at ASC, id ASC",
+            (ActivitySortKey::CreatedAt, true) => "created_at DESC, id DESC",
+            (ActivitySortKey::CreatedAt, false) => "created_at ASC, id ASC",
+            (ActivitySortKey::UpdatedAt, true) => "updated_at DESC, id DESC",
+            (ActivitySortKey::UpdatedAt, false) => "updated_at ASC, id ASC",
+            (ActivitySortKey::Cost, true) => {
+                "cost_amount IS NULL, cost_amount DESC, activity_time DESC, id DESC"
+            }
+            (ActivitySortKey::Cost, false) => {
+                "cost_amount IS NULL, cost_amount ASC, activity_time ASC, id ASC"
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ActivitySortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActivitySortKey::ActivityTime => write!(f, "activity_time"),
+            ActivitySortKey::CreatedAt => write!(f, "created_at"),
+            ActivitySortKey::UpdatedAt => write!(f, "updated_at"),
+            ActivitySortKey::Cost => write!(f, "cost"),
+        }
+    }
+}
+
+impl std::str::FromStr for ActivitySortKey {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "activity_time" | "time" => Ok(ActivitySortKey::ActivityTime),
+            "created_at" => Ok(ActivitySortKey::CreatedAt),
+            "updated_at" => Ok(ActivitySortKey::UpdatedAt),
+            "cost" | "cost_amount" => Ok(ActivitySortKey::Cost),
+            _ => Err(anyhow::anyhow!("Invalid sort key: {}", s)),
+        }
+    }
 }
 
 /// Response structure for getting activities
@@ -349,3 +587,895 @@ pub struct ActivityStatsResponse {
     pub recent_activities: Vec<Activity>,
     pub date_range_days: i64,
 }
+
+// Recurring Activity Types
+
+/// Lifecycle status of a recurring activity series
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RecurringSeriesStatus {
+    Active,
+    Paused,
+    Cancelled,
+}
+
+impl std::fmt::Display for RecurringSeriesStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecurringSeriesStatus::Active => write!(f, "active"),
+            RecurringSeriesStatus::Paused => write!(f, "paused"),
+            RecurringSeriesStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for RecurringSeriesStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(RecurringSeriesStatus::Active),
+            "paused" => Ok(RecurringSeriesStatus::Paused),
+            "cancelled" => Ok(RecurringSeriesStatus::Cancelled),
+            _ => Err(anyhow::anyhow!("Invalid recurring series status: {}", s)),
+        }
+    }
+}
+
+/// Recurring activity series (e.g. monthly flea treatment)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringSeries {
+    pub id: i64,
+    pub pet_id: i64,
+    pub category: ActivityCategory,
+    pub subcategory: String,
+    pub activity_data: Option<serde_json::Value>,
+    pub rrule: String,
+    pub start_date: chrono::NaiveDate,
+    pub materialize_days_ahead: i64,
+    pub status: RecurringSeriesStatus,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request structure for creating a recurring activity series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateRecurringActivityRequest {
+    pub template: ActivityCreateRequest,
+    pub rrule: String,
+    pub start_date: chrono::NaiveDate,
+    #[serde(default)]
+    pub materialize_days_ahead: Option<i64>,
+}
+
+/// Status of a single occurrence within a series
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RecurringOccurrenceStatus {
+    Materialized,
+    Skipped,
+    Overridden,
+}
+
+impl std::fmt::Display for RecurringOccurrenceStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecurringOccurrenceStatus::Materialized => write!(f, "materialized"),
+            RecurringOccurrenceStatus::Skipped => write!(f, "skipped"),
+            RecurringOccurrenceStatus::Overridden => write!(f, "overridden"),
+        }
+    }
+}
+
+impl std::str::FromStr for RecurringOccurrenceStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "materialized" => Ok(RecurringOccurrenceStatus::Materialized),
+            "skipped" => Ok(RecurringOccurrenceStatus::Skipped),
+            "overridden" => Ok(RecurringOccurrenceStatus::Overridden),
+            _ => Err(anyhow::anyhow!("Invalid occurrence status: {}", s)),
+        }
+    }
+}
+
+/// A generated, skipped or individually edited occurrence of a series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecurringOccurrence {
+    pub id: i64,
+    pub series_id: i64,
+    pub occurrence_date: chrono::NaiveDate,
+    pub activity_id: Option<i64>,
+    pub status: RecurringOccurrenceStatus,
+    pub override_data: Option<ActivityUpdateRequest>,
+}
+
+/// A reminder block on an activity whose time has come
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DueReminder {
+    pub activity_id: i64,
+    pub pet_id: i64,
+    pub title: String,
+    pub reminder_type: Option<String>,
+    pub due_at: DateTime<Utc>,
+}
+
+/// Local time span during which a pet's reminders are held back.
+/// `end` before `start` wraps past midnight, e.g. 22:00-07:00.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: chrono::NaiveTime,
+    pub end: chrono::NaiveTime,
+}
+
+impl QuietHours {
+    /// Whether `time` falls inside the quiet hours (start inclusive, end exclusive)
+    pub fn contains(&self, time: chrono::NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// How the notification subsystem treats one pet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NotificationPreferences {
+    pub pet_id: i64,
+    pub reminders_muted: bool,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl NotificationPreferences {
+    /// Defaults for a pet without overrides: everything announced at any time
+    pub fn defaults(pet_id: i64) -> Self {
+        NotificationPreferences {
+            pet_id,
+            reminders_muted: false,
+            quiet_hours: None,
+        }
+    }
+
+    /// Whether a due reminder may be announced at local time `now`
+    pub fn allows_reminder_at(&self, now: chrono::NaiveTime) -> bool {
+        !self.reminders_muted && !self.quiet_hours.is_some_and(|quiet| quiet.contains(now))
+    }
+}
+
+/// New notification preferences of a pet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateNotificationPreferencesRequest {
+    #[serde(default)]
+    pub reminders_muted: bool,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// A health visit whose follow-up date has passed without a later matching visit
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OverdueFollowUp {
+    pub activity_id: i64,
+    pub pet_id: i64,
+    pub title: String,
+    pub subcategory: String,
+    pub visit_date: chrono::NaiveDate,
+    pub follow_up_date: chrono::NaiveDate,
+    pub days_overdue: i64,
+}
+
+/// An activity logged to complete the reminder block of another activity
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReminderCompletion {
+    /// Activity holding the reminder block
+    pub reminder_activity_id: i64,
+    /// Activity logged to do what the reminder asked
+    pub activity_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Whether a scheduled reminder was acted on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReminderComplianceStatus {
+    /// An activity was logged for it
+    Done,
+    /// Its day passed without an activity
+    Skipped,
+    /// Due today or later
+    Upcoming,
+}
+
+/// One reminder in a compliance report
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReminderComplianceItem {
+    pub reminder_activity_id: i64,
+    pub title: String,
+    pub reminder_type: Option<String>,
+    pub due_at: DateTime<Utc>,
+    pub status: ReminderComplianceStatus,
+    /// Activity that completed the reminder
+    pub completed_by: Option<i64>,
+    /// Day that activity took place, which may be before or after the due date
+    pub completed_on: Option<chrono::NaiveDate>,
+}
+
+/// Reminders of a pet due in a date range and whether they were done
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReminderCompliance {
+    pub pet_id: i64,
+    pub start_date: chrono::NaiveDate,
+    pub end_date: chrono::NaiveDate,
+    pub items: Vec<ReminderComplianceItem>,
+    pub done: usize,
+    pub skipped: usize,
+    pub upcoming: usize,
+    /// Share of past reminders that were done (0-1); None when none are past
+    pub completion_rate: Option<f64>,
+}
+
+/// A stored file waiting to be removed after its owning row was deleted
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PendingFileDeletion {
+    pub id: i64,
+    /// Store the file lives in; currently always "photos"
+    pub storage: String,
+    pub file_name: String,
+    pub source_activity_id: Option<i64>,
+    pub attempts: i64,
+    pub last_error: Option<String>,
+}
+
+/// Outcome of processing the file deletion journal
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileCleanupReport {
+    pub deleted: usize,
+    pub failed: usize,
+}
+
+/// Rows pointed at renamed photo files
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PhotoReferenceUpdate {
+    pub pets_updated: i64,
+    pub activities_updated: i64,
+}
+
+/// Category of a stored pet document
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DocumentCategory {
+    Adoption,
+    Pedigree,
+    Insurance,
+    Medical,
+    Registration,
+    Other,
+}
+
+impl std::fmt::Display for DocumentCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentCategory::Adoption => write!(f, "adoption"),
+            DocumentCategory::Pedigree => write!(f, "pedigree"),
+            DocumentCategory::Insurance => write!(f, "insurance"),
+            DocumentCategory::Medical => write!(f, "medical"),
+            DocumentCategory::Registration => write!(f, "registration"),
+            DocumentCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
+impl std::str::FromStr for DocumentCategory {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "adoption" => Ok(DocumentCategory::Adoption),
+            "pedigree" => Ok(DocumentCategory::Pedigree),
+            "insurance" => Ok(DocumentCategory::Insurance),
+            "medical" => Ok(DocumentCategory::Medical),
+            "registration" => Ok(DocumentCategory::Registration),
+            "other" => Ok(DocumentCategory::Other),
+            _ => Err(anyhow::anyhow!("Invalid document category: {}", s)),
+        }
+    }
+}
+
+/// A file stored in a pet's document vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PetDocument {
+    pub id: i64,
+    pub pet_id: i64,
+    pub category: DocumentCategory,
+    pub title: String,
+    pub original_filename: String,
+    /// Filename served by the `documents://` protocol
+    pub stored_filename: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub sha256: String,
+    pub notes: Option<String>,
+    /// Recognized text used by global search
+    pub ocr_text: Option<String>,
+    /// Date the document must be renewed by (insurance, registration, passport)
+    #[serde(default)]
+    pub expires_on: Option<chrono::NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A document that expires soon or has recently expired
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExpiringDocument {
+    pub document: PetDocument,
+    pub pet_name: String,
+    pub expires_on: chrono::NaiveDate,
+    /// Negative once the document has expired
+    pub days_until_expiry: i64,
+}
+
+/// Request structure for recording an uploaded document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePetDocumentRequest {
+    pub pet_id: i64,
+    pub category: DocumentCategory,
+    pub title: String,
+    pub original_filename: String,
+    pub stored_filename: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub sha256: String,
+    pub notes: Option<String>,
+    pub ocr_text: Option<String>,
+    #[serde(default)]
+    pub expires_on: Option<chrono::NaiveDate>,
+}
+
+/// Status of a pet's care checklist
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ChecklistStatus {
+    Active,
+    Completed,
+}
+
+impl std::fmt::Display for ChecklistStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChecklistStatus::Active => write!(f, "active"),
+            ChecklistStatus::Completed => write!(f, "completed"),
+        }
+    }
+}
+
+impl std::str::FromStr for ChecklistStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "active" => Ok(ChecklistStatus::Active),
+            "completed" => Ok(ChecklistStatus::Completed),
+            _ => Err(anyhow::anyhow!("Invalid checklist status: {}", s)),
+        }
+    }
+}
+
+/// A care checklist instantiated for a pet from a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checklist {
+    pub id: i64,
+    pub pet_id: i64,
+    pub template_key: String,
+    pub title: String,
+    pub status: ChecklistStatus,
+    pub start_date: chrono::NaiveDate,
+    pub items: Vec<ChecklistItem>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A single task within a checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub id: i64,
+    pub checklist_id: i64,
+    pub position: i64,
+    pub title: String,
+    pub description: Option<String>,
+    pub due_date: Option<chrono::NaiveDate>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub notes: Option<String>,
+}
+
+/// Where a pet stands on a growth milestone
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MilestoneStatus {
+    Completed,
+    /// The expected age window hasn't started yet
+    Upcoming,
+    /// The pet is within the expected age window
+    Due,
+    /// The expected age window has passed without the milestone being recorded
+    Overdue,
+}
+
+/// A growth milestone on a pet's timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneProgress {
+    pub key: String,
+    pub title: String,
+    pub description: String,
+    pub expected_from: chrono::NaiveDate,
+    pub expected_until: chrono::NaiveDate,
+    pub status: MilestoneStatus,
+    pub completed_on: Option<chrono::NaiveDate>,
+    pub notes: Option<String>,
+}
+
+/// A pet's growth milestones in expected order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneProgressReport {
+    pub pet_id: i64,
+    pub completed: usize,
+    pub total: usize,
+    pub milestones: Vec<MilestoneProgress>,
+}
+
+/// A place aggregated from activity location blocks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FrequentPlace {
+    /// Place name as written in the most recent visit
+    pub name: String,
+    pub address: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub visit_count: i64,
+    pub last_visit: Option<DateTime<Utc>>,
+}
+
+/// A frequently walked area, clustered from the coordinates of walk location blocks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WalkArea {
+    /// Mean position of the walks in the area
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Distance from the centre to the farthest walk, in meters
+    pub radius_m: f64,
+    pub walk_count: i64,
+    /// Place names used for walks in the area, most used first
+    pub place_names: Vec<String>,
+    pub first_walk: DateTime<Utc>,
+    pub last_walk: DateTime<Utc>,
+}
+
+/// Kind of consumable tracked in the inventory
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InventoryKind {
+    Food,
+    Litter,
+    Medication,
+}
+
+impl std::fmt::Display for InventoryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InventoryKind::Food => write!(f, "food"),
+            InventoryKind::Litter => write!(f, "litter"),
+            InventoryKind::Medication => write!(f, "medication"),
+        }
+    }
+}
+
+impl std::str::FromStr for InventoryKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "food" => Ok(InventoryKind::Food),
+            "litter" => Ok(InventoryKind::Litter),
+            "medication" => Ok(InventoryKind::Medication),
+            _ => Err(anyhow::anyhow!("Invalid inventory kind: {}", s)),
+        }
+    }
+}
+
+/// A stocked consumable; `pet_id` is None for supplies shared by all pets
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryItem {
+    pub id: i64,
+    pub pet_id: Option<i64>,
+    pub kind: InventoryKind,
+    pub name: String,
+    pub brand: Option<String>,
+    /// Unit the quantity is counted in (g, kg, ml, l, tablet, ...)
+    pub unit: String,
+    pub quantity: f64,
+    pub low_stock_threshold: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request structure for adding an inventory item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInventoryItemRequest {
+    pub pet_id: Option<i64>,
+    pub kind: InventoryKind,
+    pub name: String,
+    pub brand: Option<String>,
+    pub unit: String,
+    pub quantity: f64,
+    pub low_stock_threshold: f64,
+}
+
+/// Request structure for editing an inventory item; stock changes go through adjustments
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct InventoryItemUpdateRequest {
+    pub name: Option<String>,
+    pub brand: Option<String>,
+    pub unit: Option<String>,
+    pub low_stock_threshold: Option<f64>,
+}
+
+/// Stock level of an item with its recent consumption rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryStatus {
+    pub item: InventoryItem,
+    pub is_low: bool,
+    /// Average consumption per day over the last 30 days, in the item's unit
+    pub daily_usage: Option<f64>,
+    /// Days until the stock runs out at the current rate
+    pub days_remaining: Option<f64>,
+}
+
+/// Measurement watched by a metric rule. Weight, height and temperature are single
+/// readings; food and water intake are totals per day.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricKind {
+    Weight,
+    Height,
+    Temperature,
+    FoodIntake,
+    WaterIntake,
+}
+
+impl MetricKind {
+    pub const ALL: [MetricKind; 5] = [
+        MetricKind::Weight,
+        MetricKind::Height,
+        MetricKind::Temperature,
+        MetricKind::FoodIntake,
+        MetricKind::WaterIntake,
+    ];
+
+    /// Unit values, baselines and absolute thresholds are stored in. Weights are
+    /// shown in the rule's or alert's `display_unit` instead.
+    pub fn unit(self) -> &'static str {
+        match self {
+            MetricKind::Weight => "kg",
+            MetricKind::Height => "cm",
+            MetricKind::Temperature => "C",
+            MetricKind::FoodIntake => "g",
+            MetricKind::WaterIntake => "ml",
+        }
+    }
+
+    /// Whether values are summed per day rather than read one by one
+    pub fn is_daily_total(self) -> bool {
+        matches!(self, MetricKind::FoodIntake | MetricKind::WaterIntake)
+    }
+}
+
+impl std::fmt::Display for MetricKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricKind::Weight => write!(f, "weight"),
+            MetricKind::Height => write!(f, "height"),
+            MetricKind::Temperature => write!(f, "temperature"),
+            MetricKind::FoodIntake => write!(f, "food_intake"),
+            MetricKind::WaterIntake => write!(f, "water_intake"),
+        }
+    }
+}
+
+impl std::str::FromStr for MetricKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "weight" => Ok(MetricKind::Weight),
+            "height" => Ok(MetricKind::Height),
+            "temperature" => Ok(MetricKind::Temperature),
+            "food_intake" => Ok(MetricKind::FoodIntake),
+            "water_intake" => Ok(MetricKind::WaterIntake),
+            _ => Err(anyhow::anyhow!("Invalid metric: {}", s)),
+        }
+    }
+}
+
+/// Direction of change a metric rule alerts on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeDirection {
+    Rise,
+    Drop,
+    Either,
+}
+
+impl std::fmt::Display for ChangeDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeDirection::Rise => write!(f, "rise"),
+            ChangeDirection::Drop => write!(f, "drop"),
+            ChangeDirection::Either => write!(f, "either"),
+        }
+    }
+}
+
+impl std::str::FromStr for ChangeDirection {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "rise" => Ok(ChangeDirection::Rise),
+            "drop" => Ok(ChangeDirection::Drop),
+            "either" => Ok(ChangeDirection::Either),
+            _ => Err(anyhow::anyhow!("Invalid change direction: {}", s)),
+        }
+    }
+}
+
+/// Alert rule on how fast a measurement changes; `pet_id` is None for rules that
+/// apply to every pet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricRule {
+    pub id: i64,
+    pub pet_id: Option<i64>,
+    pub metric: MetricKind,
+    /// Days back from a reading to find the value it is compared with
+    pub window_days: i64,
+    /// Smallest change that raises an alert, in percent or in the metric's unit
+    pub threshold: f64,
+    pub percent: bool,
+    pub direction: ChangeDirection,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Unit of an absolute weight threshold
+    #[serde(default)]
+    pub display_unit: WeightUnit,
+}
+
+impl MetricRule {
+    /// Express an absolute weight threshold in `unit`
+    pub fn with_display_unit(mut self, unit: WeightUnit) -> Self {
+        if self.metric == MetricKind::Weight && !self.percent {
+            self.threshold = unit.from_kg_f64(self.threshold);
+        }
+        self.display_unit = unit;
+        self
+    }
+}
+
+/// Request structure for adding a metric rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMetricRuleRequest {
+    pub pet_id: Option<i64>,
+    pub metric: MetricKind,
+    pub window_days: i64,
+    pub threshold: f64,
+    pub percent: bool,
+    pub direction: ChangeDirection,
+}
+
+/// Request structure for editing a metric rule
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MetricRuleUpdateRequest {
+    pub window_days: Option<i64>,
+    pub threshold: Option<f64>,
+    pub percent: Option<bool>,
+    pub direction: Option<ChangeDirection>,
+    pub enabled: Option<bool>,
+}
+
+/// A change that broke a metric rule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricAlert {
+    pub id: i64,
+    pub rule_id: i64,
+    pub pet_id: i64,
+    /// The reading that broke the rule; None for daily totals
+    pub activity_id: Option<i64>,
+    pub metric: MetricKind,
+    /// Day of the reading, or the day whose total broke the rule
+    pub observed_on: chrono::NaiveDate,
+    /// Earliest reading in the window, or the average daily total before the day
+    pub baseline: f64,
+    pub value: f64,
+    pub change: f64,
+    /// None when the baseline is zero
+    pub change_percent: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    /// Unit of the baseline, value and change of a weight alert
+    #[serde(default)]
+    pub display_unit: WeightUnit,
+}
+
+impl MetricAlert {
+    /// Express the weights of a weight alert in `unit`
+    pub fn with_display_unit(mut self, unit: WeightUnit) -> Self {
+        if self.metric == MetricKind::Weight {
+            self.baseline = unit.from_kg_f64(self.baseline);
+            self.value = unit.from_kg_f64(self.value);
+            self.change = unit.from_kg_f64(self.change);
+        }
+        self.display_unit = unit;
+        self
+    }
+}
+
+/// Kind of value a custom block field holds
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Boolean,
+    /// A `YYYY-MM-DD` date
+    Date,
+    /// One of the field's `options`
+    Choice,
+}
+
+impl std::fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CustomFieldType::Text => write!(f, "text"),
+            CustomFieldType::Number => write!(f, "number"),
+            CustomFieldType::Boolean => write!(f, "boolean"),
+            CustomFieldType::Date => write!(f, "date"),
+            CustomFieldType::Choice => write!(f, "choice"),
+        }
+    }
+}
+
+/// A field of a custom block and the rules its values must follow
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomFieldDefinition {
+    /// Key of the value in a block instance, e.g. `units`
+    pub key: String,
+    pub label: String,
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub required: bool,
+    /// Unit shown next to number fields, e.g. "IU"
+    #[serde(default)]
+    pub unit: Option<String>,
+    /// Bounds of number fields
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    /// Longest text accepted by text fields, in characters
+    #[serde(default)]
+    pub max_length: Option<usize>,
+    /// Values accepted by choice fields
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// A block type defined by the user, such as insulin doses
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomBlockDefinition {
+    pub id: i64,
+    /// Name instances refer to in their `customBlock` field; fixed once created
+    pub name: String,
+    pub label: String,
+    pub fields: Vec<CustomFieldDefinition>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Request structure for defining a custom block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCustomBlockRequest {
+    pub name: String,
+    pub label: String,
+    pub fields: Vec<CustomFieldDefinition>,
+}
+
+/// Request structure for editing a custom block's label or fields
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CustomBlockUpdateRequest {
+    pub label: Option<String>,
+    pub fields: Option<Vec<CustomFieldDefinition>>,
+}
+
+/// Totals of a pet's day, stored in the `summary` block of a daily summary activity.
+/// The day itself is the activity's time block.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DailySummary {
+    pub total_activities: usize,
+    pub meals: usize,
+    pub treats: usize,
+    pub walks: usize,
+    /// Health, growth and expense entries, by title or subcategory
+    pub notable: Vec<String>,
+}
+
+/// Link between an expense and the health activity it paid for
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivityLink {
+    /// The expense activity
+    pub activity_id: i64,
+    /// The health activity it paid for
+    pub related_activity_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// An activity returned together with the activities linked to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityWithRelated {
+    pub activity: ActivityResponse,
+    pub related: Vec<ActivityResponse>,
+}
+
+/// Remembered values of one field (block key or `brand`) for prefilling a template
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SmartDefault {
+    pub field: String,
+    /// Value used most recently
+    pub last_value: serde_json::Value,
+    pub last_used_at: DateTime<Utc>,
+    /// Value used most often, e.g. the preferred brand or usual walk duration
+    pub usual_value: serde_json::Value,
+    pub usual_count: i64,
+    /// Activities that recorded this field
+    pub times_used: i64,
+}
+
+/// Smart defaults of a pet for one subcategory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SmartDefaults {
+    pub pet_id: i64,
+    /// Lowercased subcategory the defaults were recorded under
+    pub subcategory: String,
+    pub fields: Vec<SmartDefault>,
+}
+
+/// Which pet's profile values win when a merged pet and the pet it is merged into disagree.
+/// Fields set on only one of the two pets are always kept.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum PetMergeStrategy {
+    #[default]
+    KeepTarget,
+    KeepSource,
+}
+
+/// A profile field set differently on the two merged pets
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PetFieldConflict {
+    pub field: String,
+    pub source_value: String,
+    pub target_value: String,
+    pub resolved_value: String,
+}
+
+/// Outcome of merging a duplicate pet into another one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PetMergeReport {
+    pub source_id: i64,
+    pub target_id: i64,
+    /// Nothing was written; the counts show what a merge would do
+    pub dry_run: bool,
+    pub activities_moved: i64,
+    pub documents_moved: i64,
+    /// Source documents dropped because the target already has the same file
+    pub documents_deduplicated: i64,
+    pub recurring_series_moved: i64,
+    pub checklists_moved: i64,
+    pub inventory_items_moved: i64,
+    pub conflicts: Vec<PetFieldConflict>,
+    /// Target pet with the merged profile
+    pub pet: Pet,
+}
@@ -0,0 +1,156 @@
+use super::models::*;
+use crate::errors::ActivityError;
+use chrono::{NaiveTime, Utc};
+use sqlx::Row;
+use std::collections::HashMap;
+
+/// Format quiet hour boundaries are stored in
+const QUIET_HOURS_FORMAT: &str = "%H:%M";
+
+impl super::PetDatabase {
+    /// Notification preferences of a pet, the defaults when it has no overrides
+    pub async fn get_notification_preferences(
+        &self,
+        pet_id: i64,
+    ) -> Result<NotificationPreferences, ActivityError> {
+        let row = sqlx::query("SELECT * FROM pet_notification_preferences WHERE pet_id = ?")
+            .bind(pet_id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(row
+            .map(|row| row_to_preferences(&row))
+            .unwrap_or_else(|| NotificationPreferences::defaults(pet_id)))
+    }
+
+    /// Preferences of every pet with overrides, keyed by pet ID
+    pub async fn get_all_notification_preferences(
+        &self,
+    ) -> Result<HashMap<i64, NotificationPreferences>, ActivityError> {
+        let rows = sqlx::query("SELECT * FROM pet_notification_preferences")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .map(row_to_preferences)
+            .map(|preferences| (preferences.pet_id, preferences))
+            .collect())
+    }
+
+    /// Replace the notification preferences of a pet
+    pub async fn update_notification_preferences(
+        &self,
+        pet_id: i64,
+        request: UpdateNotificationPreferencesRequest,
+    ) -> Result<NotificationPreferences, ActivityError> {
+        let format = |time: NaiveTime| time.format(QUIET_HOURS_FORMAT).to_string();
+        sqlx::query(
+            r#"
+            INSERT INTO pet_notification_preferences
+                (pet_id, reminders_muted, quiet_hours_start, quiet_hours_end, updated_at)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (pet_id) DO UPDATE SET
+                reminders_muted = excluded.reminders_muted,
+                quiet_hours_start = excluded.quiet_hours_start,
+                quiet_hours_end = excluded.quiet_hours_end,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(pet_id)
+        .bind(request.reminders_muted)
+        .bind(request.quiet_hours.map(|quiet| format(quiet.start)))
+        .bind(request.quiet_hours.map(|quiet| format(quiet.end)))
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::info!(
+            "[DB] update_notification_preferences: pet_id={pet_id}, reminders_muted={}, quiet_hours={:?}",
+            request.reminders_muted,
+            request.quiet_hours
+        );
+        self.get_notification_preferences(pet_id).await
+    }
+}
+
+fn row_to_preferences(row: &sqlx::sqlite::SqliteRow) -> NotificationPreferences {
+    let time = |column: &str| {
+        row.try_get::<Option<String>, _>(column)
+            .ok()
+            .flatten()
+            .and_then(|time| NaiveTime::parse_from_str(&time, QUIET_HOURS_FORMAT).ok())
+    };
+    let quiet_hours = match (time("quiet_hours_start"), time("quiet_hours_end")) {
+        (Some(start), Some(end)) => Some(QuietHours { start, end }),
+        _ => None,
+    };
+
+    NotificationPreferences {
+        pet_id: row.get("pet_id"),
+        reminders_muted: row.try_get("reminders_muted").unwrap_or(false),
+        quiet_hours,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_notification_preferences_round_trip() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (pet_id, other_pet_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+
+        assert_eq!(
+            db.get_notification_preferences(pet_id).await.unwrap(),
+            NotificationPreferences::defaults(pet_id)
+        );
+
+        let quiet_hours = QuietHours {
+            start: at(22, 0),
+            end: at(7, 30),
+        };
+        let preferences = db
+            .update_notification_preferences(
+                pet_id,
+                UpdateNotificationPreferencesRequest {
+                    reminders_muted: false,
+                    quiet_hours: Some(quiet_hours),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(preferences.quiet_hours, Some(quiet_hours));
+        db.update_notification_preferences(
+            other_pet_id,
+            UpdateNotificationPreferencesRequest {
+                reminders_muted: true,
+                quiet_hours: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        let all = db.get_all_notification_preferences().await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert!(all[&pet_id].allows_reminder_at(at(12, 0)));
+        assert!(!all[&pet_id].allows_reminder_at(at(23, 15)));
+        assert!(!all[&pet_id].allows_reminder_at(at(6, 0)));
+        assert!(all[&pet_id].allows_reminder_at(at(7, 30)));
+        assert!(!all[&other_pet_id].allows_reminder_at(at(12, 0)));
+    }
+}
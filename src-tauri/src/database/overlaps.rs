@@ -0,0 +1,236 @@
+use super::activity_data::ActivityDataExt;
+use super::models::{Activity, ExportActivitiesRequest};
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// When a time-bound activity (boarding, sitter visit, medication window) ran
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivityTimeRange {
+    pub activity_id: i64,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+/// Two activities of a pet with the same subcategory whose time ranges intersect
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivityOverlap {
+    pub pet_id: i64,
+    pub subcategory: String,
+    /// The activity that starts first
+    pub first: ActivityTimeRange,
+    pub second: ActivityTimeRange,
+    pub overlap_start: DateTime<Utc>,
+    pub overlap_end: DateTime<Utc>,
+}
+
+impl super::PetDatabase {
+    /// A pet's activities of `subcategory` whose time range intersects `start..end`,
+    /// leaving out `exclude_id` (the activity being edited). Ranges that only touch
+    /// don't overlap, and activities waiting for review aren't counted.
+    pub async fn find_overlapping_activities(
+        &self,
+        pet_id: i64,
+        subcategory: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        exclude_id: Option<i64>,
+    ) -> Result<Vec<ActivityTimeRange>, ActivityError> {
+        let subcategory = subcategory_key(subcategory);
+        let mut overlapping: Vec<ActivityTimeRange> = self
+            .time_bound_activities(Some(pet_id))
+            .await?
+            .into_iter()
+            .filter(|(activity, range)| {
+                Some(activity.id) != exclude_id
+                    && subcategory_key(&activity.subcategory) == subcategory
+                    && range.start < end
+                    && start < range.end
+            })
+            .map(|(_, range)| range)
+            .collect();
+        overlapping.sort_by_key(|range| (range.start, range.activity_id));
+        Ok(overlapping)
+    }
+
+    /// Pairs of intersecting time-bound activities of the same subcategory, for
+    /// one pet or every pet with None, ordered by when the overlap starts
+    pub async fn get_activity_overlaps(
+        &self,
+        pet_id: Option<i64>,
+    ) -> Result<Vec<ActivityOverlap>, ActivityError> {
+        let mut groups: BTreeMap<(i64, String), Vec<(Activity, ActivityTimeRange)>> =
+            BTreeMap::new();
+        for (activity, range) in self.time_bound_activities(pet_id).await? {
+            groups
+                .entry((activity.pet_id, subcategory_key(&activity.subcategory)))
+                .or_default()
+                .push((activity, range));
+        }
+
+        let mut overlaps = Vec::new();
+        for ((pet_id, _), mut group) in groups {
+            group.sort_by_key(|(_, range)| (range.start, range.activity_id));
+            for (i, (first_activity, first)) in group.iter().enumerate() {
+                // Sorted by start, so later activities starting after this one ends can't overlap it
+                for (_, second) in group[i + 1..]
+                    .iter()
+                    .take_while(|(_, second)| second.start < first.end)
+                {
+                    overlaps.push(ActivityOverlap {
+                        pet_id,
+                        subcategory: first_activity.subcategory.clone(),
+                        first: first.clone(),
+                        second: second.clone(),
+                        overlap_start: second.start,
+                        overlap_end: first.end.min(second.end),
+                    });
+                }
+            }
+        }
+        overlaps.sort_by_key(|overlap| {
+            (
+                overlap.overlap_start,
+                overlap.first.activity_id,
+                overlap.second.activity_id,
+            )
+        });
+        log::debug!(
+            "[DB] get_activity_overlaps: pet_id={pet_id:?}, overlaps={}",
+            overlaps.len()
+        );
+        Ok(overlaps)
+    }
+
+    /// Confirmed activities that have a time range
+    async fn time_bound_activities(
+        &self,
+        pet_id: Option<i64>,
+    ) -> Result<Vec<(Activity, ActivityTimeRange)>, ActivityError> {
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id,
+                format: None,
+            })
+            .await?;
+        Ok(activities
+            .into_iter()
+            .filter(|activity| !activity.needs_review)
+            .filter_map(|activity| {
+                let (start, end) = activity.activity_data.as_ref()?.extract_time_range()?;
+                let range = ActivityTimeRange {
+                    activity_id: activity.id,
+                    start,
+                    end,
+                };
+                Some((activity, range))
+            })
+            .collect())
+    }
+}
+
+/// Subcategories are compared ignoring case and surrounding whitespace
+fn subcategory_key(subcategory: &str) -> String {
+    subcategory.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_overlapping_time_blocks() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (pet_id, other_pet_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+
+        let boarding =
+            |pet_id: i64, subcategory: &str, start: &str, end: &str| ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Lifestyle,
+                subcategory: subcategory.to_string(),
+                activity_data: Some(serde_json::json!({
+                    "time": { "date": start, "time": "", "timezone": "" },
+                    "timer": { "type": "start_end", "startTime": start, "endTime": end }
+                })),
+                needs_review: false,
+            };
+        let first = db
+            .create_activity(boarding(
+                pet_id,
+                "Boarding",
+                "2025-03-01T09:00:00Z",
+                "2025-03-05T18:00:00Z",
+            ))
+            .await
+            .unwrap();
+        let second = db
+            .create_activity(boarding(
+                pet_id,
+                "boarding ",
+                "2025-03-04T09:00:00Z",
+                "2025-03-08T18:00:00Z",
+            ))
+            .await
+            .unwrap();
+        // Back to back, a different subcategory, another pet: none of them overlap
+        for request in [
+            boarding(
+                pet_id,
+                "Boarding",
+                "2025-03-08T18:00:00Z",
+                "2025-03-09T18:00:00Z",
+            ),
+            boarding(
+                pet_id,
+                "Sitter Visit",
+                "2025-03-02T09:00:00Z",
+                "2025-03-02T10:00:00Z",
+            ),
+            boarding(
+                other_pet_id,
+                "Boarding",
+                "2025-03-02T09:00:00Z",
+                "2025-03-03T10:00:00Z",
+            ),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let at = |day: u32, hour: u32| Utc.with_ymd_and_hms(2025, 3, day, hour, 0, 0).unwrap();
+        let found = db
+            .find_overlapping_activities(pet_id, "BOARDING", at(5, 12), at(6, 12), None)
+            .await
+            .unwrap();
+        let ids: Vec<i64> = found.iter().map(|range| range.activity_id).collect();
+        assert_eq!(ids, vec![first.id, second.id]);
+        let found = db
+            .find_overlapping_activities(pet_id, "Boarding", at(5, 12), at(6, 12), Some(first.id))
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+
+        let overlaps = db.get_activity_overlaps(None).await.unwrap();
+        assert_eq!(overlaps.len(), 1, "{overlaps:?}");
+        assert_eq!(overlaps[0].first.activity_id, first.id);
+        assert_eq!(overlaps[0].second.activity_id, second.id);
+        assert_eq!(
+            (overlaps[0].overlap_start, overlaps[0].overlap_end),
+            (at(4, 9), at(5, 18))
+        );
+        assert!(db
+            .get_activity_overlaps(Some(other_pet_id))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::errors::PetError;
+use sqlx::Row;
+use std::collections::HashMap;
+
+impl super::PetDatabase {
+    /// Alternate names of a pet, in the order they were added
+    pub async fn get_pet_aliases(&self, pet_id: i64) -> Result<Vec<String>, PetError> {
+        let rows = sqlx::query("SELECT alias FROM pet_aliases WHERE pet_id = ? ORDER BY id")
+            .bind(pet_id)
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        Ok(rows.iter().map(|row| row.get("alias")).collect())
+    }
+
+    /// Alternate names of every pet that has any, keyed by pet ID
+    pub async fn get_all_pet_aliases(&self) -> Result<HashMap<i64, Vec<String>>, PetError> {
+        let rows = sqlx::query("SELECT pet_id, alias FROM pet_aliases ORDER BY pet_id, id")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        let mut aliases: HashMap<i64, Vec<String>> = HashMap::new();
+        for row in &rows {
+            aliases
+                .entry(row.get("pet_id"))
+                .or_default()
+                .push(row.get("alias"));
+        }
+        Ok(aliases)
+    }
+
+    /// Replace the alternate names of a pet. Aliases are trimmed and repeats
+    /// (ignoring case) are dropped; returns the stored list.
+    pub async fn set_pet_aliases(
+        &self,
+        pet_id: i64,
+        aliases: Vec<String>,
+    ) -> Result<Vec<String>, PetError> {
+        let aliases = normalize_aliases(aliases);
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        sqlx::query("DELETE FROM pet_aliases WHERE pet_id = ?")
+            .bind(pet_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+        for alias in &aliases {
+            sqlx::query("INSERT INTO pet_aliases (pet_id, alias) VALUES (?, ?)")
+                .bind(pet_id)
+                .bind(alias)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| PetError::database(e.to_string()))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| PetError::database(e.to_string()))?;
+
+        log::info!(
+            "[DB] set_pet_aliases: pet_id={pet_id}, aliases={}",
+            aliases.len()
+        );
+        Ok(aliases)
+    }
+}
+
+/// Trim aliases and drop empty ones and case-insensitive repeats, keeping the first spelling
+fn normalize_aliases(aliases: Vec<String>) -> Vec<String> {
+    let mut seen = Vec::new();
+    aliases
+        .into_iter()
+        .map(|alias| alias.trim().to_string())
+        .filter(|alias| {
+            let key = alias.to_lowercase();
+            if alias.is_empty() || seen.contains(&key) {
+                return false;
+            }
+            seen.push(key);
+            true
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[test]
+    fn test_normalize_aliases() {
+        let aliases = normalize_aliases(vec![
+            " 豆豆 ".to_string(),
+            "Doudou".to_string(),
+            "doudou".to_string(),
+            "".to_string(),
+            "豆豆".to_string(),
+        ]);
+        assert_eq!(aliases, vec!["豆豆", "Doudou"]);
+    }
+
+    #[tokio::test]
+    async fn test_pet_aliases_round_trip() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (pet_id, other_pet_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+
+        let stored = db
+            .set_pet_aliases(pet_id, vec!["豆豆".to_string(), "Bean".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(stored, vec!["豆豆", "Bean"]);
+        db.set_pet_aliases(other_pet_id, vec!["Bean".to_string()])
+            .await
+            .unwrap();
+
+        // Replacing drops aliases that are no longer listed
+        db.set_pet_aliases(pet_id, vec!["小豆".to_string(), "豆豆".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            db.get_pet_aliases(pet_id).await.unwrap(),
+            vec!["小豆", "豆豆"]
+        );
+
+        let all = db.get_all_pet_aliases().await.unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[&other_pet_id], vec!["Bean"]);
+
+        db.set_pet_aliases(pet_id, Vec::new()).await.unwrap();
+        assert!(db.get_pet_aliases(pet_id).await.unwrap().is_empty());
+    }
+}
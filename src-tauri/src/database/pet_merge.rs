@@ -0,0 +1,298 @@
+use super::models::*;
+use anyhow::Result;
+use chrono::Utc;
+
+impl super::PetDatabase {
+    /// Merge a duplicate pet into `target_id`: its activities (with their attachments and
+    /// reminders), documents, recurring series, checklists, inventory, smart defaults and
+    /// aliases move to the target, profile conflicts are resolved with `strategy`, and the
+    /// source pet is archived. Runs in one transaction; `dry_run` rolls it back after counting.
+    pub async fn merge_pets(
+        &self,
+        source_id: i64,
+        target_id: i64,
+        strategy: PetMergeStrategy,
+        dry_run: bool,
+    ) -> Result<PetMergeReport> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let source_row = sqlx::query("SELECT * FROM pets WHERE id = ?")
+            .bind(source_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let source = self.row_to_pet(&source_row).await?;
+        let target_row = sqlx::query("SELECT * FROM pets WHERE id = ?")
+            .bind(target_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let target = self.row_to_pet(&target_row).await?;
+
+        let mut conflicts = Vec::new();
+        let mut resolve = |field: &str, source: Option<String>, target: Option<String>| {
+            resolve_field(field, source, target, strategy, &mut conflicts)
+        };
+        let name = resolve("name", Some(source.name), Some(target.name));
+        let birth_date = resolve(
+            "birth_date",
+            Some(source.birth_date.format("%Y-%m-%d").to_string()),
+            Some(target.birth_date.format("%Y-%m-%d").to_string()),
+        );
+        let species = resolve(
+            "species",
+            Some(source.species.to_string()),
+            Some(target.species.to_string()),
+        );
+        let gender = resolve(
+            "gender",
+            Some(source.gender.to_string()),
+            Some(target.gender.to_string()),
+        );
+        let breed = resolve("breed", source.breed, target.breed);
+        let color = resolve("color", source.color, target.color);
+        let weight_kg = resolve(
+            "weight_kg",
+            source.weight_kg.map(|weight| weight.to_string()),
+            target.weight_kg.map(|weight| weight.to_string()),
+        );
+        let photo_path = resolve("photo_path", source.photo_path, target.photo_path);
+        let notes = resolve("notes", source.notes, target.notes);
+
+        sqlx::query(
+            r#"
+            UPDATE pets SET name = ?, birth_date = ?, species = ?, gender = ?, breed = ?,
+                color = ?, weight_kg = ?, photo_path = ?, notes = ?, updated_at = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(name)
+        .bind(birth_date)
+        .bind(species)
+        .bind(gender)
+        .bind(breed)
+        .bind(color)
+        .bind(weight_kg.and_then(|weight| weight.parse::<f32>().ok()))
+        .bind(photo_path)
+        .bind(notes)
+        .bind(now)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // The target can't hold the same file twice; both rows point at the same stored file
+        let documents_deduplicated = sqlx::query(
+            r#"
+            DELETE FROM pet_documents
+            WHERE pet_id = ? AND sha256 IN (SELECT sha256 FROM pet_documents WHERE pet_id = ?)
+            "#,
+        )
+        .bind(source_id)
+        .bind(target_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+
+        let mut moved = [0; 5];
+        let tables = [
+            "activities",
+            "pet_documents",
+            "recurring_series",
+            "checklists",
+            "inventory_items",
+        ];
+        for (count, table) in moved.iter_mut().zip(tables) {
+            *count = sqlx::query(&format!("UPDATE {table} SET pet_id = ? WHERE pet_id = ?"))
+                .bind(target_id)
+                .bind(source_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected() as i64;
+        }
+        let [activities_moved, documents_moved, recurring_series_moved, checklists_moved, inventory_items_moved] =
+            moved;
+
+        sqlx::query(
+            r#"
+            INSERT INTO pet_defaults (pet_id, subcategory, field, value, use_count, last_used_at)
+            SELECT ?, subcategory, field, value, use_count, last_used_at
+            FROM pet_defaults WHERE pet_id = ?
+            ON CONFLICT (pet_id, subcategory, field, value) DO UPDATE SET
+                use_count = use_count + excluded.use_count,
+                last_used_at = max(last_used_at, excluded.last_used_at)
+            "#,
+        )
+        .bind(target_id)
+        .bind(source_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM pet_defaults WHERE pet_id = ?")
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Milestones the target already reached keep the target's record
+        sqlx::query("UPDATE OR IGNORE pet_milestones SET pet_id = ? WHERE pet_id = ?")
+            .bind(target_id)
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // Aliases the target already has are dropped with the source pet
+        sqlx::query("UPDATE OR IGNORE pet_aliases SET pet_id = ? WHERE pet_id = ?")
+            .bind(target_id)
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE pets SET is_archived = 1, updated_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(source_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let merged_row = sqlx::query("SELECT * FROM pets WHERE id = ?")
+            .bind(target_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        let pet = self.row_to_pet(&merged_row).await?;
+
+        if dry_run {
+            tx.rollback().await?;
+        } else {
+            tx.commit().await?;
+        }
+
+        log::info!(
+            "[DB] merge_pets: source_id={source_id}, target_id={target_id}, dry_run={dry_run}, activities_moved={activities_moved}, documents_moved={documents_moved}, conflicts={}",
+            conflicts.len()
+        );
+        Ok(PetMergeReport {
+            source_id,
+            target_id,
+            dry_run,
+            activities_moved,
+            documents_moved,
+            documents_deduplicated,
+            recurring_series_moved,
+            checklists_moved,
+            inventory_items_moved,
+            conflicts,
+            pet,
+        })
+    }
+}
+
+/// Pick the merged value of a profile field, recording a conflict when both pets set it differently
+fn resolve_field(
+    field: &str,
+    source: Option<String>,
+    target: Option<String>,
+    strategy: PetMergeStrategy,
+    conflicts: &mut Vec<PetFieldConflict>,
+) -> Option<String> {
+    match (source, target) {
+        (Some(source), Some(target)) if source != target => {
+            let resolved = match strategy {
+                PetMergeStrategy::KeepTarget => target.clone(),
+                PetMergeStrategy::KeepSource => source.clone(),
+            };
+            conflicts.push(PetFieldConflict {
+                field: field.to_string(),
+                source_value: source,
+                target_value: target,
+                resolved_value: resolved.clone(),
+            });
+            Some(resolved)
+        }
+        (source, target) => target.or(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_merge_pets() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 3,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (source_id, target_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+        db.update_pet(
+            source_id,
+            UpdatePetRequest {
+                color: Some("Tabby".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        for (pet_id, sha256) in [(source_id, "a"), (source_id, "b"), (target_id, "a")] {
+            db.create_pet_document(CreatePetDocumentRequest {
+                pet_id,
+                category: DocumentCategory::Medical,
+                title: format!("Record {sha256}"),
+                original_filename: format!("{sha256}.pdf"),
+                stored_filename: format!("{sha256}.pdf"),
+                mime_type: "application/pdf".to_string(),
+                file_size: 1,
+                sha256: sha256.to_string(),
+                notes: None,
+                ocr_text: None,
+                expires_on: None,
+            })
+            .await
+            .unwrap();
+        }
+
+        let preview = db
+            .merge_pets(source_id, target_id, PetMergeStrategy::KeepTarget, true)
+            .await
+            .unwrap();
+        assert!(preview.dry_run);
+        assert_eq!(preview.activities_moved, 3);
+        assert_eq!(preview.documents_moved, 1);
+        assert_eq!(preview.documents_deduplicated, 1);
+        assert_eq!(preview.pet.color.as_deref(), Some("Tabby"));
+        assert!(preview.conflicts.iter().any(|c| c.field == "name"));
+        // Nothing was written
+        assert!(!db.get_pet_by_id(source_id).await.unwrap().is_archived);
+        assert_eq!(
+            db.get_pet_documents(source_id, None).await.unwrap().len(),
+            2
+        );
+
+        let target = db.get_pet_by_id(target_id).await.unwrap();
+        let source = db.get_pet_by_id(source_id).await.unwrap();
+        let report = db
+            .merge_pets(source_id, target_id, PetMergeStrategy::KeepSource, false)
+            .await
+            .unwrap();
+        assert_eq!(report.pet.name, source.name);
+        let name_conflict = report.conflicts.iter().find(|c| c.field == "name").unwrap();
+        assert_eq!(name_conflict.target_value, target.name);
+        assert_eq!(name_conflict.resolved_value, source.name);
+
+        assert!(db.get_pet_by_id(source_id).await.unwrap().is_archived);
+        assert!(db
+            .get_pet_documents(source_id, None)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.get_pet_documents(target_id, None).await.unwrap().len(),
+            2
+        );
+        let moved: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM activities WHERE pet_id = ?")
+            .bind(target_id)
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(moved, 6);
+    }
+}
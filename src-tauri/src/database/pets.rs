@@ -1,4 +1,6 @@
+use super::activity_data::ActivityDataExt;
 use super::models::*;
+use super::query::UpdateQuery;
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use sqlx::Row;
@@ -41,7 +43,7 @@ impl super::PetDatabase {
             "SELECT * FROM pets WHERE is_archived = 0 ORDER BY display_order ASC, created_at DESC"
         };
 
-        let rows = sqlx::query(query).fetch_all(&self.pool).await?;
+        let rows = sqlx::query(query).fetch_all(&self.read_pool).await?;
 
         let mut pets = Vec::new();
         for row in rows {
@@ -55,7 +57,7 @@ impl super::PetDatabase {
     pub async fn get_pet_by_id(&self, id: i64) -> Result<Pet> {
         let row = sqlx::query("SELECT * FROM pets WHERE id = ?")
             .bind(id)
-            .fetch_one(&self.pool)
+            .fetch_one(&self.read_pool)
             .await?;
 
         self.row_to_pet(&row).await
@@ -65,87 +67,26 @@ impl super::PetDatabase {
     pub async fn update_pet(&self, id: i64, pet_data: UpdatePetRequest) -> Result<Pet> {
         let now = Utc::now();
 
-        // Build dynamic update query
-        let mut updates = Vec::new();
-        let mut params = Vec::new();
-
-        if let Some(name) = &pet_data.name {
-            updates.push("name = ?");
-            params.push(name.clone());
-        }
-        if let Some(birth_date) = pet_data.birth_date {
-            updates.push("birth_date = ?");
-            params.push(birth_date.format("%Y-%m-%d").to_string());
-        }
-        if let Some(species) = &pet_data.species {
-            updates.push("species = ?");
-            params.push(species.to_string());
-        }
-        if let Some(gender) = &pet_data.gender {
-            updates.push("gender = ?");
-            params.push(gender.to_string());
-        }
-        if pet_data.breed.is_some() {
-            updates.push("breed = ?");
-            params.push(pet_data.breed.clone().unwrap_or_default());
-        }
-        if pet_data.color.is_some() {
-            updates.push("color = ?");
-            params.push(pet_data.color.clone().unwrap_or_default());
-        }
-        if let Some(weight_kg) = pet_data.weight_kg {
-            updates.push("weight_kg = ?");
-            params.push(weight_kg.to_string());
-        }
-        if pet_data.photo_path.is_some() {
-            updates.push("photo_path = ?");
-            params.push(pet_data.photo_path.clone().unwrap_or_default());
-        }
-        if pet_data.notes.is_some() {
-            updates.push("notes = ?");
-            params.push(pet_data.notes.clone().unwrap_or_default());
-        }
-
-        if !updates.is_empty() {
-            // Build proper dynamic query with all field bindings
-            let query_sql = format!(
-                "UPDATE pets SET {}, updated_at = ? WHERE id = ?",
-                updates.join(", ")
-            );
-
-            let mut query = sqlx::query(&query_sql);
-
-            // Add bindings in the same order as updates
-            if let Some(name) = pet_data.name {
-                query = query.bind(name);
-            }
-            if let Some(birth_date) = pet_data.birth_date {
-                query = query.bind(birth_date.format("%Y-%m-%d").to_string());
-            }
-            if let Some(species) = pet_data.species {
-                query = query.bind(species.to_string());
-            }
-            if let Some(gender) = pet_data.gender {
-                query = query.bind(gender.to_string());
-            }
-            if pet_data.breed.is_some() {
-                query = query.bind(pet_data.breed.unwrap_or_default());
-            }
-            if pet_data.color.is_some() {
-                query = query.bind(pet_data.color.unwrap_or_default());
-            }
-            if let Some(weight_kg) = pet_data.weight_kg {
-                query = query.bind(weight_kg);
-            }
-            if pet_data.photo_path.is_some() {
-                query = query.bind(pet_data.photo_path.unwrap_or_default());
-            }
-            if pet_data.notes.is_some() {
-                query = query.bind(pet_data.notes.unwrap_or_default());
-            }
-
-            query = query.bind(now).bind(id);
-            query.execute(&self.pool).await?;
+        let mut update = UpdateQuery::new("pets");
+        update
+            .set_if_some("name", pet_data.name)
+            .set_if_some(
+                "birth_date",
+                pet_data
+                    .birth_date
+                    .map(|d| d.format("%Y-%m-%d").to_string()),
+            )
+            .set_if_some("species", pet_data.species.map(|s| s.to_string()))
+            .set_if_some("gender", pet_data.gender.map(|g| g.to_string()))
+            .set_if_some("breed", pet_data.breed)
+            .set_if_some("color", pet_data.color)
+            .set_if_some("weight_kg", pet_data.weight_kg)
+            .set_if_some("photo_path", pet_data.photo_path)
+            .set_if_some("notes", pet_data.notes);
+
+        if update.has_changes() {
+            update.set("updated_at", now);
+            update.where_id(id).build().execute(&self.pool).await?;
         }
 
         self.get_pet_by_id(id).await
@@ -180,18 +121,40 @@ impl super::PetDatabase {
         Ok(())
     }
 
+    /// Get a pet's weight measurements from growth activities, oldest first
+    pub async fn get_weight_history(&self, pet_id: i64, unit: WeightUnit) -> Result<WeightHistory> {
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id: Some(pet_id),
+                format: None,
+            })
+            .await?;
+
+        let records = weight_records(&activities, unit);
+
+        log::debug!(
+            "[DB] get_weight_history: pet_id={pet_id}, records={}, unit={unit}",
+            records.len()
+        );
+        Ok(WeightHistory {
+            pet_id,
+            display_unit: unit,
+            records,
+        })
+    }
+
     /// Helper method to get the next display order
     async fn get_next_display_order(&self) -> Result<i64> {
         let row =
             sqlx::query("SELECT COALESCE(MAX(display_order), -1) + 1 as next_order FROM pets")
-                .fetch_one(&self.pool)
+                .fetch_one(&self.read_pool)
                 .await?;
 
         Ok(row.try_get("next_order").unwrap_or(0))
     }
 
     /// Helper method to convert database row to Pet struct
-    async fn row_to_pet(&self, row: &sqlx::sqlite::SqliteRow) -> Result<Pet> {
+    pub(super) async fn row_to_pet(&self, row: &sqlx::sqlite::SqliteRow) -> Result<Pet> {
         let birth_date_str: String = row.try_get("birth_date")?;
         let birth_date = chrono::NaiveDate::parse_from_str(&birth_date_str, "%Y-%m-%d")
             .map_err(|_| anyhow::anyhow!("Invalid birth_date format"))?;
@@ -202,6 +165,12 @@ impl super::PetDatabase {
         let gender_str: String = row.try_get("gender")?;
         let gender = gender_str.parse::<PetGender>()?;
 
+        let passed_away_on = row
+            .try_get::<Option<String>, _>("passed_away_on")?
+            .map(|date| chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d"))
+            .transpose()
+            .map_err(|_| anyhow::anyhow!("Invalid passed_away_on format"))?;
+
         let created_at: DateTime<Utc> = row.try_get("created_at")?;
         let updated_at: DateTime<Utc> = row.try_get("updated_at")?;
 
@@ -218,8 +187,31 @@ impl super::PetDatabase {
             notes: row.try_get("notes")?,
             display_order: row.try_get("display_order")?,
             is_archived: row.try_get("is_archived")?,
+            is_memorial: row.try_get("is_memorial")?,
+            passed_away_on,
             created_at,
             updated_at,
-        })
+            display_weight: None,
+            display_unit: WeightUnit::Kg,
+        }
+        .with_display_unit(WeightUnit::Kg))
     }
 }
+
+/// Weight measurements among `activities`, oldest first
+pub(super) fn weight_records(activities: &[Activity], unit: WeightUnit) -> Vec<WeightRecord> {
+    let mut records: Vec<WeightRecord> = activities
+        .iter()
+        .filter_map(|activity| {
+            let weight_kg = activity.activity_data.as_ref()?.extract_weight_kg()?;
+            Some(WeightRecord {
+                activity_id: activity.id,
+                date: activity.occurred_on(),
+                weight_kg,
+                value: unit.from_kg(weight_kg),
+            })
+        })
+        .collect();
+    records.sort_by_key(|record| (record.date, record.activity_id));
+    records
+}
@@ -0,0 +1,189 @@
+use super::models::*;
+use super::query::escape_like;
+use crate::errors::ActivityError;
+use crate::photo::PhotoRename;
+use sqlx::Row;
+use std::collections::HashMap;
+
+impl super::PetDatabase {
+    /// Point pet photos and activity attachments at the new names of renamed photo files
+    /// and keep each old name as a redirect, all in one transaction
+    pub async fn apply_photo_renames(
+        &self,
+        renames: &[PhotoRename],
+    ) -> Result<PhotoReferenceUpdate, ActivityError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let mut update = PhotoReferenceUpdate::default();
+
+        for rename in renames {
+            update.pets_updated +=
+                sqlx::query("UPDATE pets SET photo_path = ? WHERE photo_path = ?")
+                    .bind(&rename.new_name)
+                    .bind(&rename.old_name)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+                    .rows_affected() as i64;
+
+            let rows = sqlx::query(
+                "SELECT id, activity_data FROM activities WHERE activity_data LIKE ? ESCAPE '\\'",
+            )
+            .bind(format!("%{}%", escape_like(&rename.old_name)))
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+            for row in rows {
+                let json: String = row.get("activity_data");
+                let Ok(mut data) = serde_json::from_str::<serde_json::Value>(&json) else {
+                    continue;
+                };
+                if !replace_photo_reference(&mut data, &rename.old_name, &rename.new_name) {
+                    continue;
+                }
+                sqlx::query("UPDATE activities SET activity_data = ? WHERE id = ?")
+                    .bind(data.to_string())
+                    .bind(row.get::<i64, _>("id"))
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+                update.activities_updated += 1;
+            }
+
+            sqlx::query(
+                r#"
+                INSERT INTO photo_redirects (old_name, new_name) VALUES (?, ?)
+                ON CONFLICT (old_name) DO UPDATE SET new_name = excluded.new_name
+                "#,
+            )
+            .bind(&rename.old_name)
+            .bind(&rename.new_name)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::info!(
+            "[DB] apply_photo_renames: renamed={}, pets_updated={}, activities_updated={}",
+            renames.len(),
+            update.pets_updated,
+            update.activities_updated
+        );
+        Ok(update)
+    }
+
+    /// Old photo names with the names they were renamed to
+    pub async fn get_photo_redirects(&self) -> Result<HashMap<String, String>, ActivityError> {
+        let rows = sqlx::query("SELECT old_name, new_name FROM photo_redirects")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row.get("old_name"), row.get("new_name")))
+            .collect())
+    }
+}
+
+/// Replace references to `old_name` (bare or as the last segment of a photos:// URL)
+/// anywhere in the activity data. Returns whether anything changed.
+fn replace_photo_reference(value: &mut serde_json::Value, old_name: &str, new_name: &str) -> bool {
+    match value {
+        serde_json::Value::String(reference) => {
+            let Some(prefix) = reference.strip_suffix(old_name) else {
+                return false;
+            };
+            if !(prefix.is_empty() || prefix.ends_with('/')) {
+                return false;
+            }
+            *reference = format!("{prefix}{new_name}");
+            true
+        }
+        serde_json::Value::Array(items) => items.iter_mut().fold(false, |changed, item| {
+            replace_photo_reference(item, old_name, new_name) | changed
+        }),
+        serde_json::Value::Object(map) => map.values_mut().fold(false, |changed, item| {
+            replace_photo_reference(item, old_name, new_name) | changed
+        }),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_apply_photo_renames() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+        sqlx::query("UPDATE pets SET photo_path = 'old.jpg' WHERE id = ?")
+            .bind(pet_id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+
+        let activity = db
+            .create_activity(ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Health,
+                subcategory: "Checkup".to_string(),
+                activity_data: Some(serde_json::json!({
+                    "notes": "Not a reference: bold.jpg",
+                    "attachments": [{ "url": "photos://localhost/old.jpg", "filename": "old.jpg" }]
+                })),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+
+        let renames = vec![PhotoRename {
+            old_name: "old.jpg".to_string(),
+            new_name: "ab12.jpg".to_string(),
+            created: true,
+        }];
+        let update = db.apply_photo_renames(&renames).await.unwrap();
+        assert_eq!(
+            update,
+            PhotoReferenceUpdate {
+                pets_updated: 1,
+                activities_updated: 1,
+            }
+        );
+
+        let pet = db.get_pet_by_id(pet_id).await.unwrap();
+        assert_eq!(pet.photo_path.as_deref(), Some("ab12.jpg"));
+
+        let data = db
+            .get_activity_by_id(activity.id)
+            .await
+            .unwrap()
+            .activity_data
+            .unwrap();
+        let data = serde_json::to_value(data).unwrap();
+        assert_eq!(data["attachments"][0]["url"], "photos://localhost/ab12.jpg");
+        assert_eq!(data["attachments"][0]["filename"], "ab12.jpg");
+        assert_eq!(data["notes"], "Not a reference: bold.jpg");
+
+        let redirects = db.get_photo_redirects().await.unwrap();
+        assert_eq!(
+            redirects.get("old.jpg").map(String::as_str),
+            Some("ab12.jpg")
+        );
+    }
+}
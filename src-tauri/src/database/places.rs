@@ -0,0 +1,365 @@
+use super::models::*;
+use super::query::escape_like;
+use crate::errors::ActivityError;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::Row;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Default number of places returned
+const DEFAULT_PLACE_LIMIT: i64 = 10;
+
+/// Default number of walk areas returned
+const DEFAULT_WALK_AREA_LIMIT: i64 = 20;
+
+/// Side of the grid cells walks are bucketed into; walks in touching cells share an area
+const WALK_CELL_SIZE_M: f64 = 250.0;
+
+/// Meters per degree of latitude (and of longitude at the equator)
+const METERS_PER_DEGREE: f64 = 111_320.0;
+
+const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+impl super::PetDatabase {
+    /// Places from a pet's location blocks, most visited first
+    pub async fn get_frequent_places(
+        &self,
+        pet_id: i64,
+        limit: Option<i64>,
+    ) -> Result<Vec<FrequentPlace>, ActivityError> {
+        let limit = limit.unwrap_or(DEFAULT_PLACE_LIMIT).clamp(1, 100);
+        log::debug!("[DB] get_frequent_places: pet_id={pet_id}, limit={limit}");
+
+        // SQLite takes bare columns from the row holding MAX(activity_time),
+        // so name/address/coordinates come from the most recent visit
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                trim(json_extract(activity_data, '$.location.name')) AS name,
+                json_extract(activity_data, '$.location.address') AS address,
+                json_extract(activity_data, '$.location.coordinates.lat') AS latitude,
+                json_extract(activity_data, '$.location.coordinates.lng') AS longitude,
+                COUNT(*) AS visit_count,
+                MAX(activity_time) AS last_visit
+            FROM activities
+            WHERE pet_id = ? AND location_key IS NOT NULL
+            GROUP BY location_key
+            ORDER BY visit_count DESC, last_visit DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows.iter().map(row_to_frequent_place).collect())
+    }
+
+    /// Place suggestions for a new activity.
+    /// Places matching `query` as a prefix are ranked by how often they were used
+    /// with the same subcategory, then overall; `pet_id` None searches all pets.
+    pub async fn get_place_suggestions(
+        &self,
+        pet_id: Option<i64>,
+        query: Option<&str>,
+        subcategory: Option<&str>,
+        limit: Option<i64>,
+    ) -> Result<Vec<FrequentPlace>, ActivityError> {
+        let limit = limit.unwrap_or(DEFAULT_PLACE_LIMIT).clamp(1, 100);
+        let prefix = query
+            .map(|q| q.trim().to_lowercase())
+            .filter(|q| !q.is_empty())
+            .map(|q| format!("{}%", escape_like(&q)));
+
+        log::debug!(
+            "[DB] get_place_suggestions: pet_id={pet_id:?}, prefix={prefix:?}, subcategory={subcategory:?}"
+        );
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                trim(json_extract(activity_data, '$.location.name')) AS name,
+                json_extract(activity_data, '$.location.address') AS address,
+                json_extract(activity_data, '$.location.coordinates.lat') AS latitude,
+                json_extract(activity_data, '$.location.coordinates.lng') AS longitude,
+                COUNT(*) AS visit_count,
+                SUM(CASE WHEN subcategory = ? THEN 1 ELSE 0 END) AS subcategory_count,
+                MAX(activity_time) AS last_visit
+            FROM activities
+            WHERE location_key IS NOT NULL
+                AND (? IS NULL OR pet_id = ?)
+                AND (? IS NULL OR location_key LIKE ? ESCAPE '\')
+            GROUP BY location_key
+            ORDER BY subcategory_count DESC, visit_count DESC, last_visit DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(subcategory)
+        .bind(pet_id)
+        .bind(pet_id)
+        .bind(&prefix)
+        .bind(&prefix)
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows.iter().map(row_to_frequent_place).collect())
+    }
+
+    /// Areas a pet is walked in, most walked first, for a "places we walk" map.
+    /// Walks are lifestyle activities with "walk" in the subcategory or a walk
+    /// duration or distance block, and coordinates in their location block.
+    pub async fn get_walk_areas(
+        &self,
+        pet_id: i64,
+        limit: Option<i64>,
+    ) -> Result<Vec<WalkArea>, ActivityError> {
+        let limit = limit.unwrap_or(DEFAULT_WALK_AREA_LIMIT).clamp(1, 100);
+        log::debug!("[DB] get_walk_areas: pet_id={pet_id}, limit={limit}");
+
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                activity_time,
+                trim(json_extract(activity_data, '$.location.name')) AS name,
+                json_extract(activity_data, '$.location.coordinates.lat') AS latitude,
+                json_extract(activity_data, '$.location.coordinates.lng') AS longitude
+            FROM activities
+            WHERE pet_id = ? AND category = 'lifestyle' AND needs_review = 0
+                AND json_type(activity_data, '$.location.coordinates.lat') IN ('real', 'integer')
+                AND json_type(activity_data, '$.location.coordinates.lng') IN ('real', 'integer')
+                AND (lower(subcategory) LIKE '%walk%'
+                    OR lower(json_extract(activity_data, '$.duration.durationType')) = 'walk'
+                    OR lower(json_extract(activity_data, '$.distance.distanceType')) = 'walk')
+            "#,
+        )
+        .bind(pet_id)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let walks = rows
+            .iter()
+            .map(|row| WalkPoint {
+                latitude: row.get("latitude"),
+                longitude: row.get("longitude"),
+                name: row
+                    .try_get::<Option<String>, _>("name")
+                    .ok()
+                    .flatten()
+                    .filter(|name| !name.is_empty()),
+                time: row.get("activity_time"),
+            })
+            .filter(|walk| {
+                (-90.0..=90.0).contains(&walk.latitude)
+                    && (-180.0..=180.0).contains(&walk.longitude)
+            })
+            .collect();
+
+        let mut areas = cluster_walks(walks, WALK_CELL_SIZE_M);
+        areas.truncate(limit as usize);
+        Ok(areas)
+    }
+}
+
+/// Position and details of one walk
+#[derive(Debug, Clone)]
+struct WalkPoint {
+    latitude: f64,
+    longitude: f64,
+    name: Option<String>,
+    time: DateTime<Utc>,
+}
+
+/// Group walks into areas: bucket them into a grid of `cell_size_m` cells and join
+/// occupied cells that touch, including diagonally. Most walked areas come first.
+fn cluster_walks(walks: Vec<WalkPoint>, cell_size_m: f64) -> Vec<WalkArea> {
+    if walks.is_empty() {
+        return Vec::new();
+    }
+
+    // A pet's walks are local, so one longitude step at their mean latitude keeps
+    // the cells roughly square
+    let mean_latitude = walks.iter().map(|w| w.latitude).sum::<f64>() / walks.len() as f64;
+    let lat_step = cell_size_m / METERS_PER_DEGREE;
+    let lng_step = lat_step / mean_latitude.to_radians().cos().max(0.01);
+
+    let mut cells: BTreeMap<(i64, i64), Vec<usize>> = BTreeMap::new();
+    for (index, walk) in walks.iter().enumerate() {
+        let cell = (
+            (walk.latitude / lat_step).floor() as i64,
+            (walk.longitude / lng_step).floor() as i64,
+        );
+        cells.entry(cell).or_default().push(index);
+    }
+
+    let mut seen = BTreeSet::new();
+    let mut areas = Vec::new();
+    for &start in cells.keys() {
+        if !seen.insert(start) {
+            continue;
+        }
+        let mut members = Vec::new();
+        let mut pending = vec![start];
+        while let Some((row, col)) = pending.pop() {
+            members.extend(&cells[&(row, col)]);
+            for neighbour in [
+                (row - 1, col - 1),
+                (row - 1, col),
+                (row - 1, col + 1),
+                (row, col - 1),
+                (row, col + 1),
+                (row + 1, col - 1),
+                (row + 1, col),
+                (row + 1, col + 1),
+            ] {
+                if cells.contains_key(&neighbour) && seen.insert(neighbour) {
+                    pending.push(neighbour);
+                }
+            }
+        }
+        let area: Vec<&WalkPoint> = members.iter().map(|&index| &walks[index]).collect();
+        areas.push(walk_area(&area));
+    }
+
+    areas.sort_by(|a, b| {
+        b.walk_count
+            .cmp(&a.walk_count)
+            .then(b.last_walk.cmp(&a.last_walk))
+    });
+    areas
+}
+
+fn walk_area(walks: &[&WalkPoint]) -> WalkArea {
+    let count = walks.len() as f64;
+    let latitude = walks.iter().map(|w| w.latitude).sum::<f64>() / count;
+    let longitude = walks.iter().map(|w| w.longitude).sum::<f64>() / count;
+    let radius_m = walks
+        .iter()
+        .map(|w| distance_m(latitude, longitude, w.latitude, w.longitude))
+        .fold(0.0, f64::max);
+
+    let mut names: HashMap<&str, usize> = HashMap::new();
+    for name in walks.iter().filter_map(|w| w.name.as_deref()) {
+        *names.entry(name).or_default() += 1;
+    }
+    let mut names: Vec<(&str, usize)> = names.into_iter().collect();
+    names.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+    WalkArea {
+        latitude,
+        longitude,
+        radius_m: radius_m.round(),
+        walk_count: walks.len() as i64,
+        place_names: names
+            .into_iter()
+            .map(|(name, _)| name.to_string())
+            .collect(),
+        first_walk: walks.iter().map(|w| w.time).min().unwrap_or_default(),
+        last_walk: walks.iter().map(|w| w.time).max().unwrap_or_default(),
+    }
+}
+
+/// Great-circle distance in meters
+fn distance_m(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lng = (lng2 - lng1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+fn row_to_frequent_place(row: &sqlx::sqlite::SqliteRow) -> FrequentPlace {
+    let last_visit: Option<String> = row.try_get("last_visit").ok().flatten();
+
+    FrequentPlace {
+        name: row.try_get("name").unwrap_or_default(),
+        address: row
+            .try_get::<Option<String>, _>("address")
+            .ok()
+            .flatten()
+            .filter(|a| !a.trim().is_empty()),
+        latitude: row.try_get("latitude").ok().flatten(),
+        longitude: row.try_get("longitude").ok().flatten(),
+        visit_count: row.get("visit_count"),
+        last_visit: last_visit
+            .and_then(|s| NaiveDateTime::parse_from_str(&s, "%Y-%m-%d %H:%M:%S").ok())
+            .map(|dt| DateTime::<Utc>::from_naive_utc_and_offset(dt, Utc)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+    use serde_json::json;
+
+    fn walk(latitude: f64, longitude: f64, name: &str) -> WalkPoint {
+        WalkPoint {
+            latitude,
+            longitude,
+            name: Some(name.to_string()),
+            time: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_walks_cluster_into_touching_cells() {
+        let walks = vec![
+            // Central Park, spread over a few hundred meters
+            walk(40.7812, -73.9665, "Central Park"),
+            walk(40.7822, -73.9660, "Central Park"),
+            walk(40.7835, -73.9650, "The Great Lawn"),
+            walk(40.7846, -73.9641, "Central Park"),
+            // Riverside, about 2.5 km away
+            walk(40.8007, -73.9726, "Riverside Trail"),
+            walk(40.8009, -73.9724, "Riverside Trail"),
+        ];
+        let areas = cluster_walks(walks, WALK_CELL_SIZE_M);
+
+        assert_eq!(areas.len(), 2);
+        assert_eq!(areas[0].walk_count, 4);
+        assert_eq!(areas[0].place_names, vec!["Central Park", "The Great Lawn"]);
+        assert!(areas[0].radius_m > 100.0 && areas[0].radius_m < 400.0);
+        assert_eq!(areas[1].walk_count, 2);
+        assert_eq!(areas[1].place_names, vec!["Riverside Trail"]);
+        assert!(cluster_walks(Vec::new(), WALK_CELL_SIZE_M).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_walk_areas_only_count_walks_with_coordinates() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let log = |subcategory: &str, location: serde_json::Value| ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Lifestyle,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(json!({ "location": location })),
+            needs_review: false,
+        };
+        let park =
+            json!({ "name": "Central Park", "coordinates": { "lat": 40.7812, "lng": -73.9665 } });
+        for request in [
+            log("Walk", park.clone()),
+            log("Evening walk", park.clone()),
+            log("Play", park.clone()),
+            log("Walk", json!({ "name": "Somewhere" })),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let areas = db.get_walk_areas(pet_id, None).await.unwrap();
+        assert_eq!(areas.len(), 1);
+        assert_eq!(areas[0].walk_count, 2);
+        assert_eq!(areas[0].place_names, vec!["Central Park"]);
+        assert_eq!(areas[0].radius_m, 0.0);
+    }
+}
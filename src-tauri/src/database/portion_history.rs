@@ -0,0 +1,151 @@
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// Most suggestions returned for a brand or product field
+const MAX_KNOWN_VALUES: i64 = 20;
+
+/// A brand or product entered in earlier portion blocks
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KnownPortionValue {
+    /// Spelling of the latest use; uses differing only in case count as one
+    pub value: String,
+    pub use_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl super::PetDatabase {
+    /// Brands in a pet's portion blocks, most used first
+    pub async fn get_known_brands(
+        &self,
+        pet_id: i64,
+    ) -> Result<Vec<KnownPortionValue>, ActivityError> {
+        // The bare `value` column comes from the row with the latest activity_time
+        let rows = sqlx::query(
+            r#"
+            SELECT TRIM(json_extract(activity_data, '$.portion.brand')) AS value,
+                COUNT(*) AS use_count, MAX(activity_time) AS last_used_at
+            FROM activities
+            WHERE pet_id = ? AND needs_review = 0
+                AND json_type(activity_data, '$.portion.brand') = 'text'
+            GROUP BY LOWER(value)
+            HAVING value != ''
+            ORDER BY use_count DESC, last_used_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(pet_id)
+        .bind(MAX_KNOWN_VALUES)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::debug!(
+            "[DB] get_known_brands: pet_id={pet_id}, brands={}",
+            rows.len()
+        );
+        Ok(rows.iter().map(row_to_known_value).collect())
+    }
+
+    /// Products entered with `brand` (ignoring case) for any pet, most used first
+    pub async fn get_known_products(
+        &self,
+        brand: &str,
+    ) -> Result<Vec<KnownPortionValue>, ActivityError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT TRIM(json_extract(activity_data, '$.portion.product')) AS value,
+                COUNT(*) AS use_count, MAX(activity_time) AS last_used_at
+            FROM activities
+            WHERE needs_review = 0
+                AND json_type(activity_data, '$.portion.product') = 'text'
+                AND LOWER(TRIM(json_extract(activity_data, '$.portion.brand'))) = LOWER(TRIM(?))
+            GROUP BY LOWER(value)
+            HAVING value != ''
+            ORDER BY use_count DESC, last_used_at DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(brand)
+        .bind(MAX_KNOWN_VALUES)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::debug!(
+            "[DB] get_known_products: brand={brand}, products={}",
+            rows.len()
+        );
+        Ok(rows.iter().map(row_to_known_value).collect())
+    }
+}
+
+fn row_to_known_value(row: &sqlx::sqlite::SqliteRow) -> KnownPortionValue {
+    KnownPortionValue {
+        value: row.get("value"),
+        use_count: row.get("use_count"),
+        last_used_at: row.try_get("last_used_at").ok().flatten(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_known_brands_and_products_rank_by_use() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let (pet_id, other_pet_id) = (summary.pet_ids[0], summary.pet_ids[1]);
+
+        let feeding = |pet_id: i64, day: u32, brand: &str, product: Option<&str>| {
+            ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Diet,
+                subcategory: "Feeding".to_string(),
+                activity_data: Some(serde_json::json!({
+                    "time": { "date": format!("2025-03-{day:02}T08:00:00.000Z"), "time": "", "timezone": "" },
+                    "portion": {
+                        "amount": 80, "unit": "g", "portionType": "meal",
+                        "brand": brand, "product": product
+                    }
+                })),
+                needs_review: false,
+            }
+        };
+        for request in [
+            feeding(pet_id, 1, "royal canin", Some("Kitten")),
+            feeding(pet_id, 2, "Orijen", Some("Original")),
+            feeding(pet_id, 3, "Royal Canin ", Some("Kitten")),
+            feeding(pet_id, 4, "  ", None),
+            feeding(other_pet_id, 5, "Royal Canin", Some("Indoor")),
+            feeding(other_pet_id, 6, "Royal Canin", Some("Indoor")),
+            feeding(other_pet_id, 7, "Royal Canin", Some("kitten")),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let brands = db.get_known_brands(pet_id).await.unwrap();
+        let values: Vec<(&str, i64)> = brands
+            .iter()
+            .map(|brand| (brand.value.as_str(), brand.use_count))
+            .collect();
+        assert_eq!(values, vec![("Royal Canin", 2), ("Orijen", 1)]);
+        assert!(brands[0].last_used_at > brands[1].last_used_at);
+
+        let products = db.get_known_products("royal canin").await.unwrap();
+        let values: Vec<(&str, i64)> = products
+            .iter()
+            .map(|product| (product.value.as_str(), product.use_count))
+            .collect();
+        assert_eq!(values, vec![("kitten", 3), ("Indoor", 2)]);
+        assert!(db.get_known_products("Acana").await.unwrap().is_empty());
+    }
+}
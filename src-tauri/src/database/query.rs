@@ -0,0 +1,248 @@
+use sqlx::query::Query;
+use sqlx::sqlite::SqliteArguments;
+use sqlx::{Encode, QueryBuilder, Sqlite, Type};
+
+/// Typed builder for dynamic `UPDATE ... SET ... WHERE id = ?` statements.
+///
+/// Table and column names are `&'static str`, so only identifiers written in code
+/// reach the SQL text; every value is pushed as a bind parameter.
+pub struct UpdateQuery<'args> {
+    builder: QueryBuilder<'args, Sqlite>,
+    assignments: usize,
+}
+
+impl<'args> UpdateQuery<'args> {
+    pub fn new(table: &'static str) -> Self {
+        let mut builder = QueryBuilder::new("UPDATE ");
+        builder.push(table).push(" SET ");
+        UpdateQuery {
+            builder,
+            assignments: 0,
+        }
+    }
+
+    /// Add a `column = ?` assignment
+    pub fn set<T>(&mut self, column: &'static str, value: T) -> &mut Self
+    where
+        T: 'args + Encode<'args, Sqlite> + Type<Sqlite>,
+    {
+        if self.assignments > 0 {
+            self.builder.push(", ");
+        }
+        self.builder.push(column).push(" = ").push_bind(value);
+        self.assignments += 1;
+        self
+    }
+
+    /// Add a `column = ?` assignment only when a value is given
+    pub fn set_if_some<T>(&mut self, column: &'static str, value: Option<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, Sqlite> + Type<Sqlite>,
+    {
+        if let Some(value) = value {
+            self.set(column, value);
+        }
+        self
+    }
+
+    /// Whether any assignment has been added
+    pub fn has_changes(&self) -> bool {
+        self.assignments > 0
+    }
+
+    /// Finish the statement with `WHERE id = ?`
+    pub fn where_id(mut self, id: i64) -> QueryBuilder<'args, Sqlite> {
+        self.builder.push(" WHERE id = ").push_bind(id);
+        self.builder
+    }
+}
+
+/// Typed builder for `SELECT` statements with optional filters, ordering and paging.
+///
+/// Like [`UpdateQuery`], SQL fragments must be `&'static str` and values are always bound.
+pub struct SelectQuery<'args> {
+    builder: QueryBuilder<'args, Sqlite>,
+    has_where: bool,
+}
+
+impl<'args> SelectQuery<'args> {
+    /// Start from a fixed head such as `SELECT * FROM activities`
+    pub fn new(select: &'static str) -> Self {
+        SelectQuery {
+            builder: QueryBuilder::new(select),
+            has_where: false,
+        }
+    }
+
+    fn push_condition_prefix(&mut self) {
+        self.builder
+            .push(if self.has_where { " AND " } else { " WHERE " });
+        self.has_where = true;
+    }
+
+    /// Add `column = ?`
+    pub fn where_eq<T>(&mut self, column: &'static str, value: T) -> &mut Self
+    where
+        T: 'args + Encode<'args, Sqlite> + Type<Sqlite>,
+    {
+        self.push_condition_prefix();
+        self.builder.push(column).push(" = ").push_bind(value);
+        self
+    }
+
+    /// Add `column = ?` only when a value is given
+    pub fn where_eq_opt<T>(&mut self, column: &'static str, value: Option<T>) -> &mut Self
+    where
+        T: 'args + Encode<'args, Sqlite> + Type<Sqlite>,
+    {
+        if let Some(value) = value {
+            self.where_eq(column, value);
+        }
+        self
+    }
+
+    /// Add `column <op> ?` for a comparison such as `>=`, only when a value is given
+    pub fn where_cmp_opt<T>(
+        &mut self,
+        column: &'static str,
+        op: &'static str,
+        value: Option<T>,
+    ) -> &mut Self
+    where
+        T: 'args + Encode<'args, Sqlite> + Type<Sqlite>,
+    {
+        if let Some(value) = value {
+            self.push_condition_prefix();
+            self.builder
+                .push(column)
+                .push(" ")
+                .push(op)
+                .push(" ")
+                .push_bind(value);
+        }
+        self
+    }
+
+    /// Add `(col1 LIKE ? OR col2 LIKE ? ...)` matching `text` anywhere in any column.
+    /// LIKE wildcards in `text` are matched literally.
+    pub fn where_contains_any(&mut self, columns: &[&'static str], text: &str) -> &mut Self {
+        if columns.is_empty() {
+            return self;
+        }
+
+        let pattern = format!("%{}%", escape_like(text));
+        self.push_condition_prefix();
+        self.builder.push("(");
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 {
+                self.builder.push(" OR ");
+            }
+            self.builder
+                .push(*column)
+                .push(" LIKE ")
+                .push_bind(pattern.clone())
+                .push(" ESCAPE '\\'");
+        }
+        self.builder.push(")");
+        self
+    }
+
+    /// Add an `ORDER BY` clause
+    pub fn order_by(&mut self, clause: &'static str) -> &mut Self {
+        self.builder.push(" ORDER BY ").push(clause);
+        self
+    }
+
+    /// Add `LIMIT ? OFFSET ?`
+    pub fn paginate(&mut self, limit: i64, offset: i64) -> &mut Self {
+        self.builder
+            .push(" LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        self
+    }
+
+    /// Add `LIMIT ?`
+    pub fn limit(&mut self, limit: i64) -> &mut Self {
+        self.builder.push(" LIMIT ").push_bind(limit);
+        self
+    }
+
+    /// Generated SQL, for logging and tests
+    pub fn sql(&self) -> &str {
+        self.builder.sql()
+    }
+
+    /// Build the executable query
+    pub fn build(&mut self) -> Query<'_, Sqlite, SqliteArguments<'args>> {
+        self.builder.build()
+    }
+}
+
+/// Escape LIKE wildcards (`%`, `_`) so user input only matches literally.
+/// Use together with `ESCAPE '\'`.
+pub fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_query_sql() {
+        let mut update = UpdateQuery::new("pets");
+        assert!(!update.has_changes());
+
+        update
+            .set("name", "Mochi".to_string())
+            .set_if_some("breed", None::<String>)
+            .set_if_some("weight_kg", Some(4.2_f32));
+        assert!(update.has_changes());
+
+        let builder = update.where_id(1);
+        assert_eq!(
+            builder.sql(),
+            "UPDATE pets SET name = ?, weight_kg = ? WHERE id = ?"
+        );
+    }
+
+    #[test]
+    fn test_select_query_sql() {
+        let mut select = SelectQuery::new("SELECT * FROM activities");
+        select
+            .where_eq_opt("pet_id", Some(3_i64))
+            .where_eq_opt("category", None::<String>)
+            .where_contains_any(&["activity_data", "subcategory"], "50%")
+            .order_by("created_at DESC, id DESC")
+            .paginate(10, 0);
+
+        assert_eq!(
+            select.sql(),
+            "SELECT * FROM activities WHERE pet_id = ? AND (activity_data LIKE ? ESCAPE '\\' OR subcategory LIKE ? ESCAPE '\\') ORDER BY created_at DESC, id DESC LIMIT ? OFFSET ?"
+        );
+    }
+
+    #[test]
+    fn test_select_query_comparisons() {
+        let mut select = SelectQuery::new("SELECT id FROM activities");
+        select
+            .where_cmp_opt("date(activity_time)", ">=", Some("2025-06-01".to_string()))
+            .where_cmp_opt("date(activity_time)", "<=", None::<String>);
+
+        assert_eq!(
+            select.sql(),
+            "SELECT id FROM activities WHERE date(activity_time) >= ?"
+        );
+    }
+
+    #[test]
+    fn test_escape_like() {
+        assert_eq!(escape_like("50%_off\\"), "50\\%\\_off\\\\");
+        assert_eq!(escape_like("plain"), "plain");
+    }
+}
@@ -0,0 +1,632 @@
+use super::hooks::ActivityEvent;
+use super::models::*;
+use crate::errors::ActivityError;
+use crate::recurrence::RecurrenceRule;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Row, SqliteConnection};
+use std::collections::HashMap;
+
+impl super::PetDatabase {
+    /// Create a recurring activity series from a template and an RRULE
+    pub async fn create_recurring_series(
+        &self,
+        request: CreateRecurringActivityRequest,
+    ) -> Result<RecurringSeries, ActivityError> {
+        let rule = request
+            .rrule
+            .parse::<RecurrenceRule>()
+            .map_err(|e| ActivityError::validation("rrule".to_string(), e.to_string()))?;
+
+        let days_ahead = request.materialize_days_ahead.unwrap_or(0);
+        if !(0..=365).contains(&days_ahead) {
+            return Err(ActivityError::validation(
+                "materialize_days_ahead",
+                "Must be between 0 and 365 days",
+            ));
+        }
+
+        log::debug!(
+            "[DB] create_recurring_series: pet_id={}, subcategory={}, rrule={}",
+            request.template.pet_id,
+            request.template.subcategory,
+            rule
+        );
+
+        let now = Utc::now();
+        let template_json = request
+            .template
+            .activity_data
+            .as_ref()
+            .map(|v| v.to_string());
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO recurring_series (
+                pet_id, category, subcategory, activity_data, rrule, start_date,
+                materialize_days_ahead, status, created_at, updated_at
+            )
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(request.template.pet_id)
+        .bind(request.template.category.to_string())
+        .bind(&request.template.subcategory)
+        .bind(template_json)
+        .bind(rule.to_string())
+        .bind(request.start_date.format("%Y-%m-%d").to_string())
+        .bind(days_ahead)
+        .bind(RecurringSeriesStatus::Active.to_string())
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            log::error!("[DB] create_recurring_series: insert failed, error={e}");
+            ActivityError::invalid_data(format!("Database error: {e}"))
+        })?;
+
+        self.get_recurring_series_by_id(result.last_insert_rowid())
+            .await
+    }
+
+    /// Get a recurring series by ID
+    pub async fn get_recurring_series_by_id(
+        &self,
+        id: i64,
+    ) -> Result<RecurringSeries, ActivityError> {
+        let row = sqlx::query("SELECT * FROM recurring_series WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        match row {
+            Some(row) => self.row_to_recurring_series(&row),
+            None => Err(ActivityError::series_not_found(id)),
+        }
+    }
+
+    /// Get all recurring series for a pet, including paused and cancelled ones
+    pub async fn get_recurring_series_for_pet(
+        &self,
+        pet_id: i64,
+    ) -> Result<Vec<RecurringSeries>, ActivityError> {
+        let rows =
+            sqlx::query("SELECT * FROM recurring_series WHERE pet_id = ? ORDER BY created_at DESC")
+                .bind(pet_id)
+                .fetch_all(&self.read_pool)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        rows.iter()
+            .map(|row| self.row_to_recurring_series(row))
+            .collect()
+    }
+
+    /// Change the status of a series (pause, resume or cancel)
+    pub async fn set_recurring_series_status(
+        &self,
+        id: i64,
+        status: RecurringSeriesStatus,
+    ) -> Result<RecurringSeries, ActivityError> {
+        let series = self.get_recurring_series_by_id(id).await?;
+        if series.status == RecurringSeriesStatus::Cancelled && status != series.status {
+            return Err(ActivityError::validation(
+                "status",
+                "Cancelled series cannot be reactivated",
+            ));
+        }
+
+        sqlx::query("UPDATE recurring_series SET status = ?, updated_at = ? WHERE id = ?")
+            .bind(status.to_string())
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::info!("[DB] set_recurring_series_status: series_id={id}, status={status}");
+        self.get_recurring_series_by_id(id).await
+    }
+
+    /// Get the generated, skipped and edited occurrences of a series
+    pub async fn get_recurring_occurrences(
+        &self,
+        series_id: i64,
+    ) -> Result<Vec<RecurringOccurrence>, ActivityError> {
+        let rows = sqlx::query(
+            "SELECT * FROM recurring_occurrences WHERE series_id = ? ORDER BY occurrence_date ASC",
+        )
+        .bind(series_id)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        rows.iter()
+            .map(|row| self.row_to_recurring_occurrence(row))
+            .collect()
+    }
+
+    /// Skip a single occurrence. A generated activity for that date is removed.
+    pub async fn skip_recurring_occurrence(
+        &self,
+        series_id: i64,
+        occurrence_date: NaiveDate,
+    ) -> Result<RecurringOccurrence, ActivityError> {
+        let series = self.get_recurring_series_by_id(series_id).await?;
+        self.ensure_occurrence_date(&series, occurrence_date)?;
+
+        let existing = self
+            .find_recurring_occurrence(series_id, occurrence_date)
+            .await?;
+        if let Some(activity_id) = existing.as_ref().and_then(|o| o.activity_id) {
+            self.delete_activity(activity_id).await?;
+        }
+
+        self.upsert_recurring_occurrence(
+            series_id,
+            occurrence_date,
+            None,
+            RecurringOccurrenceStatus::Skipped,
+            None,
+        )
+        .await
+    }
+
+    /// Edit a single occurrence. Generated activities are updated in place;
+    /// future occurrences store the edit and apply it when they are generated.
+    pub async fn update_recurring_occurrence(
+        &self,
+        series_id: i64,
+        occurrence_date: NaiveDate,
+        updates: ActivityUpdateRequest,
+    ) -> Result<RecurringOccurrence, ActivityError> {
+        let series = self.get_recurring_series_by_id(series_id).await?;
+        self.ensure_occurrence_date(&series, occurrence_date)?;
+
+        let existing = self
+            .find_recurring_occurrence(series_id, occurrence_date)
+            .await?;
+
+        match existing {
+            Some(
+                occurrence @ RecurringOccurrence {
+                    activity_id: Some(activity_id),
+                    ..
+                },
+            ) => {
+                self.update_activity(activity_id, updates).await?;
+                Ok(occurrence)
+            }
+            _ => {
+                self.upsert_recurring_occurrence(
+                    series_id,
+                    occurrence_date,
+                    None,
+                    RecurringOccurrenceStatus::Overridden,
+                    Some(&updates),
+                )
+                .await
+            }
+        }
+    }
+
+    /// Generate activities for all active series whose occurrences are due.
+    /// An occurrence is due once `today + materialize_days_ahead` reaches its date.
+    pub async fn materialize_recurring_activities(
+        &self,
+        today: NaiveDate,
+    ) -> Result<Vec<Activity>, ActivityError> {
+        let rows = sqlx::query("SELECT * FROM recurring_series WHERE status = ?")
+            .bind(RecurringSeriesStatus::Active.to_string())
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut created = Vec::new();
+        for row in rows {
+            let series = self.row_to_recurring_series(&row)?;
+            let rule = match series.rrule.parse::<RecurrenceRule>() {
+                Ok(rule) => rule,
+                Err(e) => {
+                    log::warn!(
+                        "[DB] materialize_recurring_activities: skipping series_id={} with invalid rrule, error={e}",
+                        series.id
+                    );
+                    continue;
+                }
+            };
+
+            let existing: HashMap<NaiveDate, RecurringOccurrence> = self
+                .get_recurring_occurrences(series.id)
+                .await?
+                .into_iter()
+                .map(|o| (o.occurrence_date, o))
+                .collect();
+
+            // Continue after the last generated occurrence instead of the series start,
+            // so long-running series don't hit the rule's occurrence limit. Edits to
+            // occurrences that were never generated are still picked up.
+            let after_last = existing
+                .values()
+                .filter(|o| o.status == RecurringOccurrenceStatus::Materialized)
+                .map(|o| o.occurrence_date)
+                .max()
+                .and_then(|date| date.succ_opt())
+                .unwrap_or(series.start_date);
+            let from = existing
+                .values()
+                .filter(|o| o.status == RecurringOccurrenceStatus::Overridden)
+                .map(|o| o.occurrence_date)
+                .fold(after_last, NaiveDate::min);
+
+            let horizon = today + chrono::Duration::days(series.materialize_days_ahead);
+            let dates = rule.occurrences_between(series.start_date, from, horizon);
+            if dates.is_empty() {
+                continue;
+            }
+
+            for date in dates {
+                let override_data = match existing.get(&date) {
+                    Some(o) if o.status != RecurringOccurrenceStatus::Overridden => continue,
+                    Some(o) => o.override_data.clone(),
+                    None => None,
+                };
+
+                let request = build_occurrence_request(&series, date, override_data.as_ref());
+                let activity = self
+                    .materialize_occurrence(series.id, date, request, override_data.as_ref())
+                    .await?;
+
+                created.push(activity);
+            }
+        }
+
+        if !created.is_empty() {
+            log::info!(
+                "[DB] materialize_recurring_activities: generated {} activities",
+                created.len()
+            );
+        }
+        Ok(created)
+    }
+
+    /// Create the activity of an occurrence and record it in one transaction, so
+    /// a crash can't leave an activity without its occurrence or the reverse
+    async fn materialize_occurrence(
+        &self,
+        series_id: i64,
+        date: NaiveDate,
+        request: ActivityCreateRequest,
+        override_data: Option<&ActivityUpdateRequest>,
+    ) -> Result<Activity, ActivityError> {
+        let _turn = self.writes.acquire_for_task().await;
+
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ActivityError::InvalidData {
+                message: format!("Failed to start transaction: {e}"),
+            })?;
+
+        let activity = self
+            .create_activity_in_transaction(&mut tx, request)
+            .await?;
+        self.hooks
+            .dispatch(&mut tx, ActivityEvent::Created(&activity))
+            .await?;
+        write_recurring_occurrence(
+            &mut tx,
+            series_id,
+            date,
+            Some(activity.id),
+            RecurringOccurrenceStatus::Materialized,
+            override_data,
+        )
+        .await?;
+
+        tx.commit().await.map_err(|e| ActivityError::InvalidData {
+            message: format!("Failed to commit transaction: {e}"),
+        })?;
+        Ok(activity)
+    }
+
+    /// Reject dates that are not part of the series schedule
+    fn ensure_occurrence_date(
+        &self,
+        series: &RecurringSeries,
+        date: NaiveDate,
+    ) -> Result<(), ActivityError> {
+        let rule = series
+            .rrule
+            .parse::<RecurrenceRule>()
+            .map_err(|e| ActivityError::invalid_data(e.to_string()))?;
+
+        if rule
+            .occurrences_between(series.start_date, date, date)
+            .is_empty()
+        {
+            return Err(ActivityError::date_out_of_range(format!(
+                "{date} is not an occurrence of series {}",
+                series.id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn find_recurring_occurrence(
+        &self,
+        series_id: i64,
+        date: NaiveDate,
+    ) -> Result<Option<RecurringOccurrence>, ActivityError> {
+        let row = sqlx::query(
+            "SELECT * FROM recurring_occurrences WHERE series_id = ? AND occurrence_date = ?",
+        )
+        .bind(series_id)
+        .bind(date.format("%Y-%m-%d").to_string())
+        .fetch_optional(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        row.map(|row| self.row_to_recurring_occurrence(&row))
+            .transpose()
+    }
+
+    async fn upsert_recurring_occurrence(
+        &self,
+        series_id: i64,
+        date: NaiveDate,
+        activity_id: Option<i64>,
+        status: RecurringOccurrenceStatus,
+        override_data: Option<&ActivityUpdateRequest>,
+    ) -> Result<RecurringOccurrence, ActivityError> {
+        let mut conn = self
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        write_recurring_occurrence(
+            &mut conn,
+            series_id,
+            date,
+            activity_id,
+            status,
+            override_data,
+        )
+        .await?;
+        // The in-memory database has a single connection shared with readers
+        drop(conn);
+
+        self.find_recurring_occurrence(series_id, date)
+            .await?
+            .ok_or_else(|| ActivityError::invalid_data("Failed to retrieve occurrence"))
+    }
+
+    fn row_to_recurring_series(
+        &self,
+        row: &sqlx::sqlite::SqliteRow,
+    ) -> Result<RecurringSeries, ActivityError> {
+        let get_err = |field: &str, e: sqlx::Error| {
+            ActivityError::invalid_data(format!("Invalid {field}: {e}"))
+        };
+
+        let category_str: String = row
+            .try_get("category")
+            .map_err(|e| get_err("category", e))?;
+        let category = category_str
+            .parse::<ActivityCategory>()
+            .map_err(|_| ActivityError::invalid_type(category_str))?;
+
+        let start_date_str: String = row
+            .try_get("start_date")
+            .map_err(|e| get_err("start_date", e))?;
+        let start_date = NaiveDate::parse_from_str(&start_date_str, "%Y-%m-%d")
+            .map_err(|e| ActivityError::invalid_data(format!("Invalid start_date: {e}")))?;
+
+        let status_str: String = row.try_get("status").map_err(|e| get_err("status", e))?;
+        let status = status_str
+            .parse::<RecurringSeriesStatus>()
+            .map_err(|e| ActivityError::invalid_data(e.to_string()))?;
+
+        let activity_data: Option<String> = row.try_get("activity_data").ok().flatten();
+        let created_at: DateTime<Utc> = row
+            .try_get("created_at")
+            .map_err(|e| get_err("created_at", e))?;
+        let updated_at: DateTime<Utc> = row
+            .try_get("updated_at")
+            .map_err(|e| get_err("updated_at", e))?;
+
+        Ok(RecurringSeries {
+            id: row.try_get("id").map_err(|e| get_err("id", e))?,
+            pet_id: row.try_get("pet_id").map_err(|e| get_err("pet_id", e))?,
+            category,
+            subcategory: row
+                .try_get("subcategory")
+                .map_err(|e| get_err("subcategory", e))?,
+            activity_data: activity_data.and_then(|s| serde_json::from_str(&s).ok()),
+            rrule: row.try_get("rrule").map_err(|e| get_err("rrule", e))?,
+            start_date,
+            materialize_days_ahead: row
+                .try_get("materialize_days_ahead")
+                .map_err(|e| get_err("materialize_days_ahead", e))?,
+            status,
+            created_at,
+            updated_at,
+        })
+    }
+
+    fn row_to_recurring_occurrence(
+        &self,
+        row: &sqlx::sqlite::SqliteRow,
+    ) -> Result<RecurringOccurrence, ActivityError> {
+        let get_err = |field: &str, e: sqlx::Error| {
+            ActivityError::invalid_data(format!("Invalid {field}: {e}"))
+        };
+
+        let date_str: String = row
+            .try_get("occurrence_date")
+            .map_err(|e| get_err("occurrence_date", e))?;
+        let occurrence_date = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
+            .map_err(|e| ActivityError::invalid_data(format!("Invalid occurrence_date: {e}")))?;
+
+        let status_str: String = row.try_get("status").map_err(|e| get_err("status", e))?;
+        let status = status_str
+            .parse::<RecurringOccurrenceStatus>()
+            .map_err(|e| ActivityError::invalid_data(e.to_string()))?;
+
+        let override_data: Option<String> = row.try_get("override_data").ok().flatten();
+
+        Ok(RecurringOccurrence {
+            id: row.try_get("id").map_err(|e| get_err("id", e))?,
+            series_id: row
+                .try_get("series_id")
+                .map_err(|e| get_err("series_id", e))?,
+            occurrence_date,
+            activity_id: row
+                .try_get("activity_id")
+                .map_err(|e| get_err("activity_id", e))?,
+            status,
+            override_data: override_data.and_then(|s| serde_json::from_str(&s).ok()),
+        })
+    }
+}
+
+/// Insert or update the occurrence of a series on `date`
+async fn write_recurring_occurrence(
+    conn: &mut SqliteConnection,
+    series_id: i64,
+    date: NaiveDate,
+    activity_id: Option<i64>,
+    status: RecurringOccurrenceStatus,
+    override_data: Option<&ActivityUpdateRequest>,
+) -> Result<(), ActivityError> {
+    let now = Utc::now();
+    let override_json = override_data
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| ActivityError::invalid_data(format!("Invalid override data: {e}")))?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO recurring_occurrences (
+            series_id, occurrence_date, activity_id, status, override_data, created_at, updated_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+        ON CONFLICT(series_id, occurrence_date) DO UPDATE SET
+            activity_id = excluded.activity_id,
+            status = excluded.status,
+            override_data = excluded.override_data,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(series_id)
+    .bind(date.format("%Y-%m-%d").to_string())
+    .bind(activity_id)
+    .bind(status.to_string())
+    .bind(override_json)
+    .bind(now)
+    .bind(now)
+    .execute(conn)
+    .await
+    .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    Ok(())
+}
+
+/// Build the create request for one occurrence, stamping its date into the time block
+fn build_occurrence_request(
+    series: &RecurringSeries,
+    date: NaiveDate,
+    override_data: Option<&ActivityUpdateRequest>,
+) -> ActivityCreateRequest {
+    let subcategory = override_data
+        .and_then(|o| o.subcategory.clone())
+        .unwrap_or_else(|| series.subcategory.clone());
+
+    let mut activity_data = override_data
+        .and_then(|o| o.activity_data.clone())
+        .or_else(|| series.activity_data.clone())
+        .unwrap_or_else(|| serde_json::json!({}));
+
+    if let Some(blocks) = activity_data.as_object_mut() {
+        let timezone = blocks
+            .get("time")
+            .and_then(|t| t.get("timezone"))
+            .and_then(|tz| tz.as_str())
+            .unwrap_or("")
+            .to_string();
+        let occurrence_time = date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc();
+
+        blocks.insert(
+            "time".to_string(),
+            serde_json::json!({
+                "date": occurrence_time.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                "time": "",
+                "timezone": timezone,
+            }),
+        );
+    }
+
+    ActivityCreateRequest {
+        pet_id: series.pet_id,
+        category: series.category,
+        subcategory,
+        activity_data: Some(activity_data),
+        // Occurrences the user overrode were already reviewed
+        needs_review: override_data.is_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_materialize_continues_past_occurrence_limit() {
+        let (db, _dir) = test_database().await;
+        let pet = db
+            .create_pet(CreatePetRequest {
+                name: "Biscuit".to_string(),
+                birth_date: NaiveDate::from_ymd_opt(2021, 6, 1).unwrap(),
+                species: PetSpecies::Dog,
+                gender: PetGender::Male,
+                breed: None,
+                color: None,
+                weight_kg: None,
+                photo_path: None,
+                notes: None,
+            })
+            .await
+            .unwrap();
+        let series = db
+            .create_recurring_series(CreateRecurringActivityRequest {
+                template: ActivityCreateRequest {
+                    pet_id: pet.id,
+                    category: ActivityCategory::Health,
+                    subcategory: "Medication".to_string(),
+                    activity_data: None,
+                    needs_review: false,
+                },
+                rrule: "FREQ=DAILY".to_string(),
+                start_date: NaiveDate::from_ymd_opt(2022, 1, 1).unwrap(),
+                materialize_days_ahead: None,
+            })
+            .await
+            .unwrap();
+
+        // 1101 daily occurrences are due, more than one run generates
+        let today = NaiveDate::from_ymd_opt(2025, 1, 5).unwrap();
+        let first = db.materialize_recurring_activities(today).await.unwrap();
+        let second = db.materialize_recurring_activities(today).await.unwrap();
+        let third = db.materialize_recurring_activities(today).await.unwrap();
+        assert_eq!(first.len() + second.len(), 1101);
+        assert!(!second.is_empty());
+        assert!(third.is_empty());
+
+        let occurrences = db.get_recurring_occurrences(series.id).await.unwrap();
+        assert_eq!(occurrences.len(), 1101);
+        assert_eq!(occurrences.last().unwrap().occurrence_date, today);
+        assert!(occurrences.iter().all(|o| o.activity_id.is_some()));
+    }
+}
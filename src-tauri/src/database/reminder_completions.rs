@@ -0,0 +1,336 @@
+use super::models::*;
+use super::reminders::{parse_reminder_due_at, reminder_block, reminder_title};
+use crate::errors::ActivityError;
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use sqlx::Row;
+
+impl super::PetDatabase {
+    /// Record that `activity_id` was logged to complete the reminder on
+    /// `reminder_activity_id`. A completed reminder is no longer announced;
+    /// completing it again moves the completion to the new activity.
+    pub async fn complete_reminder(
+        &self,
+        reminder_activity_id: i64,
+        activity_id: i64,
+    ) -> Result<ReminderCompletion, ActivityError> {
+        if reminder_activity_id == activity_id {
+            return Err(ActivityError::validation(
+                "activity_id",
+                "A reminder cannot be completed by its own activity",
+            ));
+        }
+
+        let reminder = self.get_activity_by_id(reminder_activity_id).await?;
+        if reminder_block(&reminder).is_none() {
+            return Err(ActivityError::validation(
+                "reminder_activity_id",
+                "The activity has no reminder",
+            ));
+        }
+        let activity = self.get_activity_by_id(activity_id).await?;
+        if activity.pet_id != reminder.pet_id {
+            return Err(ActivityError::pet_mismatch(reminder.pet_id, activity.id));
+        }
+
+        let row = sqlx::query(
+            r#"
+            INSERT INTO reminder_completions (reminder_activity_id, activity_id, created_at)
+            VALUES (?, ?, ?)
+            ON CONFLICT (reminder_activity_id) DO UPDATE SET
+                activity_id = excluded.activity_id,
+                created_at = excluded.created_at
+            RETURNING *
+            "#,
+        )
+        .bind(reminder.id)
+        .bind(activity.id)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::debug!(
+            "[DB] complete_reminder: reminder id={} completed by id={}",
+            reminder.id,
+            activity.id
+        );
+        Ok(row_to_reminder_completion(&row))
+    }
+
+    /// Mark a reminder as not done again. Returns false if it wasn't completed.
+    pub async fn uncomplete_reminder(
+        &self,
+        reminder_activity_id: i64,
+    ) -> Result<bool, ActivityError> {
+        let result = sqlx::query("DELETE FROM reminder_completions WHERE reminder_activity_id = ?")
+            .bind(reminder_activity_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the reminder completions of a pet's activities
+    pub async fn get_reminder_completions(
+        &self,
+        pet_id: i64,
+    ) -> Result<Vec<ReminderCompletion>, ActivityError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT rc.* FROM reminder_completions rc
+            JOIN activities a ON a.id = rc.reminder_activity_id
+            WHERE a.pet_id = ?
+            ORDER BY rc.reminder_activity_id ASC
+            "#,
+        )
+        .bind(pet_id)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(rows.iter().map(row_to_reminder_completion).collect())
+    }
+
+    /// Get the reminders of a pet due between `start_date` and `end_date`
+    /// (inclusive) and whether each was done, skipped or is still upcoming
+    pub async fn get_reminder_compliance(
+        &self,
+        pet_id: i64,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+        today: NaiveDate,
+    ) -> Result<ReminderCompliance, ActivityError> {
+        if end_date < start_date {
+            return Err(ActivityError::validation(
+                "end_date",
+                "End date must not be before start date",
+            ));
+        }
+
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id: Some(pet_id),
+                format: None,
+            })
+            .await?;
+        let completions = self.get_reminder_completions(pet_id).await?;
+
+        let compliance = build_reminder_compliance(
+            pet_id,
+            &activities,
+            &completions,
+            (start_date, end_date),
+            today,
+        );
+        log::debug!(
+            "[DB] get_reminder_compliance: pet_id={pet_id}, done={}, skipped={}, upcoming={}",
+            compliance.done,
+            compliance.skipped,
+            compliance.upcoming
+        );
+        Ok(compliance)
+    }
+}
+
+/// Compliance report of the reminder blocks in `activities` due in `range`.
+/// Disabled reminders count only if they were completed.
+pub fn build_reminder_compliance(
+    pet_id: i64,
+    activities: &[Activity],
+    completions: &[ReminderCompletion],
+    (start_date, end_date): (NaiveDate, NaiveDate),
+    today: NaiveDate,
+) -> ReminderCompliance {
+    let mut items: Vec<ReminderComplianceItem> = activities
+        .iter()
+        .filter(|activity| activity.pet_id == pet_id)
+        .filter_map(|activity| {
+            let reminder = reminder_block(activity)?;
+            let due_at: DateTime<Utc> = parse_reminder_due_at(reminder)?;
+            let due_on = due_at.with_timezone(&Local).date_naive();
+            if due_on < start_date || due_on > end_date {
+                return None;
+            }
+
+            let completion = completions
+                .iter()
+                .find(|completion| completion.reminder_activity_id == activity.id);
+            let enabled = reminder.get("isEnabled").and_then(|v| v.as_bool()) != Some(false);
+            if !enabled && completion.is_none() {
+                return None;
+            }
+
+            let status = match completion {
+                Some(_) => ReminderComplianceStatus::Done,
+                None if due_on < today => ReminderComplianceStatus::Skipped,
+                None => ReminderComplianceStatus::Upcoming,
+            };
+            let completed_by = completion.map(|completion| completion.activity_id);
+            Some(ReminderComplianceItem {
+                reminder_activity_id: activity.id,
+                title: reminder_title(reminder, &activity.subcategory),
+                reminder_type: reminder
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                due_at,
+                status,
+                completed_by,
+                completed_on: completed_by.and_then(|id| {
+                    activities
+                        .iter()
+                        .find(|activity| activity.id == id)
+                        .map(Activity::occurred_on)
+                }),
+            })
+        })
+        .collect();
+    items.sort_by_key(|item| (item.due_at, item.reminder_activity_id));
+
+    let count = |status| items.iter().filter(|item| item.status == status).count();
+    let done = count(ReminderComplianceStatus::Done);
+    let skipped = count(ReminderComplianceStatus::Skipped);
+    let upcoming = count(ReminderComplianceStatus::Upcoming);
+    // Reminders done ahead of their day count as past
+    let past = done + skipped;
+
+    ReminderCompliance {
+        pet_id,
+        start_date,
+        end_date,
+        items,
+        done,
+        skipped,
+        upcoming,
+        completion_rate: (past > 0).then(|| done as f64 / past as f64),
+    }
+}
+
+fn row_to_reminder_completion(row: &sqlx::sqlite::SqliteRow) -> ReminderCompletion {
+    ReminderCompletion {
+        reminder_activity_id: row.get("reminder_activity_id"),
+        activity_id: row.get("activity_id"),
+        created_at: row.get::<DateTime<Utc>, _>("created_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::*;
+
+    fn reminder_request(pet_id: i64, date: &str, enabled: bool) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Health,
+            subcategory: "Medication".to_string(),
+            activity_data: Some(serde_json::json!({
+                "reminder": {
+                    "type": "medical",
+                    "title": "Heartworm pill",
+                    "reminderDate": date,
+                    "isEnabled": enabled,
+                },
+            })),
+            needs_review: false,
+        }
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, d).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reminder_compliance() {
+        let (db, _dir) = test_database().await;
+        let pet = db
+            .create_pet(CreatePetRequest {
+                name: "Biscuit".to_string(),
+                birth_date: NaiveDate::from_ymd_opt(2021, 6, 1).unwrap(),
+                species: PetSpecies::Dog,
+                gender: PetGender::Male,
+                breed: None,
+                color: None,
+                weight_kg: None,
+                photo_path: None,
+                notes: None,
+            })
+            .await
+            .unwrap();
+
+        let mut reminders = Vec::new();
+        for (day, enabled) in [(1, true), (5, true), (9, false), (12, false), (25, true)] {
+            let request = reminder_request(pet.id, &format!("2025-03-{day:02}"), enabled);
+            reminders.push(db.create_activity_with_side_effects(request).await.unwrap());
+        }
+        let pill = db
+            .create_activity_with_side_effects(ActivityCreateRequest {
+                pet_id: pet.id,
+                category: ActivityCategory::Health,
+                subcategory: "Medication".to_string(),
+                activity_data: Some(serde_json::json!({
+                    "time": { "date": "2025-03-02", "time": "", "timezone": "" },
+                })),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+
+        db.complete_reminder(reminders[0].id, pill.id)
+            .await
+            .unwrap();
+        // A disabled reminder still counts once it was done
+        let completion = db
+            .complete_reminder(reminders[3].id, pill.id)
+            .await
+            .unwrap();
+        assert_eq!(completion.activity_id, pill.id);
+        assert!(db
+            .complete_reminder(pill.id, reminders[0].id)
+            .await
+            .is_err());
+        assert!(db.complete_reminder(pill.id, pill.id).await.is_err());
+
+        let compliance = db
+            .get_reminder_compliance(pet.id, date(1), date(31), date(20))
+            .await
+            .unwrap();
+        let statuses: Vec<_> = compliance
+            .items
+            .iter()
+            .map(|item| (item.reminder_activity_id, item.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                (reminders[0].id, ReminderComplianceStatus::Done),
+                (reminders[1].id, ReminderComplianceStatus::Skipped),
+                (reminders[3].id, ReminderComplianceStatus::Done),
+                (reminders[4].id, ReminderComplianceStatus::Upcoming),
+            ]
+        );
+        assert_eq!(compliance.items[0].title, "Heartworm pill");
+        assert_eq!(compliance.items[0].completed_on, Some(date(2)));
+        assert_eq!(compliance.completion_rate, Some(2.0 / 3.0));
+
+        // Completed reminders are no longer announced
+        let now = "2025-03-20T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let due = db.get_due_reminders(now, 30).await.unwrap();
+        assert!(!due
+            .iter()
+            .any(|reminder| reminder.activity_id == reminders[0].id));
+        assert!(due
+            .iter()
+            .any(|reminder| reminder.activity_id == reminders[1].id));
+
+        assert!(db.uncomplete_reminder(reminders[0].id).await.unwrap());
+        assert!(!db.uncomplete_reminder(reminders[0].id).await.unwrap());
+
+        // Deleting the logged activity undoes the completion
+        db.delete_activity(pill.id).await.unwrap();
+        assert!(db
+            .get_reminder_completions(pet.id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+}
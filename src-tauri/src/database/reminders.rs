@@ -0,0 +1,300 @@
+use super::activity_data::BlockData;
+use super::models::*;
+use crate::errors::ActivityError;
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone, Utc};
+use sqlx::Row;
+
+impl super::PetDatabase {
+    /// Get enabled reminders from activity reminder blocks that are due at `now`.
+    /// Reminders older than `lookback_days` or already completed are ignored.
+    pub async fn get_due_reminders(
+        &self,
+        now: DateTime<Utc>,
+        lookback_days: i64,
+    ) -> Result<Vec<DueReminder>, ActivityError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, pet_id, subcategory,
+                json_extract(activity_data, '$.reminder') AS reminder
+            FROM activities
+            WHERE json_extract(activity_data, '$.reminder.reminderDate') IS NOT NULL
+                AND id NOT IN (SELECT reminder_activity_id FROM reminder_completions)
+            "#,
+        )
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let earliest = now - chrono::Duration::days(lookback_days);
+        let mut reminders = Vec::new();
+
+        for row in rows {
+            let reminder_json: Option<String> = row.try_get("reminder").ok().flatten();
+            let reminder: serde_json::Value = match reminder_json
+                .as_deref()
+                .and_then(|s| serde_json::from_str(s).ok())
+            {
+                Some(value) => value,
+                None => continue,
+            };
+
+            if reminder.get("isEnabled").and_then(|v| v.as_bool()) == Some(false) {
+                continue;
+            }
+
+            let due_at = match parse_reminder_due_at(&reminder) {
+                Some(due_at) => due_at,
+                None => continue,
+            };
+            if due_at > now || due_at < earliest {
+                continue;
+            }
+
+            let subcategory: String = row.try_get("subcategory").unwrap_or_default();
+            let title = reminder_title(&reminder, &subcategory);
+
+            reminders.push(DueReminder {
+                activity_id: row
+                    .try_get("id")
+                    .map_err(|e| ActivityError::invalid_data(format!("Invalid id: {e}")))?,
+                pet_id: row
+                    .try_get("pet_id")
+                    .map_err(|e| ActivityError::invalid_data(format!("Invalid pet_id: {e}")))?,
+                title,
+                reminder_type: reminder
+                    .get("type")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                due_at,
+            });
+        }
+
+        reminders.sort_by_key(|r| r.due_at);
+        Ok(reminders)
+    }
+
+    /// Get health visits whose follow-up date is before `today` and that have
+    /// no later health activity of the same subcategory
+    pub async fn get_overdue_followups(
+        &self,
+        pet_id: i64,
+        today: NaiveDate,
+    ) -> Result<Vec<OverdueFollowUp>, ActivityError> {
+        let activities = self
+            .export_activities(ExportActivitiesRequest {
+                pet_id: Some(pet_id),
+                format: None,
+            })
+            .await?;
+
+        let overdue = find_overdue_followups(&activities, today);
+        log::debug!(
+            "[DB] get_overdue_followups: pet_id={pet_id}, overdue={}",
+            overdue.len()
+        );
+        Ok(overdue)
+    }
+}
+
+/// Reminder types the health templates use for return visits
+const FOLLOW_UP_REMINDER_TYPES: [&str; 3] = ["next_checkup", "recheck", "follow_up"];
+
+/// Title keywords that mark a reminder as a follow-up visit
+const FOLLOW_UP_TITLE_KEYWORDS: [&str; 3] = ["follow-up", "followup", "recheck"];
+
+/// Follow-up date of an activity's reminder block, if the reminder is a follow-up visit
+fn follow_up_date(activity: &Activity) -> Option<NaiveDate> {
+    let reminder = reminder_block(activity)?;
+    let reminder_type = reminder.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    let title = reminder
+        .get("title")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_lowercase()
+        .replace(' ', "-");
+    let is_follow_up = FOLLOW_UP_REMINDER_TYPES.contains(&reminder_type)
+        || FOLLOW_UP_TITLE_KEYWORDS.iter().any(|k| title.contains(k));
+    if !is_follow_up {
+        return None;
+    }
+
+    parse_reminder_due_at(reminder).map(|due_at| due_at.with_timezone(&Local).date_naive())
+}
+
+/// Find follow-ups that are past due and not yet resolved by a later visit
+pub fn find_overdue_followups(activities: &[Activity], today: NaiveDate) -> Vec<OverdueFollowUp> {
+    let health: Vec<&Activity> = activities
+        .iter()
+        .filter(|a| a.category == ActivityCategory::Health)
+        .collect();
+
+    let mut overdue: Vec<OverdueFollowUp> = health
+        .iter()
+        .filter_map(|visit| {
+            let follow_up_date = follow_up_date(visit)?;
+            if follow_up_date >= today {
+                return None;
+            }
+
+            let visit_date = visit.occurred_on();
+            let resolved = health.iter().any(|later| {
+                later.id != visit.id
+                    && later.subcategory.eq_ignore_ascii_case(&visit.subcategory)
+                    && (later.occurred_on(), later.id) > (visit_date, visit.id)
+            });
+            if resolved {
+                return None;
+            }
+
+            let title = visit
+                .activity_data
+                .as_ref()
+                .and_then(|data| match data.get("reminder") {
+                    Some(BlockData::Other(reminder)) => reminder.get("title")?.as_str(),
+                    _ => None,
+                })
+                .filter(|t| !t.trim().is_empty())
+                .unwrap_or(&visit.subcategory)
+                .to_string();
+
+            Some(OverdueFollowUp {
+                activity_id: visit.id,
+                pet_id: visit.pet_id,
+                title,
+                subcategory: visit.subcategory.clone(),
+                visit_date,
+                follow_up_date,
+                days_overdue: (today - follow_up_date).num_days(),
+            })
+        })
+        .collect();
+
+    overdue.sort_by_key(|f| (f.follow_up_date, f.activity_id));
+    overdue
+}
+
+/// Reminder block of an activity
+pub(super) fn reminder_block(activity: &Activity) -> Option<&serde_json::Value> {
+    match activity.activity_data.as_ref()?.get("reminder")? {
+        BlockData::Other(value) => Some(value),
+        _ => None,
+    }
+}
+
+/// Title of a reminder block, or the activity's subcategory if it has none
+pub(super) fn reminder_title(reminder: &serde_json::Value, subcategory: &str) -> String {
+    reminder
+        .get("title")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(subcategory)
+        .to_string()
+}
+
+/// Combine the reminder block's date and optional local time into a UTC instant
+pub(super) fn parse_reminder_due_at(reminder: &serde_json::Value) -> Option<DateTime<Utc>> {
+    let date_str = reminder.get("reminderDate")?.as_str()?;
+    let local_date = DateTime::parse_from_rfc3339(date_str)
+        .ok()
+        .map(|dt| dt.with_timezone(&Local).date_naive())
+        .or_else(|| chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok())?;
+
+    let time = reminder
+        .get("reminderTime")
+        .and_then(|v| v.as_str())
+        .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok())
+        .unwrap_or(NaiveTime::MIN);
+
+    Local
+        .from_local_datetime(&local_date.and_time(time))
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visit(
+        id: i64,
+        subcategory: &str,
+        date: &str,
+        reminder: Option<serde_json::Value>,
+    ) -> Activity {
+        let mut data = serde_json::json!({
+            "time": { "date": date, "time": "", "timezone": "" },
+        });
+        if let Some(reminder) = reminder {
+            data["reminder"] = reminder;
+        }
+        Activity {
+            id,
+            pet_id: 1,
+            category: ActivityCategory::Health,
+            subcategory: subcategory.to_string(),
+            activity_data: serde_json::from_value(data).ok(),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn follow_up(date: &str) -> serde_json::Value {
+        serde_json::json!({
+            "type": "next_checkup",
+            "title": "Recheck ear infection",
+            "reminderDate": date,
+            "isEnabled": true,
+        })
+    }
+
+    #[test]
+    fn test_overdue_followup_detected() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 20).unwrap();
+        let activities = vec![visit(
+            1,
+            "Checkup",
+            "2025-03-01",
+            Some(follow_up("2025-03-15")),
+        )];
+
+        let overdue = find_overdue_followups(&activities, today);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].title, "Recheck ear infection");
+        assert_eq!(overdue[0].days_overdue, 5);
+    }
+
+    #[test]
+    fn test_followup_resolved_by_later_visit() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 20).unwrap();
+        let activities = vec![
+            visit(1, "Checkup", "2025-03-01", Some(follow_up("2025-03-15"))),
+            visit(2, "checkup", "2025-03-16", None),
+        ];
+        assert!(find_overdue_followups(&activities, today).is_empty());
+
+        // A later visit of another kind does not resolve it
+        let activities = vec![
+            visit(1, "Checkup", "2025-03-01", Some(follow_up("2025-03-15"))),
+            visit(2, "Vaccination", "2025-03-16", None),
+        ];
+        assert_eq!(find_overdue_followups(&activities, today).len(), 1);
+    }
+
+    #[test]
+    fn test_future_and_non_followup_reminders_ignored() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 20).unwrap();
+        let medication = serde_json::json!({
+            "type": "medical",
+            "title": "Medication Time",
+            "reminderDate": "2025-03-10",
+            "isEnabled": true,
+        });
+        let activities = vec![
+            visit(1, "Checkup", "2025-03-01", Some(follow_up("2025-04-01"))),
+            visit(2, "Medication", "2025-03-01", Some(medication)),
+        ];
+        assert!(find_overdue_followups(&activities, today).is_empty());
+    }
+}
@@ -0,0 +1,459 @@
+//! Dual-read validation of the typed-block and generated-column schema.
+//!
+//! While enabled, every activity read is also parsed the legacy way, straight from
+//! its raw JSON, and compared with what the typed blocks and the generated columns
+//! (`activity_time`, `cost_amount`, `location_key`) make of it. Differences go to the
+//! `schema_discrepancies` table, so a refactor can be checked against years of data
+//! before the legacy path is dropped.
+
+use super::activity_schema::{upgrade, SCHEMA_VERSION_KEY};
+use super::models::Activity;
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::Row;
+use std::sync::atomic::Ordering;
+
+/// Setting that turns dual-read validation on
+pub const SCHEMA_VALIDATION_SETTING_KEY: &str = "schema_validation";
+
+/// Most discrepancies returned in one report
+const MAX_REPORTED_DISCREPANCIES: i64 = 500;
+
+/// A value one read path produced differently from the other
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaDiscrepancy {
+    pub activity_id: i64,
+    /// Generated column name, or "block.<key>" for a typed block
+    pub field: String,
+    /// Value read straight from the raw JSON
+    pub legacy_value: Option<String>,
+    /// Value from the generated column or the re-serialized typed block
+    pub new_value: Option<String>,
+    /// Reads that found this difference
+    pub occurrences: i64,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// Recorded discrepancies, most recently seen first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaValidationReport {
+    pub enabled: bool,
+    pub total: i64,
+    pub activities_affected: i64,
+    pub discrepancies: Vec<SchemaDiscrepancy>,
+}
+
+/// Outcome of checking every stored activity at once
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SchemaValidationScan {
+    pub scanned: usize,
+    pub discrepancies: usize,
+    pub activities_affected: usize,
+}
+
+/// Generated column values of a row; None for columns the query didn't select
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GeneratedColumns {
+    pub activity_time: Option<Option<String>>,
+    pub cost_amount: Option<Option<f64>>,
+    pub location_key: Option<Option<String>>,
+}
+
+impl GeneratedColumns {
+    pub fn from_row(row: &sqlx::sqlite::SqliteRow) -> Self {
+        GeneratedColumns {
+            activity_time: row.try_get("activity_time").ok(),
+            cost_amount: row.try_get("cost_amount").ok(),
+            location_key: row.try_get("location_key").ok(),
+        }
+    }
+}
+
+/// A difference found between the two read paths, before it is recorded
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadPathDifference {
+    pub field: String,
+    pub legacy_value: Option<String>,
+    pub new_value: Option<String>,
+}
+
+impl super::PetDatabase {
+    /// Whether activity reads are currently checked against the legacy path
+    pub fn schema_validation_enabled(&self) -> bool {
+        self.schema_validation.load(Ordering::Relaxed)
+    }
+
+    /// Turn dual-read validation on or off and remember the choice
+    pub async fn set_schema_validation(&self, enabled: bool) -> Result<(), ActivityError> {
+        self.set_setting(SCHEMA_VALIDATION_SETTING_KEY, &enabled)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        self.schema_validation.store(enabled, Ordering::Relaxed);
+        log::info!("[DB] set_schema_validation: enabled={enabled}");
+        Ok(())
+    }
+
+    /// Apply the stored validation setting, off unless it was turned on
+    pub async fn load_schema_validation(&self) -> Result<bool, ActivityError> {
+        let enabled = self
+            .get_setting::<bool>(SCHEMA_VALIDATION_SETTING_KEY)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .unwrap_or(false);
+        self.schema_validation.store(enabled, Ordering::Relaxed);
+        Ok(enabled)
+    }
+
+    /// Compare a freshly read activity with its raw JSON and record the differences.
+    /// Failures are logged and never fail the read.
+    pub(super) async fn validate_read_paths(
+        &self,
+        activity: &Activity,
+        stored: Option<&Value>,
+        columns: &GeneratedColumns,
+    ) {
+        let differences = compare_read_paths(activity, stored, columns);
+        if differences.is_empty() {
+            return;
+        }
+        log::warn!(
+            "[DB] Schema validation: activity {} differs in {}",
+            activity.id,
+            differences
+                .iter()
+                .map(|d| d.field.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        if let Err(e) = self.record_discrepancies(activity.id, &differences).await {
+            log::warn!("Failed to record schema discrepancies: {e}");
+        }
+    }
+
+    async fn record_discrepancies(
+        &self,
+        activity_id: i64,
+        differences: &[ReadPathDifference],
+    ) -> Result<(), ActivityError> {
+        let now = Utc::now();
+        for difference in differences {
+            sqlx::query(
+                r#"
+                INSERT INTO schema_discrepancies
+                    (activity_id, field, legacy_value, new_value, first_seen_at, last_seen_at)
+                VALUES (?, ?, ?, ?, ?, ?)
+                ON CONFLICT(activity_id, field) DO UPDATE SET
+                    legacy_value = excluded.legacy_value,
+                    new_value = excluded.new_value,
+                    occurrences = occurrences + 1,
+                    last_seen_at = excluded.last_seen_at
+                "#,
+            )
+            .bind(activity_id)
+            .bind(&difference.field)
+            .bind(&difference.legacy_value)
+            .bind(&difference.new_value)
+            .bind(now)
+            .bind(now)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+        Ok(())
+    }
+
+    /// Recorded discrepancies, most recently seen first
+    pub async fn get_schema_validation_report(
+        &self,
+        limit: Option<i64>,
+    ) -> Result<SchemaValidationReport, ActivityError> {
+        let limit = limit
+            .unwrap_or(MAX_REPORTED_DISCREPANCIES)
+            .clamp(1, MAX_REPORTED_DISCREPANCIES);
+        let totals = sqlx::query(
+            "SELECT COUNT(*) AS total, COUNT(DISTINCT activity_id) AS activities FROM schema_discrepancies",
+        )
+        .fetch_one(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let rows = sqlx::query(
+            "SELECT * FROM schema_discrepancies ORDER BY last_seen_at DESC, id DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(SchemaValidationReport {
+            enabled: self.schema_validation_enabled(),
+            total: totals.get("total"),
+            activities_affected: totals.get("activities"),
+            discrepancies: rows.iter().map(row_to_discrepancy).collect(),
+        })
+    }
+
+    /// Check every stored activity now instead of waiting for it to be read.
+    /// Replaces the previous report; works whether or not the mode is enabled.
+    pub async fn scan_schema_discrepancies(&self) -> Result<SchemaValidationScan, ActivityError> {
+        sqlx::query("DELETE FROM schema_discrepancies")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let rows = sqlx::query("SELECT * FROM activities ORDER BY id")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut scan = SchemaValidationScan::default();
+        for row in &rows {
+            let activity = self.read_activity_row(row).await?;
+            let differences = compare_read_paths(
+                &activity,
+                stored_activity_data(row).as_ref(),
+                &GeneratedColumns::from_row(row),
+            );
+            scan.scanned += 1;
+            if differences.is_empty() {
+                continue;
+            }
+            scan.discrepancies += differences.len();
+            scan.activities_affected += 1;
+            self.record_discrepancies(activity.id, &differences).await?;
+        }
+
+        log::info!(
+            "[DB] scan_schema_discrepancies: scanned={}, discrepancies={}, activities={}",
+            scan.scanned,
+            scan.discrepancies,
+            scan.activities_affected
+        );
+        Ok(scan)
+    }
+}
+
+/// Raw JSON payload of an activity row, as stored
+pub fn stored_activity_data(row: &sqlx::sqlite::SqliteRow) -> Option<Value> {
+    row.try_get::<Option<String>, _>("activity_data")
+        .ok()
+        .flatten()
+        .and_then(|json| serde_json::from_str(&json).ok())
+}
+
+/// Differences between the legacy reading of an activity's raw JSON and the typed
+/// blocks and generated columns. Raw values must survive the typed round-trip;
+/// defaults the typed blocks add are not differences.
+pub fn compare_read_paths(
+    activity: &Activity,
+    stored: Option<&Value>,
+    columns: &GeneratedColumns,
+) -> Vec<ReadPathDifference> {
+    let mut legacy = stored.cloned().unwrap_or(Value::Null);
+    // The typed path reads upgraded payloads, so compare with the same version
+    let _ = upgrade(&mut legacy);
+    let mut differences = Vec::new();
+
+    if let Some(column) = &columns.activity_time {
+        let legacy_time = legacy
+            .pointer("/time/date")
+            .and_then(Value::as_str)
+            .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+            .map(|date| date.with_timezone(&Utc))
+            .unwrap_or(activity.created_at)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        if column.as_deref() != Some(legacy_time.as_str()) {
+            differences.push(ReadPathDifference {
+                field: "activity_time".to_string(),
+                legacy_value: Some(legacy_time),
+                new_value: column.clone(),
+            });
+        }
+    }
+
+    if let Some(column) = &columns.cost_amount {
+        let legacy_cost = legacy
+            .pointer("/cost/amount")
+            .and_then(|amount| match amount {
+                Value::Number(n) => n.as_f64(),
+                Value::String(s) => s.trim().parse().ok(),
+                _ => None,
+            });
+        let same = match (legacy_cost, column) {
+            (Some(a), Some(b)) => numbers_match(a, *b),
+            (None, None) => true,
+            _ => false,
+        };
+        if !same {
+            differences.push(ReadPathDifference {
+                field: "cost_amount".to_string(),
+                legacy_value: legacy_cost.map(|a| a.to_string()),
+                new_value: column.map(|a| a.to_string()),
+            });
+        }
+    }
+
+    if let Some(column) = &columns.location_key {
+        let legacy_key = legacy
+            .pointer("/location/name")
+            .and_then(Value::as_str)
+            .map(|name| name.trim().to_lowercase())
+            .filter(|name| !name.is_empty());
+        if *column != legacy_key {
+            differences.push(ReadPathDifference {
+                field: "location_key".to_string(),
+                legacy_value: legacy_key,
+                new_value: column.clone(),
+            });
+        }
+    }
+
+    if let Some(blocks) = legacy.as_object() {
+        let typed = activity.activity_data.as_ref();
+        for (key, raw) in blocks {
+            if key == SCHEMA_VERSION_KEY {
+                continue;
+            }
+            let typed_block = typed
+                .and_then(|data| data.get(key))
+                .and_then(|block| serde_json::to_value(block).ok());
+            let kept = match &typed_block {
+                Some(block) => survives(raw, block),
+                None => raw.is_null(),
+            };
+            if !kept {
+                differences.push(ReadPathDifference {
+                    field: format!("block.{key}"),
+                    legacy_value: Some(raw.to_string()),
+                    new_value: typed_block.map(|block| block.to_string()),
+                });
+            }
+        }
+    }
+
+    differences
+}
+
+/// Whether a raw JSON value comes back unchanged from its typed block. Nulls may be
+/// dropped, numbers may lose float precision or be kept as numeric strings.
+fn survives(raw: &Value, typed: &Value) -> bool {
+    match (raw, typed) {
+        (Value::Null, _) => true,
+        (Value::Object(raw), Value::Object(typed)) => raw
+            .iter()
+            .all(|(key, value)| survives(value, typed.get(key).unwrap_or(&Value::Null))),
+        (Value::Array(raw), Value::Array(typed)) => {
+            raw.len() == typed.len() && raw.iter().zip(typed).all(|(r, t)| survives(r, t))
+        }
+        (Value::Number(raw), Value::Number(typed)) => match (raw.as_f64(), typed.as_f64()) {
+            (Some(a), Some(b)) => numbers_match(a, b),
+            _ => raw == typed,
+        },
+        (Value::Number(raw), Value::String(typed)) => {
+            match (raw.as_f64(), typed.trim().parse::<f64>()) {
+                (Some(a), Ok(b)) => numbers_match(a, b),
+                _ => false,
+            }
+        }
+        _ => raw == typed,
+    }
+}
+
+/// Equal up to the precision lost by storing amounts as `f32`
+fn numbers_match(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-6 * a.abs().max(b.abs()).max(1.0)
+}
+
+fn row_to_discrepancy(row: &sqlx::sqlite::SqliteRow) -> SchemaDiscrepancy {
+    SchemaDiscrepancy {
+        activity_id: row.get("activity_id"),
+        field: row.get("field"),
+        legacy_value: row.get("legacy_value"),
+        new_value: row.get("new_value"),
+        occurrences: row.get("occurrences"),
+        first_seen_at: row.get::<DateTime<Utc>, _>("first_seen_at"),
+        last_seen_at: row.get::<DateTime<Utc>, _>("last_seen_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_round_trip_keeps_raw_values() {
+        assert!(survives(
+            &json!({ "amount": 0.1, "unit": "g", "brand": null }),
+            &json!({ "amount": 0.1f32 as f64, "unit": "g", "product": null }),
+        ));
+        assert!(survives(
+            &json!({ "value": 5.2 }),
+            &json!({ "value": "5.2" })
+        ));
+        assert!(!survives(
+            &json!({ "amount": 2, "unit": "g", "note": "half" }),
+            &json!({ "amount": 2, "unit": "g" }),
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_dual_read_records_discrepancies() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let activity = db
+            .create_activity(ActivityCreateRequest {
+                pet_id,
+                category: ActivityCategory::Expense,
+                subcategory: "Purchase".to_string(),
+                activity_data: Some(json!({
+                    "time": { "date": "2024-03-02T08:30:00.000Z", "time": "", "timezone": "" },
+                    "cost": { "amount": 12.5, "currency": "USD" },
+                    "location": { "name": " Pet Shop " }
+                })),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+
+        assert!(!db.schema_validation_enabled());
+        db.set_schema_validation(true).await.unwrap();
+        db.get_activity_by_id(activity.id).await.unwrap();
+        let report = db.get_schema_validation_report(None).await.unwrap();
+        assert!(report.enabled);
+        assert_eq!(report.total, 0);
+
+        // SQLite reads a date format the typed path doesn't
+        sqlx::query("UPDATE activities SET activity_data = json_set(activity_data, '$.time.date', '2024-03-02 08:30') WHERE id = ?")
+            .bind(activity.id)
+            .execute(&db.pool)
+            .await
+            .unwrap();
+        db.get_activity_by_id(activity.id).await.unwrap();
+        db.get_activity_by_id(activity.id).await.unwrap();
+        let report = db.get_schema_validation_report(None).await.unwrap();
+        assert_eq!(report.activities_affected, 1);
+        let time = report
+            .discrepancies
+            .iter()
+            .find(|d| d.field == "activity_time")
+            .unwrap();
+        assert_eq!(time.occurrences, 2);
+
+        let scan = db.scan_schema_discrepancies().await.unwrap();
+        assert_eq!(scan.scanned, 1);
+        assert_eq!(scan.activities_affected, 1);
+        assert!(db.load_schema_validation().await.unwrap());
+    }
+}
@@ -0,0 +1,996 @@
+use super::activity_data::BlockData;
+use super::fts::{ACTIVITY_FTS_COLUMNS, DOCUMENT_FTS_COLUMNS};
+use super::search_query::{MatchKind, QueryClause, SearchQuery};
+use super::FtsTokenizer;
+use crate::errors::ActivityError;
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::HashMap;
+
+/// Default number of results returned by a global search
+const DEFAULT_SEARCH_LIMIT: i64 = 30;
+
+/// Kind of entity a search result points at
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SearchResultType {
+    Pet,
+    Activity,
+    Document,
+}
+
+impl std::fmt::Display for SearchResultType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchResultType::Pet => write!(f, "pet"),
+            SearchResultType::Activity => write!(f, "activity"),
+            SearchResultType::Document => write!(f, "document"),
+        }
+    }
+}
+
+/// A single hit from the app-wide search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalSearchResult {
+    pub result_type: SearchResultType,
+    pub id: i64,
+    pub pet_id: i64,
+    pub title: String,
+    pub snippet: Option<String>,
+    /// Relevance normalized to 0.0..=1.0, comparable across result types
+    pub score: f64,
+    pub matched_field: String,
+}
+
+/// Number of matches sharing a facet value
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FacetCount {
+    pub value: String,
+    pub count: i64,
+}
+
+/// Number of matches belonging to a pet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PetFacetCount {
+    pub pet_id: i64,
+    pub pet_name: String,
+    pub count: i64,
+}
+
+/// Aggregate counts over every full-text match, not just the returned page,
+/// so the UI can show filter chips with counts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SearchFacets {
+    /// Activity categories, most matches first
+    pub categories: Vec<FacetCount>,
+    /// Pets of matching activities and documents, most matches first
+    pub pets: Vec<PetFacetCount>,
+    /// Years of matching activities (activity time) and documents (upload), newest first
+    pub years: Vec<FacetCount>,
+}
+
+/// Merged results of a global search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GlobalSearchResponse {
+    pub query: String,
+    /// How the query was read, for showing next to the results
+    pub parsed: SearchQuery,
+    pub results: Vec<GlobalSearchResult>,
+    pub facets: SearchFacets,
+}
+
+/// One group of the facet query: matches sharing category, pet and year
+#[derive(Debug, Clone)]
+struct FacetCell {
+    category: Option<String>,
+    pet_id: i64,
+    pet_name: String,
+    year: Option<String>,
+    count: i64,
+}
+
+impl super::PetDatabase {
+    /// Search pets, activities and documents and merge the hits by relevance
+    pub async fn global_search(
+        &self,
+        query: &str,
+        limit: Option<i64>,
+    ) -> Result<GlobalSearchResponse, ActivityError> {
+        let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, 200);
+        let parsed = SearchQuery::parse(query);
+
+        log::debug!(
+            "[DB] global_search: query='{query}', parsed='{}', limit={limit}",
+            parsed.interpretation
+        );
+
+        if parsed.is_empty() {
+            return Ok(GlobalSearchResponse {
+                query: query.to_string(),
+                parsed,
+                results: Vec::new(),
+                facets: SearchFacets::default(),
+            });
+        }
+
+        let tokenizer = self
+            .get_fts_tokenizer()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let filter = FtsFilter::new(&parsed, tokenizer);
+        let mut results = self.global_search_pets(&parsed).await?;
+        results.extend(self.global_search_activities(&filter, limit).await?);
+        results.extend(self.global_search_documents(&filter, limit).await?);
+        let facets = self.search_facets(&filter).await?;
+
+        results.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.result_type.to_string().cmp(&b.result_type.to_string()))
+                .then_with(|| b.id.cmp(&a.id))
+        });
+        results.truncate(limit as usize);
+
+        log::debug!("[DB] global_search: {} results", results.len());
+        Ok(GlobalSearchResponse {
+            query: query.to_string(),
+            parsed,
+            results,
+            facets,
+        })
+    }
+
+    /// Pets matching any alternative of the query. Alternatives limited to a
+    /// field only match activities and documents.
+    async fn global_search_pets(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<Vec<GlobalSearchResult>, ActivityError> {
+        let alternatives: Vec<Vec<String>> = query
+            .groups
+            .iter()
+            .filter(|group| group.iter().all(|clause| clause.field.is_none()))
+            .map(|group| group.iter().map(|clause| clause.text.clone()).collect())
+            .collect();
+        if alternatives.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let rows = sqlx::query("SELECT id, name, breed, notes FROM pets WHERE is_archived = 0")
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let aliases = self
+            .get_all_pet_aliases()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|row| {
+                let id: i64 = row.get("id");
+                let name: String = row.get("name");
+                let breed: Option<String> = row.get("breed");
+                let notes: Option<String> = row.get("notes");
+                let pet_aliases = aliases.get(&id).map(Vec::as_slice).unwrap_or_default();
+
+                let (score, matched_field, snippet) = alternatives
+                    .iter()
+                    .filter_map(|terms| score_pet(terms, &name, pet_aliases, &breed, &notes))
+                    .max_by(|a, b| a.0.total_cmp(&b.0))?;
+                Some(GlobalSearchResult {
+                    result_type: SearchResultType::Pet,
+                    id,
+                    pet_id: id,
+                    title: name,
+                    snippet,
+                    score,
+                    matched_field: matched_field.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    async fn global_search_activities(
+        &self,
+        filter: &FtsFilter,
+        limit: i64,
+    ) -> Result<Vec<GlobalSearchResult>, ActivityError> {
+        let hits = self.fts_search_activities_matching(filter, limit).await?;
+
+        Ok(hits
+            .into_iter()
+            .map(|hit| {
+                let snippet = hit.activity.activity_data.as_ref().and_then(|data| {
+                    ["title", "notes"]
+                        .iter()
+                        .find_map(|key| match data.get(*key) {
+                            Some(BlockData::Text(text)) if !text.trim().is_empty() => {
+                                Some(text.clone())
+                            }
+                            _ => None,
+                        })
+                });
+
+                GlobalSearchResult {
+                    result_type: SearchResultType::Activity,
+                    id: hit.activity.id,
+                    pet_id: hit.activity.pet_id,
+                    title: hit.activity.subcategory.clone(),
+                    snippet,
+                    score: normalize_bm25(hit.rank),
+                    matched_field: "activity_data".to_string(),
+                }
+            })
+            .collect())
+    }
+
+    async fn global_search_documents(
+        &self,
+        filter: &FtsFilter,
+        limit: i64,
+    ) -> Result<Vec<GlobalSearchResult>, ActivityError> {
+        // snippet() needs a MATCH; a scan-only search shows the start of the notes instead
+        let snippet = if filter.has_match(DOCUMENT_FTS_COLUMNS) {
+            "snippet(pet_documents_fts, -1, '', '', '...', 12)"
+        } else {
+            "substr(COALESCE(d.notes, d.ocr_text, ''), 1, 80)"
+        };
+        let sql = format!(
+            r#"
+            SELECT d.id, d.pet_id, d.title,
+                {snippet} AS snippet,
+                {} AS rank
+            FROM pet_documents_fts
+            JOIN pet_documents d ON d.id = pet_documents_fts.rowid
+            WHERE {}
+            ORDER BY rank
+            LIMIT ?
+            "#,
+            filter.rank("pet_documents_fts", DOCUMENT_FTS_COLUMNS),
+            filter.condition("pet_documents_fts", DOCUMENT_FTS_COLUMNS)
+        );
+        let mut select = sqlx::query(&sql);
+        for value in filter.binds(DOCUMENT_FTS_COLUMNS) {
+            select = select.bind(value);
+        }
+        let rows = select
+            .bind(limit)
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Document search error: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| GlobalSearchResult {
+                result_type: SearchResultType::Document,
+                id: row.get("id"),
+                pet_id: row.get("pet_id"),
+                title: row.get("title"),
+                snippet: row.get("snippet"),
+                score: normalize_bm25(row.get("rank")),
+                matched_field: "document".to_string(),
+            })
+            .collect())
+    }
+
+    /// Count activity and document matches per category, pet and year in one grouped query
+    async fn search_facets(&self, filter: &FtsFilter) -> Result<SearchFacets, ActivityError> {
+        let searches_private = self
+            .get_privacy_settings()
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .searches_private();
+        let sql = format!(
+            r#"
+            SELECT m.category, m.pet_id, p.name AS pet_name, m.year, COUNT(*) AS count
+            FROM (
+                SELECT a.category AS category, a.pet_id AS pet_id,
+                    substr(a.activity_time, 1, 4) AS year
+                FROM activities_fts
+                JOIN activities a ON a.id = activities_fts.rowid
+                WHERE {} AND (a.is_private = 0 OR ?)
+                UNION ALL
+                SELECT NULL, d.pet_id, substr(d.created_at, 1, 4)
+                FROM pet_documents_fts
+                JOIN pet_documents d ON d.id = pet_documents_fts.rowid
+                WHERE {}
+            ) m
+            JOIN pets p ON p.id = m.pet_id
+            GROUP BY m.category, m.pet_id, m.year
+            "#,
+            filter.condition("activities_fts", ACTIVITY_FTS_COLUMNS),
+            filter.condition("pet_documents_fts", DOCUMENT_FTS_COLUMNS)
+        );
+        let mut select = sqlx::query(&sql);
+        for value in filter.binds(ACTIVITY_FTS_COLUMNS) {
+            select = select.bind(value);
+        }
+        select = select.bind(searches_private);
+        for value in filter.binds(DOCUMENT_FTS_COLUMNS) {
+            select = select.bind(value);
+        }
+        let rows = select
+            .fetch_all(&self.read_pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Search facet error: {e}")))?;
+
+        let cells: Vec<FacetCell> = rows
+            .iter()
+            .map(|row| FacetCell {
+                category: row.get("category"),
+                pet_id: row.get("pet_id"),
+                pet_name: row.get("pet_name"),
+                year: row.get("year"),
+                count: row.get("count"),
+            })
+            .collect();
+        Ok(fold_facets(&cells))
+    }
+}
+
+/// Roll grouped cells up into one count list per facet
+fn fold_facets(cells: &[FacetCell]) -> SearchFacets {
+    let mut categories: HashMap<&str, i64> = HashMap::new();
+    let mut pets: HashMap<i64, (&str, i64)> = HashMap::new();
+    let mut years: HashMap<&str, i64> = HashMap::new();
+
+    for cell in cells {
+        if let Some(category) = &cell.category {
+            *categories.entry(category).or_default() += cell.count;
+        }
+        pets.entry(cell.pet_id).or_insert((&cell.pet_name, 0)).1 += cell.count;
+        if let Some(year) = &cell.year {
+            *years.entry(year).or_default() += cell.count;
+        }
+    }
+
+    let mut categories: Vec<FacetCount> = categories
+        .into_iter()
+        .map(|(value, count)| FacetCount {
+            value: value.to_string(),
+            count,
+        })
+        .collect();
+    categories.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+
+    let mut pets: Vec<PetFacetCount> = pets
+        .into_iter()
+        .map(|(pet_id, (pet_name, count))| PetFacetCount {
+            pet_id,
+            pet_name: pet_name.to_string(),
+            count,
+        })
+        .collect();
+    pets.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.pet_name.cmp(&b.pet_name))
+            .then_with(|| a.pet_id.cmp(&b.pet_id))
+    });
+
+    let mut years: Vec<FacetCount> = years
+        .into_iter()
+        .map(|(value, count)| FacetCount {
+            value: value.to_string(),
+            count,
+        })
+        .collect();
+    years.sort_by(|a, b| b.value.cmp(&a.value));
+
+    SearchFacets {
+        categories,
+        pets,
+        years,
+    }
+}
+
+/// Column of the activity index holding the blocks as JSON, where fields such
+/// as `notes:` are found by key
+const BLOCKS_COLUMN: &str = "activity_data";
+
+/// Where a clause is looked for in an index
+#[derive(Debug, Clone, PartialEq)]
+enum ClauseTarget<'a> {
+    /// Any of these indexed columns
+    Columns(Vec<&'a str>),
+    /// A block inside the blocks column
+    Block(&'static str),
+}
+
+/// A condition matched by scanning rather than through the index
+#[derive(Debug, Clone, PartialEq)]
+struct Scan<'a> {
+    target: ClauseTarget<'a>,
+    pattern: String,
+}
+
+/// How one index matches a query: the `MATCH` expression, if any, and scan
+/// conditions where any group must hold and every condition of that group
+#[derive(Debug, Default, PartialEq)]
+struct IndexPlan<'a> {
+    match_query: Option<String>,
+    scans: Vec<Vec<Scan<'a>>>,
+}
+
+/// Full-text condition for a parsed query under the tokenizer the indexes use.
+/// A trigram index can't match text shorter than three characters (two-character
+/// Chinese words are common), and activity fields live inside the blocks column,
+/// so such clauses are matched by scanning the indexed columns. The index can't be
+/// used inside an `OR` of SQL conditions, so alternatives that need a scan are
+/// scanned in full.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct FtsFilter {
+    query: SearchQuery,
+    min_term_chars: usize,
+}
+
+impl FtsFilter {
+    pub(super) fn new(query: &SearchQuery, tokenizer: FtsTokenizer) -> Self {
+        FtsFilter {
+            query: query.clone(),
+            min_term_chars: tokenizer.min_term_chars(),
+        }
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.query.is_empty()
+    }
+
+    /// Whether the condition on an index with `columns` uses the index, which
+    /// bm25() and snippet() need
+    pub(super) fn has_match(&self, columns: &[&str]) -> bool {
+        self.plan(columns).match_query.is_some()
+    }
+
+    /// `WHERE` condition on the FTS `table` indexing `columns`
+    pub(super) fn condition(&self, table: &str, columns: &[&str]) -> String {
+        let plan = self.plan(columns);
+        let mut parts = Vec::new();
+        if plan.match_query.is_some() {
+            parts.push(format!("{table} MATCH ?"));
+        }
+        let groups: Vec<String> = plan
+            .scans
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(|scan| scan_condition(table, scan))
+                    .collect::<Vec<_>>()
+                    .join(" AND ")
+            })
+            .collect();
+        match groups.len() {
+            0 => {}
+            1 => parts.push(groups[0].clone()),
+            _ => parts.push(format!("(({}))", groups.join(") OR ("))),
+        }
+        if parts.is_empty() {
+            "0".to_string()
+        } else {
+            parts.join(" AND ")
+        }
+    }
+
+    /// Values for the placeholders of [`FtsFilter::condition`], in order
+    pub(super) fn binds(&self, columns: &[&str]) -> Vec<String> {
+        let plan = self.plan(columns);
+        let mut values: Vec<String> = plan.match_query.into_iter().collect();
+        for scan in plan.scans.iter().flatten() {
+            let placeholders = match &scan.target {
+                ClauseTarget::Columns(columns) => columns.len(),
+                ClauseTarget::Block(_) => 1,
+            };
+            values.extend((0..placeholders).map(|_| scan.pattern.clone()));
+        }
+        values
+    }
+
+    /// Rank expression, lower is better; scan-only matches all rank the same
+    pub(super) fn rank(&self, table: &str, columns: &[&str]) -> String {
+        if self.has_match(columns) {
+            format!("bm25({table})")
+        } else {
+            "0.0".to_string()
+        }
+    }
+
+    fn plan<'a>(&self, columns: &[&'a str]) -> IndexPlan<'a> {
+        // Alternatives asking for a field this index lacks can't match here
+        let groups: Vec<Vec<(&QueryClause, ClauseTarget<'a>)>> = self
+            .query
+            .groups
+            .iter()
+            .filter_map(|group| {
+                group
+                    .iter()
+                    .map(|clause| Some((clause, clause_target(clause, columns)?)))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect();
+        let indexed = |clause: &QueryClause, target: &ClauseTarget| {
+            matches!(target, ClauseTarget::Columns(_))
+                && clause.text.chars().count() >= self.min_term_chars
+        };
+        let scan = |clause: &QueryClause, target: &ClauseTarget<'a>| Scan {
+            target: target.clone(),
+            // Clause text is alphanumeric words and spaces, so it needs no LIKE escaping
+            pattern: format!("%{}%", clause.text),
+        };
+
+        match groups.as_slice() {
+            [] => IndexPlan::default(),
+            [group] => {
+                let (matched, scanned): (Vec<_>, Vec<_>) = group
+                    .iter()
+                    .partition(|(clause, target)| indexed(clause, target));
+                IndexPlan {
+                    match_query: (!matched.is_empty()).then(|| {
+                        matched
+                            .iter()
+                            .map(|(clause, target)| match_clause(clause, target, columns))
+                            .collect::<Vec<_>>()
+                            .join(" AND ")
+                    }),
+                    scans: if scanned.is_empty() {
+                        Vec::new()
+                    } else {
+                        vec![scanned
+                            .iter()
+                            .map(|(clause, target)| scan(clause, target))
+                            .collect()]
+                    },
+                }
+            }
+            _ if groups.iter().flatten().all(|(c, t)| indexed(c, t)) => IndexPlan {
+                match_query: Some(
+                    groups
+                        .iter()
+                        .map(|group| {
+                            let clauses: Vec<String> = group
+                                .iter()
+                                .map(|(clause, target)| match_clause(clause, target, columns))
+                                .collect();
+                            format!("({})", clauses.join(" AND "))
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" OR "),
+                ),
+                scans: Vec::new(),
+            },
+            _ => IndexPlan {
+                match_query: None,
+                scans: groups
+                    .iter()
+                    .map(|group| group.iter().map(|(c, t)| scan(c, t)).collect())
+                    .collect(),
+            },
+        }
+    }
+}
+
+/// Where an index with `columns` holds a clause, or None if it can't
+fn clause_target<'a>(clause: &QueryClause, columns: &[&'a str]) -> Option<ClauseTarget<'a>> {
+    let Some(field) = clause.field else {
+        return Some(ClauseTarget::Columns(columns.to_vec()));
+    };
+    if columns.contains(&field.column()) {
+        return Some(ClauseTarget::Columns(vec![field.column()]));
+    }
+    field
+        .block_key()
+        .filter(|_| columns.contains(&BLOCKS_COLUMN))
+        .map(ClauseTarget::Block)
+}
+
+/// FTS5 expression for an indexed clause, e.g. `notes : "ear drops"` or
+/// `"vacc"*`. Text is always quoted, so it is never read as an operator.
+fn match_clause(clause: &QueryClause, target: &ClauseTarget, columns: &[&str]) -> String {
+    let text = format!("\"{}\"", clause.text.replace('"', "\"\""));
+    let text = match clause.kind {
+        MatchKind::Prefix => format!("{text}*"),
+        MatchKind::Word | MatchKind::Phrase => text,
+    };
+    match target {
+        ClauseTarget::Columns(only) if only.len() < columns.len() => {
+            format!("{{{}}} : {text}", only.join(" "))
+        }
+        _ => text,
+    }
+}
+
+/// SQL condition for a scan on the FTS `table`, with one placeholder per column
+fn scan_condition(table: &str, scan: &Scan) -> String {
+    match &scan.target {
+        ClauseTarget::Columns(columns) => {
+            let any_column = columns
+                .iter()
+                .map(|column| format!("{table}.{column} LIKE ?"))
+                .collect::<Vec<_>>()
+                .join(" OR ");
+            format!("({any_column})")
+        }
+        ClauseTarget::Block(key) => {
+            format!("json_extract({table}.{BLOCKS_COLUMN}, '$.{key}') LIKE ?")
+        }
+    }
+}
+
+/// Map an FTS5 bm25 rank (negative, lower is better) into 0.5..0.9.
+/// bm25 is tiny on small corpora, so any full-text hit keeps a base score
+/// and ranks below a pet name match but above a match in pet notes.
+fn normalize_bm25(rank: f64) -> f64 {
+    let magnitude = rank.abs();
+    0.5 + 0.4 * magnitude / (1.0 + magnitude)
+}
+
+/// Score a pet against all terms; every term must match one of the fields.
+/// Aliases score like the name, just below it.
+fn score_pet(
+    terms: &[String],
+    name: &str,
+    aliases: &[String],
+    breed: &Option<String>,
+    notes: &Option<String>,
+) -> Option<(f64, &'static str, Option<String>)> {
+    let name_lc = name.to_lowercase();
+    let aliases_lc: Vec<String> = aliases.iter().map(|alias| alias.to_lowercase()).collect();
+    let breed_lc = breed.as_deref().unwrap_or_default().to_lowercase();
+    let notes_lc = notes.as_deref().unwrap_or_default().to_lowercase();
+
+    let mut total = 0.0;
+    let mut best: Option<(f64, &'static str)> = None;
+    let mut matched_alias: Option<usize> = None;
+
+    for term in terms {
+        let term = term.as_str();
+        let (score, field) = if name_lc == term {
+            (1.0, "name")
+        } else if let Some(index) = aliases_lc.iter().position(|alias| alias == term) {
+            matched_alias.get_or_insert(index);
+            (0.95, "alias")
+        } else if name_lc.starts_with(term) {
+            (0.9, "name")
+        } else if let Some(index) = aliases_lc.iter().position(|alias| alias.starts_with(term)) {
+            matched_alias.get_or_insert(index);
+            (0.85, "alias")
+        } else if name_lc.contains(term) {
+            (0.75, "name")
+        } else if let Some(index) = aliases_lc.iter().position(|alias| alias.contains(term)) {
+            matched_alias.get_or_insert(index);
+            (0.7, "alias")
+        } else if breed_lc.contains(term) {
+            (0.6, "breed")
+        } else if notes_lc.contains(term) {
+            (0.4, "notes")
+        } else {
+            return None;
+        };
+
+        total += score;
+        if best.is_none_or(|(best_score, _)| score > best_score) {
+            best = Some((score, field));
+        }
+    }
+
+    let (_, field) = best?;
+    let snippet = match field {
+        "alias" => matched_alias.map(|index| aliases[index].clone()),
+        "breed" => breed.clone(),
+        "notes" => notes.clone(),
+        _ => None,
+    };
+    Some((total / terms.len() as f64, field, snippet))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[test]
+    fn test_fts_filter_builds_quoted_match_expressions() {
+        let query = SearchQuery::parse("notes:antibiotic vacc* OR \"ear drops\"");
+        let filter = FtsFilter::new(&query, FtsTokenizer::Unicode61);
+        assert_eq!(
+            filter.binds(DOCUMENT_FTS_COLUMNS),
+            vec!["({notes} : \"antibiotic\" AND \"vacc\"*) OR (\"ear drops\")"]
+        );
+        assert_eq!(filter.condition("t", DOCUMENT_FTS_COLUMNS), "t MATCH ?");
+
+        // Activity notes live in the blocks column, and an OR can't mix a scan
+        // with the index
+        assert!(!filter.has_match(ACTIVITY_FTS_COLUMNS));
+        assert_eq!(
+            filter.condition("t", ACTIVITY_FTS_COLUMNS),
+            "((json_extract(t.activity_data, '$.notes') LIKE ? AND \
+             (t.subcategory LIKE ? OR t.activity_data LIKE ?)) OR \
+             ((t.subcategory LIKE ? OR t.activity_data LIKE ?)))"
+        );
+        assert_eq!(
+            filter.binds(ACTIVITY_FTS_COLUMNS),
+            vec![
+                "%antibiotic%",
+                "%vacc%",
+                "%vacc%",
+                "%ear drops%",
+                "%ear drops%"
+            ]
+        );
+
+        let filter = FtsFilter::new(
+            &SearchQuery::parse("notes:antibiotic ear"),
+            FtsTokenizer::Unicode61,
+        );
+        assert_eq!(
+            filter.condition("t", ACTIVITY_FTS_COLUMNS),
+            "t MATCH ? AND json_extract(t.activity_data, '$.notes') LIKE ?"
+        );
+        assert_eq!(filter.rank("t", ACTIVITY_FTS_COLUMNS), "bm25(t)");
+
+        // Activities have no recognized text
+        let filter = FtsFilter::new(&SearchQuery::parse("text:rabies"), FtsTokenizer::Unicode61);
+        assert_eq!(filter.condition("t", ACTIVITY_FTS_COLUMNS), "0");
+        assert!(!filter.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_trigram_tokenizer_finds_chinese_words() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let walk = db
+            .create_activity(super::super::ActivityCreateRequest {
+                pet_id: summary.pet_ids[0],
+                category: super::super::ActivityCategory::Lifestyle,
+                subcategory: "Walk".to_string(),
+                activity_data: Some(serde_json::json!({ "notes": "今天带它去公园散步" })),
+                needs_review: false,
+            })
+            .await
+            .unwrap();
+        let activity_ids = |response: GlobalSearchResponse| -> Vec<i64> {
+            response
+                .results
+                .iter()
+                .filter(|r| r.result_type == SearchResultType::Activity)
+                .map(|r| r.id)
+                .collect()
+        };
+
+        // unicode61 treats the whole sentence as one word
+        assert!(activity_ids(db.global_search("散步", None).await.unwrap()).is_empty());
+
+        let stats = db.set_fts_tokenizer(FtsTokenizer::Trigram).await.unwrap();
+        assert_eq!(stats.document_count, 1);
+        assert_eq!(db.get_fts_tokenizer().await.unwrap(), FtsTokenizer::Trigram);
+        for query in ["散步", "公园散步", "walk"] {
+            let response = db.global_search(query, None).await.unwrap();
+            assert_eq!(activity_ids(response), vec![walk.id], "query {query}");
+        }
+
+        // New activities are indexed by the recreated table
+        db.create_activity(super::super::ActivityCreateRequest {
+            pet_id: summary.pet_ids[0],
+            category: super::super::ActivityCategory::Health,
+            subcategory: "Checkup".to_string(),
+            activity_data: Some(serde_json::json!({ "notes": "打疫苗" })),
+            needs_review: false,
+        })
+        .await
+        .unwrap();
+        let response = db.global_search("疫苗", None).await.unwrap();
+        assert_eq!(response.facets.categories[0].value, "health");
+    }
+
+    #[test]
+    fn test_fts_filter_scans_short_trigram_terms() {
+        let query = SearchQuery::parse("散步 rabies");
+        let filter = FtsFilter::new(&query, FtsTokenizer::Trigram);
+        assert_eq!(
+            filter.condition("t", &["a", "b"]),
+            "t MATCH ? AND (t.a LIKE ? OR t.b LIKE ?)"
+        );
+        assert_eq!(
+            filter.binds(&["a", "b"]),
+            vec!["\"rabies\"*", "%散步%", "%散步%"]
+        );
+
+        let filter = FtsFilter::new(&query, FtsTokenizer::Unicode61);
+        assert_eq!(filter.condition("t", &["a"]), "t MATCH ?");
+        assert_eq!(filter.rank("t", &["a"]), "bm25(t)");
+    }
+
+    #[test]
+    fn test_score_pet_prefers_name_matches() {
+        let breed = Some("Golden Retriever".to_string());
+        let notes = Some("Loves the beach".to_string());
+
+        let (exact, field, _) =
+            score_pet(&["buddy".to_string()], "Buddy", &[], &breed, &notes).unwrap();
+        assert_eq!(field, "name");
+        assert_eq!(exact, 1.0);
+
+        let (by_breed, field, snippet) =
+            score_pet(&["golden".to_string()], "Buddy", &[], &breed, &notes).unwrap();
+        assert_eq!(field, "breed");
+        assert!(by_breed < exact);
+        assert_eq!(snippet, breed);
+
+        assert!(score_pet(
+            &["golden".to_string(), "cat".to_string()],
+            "Buddy",
+            &[],
+            &breed,
+            &notes
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_score_pet_matches_aliases() {
+        let aliases = vec!["豆豆".to_string(), "Bean".to_string()];
+
+        let (by_alias, field, snippet) =
+            score_pet(&["豆豆".to_string()], "Doudou", &aliases, &None, &None).unwrap();
+        assert_eq!(field, "alias");
+        assert_eq!(snippet.as_deref(), Some("豆豆"));
+
+        let (by_prefix, _, snippet) =
+            score_pet(&["bea".to_string()], "Doudou", &aliases, &None, &None).unwrap();
+        assert!(by_prefix < by_alias);
+        assert_eq!(snippet.as_deref(), Some("Bean"));
+
+        // The pet's own name still ranks first
+        let (by_name, field, _) =
+            score_pet(&["doudou".to_string()], "Doudou", &aliases, &None, &None).unwrap();
+        assert_eq!(field, "name");
+        assert!(by_name > by_alias);
+    }
+
+    #[test]
+    fn test_normalize_bm25() {
+        assert_eq!(normalize_bm25(0.0), 0.5);
+        assert!(normalize_bm25(-5.0) > normalize_bm25(-1.0));
+        assert!(normalize_bm25(-100.0) < 0.9);
+    }
+
+    #[test]
+    fn test_fold_facets() {
+        let cell = |category: Option<&str>, pet_id: i64, year: &str, count: i64| FacetCell {
+            category: category.map(str::to_string),
+            pet_id,
+            pet_name: format!("Pet {pet_id}"),
+            year: Some(year.to_string()),
+            count,
+        };
+        let facets = fold_facets(&[
+            cell(Some("health"), 1, "2024", 2),
+            cell(Some("health"), 2, "2025", 1),
+            cell(Some("diet"), 1, "2025", 4),
+            // A document match has no activity category
+            cell(None, 2, "2025", 3),
+        ]);
+
+        assert_eq!(
+            facets.categories,
+            vec![
+                FacetCount {
+                    value: "diet".to_string(),
+                    count: 4
+                },
+                FacetCount {
+                    value: "health".to_string(),
+                    count: 3
+                },
+            ]
+        );
+        assert_eq!(
+            facets
+                .pets
+                .iter()
+                .map(|p| (p.pet_id, p.count))
+                .collect::<Vec<_>>(),
+            vec![(1, 6), (2, 4)]
+        );
+        assert_eq!(
+            facets
+                .years
+                .iter()
+                .map(|y| (y.value.as_str(), y.count))
+                .collect::<Vec<_>>(),
+            vec![("2025", 8), ("2024", 2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_global_search_facets_cover_all_matches() {
+        let (db, _dir, _) = seeded_database(&FixtureConfig::default()).await;
+
+        let response = db.global_search("visit", Some(5)).await.unwrap();
+        assert_eq!(response.results.len(), 5);
+
+        let facets = &response.facets;
+        assert_eq!(facets.categories.len(), 1);
+        assert_eq!(facets.categories[0].value, "health");
+        let total = facets.categories[0].count;
+        assert!(total > 5, "facets must not be limited to the page");
+        assert_eq!(facets.pets.iter().map(|p| p.count).sum::<i64>(), total);
+        assert_eq!(facets.years.iter().map(|y| y.count).sum::<i64>(), total);
+    }
+
+    #[tokio::test]
+    async fn test_global_search_field_filters_and_alternatives() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let log =
+            |subcategory: &str, data: serde_json::Value| super::super::ActivityCreateRequest {
+                pet_id: summary.pet_ids[0],
+                category: super::super::ActivityCategory::Health,
+                subcategory: subcategory.to_string(),
+                activity_data: Some(data),
+                needs_review: false,
+            };
+        let in_notes = db
+            .create_activity(log(
+                "Medication",
+                serde_json::json!({ "notes": "Started antibiotic for the ear" }),
+            ))
+            .await
+            .unwrap();
+        let in_title = db
+            .create_activity(log(
+                "Medication",
+                serde_json::json!({ "title": "Antibiotic refill", "notes": "Ear drops" }),
+            ))
+            .await
+            .unwrap();
+        let activity_ids = |response: GlobalSearchResponse| -> Vec<i64> {
+            let mut ids: Vec<i64> = response
+                .results
+                .iter()
+                .filter(|r| r.result_type == SearchResultType::Activity)
+                .map(|r| r.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+
+        let response = db.global_search("notes:antibiotic", None).await.unwrap();
+        assert_eq!(response.parsed.interpretation, "notes:antibiotic");
+        assert_eq!(activity_ids(response), vec![in_notes.id]);
+
+        let response = db
+            .global_search("\"ear drops\" OR started", None)
+            .await
+            .unwrap();
+        assert_eq!(activity_ids(response), vec![in_notes.id, in_title.id]);
+
+        // Whole words unless asked for a prefix
+        let response = db.global_search("antibio", None).await.unwrap();
+        assert_eq!(
+            activity_ids(response).len(),
+            2,
+            "plain queries match prefixes"
+        );
+        let response = db.global_search("antibio AND ear", None).await.unwrap();
+        assert!(activity_ids(response).is_empty());
+        let response = db.global_search("antibio* AND ear", None).await.unwrap();
+        assert_eq!(activity_ids(response).len(), 2);
+
+        // A broken query still searches
+        let response = db
+            .global_search("title:\"antibiotic refill", None)
+            .await
+            .unwrap();
+        assert_eq!(response.parsed.warnings.len(), 1);
+        assert_eq!(activity_ids(response), vec![in_title.id]);
+    }
+}
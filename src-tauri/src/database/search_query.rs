@@ -0,0 +1,368 @@
+//! Search query syntax.
+//!
+//! The search bar understands a small, forgiving subset of FTS5 syntax:
+//! `"ear drops"` matches a phrase, `vacc*` a prefix, `notes:antibiotic` a field,
+//! and `OR` joins alternatives while words side by side (or joined by `AND`) must
+//! all match. Queries are never passed to FTS5 as written; every word is quoted
+//! again when the match expression is built, so a stray operator can't break a
+//! search. Anything the parser can't make sense of is searched as plain text
+//! and explained in `warnings`.
+
+use serde::{Deserialize, Serialize};
+
+/// Fields a clause can be limited to with `field:`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchField {
+    /// Activity subcategory
+    Subcategory,
+    /// Activity title block or document title
+    Title,
+    /// Activity notes block or document notes
+    Notes,
+    /// Text recognized in documents
+    Text,
+}
+
+impl SearchField {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "subcategory" | "type" => Some(SearchField::Subcategory),
+            "title" => Some(SearchField::Title),
+            "notes" | "note" => Some(SearchField::Notes),
+            "text" | "ocr" => Some(SearchField::Text),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchField::Subcategory => "subcategory",
+            SearchField::Title => "title",
+            SearchField::Notes => "notes",
+            SearchField::Text => "text",
+        }
+    }
+
+    /// Index column holding the field
+    pub(super) fn column(&self) -> &'static str {
+        match self {
+            SearchField::Subcategory => "subcategory",
+            SearchField::Title => "title",
+            SearchField::Notes => "notes",
+            SearchField::Text => "ocr_text",
+        }
+    }
+
+    /// Key of the activity block holding the field, for indexes that store
+    /// blocks as one JSON column
+    pub(super) fn block_key(&self) -> Option<&'static str> {
+        match self {
+            SearchField::Title => Some("title"),
+            SearchField::Notes => Some("notes"),
+            SearchField::Subcategory | SearchField::Text => None,
+        }
+    }
+}
+
+/// How a clause matches its text
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// A whole word
+    Word,
+    /// Any word starting with the text
+    Prefix,
+    /// The words in order
+    Phrase,
+}
+
+/// One thing a result has to contain
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueryClause {
+    pub field: Option<SearchField>,
+    /// Lowercased text to match
+    pub text: String,
+    pub kind: MatchKind,
+}
+
+impl std::fmt::Display for QueryClause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(field) = self.field {
+            write!(f, "{}:", field.name())?;
+        }
+        match self.kind {
+            MatchKind::Word => write!(f, "{}", self.text),
+            MatchKind::Prefix => write!(f, "{}*", self.text),
+            MatchKind::Phrase => write!(f, "\"{}\"", self.text),
+        }
+    }
+}
+
+/// A search query as the app understood it: results match any group, and
+/// every clause of that group
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SearchQuery {
+    pub groups: Vec<Vec<QueryClause>>,
+    /// The groups written back out, e.g. `notes:antibiotic AND ear* OR "ear drops"`
+    pub interpretation: String,
+    /// Parts of the query that were ignored or read as plain text
+    pub warnings: Vec<String>,
+}
+
+/// A piece of the query between operators
+enum Token {
+    Or,
+    And,
+    Clauses(Vec<QueryClause>),
+}
+
+impl SearchQuery {
+    /// Parse a search bar query. A query without any syntax matches every word
+    /// as a prefix, so results keep up while the user is still typing.
+    pub fn parse(query: &str) -> Self {
+        let mut warnings = Vec::new();
+        let (tokens, uses_syntax) = tokenize(query, &mut warnings);
+
+        let mut groups: Vec<Vec<QueryClause>> = vec![Vec::new()];
+        let mut dangling_or = false;
+        for token in tokens {
+            match token {
+                Token::Or => {
+                    if groups.last().is_some_and(Vec::is_empty) {
+                        dangling_or = true;
+                    } else {
+                        groups.push(Vec::new());
+                    }
+                }
+                // Clauses side by side must all match anyway
+                Token::And => {}
+                Token::Clauses(clauses) => groups
+                    .last_mut()
+                    .expect("groups start non-empty")
+                    .extend(clauses),
+            }
+        }
+        if groups.last().is_some_and(Vec::is_empty) {
+            groups.pop();
+            dangling_or |= !groups.is_empty();
+        }
+        if dangling_or {
+            warnings.push("OR needs words on both sides; the extra OR was ignored".to_string());
+        }
+
+        if !uses_syntax {
+            for clause in groups.iter_mut().flatten() {
+                clause.kind = MatchKind::Prefix;
+            }
+        }
+
+        let interpretation = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .map(QueryClause::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" AND ")
+            })
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        SearchQuery {
+            groups,
+            interpretation,
+            warnings,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+/// Split a query into operators and clauses, returning whether any search syntax
+/// was used
+fn tokenize(query: &str, warnings: &mut Vec<String>) -> (Vec<Token>, bool) {
+    let mut tokens = Vec::new();
+    let mut uses_syntax = false;
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' {
+            chars.next();
+            uses_syntax = true;
+            tokens.extend(read_phrase(&mut chars, None, warnings));
+            continue;
+        }
+
+        let mut word = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == '"' {
+                break;
+            }
+            word.push(c);
+            chars.next();
+        }
+
+        match word.as_str() {
+            "OR" | "|" => {
+                uses_syntax = true;
+                tokens.push(Token::Or);
+                continue;
+            }
+            "AND" | "&" => {
+                uses_syntax = true;
+                tokens.push(Token::And);
+                continue;
+            }
+            _ => {}
+        }
+
+        // `field:` directly followed by a quoted phrase
+        if let Some(name) = word.strip_suffix(':') {
+            if chars.peek() == Some(&'"') {
+                if let Some(field) = SearchField::parse(name) {
+                    chars.next();
+                    uses_syntax = true;
+                    tokens.extend(read_phrase(&mut chars, Some(field), warnings));
+                    continue;
+                }
+            }
+        }
+
+        let (field, text) = match word.split_once(':') {
+            Some((name, text)) if !name.is_empty() => match SearchField::parse(name) {
+                Some(field) => {
+                    uses_syntax = true;
+                    (Some(field), text)
+                }
+                None => {
+                    warnings.push(format!(
+                        "Unknown field \"{name}\"; searched \"{word}\" as text"
+                    ));
+                    (None, word.as_str())
+                }
+            },
+            _ => (None, word.as_str()),
+        };
+
+        let prefix = text.ends_with('*');
+        uses_syntax |= prefix;
+        let words: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|piece| !piece.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        if words.is_empty() {
+            if let Some(field) = field {
+                warnings.push(format!(
+                    "Nothing to search for after \"{}:\"; ignored",
+                    field.name()
+                ));
+            }
+            continue;
+        }
+
+        let last = words.len() - 1;
+        let clauses = words
+            .into_iter()
+            .enumerate()
+            .map(|(i, text)| QueryClause {
+                field,
+                text,
+                kind: if prefix && i == last {
+                    MatchKind::Prefix
+                } else {
+                    MatchKind::Word
+                },
+            })
+            .collect();
+        tokens.push(Token::Clauses(clauses));
+    }
+
+    (tokens, uses_syntax)
+}
+
+/// Read a phrase after its opening quote. A phrase left open runs to the end of
+/// the query; a phrase of one word is just that word.
+fn read_phrase(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    field: Option<SearchField>,
+    warnings: &mut Vec<String>,
+) -> Option<Token> {
+    let mut phrase = String::new();
+    let mut closed = false;
+    for c in chars.by_ref() {
+        if c == '"' {
+            closed = true;
+            break;
+        }
+        phrase.push(c);
+    }
+    if !closed {
+        warnings.push("Missing closing quote; the phrase runs to the end".to_string());
+    }
+
+    let words: Vec<String> = phrase
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|piece| !piece.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    let kind = match words.len() {
+        0 => return None,
+        1 => MatchKind::Word,
+        _ => MatchKind::Phrase,
+    };
+    Some(Token::Clauses(vec![QueryClause {
+        field,
+        text: words.join(" "),
+        kind,
+    }]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_queries_match_prefixes() {
+        let query = SearchQuery::parse("  Rabies-vaccine (2025) ");
+        assert_eq!(query.interpretation, "rabies* AND vaccine* AND 2025*");
+        assert!(query.warnings.is_empty());
+        assert!(SearchQuery::parse("*** \"").is_empty());
+    }
+
+    #[test]
+    fn test_query_syntax() {
+        let query = SearchQuery::parse("notes:Antibiotic vacc* OR title:\"Ear Drops\" AND 散步");
+        assert_eq!(
+            query.interpretation,
+            "notes:antibiotic AND vacc* OR title:\"ear drops\" AND 散步"
+        );
+        assert_eq!(query.groups.len(), 2);
+        assert_eq!(
+            query.groups[1][0],
+            QueryClause {
+                field: Some(SearchField::Title),
+                text: "ear drops".to_string(),
+                kind: MatchKind::Phrase,
+            }
+        );
+        assert!(query.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_syntax_errors_fall_back_to_text() {
+        let query = SearchQuery::parse("OR color:brown \"ear drops OR");
+        assert_eq!(query.interpretation, "color AND brown AND \"ear drops or\"");
+        assert_eq!(query.warnings.len(), 3, "{:?}", query.warnings);
+
+        let query = SearchQuery::parse("walk OR OR park notes:");
+        assert_eq!(query.interpretation, "walk OR park");
+        assert_eq!(query.warnings.len(), 2, "{:?}", query.warnings);
+    }
+}
@@ -0,0 +1,103 @@
+use super::models::{FtsTokenizer, WeightUnit, FTS_TOKENIZER_SETTING_KEY, WEIGHT_UNIT_SETTING_KEY};
+use crate::export::{ExportSettings, EXPORT_SETTINGS_KEY};
+use crate::notifications::{NotificationSettings, NOTIFICATION_SETTINGS_KEY};
+use crate::periods::{PeriodSettings, PERIOD_SETTINGS_KEY};
+use crate::privacy::{PrivacySettings, PRIVACY_SETTINGS_KEY};
+use crate::validation::{ActivityDateLimits, ACTIVITY_DATE_LIMITS_SETTING_KEY};
+use anyhow::Result;
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Serialize};
+
+impl super::PetDatabase {
+    /// Read a setting stored as JSON, returning None when it has never been set
+    pub async fn get_setting<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let value: Option<String> =
+            sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+                .bind(key)
+                .fetch_optional(&self.read_pool)
+                .await?;
+
+        match value {
+            Some(json) => Ok(Some(serde_json::from_str(&json).map_err(|e| {
+                anyhow::anyhow!("Invalid value stored for setting '{}': {}", key, e)
+            })?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Store a setting as JSON, replacing any previous value
+    pub async fn set_setting<T: Serialize>(&self, key: &str, value: &T) -> Result<()> {
+        let json = serde_json::to_string(value)?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO app_settings (key, value, updated_at) VALUES (?, ?, ?)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(key)
+        .bind(&json)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        log::debug!("[DB] set_setting: key={key}");
+        Ok(())
+    }
+
+    /// Preferred unit for presenting weights, kilograms unless changed
+    pub async fn get_weight_unit(&self) -> Result<WeightUnit> {
+        Ok(self
+            .get_setting::<WeightUnit>(WEIGHT_UNIT_SETTING_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Tokenizer the full-text search indexes were last built with
+    pub async fn get_fts_tokenizer(&self) -> Result<FtsTokenizer> {
+        Ok(self
+            .get_setting::<FtsTokenizer>(FTS_TOKENIZER_SETTING_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Week start and month boundaries for time-bucketed statistics
+    pub async fn get_period_settings(&self) -> Result<PeriodSettings> {
+        Ok(self
+            .get_setting::<PeriodSettings>(PERIOD_SETTINGS_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// How far in the past and future activities may be dated, per category
+    pub async fn get_activity_date_limits(&self) -> Result<ActivityDateLimits> {
+        Ok(self
+            .get_setting::<ActivityDateLimits>(ACTIVITY_DATE_LIMITS_SETTING_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Activity categories kept out of exports and webhook deliveries
+    pub async fn get_privacy_settings(&self) -> Result<PrivacySettings> {
+        Ok(self
+            .get_setting::<PrivacySettings>(PRIVACY_SETTINGS_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// File name pattern and folder of exports
+    pub async fn get_export_settings(&self) -> Result<ExportSettings> {
+        Ok(self
+            .get_setting::<ExportSettings>(EXPORT_SETTINGS_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// App-wide quiet hours and reminder batching
+    pub async fn get_notification_settings(&self) -> Result<NotificationSettings> {
+        Ok(self
+            .get_setting::<NotificationSettings>(NOTIFICATION_SETTINGS_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,244 @@
+use super::activity_data::BlockData;
+use super::models::*;
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use sqlx::{Row, SqliteConnection};
+
+/// Blocks whose last values are remembered to prefill new activities
+const REMEMBERED_BLOCKS: [&str; 2] = ["portion", "timer"];
+
+/// Distinct values kept per pet, subcategory and field
+const MAX_VALUES_PER_FIELD: i64 = 20;
+
+impl super::PetDatabase {
+    /// Remember the reusable values of a new activity for its pet and subcategory
+    pub async fn record_smart_defaults(
+        conn: &mut SqliteConnection,
+        activity: &Activity,
+    ) -> Result<(), ActivityError> {
+        let fields = default_fields(activity);
+        if fields.is_empty() {
+            return Ok(());
+        }
+        let subcategory = subcategory_key(&activity.subcategory);
+        let now = Utc::now();
+
+        for (field, value) in fields {
+            let value = value.to_string();
+            sqlx::query(
+                r#"
+                INSERT INTO pet_defaults (pet_id, subcategory, field, value, use_count, last_used_at)
+                VALUES (?, ?, ?, ?, 1, ?)
+                ON CONFLICT (pet_id, subcategory, field, value)
+                DO UPDATE SET use_count = use_count + 1, last_used_at = excluded.last_used_at
+                "#,
+            )
+            .bind(activity.pet_id)
+            .bind(&subcategory)
+            .bind(field)
+            .bind(&value)
+            .bind(now)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+            // Forget the least recently used values
+            sqlx::query(
+                r#"
+                DELETE FROM pet_defaults
+                WHERE pet_id = ? AND subcategory = ? AND field = ? AND value NOT IN (
+                    SELECT value FROM pet_defaults
+                    WHERE pet_id = ? AND subcategory = ? AND field = ?
+                    ORDER BY last_used_at DESC
+                    LIMIT ?
+                )
+                "#,
+            )
+            .bind(activity.pet_id)
+            .bind(&subcategory)
+            .bind(field)
+            .bind(activity.pet_id)
+            .bind(&subcategory)
+            .bind(field)
+            .bind(MAX_VALUES_PER_FIELD)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+
+        log::debug!(
+            "[DB] record_smart_defaults: pet_id={}, subcategory={subcategory}",
+            activity.pet_id
+        );
+        Ok(())
+    }
+
+    /// Latest and most used values of each remembered field for a pet's subcategory
+    pub async fn get_smart_defaults(
+        &self,
+        pet_id: i64,
+        subcategory: &str,
+    ) -> Result<SmartDefaults, ActivityError> {
+        let subcategory = subcategory_key(subcategory);
+        let rows = sqlx::query(
+            r#"
+            SELECT field, value, use_count, last_used_at
+            FROM pet_defaults
+            WHERE pet_id = ? AND subcategory = ?
+            ORDER BY field ASC, last_used_at DESC
+            "#,
+        )
+        .bind(pet_id)
+        .bind(&subcategory)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut fields: Vec<SmartDefault> = Vec::new();
+        for row in rows {
+            let field: String = row.get("field");
+            let value: String = row.get("value");
+            let value = serde_json::from_str(&value).unwrap_or(serde_json::Value::String(value));
+            let use_count: i64 = row.get("use_count");
+            let last_used_at = row.get::<DateTime<Utc>, _>("last_used_at");
+
+            match fields.last_mut().filter(|d| d.field == field) {
+                // Rows are newest first, so the first row of a field is its latest value
+                Some(default) => {
+                    if use_count > default.usual_count {
+                        default.usual_value = value;
+                        default.usual_count = use_count;
+                    }
+                    default.times_used += use_count;
+                }
+                None => fields.push(SmartDefault {
+                    field,
+                    last_value: value.clone(),
+                    last_used_at,
+                    usual_value: value,
+                    usual_count: use_count,
+                    times_used: use_count,
+                }),
+            }
+        }
+
+        log::debug!(
+            "[DB] get_smart_defaults: pet_id={pet_id}, subcategory={subcategory}, fields={}",
+            fields.len()
+        );
+        Ok(SmartDefaults {
+            pet_id,
+            subcategory,
+            fields,
+        })
+    }
+}
+
+fn subcategory_key(subcategory: &str) -> String {
+    subcategory.trim().to_lowercase()
+}
+
+/// Values of an activity worth remembering: whole portion and timer blocks,
+/// and the portion's brand on its own so the preferred brand can be found
+pub fn default_fields(activity: &Activity) -> Vec<(&'static str, serde_json::Value)> {
+    let Some(data) = activity.activity_data.as_ref() else {
+        return Vec::new();
+    };
+
+    let mut fields: Vec<(&'static str, serde_json::Value)> = REMEMBERED_BLOCKS
+        .iter()
+        .filter_map(|key| {
+            let value = serde_json::to_value(data.get(*key)?).ok()?;
+            (!value.is_null()).then_some((*key, value))
+        })
+        .collect();
+
+    if let Some(BlockData::Portion {
+        brand: Some(brand), ..
+    }) = data.get("portion")
+    {
+        let brand = brand.trim();
+        if !brand.is_empty() {
+            fields.push(("brand", serde_json::Value::String(brand.to_string())));
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::*;
+
+    fn feeding(pet_id: i64, amount: f32, brand: &str) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Diet,
+            subcategory: "Feeding".to_string(),
+            activity_data: Some(serde_json::json!({
+                "portion": {
+                    "amount": amount,
+                    "unit": "g",
+                    "portionType": "meal",
+                    "brand": brand,
+                    "product": null
+                },
+                "notes": "Ate everything"
+            })),
+            needs_review: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_smart_defaults_track_latest_and_usual_values() {
+        let (db, _dir) = test_database().await;
+        let pet = db
+            .create_pet(CreatePetRequest {
+                name: "Mochi".to_string(),
+                birth_date: chrono::NaiveDate::from_ymd_opt(2022, 2, 1).unwrap(),
+                species: PetSpecies::Cat,
+                gender: PetGender::Female,
+                breed: None,
+                color: None,
+                weight_kg: None,
+                photo_path: None,
+                notes: None,
+            })
+            .await
+            .unwrap();
+
+        for (amount, brand) in [
+            (60.0, "Royal Canin"),
+            (60.0, "Royal Canin"),
+            (55.0, "Royal Canin"),
+            (80.0, " Orijen "),
+        ] {
+            db.create_activity_with_side_effects(feeding(pet.id, amount, brand))
+                .await
+                .unwrap();
+        }
+
+        let defaults = db.get_smart_defaults(pet.id, " feeding ").await.unwrap();
+        assert_eq!(defaults.subcategory, "feeding");
+        let fields: Vec<_> = defaults.fields.iter().map(|f| f.field.as_str()).collect();
+        assert_eq!(fields, vec!["brand", "portion"]);
+
+        let brand = &defaults.fields[0];
+        assert_eq!(brand.last_value, serde_json::json!("Orijen"));
+        assert_eq!(brand.usual_value, serde_json::json!("Royal Canin"));
+        assert_eq!(brand.usual_count, 3);
+        assert_eq!(brand.times_used, 4);
+
+        let portion = &defaults.fields[1];
+        assert_eq!(portion.last_value["amount"], serde_json::json!(80.0));
+        assert_eq!(portion.usual_value["amount"], serde_json::json!(60.0));
+
+        // Nothing remembered for other subcategories
+        assert!(db
+            .get_smart_defaults(pet.id, "Treat")
+            .await
+            .unwrap()
+            .fields
+            .is_empty());
+    }
+}
@@ -0,0 +1,448 @@
+use super::activity_data::ActivityDataExt;
+use super::footprint::DataFootprint;
+use crate::errors::{ActivityError, PetError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::{BTreeSet, HashMap};
+
+/// Settings key the storage quota is stored under
+pub const STORAGE_QUOTA_SETTING_KEY: &str = "storage_quota";
+
+/// Photos smaller than this aren't worth re-encoding
+pub const MIN_REENCODE_BYTES: u64 = 200 * 1024;
+
+/// Attachments last used before this many days ago are suggested for deletion
+pub const STALE_ATTACHMENT_DAYS: i64 = 365;
+
+/// Suggestions returned when no amount of space to free is given
+pub const DEFAULT_CLEANUP_SUGGESTIONS: usize = 50;
+
+/// How much disk space the app may use before warning the user
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct StorageQuota {
+    /// Total bytes for the database, photos and documents; None means unlimited
+    pub max_bytes: Option<u64>,
+    /// Share of the quota, in percent, at which the app starts warning
+    pub warn_percent: u8,
+}
+
+impl Default for StorageQuota {
+    fn default() -> Self {
+        StorageQuota {
+            max_bytes: None,
+            warn_percent: 80,
+        }
+    }
+}
+
+impl StorageQuota {
+    /// Validate option ranges
+    pub fn validate(&self) -> Result<(), PetError> {
+        if self.max_bytes == Some(0) {
+            return Err(PetError::validation(
+                "max_bytes",
+                "Quota must be larger than zero",
+            ));
+        }
+        if !(1..=100).contains(&self.warn_percent) {
+            return Err(PetError::validation(
+                "warn_percent",
+                "Warning threshold must be between 1 and 100 percent",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bytes at which the warning starts, None when unlimited
+    pub fn warn_bytes(&self) -> Option<u64> {
+        self.max_bytes
+            .map(|max| (max as f64 * self.warn_percent as f64 / 100.0).round() as u64)
+    }
+}
+
+/// How close storage usage is to the quota
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaLevel {
+    Ok,
+    Warning,
+    Exceeded,
+}
+
+/// Storage used against the configured quota
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuotaStatus {
+    pub quota: StorageQuota,
+    /// Database with its write-ahead log, photos and documents
+    pub used_bytes: u64,
+    pub database_bytes: u64,
+    pub photo_bytes: u64,
+    pub document_bytes: u64,
+    /// None when the quota is unlimited
+    pub usage_percent: Option<f64>,
+    /// Bytes left before the quota is reached; None when unlimited
+    pub remaining_bytes: Option<u64>,
+    /// Bytes to free to get back under the warning threshold
+    pub bytes_over_warning: u64,
+    pub level: QuotaLevel,
+}
+
+/// Compare the storage footprint against a quota
+pub fn build_quota_status(quota: StorageQuota, footprint: &DataFootprint) -> QuotaStatus {
+    let database_bytes = footprint.database.database_bytes + footprint.database.wal_bytes;
+    let photo_bytes = footprint.photo_storage.bytes;
+    let document_bytes = footprint.documents.bytes;
+    let used_bytes = database_bytes + photo_bytes + document_bytes;
+
+    let level = match (quota.max_bytes, quota.warn_bytes()) {
+        (Some(max), _) if used_bytes >= max => QuotaLevel::Exceeded,
+        (_, Some(warn)) if used_bytes >= warn => QuotaLevel::Warning,
+        _ => QuotaLevel::Ok,
+    };
+
+    QuotaStatus {
+        quota,
+        used_bytes,
+        database_bytes,
+        photo_bytes,
+        document_bytes,
+        usage_percent: quota
+            .max_bytes
+            .map(|max| used_bytes as f64 / max as f64 * 100.0),
+        remaining_bytes: quota.max_bytes.map(|max| max.saturating_sub(used_bytes)),
+        bytes_over_warning: quota
+            .warn_bytes()
+            .map_or(0, |warn| used_bytes.saturating_sub(warn)),
+        level,
+    }
+}
+
+/// Activities that attach a stored photo
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttachmentUsage {
+    pub file_name: String,
+    pub pet_ids: BTreeSet<i64>,
+    pub activity_count: usize,
+    /// Time of the newest activity using the file
+    pub last_used_at: DateTime<Utc>,
+}
+
+/// What to do with a file to free space
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupAction {
+    /// Nothing refers to the file; deleting it loses nothing
+    DeleteUnreferenced,
+    /// Re-encode with the low-storage photo settings
+    ReEncode,
+    /// Remove an old attachment from its activities and delete the file
+    DeleteAttachment,
+}
+
+/// A file suggested for cleanup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CleanupSuggestion {
+    pub file_name: String,
+    pub action: CleanupAction,
+    pub bytes: u64,
+    pub projected_savings: u64,
+    pub pet_ids: Vec<i64>,
+    pub activity_count: usize,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+/// Files to clean up, in the order they should be offered
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StorageCleanupPlan {
+    pub suggestions: Vec<CleanupSuggestion>,
+    pub projected_savings: u64,
+    /// Space the plan was asked to free, if any
+    pub bytes_to_free: Option<u64>,
+    /// Whether the suggestions free at least `bytes_to_free`
+    pub target_met: bool,
+}
+
+/// Suggest files to delete or re-encode, cheapest losses first: unreferenced files,
+/// then large photos to re-encode, then the oldest attachments.
+///
+/// `kept_photos` are never suggested for deletion: profile photos and the attachments
+/// of trashed activities. `reencode_ratio` is the expected size after re-encoding
+/// relative to the current size.
+/// With `bytes_to_free` the plan stops once enough space is freed, otherwise it lists
+/// up to [`DEFAULT_CLEANUP_SUGGESTIONS`] files.
+pub fn build_cleanup_plan(
+    stored_photos: &[(String, u64)],
+    attachments: &[AttachmentUsage],
+    kept_photos: &BTreeSet<String>,
+    reencode_ratio: f64,
+    bytes_to_free: Option<u64>,
+    now: DateTime<Utc>,
+) -> StorageCleanupPlan {
+    let usage: HashMap<&str, &AttachmentUsage> = attachments
+        .iter()
+        .map(|attachment| (attachment.file_name.as_str(), attachment))
+        .collect();
+    let suggestion = |name: &str, bytes: u64, action: CleanupAction, savings: u64| {
+        let attachment = usage.get(name);
+        CleanupSuggestion {
+            file_name: name.to_string(),
+            action,
+            bytes,
+            projected_savings: savings,
+            pet_ids: attachment
+                .map(|a| a.pet_ids.iter().copied().collect())
+                .unwrap_or_default(),
+            activity_count: attachment.map_or(0, |a| a.activity_count),
+            last_used_at: attachment.map(|a| a.last_used_at),
+        }
+    };
+
+    let mut unreferenced = Vec::new();
+    let mut reencode = Vec::new();
+    let mut stale = Vec::new();
+    let stale_before = now - chrono::Duration::days(STALE_ATTACHMENT_DAYS);
+    let reencode_savings = |bytes: u64| (bytes as f64 * (1.0 - reencode_ratio)).max(0.0) as u64;
+
+    for (name, bytes) in stored_photos {
+        let attachment = usage.get(name.as_str());
+        if attachment.is_none() && !kept_photos.contains(name) {
+            unreferenced.push(suggestion(
+                name,
+                *bytes,
+                CleanupAction::DeleteUnreferenced,
+                *bytes,
+            ));
+        } else if *bytes >= MIN_REENCODE_BYTES && reencode_savings(*bytes) > 0 {
+            reencode.push(suggestion(
+                name,
+                *bytes,
+                CleanupAction::ReEncode,
+                reencode_savings(*bytes),
+            ));
+        } else if attachment.is_some_and(|a| a.last_used_at < stale_before)
+            && !kept_photos.contains(name)
+        {
+            stale.push(suggestion(
+                name,
+                *bytes,
+                CleanupAction::DeleteAttachment,
+                *bytes,
+            ));
+        }
+    }
+
+    unreferenced.sort_by(|a, b| b.bytes.cmp(&a.bytes).then(a.file_name.cmp(&b.file_name)));
+    reencode.sort_by(|a, b| {
+        b.projected_savings
+            .cmp(&a.projected_savings)
+            .then(a.file_name.cmp(&b.file_name))
+    });
+    stale.sort_by(|a, b| {
+        a.last_used_at
+            .cmp(&b.last_used_at)
+            .then(b.bytes.cmp(&a.bytes))
+            .then(a.file_name.cmp(&b.file_name))
+    });
+
+    let mut suggestions = Vec::new();
+    let mut projected_savings = 0;
+    for candidate in unreferenced.into_iter().chain(reencode).chain(stale) {
+        let done = match bytes_to_free {
+            Some(target) => projected_savings >= target,
+            None => suggestions.len() >= DEFAULT_CLEANUP_SUGGESTIONS,
+        };
+        if done {
+            break;
+        }
+        projected_savings += candidate.projected_savings;
+        suggestions.push(candidate);
+    }
+
+    StorageCleanupPlan {
+        suggestions,
+        projected_savings,
+        bytes_to_free,
+        target_met: bytes_to_free.is_none_or(|target| projected_savings >= target),
+    }
+}
+
+impl super::PetDatabase {
+    /// The configured storage quota, unlimited by default
+    pub async fn get_storage_quota(&self) -> Result<StorageQuota, PetError> {
+        Ok(self
+            .get_setting::<StorageQuota>(STORAGE_QUOTA_SETTING_KEY)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Save the storage quota
+    pub async fn set_storage_quota(&self, quota: &StorageQuota) -> Result<(), PetError> {
+        quota.validate()?;
+        self.set_setting(STORAGE_QUOTA_SETTING_KEY, quota).await?;
+        Ok(())
+    }
+
+    /// Every stored photo attached to an activity, with the activities using it
+    pub async fn get_attachment_usage(&self) -> Result<Vec<AttachmentUsage>, ActivityError> {
+        let rows = sqlx::query(
+            "SELECT pet_id, activity_time, activity_data FROM activities WHERE activity_data IS NOT NULL",
+        )
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut usage: HashMap<String, AttachmentUsage> = HashMap::new();
+        for row in &rows {
+            let json: String = row.get("activity_data");
+            let Ok(data) = serde_json::from_str::<super::ActivityData>(&json) else {
+                continue;
+            };
+            let pet_id: i64 = row.get("pet_id");
+            let activity_time: DateTime<Utc> = row.get("activity_time");
+            for file_name in data.attachment_files() {
+                let entry = usage
+                    .entry(file_name.clone())
+                    .or_insert_with(|| AttachmentUsage {
+                        file_name,
+                        pet_ids: BTreeSet::new(),
+                        activity_count: 0,
+                        last_used_at: activity_time,
+                    });
+                entry.pet_ids.insert(pet_id);
+                entry.activity_count += 1;
+                entry.last_used_at = entry.last_used_at.max(activity_time);
+            }
+        }
+
+        log::debug!("[DB] get_attachment_usage: {} files", usage.len());
+        Ok(usage.into_values().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::footprint::{DatabaseFootprint, StorageBucket};
+    use super::*;
+
+    fn attachment(
+        file_name: &str,
+        pet_id: i64,
+        days_ago: i64,
+        now: DateTime<Utc>,
+    ) -> AttachmentUsage {
+        AttachmentUsage {
+            file_name: file_name.to_string(),
+            pet_ids: BTreeSet::from([pet_id]),
+            activity_count: 1,
+            last_used_at: now - chrono::Duration::days(days_ago),
+        }
+    }
+
+    #[test]
+    fn test_quota_status_levels() {
+        let footprint = DataFootprint {
+            database: DatabaseFootprint {
+                tables: Vec::new(),
+                database_bytes: 100,
+                wal_bytes: 20,
+            },
+            pets: Vec::new(),
+            photo_storage: StorageBucket {
+                count: 2,
+                bytes: 600,
+            },
+            attachments: StorageBucket::default(),
+            unreferenced_photos: StorageBucket::default(),
+            documents: StorageBucket {
+                count: 1,
+                bytes: 80,
+            },
+        };
+
+        let unlimited = build_quota_status(StorageQuota::default(), &footprint);
+        assert_eq!(unlimited.used_bytes, 800);
+        assert_eq!(unlimited.level, QuotaLevel::Ok);
+        assert_eq!(unlimited.usage_percent, None);
+
+        let quota = StorageQuota {
+            max_bytes: Some(1000),
+            warn_percent: 75,
+        };
+        let warning = build_quota_status(quota, &footprint);
+        assert_eq!(warning.level, QuotaLevel::Warning);
+        assert_eq!(warning.remaining_bytes, Some(200));
+        assert_eq!(warning.bytes_over_warning, 50);
+
+        let exceeded = build_quota_status(
+            StorageQuota {
+                max_bytes: Some(800),
+                ..quota
+            },
+            &footprint,
+        );
+        assert_eq!(exceeded.level, QuotaLevel::Exceeded);
+        assert_eq!(exceeded.remaining_bytes, Some(0));
+
+        assert!(StorageQuota {
+            max_bytes: Some(0),
+            ..quota
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_cleanup_plan_orders_by_loss() {
+        let now = Utc::now();
+        let mb = 1024 * 1024;
+        let stored = vec![
+            ("orphan.jpg".to_string(), 50_000),
+            ("profile.jpg".to_string(), 3 * mb),
+            ("big.jpg".to_string(), 2 * mb),
+            ("old.jpg".to_string(), 100_000),
+            ("older.jpg".to_string(), 90_000),
+            ("recent.jpg".to_string(), 100_000),
+        ];
+        let attachments = vec![
+            attachment("big.jpg", 1, 10, now),
+            attachment("old.jpg", 1, 400, now),
+            attachment("older.jpg", 2, 800, now),
+            attachment("recent.jpg", 2, 30, now),
+        ];
+        let profiles = BTreeSet::from(["profile.jpg".to_string()]);
+
+        let plan = build_cleanup_plan(&stored, &attachments, &profiles, 0.25, None, now);
+        let order: Vec<_> = plan
+            .suggestions
+            .iter()
+            .map(|s| (s.file_name.as_str(), s.action))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("orphan.jpg", CleanupAction::DeleteUnreferenced),
+                ("profile.jpg", CleanupAction::ReEncode),
+                ("big.jpg", CleanupAction::ReEncode),
+                ("older.jpg", CleanupAction::DeleteAttachment),
+                ("old.jpg", CleanupAction::DeleteAttachment),
+            ]
+        );
+        assert_eq!(plan.suggestions[2].projected_savings, (2 * mb) * 3 / 4);
+        assert_eq!(plan.suggestions[3].pet_ids, vec![2]);
+        assert!(plan.target_met);
+
+        // Stops once enough space is freed
+        let plan = build_cleanup_plan(&stored, &attachments, &profiles, 0.25, Some(mb), now);
+        assert_eq!(plan.suggestions.len(), 2);
+        assert!(plan.target_met);
+
+        // Photos that don't shrink aren't re-encoded
+        let plan = build_cleanup_plan(&stored, &attachments, &profiles, 1.0, Some(100 * mb), now);
+        assert!(plan
+            .suggestions
+            .iter()
+            .all(|s| s.action != CleanupAction::ReEncode));
+        assert!(!plan.target_met);
+    }
+}
@@ -0,0 +1,236 @@
+use super::body_condition::BODY_CONDITION_SUBCATEGORY;
+use super::litter::LITTER_SUBCATEGORY;
+use super::models::*;
+use super::summaries::DAILY_SUMMARY_SUBCATEGORY;
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+
+/// Days after which an activity counts half as much toward a suggestion
+const RECENCY_SCALE_DAYS: f64 = 30.0;
+
+/// Weight of an activity logged for another pet of the same species
+const SPECIES_WEIGHT: f64 = 0.25;
+
+/// Where a suggestion's ranking comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionSource {
+    /// The pet's own activities
+    PetHistory,
+    /// Only other pets of the same species logged it
+    Species,
+    /// Built-in subcategory nobody has logged yet
+    Default,
+}
+
+/// A subcategory offered in Quick Log, most relevant first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubcategorySuggestion {
+    pub subcategory: String,
+    /// Frequency weighted by recency; 0.0 for built-in defaults
+    pub score: f64,
+    /// Times this pet logged the subcategory
+    pub use_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub source: SuggestionSource,
+}
+
+impl super::PetDatabase {
+    /// Rank the subcategories of `category` for a pet. Each activity of the pet counts
+    /// 1 / (1 + age in days / 30), so frequent and recent actions come first; activities
+    /// of other pets of the same species count a quarter as much. Built-in subcategories
+    /// follow in the species' usual order.
+    pub async fn suggest_subcategories(
+        &self,
+        pet_id: i64,
+        category: ActivityCategory,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SubcategorySuggestion>, ActivityError> {
+        let pet = self
+            .get_pet_by_id(pet_id)
+            .await
+            .map_err(|e| ActivityError::validation("pet_id", &format!("Pet not found: {e}")))?;
+
+        let rows = sqlx::query(
+            r#"
+            SELECT a.subcategory,
+                SUM(a.pet_id = ?) AS use_count,
+                MAX(CASE WHEN a.pet_id = ? THEN a.activity_time END) AS last_used_at,
+                SUM(
+                    (CASE WHEN a.pet_id = ? THEN 1.0 ELSE ? END)
+                    / (1.0 + max(julianday(?) - julianday(a.activity_time), 0.0) / ?)
+                ) AS score
+            FROM activities a
+            JOIN pets p ON p.id = a.pet_id
+            WHERE a.category = ? AND a.needs_review = 0 AND a.subcategory != ?
+                AND (a.pet_id = ? OR (p.species = ? AND p.is_archived = 0))
+            GROUP BY a.subcategory
+            "#,
+        )
+        .bind(pet_id)
+        .bind(pet_id)
+        .bind(pet_id)
+        .bind(SPECIES_WEIGHT)
+        .bind(now.format("%Y-%m-%d %H:%M:%S").to_string())
+        .bind(RECENCY_SCALE_DAYS)
+        .bind(category.to_string())
+        .bind(DAILY_SUMMARY_SUBCATEGORY)
+        .bind(pet_id)
+        .bind(pet.species.to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut suggestions: Vec<SubcategorySuggestion> = rows
+            .iter()
+            .map(|row| {
+                let use_count: i64 = row.get("use_count");
+                SubcategorySuggestion {
+                    subcategory: row.get("subcategory"),
+                    score: (row.get::<f64, _>("score") * 1000.0).round() / 1000.0,
+                    use_count,
+                    last_used_at: row.get("last_used_at"),
+                    source: if use_count > 0 {
+                        SuggestionSource::PetHistory
+                    } else {
+                        SuggestionSource::Species
+                    },
+                }
+            })
+            .collect();
+
+        let defaults = default_subcategories(category, &pet.species);
+        for subcategory in defaults {
+            if !suggestions.iter().any(|s| s.subcategory == *subcategory) {
+                suggestions.push(SubcategorySuggestion {
+                    subcategory: subcategory.to_string(),
+                    score: 0.0,
+                    use_count: 0,
+                    last_used_at: None,
+                    source: SuggestionSource::Default,
+                });
+            }
+        }
+
+        let default_rank = |subcategory: &str| {
+            defaults
+                .iter()
+                .position(|default| *default == subcategory)
+                .unwrap_or(defaults.len())
+        };
+        suggestions.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| b.use_count.cmp(&a.use_count))
+                .then_with(|| default_rank(&a.subcategory).cmp(&default_rank(&b.subcategory)))
+                .then_with(|| a.subcategory.cmp(&b.subcategory))
+        });
+
+        log::debug!(
+            "[DB] suggest_subcategories: pet_id={pet_id}, category={category}, suggestions={}",
+            suggestions.len()
+        );
+        Ok(suggestions)
+    }
+}
+
+/// Built-in subcategories of a category in the order a new pet of `species` sees them
+fn default_subcategories(
+    category: ActivityCategory,
+    species: &PetSpecies,
+) -> &'static [&'static str] {
+    match (category, species) {
+        (ActivityCategory::Health, PetSpecies::Dog) => &["Checkup", "Medication", "Symptom"],
+        // Litter habits are an early sign of illness in cats
+        (ActivityCategory::Health, PetSpecies::Cat) => {
+            &["Checkup", "Medication", "Symptom", LITTER_SUBCATEGORY]
+        }
+        (ActivityCategory::Growth, _) => {
+            &["Weight", BODY_CONDITION_SUBCATEGORY, "Height", "Milestone"]
+        }
+        (ActivityCategory::Diet, _) => &["Feeding", "Water", "Treat"],
+        (ActivityCategory::Lifestyle, PetSpecies::Dog) => &["Walk", "Play", "Training", "Sleep"],
+        // Few cats go for walks
+        (ActivityCategory::Lifestyle, PetSpecies::Cat) => &["Play", "Sleep", "Training", "Walk"],
+        (ActivityCategory::Expense, _) => &["Purchase", "Veterinary", "Grooming", "Insurance"],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_suggestions_rank_by_frequency_and_recency() {
+        let config = FixtureConfig {
+            pets: 3,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        // Fixture pets alternate cat, dog, cat
+        let (cat, dog, other_cat) = (summary.pet_ids[0], summary.pet_ids[1], summary.pet_ids[2]);
+
+        let on = |pet_id: i64, subcategory: &str, date: &str| ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Lifestyle,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(serde_json::json!({
+                "time": { "date": format!("{date}T10:00:00.000Z"), "time": "", "timezone": "" }
+            })),
+            needs_review: false,
+        };
+        for request in [
+            // Three older training sessions lose to two recent play sessions
+            on(cat, "Training", "2025-03-10"),
+            on(cat, "Training", "2025-03-11"),
+            on(cat, "Training", "2025-03-12"),
+            on(cat, "Play", "2025-05-30"),
+            on(cat, "Play", "2025-06-01"),
+            on(other_cat, "Grooming", "2025-06-01"),
+            on(dog, "Walk", "2025-06-01"),
+        ] {
+            db.create_activity(request).await.unwrap();
+        }
+
+        let now = DateTime::parse_from_rfc3339("2025-06-02T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let suggestions = db
+            .suggest_subcategories(cat, ActivityCategory::Lifestyle, now)
+            .await
+            .unwrap();
+        let ranked: Vec<(&str, SuggestionSource)> = suggestions
+            .iter()
+            .map(|s| (s.subcategory.as_str(), s.source))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("Play", SuggestionSource::PetHistory),
+                ("Training", SuggestionSource::PetHistory),
+                ("Grooming", SuggestionSource::Species),
+                ("Sleep", SuggestionSource::Default),
+                ("Walk", SuggestionSource::Default),
+            ]
+        );
+        assert_eq!(suggestions[0].use_count, 2);
+        assert_eq!(
+            suggestions[0].last_used_at.map(|t| t.to_rfc3339()),
+            Some("2025-06-01T10:00:00+00:00".to_string())
+        );
+
+        // A dog with no history of its own gets the dog order
+        let dog_suggestions = db
+            .suggest_subcategories(dog, ActivityCategory::Diet, now)
+            .await
+            .unwrap();
+        assert_eq!(dog_suggestions[0].subcategory, "Feeding");
+        assert!(dog_suggestions
+            .iter()
+            .all(|s| s.source == SuggestionSource::Default));
+    }
+}
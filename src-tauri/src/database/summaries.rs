@@ -0,0 +1,346 @@
+use super::activity_data::BlockData;
+use super::models::*;
+use crate::errors::ActivityError;
+use chrono::NaiveDate;
+
+/// Subcategory of generated end-of-day summary activities
+pub const DAILY_SUMMARY_SUBCATEGORY: &str = "Daily Summary";
+
+impl super::PetDatabase {
+    /// The summary activity of a pet's day, if one was generated
+    pub async fn find_daily_summary(
+        &self,
+        pet_id: i64,
+        date: NaiveDate,
+    ) -> Result<Option<Activity>, ActivityError> {
+        Ok(self
+            .get_activities_on_date(pet_id, date)
+            .await?
+            .into_iter()
+            .find(is_daily_summary))
+    }
+
+    /// Compose (or recompose) the summary activity of a pet's day.
+    /// An existing summary is updated in place; it is removed when the day has no
+    /// other activities, and `None` is returned.
+    pub async fn generate_daily_summary(
+        &self,
+        pet_id: i64,
+        date: NaiveDate,
+    ) -> Result<Option<Activity>, ActivityError> {
+        let (existing, activities): (Vec<Activity>, Vec<Activity>) = self
+            .get_activities_on_date(pet_id, date)
+            .await?
+            .into_iter()
+            .partition(is_daily_summary);
+        let mut existing = existing.into_iter();
+        let current = existing.next();
+
+        // Only one summary per day; drop duplicates left by concurrent runs
+        for duplicate in existing {
+            self.delete_activity(duplicate.id).await?;
+        }
+
+        if activities.is_empty() {
+            if let Some(current) = current {
+                log::info!(
+                    "[DB] generate_daily_summary: removing empty summary for pet_id={pet_id}, date={date}"
+                );
+                self.delete_activity(current.id).await?;
+            }
+            return Ok(None);
+        }
+
+        let summary = compose_daily_summary(&activities);
+        let request = daily_summary_request(pet_id, date, &summary);
+        let activity = match current {
+            Some(current) => {
+                self.update_activity(
+                    current.id,
+                    ActivityUpdateRequest {
+                        activity_data: request.activity_data,
+                        ..Default::default()
+                    },
+                )
+                .await?
+            }
+            None => self.create_activity_with_side_effects(request).await?,
+        };
+
+        log::debug!(
+            "[DB] generate_daily_summary: pet_id={pet_id}, date={date}, activities={}",
+            summary.total_activities
+        );
+        Ok(Some(activity))
+    }
+
+    /// Generate the summary of `date` for every active pet that doesn't have one yet
+    pub async fn generate_missing_daily_summaries(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Vec<Activity>, ActivityError> {
+        let pets = self
+            .get_pets(false)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut created = Vec::new();
+        for pet in pets {
+            if self.find_daily_summary(pet.id, date).await?.is_some() {
+                continue;
+            }
+            if let Some(summary) = self.generate_daily_summary(pet.id, date).await? {
+                created.push(summary);
+            }
+        }
+
+        if !created.is_empty() {
+            log::info!(
+                "[DB] generate_missing_daily_summaries: generated {} summaries for {date}",
+                created.len()
+            );
+        }
+        Ok(created)
+    }
+}
+
+/// Whether an activity is a generated daily summary
+pub fn is_daily_summary(activity: &Activity) -> bool {
+    activity.category == ActivityCategory::Lifestyle
+        && activity.subcategory == DAILY_SUMMARY_SUBCATEGORY
+}
+
+/// Count the day's meals, treats and walks and collect its notable entries
+pub fn compose_daily_summary(activities: &[Activity]) -> DailySummary {
+    let mut summary = DailySummary::default();
+
+    for activity in activities.iter().filter(|a| !is_daily_summary(a)) {
+        summary.total_activities += 1;
+        let subcategory = activity.subcategory.to_lowercase();
+        match activity.category {
+            ActivityCategory::Diet if subcategory == "treat" => summary.treats += 1,
+            ActivityCategory::Diet if subcategory != "water" => summary.meals += 1,
+            ActivityCategory::Lifestyle if subcategory == "walk" => summary.walks += 1,
+            ActivityCategory::Health | ActivityCategory::Growth | ActivityCategory::Expense => {
+                summary.notable.push(notable_label(activity));
+            }
+            _ => {}
+        }
+    }
+    summary
+}
+
+/// Title of a notable entry, with its measurement when it has one
+fn notable_label(activity: &Activity) -> String {
+    let data = activity.activity_data.as_ref();
+    let title = match data.and_then(|d| d.get("title")) {
+        Some(BlockData::Text(title)) if !title.trim().is_empty() => title.trim().to_string(),
+        _ => activity.subcategory.clone(),
+    };
+    let measurement = data.and_then(|d| {
+        d.values().find_map(|block| match block {
+            BlockData::Measurement { value, unit, .. } => Some(format!("{value} {unit}")),
+            _ => None,
+        })
+    });
+    match measurement {
+        Some(measurement) => format!("{title} {measurement}"),
+        None => title,
+    }
+}
+
+/// One-line text of a summary, e.g. "3 meals, 1 treat, 2 walks. Notable: Checkup"
+pub fn daily_summary_text(summary: &DailySummary) -> String {
+    fn count(n: usize, singular: &str) -> String {
+        if n == 1 {
+            format!("1 {singular}")
+        } else {
+            format!("{n} {singular}s")
+        }
+    }
+
+    let mut text = [
+        count(summary.meals, "meal"),
+        count(summary.treats, "treat"),
+        count(summary.walks, "walk"),
+    ]
+    .join(", ");
+    text.push('.');
+    if !summary.notable.is_empty() {
+        text.push_str(" Notable: ");
+        text.push_str(&summary.notable.join(", "));
+    }
+    text
+}
+
+/// Activity storing a summary. Its time is the last millisecond of the day, so it sorts
+/// first within the day in the newest-first timeline.
+fn daily_summary_request(
+    pet_id: i64,
+    date: NaiveDate,
+    summary: &DailySummary,
+) -> ActivityCreateRequest {
+    let end_of_day = date
+        .and_hms_milli_opt(23, 59, 59, 999)
+        .unwrap_or_default()
+        .and_utc();
+
+    ActivityCreateRequest {
+        pet_id,
+        category: ActivityCategory::Lifestyle,
+        subcategory: DAILY_SUMMARY_SUBCATEGORY.to_string(),
+        activity_data: Some(serde_json::json!({
+            "time": {
+                "date": end_of_day.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+                "time": "",
+                "timezone": "",
+            },
+            "title": "Daily summary",
+            "notes": daily_summary_text(summary),
+            "summary": summary,
+        })),
+        needs_review: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::test_database;
+    use super::*;
+
+    fn activity_at(
+        pet_id: i64,
+        category: ActivityCategory,
+        subcategory: &str,
+        date: NaiveDate,
+    ) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(serde_json::json!({
+                "time": { "date": format!("{date}T09:30:00.000Z"), "time": "", "timezone": "" }
+            })),
+            needs_review: false,
+        }
+    }
+
+    #[test]
+    fn test_daily_summary_text() {
+        let summary = DailySummary {
+            total_activities: 5,
+            meals: 3,
+            treats: 1,
+            walks: 0,
+            notable: vec!["Checkup".to_string(), "Weight 4.6 kg".to_string()],
+        };
+        assert_eq!(
+            daily_summary_text(&summary),
+            "3 meals, 1 treat, 0 walks. Notable: Checkup, Weight 4.6 kg"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_generate_and_regenerate_daily_summary() {
+        let (db, _dir) = test_database().await;
+        let pet = db
+            .create_pet(CreatePetRequest {
+                name: "Biscuit".to_string(),
+                birth_date: NaiveDate::from_ymd_opt(2021, 6, 1).unwrap(),
+                species: PetSpecies::Dog,
+                gender: PetGender::Male,
+                breed: None,
+                color: None,
+                weight_kg: None,
+                photo_path: None,
+                notes: None,
+            })
+            .await
+            .unwrap();
+        let day = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+
+        for (category, subcategory) in [
+            (ActivityCategory::Diet, "Feeding"),
+            (ActivityCategory::Diet, "Feeding"),
+            (ActivityCategory::Diet, "Water"),
+            (ActivityCategory::Lifestyle, "Walk"),
+            (ActivityCategory::Health, "Checkup"),
+        ] {
+            db.create_activity_with_side_effects(activity_at(pet.id, category, subcategory, day))
+                .await
+                .unwrap();
+        }
+        let next_day = day.succ_opt().unwrap();
+        let other_day = db
+            .create_activity_with_side_effects(activity_at(
+                pet.id,
+                ActivityCategory::Lifestyle,
+                "Walk",
+                next_day,
+            ))
+            .await
+            .unwrap();
+
+        let created = db.generate_missing_daily_summaries(day).await.unwrap();
+        assert_eq!(created.len(), 1);
+        assert!(db
+            .generate_missing_daily_summaries(day)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let summary = db.find_daily_summary(pet.id, day).await.unwrap().unwrap();
+        assert_eq!(summary.id, created[0].id);
+        assert_eq!(summary.occurred_on(), day);
+        match summary.activity_data.as_ref().unwrap().get("notes") {
+            Some(BlockData::Text(notes)) => {
+                assert_eq!(notes, "2 meals, 0 treats, 1 walk. Notable: Checkup")
+            }
+            other => panic!("Expected notes block, got {other:?}"),
+        }
+
+        // Newest-first timeline shows the summary before the day's activities
+        let timeline = db
+            .get_activities(GetActivitiesRequest {
+                pet_id: Some(pet.id),
+                sort_by: Some("activity_time".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(timeline.activities[0].id, other_day.id);
+        assert_eq!(timeline.activities[1].id, summary.id);
+
+        // Regenerating keeps the same activity and picks up new entries
+        db.create_activity_with_side_effects(activity_at(
+            pet.id,
+            ActivityCategory::Lifestyle,
+            "Walk",
+            day,
+        ))
+        .await
+        .unwrap();
+        let regenerated = db
+            .generate_daily_summary(pet.id, day)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(regenerated.id, summary.id);
+        assert_eq!(
+            regenerated
+                .activity_data
+                .as_ref()
+                .and_then(|d| d.get("summary"))
+                .map(|block| serde_json::to_value(block).unwrap()["walks"].clone()),
+            Some(serde_json::json!(2))
+        );
+
+        // A day without activities has no summary
+        let empty_day = NaiveDate::from_ymd_opt(2025, 3, 20).unwrap();
+        assert!(db
+            .generate_daily_summary(pet.id, empty_day)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,454 @@
+//! Test support: a seeded fixture database and golden-file assertions.
+//!
+//! Fixtures are generated from a fixed seed and base date, so every run produces
+//! the same pets, activities and IDs. Golden files live in `testdata/golden/`;
+//! run tests with `UPDATE_GOLDEN=1` to rewrite them after an intended change.
+
+use super::{
+    ActivityCategory, ActivityCreateRequest, CreatePetRequest, PetDatabase, PetGender, PetSpecies,
+};
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+use std::path::PathBuf;
+use tempfile::TempDir;
+
+/// First day covered by generated activities
+pub const FIXTURE_BASE_DATE: (i32, u32, u32) = (2025, 1, 1);
+
+/// Number of days generated activities are spread over
+const FIXTURE_SPAN_DAYS: u64 = 365;
+
+/// Small deterministic PRNG (xorshift64*) so fixtures don't depend on a rand crate
+pub struct FixtureRng(u64);
+
+impl FixtureRng {
+    pub fn new(seed: u64) -> Self {
+        FixtureRng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Uniform value in `0..upper`
+    pub fn below(&mut self, upper: u64) -> u64 {
+        self.next_u64() % upper.max(1)
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    /// True with the given probability (0.0 - 1.0)
+    pub fn chance(&mut self, probability: f64) -> bool {
+        (self.below(10_000) as f64) < probability * 10_000.0
+    }
+}
+
+/// Shape of the generated fixture data
+#[derive(Debug, Clone)]
+pub struct FixtureConfig {
+    pub seed: u64,
+    pub pets: usize,
+    pub activities_per_pet: usize,
+    /// Share of activities that carry an attachment block
+    pub attachment_ratio: f64,
+}
+
+impl Default for FixtureConfig {
+    fn default() -> Self {
+        FixtureConfig {
+            seed: 42,
+            pets: 3,
+            activities_per_pet: 40,
+            attachment_ratio: 0.2,
+        }
+    }
+}
+
+/// IDs of the generated records
+#[derive(Debug, Clone, Default)]
+pub struct FixtureSummary {
+    pub pet_ids: Vec<i64>,
+    pub activity_ids: Vec<i64>,
+}
+
+const PET_NAMES: [&str; 8] = [
+    "Mochi", "Buddy", "Luna", "Max", "Nori", "Bella", "Tofu", "Charlie",
+];
+const CAT_BREEDS: [&str; 3] = ["British Shorthair", "Ragdoll", "Siamese"];
+const DOG_BREEDS: [&str; 3] = ["Golden Retriever", "Shiba Inu", "Corgi"];
+const PLACES: [(&str, f64, f64); 4] = [
+    ("Central Park", 40.7812, -73.9665),
+    ("Happy Paws Clinic", 40.7484, -73.9857),
+    ("Riverside Trail", 40.8007, -73.9726),
+    ("Pet Supply Store", 40.7306, -73.9866),
+];
+const NOTES: [&str; 5] = [
+    "Ate well",
+    "Very playful today",
+    "A bit tired after the walk",
+    "Vet said everything looks good",
+    "Tried a new brand",
+];
+
+/// Create an empty database in a temporary directory.
+/// Keep the returned `TempDir` alive for as long as the database is used.
+pub async fn test_database() -> (PetDatabase, TempDir) {
+    let dir = TempDir::new().expect("Failed to create temp directory");
+    let path = dir.path().join("fixture.db");
+    let db = PetDatabase::new_for_test(&path.to_string_lossy())
+        .await
+        .expect("Failed to create test database");
+    (db, dir)
+}
+
+/// Create a database populated with fixtures for `config`
+pub async fn seeded_database(config: &FixtureConfig) -> (PetDatabase, TempDir, FixtureSummary) {
+    let (db, dir) = test_database().await;
+    let summary = seed_fixtures(&db, config)
+        .await
+        .expect("Failed to seed fixtures");
+    (db, dir, summary)
+}
+
+/// Insert pets and activities across all categories, with realistic blocks
+pub async fn seed_fixtures(
+    db: &PetDatabase,
+    config: &FixtureConfig,
+) -> anyhow::Result<FixtureSummary> {
+    let mut rng = FixtureRng::new(config.seed);
+    let mut summary = FixtureSummary::default();
+    let base_date = fixture_base_date();
+
+    for pet_index in 0..config.pets {
+        let species = if pet_index % 2 == 0 {
+            PetSpecies::Cat
+        } else {
+            PetSpecies::Dog
+        };
+        let breed = match species {
+            PetSpecies::Cat => rng.pick(&CAT_BREEDS),
+            PetSpecies::Dog => rng.pick(&DOG_BREEDS),
+        };
+        let base_weight = match species {
+            PetSpecies::Cat => 3.5 + rng.below(20) as f32 / 10.0,
+            PetSpecies::Dog => 8.0 + rng.below(150) as f32 / 10.0,
+        };
+
+        let pet = db
+            .create_pet(CreatePetRequest {
+                name: PET_NAMES[pet_index % PET_NAMES.len()].to_string(),
+                birth_date: base_date
+                    .checked_sub_days(Days::new(365 + rng.below(365 * 6)))
+                    .expect("valid fixture birth date"),
+                species,
+                gender: if rng.chance(0.5) {
+                    PetGender::Male
+                } else {
+                    PetGender::Female
+                },
+                breed: Some(breed.to_string()),
+                color: None,
+                weight_kg: Some(base_weight),
+                photo_path: None,
+                notes: Some(format!("Fixture pet #{pet_index}")),
+            })
+            .await?;
+        summary.pet_ids.push(pet.id);
+
+        for activity_index in 0..config.activities_per_pet {
+            let day = rng.below(FIXTURE_SPAN_DAYS);
+            let hour = 7 + rng.below(14);
+            let date = base_date + Days::new(day);
+            let request = fixture_activity(
+                &mut rng,
+                config,
+                pet.id,
+                activity_index,
+                &format!("{}T{hour:02}:00:00.000Z", date.format("%Y-%m-%d")),
+                base_weight,
+            );
+            let activity = db.create_activity_with_side_effects(request).await?;
+            summary.activity_ids.push(activity.id);
+        }
+    }
+
+    Ok(summary)
+}
+
+fn fixture_activity(
+    rng: &mut FixtureRng,
+    config: &FixtureConfig,
+    pet_id: i64,
+    index: usize,
+    date: &str,
+    base_weight: f32,
+) -> ActivityCreateRequest {
+    let category = match index % 5 {
+        0 => ActivityCategory::Health,
+        1 => ActivityCategory::Growth,
+        2 => ActivityCategory::Diet,
+        3 => ActivityCategory::Lifestyle,
+        _ => ActivityCategory::Expense,
+    };
+
+    let mut data = serde_json::json!({
+        "time": { "date": date, "time": "", "timezone": "UTC" },
+    });
+    if rng.chance(0.6) {
+        data["notes"] = serde_json::json!(rng.pick(&NOTES));
+    }
+
+    let subcategory = match category {
+        ActivityCategory::Health => {
+            let subcategory = *rng.pick(&["Checkup", "Vaccination", "Symptom", "Medication"]);
+            data["title"] = serde_json::json!(format!("{subcategory} visit"));
+            subcategory
+        }
+        ActivityCategory::Growth => {
+            let weight = base_weight + (rng.below(100) as f32 - 50.0) / 100.0;
+            data["weight"] = serde_json::json!({
+                "value": format!("{weight:.2}"),
+                "unit": "kg",
+                "measurementType": "weight",
+            });
+            "Weight"
+        }
+        ActivityCategory::Diet => {
+            data["portion"] = serde_json::json!({
+                "amount": 40 + rng.below(80),
+                "unit": "g",
+                "portionType": "bowl",
+                "brand": rng.pick(&["Purina", "Royal Canin", "Orijen"]),
+            });
+            *rng.pick(&["Breakfast", "Dinner", "Treat"])
+        }
+        ActivityCategory::Lifestyle => {
+            let (name, lat, lng) = *rng.pick(&PLACES);
+            data["location"] = serde_json::json!({
+                "name": name,
+                "coordinates": { "lat": lat, "lng": lng },
+            });
+            *rng.pick(&["Walk", "Play", "Grooming"])
+        }
+        ActivityCategory::Expense => {
+            data["cost"] = serde_json::json!({
+                "amount": (rng.below(20_000) as f64) / 100.0,
+                "currency": "USD",
+            });
+            *rng.pick(&["Food", "Vet Bill", "Toys"])
+        }
+    };
+
+    if rng.chance(config.attachment_ratio) {
+        data["attachment"] = serde_json::json!([{
+            "id": format!("att-{pet_id}-{index}"),
+            "filename": format!("fixture-{pet_id}-{index}.jpg"),
+            "originalName": "photo.jpg",
+            "mimeType": "image/jpeg",
+            "size": 1024 + rng.below(100_000),
+            "path": format!("fixture-{pet_id}-{index}.jpg"),
+            "uploadedAt": date,
+        }]);
+    }
+
+    ActivityCreateRequest {
+        pet_id,
+        category,
+        subcategory: subcategory.to_string(),
+        activity_data: Some(data),
+        needs_review: false,
+    }
+}
+
+/// Base date of generated activities
+pub fn fixture_base_date() -> NaiveDate {
+    let (year, month, day) = FIXTURE_BASE_DATE;
+    NaiveDate::from_ymd_opt(year, month, day).expect("valid fixture base date")
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("testdata")
+        .join("golden")
+        .join(format!("{name}.json"))
+}
+
+/// Compare `value` as pretty JSON against `testdata/golden/<name>.json`.
+/// With `UPDATE_GOLDEN=1` the file is (re)written instead.
+pub fn assert_golden<T: Serialize>(name: &str, value: &T) {
+    let actual = serde_json::to_string_pretty(value).expect("Failed to serialize golden value");
+    let path = golden_path(name);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().expect("golden dir")).expect("create golden dir");
+        std::fs::write(&path, format!("{actual}\n")).expect("write golden file");
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing golden file {}; run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+    assert_eq!(
+        expected.trim_end(),
+        actual,
+        "Golden mismatch for '{name}'; run with UPDATE_GOLDEN=1 if the change is intended"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{GetActivitiesRequest, WeightUnit, KG_PER_LB};
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn test_fixtures_are_deterministic() {
+        let config = FixtureConfig {
+            pets: 2,
+            activities_per_pet: 10,
+            ..FixtureConfig::default()
+        };
+        let (first, _dir1, summary) = seeded_database(&config).await;
+        let (second, _dir2, _) = seeded_database(&config).await;
+
+        assert_eq!(summary.pet_ids.len(), 2);
+        assert_eq!(summary.activity_ids.len(), 20);
+
+        let request = || GetActivitiesRequest {
+            sort_by: Some("activity_time".to_string()),
+            ..Default::default()
+        };
+        let a = first.get_activities(request()).await.unwrap();
+        let b = second.get_activities(request()).await.unwrap();
+        let key = |r: &crate::database::GetActivitiesResponse| {
+            r.activities
+                .iter()
+                .map(|a| (a.id, a.subcategory.clone(), a.activity_data.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(key(&a), key(&b));
+    }
+
+    #[tokio::test]
+    async fn test_activity_timeline_golden() {
+        let (db, _dir, summary) = seeded_database(&FixtureConfig::default()).await;
+
+        let response = db
+            .get_activities(GetActivitiesRequest {
+                pet_id: Some(summary.pet_ids[0]),
+                sort_by: Some("activity_time".to_string()),
+                sort_desc: Some(false),
+                limit: Some(15),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        let timeline: Vec<_> = response
+            .activities
+            .iter()
+            .map(|a| {
+                serde_json::json!({
+                    "id": a.id,
+                    "category": a.category.to_string(),
+                    "subcategory": a.subcategory,
+                    "occurred_on": a.occurred_on().to_string(),
+                })
+            })
+            .collect();
+        assert_golden(
+            "activity_timeline",
+            &serde_json::json!({
+                "total_count": response.total_count,
+                "has_more": response.has_more,
+                "activities": timeline,
+            }),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_frequent_places_golden() {
+        let (db, _dir, summary) = seeded_database(&FixtureConfig::default()).await;
+        let places = db
+            .get_frequent_places(summary.pet_ids[1], None)
+            .await
+            .unwrap();
+        assert_golden("frequent_places", &places);
+    }
+
+    #[tokio::test]
+    async fn test_health_score_golden() {
+        let (db, _dir, summary) = seeded_database(&FixtureConfig::default()).await;
+        let today = fixture_base_date() + Days::new(FIXTURE_SPAN_DAYS);
+
+        let mut scores = Vec::new();
+        for pet_id in &summary.pet_ids {
+            scores.push(db.get_health_score(*pet_id, today).await.unwrap());
+        }
+        assert_golden("health_scores", &scores);
+    }
+
+    #[tokio::test]
+    async fn test_weight_history_in_preferred_unit() {
+        let (db, _dir, summary) = seeded_database(&FixtureConfig::default()).await;
+        let pet_id = summary.pet_ids[0];
+
+        let kg = db.get_weight_history(pet_id, WeightUnit::Kg).await.unwrap();
+        let lb = db.get_weight_history(pet_id, WeightUnit::Lb).await.unwrap();
+        assert!(!kg.records.is_empty());
+        assert_eq!(lb.display_unit, WeightUnit::Lb);
+        for (kg, lb) in kg.records.iter().zip(&lb.records) {
+            assert_eq!(kg.weight_kg, lb.weight_kg);
+            assert!((lb.value - kg.weight_kg / KG_PER_LB).abs() < 0.01);
+        }
+        assert!(kg.records.windows(2).all(|w| w[0].date <= w[1].date));
+
+        let pet = db
+            .get_pet_by_id(pet_id)
+            .await
+            .unwrap()
+            .with_display_unit(WeightUnit::Lb);
+        assert_eq!(pet.display_unit, WeightUnit::Lb);
+        assert_eq!(
+            pet.display_weight,
+            pet.weight_kg.map(|kg| WeightUnit::Lb.from_kg(kg))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_large_fixture_queries_stay_fast() {
+        let config = FixtureConfig {
+            pets: 5,
+            activities_per_pet: 400,
+            ..FixtureConfig::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        assert_eq!(summary.activity_ids.len(), 2000);
+
+        let started = Instant::now();
+        let page = db
+            .get_activities(GetActivitiesRequest {
+                pet_id: Some(summary.pet_ids[2]),
+                sort_by: Some("cost".to_string()),
+                limit: Some(50),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let search = db.global_search("walk", None).await.unwrap();
+        let elapsed = started.elapsed();
+
+        assert_eq!(page.total_count, 400);
+        assert!(!search.results.is_empty());
+        // Generous bound: catches accidental full scans per row, not machine speed
+        assert!(elapsed.as_secs() < 5, "queries took {elapsed:?}");
+    }
+}
@@ -0,0 +1,418 @@
+use super::activity_data::BlockData;
+use super::models::*;
+use crate::errors::ActivityError;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Longest range a timeline digest covers, in days
+pub const MAX_DIGEST_DAYS: i64 = 92;
+
+/// Templates the frontend has an icon for, as `category.subcategory`
+const TEMPLATE_ICONS: [&str; 17] = [
+    "diet.feeding",
+    "diet.water",
+    "diet.treat",
+    "expense.purchase",
+    "expense.veterinary",
+    "expense.grooming",
+    "expense.insurance",
+    "growth.weight",
+    "growth.height",
+    "growth.milestone",
+    "health.checkup",
+    "health.medication",
+    "health.symptom",
+    "lifestyle.walk",
+    "lifestyle.play",
+    "lifestyle.training",
+    "lifestyle.sleep",
+];
+
+/// Language of the digest lines
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestLocale {
+    #[default]
+    En,
+    Zh,
+}
+
+/// Days covered by a digest, both ends included (by activity time, UTC)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DigestRange {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// One activity of the timeline, ready to display
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimelineDigestEntry {
+    pub activity_id: i64,
+    /// Local day of the activity, for grouping under day headers
+    pub date: NaiveDate,
+    /// Local time as "HH:MM"; empty for activities logged without a time
+    pub time: String,
+    /// Template id such as "diet.feeding", or just the category when the
+    /// activity doesn't come from a built-in template
+    pub icon: String,
+    /// Title and key values, e.g. "Breakfast · 80 g Orijen"
+    pub line: String,
+}
+
+/// Pre-formatted timeline of a pet, newest first, so low-powered devices
+/// don't have to load and format the activity blocks themselves
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TimelineDigest {
+    pub pet_id: i64,
+    pub locale: DigestLocale,
+    pub range: DigestRange,
+    pub entries: Vec<TimelineDigestEntry>,
+}
+
+impl super::PetDatabase {
+    /// Build the digest of a pet's activities in `range`. Times are shown in
+    /// `offset`; activities waiting for review are left out.
+    pub async fn get_timeline_digest(
+        &self,
+        pet_id: i64,
+        range: DigestRange,
+        locale: DigestLocale,
+        offset: FixedOffset,
+    ) -> Result<TimelineDigest, ActivityError> {
+        let days = (range.end_date - range.start_date).num_days() + 1;
+        if days < 1 {
+            return Err(ActivityError::validation(
+                "range".to_string(),
+                "End date must not be before start date".to_string(),
+            ));
+        }
+        if days > MAX_DIGEST_DAYS {
+            return Err(ActivityError::validation(
+                "range".to_string(),
+                format!("Range must not exceed {MAX_DIGEST_DAYS} days"),
+            ));
+        }
+
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM activities
+            WHERE pet_id = ? AND date(activity_time) BETWEEN ? AND ? AND needs_review = 0
+            ORDER BY activity_time DESC, id DESC
+            "#,
+        )
+        .bind(pet_id)
+        .bind(range.start_date.format("%Y-%m-%d").to_string())
+        .bind(range.end_date.format("%Y-%m-%d").to_string())
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut entries = Vec::with_capacity(rows.len());
+        for row in rows {
+            let activity = self.row_to_activity(&row).await?;
+            entries.push(digest_entry(&activity, locale, offset));
+        }
+
+        log::debug!(
+            "[DB] get_timeline_digest: pet_id={pet_id}, {}..={}, entries={}",
+            range.start_date,
+            range.end_date,
+            entries.len()
+        );
+        Ok(TimelineDigest {
+            pet_id,
+            locale,
+            range,
+            entries,
+        })
+    }
+}
+
+/// Format one activity as a digest entry
+pub fn digest_entry(
+    activity: &Activity,
+    locale: DigestLocale,
+    offset: FixedOffset,
+) -> TimelineDigestEntry {
+    let data = activity.activity_data.as_ref();
+    let moment = match data.and_then(|d| d.get("time")) {
+        Some(BlockData::Time { date, .. }) => DateTime::parse_from_rfc3339(date)
+            .ok()
+            .map(|dt| dt.with_timezone(&offset)),
+        _ => Some(activity.created_at.with_timezone(&offset)),
+    };
+    let (date, time) = match moment {
+        Some(moment) => (moment.date_naive(), moment.format("%H:%M").to_string()),
+        // Plain dates carry no time of day
+        None => (activity.occurred_on(), String::new()),
+    };
+
+    let title = match data.and_then(|d| d.get("title")) {
+        Some(BlockData::Text(title)) if !title.trim().is_empty() => title.trim().to_string(),
+        _ => subcategory_label(&activity.subcategory, locale),
+    };
+    let mut parts = vec![title];
+    if let Some(data) = data {
+        let mut blocks: Vec<(&String, &BlockData)> = data.iter().collect();
+        blocks.sort_by_key(|(key, block)| (block_rank(key, block), *key));
+        parts.extend(
+            blocks
+                .into_iter()
+                .filter_map(|(key, block)| block_fact(key, block, locale)),
+        );
+    }
+
+    TimelineDigestEntry {
+        activity_id: activity.id,
+        date,
+        time,
+        icon: icon_key(activity),
+        line: parts.join(" · "),
+    }
+}
+
+/// Template id of the activity when there is a built-in one, else its category
+fn icon_key(activity: &Activity) -> String {
+    let template = format!(
+        "{}.{}",
+        activity.category,
+        activity.subcategory.trim().to_lowercase()
+    );
+    if TEMPLATE_ICONS.contains(&template.as_str()) {
+        template
+    } else {
+        activity.category.to_string()
+    }
+}
+
+/// Order of the values within a line: measurements first, cost last
+fn block_rank(key: &str, block: &BlockData) -> u8 {
+    match block {
+        BlockData::Measurement { .. } => 0,
+        BlockData::Portion { .. } => 1,
+        BlockData::Hydration { .. } => 2,
+        BlockData::Duration { .. } => 3,
+        BlockData::Distance { .. } => 4,
+        BlockData::Litter { .. } => 5,
+        BlockData::BodyCondition { .. } => 6,
+        _ if key == "cost" => 7,
+        _ => 8,
+    }
+}
+
+/// Short text of a block worth showing in the line, if it is one
+fn block_fact(key: &str, block: &BlockData, locale: DigestLocale) -> Option<String> {
+    match block {
+        BlockData::Measurement { value, unit, .. } => Some(format!("{value} {unit}")),
+        BlockData::Portion {
+            amount,
+            unit,
+            brand,
+            product,
+            ..
+        } => {
+            let name = product
+                .as_deref()
+                .or(brand.as_deref())
+                .map(str::trim)
+                .filter(|name| !name.is_empty());
+            Some(match name {
+                Some(name) => format!("{} {unit} {name}", format_amount(*amount)),
+                None => format!("{} {unit}", format_amount(*amount)),
+            })
+        }
+        BlockData::Hydration { amount, unit, .. }
+        | BlockData::Duration { amount, unit, .. }
+        | BlockData::Distance { amount, unit, .. } => {
+            Some(format!("{} {unit}", format_amount(*amount)))
+        }
+        BlockData::Litter {
+            litter_type,
+            count,
+            consistency,
+        } => {
+            let mut fact = litter_label(litter_type, locale);
+            if *count > 1 {
+                fact.push_str(&format!(" ×{count}"));
+            }
+            if let Some(score) = consistency {
+                let label = match locale {
+                    DigestLocale::En => "score",
+                    DigestLocale::Zh => "评分",
+                };
+                fact.push_str(&format!(" ({label} {score})"));
+            }
+            Some(fact)
+        }
+        BlockData::BodyCondition { score } => Some(match locale {
+            DigestLocale::En => format!("BCS {score}/9"),
+            DigestLocale::Zh => format!("体况评分 {score}/9"),
+        }),
+        BlockData::Other(cost) if key == "cost" => {
+            let amount = match cost.get("amount")? {
+                serde_json::Value::Number(n) => n.as_f64()?,
+                serde_json::Value::String(s) => s.parse().ok()?,
+                _ => return None,
+            };
+            let currency = cost
+                .get("currency")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default()
+                .to_uppercase();
+            Some(format!("{amount:.2} {currency}").trim_end().to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Amount without trailing zeros, at most two decimals
+fn format_amount(amount: f32) -> String {
+    let text = format!("{amount:.2}");
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Built-in subcategory names in the digest language; user-defined ones are kept
+fn subcategory_label(subcategory: &str, locale: DigestLocale) -> String {
+    if locale == DigestLocale::En {
+        return subcategory.to_string();
+    }
+    let label = match subcategory.trim().to_lowercase().as_str() {
+        "feeding" => "喂食",
+        "water" => "饮水",
+        "treat" => "零食",
+        "purchase" => "购物",
+        "veterinary" => "看兽医",
+        "grooming" => "美容",
+        "insurance" => "保险",
+        "weight" => "体重",
+        "height" => "身高",
+        "milestone" => "里程碑",
+        "checkup" => "体检",
+        "medication" => "用药",
+        "symptom" => "症状",
+        "walk" => "散步",
+        "play" => "玩耍",
+        "training" => "训练",
+        "sleep" => "睡觉",
+        "litter" => "如厕",
+        "daily summary" => "每日总结",
+        _ => return subcategory.to_string(),
+    };
+    label.to_string()
+}
+
+fn litter_label(litter_type: &str, locale: DigestLocale) -> String {
+    let label = match (litter_type, locale) {
+        ("urine", DigestLocale::En) => "Urine",
+        ("stool", DigestLocale::En) => "Stool",
+        ("both", DigestLocale::En) => "Urine and stool",
+        ("urine", DigestLocale::Zh) => "小便",
+        ("stool", DigestLocale::Zh) => "大便",
+        ("both", DigestLocale::Zh) => "大小便",
+        _ => litter_type,
+    };
+    label.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::litter::LITTER_SUBCATEGORY;
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::super::{ActivityCategory, ActivityCreateRequest};
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timeline_digest_formats_lines() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let on = |date: &str, category, subcategory: &str, blocks: serde_json::Value| {
+            let mut data = serde_json::json!({
+                "time": { "date": format!("{date}:00.000Z"), "time": "", "timezone": "" }
+            });
+            data.as_object_mut()
+                .unwrap()
+                .extend(blocks.as_object().unwrap().clone());
+            ActivityCreateRequest {
+                pet_id,
+                category,
+                subcategory: subcategory.to_string(),
+                activity_data: Some(data),
+                needs_review: false,
+            }
+        };
+        let feeding = db
+            .create_activity(on(
+                "2024-03-02T08:30",
+                ActivityCategory::Diet,
+                "Feeding",
+                serde_json::json!({
+                    "title": "Breakfast",
+                    "portion": { "amount": 80.0, "unit": "g", "portionType": "bowl", "product": "Orijen" },
+                    "notes": "Ate everything"
+                }),
+            ))
+            .await
+            .unwrap();
+        db.create_activity(on(
+            "2024-03-02T19:05",
+            ActivityCategory::Health,
+            LITTER_SUBCATEGORY,
+            serde_json::json!({
+                "litter": { "litterType": "stool", "count": 2, "consistency": 4 }
+            }),
+        ))
+        .await
+        .unwrap();
+        db.create_activity(on(
+            "2024-03-05T10:00",
+            ActivityCategory::Lifestyle,
+            "Walk",
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+
+        let range = DigestRange {
+            start_date: NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+            end_date: NaiveDate::from_ymd_opt(2024, 3, 3).unwrap(),
+        };
+        let offset = FixedOffset::east_opt(8 * 3600).unwrap();
+        let digest = db
+            .get_timeline_digest(pet_id, range, DigestLocale::En, offset)
+            .await
+            .unwrap();
+        assert_eq!(digest.entries.len(), 2);
+        assert_eq!(digest.entries[0].line, "Litter · Stool ×2 (score 4)");
+        assert_eq!(digest.entries[0].icon, "health");
+        assert_eq!(
+            digest.entries[0].date,
+            NaiveDate::from_ymd_opt(2024, 3, 3).unwrap()
+        );
+        assert_eq!(digest.entries[0].time, "03:05");
+        assert_eq!(digest.entries[1].activity_id, feeding.id);
+        assert_eq!(digest.entries[1].line, "Breakfast · 80 g Orijen");
+        assert_eq!(digest.entries[1].icon, "diet.feeding");
+        assert_eq!(digest.entries[1].time, "16:30");
+
+        let digest = db
+            .get_timeline_digest(pet_id, range, DigestLocale::Zh, offset)
+            .await
+            .unwrap();
+        assert_eq!(digest.entries[0].line, "如厕 · 大便 ×2 (评分 4)");
+
+        let backwards = DigestRange {
+            start_date: range.end_date,
+            end_date: range.start_date,
+        };
+        assert!(db
+            .get_timeline_digest(pet_id, backwards, DigestLocale::En, offset)
+            .await
+            .is_err());
+    }
+}
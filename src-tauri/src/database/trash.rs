@@ -0,0 +1,501 @@
+use super::activity_data::{ActivityData, ActivityDataExt};
+use super::hooks::ActivityEvent;
+use super::models::*;
+use super::query::SelectQuery;
+use crate::errors::ActivityError;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::Row;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Days trashed activities are kept when no age is given to `purge_activity_trash`
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+/// Which activities a bulk delete removes. Conditions are combined with AND, and
+/// at least one is required so a filter can't match everything by accident.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkDeleteFilter {
+    pub pet_id: Option<i64>,
+    pub category: Option<ActivityCategory>,
+    pub subcategory: Option<String>,
+    /// Activity dates (UTC), inclusive
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    /// When the activities were saved, e.g. the minutes a bad import ran
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub needs_review: Option<bool>,
+}
+
+impl BulkDeleteFilter {
+    fn validate(&self) -> Result<(), ActivityError> {
+        let has_condition = self.pet_id.is_some()
+            || self.category.is_some()
+            || self.subcategory.is_some()
+            || self.start_date.is_some()
+            || self.end_date.is_some()
+            || self.created_after.is_some()
+            || self.created_before.is_some()
+            || self.needs_review.is_some();
+        if !has_condition {
+            return Err(ActivityError::validation(
+                "filter",
+                "A bulk delete needs at least one condition",
+            ));
+        }
+        if let (Some(start), Some(end)) = (self.start_date, self.end_date) {
+            if start > end {
+                return Err(ActivityError::validation(
+                    "end_date",
+                    "End date must not be before start date",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// `id, category` of the matching activities
+    fn select(&self) -> SelectQuery<'static> {
+        let day = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+        let instant = |at: DateTime<Utc>| at.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let mut select = SelectQuery::new("SELECT id, category FROM activities");
+        select
+            .where_eq_opt("pet_id", self.pet_id)
+            .where_eq_opt("category", self.category.map(|c| c.to_string()))
+            .where_eq_opt("subcategory", self.subcategory.clone())
+            .where_cmp_opt("date(activity_time)", ">=", self.start_date.map(day))
+            .where_cmp_opt("date(activity_time)", "<=", self.end_date.map(day))
+            .where_cmp_opt(
+                "datetime(created_at)",
+                ">=",
+                self.created_after.map(instant),
+            )
+            .where_cmp_opt(
+                "datetime(created_at)",
+                "<=",
+                self.created_before.map(instant),
+            )
+            .where_eq_opt("needs_review", self.needs_review)
+            .order_by("id ASC");
+        select
+    }
+}
+
+/// What a bulk delete removed, or would remove on a dry run
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BulkDeleteResult {
+    pub dry_run: bool,
+    pub total: usize,
+    /// Matching activities per category
+    pub by_category: BTreeMap<String, usize>,
+    pub activity_ids: Vec<i64>,
+    /// Restores the batch with `restore_activity_trash`; None on dry runs and
+    /// when nothing matched
+    pub undo_token: Option<String>,
+}
+
+/// Trashed activities deleted for good
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrashPurge {
+    pub purged: usize,
+    /// Journal entries of their attachment files, for the caller to process
+    pub pending_files: Vec<PendingFileDeletion>,
+}
+
+impl super::PetDatabase {
+    /// Move the activities matching `filter` to the trash in one transaction, or with
+    /// `dry_run` only count them. Activity hooks don't run until the trash is purged,
+    /// so attachments, stock and linked records stay as they were for an undo.
+    pub async fn bulk_delete_activities(
+        &self,
+        filter: &BulkDeleteFilter,
+        dry_run: bool,
+    ) -> Result<BulkDeleteResult, ActivityError> {
+        filter.validate()?;
+
+        let _turn = if dry_run {
+            None
+        } else {
+            Some(self.writes.acquire_for_task().await)
+        };
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to begin transaction: {e}"))
+        })?;
+
+        let mut select = filter.select();
+        let rows = select
+            .build()
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut result = BulkDeleteResult {
+            dry_run,
+            total: rows.len(),
+            by_category: BTreeMap::new(),
+            activity_ids: Vec::with_capacity(rows.len()),
+            undo_token: None,
+        };
+        for row in &rows {
+            *result
+                .by_category
+                .entry(row.get::<String, _>("category"))
+                .or_default() += 1;
+            result.activity_ids.push(row.get("id"));
+        }
+        if dry_run || result.activity_ids.is_empty() {
+            log::debug!(
+                "[DB] bulk_delete_activities: dry_run={dry_run}, {} matching",
+                result.total
+            );
+            return Ok(result);
+        }
+
+        let token = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        for &id in &result.activity_ids {
+            // Links are cascaded away with the activity, so keep them for a restore
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO activity_trash_links
+                    (batch_token, activity_id, related_activity_id, created_at)
+                SELECT ?, activity_id, related_activity_id, created_at FROM activity_links
+                WHERE activity_id = ? OR related_activity_id = ?
+                "#,
+            )
+            .bind(&token)
+            .bind(id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+            sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO activity_trash_reminder_completions
+                    (batch_token, reminder_activity_id, activity_id, created_at)
+                SELECT ?, reminder_activity_id, activity_id, created_at FROM reminder_completions
+                WHERE reminder_activity_id = ? OR activity_id = ?
+                "#,
+            )
+            .bind(&token)
+            .bind(id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO activity_trash (
+                    id, batch_token, pet_id, category, subcategory, activity_data,
+                    needs_review, is_private, created_at, updated_at, deleted_at
+                )
+                SELECT id, ?, pet_id, category, subcategory, activity_data,
+                    needs_review, is_private, created_at, updated_at, ?
+                FROM activities WHERE id = ?
+                "#,
+            )
+            .bind(&token)
+            .bind(now)
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+            sqlx::query("DELETE FROM activities WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to commit transaction: {e}"))
+        })?;
+
+        log::info!(
+            "[DB] bulk_delete_activities: moved {} activities to the trash, token={token}",
+            result.total
+        );
+        result.undo_token = Some(token);
+        Ok(result)
+    }
+
+    /// Put a bulk delete's activities back, with their original ids and links
+    pub async fn restore_activity_trash(
+        &self,
+        undo_token: &str,
+    ) -> Result<Vec<Activity>, ActivityError> {
+        let _turn = self.writes.acquire_for_task().await;
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to begin transaction: {e}"))
+        })?;
+
+        let restored = sqlx::query(
+            r#"
+            INSERT INTO activities (
+                id, pet_id, category, subcategory, activity_data,
+                needs_review, is_private, created_at, updated_at
+            )
+            SELECT id, pet_id, category, subcategory, activity_data,
+                needs_review, is_private, created_at, updated_at
+            FROM activity_trash WHERE batch_token = ?
+            "#,
+        )
+        .bind(undo_token)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+        .rows_affected();
+        if restored == 0 {
+            return Err(ActivityError::validation(
+                "undo_token",
+                "Nothing to restore; the batch was already restored or purged",
+            ));
+        }
+
+        // A linked activity deleted since then stays unlinked
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO activity_links (activity_id, related_activity_id, created_at)
+            SELECT activity_id, related_activity_id, created_at FROM activity_trash_links
+            WHERE batch_token = ?
+                AND activity_id IN (SELECT id FROM activities)
+                AND related_activity_id IN (SELECT id FROM activities)
+            "#,
+        )
+        .bind(undo_token)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        sqlx::query(
+            r#"
+            INSERT OR IGNORE INTO reminder_completions (reminder_activity_id, activity_id, created_at)
+            SELECT reminder_activity_id, activity_id, created_at
+            FROM activity_trash_reminder_completions
+            WHERE batch_token = ?
+                AND reminder_activity_id IN (SELECT id FROM activities)
+                AND activity_id IN (SELECT id FROM activities)
+            "#,
+        )
+        .bind(undo_token)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let rows = sqlx::query(
+            "SELECT * FROM activities WHERE id IN (SELECT id FROM activity_trash WHERE batch_token = ?) ORDER BY id ASC",
+        )
+        .bind(undo_token)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        let mut activities = Vec::with_capacity(rows.len());
+        for row in &rows {
+            activities.push(self.read_activity_row(row).await?);
+        }
+
+        for table in [
+            "DELETE FROM activity_trash WHERE batch_token = ?",
+            "DELETE FROM activity_trash_links WHERE batch_token = ?",
+            "DELETE FROM activity_trash_reminder_completions WHERE batch_token = ?",
+        ] {
+            sqlx::query(table)
+                .bind(undo_token)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to commit transaction: {e}"))
+        })?;
+
+        log::info!(
+            "[DB] restore_activity_trash: restored {} activities, token={undo_token}",
+            activities.len()
+        );
+        Ok(activities)
+    }
+
+    /// Delete trashed activities older than `older_than_days` for good, running the
+    /// activity hooks a deletion would have run
+    pub async fn purge_activity_trash(
+        &self,
+        older_than_days: u32,
+    ) -> Result<TrashPurge, ActivityError> {
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(older_than_days));
+
+        let _turn = self.writes.acquire_for_task().await;
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to begin transaction: {e}"))
+        })?;
+
+        let rows = sqlx::query("SELECT * FROM activity_trash WHERE deleted_at <= ?")
+            .bind(cutoff)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        let mut purged = BTreeSet::new();
+        for row in &rows {
+            let activity = self.read_activity_row(row).await?;
+            self.hooks
+                .dispatch(&mut tx, ActivityEvent::Deleted(&activity))
+                .await?;
+            sqlx::query("DELETE FROM activity_trash WHERE id = ?")
+                .bind(activity.id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+            purged.insert(activity.id);
+        }
+        for table in [
+            "activity_trash_links",
+            "activity_trash_reminder_completions",
+        ] {
+            sqlx::query(&format!(
+                "DELETE FROM {table} WHERE batch_token NOT IN (SELECT batch_token FROM activity_trash)"
+            ))
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        }
+
+        tx.commit().await.map_err(|e| {
+            ActivityError::invalid_data(format!("Failed to commit transaction: {e}"))
+        })?;
+
+        let pending_files: Vec<_> = self
+            .get_pending_file_deletions()
+            .await?
+            .into_iter()
+            .filter(|entry| {
+                entry
+                    .source_activity_id
+                    .is_some_and(|id| purged.contains(&id))
+            })
+            .collect();
+        log::info!(
+            "[DB] purge_activity_trash: purged {} activities, {} files pending",
+            purged.len(),
+            pending_files.len()
+        );
+        Ok(TrashPurge {
+            purged: purged.len(),
+            pending_files,
+        })
+    }
+
+    /// Photo files attached to trashed activities, which a restore would need back
+    pub async fn get_trashed_attachment_files(&self) -> Result<BTreeSet<String>, ActivityError> {
+        let rows: Vec<String> = sqlx::query_scalar(
+            "SELECT activity_data FROM activity_trash WHERE activity_data IS NOT NULL",
+        )
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .filter_map(|json| serde_json::from_str::<ActivityData>(json).ok())
+            .flat_map(|data| data.attachment_files())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+    use crate::database::PetDatabase;
+
+    async fn count(db: &PetDatabase) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM activities")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_to_trash_and_restore() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 10,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        let health = db
+            .get_activities_by_category(pet_id, ActivityCategory::Health, None)
+            .await
+            .unwrap();
+        let expense = db
+            .get_activities_by_category(pet_id, ActivityCategory::Expense, None)
+            .await
+            .unwrap();
+        db.link_activities(expense[0].id, health[0].id)
+            .await
+            .unwrap();
+
+        assert!(db
+            .bulk_delete_activities(&BulkDeleteFilter::default(), true)
+            .await
+            .is_err());
+
+        let filter = BulkDeleteFilter {
+            pet_id: Some(pet_id),
+            category: Some(ActivityCategory::Health),
+            ..Default::default()
+        };
+        let preview = db.bulk_delete_activities(&filter, true).await.unwrap();
+        assert_eq!(preview.total, health.len());
+        assert_eq!(preview.by_category.get("health"), Some(&health.len()));
+        assert_eq!(preview.undo_token, None);
+        assert_eq!(count(&db).await, 10);
+
+        let deleted = db.bulk_delete_activities(&filter, false).await.unwrap();
+        let token = deleted.undo_token.clone().unwrap();
+        assert_eq!(count(&db).await, (10 - health.len()) as i64);
+        assert!(db.get_activity_by_id(health[0].id).await.is_err());
+        assert!(db
+            .get_related_activities(expense[0].id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let restored = db.restore_activity_trash(&token).await.unwrap();
+        assert_eq!(restored.len(), health.len());
+        assert_eq!(count(&db).await, 10);
+        assert_eq!(
+            db.get_activity_by_id(health[0].id)
+                .await
+                .unwrap()
+                .subcategory,
+            health[0].subcategory
+        );
+        assert_eq!(
+            db.get_related_activities(expense[0].id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(db.restore_activity_trash(&token).await.is_err());
+
+        // Purged activities are gone for good
+        let deleted = db.bulk_delete_activities(&filter, false).await.unwrap();
+        assert_eq!(
+            db.purge_activity_trash(0).await.unwrap().purged,
+            health.len()
+        );
+        assert!(db
+            .restore_activity_trash(&deleted.undo_token.unwrap())
+            .await
+            .is_err());
+        assert_eq!(count(&db).await, (10 - health.len()) as i64);
+    }
+}
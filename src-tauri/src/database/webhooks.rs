@@ -0,0 +1,376 @@
+use super::hooks::{ActivityEvent, ActivityHook};
+use super::models::*;
+use super::summaries::DAILY_SUMMARY_SUBCATEGORY;
+use crate::errors::ActivityError;
+use crate::privacy::{PrivacySettings, PRIVACY_SETTINGS_KEY};
+use crate::webhook::{self, WebhookSettings, MAX_DELIVERY_ATTEMPTS, WEBHOOK_SETTINGS_KEY};
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::{Row, SqliteConnection};
+
+/// An activity waiting to be posted to the automation webhook
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookDelivery {
+    pub id: i64,
+    pub activity_id: i64,
+    pub event: String,
+    /// JSON body to post
+    pub payload: String,
+    pub attempts: i64,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+}
+
+/// Deliveries still queued, for the automation settings screen
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookQueueStatus {
+    /// Waiting for their first or next attempt
+    pub pending: i64,
+    /// Given up after too many failed attempts
+    pub failed: i64,
+    pub last_error: Option<String>,
+}
+
+impl super::PetDatabase {
+    /// Queue a webhook delivery when the webhook is enabled and the activity isn't
+    /// private or of a category hidden in the privacy settings
+    pub async fn enqueue_webhook_delivery(
+        conn: &mut SqliteConnection,
+        event: &str,
+        activity: &Activity,
+    ) -> Result<(), ActivityError> {
+        let enabled = read_setting::<WebhookSettings>(conn, WEBHOOK_SETTINGS_KEY)
+            .await?
+            .is_some_and(|settings| settings.enabled);
+        if !enabled {
+            return Ok(());
+        }
+        let privacy = read_setting::<PrivacySettings>(conn, PRIVACY_SETTINGS_KEY)
+            .await?
+            .unwrap_or_default();
+        if !privacy.shares(activity) {
+            log::debug!(
+                "[DB] enqueue_webhook_delivery: activity_id={} skipped, it is private",
+                activity.id
+            );
+            return Ok(());
+        }
+
+        let payload = serde_json::json!({
+            "event": event,
+            "activity": ActivityResponse::from(activity.clone()),
+        });
+        sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (activity_id, event, payload, attempts, next_attempt_at, created_at)
+            VALUES (?, ?, ?, 0, ?, ?)
+            "#,
+        )
+        .bind(activity.id)
+        .bind(event)
+        .bind(payload.to_string())
+        .bind(Utc::now())
+        .bind(Utc::now())
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        log::debug!(
+            "[DB] enqueue_webhook_delivery: activity_id={}, event={event}",
+            activity.id
+        );
+        Ok(())
+    }
+
+    /// Deliveries whose next attempt is due, oldest first
+    pub async fn get_due_webhook_deliveries(
+        &self,
+        now: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, ActivityError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT * FROM webhook_deliveries
+            WHERE attempts < ? AND next_attempt_at <= ?
+            ORDER BY id
+            LIMIT ?
+            "#,
+        )
+        .bind(MAX_DELIVERY_ATTEMPTS)
+        .bind(now)
+        .bind(limit)
+        .fetch_all(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(rows
+            .iter()
+            .map(|row| WebhookDelivery {
+                id: row.get("id"),
+                activity_id: row.get("activity_id"),
+                event: row.get("event"),
+                payload: row.get("payload"),
+                attempts: row.get("attempts"),
+                next_attempt_at: row.get("next_attempt_at"),
+                last_error: row.get("last_error"),
+            })
+            .collect())
+    }
+
+    /// Remove a delivery the receiver accepted
+    pub async fn complete_webhook_delivery(&self, id: i64) -> Result<(), ActivityError> {
+        sqlx::query("DELETE FROM webhook_deliveries WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(())
+    }
+
+    /// Count a failed attempt and schedule the retry with backoff
+    pub async fn record_webhook_failure(
+        &self,
+        delivery: &WebhookDelivery,
+        error: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), ActivityError> {
+        let attempts = delivery.attempts + 1;
+        sqlx::query(
+            "UPDATE webhook_deliveries SET attempts = ?, next_attempt_at = ?, last_error = ? WHERE id = ?",
+        )
+        .bind(attempts)
+        .bind(now + webhook::retry_delay(attempts))
+        .bind(error)
+        .bind(delivery.id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        if attempts >= MAX_DELIVERY_ATTEMPTS {
+            log::warn!(
+                "[DB] record_webhook_failure: giving up on delivery_id={} after {attempts} attempts: {error}",
+                delivery.id
+            );
+        }
+        Ok(())
+    }
+
+    /// Pending and failed deliveries
+    pub async fn get_webhook_queue_status(&self) -> Result<WebhookQueueStatus, ActivityError> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COALESCE(SUM(attempts < ?), 0) AS pending,
+                COALESCE(SUM(attempts >= ?), 0) AS failed,
+                (SELECT last_error FROM webhook_deliveries
+                 WHERE last_error IS NOT NULL ORDER BY id DESC LIMIT 1) AS last_error
+            FROM webhook_deliveries
+            "#,
+        )
+        .bind(MAX_DELIVERY_ATTEMPTS)
+        .bind(MAX_DELIVERY_ATTEMPTS)
+        .fetch_one(&self.read_pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+
+        Ok(WebhookQueueStatus {
+            pending: row.get("pending"),
+            failed: row.get("failed"),
+            last_error: row.get("last_error"),
+        })
+    }
+
+    /// Give failed deliveries a fresh set of attempts, starting now.
+    /// Returns the number of deliveries requeued.
+    pub async fn retry_failed_webhook_deliveries(&self) -> Result<u64, ActivityError> {
+        let result = sqlx::query(
+            "UPDATE webhook_deliveries SET attempts = 0, next_attempt_at = ? WHERE attempts >= ?",
+        )
+        .bind(Utc::now())
+        .bind(MAX_DELIVERY_ATTEMPTS)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Drop every queued delivery
+    pub async fn clear_webhook_deliveries(&self) -> Result<u64, ActivityError> {
+        let result = sqlx::query("DELETE FROM webhook_deliveries")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Drop queued deliveries of activities in `categories`, for categories that
+    /// were just made private
+    pub async fn clear_webhook_deliveries_for(
+        &self,
+        categories: &[ActivityCategory],
+    ) -> Result<u64, ActivityError> {
+        let mut dropped = 0;
+        for category in categories {
+            dropped += sqlx::query(
+                r#"
+                DELETE FROM webhook_deliveries
+                WHERE activity_id IN (SELECT id FROM activities WHERE category = ?)
+                "#,
+            )
+            .bind(category.to_string())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?
+            .rows_affected();
+        }
+        Ok(dropped)
+    }
+}
+
+/// Setting read on the transaction's connection; unreadable values count as unset
+async fn read_setting<T: DeserializeOwned>(
+    conn: &mut SqliteConnection,
+    key: &str,
+) -> Result<Option<T>, ActivityError> {
+    let value: Option<String> = sqlx::query_scalar("SELECT value FROM app_settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| ActivityError::invalid_data(format!("Database error: {e}")))?;
+    Ok(value.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+/// Queues new activities for the automation webhook. Queuing in the same transaction
+/// means an activity is never posted unless it was saved, and never lost if the app
+/// closes before the receiver answers.
+pub struct WebhookOutboxHook;
+
+impl ActivityHook for WebhookOutboxHook {
+    fn name(&self) -> &'static str {
+        "webhook_outbox"
+    }
+
+    fn on_activity_event<'a>(
+        &'a self,
+        conn: &'a mut SqliteConnection,
+        event: ActivityEvent<'a>,
+    ) -> BoxFuture<'a, Result<(), ActivityError>> {
+        Box::pin(async move {
+            let ActivityEvent::Created(activity) = event else {
+                return Ok(());
+            };
+            if activity.subcategory == DAILY_SUMMARY_SUBCATEGORY {
+                return Ok(());
+            }
+            super::PetDatabase::enqueue_webhook_delivery(
+                conn,
+                webhook::ACTIVITY_CREATED_EVENT,
+                activity,
+            )
+            .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{seeded_database, FixtureConfig};
+    use super::*;
+
+    fn meal(pet_id: i64) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Diet,
+            subcategory: "Meal".to_string(),
+            activity_data: None,
+            needs_review: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_created_activities_are_queued_when_enabled() {
+        let config = FixtureConfig {
+            pets: 1,
+            activities_per_pet: 0,
+            ..Default::default()
+        };
+        let (db, _dir, summary) = seeded_database(&config).await;
+        let pet_id = summary.pet_ids[0];
+
+        // Disabled by default: nothing is queued
+        db.create_activity_with_side_effects(meal(pet_id))
+            .await
+            .unwrap();
+        assert_eq!(db.get_webhook_queue_status().await.unwrap().pending, 0);
+
+        db.set_setting(
+            WEBHOOK_SETTINGS_KEY,
+            &WebhookSettings {
+                enabled: true,
+                url: Some("http://localhost:8123/api/webhook/feed".to_string()),
+            },
+        )
+        .await
+        .unwrap();
+        let activity = db
+            .create_activity_with_side_effects(meal(pet_id))
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        let due = db.get_due_webhook_deliveries(now, 10).await.unwrap();
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].activity_id, activity.id);
+        let payload: serde_json::Value = serde_json::from_str(&due[0].payload).unwrap();
+        assert_eq!(payload["event"], "activity.created");
+        assert_eq!(payload["activity"]["id"], activity.id);
+
+        // Failures back off and are given up after the last attempt
+        let mut delivery = due[0].clone();
+        for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+            db.record_webhook_failure(&delivery, "connection refused", now)
+                .await
+                .unwrap();
+            delivery.attempts = attempt;
+        }
+        assert!(db
+            .get_due_webhook_deliveries(now, 10)
+            .await
+            .unwrap()
+            .is_empty());
+        let status = db.get_webhook_queue_status().await.unwrap();
+        assert_eq!((status.pending, status.failed), (0, 1));
+        assert_eq!(status.last_error.as_deref(), Some("connection refused"));
+
+        assert_eq!(db.retry_failed_webhook_deliveries().await.unwrap(), 1);
+        let due = db.get_due_webhook_deliveries(Utc::now(), 10).await.unwrap();
+        assert_eq!(due.len(), 1);
+        db.complete_webhook_delivery(due[0].id).await.unwrap();
+        assert_eq!(db.get_webhook_queue_status().await.unwrap().pending, 0);
+
+        // Categories hidden in the privacy settings are never posted
+        db.create_activity_with_side_effects(meal(pet_id))
+            .await
+            .unwrap();
+        assert_eq!(
+            db.clear_webhook_deliveries_for(&[ActivityCategory::Diet])
+                .await
+                .unwrap(),
+            1
+        );
+        db.set_setting(
+            PRIVACY_SETTINGS_KEY,
+            &PrivacySettings {
+                hidden_categories: vec![ActivityCategory::Diet],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        db.create_activity_with_side_effects(meal(pet_id))
+            .await
+            .unwrap();
+        assert_eq!(db.get_webhook_queue_status().await.unwrap().pending, 0);
+    }
+}
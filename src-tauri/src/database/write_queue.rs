@@ -0,0 +1,185 @@
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// Whose write is waiting: the user's, or a job running behind their back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WritePriority {
+    /// Commands the user is waiting on, such as logging an activity
+    #[default]
+    Interactive,
+    /// Maintenance and scheduled jobs (index rebuilds, daily summaries, ...)
+    Background,
+}
+
+tokio::task_local! {
+    static TASK_PRIORITY: WritePriority;
+}
+
+/// Lets one write run at a time. Interactive writes go ahead of every waiting
+/// background write, so a maintenance job that starts while the user is logging
+/// costs them at most the write already running.
+#[derive(Debug, Default)]
+pub struct WriteScheduler {
+    queue: Mutex<WriteQueue>,
+    turn: Notify,
+}
+
+#[derive(Debug, Default)]
+struct WriteQueue {
+    writing: bool,
+    interactive_waiting: usize,
+    background_waiting: usize,
+}
+
+impl WriteQueue {
+    fn waiting(&mut self, priority: WritePriority) -> &mut usize {
+        match priority {
+            WritePriority::Interactive => &mut self.interactive_waiting,
+            WritePriority::Background => &mut self.background_waiting,
+        }
+    }
+}
+
+/// The turn to write; the next waiting write may start once it is dropped
+#[derive(Debug)]
+pub struct WritePermit<'a> {
+    scheduler: &'a WriteScheduler,
+}
+
+impl WriteScheduler {
+    /// Wait for the turn to write. Don't call this again while holding a permit:
+    /// the second call waits for the first permit and never gets one.
+    pub async fn acquire(&self, priority: WritePriority) -> WritePermit<'_> {
+        *self.lock().waiting(priority) += 1;
+        // Leaves the queue if this future is dropped while waiting
+        let _waiting = Waiting {
+            scheduler: self,
+            priority,
+        };
+
+        loop {
+            let turn = self.turn.notified();
+            tokio::pin!(turn);
+            // Register before checking, so a release in between isn't missed
+            turn.as_mut().enable();
+            {
+                let mut queue = self.lock();
+                let ahead = priority == WritePriority::Background && queue.interactive_waiting > 0;
+                if !queue.writing && !ahead {
+                    queue.writing = true;
+                    return WritePermit { scheduler: self };
+                }
+            }
+            turn.await;
+        }
+    }
+
+    /// Wait for the turn to write at the priority of the current task:
+    /// background inside `in_background`, interactive otherwise
+    pub async fn acquire_for_task(&self) -> WritePermit<'_> {
+        self.acquire(current_priority()).await
+    }
+
+    /// Writes waiting for their turn, interactive and background
+    pub fn waiting(&self) -> (usize, usize) {
+        let queue = self.lock();
+        (queue.interactive_waiting, queue.background_waiting)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, WriteQueue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for WritePermit<'_> {
+    fn drop(&mut self) {
+        self.scheduler.lock().writing = false;
+        self.scheduler.turn.notify_waiters();
+    }
+}
+
+struct Waiting<'a> {
+    scheduler: &'a WriteScheduler,
+    priority: WritePriority,
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        *self.scheduler.lock().waiting(self.priority) -= 1;
+        // Background writes may have been held back only by this one
+        self.scheduler.turn.notify_waiters();
+    }
+}
+
+/// Priority the writes of the current task get
+pub fn current_priority() -> WritePriority {
+    TASK_PRIORITY
+        .try_with(|priority| *priority)
+        .unwrap_or_default()
+}
+
+/// Run `job` with its database writes queued as background work
+pub async fn in_background<F: Future>(job: F) -> F::Output {
+    TASK_PRIORITY.scope(WritePriority::Background, job).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    async fn wait_until_queued(scheduler: &WriteScheduler, expected: (usize, usize)) {
+        while scheduler.waiting() != expected {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_interactive_writes_go_first() {
+        let scheduler = Arc::new(WriteScheduler::default());
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let running = scheduler.acquire(WritePriority::Background).await;
+
+        let write = |label: &'static str, priority| {
+            let (scheduler, order) = (scheduler.clone(), order.clone());
+            tokio::spawn(async move {
+                let _permit = scheduler.acquire(priority).await;
+                order.lock().unwrap().push(label);
+            })
+        };
+        let maintenance = write("maintenance", WritePriority::Background);
+        wait_until_queued(&scheduler, (0, 1)).await;
+        let log_meal = write("log meal", WritePriority::Interactive);
+        wait_until_queued(&scheduler, (1, 1)).await;
+
+        drop(running);
+        maintenance.await.unwrap();
+        log_meal.await.unwrap();
+        assert_eq!(*order.lock().unwrap(), vec!["log meal", "maintenance"]);
+        assert_eq!(scheduler.waiting(), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_task_priority_and_cancelled_waits() {
+        assert_eq!(current_priority(), WritePriority::Interactive);
+        assert_eq!(
+            in_background(async { current_priority() }).await,
+            WritePriority::Background
+        );
+
+        let scheduler = WriteScheduler::default();
+        let running = scheduler.acquire(WritePriority::Interactive).await;
+        // A wait that gives up leaves the queue
+        let timed_out = tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            scheduler.acquire(WritePriority::Interactive),
+        )
+        .await;
+        assert!(timed_out.is_err());
+        assert_eq!(scheduler.waiting(), (0, 0));
+
+        drop(running);
+        let _background = in_background(scheduler.acquire_for_task()).await;
+    }
+}
@@ -0,0 +1,305 @@
+use image::imageops::{self, FilterType};
+use image::{DynamicImage, GenericImageView, GrayImage, Rgba, RgbaImage};
+use std::collections::VecDeque;
+
+/// Longest edge of the downscaled copy the document edges are detected on
+const DETECTION_SIZE_PX: u32 = 512;
+
+/// A detected document must cover at least this share of the photo
+const MIN_DOCUMENT_AREA_RATIO: f32 = 0.2;
+
+/// A document covering more than this share is already framed; there is nothing to crop
+const MAX_DOCUMENT_AREA_RATIO: f32 = 0.95;
+
+/// Corners of a document in a photo, in pixels: top-left, top-right, bottom-right, bottom-left
+pub type DocumentCorners = [(f32, f32); 4];
+
+/// Crop a photographed document out of its background and correct the perspective,
+/// so a certificate shot at an angle comes out as a flat, upright page.
+/// Returns None when no document stands out from the background.
+pub fn straighten_document(img: &DynamicImage) -> Option<DynamicImage> {
+    let corners = detect_document_corners(img)?;
+    Some(DynamicImage::ImageRgba8(warp_perspective(
+        &img.to_rgba8(),
+        &corners,
+    )))
+}
+
+/// Find the corners of a sheet of paper lying on a darker background.
+///
+/// Heuristic: on a blurred, downscaled grayscale copy, split pixels into paper and
+/// background with Otsu's threshold, take the largest bright region and use its
+/// extreme points along the diagonals as corners.
+pub fn detect_document_corners(img: &DynamicImage) -> Option<DocumentCorners> {
+    let (width, height) = img.dimensions();
+    if width < 16 || height < 16 {
+        return None;
+    }
+
+    let small = img
+        .resize(DETECTION_SIZE_PX, DETECTION_SIZE_PX, FilterType::Triangle)
+        .to_luma8();
+    let small = imageops::blur(&small, 1.5);
+    let threshold = otsu_threshold(&small);
+    let region = largest_bright_region(&small, threshold);
+
+    let small_area = (small.width() * small.height()) as f32;
+    if (region.len() as f32) < small_area * MIN_DOCUMENT_AREA_RATIO {
+        return None;
+    }
+
+    // Extreme points along x + y and x - y are the corners of a roughly upright quad
+    let by = |key: fn(&(u32, u32)) -> i64, largest: bool| {
+        let point = if largest {
+            region.iter().max_by_key(|p| key(p))
+        } else {
+            region.iter().min_by_key(|p| key(p))
+        };
+        point.map(|&(x, y)| (x as f32, y as f32))
+    };
+    let sum = |p: &(u32, u32)| p.0 as i64 + p.1 as i64;
+    let difference = |p: &(u32, u32)| p.0 as i64 - p.1 as i64;
+    let small_corners = [
+        by(sum, false)?,
+        by(difference, true)?,
+        by(sum, true)?,
+        by(difference, false)?,
+    ];
+
+    let quad_area = polygon_area(&small_corners);
+    if !(small_area * MIN_DOCUMENT_AREA_RATIO..=small_area * MAX_DOCUMENT_AREA_RATIO)
+        .contains(&quad_area)
+    {
+        return None;
+    }
+
+    // Map pixel centers of the small copy back to the full-size photo
+    let scale_x = width as f32 / small.width() as f32;
+    let scale_y = height as f32 / small.height() as f32;
+    Some(small_corners.map(|(x, y)| ((x + 0.5) * scale_x, (y + 0.5) * scale_y)))
+}
+
+/// Threshold that best separates the two classes of a grayscale histogram
+fn otsu_threshold(img: &GrayImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in img.pixels() {
+        histogram[pixel.0[0] as usize] += 1;
+    }
+
+    let total = img.pixels().len() as f64;
+    let weighted_total: f64 = histogram
+        .iter()
+        .enumerate()
+        .map(|(level, &count)| level as f64 * count as f64)
+        .sum();
+
+    let (mut best_threshold, mut best_variance) = (0, 0.0);
+    let (mut background_count, mut background_sum) = (0.0, 0.0);
+    for (level, &count) in histogram.iter().enumerate() {
+        background_count += count as f64;
+        background_sum += level as f64 * count as f64;
+        let foreground_count = total - background_count;
+        if background_count == 0.0 || foreground_count == 0.0 {
+            continue;
+        }
+        let background_mean = background_sum / background_count;
+        let foreground_mean = (weighted_total - background_sum) / foreground_count;
+        let variance =
+            background_count * foreground_count * (background_mean - foreground_mean).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best_threshold = level;
+        }
+    }
+    best_threshold as u8
+}
+
+/// Pixels of the largest 4-connected region brighter than `threshold`
+fn largest_bright_region(img: &GrayImage, threshold: u8) -> Vec<(u32, u32)> {
+    let (width, height) = img.dimensions();
+    let mut visited = vec![false; (width * height) as usize];
+    let mut largest = Vec::new();
+
+    for start_y in 0..height {
+        for start_x in 0..width {
+            let start = (start_y * width + start_x) as usize;
+            if visited[start] || img.get_pixel(start_x, start_y).0[0] <= threshold {
+                continue;
+            }
+
+            visited[start] = true;
+            let mut region = Vec::new();
+            let mut queue = VecDeque::from([(start_x, start_y)]);
+            while let Some((x, y)) = queue.pop_front() {
+                region.push((x, y));
+                let neighbors = [
+                    (x.wrapping_sub(1), y),
+                    (x + 1, y),
+                    (x, y.wrapping_sub(1)),
+                    (x, y + 1),
+                ];
+                for (nx, ny) in neighbors {
+                    if nx >= width || ny >= height {
+                        continue;
+                    }
+                    let index = (ny * width + nx) as usize;
+                    if !visited[index] && img.get_pixel(nx, ny).0[0] > threshold {
+                        visited[index] = true;
+                        queue.push_back((nx, ny));
+                    }
+                }
+            }
+
+            if region.len() > largest.len() {
+                largest = region;
+            }
+        }
+    }
+    largest
+}
+
+/// Area of a polygon with the shoelace formula
+fn polygon_area(points: &[(f32, f32)]) -> f32 {
+    let twice_area: f32 = points
+        .iter()
+        .zip(points.iter().cycle().skip(1))
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum();
+    twice_area.abs() / 2.0
+}
+
+fn distance(a: (f32, f32), b: (f32, f32)) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// Resample the quad at `corners` into an upright rectangle as large as its longest edges
+fn warp_perspective(img: &RgbaImage, corners: &DocumentCorners) -> RgbaImage {
+    let [top_left, top_right, bottom_right, bottom_left] = *corners;
+    let width = distance(top_left, top_right)
+        .max(distance(bottom_left, bottom_right))
+        .round()
+        .max(1.0);
+    let height = distance(top_left, bottom_left)
+        .max(distance(top_right, bottom_right))
+        .round()
+        .max(1.0);
+
+    let Some(h) = homography(
+        &[
+            (0.0, 0.0),
+            (width - 1.0, 0.0),
+            (width - 1.0, height - 1.0),
+            (0.0, height - 1.0),
+        ],
+        corners,
+    ) else {
+        return img.clone();
+    };
+
+    RgbaImage::from_fn(width as u32, height as u32, |x, y| {
+        let (x, y) = (x as f32, y as f32);
+        let w = h[6] * x + h[7] * y + 1.0;
+        let u = (h[0] * x + h[1] * y + h[2]) / w;
+        let v = (h[3] * x + h[4] * y + h[5]) / w;
+        imageops::interpolate_bilinear(img, u, v).unwrap_or(Rgba([255, 255, 255, 255]))
+    })
+}
+
+/// Homography taking each `from` point to the matching `to` point, as the first
+/// eight entries of the 3x3 matrix (the last one is 1)
+fn homography(from: &[(f32, f32); 4], to: &[(f32, f32); 4]) -> Option<[f32; 8]> {
+    let mut system = [[0f64; 9]; 8];
+    for (i, (&(x, y), &(u, v))) in from.iter().zip(to).enumerate() {
+        let (x, y, u, v) = (x as f64, y as f64, u as f64, v as f64);
+        system[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y, u];
+        system[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y, v];
+    }
+
+    // Gaussian elimination with partial pivoting
+    for column in 0..8 {
+        let pivot = (column..8)
+            .max_by(|&a, &b| system[a][column].abs().total_cmp(&system[b][column].abs()))?;
+        if system[pivot][column].abs() < 1e-9 {
+            return None;
+        }
+        system.swap(column, pivot);
+        for row in 0..8 {
+            if row == column {
+                continue;
+            }
+            let pivot_row = system[column];
+            let factor = system[row][column] / pivot_row[column];
+            for (value, pivot_value) in system[row][column..].iter_mut().zip(&pivot_row[column..]) {
+                *value -= factor * pivot_value;
+            }
+        }
+    }
+
+    let mut h = [0f32; 8];
+    for (i, value) in h.iter_mut().enumerate() {
+        *value = (system[i][8] / system[i][i]) as f32;
+    }
+    Some(h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dark table with a white sheet photographed at an angle
+    fn photographed_sheet(corners: DocumentCorners) -> DynamicImage {
+        let inside = |x: f32, y: f32| {
+            (0..4).all(|i| {
+                let (a, b) = (corners[i], corners[(i + 1) % 4]);
+                (b.0 - a.0) * (y - a.1) - (b.1 - a.1) * (x - a.0) >= 0.0
+            })
+        };
+        DynamicImage::ImageRgba8(RgbaImage::from_fn(800, 600, |x, y| {
+            if inside(x as f32 + 0.5, y as f32 + 0.5) {
+                Rgba([245, 240, 230, 255])
+            } else {
+                Rgba([60, 50, 40, 255])
+            }
+        }))
+    }
+
+    #[test]
+    fn test_straighten_photographed_sheet() {
+        let sheet = [
+            (150.0, 80.0),
+            (640.0, 120.0),
+            (600.0, 540.0),
+            (120.0, 500.0),
+        ];
+        let img = photographed_sheet(sheet);
+
+        let corners = detect_document_corners(&img).unwrap();
+        for (found, expected) in corners.iter().zip(sheet) {
+            assert!(
+                distance(*found, expected) < 8.0,
+                "corner {found:?} too far from {expected:?}"
+            );
+        }
+
+        let straightened = straighten_document(&img).unwrap();
+        let (width, height) = straightened.dimensions();
+        assert!((485..=500).contains(&width), "width {width}");
+        assert!((415..=430).contains(&height), "height {height}");
+        // Only paper is left after cropping
+        let center = straightened.get_pixel(width / 2, height / 2);
+        assert!(center.0[0] > 200);
+        let edge = straightened.get_pixel(4, height / 2);
+        assert!(edge.0[0] > 200);
+    }
+
+    #[test]
+    fn test_no_document_found() {
+        // Uniform photo: nothing to separate
+        let blank = DynamicImage::ImageRgba8(RgbaImage::from_pixel(400, 300, Rgba([200; 4])));
+        assert!(straighten_document(&blank).is_none());
+
+        // Already a tightly framed page
+        let framed = photographed_sheet([(2.0, 2.0), (797.0, 2.0), (797.0, 597.0), (2.0, 597.0)]);
+        assert!(straighten_document(&framed).is_none());
+    }
+}
@@ -0,0 +1,201 @@
+use crate::errors::PetError;
+use crate::file_type::{self, FileKind};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Maximum accepted document size (25 MB)
+pub const MAX_DOCUMENT_SIZE: usize = 25 * 1024 * 1024;
+
+/// File extensions accepted by the document vault
+const ALLOWED_EXTENSIONS: [&str; 7] = ["pdf", "jpg", "jpeg", "png", "webp", "txt", "heic"];
+
+/// Content types accepted by the document vault
+const DOCUMENT_FILE_KINDS: [FileKind; 6] = [
+    FileKind::Pdf,
+    FileKind::Jpeg,
+    FileKind::Png,
+    FileKind::Webp,
+    FileKind::Text,
+    FileKind::Heic,
+];
+
+/// Content-addressed storage for pet documents
+pub struct DocumentService {
+    storage_dir: PathBuf,
+}
+
+/// Result of storing a document file
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct StoredDocument {
+    /// Stored filename (`<sha256>.<ext>`)
+    pub filename: String,
+    pub sha256: String,
+    pub file_size: u64,
+    pub mime_type: String,
+}
+
+impl DocumentService {
+    /// Create a new DocumentService with the specified storage directory
+    pub fn new<P: AsRef<Path>>(storage_dir: P) -> Result<Self, PetError> {
+        let storage_dir = storage_dir.as_ref().to_path_buf();
+
+        if !storage_dir.exists() {
+            fs::create_dir_all(&storage_dir).map_err(|e| {
+                PetError::file_system(format!("Failed to create document directory: {e}"))
+            })?;
+        }
+
+        if !storage_dir.is_dir() {
+            return Err(PetError::file_system(
+                "Document path exists but is not a directory",
+            ));
+        }
+
+        Ok(DocumentService { storage_dir })
+    }
+
+    /// Store document bytes under their SHA-256 hash.
+    /// Identical content is stored only once.
+    pub fn store_document(
+        &self,
+        bytes: &[u8],
+        original_filename: &str,
+    ) -> Result<StoredDocument, PetError> {
+        if bytes.is_empty() {
+            return Err(PetError::validation(
+                "document_bytes",
+                "Document data cannot be empty",
+            ));
+        }
+
+        if bytes.len() > MAX_DOCUMENT_SIZE {
+            return Err(PetError::resource_limit(format!(
+                "Document exceeds maximum size of {} MB",
+                MAX_DOCUMENT_SIZE / 1024 / 1024
+            )));
+        }
+
+        let extension = Path::new(original_filename)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_lowercase())
+            .unwrap_or_default();
+        if !ALLOWED_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(PetError::validation(
+                "filename".to_string(),
+                format!("Unsupported document type '.{extension}'"),
+            ));
+        }
+
+        let kind = file_type::verify_content(bytes, Some(&extension), &DOCUMENT_FILE_KINDS)?;
+
+        let sha256 = hash_bytes(bytes);
+        let filename = format!("{sha256}.{extension}");
+        let target_path = self.storage_dir.join(&filename);
+
+        if target_path.exists() {
+            log::info!("Document content already stored: {filename}");
+        } else {
+            // Write to a temporary file first so a partial write never leaves a valid-looking file
+            let temp_path = self.storage_dir.join(format!("{filename}.tmp"));
+            fs::write(&temp_path, bytes)
+                .map_err(|e| PetError::file_system(format!("Failed to write document: {e}")))?;
+            fs::rename(&temp_path, &target_path)
+                .map_err(|e| PetError::file_system(format!("Failed to store document: {e}")))?;
+            log::info!("Stored document: {filename} ({} bytes)", bytes.len());
+        }
+
+        Ok(StoredDocument {
+            mime_type: kind.mime_type().to_string(),
+            filename,
+            sha256,
+            file_size: bytes.len() as u64,
+        })
+    }
+
+    /// Get the full path to a stored document
+    pub fn get_document_path(&self, filename: &str) -> Result<PathBuf, PetError> {
+        validate_filename(filename)?;
+
+        let path = self.storage_dir.join(filename);
+        if !path.exists() {
+            return Err(PetError::file_system("Document file does not exist"));
+        }
+        Ok(path)
+    }
+
+    /// Delete a stored document file
+    pub fn delete_document(&self, filename: &str) -> Result<(), PetError> {
+        validate_filename(filename)?;
+
+        let path = self.storage_dir.join(filename);
+        if path.exists() {
+            fs::remove_file(&path)
+                .map_err(|e| PetError::file_system(format!("Failed to delete document: {e}")))?;
+            log::info!("Deleted document file: {filename}");
+        } else {
+            log::warn!("Document file not found for deletion: {filename}");
+        }
+        Ok(())
+    }
+}
+
+/// Compute the lowercase hex SHA-256 digest of the given bytes
+pub fn hash_bytes(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn validate_filename(filename: &str) -> Result<(), PetError> {
+    if filename.trim().is_empty() {
+        return Err(PetError::invalid_input("Document filename cannot be empty"));
+    }
+
+    // Validate filename for security (prevent path traversal)
+    if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
+        return Err(PetError::invalid_input("Invalid document filename"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_store_document_is_content_addressed() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = DocumentService::new(temp_dir.path()).unwrap();
+
+        let first = service
+            .store_document(b"%PDF-1.4 test", "papers.pdf")
+            .unwrap();
+        let second = service
+            .store_document(b"%PDF-1.4 test", "copy.PDF")
+            .unwrap();
+
+        assert_eq!(first.filename, second.filename);
+        assert_eq!(first.sha256.len(), 64);
+        assert_eq!(first.mime_type, "application/pdf");
+        assert!(service.get_document_path(&first.filename).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unsupported_and_unsafe_input() {
+        let temp_dir = TempDir::new().unwrap();
+        let service = DocumentService::new(temp_dir.path()).unwrap();
+
+        assert!(service.store_document(b"data", "script.exe").is_err());
+        assert!(service.store_document(b"", "empty.pdf").is_err());
+        // Content must match the extension
+        assert!(service
+            .store_document(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "report.pdf")
+            .is_err());
+        assert!(service
+            .store_document(b"MZ\x90\0\x03\0\0\0", "invoice.pdf")
+            .is_err());
+        assert!(service.get_document_path("../pets.db").is_err());
+        assert!(service.delete_document("a/b.pdf").is_err());
+    }
+}
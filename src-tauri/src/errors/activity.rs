@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::common::{AppError, ErrorSeverity};
+use super::common::{AccessDenied, AppError, ErrorSeverity};
 
 /// Error types for activity management operations
 #[derive(Error, Debug, Serialize, Deserialize, Clone)]
@@ -23,6 +23,18 @@ pub enum ActivityError {
 
     #[error("Activity date out of range: {message}")]
     DateOutOfRange { message: String },
+
+    #[error("Recurring series not found with id: {id}")]
+    SeriesNotFound { id: i64 },
+
+    #[error("Permission denied: {message}")]
+    PermissionDenied { message: String },
+
+    #[error("Custom block not found with id: {id}")]
+    CustomBlockNotFound { id: i64 },
+
+    #[error("Job not found with id: {id}")]
+    JobNotFound { id: String },
 }
 
 impl ActivityError {
@@ -61,6 +73,28 @@ impl ActivityError {
         }
     }
 
+    /// Create a new SeriesNotFound error
+    pub fn series_not_found(id: i64) -> Self {
+        ActivityError::SeriesNotFound { id }
+    }
+
+    /// Create a new PermissionDenied error
+    pub fn permission_denied<S: Into<String>>(message: S) -> Self {
+        ActivityError::PermissionDenied {
+            message: message.into(),
+        }
+    }
+
+    /// Create a new CustomBlockNotFound error
+    pub fn custom_block_not_found(id: i64) -> Self {
+        ActivityError::CustomBlockNotFound { id }
+    }
+
+    /// Create a new JobNotFound error
+    pub fn job_not_found<S: Into<String>>(id: S) -> Self {
+        ActivityError::JobNotFound { id: id.into() }
+    }
+
     /// Create a new DateOutOfRange error
     pub fn date_out_of_range<S: Into<String>>(message: S) -> Self {
         ActivityError::DateOutOfRange {
@@ -78,6 +112,10 @@ impl AppError for ActivityError {
             ActivityError::Validation { .. } => ErrorSeverity::Warning,
             ActivityError::PetMismatch { .. } => ErrorSeverity::Error,
             ActivityError::DateOutOfRange { .. } => ErrorSeverity::Warning,
+            ActivityError::SeriesNotFound { .. } => ErrorSeverity::Info,
+            ActivityError::PermissionDenied { .. } => ErrorSeverity::Error,
+            ActivityError::CustomBlockNotFound { .. } => ErrorSeverity::Info,
+            ActivityError::JobNotFound { .. } => ErrorSeverity::Info,
         }
     }
 
@@ -89,6 +127,10 @@ impl AppError for ActivityError {
             ActivityError::Validation { .. } => true,
             ActivityError::PetMismatch { .. } => false,
             ActivityError::DateOutOfRange { .. } => true,
+            ActivityError::SeriesNotFound { .. } => false,
+            ActivityError::PermissionDenied { .. } => false,
+            ActivityError::CustomBlockNotFound { .. } => false,
+            ActivityError::JobNotFound { .. } => false,
         }
     }
 
@@ -100,6 +142,16 @@ impl AppError for ActivityError {
             ActivityError::Validation { .. } => "ACTIVITY_VALIDATION_ERROR",
             ActivityError::PetMismatch { .. } => "PET_ACTIVITY_MISMATCH",
             ActivityError::DateOutOfRange { .. } => "ACTIVITY_DATE_OUT_OF_RANGE",
+            ActivityError::SeriesNotFound { .. } => "RECURRING_SERIES_NOT_FOUND",
+            ActivityError::PermissionDenied { .. } => "PERMISSION_DENIED",
+            ActivityError::CustomBlockNotFound { .. } => "CUSTOM_BLOCK_NOT_FOUND",
+            ActivityError::JobNotFound { .. } => "JOB_NOT_FOUND",
         }
     }
 }
+
+impl From<AccessDenied> for ActivityError {
+    fn from(error: AccessDenied) -> Self {
+        ActivityError::permission_denied(error.to_string())
+    }
+}
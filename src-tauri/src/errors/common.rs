@@ -20,3 +20,17 @@ pub trait AppError {
     /// Get error code for client-side handling
     fn error_code(&self) -> &'static str;
 }
+
+/// A command was refused because the current access level does not allow it.
+/// Converts into the `PermissionDenied` variant of each command error type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessDenied {
+    pub command: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for AccessDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.command, self.message)
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
-use super::common::{AppError, ErrorSeverity};
+use super::common::{AccessDenied, AppError, ErrorSeverity};
 
 /// Comprehensive error types for pet management operations
 #[derive(Error, Debug, Serialize, Deserialize, Clone)]
@@ -35,6 +35,18 @@ pub enum PetError {
 
     #[error("Permission denied: {message}")]
     PermissionDenied { message: String },
+
+    #[error("Document not found with id: {id}")]
+    DocumentNotFound { id: i64 },
+
+    #[error("Checklist not found with id: {id}")]
+    ChecklistNotFound { id: i64 },
+
+    #[error("Inventory item not found with id: {id}")]
+    InventoryItemNotFound { id: i64 },
+
+    #[error("Metric rule not found with id: {id}")]
+    MetricRuleNotFound { id: i64 },
 }
 
 impl PetError {
@@ -106,6 +118,26 @@ impl PetError {
             message: message.into(),
         }
     }
+
+    /// Create a new DocumentNotFound error
+    pub fn document_not_found(id: i64) -> Self {
+        PetError::DocumentNotFound { id }
+    }
+
+    /// Create a new ChecklistNotFound error
+    pub fn checklist_not_found(id: i64) -> Self {
+        PetError::ChecklistNotFound { id }
+    }
+
+    /// Create a new InventoryItemNotFound error
+    pub fn inventory_item_not_found(id: i64) -> Self {
+        PetError::InventoryItemNotFound { id }
+    }
+
+    /// Create a new MetricRuleNotFound error
+    pub fn metric_rule_not_found(id: i64) -> Self {
+        PetError::MetricRuleNotFound { id }
+    }
 }
 
 impl AppError for PetError {
@@ -121,6 +153,10 @@ impl AppError for PetError {
             PetError::ConcurrentAccess { .. } => ErrorSeverity::Warning,
             PetError::ResourceLimit { .. } => ErrorSeverity::Error,
             PetError::PermissionDenied { .. } => ErrorSeverity::Error,
+            PetError::DocumentNotFound { .. } => ErrorSeverity::Info,
+            PetError::ChecklistNotFound { .. } => ErrorSeverity::Info,
+            PetError::InventoryItemNotFound { .. } => ErrorSeverity::Info,
+            PetError::MetricRuleNotFound { .. } => ErrorSeverity::Info,
         }
     }
 
@@ -136,6 +172,10 @@ impl AppError for PetError {
             PetError::ConcurrentAccess { .. } => true,
             PetError::ResourceLimit { .. } => false,
             PetError::PermissionDenied { .. } => false,
+            PetError::DocumentNotFound { .. } => false,
+            PetError::ChecklistNotFound { .. } => false,
+            PetError::InventoryItemNotFound { .. } => false,
+            PetError::MetricRuleNotFound { .. } => false,
         }
     }
 
@@ -151,10 +191,20 @@ impl AppError for PetError {
             PetError::ConcurrentAccess { .. } => "CONCURRENT_ACCESS",
             PetError::ResourceLimit { .. } => "RESOURCE_LIMIT",
             PetError::PermissionDenied { .. } => "PERMISSION_DENIED",
+            PetError::DocumentNotFound { .. } => "DOCUMENT_NOT_FOUND",
+            PetError::ChecklistNotFound { .. } => "CHECKLIST_NOT_FOUND",
+            PetError::InventoryItemNotFound { .. } => "INVENTORY_ITEM_NOT_FOUND",
+            PetError::MetricRuleNotFound { .. } => "METRIC_RULE_NOT_FOUND",
         }
     }
 }
 
+impl From<AccessDenied> for PetError {
+    fn from(error: AccessDenied) -> Self {
+        PetError::permission_denied(error.to_string())
+    }
+}
+
 impl From<anyhow::Error> for PetError {
     fn from(error: anyhow::Error) -> Self {
         PetError::operation_failed(error.to_string())
@@ -0,0 +1,504 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Event names emitted to the frontend
+pub const PET_CREATED: &str = "pet:created";
+pub const PET_UPDATED: &str = "pet:updated";
+pub const PET_DELETED: &str = "pet:deleted";
+pub const ACTIVITY_CREATED: &str = "activity:created";
+pub const ACTIVITY_UPDATED: &str = "activity:updated";
+pub const ACTIVITY_DELETED: &str = "activity:deleted";
+pub const REMINDER_DUE: &str = "reminder:due";
+pub const REMINDER_DIGEST: &str = "reminder:digest";
+pub const DOCUMENT_EXPIRING: &str = "document:expiring";
+pub const INVENTORY_LOW_STOCK: &str = "inventory:low-stock";
+pub const METRIC_ALERT: &str = "metric:alert";
+pub const BUDGET_ALERT: &str = "budget:alert";
+pub const GROOMING_DUE: &str = "grooming:due";
+pub const PHOTO_UPLOAD_PROGRESS: &str = "photo:upload-progress";
+pub const EXPORT_PROGRESS: &str = "export:progress";
+pub const EXPORT_COMPLETED: &str = "export:completed";
+pub const EXPORT_FAILED: &str = "export:failed";
+pub const EXPORT_CANCELLED: &str = "export:cancelled";
+pub const JOB_UPDATED: &str = "job:updated";
+pub const JOB_PROGRESS: &str = "job:progress";
+
+/// Number of events kept in the outbox for replay
+const DEFAULT_OUTBOX_CAPACITY: usize = 500;
+
+/// Most changes kept in the session journal; older ones are dropped
+const MAX_SESSION_CHANGES: usize = 5000;
+
+/// An event recorded in the outbox and delivered to all windows
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppEvent {
+    pub seq: u64,
+    pub name: String,
+    pub payload: serde_json::Value,
+    pub emitted_at: DateTime<Utc>,
+}
+
+/// Result of replaying events after a known sequence number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventReplay {
+    pub events: Vec<AppEvent>,
+    pub latest_seq: u64,
+    /// True when older events were evicted and the caller should do a full refresh
+    pub truncated: bool,
+}
+
+/// Payload for events about a deleted entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedPayload {
+    pub id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pet_id: Option<i64>,
+}
+
+/// What a data-changing event did to its entity
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+impl ChangeKind {
+    /// Entity and change of an event named like `activity:created`; None for other events
+    fn from_event_name(name: &str) -> Option<(&str, ChangeKind)> {
+        let (entity, action) = name.split_once(':')?;
+        let kind = match action {
+            "created" => ChangeKind::Created,
+            "updated" => ChangeKind::Updated,
+            "deleted" => ChangeKind::Deleted,
+            _ => return None,
+        };
+        Some((entity, kind))
+    }
+}
+
+/// A create, update or delete made since the app started
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionChange {
+    pub seq: u64,
+    /// Kind of entity changed, e.g. "pet" or "activity"
+    pub entity: String,
+    pub kind: ChangeKind,
+    pub id: i64,
+    pub pet_id: Option<i64>,
+    /// Pet name or activity subcategory, when the event carried it
+    pub label: Option<String>,
+    pub changed_at: DateTime<Utc>,
+}
+
+impl SessionChange {
+    fn from_event(event: &AppEvent) -> Option<Self> {
+        let (entity, kind) = ChangeKind::from_event_name(&event.name)?;
+        let payload = &event.payload;
+        let id = payload.get("id")?.as_i64()?;
+        // Activities are emitted as camelCase DTOs; deletions use `pet_id`
+        let pet_id = match entity {
+            "pet" => Some(id),
+            _ => ["petId", "pet_id"]
+                .iter()
+                .find_map(|key| payload.get(*key)?.as_i64()),
+        };
+        let label = ["name", "subcategory"]
+            .iter()
+            .find_map(|key| payload.get(*key)?.as_str())
+            .map(str::to_string);
+
+        Some(SessionChange {
+            seq: event.seq,
+            entity: entity.to_string(),
+            kind,
+            id,
+            pet_id,
+            label,
+            changed_at: event.emitted_at,
+        })
+    }
+}
+
+/// Number of entities of one kind changed this session, by their net change:
+/// an entity created and then edited counts as created, one created and then
+/// deleted is left out
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SessionChangeCounts {
+    pub entity: String,
+    pub created: usize,
+    pub updated: usize,
+    pub deleted: usize,
+}
+
+/// Everything created, updated or deleted since the app started
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionChanges {
+    pub session_started_at: DateTime<Utc>,
+    /// Changes in the order they were made
+    pub changes: Vec<SessionChange>,
+    pub counts: Vec<SessionChangeCounts>,
+    /// True when the earliest changes were dropped from the journal
+    pub truncated: bool,
+}
+
+/// Net change counts per entity kind, in the order the kinds were first changed
+fn count_net_changes(changes: &[SessionChange]) -> Vec<SessionChangeCounts> {
+    // First and last change of each entity
+    let mut entities: Vec<(&str, i64, ChangeKind, ChangeKind)> = Vec::new();
+    for change in changes {
+        match entities
+            .iter_mut()
+            .find(|(entity, id, ..)| *entity == change.entity && *id == change.id)
+        {
+            Some(entry) => entry.3 = change.kind,
+            None => entities.push((change.entity.as_str(), change.id, change.kind, change.kind)),
+        }
+    }
+
+    let mut counts: Vec<SessionChangeCounts> = Vec::new();
+    for (entity, _, first, last) in entities {
+        let index = match counts.iter().position(|c| c.entity == entity) {
+            Some(index) => index,
+            None => {
+                counts.push(SessionChangeCounts {
+                    entity: entity.to_string(),
+                    ..Default::default()
+                });
+                counts.len() - 1
+            }
+        };
+        let count = &mut counts[index];
+        match (first, last) {
+            (ChangeKind::Created, ChangeKind::Deleted) => {}
+            (_, ChangeKind::Deleted) => count.deleted += 1,
+            (ChangeKind::Created, _) => count.created += 1,
+            _ => count.updated += 1,
+        }
+    }
+    counts
+}
+
+struct SessionJournal {
+    started_at: DateTime<Utc>,
+    changes: VecDeque<SessionChange>,
+    truncated: bool,
+}
+
+struct Outbox {
+    events: VecDeque<AppEvent>,
+    next_seq: u64,
+}
+
+/// Small in-process event bus.
+/// Every published event is appended to a bounded outbox before being emitted,
+/// so views that missed a live event can catch up with `replay_since`.
+pub struct EventBus {
+    outbox: Mutex<Outbox>,
+    capacity: usize,
+    announced_reminders: Mutex<HashSet<(i64, DateTime<Utc>)>>,
+    announced_expiries: Mutex<HashSet<(i64, NaiveDate)>>,
+    announced_budget_alerts: Mutex<HashSet<(i64, NaiveDate, u32)>>,
+    announced_grooming: Mutex<HashSet<(i64, String, NaiveDate)>>,
+    journal: Mutex<SessionJournal>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_OUTBOX_CAPACITY)
+    }
+}
+
+impl EventBus {
+    /// Create an event bus keeping at most `capacity` events for replay
+    pub fn new(capacity: usize) -> Self {
+        EventBus {
+            outbox: Mutex::new(Outbox {
+                events: VecDeque::with_capacity(capacity),
+                next_seq: 1,
+            }),
+            capacity: capacity.max(1),
+            announced_reminders: Mutex::new(HashSet::new()),
+            announced_expiries: Mutex::new(HashSet::new()),
+            announced_budget_alerts: Mutex::new(HashSet::new()),
+            announced_grooming: Mutex::new(HashSet::new()),
+            journal: Mutex::new(SessionJournal {
+                started_at: Utc::now(),
+                changes: VecDeque::new(),
+                truncated: false,
+            }),
+        }
+    }
+
+    /// Record an event in the outbox without delivering it
+    pub fn publish<S: Serialize>(&self, name: &str, payload: S) -> AppEvent {
+        let payload = serde_json::to_value(payload).unwrap_or(serde_json::Value::Null);
+        let mut outbox = self.outbox.lock().unwrap_or_else(|e| e.into_inner());
+
+        let event = AppEvent {
+            seq: outbox.next_seq,
+            name: name.to_string(),
+            payload,
+            emitted_at: Utc::now(),
+        };
+        outbox.next_seq += 1;
+
+        if outbox.events.len() >= self.capacity {
+            outbox.events.pop_front();
+        }
+        outbox.events.push_back(event.clone());
+        drop(outbox);
+
+        if let Some(change) = SessionChange::from_event(&event) {
+            let mut journal = self.journal.lock().unwrap_or_else(|e| e.into_inner());
+            if journal.changes.len() >= MAX_SESSION_CHANGES {
+                journal.changes.pop_front();
+                journal.truncated = true;
+            }
+            journal.changes.push_back(change);
+        }
+        event
+    }
+
+    /// Record an event and deliver it to all windows.
+    /// Delivery failures are logged and never fail the calling command.
+    pub fn emit<S: Serialize>(&self, app: &AppHandle, name: &str, payload: S) -> AppEvent {
+        let event = self.publish(name, payload);
+        if let Err(e) = app.emit(name, event.clone()) {
+            log::warn!("Failed to emit event {name} (seq={}): {e}", event.seq);
+        } else {
+            log::debug!("Emitted event {name} (seq={})", event.seq);
+        }
+        event
+    }
+
+    /// Deliver a transient event (such as progress) without recording it for replay
+    pub fn notify<S: Serialize + Clone>(&self, app: &AppHandle, name: &str, payload: S) {
+        if let Err(e) = app.emit(name, payload) {
+            log::warn!("Failed to emit event {name}: {e}");
+        }
+    }
+
+    /// Get all events with a sequence number greater than `since_seq`
+    pub fn replay_since(&self, since_seq: u64) -> EventReplay {
+        let outbox = self.outbox.lock().unwrap_or_else(|e| e.into_inner());
+        let oldest_seq = outbox.events.front().map(|e| e.seq);
+
+        EventReplay {
+            events: outbox
+                .events
+                .iter()
+                .filter(|e| e.seq > since_seq)
+                .cloned()
+                .collect(),
+            latest_seq: outbox.next_seq - 1,
+            truncated: oldest_seq.is_some_and(|oldest| oldest > since_seq + 1),
+        }
+    }
+
+    /// Creates, updates and deletes published since the bus was created at app start.
+    /// Unlike the replay outbox, the journal keeps every change of the session.
+    pub fn session_changes(&self) -> SessionChanges {
+        let journal = self.journal.lock().unwrap_or_else(|e| e.into_inner());
+        let changes: Vec<SessionChange> = journal.changes.iter().cloned().collect();
+
+        SessionChanges {
+            session_started_at: journal.started_at,
+            counts: count_net_changes(&changes),
+            changes,
+            truncated: journal.truncated,
+        }
+    }
+
+    /// Mark a reminder due at `due_at` as announced. Returns false if it was already
+    /// announced; a rescheduled or repeating reminder is announced again.
+    pub fn mark_reminder_announced(&self, activity_id: i64, due_at: DateTime<Utc>) -> bool {
+        self.announced_reminders
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((activity_id, due_at))
+    }
+
+    /// Mark a document's expiry as announced. Returns false if this expiry date was
+    /// already announced; a renewed date is announced again.
+    pub fn mark_expiry_announced(&self, document_id: i64, expires_on: NaiveDate) -> bool {
+        self.announced_expiries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((document_id, expires_on))
+    }
+
+    /// Mark that a pet's spending in `month` reached `percent` of its budget.
+    /// Returns false if this level was already announced for the month.
+    pub fn mark_budget_alert_announced(&self, pet_id: i64, month: NaiveDate, percent: u32) -> bool {
+        self.announced_budget_alerts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((pet_id, month, percent))
+    }
+
+    /// Mark a pet's grooming task due on `due_on` as announced. Returns false if
+    /// it was already announced; once the task is done, its next due date is new.
+    pub fn mark_grooming_announced(&self, pet_id: i64, task: &str, due_on: NaiveDate) -> bool {
+        self.announced_grooming
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert((pet_id, task.to_string(), due_on))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::dto::ActivityDto;
+    use crate::database::ActivityCategory;
+
+    #[test]
+    fn test_publish_assigns_increasing_seq() {
+        let bus = EventBus::new(10);
+        let first = bus.publish(PET_CREATED, serde_json::json!({ "id": 1 }));
+        let second = bus.publish(PET_UPDATED, serde_json::json!({ "id": 1 }));
+
+        assert_eq!(first.seq, 1);
+        assert_eq!(second.seq, 2);
+
+        let replay = bus.replay_since(1);
+        assert_eq!(replay.events.len(), 1);
+        assert_eq!(replay.events[0].name, PET_UPDATED);
+        assert_eq!(replay.latest_seq, 2);
+        assert!(!replay.truncated);
+    }
+
+    #[test]
+    fn test_replay_reports_truncation() {
+        let bus = EventBus::new(2);
+        for id in 0..5 {
+            bus.publish(ACTIVITY_CREATED, DeletedPayload { id, pet_id: None });
+        }
+
+        let replay = bus.replay_since(0);
+        assert_eq!(replay.events.len(), 2);
+        assert!(replay.truncated);
+
+        let replay = bus.replay_since(3);
+        assert_eq!(replay.events.len(), 2);
+        assert!(!replay.truncated);
+    }
+
+    fn activity_dto(id: i64, subcategory: &str) -> ActivityDto {
+        ActivityDto {
+            id,
+            pet_id: 1,
+            category: ActivityCategory::Lifestyle,
+            subcategory: subcategory.to_string(),
+            activity_data: None,
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_session_changes_survive_outbox_eviction() {
+        let bus = EventBus::new(2);
+        bus.publish(PET_CREATED, serde_json::json!({ "id": 1, "name": "Mochi" }));
+        bus.publish(ACTIVITY_CREATED, activity_dto(10, "Walk"));
+        bus.publish(ACTIVITY_UPDATED, activity_dto(10, "Walk"));
+        bus.publish(ACTIVITY_UPDATED, activity_dto(11, "Play"));
+        bus.publish(
+            ACTIVITY_DELETED,
+            DeletedPayload {
+                id: 12,
+                pet_id: Some(1),
+            },
+        );
+        bus.publish(ACTIVITY_CREATED, activity_dto(13, "Treat"));
+        bus.publish(
+            ACTIVITY_DELETED,
+            DeletedPayload {
+                id: 13,
+                pet_id: Some(1),
+            },
+        );
+        // Progress and export events are not data changes
+        bus.publish(EXPORT_COMPLETED, serde_json::json!({ "id": 99 }));
+
+        let session = bus.session_changes();
+        assert_eq!(session.changes.len(), 7);
+        assert!(!session.truncated);
+        assert_eq!(session.changes[0].entity, "pet");
+        assert_eq!(session.changes[0].pet_id, Some(1));
+        assert_eq!(session.changes[0].label.as_deref(), Some("Mochi"));
+        assert!(session
+            .changes
+            .iter()
+            .all(|change| change.pet_id == Some(1)));
+        assert_eq!(session.changes[1].label.as_deref(), Some("Walk"));
+        assert_eq!(session.changes[4].kind, ChangeKind::Deleted);
+        assert_eq!(session.changes[4].label, None);
+
+        assert_eq!(
+            session.counts,
+            vec![
+                SessionChangeCounts {
+                    entity: "pet".to_string(),
+                    created: 1,
+                    updated: 0,
+                    deleted: 0,
+                },
+                // Created-and-edited counts as created, created-and-deleted not at all
+                SessionChangeCounts {
+                    entity: "activity".to_string(),
+                    created: 1,
+                    updated: 1,
+                    deleted: 1,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reminder_announced_once_per_due_time() {
+        let bus = EventBus::default();
+        let due_at = "2025-03-01T09:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        assert!(bus.mark_reminder_announced(7, due_at));
+        assert!(!bus.mark_reminder_announced(7, due_at));
+        // Rescheduled
+        assert!(bus.mark_reminder_announced(7, due_at + chrono::Duration::days(1)));
+    }
+
+    #[test]
+    fn test_budget_alert_announced_once_per_month_and_level() {
+        let bus = EventBus::default();
+        let march = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let april = NaiveDate::from_ymd_opt(2025, 4, 1).unwrap();
+        assert!(bus.mark_budget_alert_announced(1, march, 80));
+        assert!(!bus.mark_budget_alert_announced(1, march, 80));
+        assert!(bus.mark_budget_alert_announced(1, march, 100));
+        assert!(bus.mark_budget_alert_announced(1, april, 80));
+    }
+
+    #[test]
+    fn test_expiry_announced_once_per_date() {
+        let bus = EventBus::default();
+        let date = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+        assert!(bus.mark_expiry_announced(3, date(1)));
+        assert!(!bus.mark_expiry_announced(3, date(1)));
+        assert!(bus.mark_expiry_announced(3, date(30)));
+    }
+
+    #[test]
+    fn test_grooming_announced_once_per_due_date() {
+        let bus = EventBus::default();
+        let date = |d| NaiveDate::from_ymd_opt(2025, 6, d).unwrap();
+        assert!(bus.mark_grooming_announced(1, "bath", date(1)));
+        assert!(!bus.mark_grooming_announced(1, "bath", date(1)));
+        assert!(bus.mark_grooming_announced(1, "nail_trim", date(1)));
+        assert!(bus.mark_grooming_announced(1, "bath", date(29)));
+    }
+}
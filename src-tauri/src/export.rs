@@ -0,0 +1,650 @@
+use crate::database::activity_data::BlockData;
+use crate::database::{Activity, Pet};
+use crate::errors::PetError;
+use crate::jobs::JobHandle;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Settings key of how export files are named and where they go
+pub const EXPORT_SETTINGS_KEY: &str = "export_settings";
+
+/// Export file name, without extension, unless the user picks another
+pub const DEFAULT_FILENAME_PATTERN: &str = "{pet}_{type}_{date}";
+
+/// Placeholders a file name pattern may use
+pub const FILENAME_PLACEHOLDERS: [&str; 5] = ["pet", "pet_id", "type", "date", "time"];
+
+/// Longest file name pattern accepted
+const MAX_FILENAME_PATTERN_LENGTH: usize = 120;
+
+/// Activities written between progress updates and cancellation checks
+pub const EXPORT_CHUNK_SIZE: usize = 250;
+
+/// Columns of the anonymized research dataset
+pub const ANONYMIZED_CSV_HEADER: [&str; 11] = [
+    "subject",
+    "species",
+    "breed",
+    "gender",
+    "age_months",
+    "recorded_at",
+    "category",
+    "subcategory",
+    "measurement_type",
+    "measurement_value",
+    "measurement_unit",
+];
+
+/// Build a CSV dataset that keeps timestamps, species, breed and measurements
+/// but drops names, notes, photos, attachments, locations and costs.
+///
+/// Pets are replaced by sequential subject codes (`S001`, `S002`, ...) assigned in
+/// the order of `pets`, so the same export never leaks database IDs.
+/// Activities are written one row per measurement block, or a single row
+/// with empty measurement columns when there is none.
+pub fn anonymized_csv(pets: &[Pet], activities: &[Activity]) -> String {
+    let writer = AnonymizedCsvWriter::new(pets);
+    let mut csv = AnonymizedCsvWriter::header();
+    for activity in activities {
+        writer.push_activity(&mut csv, activity);
+    }
+    csv
+}
+
+/// Writes the rows of [`anonymized_csv`] incrementally, for exports built in chunks
+pub struct AnonymizedCsvWriter<'a> {
+    subjects: HashMap<i64, (String, &'a Pet)>,
+}
+
+impl<'a> AnonymizedCsvWriter<'a> {
+    pub fn new(pets: &'a [Pet]) -> Self {
+        AnonymizedCsvWriter {
+            subjects: pets
+                .iter()
+                .enumerate()
+                .map(|(index, pet)| (pet.id, (format!("S{:03}", index + 1), pet)))
+                .collect(),
+        }
+    }
+
+    /// Header line of the dataset
+    pub fn header() -> String {
+        let mut header = ANONYMIZED_CSV_HEADER.join(",");
+        header.push('\n');
+        header
+    }
+
+    /// Append the rows of one activity; returns the number of rows written.
+    /// Activities of pets outside the export are skipped.
+    pub fn push_activity(&self, csv: &mut String, activity: &Activity) -> usize {
+        let Some((subject, pet)) = self.subjects.get(&activity.pet_id) else {
+            return 0;
+        };
+
+        let recorded_at = activity_timestamp(activity);
+        let age_months = age_in_months(pet.birth_date, recorded_at.date_naive());
+        let prefix = [
+            subject.clone(),
+            pet.species.to_string(),
+            pet.breed.clone().unwrap_or_default(),
+            pet.gender.to_string(),
+            age_months.map(|m| m.to_string()).unwrap_or_default(),
+            recorded_at.to_rfc3339(),
+            activity.category.to_string(),
+            activity.subcategory.clone(),
+        ];
+
+        let measurements = measurement_rows(activity);
+        if measurements.is_empty() {
+            push_row(
+                csv,
+                prefix
+                    .iter()
+                    .chain(&[String::new(), String::new(), String::new()]),
+            );
+            return 1;
+        }
+        for measurement in &measurements {
+            push_row(csv, prefix.iter().chain(measurement));
+        }
+        measurements.len()
+    }
+}
+
+/// Payload of export progress events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportProgress {
+    pub job_id: String,
+    /// Activities written so far
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// Payload of the event sent when an export file is ready
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportCompleted {
+    pub job_id: String,
+    pub file_path: String,
+    pub rows: usize,
+}
+
+/// Payload of the event sent when an export failed or was cancelled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportStopped {
+    pub job_id: String,
+    /// None when the export was cancelled
+    pub error: Option<String>,
+}
+
+/// How a background export ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportOutcome {
+    Completed { rows: usize },
+    Cancelled,
+}
+
+/// Write the anonymized dataset to `path`, calling `on_progress(processed, total)` after
+/// each chunk of activities. Data goes to a `.part` file that is only renamed to `path`
+/// once complete, so a cancelled or failed export leaves no file behind.
+pub fn write_anonymized_csv(
+    path: &Path,
+    pets: &[Pet],
+    activities: &[Activity],
+    job: &JobHandle,
+    mut on_progress: impl FnMut(usize, usize),
+) -> std::io::Result<ExportOutcome> {
+    let part_path = path.with_extension("part");
+    let result = write_chunks(&part_path, pets, activities, job, &mut on_progress);
+    match result {
+        Ok(ExportOutcome::Completed { rows }) => {
+            std::fs::rename(&part_path, path)?;
+            Ok(ExportOutcome::Completed { rows })
+        }
+        other => {
+            let _ = std::fs::remove_file(&part_path);
+            other
+        }
+    }
+}
+
+fn write_chunks(
+    part_path: &Path,
+    pets: &[Pet],
+    activities: &[Activity],
+    job: &JobHandle,
+    on_progress: &mut impl FnMut(usize, usize),
+) -> std::io::Result<ExportOutcome> {
+    let writer = AnonymizedCsvWriter::new(pets);
+    let mut file = std::io::BufWriter::new(std::fs::File::create(part_path)?);
+    file.write_all(AnonymizedCsvWriter::header().as_bytes())?;
+
+    let mut rows = 0;
+    let mut processed = 0;
+    let mut chunk = String::new();
+    for batch in activities.chunks(EXPORT_CHUNK_SIZE) {
+        if job.is_cancelled() {
+            return Ok(ExportOutcome::Cancelled);
+        }
+        chunk.clear();
+        for activity in batch {
+            rows += writer.push_activity(&mut chunk, activity);
+        }
+        file.write_all(chunk.as_bytes())?;
+        processed += batch.len();
+        on_progress(processed, activities.len());
+    }
+    file.flush()?;
+    Ok(ExportOutcome::Completed { rows })
+}
+
+/// When the activity happened, from its time block or else its creation time
+fn activity_timestamp(activity: &Activity) -> DateTime<Utc> {
+    let time_block = activity
+        .activity_data
+        .as_ref()
+        .and_then(|data| match data.get("time") {
+            Some(BlockData::Time { date, .. }) => Some(date.as_str()),
+            _ => None,
+        });
+
+    time_block
+        .and_then(|date| {
+            DateTime::parse_from_rfc3339(date)
+                .map(|dt| dt.with_timezone(&Utc))
+                .ok()
+                .or_else(|| {
+                    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                        .ok()
+                        .map(|d| d.and_time(chrono::NaiveTime::MIN).and_utc())
+                })
+        })
+        .unwrap_or(activity.created_at)
+}
+
+/// Whole months between birth and `on`; None for dates before birth
+fn age_in_months(birth_date: NaiveDate, on: NaiveDate) -> Option<u32> {
+    if on < birth_date {
+        return None;
+    }
+    let mut months =
+        (on.year() - birth_date.year()) * 12 + on.month() as i32 - birth_date.month() as i32;
+    if on.day() < birth_date.day() {
+        months -= 1;
+    }
+    u32::try_from(months).ok()
+}
+
+/// `[type, value, unit]` for each measurement block, sorted by type
+fn measurement_rows(activity: &Activity) -> Vec<[String; 3]> {
+    let mut rows: Vec<[String; 3]> = activity
+        .activity_data
+        .iter()
+        .flat_map(|data| data.values())
+        .filter_map(|block| match block {
+            BlockData::Measurement {
+                value,
+                unit,
+                measurement_type,
+            } => Some([measurement_type.clone(), value.clone(), unit.clone()]),
+            _ => None,
+        })
+        .collect();
+    rows.sort();
+    rows
+}
+
+fn push_row<'a>(csv: &mut String, fields: impl Iterator<Item = &'a String>) {
+    let line = fields.map(|f| csv_field(f)).collect::<Vec<_>>().join(",");
+    csv.push_str(&line);
+    csv.push('\n');
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// How every exporter names its files and where it writes them
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ExportSettings {
+    /// File name without extension, e.g. `{pet}_{type}_{date}`. `{pet}` is the pet's
+    /// name, `{pet_id}` its ID, `{type}` the kind of export such as
+    /// `vaccination-card`, `{date}` the day (2025-06-01) and `{time}` the time (143005).
+    pub filename_pattern: String,
+    /// Folder exports are written to; None for the app's exports folder
+    pub directory: Option<String>,
+}
+
+impl Default for ExportSettings {
+    fn default() -> Self {
+        ExportSettings {
+            filename_pattern: DEFAULT_FILENAME_PATTERN.to_string(),
+            directory: None,
+        }
+    }
+}
+
+/// What an export file holds, for naming it
+#[derive(Debug, Clone, Copy)]
+pub struct ExportFile<'a> {
+    /// Kind of export, e.g. `vaccination-card`
+    pub kind: &'a str,
+    /// ID and name of the pet the export is about; None when it covers several
+    pub pet: Option<(i64, &'a str)>,
+    pub extension: &'a str,
+    pub created: NaiveDateTime,
+}
+
+impl ExportSettings {
+    /// The same settings trimmed, or why they can't be used
+    pub fn validated(self) -> Result<Self, PetError> {
+        let pattern = self.filename_pattern.trim().to_string();
+        if pattern.is_empty() {
+            return Err(PetError::validation(
+                "filename_pattern",
+                "File name pattern cannot be empty",
+            ));
+        }
+        if pattern.chars().count() > MAX_FILENAME_PATTERN_LENGTH {
+            return Err(PetError::validation(
+                "filename_pattern",
+                &format!(
+                    "File name pattern cannot be longer than {MAX_FILENAME_PATTERN_LENGTH} characters"
+                ),
+            ));
+        }
+        if pattern.contains(['/', '\\']) {
+            return Err(PetError::validation(
+                "filename_pattern",
+                "File name pattern cannot contain folders",
+            ));
+        }
+        let mut rest = pattern.as_str();
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                return Err(PetError::validation(
+                    "filename_pattern",
+                    "File name pattern has a { without a closing }",
+                ));
+            };
+            let placeholder = &rest[start + 1..start + end];
+            if !FILENAME_PLACEHOLDERS.contains(&placeholder) {
+                return Err(PetError::validation(
+                    "filename_pattern",
+                    &format!(
+                        "Unknown placeholder {{{placeholder}}}; use {}",
+                        FILENAME_PLACEHOLDERS.map(|p| format!("{{{p}}}")).join(", ")
+                    ),
+                ));
+            }
+            rest = &rest[start + end + 1..];
+        }
+
+        let directory = self
+            .directory
+            .map(|directory| directory.trim().to_string())
+            .filter(|directory| !directory.is_empty());
+        if directory
+            .as_deref()
+            .is_some_and(|directory| !Path::new(directory).is_absolute())
+        {
+            return Err(PetError::validation(
+                "directory",
+                "Export folder must be an absolute path",
+            ));
+        }
+        Ok(ExportSettings {
+            filename_pattern: pattern,
+            directory,
+        })
+    }
+
+    /// Folder exports go to, `default_dir` unless one was chosen
+    pub fn directory_or(&self, default_dir: &Path) -> PathBuf {
+        self.directory
+            .as_deref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| default_dir.to_path_buf())
+    }
+
+    /// File name of an export, with the placeholders filled in and characters that
+    /// file systems reject replaced by `-`
+    pub fn file_name(&self, file: &ExportFile) -> String {
+        let (pet_id, pet) = match file.pet {
+            Some((id, name)) => (id.to_string(), name.trim().to_string()),
+            None => ("all".to_string(), "pets".to_string()),
+        };
+        let name = self
+            .filename_pattern
+            .replace("{pet_id}", &pet_id)
+            .replace("{pet}", &pet)
+            .replace("{type}", file.kind)
+            .replace("{date}", &file.created.format("%Y-%m-%d").to_string())
+            .replace("{time}", &file.created.format("%H%M%S").to_string());
+        let name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_control()
+                    || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|')
+                {
+                    '-'
+                } else {
+                    c
+                }
+            })
+            .collect();
+        let name = name.trim_matches(|c: char| c == '.' || c.is_whitespace());
+        let name = if name.is_empty() { file.kind } else { name };
+        format!("{name}.{}", file.extension)
+    }
+}
+
+/// `file_name` in `dir`, with `-2`, `-3`, ... before the extension if it is taken
+pub fn unique_path(dir: &Path, file_name: &str) -> PathBuf {
+    let path = dir.join(file_name);
+    if !path.exists() {
+        return path;
+    }
+    let (stem, extension) = file_name
+        .rsplit_once('.')
+        .map(|(stem, extension)| (stem, format!(".{extension}")))
+        .unwrap_or((file_name, String::new()));
+    (2..)
+        .map(|copy| dir.join(format!("{stem}-{copy}{extension}")))
+        .find(|path| !path.exists())
+        .expect("some numbered name is free")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{ActivityCategory, PetGender, PetSpecies};
+    use crate::jobs::RunningJobs;
+
+    fn pet(id: i64, name: &str) -> Pet {
+        Pet {
+            id,
+            name: name.to_string(),
+            birth_date: NaiveDate::from_ymd_opt(2023, 1, 15).unwrap(),
+            species: PetSpecies::Dog,
+            gender: PetGender::Female,
+            breed: Some("Retriever, Golden".to_string()),
+            color: None,
+            weight_kg: Some(20.0),
+            photo_path: Some("photo.jpg".to_string()),
+            notes: Some("Lives at 12 Main St".to_string()),
+            display_order: 0,
+            is_archived: false,
+            is_memorial: false,
+            passed_away_on: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_weight: None,
+            display_unit: Default::default(),
+        }
+    }
+
+    fn activity(id: i64, pet_id: i64, data: serde_json::Value) -> Activity {
+        Activity {
+            id,
+            pet_id,
+            category: ActivityCategory::Growth,
+            subcategory: "Weight".to_string(),
+            activity_data: serde_json::from_value(data).ok(),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_anonymized_csv_strips_identifying_fields() {
+        let pets = vec![pet(42, "Buddy")];
+        let activities = vec![activity(
+            1,
+            42,
+            serde_json::json!({
+                "time": { "date": "2025-03-20T09:30:00.000Z", "time": "", "timezone": "" },
+                "weight": { "value": "21.5", "unit": "kg", "measurementType": "weight" },
+                "notes": "Buddy at the park",
+                "location": { "name": "Central Park" },
+                "cost": { "amount": 50, "currency": "USD" },
+            }),
+        )];
+
+        let csv = anonymized_csv(&pets, &activities);
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines[0], ANONYMIZED_CSV_HEADER.join(","));
+        assert_eq!(
+            lines[1],
+            "S001,dog,\"Retriever, Golden\",female,26,2025-03-20T09:30:00+00:00,growth,Weight,weight,21.5,kg"
+        );
+        for leaked in ["Buddy", "Central Park", "50", "photo.jpg", "Main St", "42"] {
+            assert!(!lines[1].contains(leaked), "leaked {leaked}");
+        }
+    }
+
+    #[test]
+    fn test_activities_without_measurements_keep_timestamp() {
+        let pets = vec![pet(7, "Luna")];
+        let activities = vec![
+            activity(
+                1,
+                7,
+                serde_json::json!({ "time": { "date": "2025-01-14" } }),
+            ),
+            activity(2, 99, serde_json::json!({})),
+        ];
+
+        let csv = anonymized_csv(&pets, &activities);
+        let lines: Vec<_> = csv.lines().collect();
+        // Activities of pets outside the export are skipped
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].ends_with(",23,2025-01-14T00:00:00+00:00,growth,Weight,,,"));
+    }
+
+    #[test]
+    fn test_write_anonymized_csv_matches_in_memory_export() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("dataset.csv");
+        let pets = vec![pet(7, "Luna")];
+        let activities: Vec<_> = (0..EXPORT_CHUNK_SIZE as i64 + 5)
+            .map(|id| {
+                activity(
+                    id,
+                    7,
+                    serde_json::json!({ "time": { "date": "2025-01-14" } }),
+                )
+            })
+            .collect();
+
+        let job = RunningJobs::default().start("export");
+        let mut progress = Vec::new();
+        let outcome = write_anonymized_csv(&path, &pets, &activities, &job, |done, total| {
+            progress.push((done, total))
+        })
+        .unwrap();
+
+        assert_eq!(
+            outcome,
+            ExportOutcome::Completed {
+                rows: activities.len()
+            }
+        );
+        assert_eq!(
+            progress,
+            vec![
+                (EXPORT_CHUNK_SIZE, activities.len()),
+                (activities.len(), activities.len())
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            anonymized_csv(&pets, &activities)
+        );
+        assert!(!path.with_extension("part").exists());
+    }
+
+    #[test]
+    fn test_cancelled_export_leaves_no_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("dataset.csv");
+        let pets = vec![pet(7, "Luna")];
+        let activities = vec![activity(1, 7, serde_json::json!({}))];
+
+        let job = RunningJobs::default().start("export");
+        job.cancel();
+
+        let outcome = write_anonymized_csv(&path, &pets, &activities, &job, |_, _| {}).unwrap();
+        assert_eq!(outcome, ExportOutcome::Cancelled);
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_age_in_months() {
+        let birth = NaiveDate::from_ymd_opt(2024, 5, 31).unwrap();
+        assert_eq!(age_in_months(birth, birth), Some(0));
+        assert_eq!(
+            age_in_months(birth, NaiveDate::from_ymd_opt(2024, 6, 30).unwrap()),
+            Some(0)
+        );
+        assert_eq!(
+            age_in_months(birth, NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()),
+            Some(12)
+        );
+        assert_eq!(
+            age_in_months(birth, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_export_file_names() {
+        let created = NaiveDate::from_ymd_opt(2025, 6, 1)
+            .unwrap()
+            .and_hms_opt(14, 30, 5)
+            .unwrap();
+        let card = ExportFile {
+            kind: "vaccination-card",
+            pet: Some((3, "Mr. Whiskers/Jr")),
+            extension: "pdf",
+            created,
+        };
+        assert_eq!(
+            ExportSettings::default().file_name(&card),
+            "Mr. Whiskers-Jr_vaccination-card_2025-06-01.pdf"
+        );
+
+        let settings = ExportSettings {
+            filename_pattern: " {type}-{pet_id}-{date}T{time} ".to_string(),
+            directory: Some("  ".to_string()),
+        }
+        .validated()
+        .unwrap();
+        assert_eq!(settings.directory, None);
+        let dataset = ExportFile {
+            kind: "anonymized-dataset",
+            pet: None,
+            ..card
+        };
+        assert_eq!(
+            settings.file_name(&dataset),
+            "anonymized-dataset-all-2025-06-01T143005.pdf"
+        );
+
+        for pattern in ["", "{pet}/{type}", "{pet}_{year}", "{pet"] {
+            let settings = ExportSettings {
+                filename_pattern: pattern.to_string(),
+                directory: None,
+            };
+            assert!(settings.validated().is_err(), "{pattern}");
+        }
+        let relative = ExportSettings {
+            directory: Some("exports".to_string()),
+            ..Default::default()
+        };
+        assert!(relative.validated().is_err());
+    }
+
+    #[test]
+    fn test_unique_export_path() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let first = unique_path(dir.path(), "Mochi_care-handoff_2025-06-01.md");
+        assert_eq!(first, dir.path().join("Mochi_care-handoff_2025-06-01.md"));
+        std::fs::write(&first, "").unwrap();
+        std::fs::write(dir.path().join("Mochi_care-handoff_2025-06-01-2.md"), "").unwrap();
+        assert_eq!(
+            unique_path(dir.path(), "Mochi_care-handoff_2025-06-01.md"),
+            dir.path().join("Mochi_care-handoff_2025-06-01-3.md")
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use crate::errors::PetError;
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+
+/// Number of leading bytes needed to recognize every supported type
+pub const SNIFF_LENGTH: usize = 512;
+
+/// File type recognized from its content
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileKind {
+    Jpeg,
+    Png,
+    Webp,
+    Gif,
+    Bmp,
+    Tiff,
+    Heic,
+    Pdf,
+    Text,
+}
+
+impl FileKind {
+    /// MIME type recorded for the file
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            FileKind::Jpeg => "image/jpeg",
+            FileKind::Png => "image/png",
+            FileKind::Webp => "image/webp",
+            FileKind::Gif => "image/gif",
+            FileKind::Bmp => "image/bmp",
+            FileKind::Tiff => "image/tiff",
+            FileKind::Heic => "image/heic",
+            FileKind::Pdf => "application/pdf",
+            FileKind::Text => "text/plain",
+        }
+    }
+
+    /// Extensions that may carry this content; the first is canonical
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            FileKind::Jpeg => &["jpg", "jpeg"],
+            FileKind::Png => &["png"],
+            FileKind::Webp => &["webp"],
+            FileKind::Gif => &["gif"],
+            FileKind::Bmp => &["bmp"],
+            FileKind::Tiff => &["tiff", "tif"],
+            FileKind::Heic => &["heic", "heif"],
+            FileKind::Pdf => &["pdf"],
+            FileKind::Text => &["txt"],
+        }
+    }
+
+    /// Format the image crate decodes and encodes this kind with
+    pub fn image_format(self) -> Option<ImageFormat> {
+        match self {
+            FileKind::Jpeg => Some(ImageFormat::Jpeg),
+            FileKind::Png => Some(ImageFormat::Png),
+            FileKind::Webp => Some(ImageFormat::WebP),
+            FileKind::Gif => Some(ImageFormat::Gif),
+            FileKind::Bmp => Some(ImageFormat::Bmp),
+            FileKind::Tiff => Some(ImageFormat::Tiff),
+            FileKind::Heic | FileKind::Pdf | FileKind::Text => None,
+        }
+    }
+}
+
+/// Signatures of content that must never be stored, whatever its extension
+const EXECUTABLE_SIGNATURES: [(&[u8], &str); 6] = [
+    (b"MZ", "Windows executable"),
+    (b"\x7fELF", "ELF executable"),
+    (b"\xcf\xfa\xed\xfe", "Mach-O executable"),
+    (b"\xca\xfe\xba\xbe", "Mach-O universal binary"),
+    (b"#!", "script"),
+    (b"PK\x03\x04", "archive"),
+];
+
+/// Recognize a file from its leading bytes
+pub fn sniff(bytes: &[u8]) -> Option<FileKind> {
+    let kind = if bytes.starts_with(b"\xff\xd8\xff") {
+        FileKind::Jpeg
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        FileKind::Png
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        FileKind::Webp
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        FileKind::Gif
+    } else if bytes.starts_with(b"BM")
+        && bytes.len() >= 18
+        && matches!(bytes[14..18], [12 | 40 | 52 | 56 | 108 | 124, 0, 0, 0])
+    {
+        // The DIB header size distinguishes bitmaps from text starting with "BM"
+        FileKind::Bmp
+    } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        FileKind::Tiff
+    } else if bytes.len() >= 12
+        && &bytes[4..8] == b"ftyp"
+        && matches!(
+            &bytes[8..12],
+            b"heic" | b"heix" | b"mif1" | b"msf1" | b"heim" | b"heis"
+        )
+    {
+        FileKind::Heic
+    } else if bytes.starts_with(b"%PDF-") {
+        FileKind::Pdf
+    } else if is_plain_text(bytes) {
+        FileKind::Text
+    } else {
+        return None;
+    };
+    Some(kind)
+}
+
+/// UTF-8 without control characters other than whitespace.
+/// Only the sniffed prefix is checked, so a multi-byte character cut at the end is accepted.
+fn is_plain_text(bytes: &[u8]) -> bool {
+    let sample = &bytes[..bytes.len().min(SNIFF_LENGTH)];
+    let text = match std::str::from_utf8(sample) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => {
+            std::str::from_utf8(&sample[..e.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return false,
+    };
+    !text.is_empty()
+        && !text.starts_with("#!")
+        && text
+            .chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t' | '\u{feff}'))
+}
+
+/// Check that content matches the extension it was uploaded with and return its kind.
+/// `allowed` limits the accepted kinds; executables are always rejected.
+pub fn verify_content(
+    bytes: &[u8],
+    extension: Option<&str>,
+    allowed: &[FileKind],
+) -> Result<FileKind, PetError> {
+    if let Some((_, description)) = EXECUTABLE_SIGNATURES
+        .iter()
+        .find(|(signature, _)| bytes.starts_with(signature))
+    {
+        log::warn!("Rejected upload: content is a {description}");
+        return Err(PetError::validation(
+            "file".to_string(),
+            format!("File content is a {description}, not an accepted file type"),
+        ));
+    }
+
+    let kind = sniff(bytes)
+        .filter(|kind| allowed.contains(kind))
+        .ok_or_else(|| PetError::validation("file", "File content is not a supported file type"))?;
+
+    if let Some(extension) = extension.map(str::to_lowercase) {
+        if !kind.extensions().contains(&extension.as_str()) {
+            log::warn!(
+                "Rejected upload: '.{extension}' file contains {}",
+                kind.mime_type()
+            );
+            return Err(PetError::validation(
+                "file".to_string(),
+                format!(
+                    "File extension '.{extension}' does not match its content ({})",
+                    kind.mime_type()
+                ),
+            ));
+        }
+    }
+    Ok(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IMAGES: [FileKind; 3] = [FileKind::Jpeg, FileKind::Png, FileKind::Webp];
+
+    fn png_bytes() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image::DynamicImage::new_rgb8(4, 4)
+            .write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+            .unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_sniff_known_signatures() {
+        assert_eq!(sniff(&png_bytes()), Some(FileKind::Png));
+        assert_eq!(sniff(b"\xff\xd8\xff\xe0\0\x10JFIF"), Some(FileKind::Jpeg));
+        assert_eq!(sniff(b"RIFF\x24\0\0\0WEBPVP8 "), Some(FileKind::Webp));
+        assert_eq!(sniff(b"\0\0\0\x18ftypheic\0\0\0\0"), Some(FileKind::Heic));
+        assert_eq!(sniff(b"%PDF-1.7\n"), Some(FileKind::Pdf));
+        assert_eq!(sniff("Vet notes: ok\n".as_bytes()), Some(FileKind::Text));
+        assert_eq!(sniff(b"\0\x01\x02garbage"), None);
+        assert_eq!(sniff(b""), None);
+    }
+
+    #[test]
+    fn test_verify_content_rejects_mismatches() {
+        assert_eq!(
+            verify_content(&png_bytes(), Some("PNG"), &IMAGES).unwrap(),
+            FileKind::Png
+        );
+        assert_eq!(
+            verify_content(&png_bytes(), None, &IMAGES).unwrap(),
+            FileKind::Png
+        );
+
+        // A PNG renamed to .jpg
+        assert!(verify_content(&png_bytes(), Some("jpg"), &IMAGES).is_err());
+        // An executable renamed to .jpg
+        let exe = b"MZ\x90\0\x03\0\0\0\x04\0\0\0\xff\xff\0\0";
+        assert!(verify_content(exe, Some("jpg"), &IMAGES).is_err());
+        let script = b"#!/bin/sh\nrm -rf ~\n";
+        assert!(verify_content(script, Some("txt"), &[FileKind::Text]).is_err());
+        // Recognized, but not allowed here
+        assert!(verify_content(b"%PDF-1.4", Some("pdf"), &IMAGES).is_err());
+    }
+}
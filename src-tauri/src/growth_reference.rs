@@ -0,0 +1,230 @@
+use crate::database::PetSpecies;
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+
+/// Typical range of an adult measurement
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct ReferenceRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Typical adult size of a breed and the age it is reached at
+#[derive(Debug, Clone, Serialize)]
+pub struct BreedReference {
+    pub breed: &'static str,
+    /// Other names owners enter for the breed, lowercase
+    #[serde(skip)]
+    pub aliases: &'static [&'static str],
+    pub species: PetSpecies,
+    pub adult_weight_kg: ReferenceRange,
+    /// Height at the shoulder
+    pub adult_height_cm: ReferenceRange,
+    /// Age in weeks at which most pets of the breed reach adult size
+    pub adult_at_weeks: u32,
+}
+
+impl BreedReference {
+    /// Date a pet born on `birth_date` is expected to reach adult size
+    pub fn adult_from(&self, birth_date: NaiveDate) -> NaiveDate {
+        birth_date
+            .checked_add_days(Days::new(self.adult_at_weeks as u64 * 7))
+            .unwrap_or(NaiveDate::MAX)
+    }
+}
+
+const fn range(min: f32, max: f32) -> ReferenceRange {
+    ReferenceRange { min, max }
+}
+
+/// Breed used for cats whose breed is unknown or not in the table. Dogs vary too
+/// much in size for a species-wide reference.
+const CAT_DEFAULT_BREED: &str = "Domestic Shorthair";
+
+static BREEDS: [BreedReference; 16] = [
+    BreedReference {
+        breed: "Domestic Shorthair",
+        aliases: &["dsh", "domestic cat", "moggy", "mixed"],
+        species: PetSpecies::Cat,
+        adult_weight_kg: range(3.6, 6.3),
+        adult_height_cm: range(23.0, 25.0),
+        adult_at_weeks: 52,
+    },
+    BreedReference {
+        breed: "Maine Coon",
+        aliases: &[],
+        species: PetSpecies::Cat,
+        adult_weight_kg: range(5.5, 11.0),
+        adult_height_cm: range(25.0, 41.0),
+        adult_at_weeks: 156,
+    },
+    BreedReference {
+        breed: "British Shorthair",
+        aliases: &["bsh"],
+        species: PetSpecies::Cat,
+        adult_weight_kg: range(4.0, 8.0),
+        adult_height_cm: range(30.0, 36.0),
+        adult_at_weeks: 156,
+    },
+    BreedReference {
+        breed: "Siamese",
+        aliases: &[],
+        species: PetSpecies::Cat,
+        adult_weight_kg: range(3.0, 5.5),
+        adult_height_cm: range(20.0, 25.0),
+        adult_at_weeks: 52,
+    },
+    BreedReference {
+        breed: "Ragdoll",
+        aliases: &[],
+        species: PetSpecies::Cat,
+        adult_weight_kg: range(4.5, 9.0),
+        adult_height_cm: range(23.0, 28.0),
+        adult_at_weeks: 156,
+    },
+    BreedReference {
+        breed: "Persian",
+        aliases: &[],
+        species: PetSpecies::Cat,
+        adult_weight_kg: range(3.0, 5.5),
+        adult_height_cm: range(25.0, 38.0),
+        adult_at_weeks: 78,
+    },
+    BreedReference {
+        breed: "Labrador Retriever",
+        aliases: &["labrador", "lab"],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(25.0, 36.0),
+        adult_height_cm: range(55.0, 62.0),
+        adult_at_weeks: 78,
+    },
+    BreedReference {
+        breed: "Golden Retriever",
+        aliases: &["golden"],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(25.0, 34.0),
+        adult_height_cm: range(51.0, 61.0),
+        adult_at_weeks: 78,
+    },
+    BreedReference {
+        breed: "German Shepherd",
+        aliases: &["german shepherd dog", "gsd", "alsatian"],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(22.0, 40.0),
+        adult_height_cm: range(55.0, 65.0),
+        adult_at_weeks: 78,
+    },
+    BreedReference {
+        breed: "Border Collie",
+        aliases: &[],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(12.0, 20.0),
+        adult_height_cm: range(46.0, 56.0),
+        adult_at_weeks: 65,
+    },
+    BreedReference {
+        breed: "Standard Poodle",
+        aliases: &["poodle"],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(18.0, 32.0),
+        adult_height_cm: range(45.0, 60.0),
+        adult_at_weeks: 65,
+    },
+    BreedReference {
+        breed: "Beagle",
+        aliases: &[],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(9.0, 11.0),
+        adult_height_cm: range(33.0, 41.0),
+        adult_at_weeks: 52,
+    },
+    BreedReference {
+        breed: "Pembroke Welsh Corgi",
+        aliases: &["corgi", "welsh corgi"],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(10.0, 14.0),
+        adult_height_cm: range(25.0, 30.0),
+        adult_at_weeks: 52,
+    },
+    BreedReference {
+        breed: "Shiba Inu",
+        aliases: &["shiba"],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(7.0, 11.0),
+        adult_height_cm: range(35.0, 41.0),
+        adult_at_weeks: 52,
+    },
+    BreedReference {
+        breed: "French Bulldog",
+        aliases: &["frenchie"],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(8.0, 14.0),
+        adult_height_cm: range(28.0, 33.0),
+        adult_at_weeks: 52,
+    },
+    BreedReference {
+        breed: "Chihuahua",
+        aliases: &[],
+        species: PetSpecies::Dog,
+        adult_weight_kg: range(1.5, 3.0),
+        adult_height_cm: range(15.0, 23.0),
+        adult_at_weeks: 40,
+    },
+];
+
+/// All built-in breed references
+pub fn all_breed_references() -> &'static [BreedReference] {
+    &BREEDS
+}
+
+/// Reference for a pet's breed, matched by name or alias ignoring case. Cats
+/// without a known breed get the domestic shorthair reference; the flag tells
+/// whether the breed itself matched.
+pub fn find_breed_reference(
+    species: &PetSpecies,
+    breed: Option<&str>,
+) -> Option<(&'static BreedReference, bool)> {
+    let breed = breed.map(|breed| breed.trim().to_lowercase());
+    let matched = breed.as_deref().and_then(|breed| {
+        BREEDS.iter().find(|reference| {
+            reference.species == *species
+                && (reference.breed.to_lowercase() == breed || reference.aliases.contains(&breed))
+        })
+    });
+    match (matched, species) {
+        (Some(reference), _) => Some((reference, true)),
+        (None, PetSpecies::Cat) => BREEDS
+            .iter()
+            .find(|reference| reference.breed == CAT_DEFAULT_BREED)
+            .map(|reference| (reference, false)),
+        (None, PetSpecies::Dog) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breed_lookup() {
+        assert!(all_breed_references().iter().all(|reference| {
+            reference.adult_weight_kg.min < reference.adult_weight_kg.max
+                && reference.adult_height_cm.min < reference.adult_height_cm.max
+        }));
+
+        let (lab, matched) = find_breed_reference(&PetSpecies::Dog, Some(" Lab ")).unwrap();
+        assert_eq!((lab.breed, matched), ("Labrador Retriever", true));
+        let birth = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        assert_eq!(
+            lab.adult_from(birth),
+            NaiveDate::from_ymd_opt(2026, 7, 1).unwrap()
+        );
+
+        // A cat breed name doesn't match a dog, and unknown dogs get no reference
+        assert!(find_breed_reference(&PetSpecies::Dog, Some("Maine Coon")).is_none());
+        assert!(find_breed_reference(&PetSpecies::Dog, None).is_none());
+
+        let (cat, matched) = find_breed_reference(&PetSpecies::Cat, Some("Sphynx")).unwrap();
+        assert_eq!((cat.breed, matched), (CAT_DEFAULT_BREED, false));
+    }
+}
@@ -0,0 +1,95 @@
+use super::{parse_amount, parse_date_time, ImportFormat, ImportRow, ImportedActivity, Importer};
+use crate::database::activity_blocks::{portion_type_for, ActivityBlocksBuilder};
+use crate::database::ActivityCategory;
+use chrono::FixedOffset;
+
+const DATE_FORMATS: [&str; 2] = ["%m/%d/%Y", "%Y-%m-%d"];
+
+/// Dog Log CSV export: one row per logged event, with US dates and 12-hour times.
+/// Potty breaks have no matching activity and are skipped.
+pub struct DogLogImporter;
+
+impl Importer for DogLogImporter {
+    fn format(&self) -> ImportFormat {
+        ImportFormat::DogLog
+    }
+
+    fn required_columns(&self) -> &'static [&'static str] {
+        &["Dog", "Activity", "Date"]
+    }
+
+    fn known_columns(&self) -> &'static [&'static str] {
+        &[
+            "Dog",
+            "Activity",
+            "Date",
+            "Time",
+            "Duration (min)",
+            "Amount",
+            "Unit",
+            "Notes",
+        ]
+    }
+
+    fn map_row(&self, row: &ImportRow, offset: FixedOffset) -> Result<ImportedActivity, String> {
+        let activity = row.require("Activity")?;
+        let (category, subcategory) = match activity.to_lowercase().as_str() {
+            "walk" => (ActivityCategory::Lifestyle, "Walk"),
+            "play" => (ActivityCategory::Lifestyle, "Play"),
+            "training" => (ActivityCategory::Lifestyle, "Training"),
+            "sleep" | "nap" => (ActivityCategory::Lifestyle, "Sleep"),
+            "groom" | "grooming" => (ActivityCategory::Lifestyle, "Grooming"),
+            "food" | "meal" => (ActivityCategory::Diet, "Feeding"),
+            "treat" => (ActivityCategory::Diet, "Treat"),
+            "water" => (ActivityCategory::Diet, "Water"),
+            "medicine" | "medication" => (ActivityCategory::Health, "Medication"),
+            "weight" => (ActivityCategory::Growth, "Weight"),
+            _ => return Err(format!("Unsupported Dog Log activity '{activity}'")),
+        };
+
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(parse_date_time(
+            row.require("Date")?,
+            row.get("Time"),
+            &DATE_FORMATS,
+            offset,
+        )?);
+
+        let amount = row.get("Amount").and_then(parse_amount);
+        match category {
+            ActivityCategory::Lifestyle => {
+                if let Some(minutes) = row.get("Duration (min)").and_then(parse_amount) {
+                    blocks
+                        .duration_minutes(minutes)
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            ActivityCategory::Diet => {
+                if let Some(amount) = amount {
+                    let unit = row.get("Unit").unwrap_or("serving");
+                    blocks
+                        .portion(amount, unit, portion_type_for(subcategory), None)
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+            ActivityCategory::Growth => {
+                let weight = amount.ok_or("Missing weight Amount")?;
+                blocks
+                    .weight(weight, row.get("Unit").unwrap_or("kg"))
+                    .map_err(|e| e.to_string())?;
+            }
+            ActivityCategory::Health | ActivityCategory::Expense => {}
+        }
+        if let Some(notes) = row.get("Notes") {
+            blocks.notes(notes);
+        }
+
+        Ok(ImportedActivity {
+            line: row.line,
+            source_pet: row.get("Dog").map(str::to_string),
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: blocks.into_json(),
+        })
+    }
+}
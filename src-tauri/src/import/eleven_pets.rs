@@ -0,0 +1,129 @@
+use super::{parse_amount, parse_date_time, ImportFormat, ImportRow, ImportedActivity, Importer};
+use crate::database::activity_blocks::{portion_type_for, ActivityBlocksBuilder};
+use crate::database::ActivityCategory;
+use chrono::{FixedOffset, NaiveDateTime};
+
+const DATE_FORMATS: [&str; 2] = ["%Y-%m-%d", "%d.%m.%Y"];
+
+/// 11pets CSV export: care events by category, with an optional value, cost and note.
+/// Dates are ISO with an optional `HH:MM` time in the same cell.
+pub struct ElevenPetsImporter;
+
+impl Importer for ElevenPetsImporter {
+    fn format(&self) -> ImportFormat {
+        ImportFormat::ElevenPets
+    }
+
+    fn required_columns(&self) -> &'static [&'static str] {
+        &["Pet", "Category", "Event", "Date"]
+    }
+
+    fn known_columns(&self) -> &'static [&'static str] {
+        &[
+            "Pet", "Category", "Event", "Date", "Value", "Unit", "Cost", "Currency", "Note",
+        ]
+    }
+
+    fn map_row(&self, row: &ImportRow, offset: FixedOffset) -> Result<ImportedActivity, String> {
+        let source_category = row.require("Category")?;
+        let (category, subcategory) = match source_category.to_lowercase().as_str() {
+            "weight" => (ActivityCategory::Growth, "Weight"),
+            "vet visit" | "vaccination" | "examination" => (ActivityCategory::Health, "Checkup"),
+            "medication" | "deworming" | "flea treatment" | "tick treatment" => {
+                (ActivityCategory::Health, "Medication")
+            }
+            "symptom" | "illness" => (ActivityCategory::Health, "Symptom"),
+            "food" | "feeding" => (ActivityCategory::Diet, "Feeding"),
+            "grooming" | "bath" => (ActivityCategory::Lifestyle, "Grooming"),
+            "walk" => (ActivityCategory::Lifestyle, "Walk"),
+            "expense" | "purchase" => (ActivityCategory::Expense, "Purchase"),
+            "insurance" => (ActivityCategory::Expense, "Insurance"),
+            _ => return Err(format!("Unsupported 11pets category '{source_category}'")),
+        };
+
+        let date = row.require("Date")?;
+        let time = match NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M") {
+            Ok(local) => super::local_to_utc(local, offset),
+            Err(_) => parse_date_time(date, None, &DATE_FORMATS, offset)?,
+        };
+
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(time).title(row.require("Event")?);
+
+        let value = row.get("Value").and_then(parse_amount);
+        match (subcategory, value) {
+            ("Weight", Some(weight)) => {
+                blocks
+                    .weight(weight, row.get("Unit").unwrap_or("kg"))
+                    .map_err(|e| e.to_string())?;
+            }
+            ("Weight", None) => return Err("Missing weight Value".to_string()),
+            ("Feeding" | "Medication", Some(amount)) => {
+                let unit = row.get("Unit").unwrap_or("serving");
+                blocks
+                    .portion(amount, unit, portion_type_for(subcategory), None)
+                    .map_err(|e| e.to_string())?;
+            }
+            _ => {}
+        }
+
+        if let Some(cost) = row.get("Cost").and_then(parse_amount) {
+            blocks
+                .cost(cost, row.get("Currency").unwrap_or("USD"))
+                .map_err(|e| e.to_string())?;
+        } else if category == ActivityCategory::Expense {
+            return Err("Missing Cost for expense".to_string());
+        }
+        if let Some(note) = row.get("Note") {
+            blocks.notes(note);
+        }
+
+        Ok(ImportedActivity {
+            line: row.line,
+            source_pet: row.get("Pet").map(str::to_string),
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: blocks.into_json(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{map_import, ImportFormat};
+    use crate::database::ActivityCategory;
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_map_eleven_pets_export() {
+        let csv = "Pet,Category,Event,Date,Value,Unit,Cost,Currency,Note\n\
+                   Mochi,Weight,Weighing,2025-02-03 09:15,\"4,6\",kg,,,\n\
+                   Mochi,Vaccination,Rabies booster,2025-02-10,,,45,eur,Next in 3 years\n\
+                   Mochi,Expense,Scratching post,2025-02-11,,,,,\n\
+                   Mochi,Weight,Weighing,2025-02-12,9,stone,,,\n";
+        let mapping = map_import(csv.as_bytes(), None, FixedOffset::east_opt(0).unwrap()).unwrap();
+
+        assert_eq!(mapping.report.format, ImportFormat::ElevenPets);
+        assert_eq!(mapping.report.mapped_rows, 2);
+        assert_eq!(mapping.report.skipped[0].reason, "Missing Cost for expense");
+        assert!(mapping.report.skipped[1]
+            .reason
+            .contains("Unsupported unit 'stone'"));
+
+        let weight = &mapping.activities[0];
+        assert_eq!(weight.category, ActivityCategory::Growth);
+        assert_eq!(weight.activity_data["weight"]["value"], "4.6");
+        assert_eq!(
+            weight.activity_data["time"]["date"],
+            "2025-02-03T09:15:00.000Z"
+        );
+
+        let vaccination = &mapping.activities[1];
+        assert_eq!(vaccination.subcategory, "Checkup");
+        assert_eq!(vaccination.activity_data["title"], "Rabies booster");
+        assert_eq!(
+            vaccination.activity_data["cost"],
+            serde_json::json!({ "amount": 45.0, "currency": "EUR" })
+        );
+    }
+}
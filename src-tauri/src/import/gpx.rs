@@ -0,0 +1,549 @@
+//! GPX tracks recorded by watches and fitness trackers, imported as walks.
+//!
+//! Only what a walk needs is read: the track name and the track points with their
+//! times. The file is scanned tag by tag instead of with a full XML parser, so
+//! namespace prefixes are ignored and extensions such as heart rate are skipped.
+
+use crate::database::activity_blocks::ActivityBlocksBuilder;
+use crate::database::{ActivityCategory, ActivityCreateRequest};
+use crate::errors::ActivityError;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Largest GPX file read, in bytes
+pub const MAX_GPX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Most points kept in a walk's simplified route
+const MAX_ROUTE_POINTS: usize = 500;
+
+/// Smallest detour the simplified route keeps, in metres; raised for long tracks
+/// until the route fits in `MAX_ROUTE_POINTS`
+const ROUTE_TOLERANCE_M: f64 = 5.0;
+
+/// Mean radius of the earth in metres
+const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// A recorded position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrackPoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub time: Option<DateTime<Utc>>,
+}
+
+/// Track points of a GPX file, split where the recording was paused
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpxTrack {
+    pub name: Option<String>,
+    pub segments: Vec<Vec<TrackPoint>>,
+}
+
+/// Smallest box holding a route
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteBounds {
+    pub min_lat: f64,
+    pub min_lng: f64,
+    pub max_lat: f64,
+    pub max_lng: f64,
+}
+
+/// A walk measured from a GPX track
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GpxWalk {
+    /// Track name, e.g. "Morning Walk"
+    pub name: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// From the first to the last timestamp, pauses included
+    pub duration_minutes: Option<f64>,
+    /// Sum of the segments; pauses between segments aren't counted
+    pub distance_km: f64,
+    /// First recorded position as [lat, lng]
+    pub start: [f64; 2],
+    pub bounds: RouteBounds,
+    /// Points recorded in the file
+    pub point_count: usize,
+    /// Route simplified to at most `MAX_ROUTE_POINTS` points, as [lat, lng]
+    pub route: Vec<[f64; 2]>,
+}
+
+impl GpxWalk {
+    /// Measure a parsed track. Needs at least one timestamp to date the walk.
+    pub fn from_track(track: &GpxTrack) -> Result<Self, ActivityError> {
+        let points: Vec<&TrackPoint> = track.segments.iter().flatten().collect();
+        let Some(first) = points.first() else {
+            return Err(ActivityError::validation(
+                "path",
+                "The GPX file has no track points",
+            ));
+        };
+        let mut times = points.iter().filter_map(|point| point.time);
+        let Some(started_at) = times.next() else {
+            return Err(ActivityError::validation(
+                "path",
+                "The GPX track has no timestamps, so the walk's date is unknown",
+            ));
+        };
+        let ended_at = times.next_back().unwrap_or(started_at);
+        let duration_minutes =
+            (ended_at > started_at).then(|| (ended_at - started_at).num_seconds() as f64 / 60.0);
+
+        let distance_m: f64 = track
+            .segments
+            .iter()
+            .flat_map(|segment| segment.windows(2))
+            .map(|pair| distance_m(pair[0], pair[1]))
+            .sum();
+
+        let mut bounds = RouteBounds {
+            min_lat: first.lat,
+            min_lng: first.lon,
+            max_lat: first.lat,
+            max_lng: first.lon,
+        };
+        for point in &points {
+            bounds.min_lat = bounds.min_lat.min(point.lat);
+            bounds.min_lng = bounds.min_lng.min(point.lon);
+            bounds.max_lat = bounds.max_lat.max(point.lat);
+            bounds.max_lng = bounds.max_lng.max(point.lon);
+        }
+
+        let route = simplify_route(&points)
+            .into_iter()
+            .map(|point| [round_coordinate(point.lat), round_coordinate(point.lon)])
+            .collect();
+
+        Ok(GpxWalk {
+            name: track.name.clone(),
+            started_at,
+            duration_minutes,
+            distance_km: distance_m / 1000.0,
+            start: [first.lat, first.lon],
+            bounds,
+            point_count: points.len(),
+            route,
+        })
+    }
+
+    /// Lifestyle walk with distance and duration blocks, the start point as its
+    /// location (so it shows on the walk areas map) and a route block holding the
+    /// bounds and, with `include_route`, the simplified route
+    pub fn into_request(
+        self,
+        pet_id: i64,
+        include_route: bool,
+    ) -> Result<ActivityCreateRequest, ActivityError> {
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(self.started_at);
+        if let Some(name) = &self.name {
+            blocks.title(name);
+        }
+        blocks.distance((self.distance_km * 100.0).round() / 100.0, "km", "walk")?;
+        if let Some(minutes) = self.duration_minutes {
+            blocks.duration((minutes * 10.0).round() / 10.0, "min", "walk")?;
+        }
+
+        let mut data = blocks.into_json();
+        data["location"] = serde_json::json!({
+            "name": "",
+            "coordinates": { "lat": self.start[0], "lng": self.start[1] },
+        });
+        let mut route = serde_json::json!({
+            "source": "gpx",
+            "bounds": self.bounds,
+            "pointCount": self.point_count,
+        });
+        if include_route {
+            route["points"] = serde_json::json!(self.route);
+        }
+        data["route"] = route;
+
+        Ok(ActivityCreateRequest {
+            pet_id,
+            category: ActivityCategory::Lifestyle,
+            subcategory: "Walk".to_string(),
+            activity_data: Some(data),
+            needs_review: false,
+        })
+    }
+}
+
+/// Read and measure the walk in a GPX file
+pub fn read_gpx_walk(path: &Path) -> Result<GpxWalk, ActivityError> {
+    let size = std::fs::metadata(path)
+        .map_err(|e| ActivityError::invalid_data(format!("Failed to open GPX file: {e}")))?
+        .len();
+    if size > MAX_GPX_FILE_BYTES {
+        return Err(ActivityError::validation(
+            "path",
+            &format!(
+                "The GPX file is larger than {} MB",
+                MAX_GPX_FILE_BYTES / 1024 / 1024
+            ),
+        ));
+    }
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| ActivityError::invalid_data(format!("Failed to read GPX file: {e}")))?;
+    GpxWalk::from_track(&parse_gpx(&text)?)
+}
+
+/// Track name and points of a GPX document. Points with a missing or out of
+/// range position are left out.
+pub fn parse_gpx(text: &str) -> Result<GpxTrack, ActivityError> {
+    let malformed = || ActivityError::invalid_data("The GPX file is not valid XML");
+
+    let mut track = GpxTrack::default();
+    let mut open: Vec<&str> = Vec::new();
+    let mut point: Option<TrackPoint> = None;
+    let mut content = String::new();
+    let mut rest = text.trim_start_matches('\u{feff}');
+
+    while let Some(start) = rest.find('<') {
+        content.push_str(&decode_entities(&rest[..start]));
+        rest = &rest[start..];
+
+        if let Some(after) = rest.strip_prefix("<![CDATA[") {
+            let end = after.find("]]>").ok_or_else(malformed)?;
+            content.push_str(&after[..end]);
+            rest = &after[end + 3..];
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("<!--") {
+            let end = after.find("-->").ok_or_else(malformed)?;
+            rest = &after[end + 3..];
+            continue;
+        }
+        let end = tag_end(rest).ok_or_else(malformed)?;
+        let tag = &rest[1..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+
+        if let Some(name) = tag.strip_prefix('/') {
+            let name = local_name(name.trim());
+            let parent = open.len().checked_sub(2).map(|i| open[i]);
+            match (name, parent) {
+                ("trkpt", _) => push_point(&mut track, point.take()),
+                ("time", Some("trkpt")) => {
+                    if let Some(point) = point.as_mut() {
+                        point.time = DateTime::parse_from_rfc3339(content.trim())
+                            .ok()
+                            .map(|time| time.with_timezone(&Utc));
+                    }
+                }
+                ("name", Some("trk")) if track.name.is_none() => {
+                    track.name = Some(content.trim().to_string()).filter(|name| !name.is_empty());
+                }
+                _ => {}
+            }
+            if open.last() == Some(&name) {
+                open.pop();
+            }
+            content.clear();
+            continue;
+        }
+
+        let self_closing = tag.ends_with('/');
+        let tag = tag.trim_end_matches('/');
+        let (name, attributes) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+        let name = local_name(name);
+        content.clear();
+        match name {
+            "trkseg" => track.segments.push(Vec::new()),
+            "trkpt" => {
+                let coordinate = |key: &str| attribute(attributes, key)?.trim().parse::<f64>().ok();
+                point = match (coordinate("lat"), coordinate("lon")) {
+                    (Some(lat), Some(lon))
+                        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) =>
+                    {
+                        Some(TrackPoint {
+                            lat,
+                            lon,
+                            time: None,
+                        })
+                    }
+                    _ => None,
+                };
+            }
+            _ => {}
+        }
+        if !self_closing {
+            open.push(name);
+        } else if name == "trkpt" {
+            push_point(&mut track, point.take());
+        }
+    }
+
+    track.segments.retain(|segment| !segment.is_empty());
+    Ok(track)
+}
+
+/// Add a finished point to the last segment; points outside a segment start one
+fn push_point(track: &mut GpxTrack, point: Option<TrackPoint>) {
+    let Some(point) = point else {
+        return;
+    };
+    match track.segments.last_mut() {
+        Some(segment) => segment.push(point),
+        None => track.segments.push(vec![point]),
+    }
+}
+
+/// Index of the `>` closing the tag at the start of `text`, skipping quoted values
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Element name without its namespace prefix, e.g. `trkpt` for `gpx:trkpt`
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Value of the attribute `key` in a tag's attribute text
+fn attribute(attributes: &str, key: &str) -> Option<String> {
+    let mut rest = attributes.trim_start();
+    while let Some((name, after)) = rest.split_once('=') {
+        let after = after.trim_start();
+        let quote = after.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let (value, next) = after[1..].split_once(quote)?;
+        if local_name(name.trim()) == key {
+            return Some(decode_entities(value));
+        }
+        rest = next.trim_start();
+    }
+    None
+}
+
+/// Replace XML character and entity references
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';').filter(|end| *end <= 10) else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..end];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(character) => {
+                decoded.push(character);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Great-circle distance between two points in metres (haversine)
+fn distance_m(from: TrackPoint, to: TrackPoint) -> f64 {
+    let (lat1, lat2) = (from.lat.to_radians(), to.lat.to_radians());
+    let half_lat = (lat2 - lat1) / 2.0;
+    let half_lon = (to.lon - from.lon).to_radians() / 2.0;
+    let a = half_lat.sin().powi(2) + lat1.cos() * lat2.cos() * half_lon.sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+}
+
+/// Route through `points` without the detours smaller than a tolerance
+/// (Douglas-Peucker), keeping at most `MAX_ROUTE_POINTS` points
+fn simplify_route<'a>(points: &[&'a TrackPoint]) -> Vec<&'a TrackPoint> {
+    if points.len() <= 2 {
+        return points.to_vec();
+    }
+    // Metres on a plane centred on the first point; fine at the scale of a walk
+    let origin = points[0];
+    let metres_per_degree = EARTH_RADIUS_M.to_radians();
+    let lon_scale = origin.lat.to_radians().cos();
+    let projected: Vec<(f64, f64)> = points
+        .iter()
+        .map(|point| {
+            (
+                (point.lon - origin.lon) * lon_scale * metres_per_degree,
+                (point.lat - origin.lat) * metres_per_degree,
+            )
+        })
+        .collect();
+
+    let mut tolerance = ROUTE_TOLERANCE_M;
+    loop {
+        let mut keep = vec![false; points.len()];
+        keep[0] = true;
+        keep[points.len() - 1] = true;
+        let mut spans = vec![(0, points.len() - 1)];
+        while let Some((first, last)) = spans.pop() {
+            let farthest = (first + 1..last)
+                .map(|index| {
+                    let offset =
+                        offset_from_line(projected[index], projected[first], projected[last]);
+                    (index, offset)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            if let Some((index, offset)) = farthest {
+                if offset > tolerance {
+                    keep[index] = true;
+                    spans.push((first, index));
+                    spans.push((index, last));
+                }
+            }
+        }
+        if keep.iter().filter(|kept| **kept).count() <= MAX_ROUTE_POINTS {
+            return points
+                .iter()
+                .zip(keep)
+                .filter(|(_, kept)| *kept)
+                .map(|(point, _)| *point)
+                .collect();
+        }
+        tolerance *= 2.0;
+    }
+}
+
+/// Distance of `point` from the segment `start`-`end` on the plane
+fn offset_from_line(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_squared = dx * dx + dy * dy;
+    let t = if length_squared == 0.0 {
+        0.0
+    } else {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_squared).clamp(0.0, 1.0)
+    };
+    let (x, y) = (start.0 + t * dx, start.1 + t * dy);
+    ((point.0 - x).powi(2) + (point.1 - y).powi(2)).sqrt()
+}
+
+/// Coordinate rounded to six decimals, about 10 cm
+fn round_coordinate(value: f64) -> f64 {
+    (value * 1e6).round() / 1e6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WATCH_EXPORT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<gpx version="1.1" creator="Watch" xmlns="http://www.topografix.com/GPX/1/1"
+     xmlns:gpxtpx="http://www.garmin.com/xmlschemas/TrackPointExtension/v1">
+  <metadata><name>Export</name><time>2025-06-01T06:59:00Z</time></metadata>
+  <trk>
+    <name><![CDATA[Morning Walk & Sniff]]></name>
+    <trkseg>
+      <trkpt lat="52.5200" lon="13.4050"><ele>34</ele><time>2025-06-01T07:00:00Z</time>
+        <extensions><gpxtpx:TrackPointExtension><gpxtpx:hr>92</gpxtpx:hr></gpxtpx:TrackPointExtension></extensions>
+      </trkpt>
+      <trkpt lat="52.5209" lon="13.4050"><time>2025-06-01T07:01:00Z</time></trkpt>
+      <!-- <trkpt lat="0" lon="0"></trkpt> -->
+      <trkpt lat="52.5218" lon="13.4050"><time>2025-06-01T07:02:00Z</time></trkpt>
+    </trkseg>
+    <trkseg>
+      <trkpt lat='52.5300' lon='13.4050'><time>2025-06-01T07:20:00Z</time></trkpt>
+      <trkpt lat="52.5309" lon="13.4050"/>
+      <trkpt lat="not a number" lon="13.4050"/>
+    </trkseg>
+  </trk>
+</gpx>"#;
+
+    #[test]
+    fn test_parse_watch_export() {
+        let track = parse_gpx(WATCH_EXPORT).unwrap();
+        assert_eq!(track.name.as_deref(), Some("Morning Walk & Sniff"));
+        assert_eq!(
+            track.segments.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![3, 2]
+        );
+        assert_eq!(track.segments[0][1].lat, 52.5209);
+        assert!(track.segments[1][1].time.is_none());
+
+        let walk = GpxWalk::from_track(&track).unwrap();
+        assert_eq!(walk.started_at.to_rfc3339(), "2025-06-01T07:00:00+00:00");
+        assert_eq!(walk.duration_minutes, Some(20.0));
+        // 0.0009° of latitude is about 100 m; the gap between segments isn't walked
+        assert!(
+            (walk.distance_km - 0.3).abs() < 0.001,
+            "{}",
+            walk.distance_km
+        );
+        assert_eq!(walk.point_count, 5);
+        assert_eq!(walk.bounds.max_lat, 52.5309);
+        // Points on a straight line collapse to its ends
+        assert_eq!(walk.route, vec![[52.52, 13.405], [52.5309, 13.405]]);
+    }
+
+    #[test]
+    fn test_walk_request_blocks() {
+        let walk = GpxWalk::from_track(&parse_gpx(WATCH_EXPORT).unwrap()).unwrap();
+        let request = walk.clone().into_request(7, true).unwrap();
+        assert_eq!(request.category, ActivityCategory::Lifestyle);
+        assert_eq!(request.subcategory, "Walk");
+        let data = request.activity_data.unwrap();
+        assert_eq!(data["title"], "Morning Walk & Sniff");
+        assert_eq!(data["distance"]["amount"], 0.3);
+        assert_eq!(data["distance"]["distanceType"], "walk");
+        assert_eq!(data["duration"]["amount"], 20.0);
+        assert_eq!(data["location"]["coordinates"]["lat"], 52.52);
+        assert_eq!(data["route"]["points"].as_array().unwrap().len(), 2);
+
+        let data = walk.into_request(7, false).unwrap().activity_data.unwrap();
+        assert!(data["route"].get("points").is_none());
+        assert_eq!(data["route"]["bounds"]["minLng"], 13.405);
+    }
+
+    #[test]
+    fn test_long_track_is_simplified() {
+        let points: Vec<TrackPoint> = (0..5000)
+            .map(|i| TrackPoint {
+                lat: 52.0 + i as f64 * 0.0001,
+                lon: 13.0 + (i as f64 / 10.0).sin() * 0.001,
+                time: None,
+            })
+            .collect();
+        let refs: Vec<&TrackPoint> = points.iter().collect();
+        let route = simplify_route(&refs);
+        assert!(route.len() <= MAX_ROUTE_POINTS && route.len() > 100);
+        assert_eq!(route[0], &points[0]);
+        assert_eq!(route.last(), points.last().as_ref());
+    }
+
+    #[test]
+    fn test_track_without_times_is_rejected() {
+        let track = parse_gpx(
+            r#"<gpx><trk><trkseg><trkpt lat="1" lon="2"/><trkpt lat="1.1" lon="2"/></trkseg></trk></gpx>"#,
+        )
+        .unwrap();
+        assert!(GpxWalk::from_track(&track).is_err());
+        assert!(parse_gpx("<gpx><trk").is_err());
+    }
+}
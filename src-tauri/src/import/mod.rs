@@ -0,0 +1,457 @@
+pub mod dog_log;
+pub mod eleven_pets;
+pub mod gpx;
+pub mod pet_first_aid;
+
+use crate::database::{ActivityCategory, ActivityCreateRequest};
+use crate::errors::ActivityError;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read;
+use std::path::Path;
+
+/// Exports of other pet apps that can be imported
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportFormat {
+    DogLog,
+    ElevenPets,
+    PetFirstAid,
+}
+
+impl std::fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportFormat::DogLog => write!(f, "dog_log"),
+            ImportFormat::ElevenPets => write!(f, "eleven_pets"),
+            ImportFormat::PetFirstAid => write!(f, "pet_first_aid"),
+        }
+    }
+}
+
+impl std::str::FromStr for ImportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "dog_log" => Ok(ImportFormat::DogLog),
+            "eleven_pets" | "11pets" => Ok(ImportFormat::ElevenPets),
+            "pet_first_aid" => Ok(ImportFormat::PetFirstAid),
+            _ => Err(anyhow::anyhow!("Invalid import format: {}", s)),
+        }
+    }
+}
+
+/// One data row of an import file, with cells looked up by column name
+pub struct ImportRow<'a> {
+    /// Line number in the file, counting the header as line 1
+    pub line: usize,
+    columns: &'a HashMap<String, usize>,
+    record: &'a csv::StringRecord,
+}
+
+impl ImportRow<'_> {
+    /// Trimmed cell of `column` (case-insensitive); None when missing or empty
+    pub fn get(&self, column: &str) -> Option<&str> {
+        let index = *self.columns.get(&column.to_lowercase())?;
+        self.record
+            .get(index)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Cell that the row can't be mapped without
+    pub fn require(&self, column: &str) -> Result<&str, String> {
+        self.get(column).ok_or_else(|| format!("Missing {column}"))
+    }
+}
+
+/// An activity mapped from an import row, not yet assigned to a pet
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportedActivity {
+    pub line: usize,
+    /// Pet name in the source app, if the file has one
+    pub source_pet: Option<String>,
+    pub category: ActivityCategory,
+    pub subcategory: String,
+    pub activity_data: serde_json::Value,
+}
+
+impl ImportedActivity {
+    pub fn into_request(self, pet_id: i64) -> ActivityCreateRequest {
+        ActivityCreateRequest {
+            pet_id,
+            category: self.category,
+            subcategory: self.subcategory,
+            activity_data: Some(self.activity_data),
+            needs_review: true,
+        }
+    }
+}
+
+/// Adapter that maps rows of one app's export to activities
+pub trait Importer: Send + Sync {
+    fn format(&self) -> ImportFormat;
+
+    /// Columns that must all be present for a file to be recognized
+    fn required_columns(&self) -> &'static [&'static str];
+
+    /// Every column the importer reads; the rest are listed as unmapped in the report
+    fn known_columns(&self) -> &'static [&'static str];
+
+    /// Map one row; an error is the reason the row is skipped.
+    /// Times without an offset are read in `offset`.
+    fn map_row(&self, row: &ImportRow, offset: FixedOffset) -> Result<ImportedActivity, String>;
+}
+
+static IMPORTERS: [&dyn Importer; 3] = [
+    &dog_log::DogLogImporter,
+    &eleven_pets::ElevenPetsImporter,
+    &pet_first_aid::PetFirstAidImporter,
+];
+
+/// All import adapters
+pub fn importers() -> &'static [&'static dyn Importer] {
+    &IMPORTERS
+}
+
+/// Adapter for a format
+pub fn importer_for(format: ImportFormat) -> &'static dyn Importer {
+    *IMPORTERS
+        .iter()
+        .find(|importer| importer.format() == format)
+        .expect("every import format has an importer")
+}
+
+/// Format recognized from the header of an import file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportDetection {
+    /// None when no importer recognizes the columns
+    pub format: Option<ImportFormat>,
+    pub columns: Vec<String>,
+}
+
+/// A row that could not be imported
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SkippedRow {
+    pub line: usize,
+    pub reason: String,
+}
+
+/// What an import file maps to, shown before anything is saved
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ImportMappingReport {
+    pub format: ImportFormat,
+    pub total_rows: usize,
+    pub mapped_rows: usize,
+    pub skipped: Vec<SkippedRow>,
+    /// Mapped activities per `category/subcategory`
+    pub subcategory_counts: BTreeMap<String, usize>,
+    /// Distinct pet names found in the file
+    pub source_pets: Vec<String>,
+    /// Columns of the file that no importer field reads
+    pub unmapped_columns: Vec<String>,
+}
+
+/// Activities mapped from an import file, with the report describing them
+#[derive(Debug, Clone)]
+pub struct ImportMapping {
+    pub activities: Vec<ImportedActivity>,
+    pub report: ImportMappingReport,
+}
+
+/// Importer whose required columns are all among `columns`.
+/// When several match, the one requiring the most columns is the most specific.
+pub fn detect_format(columns: &[String]) -> Option<ImportFormat> {
+    let present: Vec<String> = columns.iter().map(|c| c.trim().to_lowercase()).collect();
+    IMPORTERS
+        .iter()
+        .filter(|importer| {
+            importer
+                .required_columns()
+                .iter()
+                .all(|column| present.contains(&column.to_lowercase()))
+        })
+        .max_by_key(|importer| importer.required_columns().len())
+        .map(|importer| importer.format())
+}
+
+/// Read the header of an import file and recognize its format
+pub fn detect_import_format(path: &Path) -> Result<ImportDetection, ActivityError> {
+    let columns = read_headers(&mut csv_reader(open(path)?))?;
+
+    Ok(ImportDetection {
+        format: detect_format(&columns),
+        columns,
+    })
+}
+
+/// Map an import file, detecting its format unless one is given
+pub fn map_import_file(
+    path: &Path,
+    format: Option<ImportFormat>,
+    offset: FixedOffset,
+) -> Result<ImportMapping, ActivityError> {
+    map_import(open(path)?, format, offset)
+}
+
+/// Map CSV data to activities. Rows that can't be mapped are listed in the report.
+pub fn map_import<R: Read>(
+    data: R,
+    format: Option<ImportFormat>,
+    offset: FixedOffset,
+) -> Result<ImportMapping, ActivityError> {
+    let mut reader = csv_reader(data);
+    let headers = read_headers(&mut reader)?;
+
+    let format = format
+        .or_else(|| detect_format(&headers))
+        .ok_or_else(|| ActivityError::validation("format", "Unrecognized import file format"))?;
+    let importer = importer_for(format);
+    let columns: HashMap<String, usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(index, column)| (column.to_lowercase(), index))
+        .collect();
+    if let Some(missing) = importer
+        .required_columns()
+        .iter()
+        .find(|column| !columns.contains_key(&column.to_lowercase()))
+    {
+        return Err(ActivityError::validation(
+            "format".to_string(),
+            format!("The file is missing the '{missing}' column required for {format}"),
+        ));
+    }
+
+    let mut activities = Vec::new();
+    let mut skipped = Vec::new();
+    let mut total_rows = 0;
+    for (index, record) in reader.records().enumerate() {
+        let line = index + 2;
+        total_rows += 1;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                skipped.push(SkippedRow {
+                    line,
+                    reason: format!("Unreadable row: {e}"),
+                });
+                continue;
+            }
+        };
+        let row = ImportRow {
+            line,
+            columns: &columns,
+            record: &record,
+        };
+        match importer.map_row(&row, offset) {
+            Ok(activity) => activities.push(activity),
+            Err(reason) => skipped.push(SkippedRow { line, reason }),
+        }
+    }
+
+    let report = mapping_report(format, importer, &headers, total_rows, &activities, skipped);
+    log::info!(
+        "Mapped {} of {} rows from {format} import",
+        report.mapped_rows,
+        report.total_rows
+    );
+    Ok(ImportMapping { activities, report })
+}
+
+fn mapping_report(
+    format: ImportFormat,
+    importer: &dyn Importer,
+    headers: &[String],
+    total_rows: usize,
+    activities: &[ImportedActivity],
+    skipped: Vec<SkippedRow>,
+) -> ImportMappingReport {
+    let mut subcategory_counts = BTreeMap::new();
+    let mut source_pets: Vec<String> = Vec::new();
+    for activity in activities {
+        *subcategory_counts
+            .entry(format!("{}/{}", activity.category, activity.subcategory))
+            .or_insert(0) += 1;
+        if let Some(pet) = &activity.source_pet {
+            if !source_pets.contains(pet) {
+                source_pets.push(pet.clone());
+            }
+        }
+    }
+
+    ImportMappingReport {
+        format,
+        total_rows,
+        mapped_rows: activities.len(),
+        skipped,
+        subcategory_counts,
+        source_pets,
+        unmapped_columns: headers
+            .iter()
+            .filter(|column| {
+                !importer
+                    .known_columns()
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(column))
+            })
+            .cloned()
+            .collect(),
+    }
+}
+
+fn open(path: &Path) -> Result<std::fs::File, ActivityError> {
+    std::fs::File::open(path)
+        .map_err(|e| ActivityError::invalid_data(format!("Failed to open import file: {e}")))
+}
+
+/// Header cells, without the byte order mark spreadsheet apps put in front
+fn read_headers<R: Read>(reader: &mut csv::Reader<R>) -> Result<Vec<String>, ActivityError> {
+    Ok(reader
+        .headers()
+        .map_err(|e| ActivityError::invalid_data(format!("Failed to read import file: {e}")))?
+        .iter()
+        .map(|column| column.trim().trim_start_matches('\u{feff}').to_string())
+        .collect())
+}
+
+fn csv_reader<R: Read>(data: R) -> csv::Reader<R> {
+    csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data)
+}
+
+/// Instant of a date and optional time of day in one of `date_formats`.
+/// A missing time means noon, so the day doesn't shift in other timezones.
+pub fn parse_date_time(
+    date: &str,
+    time: Option<&str>,
+    date_formats: &[&str],
+    offset: FixedOffset,
+) -> Result<DateTime<Utc>, String> {
+    let date = date_formats
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| format!("Unrecognized date '{date}'"))?;
+    let time = match time {
+        Some(time) => {
+            parse_time_of_day(time).ok_or_else(|| format!("Unrecognized time '{time}'"))?
+        }
+        None => NaiveTime::from_hms_opt(12, 0, 0).unwrap_or_default(),
+    };
+    Ok(local_to_utc(date.and_time(time), offset))
+}
+
+/// Instant of a local date and time
+pub fn local_to_utc(local: NaiveDateTime, offset: FixedOffset) -> DateTime<Utc> {
+    offset
+        .from_local_datetime(&local)
+        .single()
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+/// `14:30`, `2:30 PM` or `2:30pm`
+fn parse_time_of_day(time: &str) -> Option<NaiveTime> {
+    let compact = time.replace(' ', "").to_uppercase();
+    ["%H:%M", "%H:%M:%S", "%I:%M%p", "%I%p"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(&compact, format).ok())
+}
+
+/// A number from a cell, accepting a decimal comma and surrounding units ("4,2 kg")
+pub fn parse_amount(value: &str) -> Option<f64> {
+    let number: String = value
+        .trim()
+        .chars()
+        .skip_while(|c| !c.is_ascii_digit() && *c != '-')
+        .take_while(|c| c.is_ascii_digit() || matches!(c, '.' | ',' | '-'))
+        .collect();
+    number.replace(',', ".").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offset() -> FixedOffset {
+        FixedOffset::east_opt(2 * 3600).unwrap()
+    }
+
+    #[test]
+    fn test_detect_format_from_columns() {
+        let columns = |names: &[&str]| names.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            detect_format(&columns(&["Dog", "Activity", "Date", "Time", "Notes"])),
+            Some(ImportFormat::DogLog)
+        );
+        assert_eq!(
+            detect_format(&columns(&["pet", "category", "event", "date", "value"])),
+            Some(ImportFormat::ElevenPets)
+        );
+        assert_eq!(
+            detect_format(&columns(&[
+                "Pet Name",
+                "Record Type",
+                "Date",
+                "Description"
+            ])),
+            Some(ImportFormat::PetFirstAid)
+        );
+        assert_eq!(detect_format(&columns(&["name", "when"])), None);
+    }
+
+    #[test]
+    fn test_detect_import_format_reads_header() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("export.csv");
+        std::fs::write(
+            &path,
+            "\u{feff}Dog,Activity,Date,Time\nRex,Walk,03/01/2025,8:00 AM\n",
+        )
+        .unwrap();
+
+        let detection = detect_import_format(&path).unwrap();
+        assert_eq!(detection.format, Some(ImportFormat::DogLog));
+        assert_eq!(detection.columns, vec!["Dog", "Activity", "Date", "Time"]);
+    }
+
+    #[test]
+    fn test_map_import_reports_skipped_and_unmapped() {
+        let csv = "Dog,Activity,Date,Time,Duration (min),Amount,Unit,Notes,Mood\n\
+                   Rex,Walk,03/01/2025,8:00 AM,45,,,Park loop,happy\n\
+                   Rex,Pee,03/01/2025,8:10 AM,,,,,\n\
+                   Rex,Food,not a date,,,,,,\n";
+        let mapping = map_import(csv.as_bytes(), None, offset()).unwrap();
+        let report = &mapping.report;
+
+        assert_eq!(report.format, ImportFormat::DogLog);
+        assert_eq!(report.total_rows, 3);
+        assert_eq!(report.mapped_rows, 1);
+        assert_eq!(report.skipped.len(), 2);
+        assert_eq!(report.skipped[1].line, 4);
+        assert_eq!(report.subcategory_counts.get("lifestyle/Walk"), Some(&1));
+        assert_eq!(report.source_pets, vec!["Rex"]);
+        assert_eq!(report.unmapped_columns, vec!["Mood"]);
+
+        // 8:00 at UTC+2
+        let walk = &mapping.activities[0];
+        assert_eq!(
+            walk.activity_data["time"]["date"],
+            "2025-03-01T06:00:00.000Z"
+        );
+
+        assert!(map_import("a,b\n1,2\n".as_bytes(), None, offset()).is_err());
+        assert!(map_import(csv.as_bytes(), Some(ImportFormat::PetFirstAid), offset()).is_err());
+    }
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("4,2 kg"), Some(4.2));
+        assert_eq!(parse_amount("$35.50"), Some(35.5));
+        assert_eq!(parse_amount("n/a"), None);
+    }
+}
@@ -0,0 +1,141 @@
+use super::{parse_amount, parse_date_time, ImportFormat, ImportRow, ImportedActivity, Importer};
+use crate::database::activity_blocks::{portion_type_for, ActivityBlocksBuilder};
+use crate::database::ActivityCategory;
+use chrono::FixedOffset;
+
+const DATE_FORMATS: [&str; 3] = ["%d/%m/%Y", "%Y-%m-%d", "%d-%m-%Y"];
+
+/// Pet First Aid CSV export: health records (injuries, illnesses, vet visits and
+/// medications) with day-first dates
+pub struct PetFirstAidImporter;
+
+impl Importer for PetFirstAidImporter {
+    fn format(&self) -> ImportFormat {
+        ImportFormat::PetFirstAid
+    }
+
+    fn required_columns(&self) -> &'static [&'static str] {
+        &["Pet Name", "Record Type", "Date", "Description"]
+    }
+
+    fn known_columns(&self) -> &'static [&'static str] {
+        &[
+            "Pet Name",
+            "Record Type",
+            "Date",
+            "Description",
+            "Medication",
+            "Dosage",
+            "Veterinarian",
+            "Notes",
+        ]
+    }
+
+    fn map_row(&self, row: &ImportRow, offset: FixedOffset) -> Result<ImportedActivity, String> {
+        let record_type = row.require("Record Type")?;
+        let subcategory = match record_type.to_lowercase().as_str() {
+            "injury" | "illness" | "symptom" | "first aid" => "Symptom",
+            "vet visit" | "vaccination" | "checkup" => "Checkup",
+            "medication" | "treatment" => "Medication",
+            _ => return Err(format!("Unsupported Pet First Aid record '{record_type}'")),
+        };
+
+        let mut blocks = ActivityBlocksBuilder::new();
+        blocks.time(parse_date_time(
+            row.require("Date")?,
+            None,
+            &DATE_FORMATS,
+            offset,
+        )?);
+
+        let description = row.require("Description")?;
+        let title = match row.get("Medication") {
+            Some(medication) if subcategory == "Medication" => medication,
+            _ => description,
+        };
+        blocks.title(title);
+
+        // Dosages the portion block can't hold, like "5 ml/kg", are kept in the notes
+        let unplaced_dosage = row.get("Dosage").filter(|dosage| {
+            let Some(amount) = parse_amount(dosage) else {
+                return true;
+            };
+            let unit = dosage
+                .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | ','))
+                .trim();
+            blocks
+                .portion(
+                    amount,
+                    if unit.is_empty() { "dose" } else { unit },
+                    portion_type_for(subcategory),
+                    None,
+                )
+                .is_err()
+        });
+
+        let notes: Vec<String> = [
+            (title != description).then(|| description.to_string()),
+            unplaced_dosage.map(|dosage| format!("Dosage: {dosage}")),
+            row.get("Veterinarian").map(|vet| format!("Vet: {vet}")),
+            row.get("Notes").map(str::to_string),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if !notes.is_empty() {
+            blocks.notes(&notes.join("\n"));
+        }
+
+        Ok(ImportedActivity {
+            line: row.line,
+            source_pet: row.get("Pet Name").map(str::to_string),
+            category: ActivityCategory::Health,
+            subcategory: subcategory.to_string(),
+            activity_data: blocks.into_json(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{map_import, ImportFormat};
+    use chrono::FixedOffset;
+
+    #[test]
+    fn test_map_pet_first_aid_export() {
+        let csv = "Pet Name,Record Type,Date,Description,Medication,Dosage,Veterinarian,Notes\n\
+                   Rex,Injury,14/05/2025,Cut paw pad,,,,Cleaned and bandaged\n\
+                   Rex,Medication,15/05/2025,Infection prevention,Amoxicillin,250 mg,Dr. Lee,\n\
+                   Rex,Grooming,16/05/2025,Bath,,,,\n\
+                   Rex,Treatment,17/05/2025,Rehydration,Saline,5 ml/kg,,\n";
+        let mapping = map_import(csv.as_bytes(), None, FixedOffset::east_opt(0).unwrap()).unwrap();
+
+        assert_eq!(mapping.report.format, ImportFormat::PetFirstAid);
+        assert_eq!(mapping.report.mapped_rows, 3);
+        assert_eq!(mapping.report.skipped[0].line, 4);
+
+        let injury = &mapping.activities[0];
+        assert_eq!(injury.subcategory, "Symptom");
+        assert_eq!(injury.activity_data["title"], "Cut paw pad");
+        assert_eq!(
+            injury.activity_data["time"]["date"],
+            "2025-05-14T12:00:00.000Z"
+        );
+
+        let medication = &mapping.activities[1];
+        assert_eq!(medication.activity_data["title"], "Amoxicillin");
+        assert_eq!(medication.activity_data["portion"]["amount"], 250.0);
+        assert_eq!(medication.activity_data["portion"]["unit"], "mg");
+        assert_eq!(
+            medication.activity_data["notes"],
+            "Infection prevention\nVet: Dr. Lee"
+        );
+
+        let fluids = &mapping.activities[2];
+        assert!(fluids.activity_data.get("portion").is_none());
+        assert_eq!(
+            fluids.activity_data["notes"],
+            "Rehydration\nDosage: 5 ml/kg"
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::import::ImportFormat;
+use crate::photo::PhotoSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Finished jobs are kept this long for the jobs screen, then pruned at startup
+pub const JOB_RETENTION_DAYS: i64 = 30;
+
+/// Kind of a background job
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Import,
+    Export,
+    PhotoReencode,
+}
+
+impl std::fmt::Display for JobKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobKind::Import => write!(f, "import"),
+            JobKind::Export => write!(f, "export"),
+            JobKind::PhotoReencode => write!(f, "photo_reencode"),
+        }
+    }
+}
+
+impl std::str::FromStr for JobKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "import" => Ok(JobKind::Import),
+            "export" => Ok(JobKind::Export),
+            "photo_reencode" => Ok(JobKind::PhotoReencode),
+            _ => Err(anyhow::anyhow!("Invalid job kind: {}", s)),
+        }
+    }
+}
+
+/// Where a job is in its lifecycle. Queued and running jobs are picked up again
+/// after a restart; the others are final.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+impl std::fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobStatus::Queued => write!(f, "queued"),
+            JobStatus::Running => write!(f, "running"),
+            JobStatus::Done => write!(f, "done"),
+            JobStatus::Failed => write!(f, "failed"),
+            JobStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl std::str::FromStr for JobStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "queued" => Ok(JobStatus::Queued),
+            "running" => Ok(JobStatus::Running),
+            "done" => Ok(JobStatus::Done),
+            "failed" => Ok(JobStatus::Failed),
+            "cancelled" => Ok(JobStatus::Cancelled),
+            _ => Err(anyhow::anyhow!("Invalid job status: {}", s)),
+        }
+    }
+}
+
+/// What a job does, stored with it so it can run again after a restart
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JobPayload {
+    /// Import activities from another pet app's CSV export
+    Import {
+        path: String,
+        pet_id: i64,
+        format: Option<ImportFormat>,
+        source_pet: Option<String>,
+    },
+    /// Write the anonymized research dataset
+    Export {
+        pet_ids: Option<Vec<i64>>,
+        file_path: String,
+    },
+    /// Re-encode stored photos with new photo settings
+    PhotoReencode { settings: PhotoSettings },
+}
+
+impl JobPayload {
+    pub fn kind(&self) -> JobKind {
+        match self {
+            JobPayload::Import { .. } => JobKind::Import,
+            JobPayload::Export { .. } => JobKind::Export,
+            JobPayload::PhotoReencode { .. } => JobKind::PhotoReencode,
+        }
+    }
+}
+
+/// Payload of job progress events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobProgress {
+    pub job_id: String,
+    pub kind: JobKind,
+    /// Items handled so far: rows, activities or photos depending on the kind
+    pub processed: i64,
+    pub total: i64,
+}
+
+/// Handle of a running job, used to check for cancellation
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    pub id: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Jobs the worker is running in this session, so they can be cancelled mid-way
+#[derive(Debug, Default)]
+pub struct RunningJobs {
+    running: Mutex<HashMap<String, JobHandle>>,
+}
+
+impl RunningJobs {
+    /// Register a job the worker is starting
+    pub fn start(&self, job_id: &str) -> JobHandle {
+        let handle = JobHandle {
+            id: job_id.to_string(),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(handle.id.clone(), handle.clone());
+        handle
+    }
+
+    /// Ask a running job to stop. Returns false if no such job is running.
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self
+            .running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(job_id)
+        {
+            Some(handle) => {
+                handle.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a job once it completed, failed or was cancelled
+    pub fn finish(&self, job_id: &str) {
+        self.running
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(job_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_round_trips_with_kind_tag() {
+        let payload = JobPayload::Import {
+            path: "/tmp/export.csv".to_string(),
+            pet_id: 3,
+            format: None,
+            source_pet: Some("Luna".to_string()),
+        };
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["kind"], "import");
+        assert_eq!(serde_json::from_value::<JobPayload>(json).unwrap(), payload);
+        assert_eq!(
+            payload.kind().to_string().parse::<JobKind>().unwrap(),
+            JobKind::Import
+        );
+
+        let jobs = RunningJobs::default();
+        let handle = jobs.start("job-1");
+        assert!(jobs.cancel("job-1"));
+        assert!(handle.is_cancelled());
+        jobs.finish("job-1");
+        assert!(!jobs.cancel("job-1"));
+    }
+}
@@ -1,13 +1,42 @@
 // Pet Management System modules
+pub mod care_handoff;
+pub mod checklists;
 pub mod commands;
+pub mod data_dir;
 pub mod database;
+pub mod document_scan;
+pub mod documents;
 pub mod errors;
+pub mod events;
+pub mod export;
+pub mod file_type;
+pub mod growth_reference;
+pub mod import;
+pub mod jobs;
 pub mod logger;
+pub mod milestones;
+pub mod note_templates;
+pub mod notifications;
+pub mod pdf;
+pub mod periods;
+pub mod pet_tag;
 pub mod photo;
+pub mod photo_album;
+pub mod photo_classifier;
+pub mod privacy;
 pub mod protocol;
+pub mod quick_entry;
+pub mod receipt;
+pub mod recurrence;
+pub mod startup;
+pub mod tips;
+pub mod vaccination_card;
 pub mod validation;
+pub mod webhook;
+pub mod zip;
 
 use commands::*;
+use startup::InitReport;
 use tauri::http::Response;
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
@@ -15,17 +44,40 @@ fn greet(name: &str) -> String {
     format!("Hello, {name}! You've been greeted from Rust!")
 }
 
+/// Makes `initialize_app` run once per process. Every window calls it on load and a
+/// hot reload calls it again; after the first success they get the same report and
+/// share the `AppState` it manages instead of opening the database a second time.
+#[derive(Default)]
+pub struct AppInitGuard {
+    /// Held while initializing, so a second window waits for the first
+    pub(crate) report: tokio::sync::Mutex<Option<InitReport>>,
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(logger::get_log_plugin())
         .plugin(tauri_plugin_opener::init())
+        .manage(AppInitGuard::default())
         .invoke_handler(tauri::generate_handler![
             // Original demo command
             greet,
             // Application initialization
             initialize_app,
+            is_app_initialized,
+            get_data_directory,
+            set_data_directory,
             get_app_statistics,
+            get_access_level,
+            get_data_footprint,
+            get_storage_quota,
+            set_storage_quota,
+            get_quota_status,
+            suggest_storage_cleanup,
+            run_diagnostic_query,
+            retry_file_deletions,
+            seed_demo_data,
+            remove_demo_data,
             // Pet management commands
             create_pet,
             get_pets,
@@ -33,19 +85,177 @@ pub fn run() {
             update_pet,
             delete_pet,
             reorder_pets,
+            merge_pets,
+            mark_pet_deceased,
+            get_memorial_report,
+            get_pet_aliases,
+            set_pet_aliases,
+            get_weight_history,
+            get_growth_chart,
+            get_weight_unit,
+            set_weight_unit,
+            get_period_settings,
+            set_period_settings,
+            generate_pet_qr,
+            generate_vaccination_card,
+            generate_care_handoff,
+            recalculate_pet_derived_data,
             // Photo management commands
             upload_pet_photo,
             upload_pet_photo_from_path,
+            upload_photos_batch,
             delete_pet_photo,
+            rotate_photo,
+            crop_photo,
             get_pet_photo_info,
+            suggest_photo_categories,
+            export_photo_album,
             list_pet_photos,
             get_photo_storage_stats,
+            get_photo_settings,
+            update_photo_settings,
+            set_low_storage_mode,
+            estimate_photo_storage_savings,
+            migrate_photos_to_content_names,
+            start_photo_reencode_job,
+            // Document vault commands
+            upload_pet_document,
+            list_pet_documents,
+            delete_pet_document,
+            set_pet_document_expiry,
+            get_expiring_documents,
             // Activity management commands
             create_activity,
+            parse_quick_entry,
+            extract_receipt_details,
+            get_smart_defaults,
+            suggest_subcategories,
+            get_known_brands,
+            get_known_products,
+            list_note_variables,
+            migrate_activity_data_versions,
+            set_schema_validation,
+            get_schema_validation_report,
+            scan_schema_discrepancies,
             update_activity,
+            list_activities_needing_review,
+            approve_activity,
+            set_activity_private,
             get_activity,
             get_activities_for_pet,
             delete_activity,
+            bulk_delete_activities,
+            restore_deleted_activities,
+            purge_activity_trash,
+            get_activity_overlaps,
+            get_activity_date_limits,
+            set_activity_date_limits,
+            link_activities,
+            unlink_activities,
+            get_activity_with_related,
+            get_pet_activity_links,
+            complete_reminder,
+            uncomplete_reminder,
+            get_reminder_compliance,
+            // Place commands
+            get_frequent_places,
+            get_place_suggestions,
+            get_walk_areas,
+            // Checklist commands
+            list_checklist_templates,
+            get_contextual_tips,
+            start_checklist,
+            get_pet_checklists,
+            set_checklist_item_completed,
+            delete_checklist,
+            // Growth milestone commands
+            list_milestones,
+            get_milestone_progress,
+            complete_milestone,
+            reopen_milestone,
+            // Inventory commands
+            create_inventory_item,
+            update_inventory_item,
+            adjust_inventory,
+            delete_inventory_item,
+            get_inventory_status,
+            // Metric rule commands
+            create_metric_rule,
+            update_metric_rule,
+            delete_metric_rule,
+            get_metric_rules,
+            get_metric_alerts,
+            // Custom block commands
+            create_custom_block,
+            update_custom_block,
+            delete_custom_block,
+            get_custom_blocks,
+            // Search commands
+            global_search,
+            parse_search_query,
+            get_search_tokenizer,
+            set_search_tokenizer,
+            // Health commands
+            get_health_score,
+            get_water_intake,
+            set_water_target,
+            get_exercise_summary,
+            set_exercise_target,
+            get_litter_trends,
+            get_body_condition_history,
+            get_grooming_status,
+            set_grooming_plan,
+            compare_pets,
+            get_cost_benchmarks,
+            forecast_expenses,
+            get_budget_status,
+            get_pet_budget,
+            set_pet_budget,
+            get_activity_heatmap,
+            // Recurring activity commands
+            create_recurring_activity,
+            get_recurring_series,
+            get_recurring_occurrences,
+            pause_recurring_series,
+            resume_recurring_series,
+            cancel_recurring_series,
+            update_recurring_occurrence,
+            skip_recurring_occurrence,
+            materialize_recurring_activities,
+            // Event commands
+            replay_events,
+            get_session_changes,
+            check_due_reminders,
+            get_overdue_followups,
+            get_notification_preferences,
+            update_notification_preferences,
+            get_notification_settings,
+            update_notification_settings,
+            regenerate_daily_summary,
+            get_timeline_digest,
+            // Automation webhook commands
+            get_webhook_settings,
+            update_webhook_settings,
+            test_webhook,
+            get_webhook_queue_status,
+            retry_webhook_deliveries,
+            // Export commands
+            export_anonymized_dataset,
+            cancel_export,
+            get_privacy_settings,
+            update_privacy_settings,
+            get_export_settings,
+            update_export_settings,
+            reveal_in_folder,
+            // Import commands
+            detect_import_format,
+            preview_import,
+            import_activities,
+            import_walk_gpx,
+            start_import_job,
+            // Background job commands
+            list_jobs,
+            cancel_job,
         ])
         .register_asynchronous_uri_scheme_protocol("photos", move |app, request, responder| {
             let app_handle = app.app_handle().clone();
@@ -59,6 +269,18 @@ pub fn run() {
                 }
             });
         })
+        .register_asynchronous_uri_scheme_protocol("documents", move |app, request, responder| {
+            let app_handle = app.app_handle().clone();
+            tauri::async_runtime::spawn(async move {
+                match protocol::handle_documents_protocol_request(&app_handle, request).await {
+                    Ok(response) => responder.respond(response),
+                    Err(e) => {
+                        log::error!("Documents protocol error: {e}");
+                        responder.respond(Response::builder().status(404).body(Vec::new()).unwrap())
+                    }
+                }
+            });
+        })
         .setup(|_app| {
             log::info!("Tauri application setup started");
             // Don't initialize AppState here - let initialize_app command handle it
@@ -0,0 +1,153 @@
+use crate::database::{MilestoneStatus, PetSpecies};
+use chrono::{Days, NaiveDate};
+use serde::Serialize;
+
+/// Age range, in weeks since birth, in which a milestone usually happens
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct AgeWindow {
+    pub from_weeks: u32,
+    pub until_weeks: u32,
+}
+
+impl AgeWindow {
+    /// First and last expected date for a pet born on `birth_date`
+    pub fn dates(&self, birth_date: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let after = |weeks: u32| {
+            birth_date
+                .checked_add_days(Days::new(weeks as u64 * 7))
+                .unwrap_or(NaiveDate::MAX)
+        };
+        (after(self.from_weeks), after(self.until_weeks))
+    }
+
+    /// Where a pet born on `birth_date` stands on `today`
+    pub fn status(
+        &self,
+        birth_date: NaiveDate,
+        completed_on: Option<NaiveDate>,
+        today: NaiveDate,
+    ) -> MilestoneStatus {
+        let (start, end) = self.dates(birth_date);
+        match completed_on {
+            Some(_) => MilestoneStatus::Completed,
+            None if today < start => MilestoneStatus::Upcoming,
+            None if today <= end => MilestoneStatus::Due,
+            None => MilestoneStatus::Overdue,
+        }
+    }
+}
+
+/// A growth milestone with the age it is expected at for each species
+#[derive(Debug, Clone, Serialize)]
+pub struct MilestoneDefinition {
+    pub key: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Expected window for cats; None when the milestone doesn't apply to cats
+    pub cat: Option<AgeWindow>,
+    /// Expected window for dogs; None when the milestone doesn't apply to dogs
+    pub dog: Option<AgeWindow>,
+}
+
+impl MilestoneDefinition {
+    /// Expected age window for a species, None when the milestone doesn't apply to it
+    pub fn window_for(&self, species: &PetSpecies) -> Option<AgeWindow> {
+        match species {
+            PetSpecies::Cat => self.cat,
+            PetSpecies::Dog => self.dog,
+        }
+    }
+}
+
+const fn weeks(from_weeks: u32, until_weeks: u32) -> Option<AgeWindow> {
+    Some(AgeWindow {
+        from_weeks,
+        until_weeks,
+    })
+}
+
+static MILESTONES: [MilestoneDefinition; 4] = [
+    MilestoneDefinition {
+        key: "first_vaccine",
+        title: "First vaccination",
+        description: "First core vaccine dose (FVRCP for cats, DHPP for dogs)",
+        cat: weeks(6, 9),
+        dog: weeks(6, 8),
+    },
+    MilestoneDefinition {
+        key: "first_walk",
+        title: "First walk outside",
+        description: "First walk once the vaccination course allows it",
+        cat: None,
+        dog: weeks(10, 16),
+    },
+    MilestoneDefinition {
+        key: "teeth_change",
+        title: "Adult teeth in",
+        description: "Baby teeth have been replaced by adult teeth",
+        cat: weeks(11, 26),
+        dog: weeks(12, 30),
+    },
+    MilestoneDefinition {
+        key: "neutering",
+        title: "Spay / neuter",
+        description: "Spaying or neutering as planned with the vet",
+        cat: weeks(16, 26),
+        dog: weeks(26, 78),
+    },
+];
+
+/// All built-in growth milestones
+pub fn all_milestones() -> &'static [MilestoneDefinition] {
+    &MILESTONES
+}
+
+/// Look up a milestone by key
+pub fn find_milestone(key: &str) -> Option<&'static MilestoneDefinition> {
+    MILESTONES.iter().find(|milestone| milestone.key == key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_milestone_keys_are_unique() {
+        let mut keys: Vec<_> = all_milestones().iter().map(|m| m.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), all_milestones().len());
+        assert!(all_milestones()
+            .iter()
+            .flat_map(|m| [m.cat, m.dog])
+            .flatten()
+            .all(|window| window.from_weeks <= window.until_weeks));
+    }
+
+    #[test]
+    fn test_window_status() {
+        let first_walk = find_milestone("first_walk").unwrap();
+        assert!(first_walk.window_for(&PetSpecies::Cat).is_none());
+
+        let window = first_walk.window_for(&PetSpecies::Dog).unwrap();
+        let birth = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let (start, end) = window.dates(birth);
+        assert_eq!(start, NaiveDate::from_ymd_opt(2025, 3, 12).unwrap());
+        assert_eq!(end, NaiveDate::from_ymd_opt(2025, 4, 23).unwrap());
+
+        let day = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        assert_eq!(
+            window.status(birth, None, day(3, 11)),
+            MilestoneStatus::Upcoming
+        );
+        assert_eq!(window.status(birth, None, day(4, 23)), MilestoneStatus::Due);
+        assert_eq!(
+            window.status(birth, None, day(4, 24)),
+            MilestoneStatus::Overdue
+        );
+        assert_eq!(
+            window.status(birth, Some(day(2, 1)), day(4, 24)),
+            MilestoneStatus::Completed
+        );
+    }
+}
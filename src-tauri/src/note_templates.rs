@@ -0,0 +1,178 @@
+use crate::database::activity_data::ActivityDataExt;
+use crate::database::{Activity, Pet, WeightUnit};
+use crate::privacy::PrivacySettings;
+use crate::vaccination_card;
+use chrono::{Datelike, NaiveDate};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Written in place of a variable that has no value for the pet, e.g. `{weight}`
+/// before any weight was logged
+pub const UNKNOWN_VALUE: &str = "unknown";
+
+/// A variable that can appear in a note template as `{name}`
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct NoteVariable {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Variables expanded in notes; anything else in braces is left as written
+pub static NOTE_VARIABLES: [NoteVariable; 6] = [
+    NoteVariable {
+        name: "pet_name",
+        description: "The pet's name",
+    },
+    NoteVariable {
+        name: "age",
+        description: "The pet's age, e.g. 2 years 3 months",
+    },
+    NoteVariable {
+        name: "weight",
+        description: "Latest logged weight in the preferred unit",
+    },
+    NoteVariable {
+        name: "last_vaccine",
+        description: "Name of the latest vaccination",
+    },
+    NoteVariable {
+        name: "days_since_last_vaccine",
+        description: "Days since the latest vaccination",
+    },
+    NoteVariable {
+        name: "today",
+        description: "Today's date",
+    },
+];
+
+/// Whether `text` may contain a variable, to skip gathering values for plain notes
+pub fn has_variables(text: &str) -> bool {
+    NOTE_VARIABLES
+        .iter()
+        .any(|variable| text.contains(&format!("{{{}}}", variable.name)))
+}
+
+/// Values of the note variables for a pet, from its activities as of `today`
+pub fn note_variables(
+    pet: &Pet,
+    activities: &[Activity],
+    unit: WeightUnit,
+    today: NaiveDate,
+) -> HashMap<&'static str, String> {
+    let mut values = HashMap::new();
+    values.insert("pet_name", pet.name.clone());
+    values.insert("age", age_text(pet.birth_date, today));
+    values.insert("today", today.to_string());
+
+    let latest_weight = activities
+        .iter()
+        .filter(|activity| !activity.needs_review && activity.occurred_on() <= today)
+        .filter_map(|activity| {
+            let weight_kg = activity.activity_data.as_ref()?.extract_weight_kg()?;
+            Some(((activity.occurred_on(), activity.id), weight_kg))
+        })
+        .max_by_key(|(order, _)| *order);
+    if let Some((_, weight_kg)) = latest_weight {
+        values.insert("weight", format!("{} {unit}", unit.from_kg(weight_kg)));
+    }
+
+    let last_vaccine =
+        vaccination_card::vaccination_entries(activities, &PrivacySettings::default())
+            .into_iter()
+            .filter(|entry| entry.given_on <= today)
+            .next_back();
+    if let Some(entry) = last_vaccine {
+        values.insert(
+            "days_since_last_vaccine",
+            (today - entry.given_on).num_days().to_string(),
+        );
+        values.insert("last_vaccine", entry.vaccine);
+    }
+    values
+}
+
+/// Replace each whitelisted `{name}` in `template` with its value, or
+/// [`UNKNOWN_VALUE`] when it has none. `{{` and `}}` write literal braces.
+pub fn expand_note_template(template: &str, values: &HashMap<&'static str, String>) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if let Some(after) = rest.strip_prefix("{{").or_else(|| rest.strip_prefix("}}")) {
+            expanded.push_str(&rest[..1]);
+            rest = after;
+            continue;
+        }
+        let variable = rest
+            .strip_prefix('{')
+            .and_then(|inner| inner.find('}').map(|end| &inner[..end]))
+            .filter(|name| NOTE_VARIABLES.iter().any(|variable| variable.name == *name));
+        match variable {
+            Some(name) => {
+                expanded.push_str(values.get(name).map_or(UNKNOWN_VALUE, String::as_str));
+                rest = &rest[name.len() + 2..];
+            }
+            None => {
+                expanded.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Age as whole years and months, or days for pets younger than a month
+fn age_text(birth_date: NaiveDate, today: NaiveDate) -> String {
+    let mut months =
+        (today.year() - birth_date.year()) * 12 + today.month() as i32 - birth_date.month() as i32;
+    if today.day() < birth_date.day() {
+        months -= 1;
+    }
+    let plural =
+        |count: i32, unit: &str| format!("{count} {unit}{}", if count == 1 { "" } else { "s" });
+    match (months / 12, months % 12) {
+        _ if months < 1 => plural((today - birth_date).num_days().max(0) as i32, "day"),
+        (0, months) => plural(months, "month"),
+        (years, 0) => plural(years, "year"),
+        (years, months) => format!("{} {}", plural(years, "year"), plural(months, "month")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_whitelisted_variables() {
+        let mut values = HashMap::new();
+        values.insert("pet_name", "Mochi".to_string());
+        values.insert("weight", "4.2 kg".to_string());
+
+        assert_eq!(
+            expand_note_template(
+                "{pet_name} weighs {weight}; vaccine {last_vaccine}",
+                &values
+            ),
+            "Mochi weighs 4.2 kg; vaccine unknown"
+        );
+        // Unknown names, literal braces and unclosed braces are kept
+        assert_eq!(
+            expand_note_template("{mood} {{weight}} {pet_name", &values),
+            "{mood} {weight} {pet_name"
+        );
+        assert!(has_variables("Weight: {weight}"));
+        assert!(!has_variables("{mood}"));
+    }
+
+    #[test]
+    fn test_age_text() {
+        let birth = NaiveDate::from_ymd_opt(2023, 3, 15).unwrap();
+        let on = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(age_text(birth, on(2023, 3, 25)), "10 days");
+        assert_eq!(age_text(birth, on(2023, 4, 15)), "1 month");
+        assert_eq!(age_text(birth, on(2025, 3, 14)), "1 year 11 months");
+        assert_eq!(age_text(birth, on(2025, 3, 15)), "2 years");
+    }
+}
@@ -0,0 +1,221 @@
+use crate::database::{DueReminder, NotificationPreferences, QuietHours};
+use crate::errors::ActivityError;
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Settings key of the app-wide notification settings
+pub const NOTIFICATION_SETTINGS_KEY: &str = "notification_settings";
+
+/// Fewest reminders that can be grouped into a digest
+pub const MIN_DIGEST_REMINDERS: usize = 2;
+
+/// How reminder notifications are scheduled for all pets
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Local time span during which every pet's reminders are queued. A pet's own
+    /// quiet hours apply on top of these.
+    pub quiet_hours: Option<QuietHours>,
+    /// Send reminders that come due together as one digest notification
+    pub batch_reminders: bool,
+    /// Number of reminders due at once from which they are sent as a digest
+    pub digest_threshold: usize,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            quiet_hours: None,
+            batch_reminders: true,
+            digest_threshold: MIN_DIGEST_REMINDERS,
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// Check the quiet hours span some time and the digest threshold groups reminders
+    pub fn validate(&self) -> Result<(), ActivityError> {
+        if self
+            .quiet_hours
+            .is_some_and(|quiet_hours| quiet_hours.start == quiet_hours.end)
+        {
+            return Err(ActivityError::validation(
+                "quiet_hours",
+                "Quiet hours must start and end at different times",
+            ));
+        }
+        if self.digest_threshold < MIN_DIGEST_REMINDERS {
+            return Err(ActivityError::validation(
+                "digest_threshold",
+                &format!("A digest groups at least {MIN_DIGEST_REMINDERS} reminders"),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Due reminders split by what to do with them now
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReminderSchedule {
+    pub send: Vec<DueReminder>,
+    /// In quiet hours; they stay due and are sent once the quiet hours are over
+    pub queued: Vec<DueReminder>,
+}
+
+/// Several reminders sent as one notification
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReminderDigest {
+    pub count: usize,
+    /// Oldest first
+    pub reminders: Vec<DueReminder>,
+}
+
+/// One notification to send for due reminders
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReminderNotification {
+    Single(DueReminder),
+    Digest(ReminderDigest),
+}
+
+/// Decide which due reminders go out at `local_time`. Reminders of muted pets are
+/// dropped; those inside the app-wide or the pet's quiet hours are queued.
+pub fn schedule_reminders(
+    due: Vec<DueReminder>,
+    settings: &NotificationSettings,
+    preferences: &HashMap<i64, NotificationPreferences>,
+    local_time: NaiveTime,
+) -> ReminderSchedule {
+    let app_quiet = settings
+        .quiet_hours
+        .is_some_and(|quiet_hours| quiet_hours.contains(local_time));
+
+    let mut schedule = ReminderSchedule::default();
+    for reminder in due {
+        let preferences = preferences.get(&reminder.pet_id);
+        if preferences.is_some_and(|preferences| preferences.reminders_muted) {
+            continue;
+        }
+        let pet_quiet =
+            preferences.is_some_and(|preferences| !preferences.allows_reminder_at(local_time));
+        if app_quiet || pet_quiet {
+            schedule.queued.push(reminder);
+        } else {
+            schedule.send.push(reminder);
+        }
+    }
+    schedule
+}
+
+/// Group reminders sent in the same check into a digest once there are at least
+/// `digest_threshold` of them, e.g. everything queued overnight
+pub fn batch_reminders(
+    mut reminders: Vec<DueReminder>,
+    settings: &NotificationSettings,
+) -> Vec<ReminderNotification> {
+    if settings.batch_reminders && reminders.len() >= settings.digest_threshold {
+        reminders.sort_by_key(|reminder| (reminder.due_at, reminder.activity_id));
+        return vec![ReminderNotification::Digest(ReminderDigest {
+            count: reminders.len(),
+            reminders,
+        })];
+    }
+    reminders
+        .into_iter()
+        .map(ReminderNotification::Single)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn reminder(activity_id: i64, pet_id: i64, hour: u32) -> DueReminder {
+        DueReminder {
+            activity_id,
+            pet_id,
+            title: format!("Reminder {activity_id}"),
+            reminder_type: None,
+            due_at: Utc.with_ymd_and_hms(2025, 3, 1, hour, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_quiet_hours_queue_reminders() {
+        let settings = NotificationSettings {
+            quiet_hours: Some(QuietHours {
+                start: at(22, 0),
+                end: at(7, 0),
+            }),
+            ..Default::default()
+        };
+        let mut preferences = HashMap::new();
+        preferences.insert(
+            2,
+            NotificationPreferences {
+                reminders_muted: true,
+                ..NotificationPreferences::defaults(2)
+            },
+        );
+        preferences.insert(
+            3,
+            NotificationPreferences {
+                quiet_hours: Some(QuietHours {
+                    start: at(12, 0),
+                    end: at(14, 0),
+                }),
+                ..NotificationPreferences::defaults(3)
+            },
+        );
+        let due = vec![reminder(10, 1, 1), reminder(20, 2, 1), reminder(30, 3, 1)];
+
+        let night = schedule_reminders(due.clone(), &settings, &preferences, at(23, 30));
+        assert!(night.send.is_empty());
+        assert_eq!(night.queued, vec![reminder(10, 1, 1), reminder(30, 3, 1)]);
+
+        let noon = schedule_reminders(due.clone(), &settings, &preferences, at(12, 30));
+        assert_eq!(noon.send, vec![reminder(10, 1, 1)]);
+        assert_eq!(noon.queued, vec![reminder(30, 3, 1)]);
+
+        let morning = schedule_reminders(due, &settings, &preferences, at(7, 0));
+        assert_eq!(morning.send.len(), 2);
+        assert!(morning.queued.is_empty());
+
+        assert!(NotificationSettings {
+            digest_threshold: 1,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_reminders_due_together_are_batched() {
+        let mut settings = NotificationSettings::default();
+        assert_eq!(
+            batch_reminders(vec![reminder(1, 1, 9)], &settings),
+            vec![ReminderNotification::Single(reminder(1, 1, 9))]
+        );
+
+        let batched = batch_reminders(vec![reminder(2, 1, 9), reminder(1, 2, 6)], &settings);
+        assert_eq!(
+            batched,
+            vec![ReminderNotification::Digest(ReminderDigest {
+                count: 2,
+                reminders: vec![reminder(1, 2, 6), reminder(2, 1, 9)],
+            })]
+        );
+
+        settings.batch_reminders = false;
+        assert_eq!(
+            batch_reminders(vec![reminder(2, 1, 9), reminder(1, 2, 6)], &settings).len(),
+            2
+        );
+    }
+}
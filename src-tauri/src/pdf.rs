@@ -0,0 +1,105 @@
+//! Minimal PDF writer for the printable exports (vaccination card, care handoff).
+//!
+//! Text uses the built-in Helvetica fonts so no font is embedded; characters
+//! outside Latin-1 are printed as `?`.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Font {
+    Regular,
+    Bold,
+}
+
+/// Drawing operators of one page
+#[derive(Debug, Default)]
+pub struct PageContent {
+    pub bytes: Vec<u8>,
+}
+
+impl PageContent {
+    pub fn text(&mut self, font: Font, size: f32, x: f32, y: f32, text: &str) {
+        let name = match font {
+            Font::Regular => "F1",
+            Font::Bold => "F2",
+        };
+        self.bytes
+            .extend(format!("BT /{name} {size:.1} Tf {x:.2} {y:.2} Td (").as_bytes());
+        self.bytes.extend(pdf_string(text));
+        self.bytes.extend(b") Tj ET\n");
+    }
+
+    /// Thin horizontal line from `x1` to `x2` at `y`
+    pub fn rule(&mut self, x1: f32, x2: f32, y: f32) {
+        self.bytes
+            .extend(format!("0.4 w {x1:.2} {y:.2} m {x2:.2} {y:.2} l S\n").as_bytes());
+    }
+}
+
+/// Text encoded for a PDF string literal in WinAnsiEncoding
+fn pdf_string(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => bytes.extend([b'\\', c as u8]),
+            ' '..='~' | '\u{a0}'..='\u{ff}' => bytes.push(c as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
+
+/// Assemble a PDF whose pages are `width` x `height` points and have the given
+/// content streams
+pub fn document(pages: &[PageContent], width: f32, height: f32) -> Vec<u8> {
+    // 1: catalog, 2: page tree, 3-4: fonts, then a page and its content per page
+    let first_page = 5;
+    let kids = (0..pages.len())
+        .map(|i| format!("{} 0 R", first_page + 2 * i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{kids}] /Count {} >>", pages.len()).into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+    ];
+    for (i, page) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {width:.2} {height:.2}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                first_page + 2 * i + 1
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", page.bytes.len()).into_bytes();
+        stream.extend(&page.bytes);
+        stream.extend(b"\nendstream");
+        objects.push(stream);
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+
+    let xref_offset = pdf.len();
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = write!(xref, "{offset:010} 00000 n \n");
+    }
+    let _ = write!(
+        xref,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+        objects.len() + 1
+    );
+    pdf.extend(xref.as_bytes());
+    pdf
+}
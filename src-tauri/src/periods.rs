@@ -0,0 +1,249 @@
+use anyhow::Result;
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Settings key for week start and month boundaries of time-bucketed statistics
+pub const PERIOD_SETTINGS_KEY: &str = "period_settings";
+
+/// Latest allowed month start day, so every month contains it
+pub const MAX_MONTH_START_DAY: u32 = 28;
+
+/// First day of the week for weekly buckets
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl WeekStart {
+    fn weekday(self) -> Weekday {
+        match self {
+            WeekStart::Monday => Weekday::Mon,
+            WeekStart::Sunday => Weekday::Sun,
+        }
+    }
+}
+
+impl std::fmt::Display for WeekStart {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WeekStart::Monday => write!(f, "monday"),
+            WeekStart::Sunday => write!(f, "sunday"),
+        }
+    }
+}
+
+impl std::str::FromStr for WeekStart {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "monday" | "mon" => Ok(WeekStart::Monday),
+            "sunday" | "sun" => Ok(WeekStart::Sunday),
+            _ => Err(anyhow::anyhow!("Invalid week start: {}", s)),
+        }
+    }
+}
+
+/// Size of a time bucket
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum PeriodGranularity {
+    Week,
+    #[default]
+    Month,
+}
+
+/// How weekly and monthly statistics are bucketed.
+/// A month runs from `month_start_day` to the day before it in the next month
+/// (e.g. 15 for statements that close mid-month); 1 means calendar months.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PeriodSettings {
+    pub week_start: WeekStart,
+    pub month_start_day: u32,
+}
+
+impl Default for PeriodSettings {
+    fn default() -> Self {
+        PeriodSettings {
+            week_start: WeekStart::Monday,
+            month_start_day: 1,
+        }
+    }
+}
+
+impl PeriodSettings {
+    /// Check the month start day is one every month has
+    pub fn validate(&self) -> Result<()> {
+        if !(1..=MAX_MONTH_START_DAY).contains(&self.month_start_day) {
+            return Err(anyhow::anyhow!(
+                "Month start day must be between 1 and {}",
+                MAX_MONTH_START_DAY
+            ));
+        }
+        Ok(())
+    }
+
+    /// First day of the week containing `date`
+    pub fn week_start_of(&self, date: NaiveDate) -> NaiveDate {
+        date.week(self.week_start.weekday()).first_day()
+    }
+
+    /// First day of the month period containing `date`
+    pub fn month_start_of(&self, date: NaiveDate) -> NaiveDate {
+        let start_day = self.month_start_day.clamp(1, MAX_MONTH_START_DAY);
+        let start = date.with_day(start_day).unwrap_or(date);
+        if date.day() >= start_day {
+            start
+        } else {
+            start.checked_sub_months(Months::new(1)).unwrap_or(start)
+        }
+    }
+
+    /// First day of the `granularity` period containing `date`
+    pub fn period_start_of(&self, date: NaiveDate, granularity: PeriodGranularity) -> NaiveDate {
+        match granularity {
+            PeriodGranularity::Week => self.week_start_of(date),
+            PeriodGranularity::Month => self.month_start_of(date),
+        }
+    }
+}
+
+/// Consecutive periods used as buckets for a time series, oldest first
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodBuckets {
+    pub granularity: PeriodGranularity,
+    pub settings: PeriodSettings,
+    /// First day of each period
+    pub starts: Vec<NaiveDate>,
+}
+
+impl PeriodBuckets {
+    /// Periods covering the `months` month periods that end with the one containing `today`
+    pub fn covering_months(
+        today: NaiveDate,
+        months: u32,
+        granularity: PeriodGranularity,
+        settings: PeriodSettings,
+    ) -> Self {
+        let current_month = settings.month_start_of(today);
+        let first_month = current_month
+            .checked_sub_months(Months::new(months.saturating_sub(1)))
+            .unwrap_or(current_month);
+
+        let starts = match granularity {
+            PeriodGranularity::Month => (0..months.max(1))
+                .filter_map(|n| first_month.checked_add_months(Months::new(n)))
+                .collect(),
+            PeriodGranularity::Week => {
+                let last = settings.week_start_of(today);
+                std::iter::successors(Some(settings.week_start_of(first_month)), |week| {
+                    week.checked_add_days(Days::new(7))
+                })
+                .take_while(|week| *week <= last)
+                .collect()
+            }
+        };
+
+        PeriodBuckets {
+            granularity,
+            settings,
+            starts,
+        }
+    }
+
+    /// Index of the period containing `date`
+    pub fn index_of(&self, date: NaiveDate) -> Option<usize> {
+        let start = self.settings.period_start_of(date, self.granularity);
+        self.starts.binary_search(&start).ok()
+    }
+
+    /// Labels of the periods: `YYYY-MM` for calendar months, the start date otherwise
+    pub fn labels(&self) -> Vec<String> {
+        let format = match self.granularity {
+            PeriodGranularity::Month if self.settings.month_start_day == 1 => "%Y-%m",
+            _ => "%Y-%m-%d",
+        };
+        self.starts
+            .iter()
+            .map(|start| start.format(format).to_string())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_week_start_of() {
+        // 2025-02-19 is a Wednesday
+        let monday = PeriodSettings::default();
+        let sunday = PeriodSettings {
+            week_start: WeekStart::Sunday,
+            ..Default::default()
+        };
+        assert_eq!(monday.week_start_of(date(2025, 2, 19)), date(2025, 2, 17));
+        assert_eq!(sunday.week_start_of(date(2025, 2, 19)), date(2025, 2, 16));
+        assert_eq!(sunday.week_start_of(date(2025, 2, 16)), date(2025, 2, 16));
+        assert_eq!(monday.week_start_of(date(2025, 2, 16)), date(2025, 2, 10));
+    }
+
+    #[test]
+    fn test_month_start_of_with_custom_boundary() {
+        let fiscal = PeriodSettings {
+            month_start_day: 15,
+            ..Default::default()
+        };
+        assert_eq!(fiscal.month_start_of(date(2025, 3, 15)), date(2025, 3, 15));
+        assert_eq!(fiscal.month_start_of(date(2025, 3, 14)), date(2025, 2, 15));
+        assert_eq!(fiscal.month_start_of(date(2025, 1, 2)), date(2024, 12, 15));
+        assert_eq!(
+            PeriodSettings::default().month_start_of(date(2025, 3, 14)),
+            date(2025, 3, 1)
+        );
+
+        assert!(fiscal.validate().is_ok());
+        assert!(PeriodSettings {
+            month_start_day: 31,
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_buckets() {
+        let today = date(2025, 2, 19);
+        let months = PeriodBuckets::covering_months(
+            today,
+            2,
+            PeriodGranularity::Month,
+            PeriodSettings::default(),
+        );
+        assert_eq!(months.labels(), vec!["2025-01", "2025-02"]);
+        assert_eq!(months.index_of(date(2025, 1, 31)), Some(0));
+        assert_eq!(months.index_of(date(2024, 12, 31)), None);
+
+        let weeks = PeriodBuckets::covering_months(
+            today,
+            1,
+            PeriodGranularity::Week,
+            PeriodSettings {
+                week_start: WeekStart::Sunday,
+                month_start_day: 1,
+            },
+        );
+        assert_eq!(
+            weeks.labels(),
+            vec!["2025-01-26", "2025-02-02", "2025-02-09", "2025-02-16"]
+        );
+        assert_eq!(weeks.index_of(date(2025, 2, 15)), Some(2));
+        assert_eq!(weeks.index_of(date(2025, 2, 16)), Some(3));
+    }
+}
@@ -0,0 +1,203 @@
+use crate::database::Pet;
+use crate::errors::PetError;
+use image::{DynamicImage, Luma};
+use qrcode::{EcLevel, QrCode};
+use serde::{Deserialize, Serialize};
+
+/// Default and allowed edge length of a generated tag image, in pixels
+pub const DEFAULT_QR_SIZE_PX: u32 = 512;
+const MIN_QR_SIZE_PX: u32 = 128;
+const MAX_QR_SIZE_PX: u32 = 2048;
+
+/// Longest text accepted for a single free-form tag field
+const MAX_TAG_FIELD_LENGTH: usize = 200;
+
+/// What to print on a collar tag besides the pet's name, species and breed.
+/// Emergency details are not part of the pet profile, so the caller supplies them.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct PetQrOptions {
+    pub contact_name: Option<String>,
+    pub contact_phone: String,
+    pub microchip: Option<String>,
+    pub allergies: Option<String>,
+    pub medical_notes: Option<String>,
+    /// Include the birth date line
+    pub include_birth_date: bool,
+    /// Edge length of the image in pixels (128-2048, default 512)
+    pub size_px: Option<u32>,
+}
+
+/// A generated QR tag stored with the photos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PetQrTag {
+    pub pet_id: i64,
+    /// Filename in photo storage, servable through the photos protocol
+    pub filename: String,
+    /// Text encoded in the QR code
+    pub payload: String,
+    pub size_px: u32,
+}
+
+impl PetQrOptions {
+    /// Check the emergency details before encoding
+    pub fn validate(&self) -> Result<(), PetError> {
+        if self.contact_phone.trim().is_empty() {
+            return Err(PetError::validation(
+                "contact_phone",
+                "An emergency contact phone number is required",
+            ));
+        }
+
+        let fields = [
+            ("contact_name", self.contact_name.as_deref()),
+            ("contact_phone", Some(self.contact_phone.as_str())),
+            ("microchip", self.microchip.as_deref()),
+            ("allergies", self.allergies.as_deref()),
+            ("medical_notes", self.medical_notes.as_deref()),
+        ];
+        for (field, value) in fields {
+            if value.is_some_and(|v| v.chars().count() > MAX_TAG_FIELD_LENGTH) {
+                return Err(PetError::validation(
+                    field.to_string(),
+                    format!("Must be at most {MAX_TAG_FIELD_LENGTH} characters"),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Requested image size, clamped to the supported range
+    pub fn size_px(&self) -> u32 {
+        self.size_px
+            .unwrap_or(DEFAULT_QR_SIZE_PX)
+            .clamp(MIN_QR_SIZE_PX, MAX_QR_SIZE_PX)
+    }
+}
+
+/// Plain-text tag content, one `LABEL: value` line per field so any phone
+/// camera can show it without an app
+pub fn tag_payload(pet: &Pet, options: &PetQrOptions) -> String {
+    let mut description = pet.species.to_string();
+    if let Some(breed) = non_empty(pet.breed.as_deref()) {
+        description.push_str(", ");
+        description.push_str(breed);
+    }
+
+    let mut lines = vec![format!("PET: {} ({description})", pet.name.trim())];
+    if options.include_birth_date {
+        lines.push(format!("BORN: {}", pet.birth_date.format("%Y-%m-%d")));
+    }
+    if let Some(microchip) = non_empty(options.microchip.as_deref()) {
+        lines.push(format!("MICROCHIP: {microchip}"));
+    }
+    if let Some(allergies) = non_empty(options.allergies.as_deref()) {
+        lines.push(format!("ALLERGIES: {allergies}"));
+    }
+    if let Some(notes) = non_empty(options.medical_notes.as_deref()) {
+        lines.push(format!("MEDICAL: {notes}"));
+    }
+    let contact = match non_empty(options.contact_name.as_deref()) {
+        Some(name) => format!("{name} {}", options.contact_phone.trim()),
+        None => options.contact_phone.trim().to_string(),
+    };
+    lines.push(format!("IF FOUND CALL: {contact}"));
+
+    lines.join("\n")
+}
+
+/// Render `payload` as a black-on-white QR code of roughly `size_px` pixels
+pub fn render_qr(payload: &str, size_px: u32) -> Result<DynamicImage, PetError> {
+    // Medium error correction survives scuffed tags while keeping modules large
+    let code =
+        QrCode::with_error_correction_level(payload.as_bytes(), EcLevel::M).map_err(|e| {
+            PetError::validation("options".to_string(), format!("Tag content too long: {e}"))
+        })?;
+
+    let image = code
+        .render::<Luma<u8>>()
+        .quiet_zone(true)
+        .min_dimensions(size_px, size_px)
+        .build();
+    Ok(DynamicImage::ImageLuma8(image))
+}
+
+fn non_empty(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{PetGender, PetSpecies, WeightUnit};
+    use chrono::{NaiveDate, Utc};
+
+    fn pet() -> Pet {
+        Pet {
+            id: 1,
+            name: "Mochi".to_string(),
+            birth_date: NaiveDate::from_ymd_opt(2022, 4, 1).unwrap(),
+            species: PetSpecies::Cat,
+            gender: PetGender::Female,
+            breed: Some("Siamese".to_string()),
+            color: None,
+            weight_kg: Some(4.2),
+            photo_path: None,
+            notes: Some("Private notes".to_string()),
+            display_order: 0,
+            is_archived: false,
+            is_memorial: false,
+            passed_away_on: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_weight: None,
+            display_unit: WeightUnit::Kg,
+        }
+    }
+
+    fn options() -> PetQrOptions {
+        PetQrOptions {
+            contact_name: Some("Alex".to_string()),
+            contact_phone: " +1 555 0100 ".to_string(),
+            microchip: Some("981020000000001".to_string()),
+            allergies: Some("Chicken".to_string()),
+            medical_notes: Some("  ".to_string()),
+            include_birth_date: true,
+            size_px: None,
+        }
+    }
+
+    #[test]
+    fn test_tag_payload() {
+        assert_eq!(
+            tag_payload(&pet(), &options()),
+            "PET: Mochi (cat, Siamese)\nBORN: 2022-04-01\nMICROCHIP: 981020000000001\nALLERGIES: Chicken\nIF FOUND CALL: Alex +1 555 0100"
+        );
+    }
+
+    #[test]
+    fn test_options_validation() {
+        assert!(options().validate().is_ok());
+        assert!(PetQrOptions::default().validate().is_err());
+        assert!(PetQrOptions {
+            allergies: Some("x".repeat(MAX_TAG_FIELD_LENGTH + 1)),
+            ..options()
+        }
+        .validate()
+        .is_err());
+
+        assert_eq!(options().size_px(), DEFAULT_QR_SIZE_PX);
+        let huge = PetQrOptions {
+            size_px: Some(10_000),
+            ..options()
+        };
+        assert_eq!(huge.size_px(), MAX_QR_SIZE_PX);
+    }
+
+    #[test]
+    fn test_render_qr_is_square_and_large_enough() {
+        let image = render_qr(&tag_payload(&pet(), &options()), 256).unwrap();
+        assert_eq!(image.width(), image.height());
+        assert!(image.width() >= 256);
+    }
+}
@@ -1,12 +1,393 @@
+use crate::document_scan;
+use crate::documents::hash_bytes;
 use crate::errors::PetError;
+use crate::file_type::{self, FileKind, SNIFF_LENGTH};
+use image::codecs::jpeg::JpegEncoder;
 use image::{GenericImageView, ImageFormat, ImageReader};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::panic::AssertUnwindSafe;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use tokio::sync::oneshot;
 use uuid::Uuid;
 
+/// Settings key under which photo processing options are persisted
+pub const PHOTO_SETTINGS_KEY: &str = "photo_settings";
+
+/// Maximum number of stored photos re-encoded when estimating savings
+const MAX_ESTIMATE_SAMPLES: usize = 25;
+
+/// Share of its size a re-encoded photo must save to replace the stored one, so
+/// re-encoding again with the same settings leaves photos alone
+const MIN_REENCODE_SAVING_RATIO: f64 = 0.1;
+
+/// Subdirectory of the photo storage holding resized renditions
+const RENDITIONS_DIR: &str = ".renditions";
+
+/// Largest width or height a rendition can be requested at
+pub const MAX_RENDITION_DIMENSION: u32 = 2048;
+
+/// Subdirectory of the photo storage holding the full-size copies kept for viewing
+const FULL_SIZE_DIR: &str = ".full";
+
+/// Default longest edge of the full-size copy of an upload
+pub const DEFAULT_FULL_SIZE_DIMENSION: u32 = 2048;
+
+/// Largest longest edge the full-size copy can be configured to
+pub const MAX_FULL_SIZE_DIMENSION: u32 = 4096;
+
+/// Content types accepted as photos
+const PHOTO_FILE_KINDS: [FileKind; 5] = [
+    FileKind::Jpeg,
+    FileKind::Png,
+    FileKind::Webp,
+    FileKind::Bmp,
+    FileKind::Tiff,
+];
+
+/// Output encoding for processed photos
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum PhotoOutputFormat {
+    /// Keep the format of the uploaded file
+    #[default]
+    Original,
+    Jpeg,
+    WebP,
+}
+
+/// Photo processing options
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PhotoSettings {
+    /// Longest edge of a processed photo in pixels
+    pub max_dimension: u32,
+    pub output_format: PhotoOutputFormat,
+    /// JPEG quality (1-100). WebP output is always lossless.
+    pub quality: u8,
+    /// Keep the original aspect ratio instead of centering on a white square canvas
+    pub skip_canvas_padding: bool,
+    /// Longest edge of the larger copy of each upload kept for viewing, downscaled
+    /// from the upload and never enlarged. None keeps only the processed photo.
+    pub full_size_dimension: Option<u32>,
+}
+
+impl Default for PhotoSettings {
+    fn default() -> Self {
+        PhotoSettings {
+            max_dimension: 512,
+            output_format: PhotoOutputFormat::Original,
+            quality: 75,
+            skip_canvas_padding: false,
+            full_size_dimension: Some(DEFAULT_FULL_SIZE_DIMENSION),
+        }
+    }
+}
+
+impl PhotoSettings {
+    /// Preset used by low-storage mode
+    pub fn low_storage() -> Self {
+        PhotoSettings {
+            max_dimension: 384,
+            output_format: PhotoOutputFormat::Jpeg,
+            quality: 60,
+            skip_canvas_padding: true,
+            full_size_dimension: None,
+        }
+    }
+
+    /// Validate option ranges
+    pub fn validate(&self) -> Result<(), PetError> {
+        if !(64..=4096).contains(&self.max_dimension) {
+            return Err(PetError::validation(
+                "max_dimension",
+                "Max dimension must be between 64 and 4096 pixels",
+            ));
+        }
+        if !(1..=100).contains(&self.quality) {
+            return Err(PetError::validation(
+                "quality",
+                "Quality must be between 1 and 100",
+            ));
+        }
+        if let Some(full_size) = self.full_size_dimension {
+            if !(self.max_dimension..=MAX_FULL_SIZE_DIMENSION).contains(&full_size) {
+                return Err(PetError::validation(
+                    "full_size_dimension".to_string(),
+                    format!(
+                        "Full-size dimension must be between the max dimension ({}) and {MAX_FULL_SIZE_DIMENSION} pixels",
+                        self.max_dimension
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Estimated effect of re-processing stored photos with different settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoStorageEstimate {
+    pub photo_count: usize,
+    /// Number of photos actually re-encoded to build the estimate
+    pub sampled_count: usize,
+    pub current_size: u64,
+    pub estimated_size: u64,
+    /// Bytes saved; negative when the new settings would use more space
+    pub estimated_savings: i64,
+    pub savings_percent: f64,
+}
+
+/// Upper bound on photo worker threads, so uploads never take over every core
+const MAX_PHOTO_WORKERS: usize = 4;
+
+/// Maximum number of photos accepted by a single batch upload
+pub const MAX_BATCH_PHOTOS: usize = 100;
+
+/// One photo in a batch upload, given either as bytes or as a local file path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoUploadItem {
+    pub filename: String,
+    #[serde(default)]
+    pub photo_bytes: Option<Vec<u8>>,
+    #[serde(default)]
+    pub file_path: Option<String>,
+    /// Crop and straighten a photographed document before storing it
+    #[serde(default)]
+    pub process_as_document: bool,
+}
+
+/// Outcome of one photo in a batch upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoUploadResult {
+    /// Position of the item in the request
+    pub index: usize,
+    pub filename: String,
+    pub photo_id: Option<String>,
+    pub error: Option<String>,
+}
+
+impl PhotoUploadResult {
+    pub fn new(index: usize, filename: String, outcome: Result<String, PetError>) -> Self {
+        let (photo_id, error) = match outcome {
+            Ok(photo_id) => (Some(photo_id), None),
+            Err(e) => (None, Some(e.to_string())),
+        };
+        PhotoUploadResult {
+            index,
+            filename,
+            photo_id,
+            error,
+        }
+    }
+}
+
+/// Progress event payload sent after each photo of a batch finishes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoUploadProgress {
+    pub completed: usize,
+    pub total: usize,
+    pub result: PhotoUploadResult,
+}
+
+/// A stored photo copied to its content-addressed name
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhotoRename {
+    pub old_name: String,
+    pub new_name: String,
+    /// False when another photo with the same content already had the name
+    pub created: bool,
+}
+
+/// Outcome of moving stored photos to content-addressed names
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PhotoRenameReport {
+    pub renamed: usize,
+    /// Renamed photos whose content was already stored under another name
+    pub deduplicated: usize,
+    /// Photos left as they are because a deletion is pending for them
+    pub skipped: usize,
+    pub pets_updated: i64,
+    pub activities_updated: i64,
+}
+
+/// Outcome of re-encoding stored photos with new settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PhotoReencodeReport {
+    pub reencoded: usize,
+    /// Photos left as they are: already small enough, PNGs or pending deletion
+    pub skipped: usize,
+    /// Photos that could not be read or re-encoded, by name
+    pub failed: Vec<String>,
+    pub bytes_saved: u64,
+    pub pets_updated: i64,
+    pub activities_updated: i64,
+}
+
+/// Pixel rectangle of a stored photo to keep when cropping
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PhotoCropRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// How a rendition fills the requested size
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RenditionFit {
+    /// Scale to fit inside the size, keeping the whole photo
+    #[default]
+    Contain,
+    /// Scale and crop to fill the size exactly
+    Cover,
+}
+
+impl RenditionFit {
+    fn as_str(self) -> &'static str {
+        match self {
+            RenditionFit::Contain => "contain",
+            RenditionFit::Cover => "cover",
+        }
+    }
+}
+
+/// Resized copy of a photo requested through the photos protocol,
+/// e.g. `photos://localhost/<name>.jpg?w=256&h=256&fit=cover`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenditionRequest {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fit: RenditionFit,
+}
+
+impl RenditionRequest {
+    /// Parse the query of a photos URL. Returns None when it asks for no size,
+    /// so the stored photo is served as is. Unknown parameters are ignored.
+    pub fn from_query(query: &str) -> Result<Option<Self>, PetError> {
+        let mut request = RenditionRequest::default();
+        for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "w" => request.width = Some(parse_rendition_dimension(value)?),
+                "h" => request.height = Some(parse_rendition_dimension(value)?),
+                "fit" => {
+                    request.fit = match value {
+                        "contain" => RenditionFit::Contain,
+                        "cover" => RenditionFit::Cover,
+                        _ => {
+                            return Err(PetError::invalid_input(format!(
+                                "Unknown rendition fit '{value}'"
+                            )))
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok((request.width.is_some() || request.height.is_some()).then_some(request))
+    }
+
+    /// Name of the cached rendition of `photo_filename`; it starts with the photo's
+    /// name and keeps its extension
+    fn cache_name(&self, photo_filename: &str) -> String {
+        let size = |dimension: Option<u32>| dimension.map(|d| d.to_string()).unwrap_or_default();
+        let extension = Path::new(photo_filename)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("jpg");
+        format!(
+            "{photo_filename}@{}x{}-{}.{extension}",
+            size(self.width),
+            size(self.height),
+            self.fit.as_str()
+        )
+    }
+
+    /// Resize `img` as requested. Photos are never enlarged: a size bigger than the
+    /// photo is shrunk to fit it, keeping the requested aspect ratio for `cover`.
+    fn render(&self, img: image::DynamicImage) -> image::DynamicImage {
+        let (width, height) = img.dimensions();
+        match (self.width, self.height, self.fit) {
+            (Some(target_width), Some(target_height), RenditionFit::Cover) => {
+                let scale = (width as f32 / target_width as f32)
+                    .min(height as f32 / target_height as f32)
+                    .min(1.0);
+                let target_width = ((target_width as f32 * scale).round() as u32).max(1);
+                let target_height = ((target_height as f32 * scale).round() as u32).max(1);
+                img.resize_to_fill(
+                    target_width,
+                    target_height,
+                    image::imageops::FilterType::Lanczos3,
+                )
+            }
+            (target_width, target_height, _) => {
+                let target_width = target_width.unwrap_or(width).min(width);
+                let target_height = target_height.unwrap_or(height).min(height);
+                if target_width == width && target_height == height {
+                    img
+                } else {
+                    img.resize(
+                        target_width,
+                        target_height,
+                        image::imageops::FilterType::Lanczos3,
+                    )
+                }
+            }
+        }
+    }
+}
+
+/// Whether the query of a photos URL asks for the full-size copy (`?size=full`)
+pub fn wants_full_size(query: &str) -> bool {
+    query.split('&').any(|pair| pair == "size=full")
+}
+
+fn parse_rendition_dimension(value: &str) -> Result<u32, PetError> {
+    value
+        .parse::<u32>()
+        .ok()
+        .filter(|size| (1..=MAX_RENDITION_DIMENSION).contains(size))
+        .ok_or_else(|| {
+            PetError::invalid_input(format!(
+                "Rendition size must be between 1 and {MAX_RENDITION_DIMENSION} pixels"
+            ))
+        })
+}
+
+/// An edit applied to a stored photo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhotoEdit {
+    /// Clockwise quarter turns; negative degrees turn counter-clockwise
+    Rotate {
+        degrees: i32,
+    },
+    Crop(PhotoCropRect),
+}
+
+/// Outcome of editing a stored photo
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhotoEditResult {
+    /// New name of the edited photo; the old name redirects to it
+    pub photo_id: String,
+    /// Copy of the photo as it was before its first edit
+    pub original: String,
+    pub dimensions: Option<(u32, u32)>,
+    pub pets_updated: i64,
+    pub activities_updated: i64,
+}
+
 /// Photo processing service for pet photos
 pub struct PhotoService {
     storage_dir: PathBuf,
+    settings: RwLock<PhotoSettings>,
+    /// Old names of renamed photos, resolved by the photos:// protocol
+    redirects: RwLock<HashMap<String, String>>,
+    /// Dedicated pool for CPU-bound decode/resize/encode work.
+    /// Its size bounds how many photos are processed at once.
+    workers: rayon::ThreadPool,
 }
 
 impl PhotoService {
@@ -28,12 +409,137 @@ impl PhotoService {
             ));
         }
 
-        Ok(PhotoService { storage_dir })
+        let worker_count = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(2)
+            .clamp(1, MAX_PHOTO_WORKERS);
+        let workers = rayon::ThreadPoolBuilder::new()
+            .num_threads(worker_count)
+            .thread_name(|index| format!("photo-worker-{index}"))
+            .build()
+            .map_err(|e| {
+                PetError::operation_failed(format!("Failed to start photo workers: {e}"))
+            })?;
+
+        Ok(PhotoService {
+            storage_dir,
+            settings: RwLock::new(PhotoSettings::default()),
+            redirects: RwLock::new(HashMap::new()),
+            workers,
+        })
+    }
+
+    /// Number of threads processing photos
+    pub fn worker_count(&self) -> usize {
+        self.workers.current_num_threads()
+    }
+
+    /// Run CPU-bound photo work on the worker pool and await its result,
+    /// keeping the async runtime free for other commands
+    pub async fn run_on_workers<T, F>(self: &Arc<Self>, job: F) -> Result<T, PetError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&PhotoService) -> Result<T, PetError> + Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let service = Arc::clone(self);
+        self.workers.spawn(move || {
+            // A panic in a spawned job would abort the process, e.g. when a decoder
+            // trips over a malformed image, so it's turned into an error instead
+            let result = std::panic::catch_unwind(AssertUnwindSafe(|| job(&service)))
+                .unwrap_or_else(|panic| {
+                    let message = panic
+                        .downcast_ref::<&str>()
+                        .map(|s| s.to_string())
+                        .or_else(|| panic.downcast_ref::<String>().cloned())
+                        .unwrap_or_else(|| "unknown panic".to_string());
+                    log::error!("Photo worker job panicked: {message}");
+                    Err(PetError::operation_failed(format!(
+                        "Photo processing failed: {message}"
+                    )))
+                });
+            // The receiver is gone only if the caller stopped waiting
+            let _ = sender.send(result);
+        });
+
+        receiver
+            .await
+            .map_err(|_| PetError::operation_failed("Photo worker stopped before finishing"))?
+    }
+
+    /// Async wrapper for [`PhotoService::store_photo`]
+    pub async fn store_photo_async(
+        self: &Arc<Self>,
+        source_path: PathBuf,
+    ) -> Result<String, PetError> {
+        self.run_on_workers(move |service| service.store_photo(&source_path))
+            .await
+    }
+
+    /// Async wrapper for [`PhotoService::store_photo_from_bytes_as`]
+    pub async fn store_photo_from_bytes_async(
+        self: &Arc<Self>,
+        image_data: Vec<u8>,
+        original_extension: Option<String>,
+        process_as_document: bool,
+    ) -> Result<String, PetError> {
+        self.run_on_workers(move |service| {
+            service.store_photo_from_bytes_as(
+                &image_data,
+                original_extension.as_deref(),
+                process_as_document,
+            )
+        })
+        .await
+    }
+
+    /// Async wrapper for [`PhotoService::store_upload`]
+    pub async fn store_upload_async(
+        self: &Arc<Self>,
+        item: PhotoUploadItem,
+    ) -> Result<String, PetError> {
+        self.run_on_workers(move |service| service.store_upload(&item))
+            .await
+    }
+
+    /// Async wrapper for [`PhotoService::estimate_storage_with_settings`]
+    pub async fn estimate_storage_async(
+        self: &Arc<Self>,
+        settings: PhotoSettings,
+    ) -> Result<PhotoStorageEstimate, PetError> {
+        self.run_on_workers(move |service| service.estimate_storage_with_settings(&settings))
+            .await
+    }
+
+    /// Current photo processing settings
+    pub fn settings(&self) -> PhotoSettings {
+        self.settings
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Replace the photo processing settings used for new uploads
+    pub fn update_settings(&self, settings: PhotoSettings) -> Result<(), PetError> {
+        settings.validate()?;
+        log::info!("Photo settings updated: {settings:?}");
+        *self.settings.write().unwrap_or_else(|e| e.into_inner()) = settings;
+        Ok(())
     }
 
     /// Process and store a pet photo from a source path
     /// Returns the relative path where the processed photo was stored
     pub fn store_photo<P: AsRef<Path>>(&self, source_path: P) -> Result<String, PetError> {
+        self.store_photo_as(source_path, false)
+    }
+
+    /// Process and store a photo; with `process_as_document` a photographed document
+    /// (e.g. a vaccination certificate) is cropped and its perspective corrected first
+    pub fn store_photo_as<P: AsRef<Path>>(
+        &self,
+        source_path: P,
+        process_as_document: bool,
+    ) -> Result<String, PetError> {
         let source_path = source_path.as_ref();
 
         // Validate source file exists
@@ -41,13 +547,16 @@ impl PhotoService {
             return Err(PetError::file_system("Source photo file does not exist"));
         }
 
-        // Generate unique filename
-        let file_extension = source_path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("jpg");
-        let unique_filename = format!("{}.{}", Uuid::new_v4(), file_extension);
-        let target_path = self.storage_dir.join(&unique_filename);
+        let settings = self.settings();
+        let declared_extension = source_path.extension().and_then(|ext| ext.to_str());
+
+        // Trust the content, not the extension
+        let kind = file_type::verify_content(
+            &read_file_head(source_path)?,
+            declared_extension,
+            &PHOTO_FILE_KINDS,
+        )?;
+        let file_extension = declared_extension.unwrap_or(kind.extensions()[0]);
 
         // Load and validate image with EXIF orientation correction
         let mut reader = ImageReader::open(source_path)
@@ -68,18 +577,61 @@ impl PhotoService {
         // Apply EXIF orientation if present (this handles camera rotation metadata)
         let img = self.apply_exif_orientation(source_path, img)?;
 
-        // Resize to 512x512 while maintaining aspect ratio
-        let resized_img = self.resize_image_with_aspect_ratio(img, 512, 512);
+        let img = if process_as_document {
+            match document_scan::straighten_document(&img) {
+                Some(document) => {
+                    log::info!(
+                        "Document edges detected, cropped to {}x{}",
+                        document.width(),
+                        document.height()
+                    );
+                    document
+                }
+                None => {
+                    log::info!("No document edges detected, storing photo as is");
+                    img
+                }
+            }
+        } else {
+            img
+        };
+
+        // Larger copy for viewing, only when the upload is bigger than the processed photo
+        let full_size_img = settings
+            .full_size_dimension
+            .filter(|_| {
+                img.width() > settings.max_dimension || img.height() > settings.max_dimension
+            })
+            .map(|max| {
+                if img.width() <= max && img.height() <= max {
+                    img.clone()
+                } else {
+                    img.resize(max, max, image::imageops::FilterType::Lanczos3)
+                }
+            });
+
+        // Resize to the configured size while maintaining aspect ratio
+        let resized_img = self.process_image(img, &settings);
 
         // Determine output format
-        let format = self.determine_output_format(file_extension)?;
+        let (format, output_extension) = match settings.output_format {
+            PhotoOutputFormat::Original => (
+                kind.image_format().unwrap_or(ImageFormat::Jpeg),
+                file_extension.to_lowercase(),
+            ),
+            PhotoOutputFormat::Jpeg => (ImageFormat::Jpeg, "jpg".to_string()),
+            PhotoOutputFormat::WebP => (ImageFormat::WebP, "webp".to_string()),
+        };
+
+        // Generate unique filename
+        let unique_filename = format!("{}.{}", Uuid::new_v4(), output_extension);
+        let target_path = self.storage_dir.join(&unique_filename);
 
         // Save processed image
-        resized_img
-            .save_with_format(&target_path, format)
-            .map_err(|e| {
-                PetError::photo_processing(format!("Failed to save processed image: {e}"))
-            })?;
+        let encoded = self.encode_image(&resized_img, format, settings.quality)?;
+        fs::write(&target_path, encoded).map_err(|e| {
+            PetError::photo_processing(format!("Failed to save processed image: {e}"))
+        })?;
 
         // Verify file was created successfully
         if !target_path.exists() {
@@ -88,6 +640,30 @@ impl PhotoService {
             ));
         }
 
+        if let Some(full_size_img) = full_size_img {
+            let full_size_dir = self.storage_dir.join(FULL_SIZE_DIR);
+            let saved = self
+                .encode_image(&full_size_img, format, settings.quality)
+                .and_then(|encoded| {
+                    fs::create_dir_all(&full_size_dir)
+                        .and_then(|_| fs::write(full_size_dir.join(&unique_filename), encoded))
+                        .map_err(|e| {
+                            PetError::photo_processing(format!(
+                                "Failed to save full-size copy: {e}"
+                            ))
+                        })
+                });
+            if let Err(e) = saved {
+                let _ = fs::remove_file(&target_path);
+                return Err(e);
+            }
+            log::info!(
+                "Full-size copy saved: {}x{}",
+                full_size_img.width(),
+                full_size_img.height()
+            );
+        }
+
         // Log file size for monitoring
         if let Ok(metadata) = fs::metadata(&target_path) {
             log::info!("Processed photo saved: {} bytes", metadata.len());
@@ -101,17 +677,36 @@ impl PhotoService {
         Ok(unique_filename)
     }
 
-    /// Store photo from binary data
+    /// Store photo from binary data.
+    /// `original_extension` may be an extension or the uploaded filename.
     pub fn store_photo_from_bytes(
         &self,
         image_data: &[u8],
         original_extension: Option<&str>,
     ) -> Result<String, PetError> {
+        self.store_photo_from_bytes_as(image_data, original_extension, false)
+    }
+
+    /// Store photo from binary data, optionally processing it as a photographed document
+    pub fn store_photo_from_bytes_as(
+        &self,
+        image_data: &[u8],
+        original_extension: Option<&str>,
+        process_as_document: bool,
+    ) -> Result<String, PetError> {
+        let declared_extension = original_extension.map(|name| {
+            Path::new(name)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or(name)
+        });
+        let kind = file_type::verify_content(image_data, declared_extension, &PHOTO_FILE_KINDS)?;
+
         // Create temporary file for processing
         let temp_filename = format!(
             "temp_{}.{}",
             Uuid::new_v4(),
-            original_extension.unwrap_or("jpg")
+            declared_extension.unwrap_or(kind.extensions()[0])
         );
         let temp_path = self.storage_dir.join(&temp_filename);
 
@@ -121,7 +716,7 @@ impl PhotoService {
         })?;
 
         // Process the temporary file
-        let result = self.store_photo(&temp_path);
+        let result = self.store_photo_as(&temp_path, process_as_document);
 
         // Clean up temporary file
         let _ = fs::remove_file(&temp_path);
@@ -129,6 +724,40 @@ impl PhotoService {
         result
     }
 
+    /// Store an image generated by the app (e.g. a QR tag) as a lossless PNG,
+    /// skipping the resize and re-encoding applied to uploads
+    pub fn store_generated_png(&self, img: &image::DynamicImage) -> Result<String, PetError> {
+        let filename = format!("{}.png", Uuid::new_v4());
+        let encoded = self.encode_image(img, ImageFormat::Png, 100)?;
+        fs::write(self.storage_dir.join(&filename), encoded).map_err(|e| {
+            PetError::photo_processing(format!("Failed to save generated image: {e}"))
+        })?;
+        Ok(filename)
+    }
+
+    /// Store one item of a batch upload
+    pub fn store_upload(&self, item: &PhotoUploadItem) -> Result<String, PetError> {
+        match (&item.photo_bytes, &item.file_path) {
+            (Some(bytes), None) => {
+                if bytes.is_empty() {
+                    return Err(PetError::validation(
+                        "photo_bytes",
+                        "Photo data cannot be empty",
+                    ));
+                }
+                let extension = Path::new(&item.filename)
+                    .extension()
+                    .and_then(|ext| ext.to_str());
+                self.store_photo_from_bytes_as(bytes, extension, item.process_as_document)
+            }
+            (None, Some(path)) => self.store_photo_as(path, item.process_as_document),
+            _ => Err(PetError::validation(
+                "photo",
+                "Provide exactly one of photo_bytes or file_path",
+            )),
+        }
+    }
+
     /// Delete a stored photo
     pub fn delete_photo(&self, photo_filename: &str) -> Result<(), PetError> {
         if photo_filename.trim().is_empty() {
@@ -153,9 +782,110 @@ impl PhotoService {
             log::warn!("Photo file not found for deletion: {photo_filename}");
         }
 
+        // The original of an edited photo goes with it
+        let original_path = self.storage_dir.join(original_copy_name(photo_filename));
+        if original_path.exists() {
+            fs::remove_file(&original_path).map_err(|e| {
+                PetError::file_system(format!("Failed to delete original photo file: {e}"))
+            })?;
+        }
+
+        let full_size_path = self.storage_dir.join(FULL_SIZE_DIR).join(photo_filename);
+        if full_size_path.exists() {
+            fs::remove_file(&full_size_path).map_err(|e| {
+                PetError::file_system(format!("Failed to delete full-size photo file: {e}"))
+            })?;
+        }
+
+        self.delete_renditions(photo_filename);
         Ok(())
     }
 
+    /// Path of the full-size copy of a photo, or of the photo itself when it has
+    /// none (uploaded before full-size copies, small uploads, low-storage mode)
+    pub fn get_full_size_path(&self, photo_filename: &str) -> Result<PathBuf, PetError> {
+        let photo_path = self.resolve_photo_path(photo_filename)?;
+        let full_size_path = photo_path
+            .file_name()
+            .map(|name| self.storage_dir.join(FULL_SIZE_DIR).join(name))
+            .filter(|path| path.exists());
+        Ok(full_size_path.unwrap_or(photo_path))
+    }
+
+    /// Path of a resized copy of a photo, generated on first request and cached
+    /// with the photos. A cached copy older than the photo is generated again.
+    pub fn get_rendition(
+        &self,
+        photo_filename: &str,
+        request: &RenditionRequest,
+    ) -> Result<PathBuf, PetError> {
+        // Rendered from the full-size copy when there is one
+        let source_path = self.get_full_size_path(photo_filename)?;
+        // Cache under the current name, so a redirected old name shares its renditions
+        let source_name = source_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| PetError::invalid_input("Invalid photo filename"))?;
+        let cache_dir = self.storage_dir.join(RENDITIONS_DIR);
+        let rendition_path = cache_dir.join(request.cache_name(source_name));
+
+        let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+        if let (Some(cached_at), Some(changed_at)) =
+            (modified(&rendition_path), modified(&source_path))
+        {
+            if cached_at >= changed_at {
+                return Ok(rendition_path);
+            }
+        }
+
+        let img = ImageReader::open(&source_path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| PetError::photo_processing(format!("Failed to open photo: {e}")))?
+            .decode()?;
+        let rendition = request.render(img);
+        let extension = source_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("jpg");
+        let format = self.determine_output_format(extension)?;
+        let encoded = self.encode_image(&rendition, format, self.settings().quality)?;
+
+        fs::create_dir_all(&cache_dir).map_err(|e| {
+            PetError::file_system(format!("Failed to create renditions directory: {e}"))
+        })?;
+        // Written under a temporary name so a concurrent request never reads half a file
+        let partial_path = cache_dir.join(format!("{}.partial", Uuid::new_v4()));
+        fs::write(&partial_path, encoded)
+            .and_then(|_| fs::rename(&partial_path, &rendition_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&partial_path);
+                PetError::file_system(format!("Failed to save rendition: {e}"))
+            })?;
+
+        log::debug!(
+            "Generated rendition {} ({}x{})",
+            rendition_path.display(),
+            rendition.width(),
+            rendition.height()
+        );
+        Ok(rendition_path)
+    }
+
+    /// Remove the cached renditions of a photo
+    fn delete_renditions(&self, photo_filename: &str) {
+        let Ok(entries) = fs::read_dir(self.storage_dir.join(RENDITIONS_DIR)) else {
+            return;
+        };
+        let prefix = format!("{photo_filename}@");
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                if let Err(e) = fs::remove_file(entry.path()) {
+                    log::warn!("Failed to delete rendition {:?}: {e}", entry.file_name());
+                }
+            }
+        }
+    }
+
     /// Get the full path to a stored photo
     pub fn get_photo_path(&self, photo_filename: &str) -> Result<PathBuf, PetError> {
         if photo_filename.trim().is_empty() {
@@ -179,6 +909,345 @@ impl PhotoService {
         Ok(photo_path)
     }
 
+    /// Path of a photo by its current or a redirected old name
+    pub fn resolve_photo_path(&self, photo_filename: &str) -> Result<PathBuf, PetError> {
+        match self.get_photo_path(photo_filename) {
+            Err(e) => match self.redirect_for(photo_filename) {
+                Some(new_name) => self.get_photo_path(&new_name),
+                None => Err(e),
+            },
+            found => found,
+        }
+    }
+
+    fn redirect_for(&self, photo_filename: &str) -> Option<String> {
+        self.redirects
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(photo_filename)
+            .cloned()
+    }
+
+    /// Add old-to-new name redirects of renamed photos
+    pub fn add_redirects(&self, redirects: impl IntoIterator<Item = (String, String)>) {
+        self.redirects
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(redirects);
+    }
+
+    /// Copy every photo that isn't named by its content yet to its content-addressed name,
+    /// except the ones in `excluded`. Originals are kept until [`PhotoService::complete_renames`],
+    /// so every stored reference keeps working until the database points to the new names.
+    pub fn prepare_content_addressed_renames(
+        &self,
+        excluded: &HashSet<String>,
+    ) -> Result<Vec<PhotoRename>, PetError> {
+        let mut renames = Vec::new();
+        for old_name in self.list_photos()? {
+            if is_content_addressed(&old_name)
+                || old_name.starts_with("temp_")
+                || excluded.contains(&old_name)
+            {
+                continue;
+            }
+            let old_path = self.storage_dir.join(&old_name);
+            let bytes = fs::read(&old_path)
+                .map_err(|e| PetError::file_system(format!("Failed to read {old_name}: {e}")))?;
+            let extension = old_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("jpg");
+            let new_name = content_addressed_name(&bytes, extension);
+            let new_path = self.storage_dir.join(&new_name);
+
+            let created = !new_path.exists();
+            if created {
+                // Write next to the target and rename, so a crash never leaves a partial file
+                let temp_path = self.storage_dir.join(format!("temp_{}", Uuid::new_v4()));
+                fs::write(&temp_path, &bytes)
+                    .and_then(|_| fs::rename(&temp_path, &new_path))
+                    .map_err(|e| {
+                        let _ = fs::remove_file(&temp_path);
+                        PetError::file_system(format!("Failed to copy {old_name}: {e}"))
+                    })?;
+            }
+            // The full-size copy follows the photo to its new name
+            let full_size_dir = self.storage_dir.join(FULL_SIZE_DIR);
+            if full_size_dir.join(&old_name).exists() && !full_size_dir.join(&new_name).exists() {
+                fs::copy(full_size_dir.join(&old_name), full_size_dir.join(&new_name)).map_err(
+                    |e| {
+                        if created {
+                            let _ = fs::remove_file(&new_path);
+                        }
+                        PetError::file_system(format!(
+                            "Failed to copy full-size copy of {old_name}: {e}"
+                        ))
+                    },
+                )?;
+            }
+            renames.push(PhotoRename {
+                old_name,
+                new_name,
+                created,
+            });
+        }
+        Ok(renames)
+    }
+
+    /// Write a copy of a stored photo re-encoded with `settings` under a new name, or
+    /// return None when that would not save at least a tenth of its size. PNGs are left
+    /// alone, as the images the app generates itself (QR tags) must stay lossless.
+    /// The photo is kept until [`PhotoService::complete_renames`], like a content rename;
+    /// its full-size copy follows only when `settings` keep full-size copies.
+    /// A discarded re-encode is cleaned up with [`PhotoService::discard_renames`].
+    pub fn prepare_reencode(
+        &self,
+        photo_filename: &str,
+        settings: &PhotoSettings,
+    ) -> Result<Option<PhotoRename>, PetError> {
+        settings.validate()?;
+        let path = self.get_photo_path(photo_filename)?;
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("jpg")
+            .to_lowercase();
+        if extension == "png" {
+            return Ok(None);
+        }
+        let current_size = fs::metadata(&path)
+            .map_err(|e| PetError::file_system(format!("Failed to read {photo_filename}: {e}")))?
+            .len();
+        let img = ImageReader::open(&path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| PetError::photo_processing(format!("Failed to open image: {e}")))?
+            .decode()
+            .map_err(|e| PetError::photo_processing(format!("Failed to decode image: {e}")))?;
+
+        let (format, new_extension) = match settings.output_format {
+            PhotoOutputFormat::Original => (self.determine_output_format(&extension)?, extension),
+            PhotoOutputFormat::Jpeg => (ImageFormat::Jpeg, "jpg".to_string()),
+            PhotoOutputFormat::WebP => (ImageFormat::WebP, "webp".to_string()),
+        };
+        let encoded =
+            self.encode_image(&self.process_image(img, settings), format, settings.quality)?;
+        if encoded.len() as f64 > current_size as f64 * (1.0 - MIN_REENCODE_SAVING_RATIO) {
+            return Ok(None);
+        }
+
+        let new_name = if is_content_addressed(photo_filename) {
+            content_addressed_name(&encoded, &new_extension)
+        } else {
+            format!("{}.{}", Uuid::new_v4(), new_extension)
+        };
+        let new_path = self.storage_dir.join(&new_name);
+        let created = !new_path.exists();
+        if created {
+            // Write next to the target and rename, so a crash never leaves a partial file
+            let temp_path = self.storage_dir.join(format!("temp_{}", Uuid::new_v4()));
+            fs::write(&temp_path, &encoded)
+                .and_then(|_| fs::rename(&temp_path, &new_path))
+                .map_err(|e| {
+                    let _ = fs::remove_file(&temp_path);
+                    PetError::photo_processing(format!("Failed to save re-encoded image: {e}"))
+                })?;
+        }
+
+        let full_size_dir = self.storage_dir.join(FULL_SIZE_DIR);
+        if settings.full_size_dimension.is_some()
+            && full_size_dir.join(photo_filename).exists()
+            && !full_size_dir.join(&new_name).exists()
+        {
+            fs::copy(
+                full_size_dir.join(photo_filename),
+                full_size_dir.join(&new_name),
+            )
+            .map_err(|e| {
+                if created {
+                    let _ = fs::remove_file(&new_path);
+                }
+                PetError::file_system(format!(
+                    "Failed to copy full-size copy of {photo_filename}: {e}"
+                ))
+            })?;
+        }
+
+        // An edited photo keeps the original it had before its first edit
+        let original_path = self.storage_dir.join(original_copy_name(photo_filename));
+        let new_original_path = self.storage_dir.join(original_copy_name(&new_name));
+        if original_path.exists() && !new_original_path.exists() {
+            fs::copy(&original_path, &new_original_path).map_err(|e| {
+                if created {
+                    let _ = fs::remove_file(&new_path);
+                    let _ = fs::remove_file(full_size_dir.join(&new_name));
+                }
+                PetError::file_system(format!("Failed to copy original of {photo_filename}: {e}"))
+            })?;
+        }
+
+        log::info!(
+            "Re-encoded photo {photo_filename} ({current_size} bytes) as {new_name} ({} bytes)",
+            encoded.len()
+        );
+        Ok(Some(PhotoRename {
+            old_name: photo_filename.to_string(),
+            new_name,
+            created,
+        }))
+    }
+
+    /// Remove the copies made by [`PhotoService::prepare_content_addressed_renames`]
+    /// or [`PhotoService::prepare_reencode`] after the database could not be updated
+    pub fn discard_renames(&self, renames: &[PhotoRename]) {
+        for rename in renames.iter().filter(|rename| rename.created) {
+            if let Err(e) = fs::remove_file(self.storage_dir.join(&rename.new_name)) {
+                log::warn!("Failed to remove photo copy {}: {e}", rename.new_name);
+            }
+            let _ = fs::remove_file(self.storage_dir.join(FULL_SIZE_DIR).join(&rename.new_name));
+            let _ = fs::remove_file(self.storage_dir.join(original_copy_name(&rename.new_name)));
+        }
+    }
+
+    /// Delete the originals of renamed photos and redirect their old names
+    pub fn complete_renames(&self, renames: &[PhotoRename]) {
+        for rename in renames {
+            if let Err(e) = self.delete_photo(&rename.old_name) {
+                log::warn!("Failed to remove renamed photo {}: {e}", rename.old_name);
+            }
+        }
+        self.add_redirects(
+            renames
+                .iter()
+                .map(|rename| (rename.old_name.clone(), rename.new_name.clone())),
+        );
+    }
+
+    /// Write an edited copy of a stored photo under a new name, content-addressed when the
+    /// photo was. The photo itself is kept until [`PhotoService::complete_edit`], so every
+    /// stored reference keeps working until the database points to the new name.
+    pub fn prepare_edit(
+        &self,
+        photo_filename: &str,
+        edit: PhotoEdit,
+    ) -> Result<PhotoRename, PetError> {
+        let path = self.get_photo_path(photo_filename)?;
+        let img = ImageReader::open(&path)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| PetError::photo_processing(format!("Failed to open image: {e}")))?
+            .decode()
+            .map_err(|e| PetError::photo_processing(format!("Failed to decode image: {e}")))?;
+
+        let edited = match edit {
+            PhotoEdit::Rotate { degrees } => match degrees.rem_euclid(360) {
+                90 => img.rotate90(),
+                180 => img.rotate180(),
+                270 => img.rotate270(),
+                _ => {
+                    return Err(PetError::validation(
+                        "degrees",
+                        "Rotation must be a non-zero multiple of 90 degrees",
+                    ))
+                }
+            },
+            PhotoEdit::Crop(rect) => {
+                let (width, height) = img.dimensions();
+                let fits = rect.width > 0
+                    && rect.height > 0
+                    && rect
+                        .x
+                        .checked_add(rect.width)
+                        .is_some_and(|right| right <= width)
+                    && rect
+                        .y
+                        .checked_add(rect.height)
+                        .is_some_and(|bottom| bottom <= height);
+                if !fits {
+                    return Err(PetError::validation(
+                        "rect".to_string(),
+                        format!(
+                            "Crop area must be non-empty and inside the {width}x{height} photo"
+                        ),
+                    ));
+                }
+                img.crop_imm(rect.x, rect.y, rect.width, rect.height)
+            }
+        };
+
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("jpg")
+            .to_lowercase();
+        let format = self.determine_output_format(&extension)?;
+        let encoded = self.encode_image(&edited, format, self.settings().quality)?;
+
+        let new_name = if is_content_addressed(photo_filename) {
+            content_addressed_name(&encoded, &extension)
+        } else {
+            format!("{}.{}", Uuid::new_v4(), extension)
+        };
+        if new_name == photo_filename {
+            return Err(PetError::invalid_input("The edit did not change the photo"));
+        }
+
+        let new_path = self.storage_dir.join(&new_name);
+        let created = !new_path.exists();
+        if created {
+            // Write next to the target and rename, so a crash never leaves a partial file
+            let temp_path = self.storage_dir.join(format!("temp_{}", Uuid::new_v4()));
+            fs::write(&temp_path, &encoded)
+                .and_then(|_| fs::rename(&temp_path, &new_path))
+                .map_err(|e| {
+                    let _ = fs::remove_file(&temp_path);
+                    PetError::photo_processing(format!("Failed to save edited image: {e}"))
+                })?;
+        }
+
+        log::info!("Edited photo {photo_filename} ({edit:?}) saved as {new_name}");
+        Ok(PhotoRename {
+            old_name: photo_filename.to_string(),
+            new_name,
+            created,
+        })
+    }
+
+    /// Keep the photo as it was before its first edit next to the edited photo, drop
+    /// intermediate versions and redirect the old name. Returns the name of the kept original.
+    pub fn complete_edit(&self, rename: &PhotoRename) -> Result<String, PetError> {
+        let old_path = self.storage_dir.join(&rename.old_name);
+        let earlier_original = self.storage_dir.join(original_copy_name(&rename.old_name));
+        let original = original_copy_name(&rename.new_name);
+        let original_path = self.storage_dir.join(&original);
+
+        // An edited photo's own original is the one to keep
+        let keep = if earlier_original.exists() {
+            earlier_original
+        } else {
+            old_path.clone()
+        };
+        if original_path.exists() {
+            // Another photo with the same content already keeps its original
+            if keep != old_path {
+                let _ = fs::remove_file(&keep);
+            }
+        } else {
+            fs::rename(&keep, &original_path).map_err(|e| {
+                PetError::file_system(format!("Failed to keep original photo: {e}"))
+            })?;
+        }
+        if old_path.exists() {
+            fs::remove_file(&old_path).map_err(|e| {
+                PetError::file_system(format!("Failed to remove edited photo: {e}"))
+            })?;
+        }
+        // The full-size copy shows the photo before the edit
+        let _ = fs::remove_file(self.storage_dir.join(FULL_SIZE_DIR).join(&rename.old_name));
+
+        self.add_redirects([(rename.old_name.clone(), rename.new_name.clone())]);
+        Ok(original)
+    }
+
     /// Get photo file info
     pub fn get_photo_info(&self, photo_filename: &str) -> Result<PhotoInfo, PetError> {
         let photo_path = self.get_photo_path(photo_filename)?;
@@ -194,10 +1263,16 @@ impl PhotoService {
             Err(_) => None,
         };
 
+        let mime_type = read_file_head(&photo_path)
+            .ok()
+            .and_then(|head| file_type::sniff(&head))
+            .map(|kind| kind.mime_type().to_string());
+
         Ok(PhotoInfo {
             filename: photo_filename.to_string(),
             file_size: metadata.len(),
             dimensions,
+            mime_type,
             created: metadata.created().ok(),
             modified: metadata.modified().ok(),
         })
@@ -233,6 +1308,18 @@ impl PhotoService {
         Ok(photos)
     }
 
+    /// All stored photos with their size in bytes
+    pub fn list_photo_sizes(&self) -> Result<Vec<(String, u64)>, PetError> {
+        Ok(self
+            .list_photos()?
+            .into_iter()
+            .filter_map(|filename| {
+                let size = fs::metadata(self.storage_dir.join(&filename)).ok()?.len();
+                Some((filename, size))
+            })
+            .collect())
+    }
+
     /// Get storage directory statistics
     pub fn get_storage_stats(&self) -> Result<StorageStats, PetError> {
         let mut total_size = 0u64;
@@ -269,6 +1356,121 @@ impl PhotoService {
         })
     }
 
+    /// Estimate the storage used if all stored photos were re-processed with `settings`.
+    /// Large libraries are estimated from an evenly spread sample.
+    pub fn estimate_storage_with_settings(
+        &self,
+        settings: &PhotoSettings,
+    ) -> Result<PhotoStorageEstimate, PetError> {
+        settings.validate()?;
+
+        let photos = self.list_photos()?;
+        let step = photos.len().div_ceil(MAX_ESTIMATE_SAMPLES).max(1);
+
+        let mut current_size = 0u64;
+        let mut sampled_current = 0u64;
+        let mut sampled_estimated = 0u64;
+        let mut sampled_count = 0usize;
+
+        for (index, filename) in photos.iter().enumerate() {
+            let path = self.storage_dir.join(filename);
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            current_size += size;
+
+            if index % step != 0 {
+                continue;
+            }
+
+            let img = match ImageReader::open(&path).and_then(|r| r.with_guessed_format()) {
+                Ok(reader) => match reader.decode() {
+                    Ok(img) => img,
+                    Err(e) => {
+                        log::warn!("Skipping undecodable photo {filename} in estimate: {e}");
+                        continue;
+                    }
+                },
+                Err(_) => continue,
+            };
+
+            let format = match settings.output_format {
+                PhotoOutputFormat::Original => {
+                    let extension = Path::new(filename)
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .unwrap_or("jpg");
+                    self.determine_output_format(extension)?
+                }
+                PhotoOutputFormat::Jpeg => ImageFormat::Jpeg,
+                PhotoOutputFormat::WebP => ImageFormat::WebP,
+            };
+
+            let processed = self.process_image(img, settings);
+            let encoded = self.encode_image(&processed, format, settings.quality)?;
+
+            sampled_current += size;
+            sampled_estimated += encoded.len() as u64;
+            sampled_count += 1;
+        }
+
+        let ratio = if sampled_current > 0 {
+            sampled_estimated as f64 / sampled_current as f64
+        } else {
+            1.0
+        };
+        let estimated_size = (current_size as f64 * ratio).round() as u64;
+        let estimated_savings = current_size as i64 - estimated_size as i64;
+
+        Ok(PhotoStorageEstimate {
+            photo_count: photos.len(),
+            sampled_count,
+            current_size,
+            estimated_size,
+            estimated_savings,
+            savings_percent: if current_size > 0 {
+                estimated_savings as f64 / current_size as f64 * 100.0
+            } else {
+                0.0
+            },
+        })
+    }
+
+    /// Resize an image according to the given settings
+    fn process_image(
+        &self,
+        img: image::DynamicImage,
+        settings: &PhotoSettings,
+    ) -> image::DynamicImage {
+        let max = settings.max_dimension;
+        if settings.skip_canvas_padding {
+            let (width, height) = img.dimensions();
+            if width <= max && height <= max {
+                img
+            } else {
+                img.resize(max, max, image::imageops::FilterType::Lanczos3)
+            }
+        } else {
+            self.resize_image_with_aspect_ratio(img, max, max)
+        }
+    }
+
+    /// Encode an image, applying the quality setting where the format supports it
+    fn encode_image(
+        &self,
+        img: &image::DynamicImage,
+        format: ImageFormat,
+        quality: u8,
+    ) -> Result<Vec<u8>, PetError> {
+        let mut bytes = Vec::new();
+        match format {
+            ImageFormat::Jpeg => {
+                let encoder = JpegEncoder::new_with_quality(&mut bytes, quality);
+                image::DynamicImage::ImageRgb8(img.to_rgb8()).write_with_encoder(encoder)?;
+            }
+            _ => img.write_to(&mut std::io::Cursor::new(&mut bytes), format)?,
+        }
+        Ok(bytes)
+    }
+
     /// Apply EXIF orientation correction to an image
     fn apply_exif_orientation(
         &self,
@@ -390,12 +1592,42 @@ impl PhotoService {
     }
 }
 
+/// Name of a photo derived from its content: the SHA-256 digest and the lowercased extension
+pub fn content_addressed_name(bytes: &[u8], extension: &str) -> String {
+    format!("{}.{}", hash_bytes(bytes), extension.to_lowercase())
+}
+
+/// Whether a stored photo name is a content hash rather than a random UUID
+pub fn is_content_addressed(photo_filename: &str) -> bool {
+    photo_filename
+        .split_once('.')
+        .is_some_and(|(stem, _)| stem.len() == 64 && stem.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Name of the copy kept of an edited photo as it was uploaded
+pub fn original_copy_name(photo_filename: &str) -> String {
+    format!("{photo_filename}.orig")
+}
+
+/// Leading bytes of a file, enough to sniff its type
+fn read_file_head(path: &Path) -> Result<Vec<u8>, PetError> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(SNIFF_LENGTH);
+    fs::File::open(path)
+        .and_then(|file| file.take(SNIFF_LENGTH as u64).read_to_end(&mut head))
+        .map_err(|e| PetError::file_system(format!("Failed to read photo file: {e}")))?;
+    Ok(head)
+}
+
 /// Information about a stored photo
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PhotoInfo {
     pub filename: String,
     pub file_size: u64,
     pub dimensions: Option<(u32, u32)>,
+    /// Type detected from the file content
+    pub mime_type: Option<String>,
     pub created: Option<std::time::SystemTime>,
     pub modified: Option<std::time::SystemTime>,
 }
@@ -459,6 +1691,87 @@ mod tests {
         assert!(photo_path.unwrap().exists());
     }
 
+    #[test]
+    fn test_store_upload_items() {
+        let (photo_service, temp_dir) = setup_test_photo_service();
+
+        let mut img_bytes = Vec::new();
+        create_test_image(50, 50)
+            .write_to(&mut std::io::Cursor::new(&mut img_bytes), ImageFormat::Png)
+            .unwrap();
+        let source_path = temp_dir.path().join("source.png");
+        fs::write(&source_path, &img_bytes).unwrap();
+
+        let from_bytes = PhotoUploadItem {
+            filename: "cat.png".to_string(),
+            photo_bytes: Some(img_bytes),
+            file_path: None,
+            process_as_document: false,
+        };
+        assert!(photo_service
+            .store_upload(&from_bytes)
+            .unwrap()
+            .ends_with(".png"));
+
+        let from_path = PhotoUploadItem {
+            filename: "source.png".to_string(),
+            photo_bytes: None,
+            file_path: Some(source_path.to_string_lossy().to_string()),
+            process_as_document: false,
+        };
+        assert!(photo_service.store_upload(&from_path).is_ok());
+
+        let neither = PhotoUploadItem {
+            filename: "nothing.png".to_string(),
+            photo_bytes: None,
+            file_path: None,
+            process_as_document: false,
+        };
+        let result = PhotoUploadResult::new(
+            2,
+            neither.filename.clone(),
+            photo_service.store_upload(&neither),
+        );
+        assert!(result.photo_id.is_none());
+        assert!(result.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_async_processing_on_worker_pool() {
+        let (photo_service, _temp_dir) = setup_test_photo_service();
+        let photo_service = Arc::new(photo_service);
+        assert!((1..=MAX_PHOTO_WORKERS).contains(&photo_service.worker_count()));
+
+        let mut img_bytes = Vec::new();
+        create_test_image(64, 48)
+            .write_to(&mut std::io::Cursor::new(&mut img_bytes), ImageFormat::Png)
+            .unwrap();
+
+        let uploads = (0..6).map(|_| {
+            photo_service.store_photo_from_bytes_async(
+                img_bytes.clone(),
+                Some("png".to_string()),
+                false,
+            )
+        });
+        let photo_ids = futures::future::join_all(uploads).await;
+        assert!(photo_ids.iter().all(Result::is_ok));
+        assert_eq!(photo_service.list_photos().unwrap().len(), 6);
+
+        let thread_name = photo_service
+            .run_on_workers(|_| Ok(std::thread::current().name().map(str::to_string)))
+            .await
+            .unwrap();
+        assert!(thread_name.unwrap().starts_with("photo-worker-"));
+
+        // A panicking job fails the call instead of taking the process down
+        let panicked = photo_service
+            .run_on_workers(|_| -> Result<(), PetError> { panic!("corrupt image") })
+            .await;
+        assert!(panicked.unwrap_err().to_string().contains("corrupt image"));
+        assert!(photo_service.run_on_workers(|_| Ok(())).await.is_ok());
+    }
+
     #[test]
     fn test_resize_image_aspect_ratio() {
         let (photo_service, _temp_dir) = setup_test_photo_service();
@@ -472,6 +1785,92 @@ mod tests {
         assert_eq!(height, 512);
     }
 
+    #[test]
+    fn test_renditions_are_cached_and_deleted_with_the_photo() {
+        let (photo_service, temp_dir) = setup_test_photo_service();
+        let photo_id = photo_service
+            .store_generated_png(&create_test_image(400, 200))
+            .unwrap();
+
+        assert_eq!(RenditionRequest::from_query("").unwrap(), None);
+        assert_eq!(RenditionRequest::from_query("v=2").unwrap(), None);
+        assert!(RenditionRequest::from_query("w=0").is_err());
+        assert!(RenditionRequest::from_query("w=64&fit=stretch").is_err());
+
+        let cover = RenditionRequest::from_query("w=100&h=100&fit=cover")
+            .unwrap()
+            .unwrap();
+        let path = photo_service.get_rendition(&photo_id, &cover).unwrap();
+        assert!(path.starts_with(temp_dir.path().join(RENDITIONS_DIR)));
+        assert!(path.to_string_lossy().ends_with(".png"));
+        assert_eq!(image::open(&path).unwrap().dimensions(), (100, 100));
+        assert_eq!(
+            photo_service.get_rendition(&photo_id, &cover).unwrap(),
+            path
+        );
+
+        // Contain keeps the aspect ratio and never enlarges
+        let contain = RenditionRequest::from_query("w=100").unwrap().unwrap();
+        let contained = photo_service.get_rendition(&photo_id, &contain).unwrap();
+        assert_eq!(image::open(&contained).unwrap().dimensions(), (100, 50));
+        let large = RenditionRequest::from_query("w=2000&h=2000&fit=cover")
+            .unwrap()
+            .unwrap();
+        let large = photo_service.get_rendition(&photo_id, &large).unwrap();
+        assert_eq!(image::open(&large).unwrap().dimensions(), (200, 200));
+
+        // Renditions aren't listed as photos and go away with the photo
+        assert_eq!(photo_service.list_photos().unwrap(), vec![photo_id.clone()]);
+        photo_service.delete_photo(&photo_id).unwrap();
+        assert!(!path.exists() && !contained.exists() && !large.exists());
+    }
+
+    #[test]
+    fn test_full_size_copy_is_kept_for_large_uploads() {
+        let (photo_service, temp_dir) = setup_test_photo_service();
+        let encode = |img: image::DynamicImage| {
+            let mut bytes = Vec::new();
+            img.write_to(&mut std::io::Cursor::new(&mut bytes), ImageFormat::Png)
+                .unwrap();
+            bytes
+        };
+
+        let photo_id = photo_service
+            .store_photo_from_bytes(&encode(create_test_image(3000, 1500)), Some("png"))
+            .unwrap();
+        let info = photo_service.get_photo_info(&photo_id).unwrap();
+        assert_eq!(info.dimensions, Some((512, 512)));
+
+        let full_size = photo_service.get_full_size_path(&photo_id).unwrap();
+        assert!(full_size.starts_with(temp_dir.path().join(FULL_SIZE_DIR)));
+        assert_eq!(image::open(&full_size).unwrap().dimensions(), (2048, 1024));
+        assert_eq!(photo_service.list_photos().unwrap(), vec![photo_id.clone()]);
+
+        // Renditions are rendered from the full-size copy
+        let rendition = RenditionRequest::from_query("w=1000").unwrap().unwrap();
+        let rendition = photo_service.get_rendition(&photo_id, &rendition).unwrap();
+        assert_eq!(image::open(&rendition).unwrap().dimensions(), (1000, 500));
+        assert!(wants_full_size("v=2&size=full"));
+
+        // Uploads no bigger than the processed photo get no copy
+        let small = photo_service
+            .store_photo_from_bytes(&encode(create_test_image(300, 200)), Some("png"))
+            .unwrap();
+        assert_eq!(
+            photo_service.get_full_size_path(&small).unwrap(),
+            photo_service.get_photo_path(&small).unwrap()
+        );
+
+        photo_service.delete_photo(&photo_id).unwrap();
+        assert!(!full_size.exists());
+
+        let too_small = PhotoSettings {
+            full_size_dimension: Some(256),
+            ..PhotoSettings::default()
+        };
+        assert!(photo_service.update_settings(too_small).is_err());
+    }
+
     #[test]
     fn test_delete_photo() {
         let (photo_service, _temp_dir) = setup_test_photo_service();
@@ -521,6 +1920,34 @@ mod tests {
         assert_eq!(info.filename, filename);
         assert!(info.file_size > 0);
         assert_eq!(info.dimensions, Some((512, 512))); // Should be resized
+        assert_eq!(info.mime_type.as_deref(), Some("image/png"));
+    }
+
+    #[test]
+    fn test_rejects_content_not_matching_extension() {
+        let (photo_service, _temp_dir) = setup_test_photo_service();
+
+        let mut png_bytes = Vec::new();
+        create_test_image(20, 20)
+            .write_to(&mut std::io::Cursor::new(&mut png_bytes), ImageFormat::Png)
+            .unwrap();
+        assert!(photo_service
+            .store_photo_from_bytes(&png_bytes, Some("jpg"))
+            .is_err());
+        // A filename works as well as a bare extension
+        assert!(photo_service
+            .store_photo_from_bytes(&png_bytes, Some("holiday.PNG"))
+            .unwrap()
+            .ends_with(".png"));
+
+        // An executable disguised as a photo
+        let source_dir = TempDir::new().unwrap();
+        let exe_path = source_dir.path().join("cute_cat.jpg");
+        fs::write(&exe_path, b"MZ\x90\0\x03\0\0\0\x04\0\0\0\xff\xff\0\0").unwrap();
+        assert!(photo_service.store_photo(&exe_path).is_err());
+
+        // Only the one valid upload was stored
+        assert_eq!(photo_service.list_photos().unwrap().len(), 1);
     }
 
     #[test]
@@ -561,6 +1988,169 @@ mod tests {
         assert_eq!(photos.len(), 2);
     }
 
+    #[test]
+    fn test_content_addressed_renames() {
+        let (photo_service, temp_dir) = setup_test_photo_service();
+        let bytes = b"same photo bytes".to_vec();
+        for name in ["a1.jpg", "b2.JPG", "pending.png"] {
+            fs::write(temp_dir.path().join(name), &bytes).unwrap();
+        }
+
+        let excluded = HashSet::from(["pending.png".to_string()]);
+        let renames = photo_service
+            .prepare_content_addressed_renames(&excluded)
+            .unwrap();
+        let new_name = content_addressed_name(&bytes, "jpg");
+        assert!(is_content_addressed(&new_name));
+        assert_eq!(renames.len(), 2);
+        assert!(renames.iter().all(|rename| rename.new_name == new_name));
+        assert_eq!(renames.iter().filter(|rename| rename.created).count(), 1);
+        // Originals stay until the rename is completed
+        assert!(temp_dir.path().join("a1.jpg").exists());
+
+        photo_service.complete_renames(&renames);
+        assert_eq!(
+            photo_service.list_photos().unwrap(),
+            vec![new_name.clone(), "pending.png".to_string()]
+        );
+        assert_eq!(
+            photo_service.resolve_photo_path("b2.JPG").unwrap(),
+            temp_dir.path().join(&new_name)
+        );
+
+        // Nothing left to rename
+        assert!(photo_service
+            .prepare_content_addressed_renames(&excluded)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_reencode_shrinks_photos_once() {
+        let (photo_service, temp_dir) = setup_test_photo_service();
+        let mut img_bytes = Vec::new();
+        create_test_image(1200, 900)
+            .write_to(&mut std::io::Cursor::new(&mut img_bytes), ImageFormat::Jpeg)
+            .unwrap();
+        let stored = photo_service
+            .store_photo_from_bytes(&img_bytes, Some("jpg"))
+            .unwrap();
+        fs::write(temp_dir.path().join("tag.png"), b"generated").unwrap();
+
+        let settings = PhotoSettings::low_storage();
+        let rename = photo_service
+            .prepare_reencode(&stored, &settings)
+            .unwrap()
+            .expect("low-storage settings shrink the photo");
+        assert!(
+            fs::metadata(temp_dir.path().join(&rename.new_name))
+                .unwrap()
+                .len()
+                < fs::metadata(temp_dir.path().join(&stored)).unwrap().len()
+        );
+        // Low-storage mode keeps no full-size copies
+        assert!(!temp_dir
+            .path()
+            .join(FULL_SIZE_DIR)
+            .join(&rename.new_name)
+            .exists());
+        photo_service.complete_renames(std::slice::from_ref(&rename));
+        assert!(!temp_dir.path().join(&stored).exists());
+        assert_eq!(
+            photo_service.resolve_photo_path(&stored).unwrap(),
+            temp_dir.path().join(&rename.new_name)
+        );
+
+        // Already re-encoded photos and PNGs are left alone
+        assert!(photo_service
+            .prepare_reencode(&rename.new_name, &settings)
+            .unwrap()
+            .is_none());
+        assert!(photo_service
+            .prepare_reencode("tag.png", &settings)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_edit_photo_keeps_first_original() {
+        let (photo_service, temp_dir) = setup_test_photo_service();
+        let mut img_bytes = Vec::new();
+        create_test_image(80, 40)
+            .write_to(&mut std::io::Cursor::new(&mut img_bytes), ImageFormat::Png)
+            .unwrap();
+        fs::write(temp_dir.path().join("sideways.png"), &img_bytes).unwrap();
+
+        let rotated = photo_service
+            .prepare_edit("sideways.png", PhotoEdit::Rotate { degrees: -90 })
+            .unwrap();
+        // The photo stays until the edit is completed
+        assert!(temp_dir.path().join("sideways.png").exists());
+        let original = photo_service.complete_edit(&rotated).unwrap();
+        assert_eq!(original, original_copy_name(&rotated.new_name));
+        assert_eq!(
+            photo_service
+                .get_photo_info(&rotated.new_name)
+                .unwrap()
+                .dimensions,
+            Some((40, 80))
+        );
+        assert_eq!(
+            photo_service.resolve_photo_path("sideways.png").unwrap(),
+            temp_dir.path().join(&rotated.new_name)
+        );
+
+        let rect = PhotoCropRect {
+            x: 5,
+            y: 10,
+            width: 30,
+            height: 50,
+        };
+        let cropped = photo_service
+            .prepare_edit(&rotated.new_name, PhotoEdit::Crop(rect))
+            .unwrap();
+        let original = photo_service.complete_edit(&cropped).unwrap();
+        assert_eq!(
+            photo_service.list_photos().unwrap(),
+            vec![cropped.new_name.clone()]
+        );
+        assert_eq!(
+            fs::read(temp_dir.path().join(&original)).unwrap(),
+            img_bytes,
+            "the original is the photo before the first edit"
+        );
+
+        // Deleting the photo removes its original too
+        photo_service.delete_photo(&cropped.new_name).unwrap();
+        assert!(!temp_dir.path().join(&original).exists());
+    }
+
+    #[test]
+    fn test_edit_photo_rejects_invalid_edits() {
+        let (photo_service, temp_dir) = setup_test_photo_service();
+        let mut img_bytes = Vec::new();
+        create_test_image(20, 20)
+            .write_to(&mut std::io::Cursor::new(&mut img_bytes), ImageFormat::Png)
+            .unwrap();
+        fs::write(temp_dir.path().join("photo.png"), &img_bytes).unwrap();
+
+        for degrees in [0, 45, 360] {
+            assert!(photo_service
+                .prepare_edit("photo.png", PhotoEdit::Rotate { degrees })
+                .is_err());
+        }
+        let outside = PhotoCropRect {
+            x: 10,
+            y: 0,
+            width: 11,
+            height: 5,
+        };
+        assert!(photo_service
+            .prepare_edit("photo.png", PhotoEdit::Crop(outside))
+            .is_err());
+        assert_eq!(photo_service.list_photos().unwrap(), vec!["photo.png"]);
+    }
+
     #[test]
     fn test_storage_stats() {
         let (photo_service, temp_dir) = setup_test_photo_service();
@@ -589,6 +2179,50 @@ mod tests {
         assert!(stats.total_size > 0);
     }
 
+    #[test]
+    fn test_low_storage_settings() {
+        let (photo_service, _temp_dir) = setup_test_photo_service();
+
+        // Photo-like noise, which PNG cannot compress well
+        let mut test_img = image::DynamicImage::new_rgb8(800, 400);
+        for (x, y, pixel) in test_img.as_mut_rgb8().unwrap().enumerate_pixels_mut() {
+            let noise = (x.wrapping_mul(2_654_435_761) ^ y.wrapping_mul(40_503)) >> 7;
+            *pixel = image::Rgb([noise as u8, (noise >> 8) as u8, (x + y) as u8]);
+        }
+        let mut img_bytes = Vec::new();
+        test_img
+            .write_to(&mut std::io::Cursor::new(&mut img_bytes), ImageFormat::Png)
+            .unwrap();
+        let original = photo_service
+            .store_photo_from_bytes(&img_bytes, Some("png"))
+            .unwrap();
+
+        let estimate = photo_service
+            .estimate_storage_with_settings(&PhotoSettings::low_storage())
+            .unwrap();
+        assert_eq!(estimate.photo_count, 1);
+        assert_eq!(estimate.sampled_count, 1);
+        assert!(estimate.estimated_savings > 0);
+
+        photo_service
+            .update_settings(PhotoSettings::low_storage())
+            .unwrap();
+        let filename = photo_service
+            .store_photo_from_bytes(&img_bytes, Some("png"))
+            .unwrap();
+        assert!(filename.ends_with(".jpg"));
+
+        let info = photo_service.get_photo_info(&filename).unwrap();
+        assert_eq!(info.dimensions, Some((384, 192)));
+        assert!(info.file_size < photo_service.get_photo_info(&original).unwrap().file_size);
+
+        let invalid = PhotoSettings {
+            quality: 0,
+            ..PhotoSettings::default()
+        };
+        assert!(photo_service.update_settings(invalid).is_err());
+    }
+
     #[test]
     fn test_invalid_filename_security() {
         let (photo_service, _temp_dir) = setup_test_photo_service();
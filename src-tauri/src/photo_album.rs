@@ -0,0 +1,343 @@
+//! Photo albums to share with family.
+//!
+//! An album holds a pet's profile photo and the photos attached to its activities,
+//! renamed by date and event (`photos/2025-06-01_walk.jpg`), with an `index.html`
+//! contact sheet that opens in any browser once the ZIP is extracted.
+
+use crate::database::activity_data::{ActivityDataExt, BlockData};
+use crate::database::{Activity, Pet};
+use crate::zip::ZipWriter;
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::Write;
+
+/// Contact sheet at the root of the album
+pub const INDEX_FILE: &str = "index.html";
+
+/// Folder in the album holding the photos
+const PHOTO_FOLDER: &str = "photos";
+
+/// Longest event part of a photo name, in characters
+const MAX_EVENT_CHARS: usize = 40;
+
+/// A photo going into an album
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AlbumPhoto {
+    /// Stored photo file
+    pub photo: String,
+    /// Path in the album, e.g. `photos/2025-06-01_walk.jpg`
+    pub entry_name: String,
+    /// Day of the activity; None for the profile photo
+    pub date: Option<NaiveDate>,
+    /// Activity title or subcategory, or "Profile photo"
+    pub caption: String,
+    pub activity_id: Option<i64>,
+}
+
+/// An album written to the export directory
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhotoAlbumExport {
+    pub pet_id: i64,
+    /// Path of the ZIP file
+    pub file_path: String,
+    pub photos: Vec<AlbumPhoto>,
+    /// Stored photos referenced by the pet or its activities that couldn't be read
+    pub missing: Vec<String>,
+}
+
+/// Result of writing an album
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlbumContents {
+    pub written: Vec<AlbumPhoto>,
+    /// Stored photos that couldn't be read
+    pub missing: Vec<String>,
+}
+
+/// Photos for an album of `pet`: the profile photo first, then activity photos
+/// between `start` and `end` (inclusive) by date. A photo attached to several
+/// activities is included once.
+pub fn album_photos(
+    pet: &Pet,
+    activities: &[Activity],
+    start: Option<NaiveDate>,
+    end: Option<NaiveDate>,
+) -> Vec<AlbumPhoto> {
+    let mut photos = Vec::new();
+    let mut seen = HashSet::new();
+    if let Some(profile) = &pet.photo_path {
+        seen.insert(profile.clone());
+        photos.push((
+            profile.clone(),
+            None,
+            "Profile photo".to_string(),
+            format!("{}_profile", slug(&pet.name)),
+            None,
+        ));
+    }
+
+    let mut dated: Vec<(NaiveDate, &Activity)> = activities
+        .iter()
+        .filter(|activity| activity.pet_id == pet.id)
+        .map(|activity| (activity.occurred_on(), activity))
+        .filter(|(date, _)| start.is_none_or(|start| *date >= start))
+        .filter(|(date, _)| end.is_none_or(|end| *date <= end))
+        .collect();
+    dated.sort_by_key(|(date, activity)| (*date, activity.id));
+    for (date, activity) in dated {
+        let Some(data) = &activity.activity_data else {
+            continue;
+        };
+        let caption = match data.get("title") {
+            Some(BlockData::Text(title)) if !title.trim().is_empty() => title.trim().to_string(),
+            _ => activity.subcategory.clone(),
+        };
+        for photo in data.attachment_files() {
+            if seen.insert(photo.clone()) {
+                photos.push((
+                    photo,
+                    Some(date),
+                    caption.clone(),
+                    format!("{date}_{}", slug(&caption)),
+                    Some(activity.id),
+                ));
+            }
+        }
+    }
+
+    let mut used = HashSet::new();
+    photos
+        .into_iter()
+        .map(|(photo, date, caption, stem, activity_id)| {
+            let extension = photo
+                .rsplit_once('.')
+                .map(|(_, extension)| extension.to_lowercase())
+                .unwrap_or_else(|| "jpg".to_string());
+            let mut entry_name = format!("{PHOTO_FOLDER}/{stem}.{extension}");
+            let mut copy = 1;
+            while !used.insert(entry_name.clone()) {
+                copy += 1;
+                entry_name = format!("{PHOTO_FOLDER}/{stem}_{copy}.{extension}");
+            }
+            AlbumPhoto {
+                photo,
+                entry_name,
+                date,
+                caption,
+                activity_id,
+            }
+        })
+        .collect()
+}
+
+/// Write the album ZIP: the photos `read` can load, then the contact sheet listing
+/// them. `created` dates the contact sheet.
+pub fn write_album<W: Write>(
+    out: W,
+    pet: &Pet,
+    range: (Option<NaiveDate>, Option<NaiveDate>),
+    photos: Vec<AlbumPhoto>,
+    created: NaiveDateTime,
+    mut read: impl FnMut(&str) -> Option<Vec<u8>>,
+) -> std::io::Result<(W, AlbumContents)> {
+    let mut zip = ZipWriter::new(out);
+    let mut contents = AlbumContents::default();
+    for photo in photos {
+        let Some(bytes) = read(&photo.photo) else {
+            contents.missing.push(photo.photo);
+            continue;
+        };
+        let modified = photo
+            .date
+            .and_then(|date| date.and_hms_opt(12, 0, 0))
+            .unwrap_or(created);
+        zip.add(&photo.entry_name, &bytes, modified)?;
+        contents.written.push(photo);
+    }
+    let sheet = contact_sheet(pet, range, &contents.written);
+    zip.add(INDEX_FILE, sheet.as_bytes(), created)?;
+    Ok((zip.finish()?, contents))
+}
+
+/// HTML page showing every photo of the album with its date and caption
+pub fn contact_sheet(
+    pet: &Pet,
+    (start, end): (Option<NaiveDate>, Option<NaiveDate>),
+    photos: &[AlbumPhoto],
+) -> String {
+    let day = |date: NaiveDate| date.format("%-d %B %Y").to_string();
+    let period = match (start, end) {
+        (Some(start), Some(end)) => format!("{} – {}", day(start), day(end)),
+        (Some(start), None) => format!("Since {}", day(start)),
+        (None, Some(end)) => format!("Until {}", day(end)),
+        (None, None) => "All photos".to_string(),
+    };
+    let title = escape_html(&format!("{}'s photo album", pet.name));
+
+    let mut html = format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; margin: 2rem; color: #333; }}
+.grid {{ display: grid; grid-template-columns: repeat(auto-fill, minmax(200px, 1fr)); gap: 1rem; }}
+figure {{ margin: 0; }}
+img {{ width: 100%; aspect-ratio: 1; object-fit: cover; border-radius: 6px; }}
+figcaption {{ font-size: 0.85rem; margin-top: 0.3rem; }}
+</style>
+</head>
+<body>
+<h1>{title}</h1>
+<p>{} · {} photos</p>
+<div class="grid">
+"#,
+        escape_html(&period),
+        photos.len()
+    );
+    for photo in photos {
+        let caption = match photo.date {
+            Some(date) => format!("{} · {}", day(date), photo.caption),
+            None => photo.caption.clone(),
+        };
+        let path = escape_html(&photo.entry_name);
+        let caption = escape_html(&caption);
+        html.push_str(&format!(
+            "<figure><a href=\"{path}\"><img src=\"{path}\" alt=\"{caption}\" loading=\"lazy\"></a><figcaption>{caption}</figcaption></figure>\n"
+        ));
+    }
+    html.push_str("</div>\n</body>\n</html>\n");
+    html
+}
+
+/// Lowercase words joined by dashes, e.g. "Morning walk!" becomes `morning-walk`
+fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if slug.chars().count() >= MAX_EVENT_CHARS {
+            break;
+        }
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "photo".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::activity_data::ActivityData;
+    use crate::database::{ActivityCategory, PetGender, PetSpecies, WeightUnit};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn pet() -> Pet {
+        Pet {
+            id: 1,
+            name: "Mochi".to_string(),
+            birth_date: NaiveDate::from_ymd_opt(2022, 4, 1).unwrap(),
+            species: PetSpecies::Cat,
+            gender: PetGender::Female,
+            breed: None,
+            color: None,
+            weight_kg: None,
+            photo_path: Some("profile.png".to_string()),
+            notes: None,
+            display_order: 0,
+            is_archived: false,
+            is_memorial: false,
+            passed_away_on: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_weight: None,
+            display_unit: WeightUnit::Kg,
+        }
+    }
+
+    fn activity(id: i64, day: u32, title: Option<&str>, photos: &[&str]) -> Activity {
+        let mut data = json!({
+            "time": { "date": format!("2025-06-{day:02}T09:00:00.000Z"), "time": "", "timezone": "" },
+            "attachments": photos.iter().map(|photo| json!({ "url": photo })).collect::<Vec<_>>(),
+        });
+        if let Some(title) = title {
+            data["title"] = json!(title);
+        }
+        Activity {
+            id,
+            pet_id: 1,
+            category: ActivityCategory::Lifestyle,
+            subcategory: "Walk".to_string(),
+            activity_data: Some(ActivityData::from_legacy_json(data)),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, d).unwrap()
+    }
+
+    #[test]
+    fn test_album_names_photos_by_date_and_event() {
+        let activities = vec![
+            activity(3, 12, None, &["late.jpg"]),
+            activity(1, 2, Some("Beach day! <3"), &["a.JPG", "b.jpg"]),
+            activity(2, 2, Some("Beach day"), &["b.jpg", "c.webp"]),
+            activity(4, 1, None, &["early.jpg"]),
+        ];
+        let photos = album_photos(&pet(), &activities, Some(date(2)), Some(date(10)));
+        let names: Vec<&str> = photos.iter().map(|p| p.entry_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "photos/mochi_profile.png",
+                "photos/2025-06-02_beach-day-3.jpg",
+                "photos/2025-06-02_beach-day-3_2.jpg",
+                "photos/2025-06-02_beach-day.webp",
+            ]
+        );
+        assert_eq!(photos[1].caption, "Beach day! <3");
+        assert_eq!(photos[3].activity_id, Some(2));
+    }
+
+    #[test]
+    fn test_write_album_skips_missing_photos() {
+        let activities = vec![activity(1, 2, None, &["a.jpg", "gone.jpg"])];
+        let photos = album_photos(&pet(), &activities, None, None);
+        let created = date(20).and_hms_opt(8, 0, 0).unwrap();
+
+        let (bytes, contents) =
+            write_album(Vec::new(), &pet(), (None, None), photos, created, |photo| {
+                (photo != "gone.jpg").then(|| photo.as_bytes().to_vec())
+            })
+            .unwrap();
+        assert_eq!(contents.missing, vec!["gone.jpg".to_string()]);
+        assert_eq!(contents.written.len(), 2);
+
+        let sheet = contact_sheet(&pet(), (None, None), &contents.written);
+        assert!(sheet.contains("<img src=\"photos/2025-06-02_walk.jpg\""));
+        assert!(sheet.contains("2 June 2025 · Walk"));
+        assert!(!sheet.contains("gone"));
+        // Both photos and the contact sheet
+        let end = bytes.len() - 22;
+        assert_eq!(u16::from_le_bytes([bytes[end + 10], bytes[end + 11]]), 3);
+    }
+}
@@ -0,0 +1,305 @@
+//! Category suggestions for photos attached in the activity editor.
+//!
+//! Built with the `photo-classifier` feature, an ONNX image classifier placed in
+//! the `models` directory of the app data ranks what a photo shows, and its labels
+//! are mapped to activity categories. The model is optional: without the feature
+//! or the model file, a photo of a sheet of paper still suggests a vet visit,
+//! using the document detection that straightens scanned certificates.
+
+use crate::database::ActivityCategory;
+use crate::document_scan;
+use crate::errors::PetError;
+use image::DynamicImage;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// File name of the classifier model in the models directory
+pub const MODEL_FILE: &str = "photo_classifier.onnx";
+
+/// File with one label per line, in the order of the model's outputs
+pub const LABELS_FILE: &str = "photo_classifier.labels";
+
+/// Most suggestions returned for a photo
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Suggestions less likely than this are left out
+const MIN_CONFIDENCE: f32 = 0.1;
+
+/// Confidence of the document suggestion made without a model
+const DOCUMENT_CONFIDENCE: f32 = 0.5;
+
+/// Model labels and the activity a photo of them suggests. Labels the app doesn't
+/// know are ignored, so a model may be trained on more classes.
+const LABEL_ACTIVITIES: [(&str, ActivityCategory, &str); 13] = [
+    ("food_bowl", ActivityCategory::Diet, "Feeding"),
+    ("water_bowl", ActivityCategory::Diet, "Water"),
+    ("treat", ActivityCategory::Diet, "Treat"),
+    ("vet_document", ActivityCategory::Health, "Checkup"),
+    ("vet_clinic", ActivityCategory::Health, "Checkup"),
+    ("medication", ActivityCategory::Health, "Medication"),
+    ("litter_box", ActivityCategory::Health, "Litter"),
+    ("scale", ActivityCategory::Growth, "Weight"),
+    ("outdoor_walk", ActivityCategory::Lifestyle, "Walk"),
+    ("toy", ActivityCategory::Lifestyle, "Play"),
+    ("sleeping", ActivityCategory::Lifestyle, "Sleep"),
+    ("grooming", ActivityCategory::Expense, "Grooming"),
+    ("receipt", ActivityCategory::Expense, "Purchase"),
+];
+
+/// An activity a photo probably belongs to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CategorySuggestion {
+    pub category: ActivityCategory,
+    pub subcategory: String,
+    /// What the photo was recognized as, e.g. `food_bowl`
+    pub label: String,
+    /// 0.0..=1.0
+    pub confidence: f32,
+}
+
+/// Ranked suggestions for a photo, most likely first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PhotoClassification {
+    /// Whether the image classifier ran; false when the app was built without
+    /// it or no model is installed
+    pub model_used: bool,
+    pub suggestions: Vec<CategorySuggestion>,
+}
+
+/// Suggest categories for a photo, with the model in `model_dir` when there is one
+#[cfg_attr(not(feature = "photo-classifier"), allow(unused_variables))]
+pub fn classify_photo(
+    img: &DynamicImage,
+    model_dir: &Path,
+) -> Result<PhotoClassification, PetError> {
+    #[cfg(feature = "photo-classifier")]
+    if let Some(model) = onnx::load(model_dir)? {
+        let scores = model.scores(img)?;
+        return Ok(PhotoClassification {
+            model_used: true,
+            suggestions: rank_suggestions(&model.labels, &scores, MAX_SUGGESTIONS),
+        });
+    }
+
+    let suggestions = match document_scan::detect_document_corners(img) {
+        Some(_) => vec![CategorySuggestion {
+            category: ActivityCategory::Health,
+            subcategory: "Checkup".to_string(),
+            label: "document".to_string(),
+            confidence: DOCUMENT_CONFIDENCE,
+        }],
+        None => Vec::new(),
+    };
+    Ok(PhotoClassification {
+        model_used: false,
+        suggestions,
+    })
+}
+
+/// Turn the model's outputs into suggestions. Outputs that aren't already
+/// probabilities are treated as logits; labels suggesting the same activity
+/// add up, and the best label names the suggestion.
+pub fn rank_suggestions(
+    labels: &[String],
+    scores: &[f32],
+    limit: usize,
+) -> Vec<CategorySuggestion> {
+    let is_distribution = scores.iter().all(|s| (0.0..=1.0).contains(s))
+        && (scores.iter().sum::<f32>() - 1.0).abs() < 0.01;
+    let probabilities: Vec<f32> = if is_distribution {
+        scores.to_vec()
+    } else {
+        let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+        let exps: Vec<f32> = scores.iter().map(|s| (s - max).exp()).collect();
+        let total: f32 = exps.iter().sum();
+        exps.iter().map(|e| e / total).collect()
+    };
+
+    let mut by_activity: HashMap<(ActivityCategory, &str), (f32, &str, f32)> = HashMap::new();
+    for (label, probability) in labels.iter().zip(probabilities) {
+        let Some((_, category, subcategory)) = LABEL_ACTIVITIES
+            .iter()
+            .find(|(known, _, _)| known.eq_ignore_ascii_case(label.trim()))
+        else {
+            continue;
+        };
+        let entry =
+            by_activity
+                .entry((*category, *subcategory))
+                .or_insert((0.0, label.as_str(), 0.0));
+        entry.0 += probability;
+        if probability > entry.2 {
+            entry.1 = label.as_str();
+            entry.2 = probability;
+        }
+    }
+
+    let mut suggestions: Vec<CategorySuggestion> = by_activity
+        .into_iter()
+        .filter(|(_, (confidence, _, _))| *confidence >= MIN_CONFIDENCE)
+        .map(
+            |((category, subcategory), (confidence, label, _))| CategorySuggestion {
+                category,
+                subcategory: subcategory.to_string(),
+                label: label.trim().to_string(),
+                confidence: confidence.min(1.0),
+            },
+        )
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.subcategory.cmp(&b.subcategory))
+    });
+    suggestions.truncate(limit);
+    suggestions
+}
+
+#[cfg(feature = "photo-classifier")]
+mod onnx {
+    use super::{LABELS_FILE, MODEL_FILE};
+    use crate::errors::PetError;
+    use image::imageops::FilterType;
+    use image::DynamicImage;
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+    use tract_onnx::prelude::*;
+
+    /// Side of the square image the model takes
+    const INPUT_SIZE: usize = 224;
+
+    /// ImageNet channel means and deviations the model was trained with
+    const MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+    const STD: [f32; 3] = [0.229, 0.224, 0.225];
+
+    /// Loaded model, kept with the path it came from
+    static LOADED: Mutex<Option<(PathBuf, Arc<Model>)>> = Mutex::new(None);
+
+    pub struct Model {
+        plan: TypedRunnableModel<TypedModel>,
+        pub labels: Vec<String>,
+    }
+
+    /// The model in `model_dir`, loaded once; None when none is installed
+    pub fn load(model_dir: &Path) -> Result<Option<Arc<Model>>, PetError> {
+        let path = model_dir.join(MODEL_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let mut loaded = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((loaded_path, model)) = loaded.as_ref() {
+            if *loaded_path == path {
+                return Ok(Some(Arc::clone(model)));
+            }
+        }
+
+        let failed = |e: TractError| {
+            PetError::operation_failed(format!("Failed to load photo classifier: {e}"))
+        };
+        let size = INPUT_SIZE as i64;
+        let plan = tract_onnx::onnx()
+            .model_for_path(&path)
+            .and_then(|model| model.with_input_fact(0, f32::fact([1, 3, size, size]).into()))
+            .and_then(|model| model.into_optimized())
+            .and_then(|model| model.into_runnable())
+            .map_err(failed)?;
+        let labels: Vec<String> = std::fs::read_to_string(model_dir.join(LABELS_FILE))
+            .map_err(|e| PetError::file_system(format!("Failed to read classifier labels: {e}")))?
+            .lines()
+            .map(str::trim)
+            .filter(|label| !label.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        log::info!(
+            "Loaded photo classifier {} with {} labels",
+            path.display(),
+            labels.len()
+        );
+        let model = Arc::new(Model { plan, labels });
+        *loaded = Some((path, Arc::clone(&model)));
+        Ok(Some(model))
+    }
+
+    impl Model {
+        /// One score per label for a photo
+        pub fn scores(&self, img: &DynamicImage) -> Result<Vec<f32>, PetError> {
+            let rgb = img
+                .resize_exact(INPUT_SIZE as u32, INPUT_SIZE as u32, FilterType::Triangle)
+                .to_rgb8();
+            let input: Tensor = tract_ndarray::Array4::from_shape_fn(
+                (1, 3, INPUT_SIZE, INPUT_SIZE),
+                |(_, channel, y, x)| {
+                    let value = rgb.get_pixel(x as u32, y as u32)[channel] as f32 / 255.0;
+                    (value - MEAN[channel]) / STD[channel]
+                },
+            )
+            .into();
+
+            let failed = |e: TractError| {
+                PetError::operation_failed(format!("Photo classification failed: {e}"))
+            };
+            let outputs = self.plan.run(tvec!(input.into())).map_err(failed)?;
+            let scores: Vec<f32> = outputs[0]
+                .to_array_view::<f32>()
+                .map_err(failed)?
+                .iter()
+                .copied()
+                .collect();
+            if scores.len() != self.labels.len() {
+                return Err(PetError::operation_failed(format!(
+                    "Photo classifier returned {} scores for {} labels",
+                    scores.len(),
+                    self.labels.len()
+                )));
+            }
+            Ok(scores)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_rank_suggestions_merges_labels_of_one_activity() {
+        let labels = labels(&["vet_clinic", "food_bowl", "vet_document", "cat_tree"]);
+        let suggestions = rank_suggestions(&labels, &[0.3, 0.35, 0.25, 0.1], 3);
+
+        let ranked: Vec<_> = suggestions
+            .iter()
+            .map(|s| (s.subcategory.as_str(), s.label.as_str()))
+            .collect();
+        // Clinic and document together outweigh the bowl; the unknown label is dropped
+        assert_eq!(
+            ranked,
+            vec![("Checkup", "vet_clinic"), ("Feeding", "food_bowl")]
+        );
+        assert!((suggestions[0].confidence - 0.55).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rank_suggestions_reads_logits() {
+        let labels = labels(&["outdoor_walk", "toy", "receipt"]);
+        let suggestions = rank_suggestions(&labels, &[4.0, 1.0, -2.0], 1);
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].category, ActivityCategory::Lifestyle);
+        assert_eq!(suggestions[0].subcategory, "Walk");
+        assert!(suggestions[0].confidence > 0.9);
+    }
+
+    #[test]
+    fn test_classify_without_model() {
+        let dir = tempfile::tempdir().unwrap();
+        let img = DynamicImage::new_rgb8(64, 64);
+        let classification = classify_photo(&img, dir.path()).unwrap();
+        assert!(!classification.model_used);
+        assert!(classification.suggestions.is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+use crate::database::{Activity, ActivityCategory};
+use serde::{Deserialize, Serialize};
+
+/// Settings key of the category privacy settings
+pub const PRIVACY_SETTINGS_KEY: &str = "privacy_settings";
+
+/// Activity categories kept on this machine. Hidden categories stay in the diary
+/// but are left out of everything written for someone else: anonymized datasets,
+/// webhook deliveries, photo albums, care handoffs and vaccination cards.
+/// Activities marked private are always left out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct PrivacySettings {
+    pub hidden_categories: Vec<ActivityCategory>,
+    /// Leave private activities out of search results as well
+    pub hide_private_from_search: bool,
+}
+
+impl PrivacySettings {
+    /// Whether activities of `category` may be exported or shared
+    pub fn is_shared(&self, category: ActivityCategory) -> bool {
+        !self.hidden_categories.contains(&category)
+    }
+
+    /// Whether `activity` may be exported or shared
+    pub fn shares(&self, activity: &Activity) -> bool {
+        !activity.is_private && self.is_shared(activity.category)
+    }
+
+    /// Whether private activities show up in search
+    pub fn searches_private(&self) -> bool {
+        !self.hide_private_from_search
+    }
+
+    /// The same settings with each hidden category listed once
+    pub fn normalized(mut self) -> Self {
+        let mut seen = Vec::with_capacity(self.hidden_categories.len());
+        self.hidden_categories.retain(|category| {
+            let first = !seen.contains(category);
+            seen.push(*category);
+            first
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hidden_categories_are_not_shared() {
+        let settings = PrivacySettings {
+            hidden_categories: vec![ActivityCategory::Expense, ActivityCategory::Expense],
+            ..Default::default()
+        }
+        .normalized();
+        assert_eq!(settings.hidden_categories, vec![ActivityCategory::Expense]);
+        assert!(!settings.is_shared(ActivityCategory::Expense));
+        assert!(settings.is_shared(ActivityCategory::Health));
+        assert!(PrivacySettings::default().is_shared(ActivityCategory::Expense));
+
+        let stored: PrivacySettings = serde_json::from_str("{}").unwrap();
+        assert_eq!(stored, PrivacySettings::default());
+        assert!(stored.searches_private());
+    }
+
+    #[test]
+    fn test_private_activities_are_not_shared() {
+        let mut activity = Activity {
+            id: 1,
+            pet_id: 1,
+            category: ActivityCategory::Lifestyle,
+            subcategory: "Incident".to_string(),
+            activity_data: None,
+            needs_review: false,
+            is_private: false,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let settings = PrivacySettings::default();
+        assert!(settings.shares(&activity));
+        activity.is_private = true;
+        assert!(!settings.shares(&activity));
+    }
+}
@@ -4,6 +4,7 @@ use tauri::{
 };
 
 use crate::commands::AppState;
+use crate::photo::{self, RenditionRequest};
 
 /// Handle requests to the custom photos:// protocol
 ///
@@ -14,6 +15,11 @@ use crate::commands::AppState;
 /// Extract filename from URL and get the photo from the photo service
 /// (photos://localhost/filename.jpg -> filename.jpg)
 ///
+/// `w`, `h` and `fit` (`contain` or `cover`) query parameters serve a resized
+/// rendition instead (photos://localhost/filename.jpg?w=256&h=256&fit=cover)
+///
+/// `size=full` serves the larger copy kept for viewing (photos://localhost/filename.jpg?size=full)
+///
 pub async fn handle_photos_protocol_request(
     app: &AppHandle,
     request: Request<Vec<u8>>,
@@ -46,11 +52,30 @@ pub async fn handle_photos_protocol_request(
     // Get the app state
     let app_state: State<AppState> = app.state();
 
-    // Get photo path from photo service
-    let photo_path = app_state
-        .photo_service
-        .get_photo_path(filename)
-        .map_err(|e| format!("Failed to get photo path: {e}"))?;
+    // A size in the query (`?w=256&h=256&fit=cover`) asks for a resized rendition,
+    // generated on the photo workers the first time and cached afterwards
+    let query = uri.query().unwrap_or_default();
+    let rendition = RenditionRequest::from_query(query)?;
+
+    // Get photo path from photo service, following the old names of renamed photos
+    let photo_path = match rendition {
+        Some(rendition) => {
+            let filename = filename.to_string();
+            app_state
+                .photo_service
+                .run_on_workers(move |service| service.get_rendition(&filename, &rendition))
+                .await
+                .map_err(|e| format!("Failed to get photo rendition: {e}"))?
+        }
+        None if photo::wants_full_size(query) => app_state
+            .photo_service
+            .get_full_size_path(filename)
+            .map_err(|e| format!("Failed to get full-size photo path: {e}"))?,
+        None => app_state
+            .photo_service
+            .resolve_photo_path(filename)
+            .map_err(|e| format!("Failed to get photo path: {e}"))?,
+    };
 
     log::info!(
         "handle_photos_protocol_request: photo_path: {}",
@@ -68,3 +93,37 @@ pub async fn handle_photos_protocol_request(
         .body(bytes)?;
     Ok(resp)
 }
+
+/// Handle requests to the custom documents:// protocol for previewing vault documents
+///
+/// URL format mirrors photos://, e.g. documents://localhost/<sha256>.pdf
+pub async fn handle_documents_protocol_request(
+    app: &AppHandle,
+    request: Request<Vec<u8>>,
+) -> Result<Response<Vec<u8>>, Box<dyn std::error::Error + Send + Sync>> {
+    let path = request.uri().path();
+    let filename = path.strip_prefix("/").unwrap_or(path);
+
+    log::info!("handle_documents_protocol_request: filename: {filename}");
+
+    let app_state: State<AppState> = app.state();
+
+    // DocumentService validates the filename against path traversal
+    let document_path = app_state
+        .document_service
+        .get_document_path(filename)
+        .map_err(|e| format!("Failed to get document path: {e}"))?;
+
+    let bytes = std::fs::read(&document_path).map_err(|e| format!("read document failed: {e}"))?;
+
+    let mime = mime_guess::from_path(&document_path).first_or_octet_stream();
+
+    // Stored files are content-addressed, so they never change under the same name
+    let resp = Response::builder()
+        .status(200)
+        .header("Content-Type", mime.as_ref())
+        .header("Content-Disposition", "inline")
+        .header("Cache-Control", "public, max-age=31536000, immutable")
+        .body(bytes)?;
+    Ok(resp)
+}
@@ -0,0 +1,830 @@
+use crate::database::activity_blocks::{portion_type_for, ActivityBlocksBuilder};
+use crate::database::{ActivityCategory, ActivityCreateRequest};
+use chrono::{DateTime, Days, FixedOffset, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Longest quick entry text that will be parsed
+pub const MAX_QUICK_ENTRY_LENGTH: usize = 500;
+
+/// Kind of information recognized in a quick entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum QuickEntryMatchKind {
+    Activity,
+    Quantity,
+    Cost,
+    Time,
+    Brand,
+    /// A pet's name or alias
+    Pet,
+}
+
+/// A piece of the text the parser understood
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuickEntryMatch {
+    pub kind: QuickEntryMatchKind,
+    pub text: String,
+}
+
+/// Activity draft parsed from free text, to be confirmed by the user before saving
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuickEntryDraft {
+    pub request: ActivityCreateRequest,
+    /// How sure the parser is about the draft, 0.0..=1.0
+    pub confidence: f32,
+    pub matches: Vec<QuickEntryMatch>,
+    /// Words that were not understood (kept in the notes block)
+    pub unrecognized: Vec<String>,
+}
+
+/// Names a pet can be called by in quick entries
+#[derive(Debug, Clone, PartialEq)]
+pub struct PetNames {
+    pub pet_id: i64,
+    /// Name and aliases, in any script
+    pub names: Vec<String>,
+}
+
+/// Words that start an activity, with the category and subcategory they imply
+const ACTIVITY_KEYWORDS: &[(&str, ActivityCategory, &str)] = &[
+    ("fed", ActivityCategory::Diet, "Feeding"),
+    ("feed", ActivityCategory::Diet, "Feeding"),
+    ("ate", ActivityCategory::Diet, "Feeding"),
+    ("breakfast", ActivityCategory::Diet, "Feeding"),
+    ("lunch", ActivityCategory::Diet, "Feeding"),
+    ("dinner", ActivityCategory::Diet, "Feeding"),
+    ("meal", ActivityCategory::Diet, "Feeding"),
+    ("treat", ActivityCategory::Diet, "Treat"),
+    ("treats", ActivityCategory::Diet, "Treat"),
+    ("drank", ActivityCategory::Diet, "Water"),
+    ("water", ActivityCategory::Diet, "Water"),
+    ("weighed", ActivityCategory::Growth, "Weight"),
+    ("weighs", ActivityCategory::Growth, "Weight"),
+    ("weight", ActivityCategory::Growth, "Weight"),
+    ("walk", ActivityCategory::Lifestyle, "Walk"),
+    ("walked", ActivityCategory::Lifestyle, "Walk"),
+    ("played", ActivityCategory::Lifestyle, "Play"),
+    ("play", ActivityCategory::Lifestyle, "Play"),
+    ("trained", ActivityCategory::Lifestyle, "Training"),
+    ("training", ActivityCategory::Lifestyle, "Training"),
+    ("slept", ActivityCategory::Lifestyle, "Sleep"),
+    ("nap", ActivityCategory::Lifestyle, "Sleep"),
+    ("vet", ActivityCategory::Health, "Checkup"),
+    ("checkup", ActivityCategory::Health, "Checkup"),
+    ("vaccinated", ActivityCategory::Health, "Checkup"),
+    ("vaccine", ActivityCategory::Health, "Checkup"),
+    ("pill", ActivityCategory::Health, "Medication"),
+    ("medication", ActivityCategory::Health, "Medication"),
+    ("meds", ActivityCategory::Health, "Medication"),
+    ("vomited", ActivityCategory::Health, "Symptom"),
+    ("diarrhea", ActivityCategory::Health, "Symptom"),
+    ("limping", ActivityCategory::Health, "Symptom"),
+    ("sneezing", ActivityCategory::Health, "Symptom"),
+    ("litter", ActivityCategory::Health, "Litter"),
+    ("pooped", ActivityCategory::Health, "Litter"),
+    ("peed", ActivityCategory::Health, "Litter"),
+    ("paid", ActivityCategory::Expense, "Purchase"),
+    ("bought", ActivityCategory::Expense, "Purchase"),
+    ("spent", ActivityCategory::Expense, "Purchase"),
+    ("groomed", ActivityCategory::Expense, "Grooming"),
+    ("insurance", ActivityCategory::Expense, "Insurance"),
+    ("喂", ActivityCategory::Diet, "Feeding"),
+    ("喂了", ActivityCategory::Diet, "Feeding"),
+    ("吃了", ActivityCategory::Diet, "Feeding"),
+    ("零食", ActivityCategory::Diet, "Treat"),
+    ("喝水", ActivityCategory::Diet, "Water"),
+    ("喝了", ActivityCategory::Diet, "Water"),
+    ("称重", ActivityCategory::Growth, "Weight"),
+    ("体重", ActivityCategory::Growth, "Weight"),
+    ("遛", ActivityCategory::Lifestyle, "Walk"),
+    ("遛了", ActivityCategory::Lifestyle, "Walk"),
+    ("遛狗", ActivityCategory::Lifestyle, "Walk"),
+    ("散步", ActivityCategory::Lifestyle, "Walk"),
+    ("玩了", ActivityCategory::Lifestyle, "Play"),
+    ("看病", ActivityCategory::Health, "Checkup"),
+    ("体检", ActivityCategory::Health, "Checkup"),
+    ("打疫苗", ActivityCategory::Health, "Checkup"),
+    ("吃药", ActivityCategory::Health, "Medication"),
+    ("喂药", ActivityCategory::Health, "Medication"),
+    ("呕吐", ActivityCategory::Health, "Symptom"),
+    ("猫砂", ActivityCategory::Health, "Litter"),
+    ("买了", ActivityCategory::Expense, "Purchase"),
+    ("花了", ActivityCategory::Expense, "Purchase"),
+];
+
+/// Pet food brands recognized in diet entries, lowercase words and display name
+const KNOWN_BRANDS: &[(&[&str], &str)] = &[
+    (&["royal", "canin"], "Royal Canin"),
+    (&["pro", "plan"], "Pro Plan"),
+    (&["purina"], "Purina"),
+    (&["hill's"], "Hill's"),
+    (&["hills"], "Hill's"),
+    (&["science", "diet"], "Science Diet"),
+    (&["orijen"], "Orijen"),
+    (&["acana"], "Acana"),
+    (&["blue", "buffalo"], "Blue Buffalo"),
+    (&["iams"], "Iams"),
+    (&["eukanuba"], "Eukanuba"),
+    (&["taste", "of", "the", "wild"], "Taste of the Wild"),
+    (&["wellness"], "Wellness"),
+    (&["whiskas"], "Whiskas"),
+    (&["fancy", "feast"], "Fancy Feast"),
+    (&["friskies"], "Friskies"),
+    (&["pedigree"], "Pedigree"),
+    (&["ziwi", "peak"], "Ziwi Peak"),
+    (&["farmina"], "Farmina"),
+];
+
+/// Filler words that carry no information on their own
+const FILLER_WORDS: &[&str] = &[
+    "a", "an", "the", "at", "of", "for", "with", "to", "on", "in", "and", "her", "him", "his",
+    "some", "about", "around", "给", "了",
+];
+
+/// Word for the day before, in any supported language
+const YESTERDAY_WORDS: &[&str] = &["yesterday", "昨天"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum UnitKind {
+    Mass,
+    Volume,
+    Duration,
+    Distance,
+}
+
+/// Recognized unit spellings, normalized unit and kind
+const UNITS: &[(&str, &str, UnitKind)] = &[
+    ("g", "g", UnitKind::Mass),
+    ("gram", "g", UnitKind::Mass),
+    ("grams", "g", UnitKind::Mass),
+    ("kg", "kg", UnitKind::Mass),
+    ("kgs", "kg", UnitKind::Mass),
+    ("kilo", "kg", UnitKind::Mass),
+    ("kilos", "kg", UnitKind::Mass),
+    ("lb", "lb", UnitKind::Mass),
+    ("lbs", "lb", UnitKind::Mass),
+    ("pound", "lb", UnitKind::Mass),
+    ("pounds", "lb", UnitKind::Mass),
+    ("oz", "oz", UnitKind::Mass),
+    ("cup", "cup", UnitKind::Volume),
+    ("cups", "cup", UnitKind::Volume),
+    ("ml", "ml", UnitKind::Volume),
+    ("l", "l", UnitKind::Volume),
+    ("min", "min", UnitKind::Duration),
+    ("mins", "min", UnitKind::Duration),
+    ("minute", "min", UnitKind::Duration),
+    ("minutes", "min", UnitKind::Duration),
+    ("h", "h", UnitKind::Duration),
+    ("hr", "h", UnitKind::Duration),
+    ("hrs", "h", UnitKind::Duration),
+    ("hour", "h", UnitKind::Duration),
+    ("hours", "h", UnitKind::Duration),
+    ("km", "km", UnitKind::Distance),
+    ("mi", "mi", UnitKind::Distance),
+    ("mile", "mi", UnitKind::Distance),
+    ("miles", "mi", UnitKind::Distance),
+    ("克", "g", UnitKind::Mass),
+    ("公斤", "kg", UnitKind::Mass),
+    ("千克", "kg", UnitKind::Mass),
+    ("毫升", "ml", UnitKind::Volume),
+    ("分钟", "min", UnitKind::Duration),
+    ("小时", "h", UnitKind::Duration),
+    ("公里", "km", UnitKind::Distance),
+];
+
+/// Currency words and symbols with their ISO code
+const CURRENCIES: &[(&str, &str)] = &[
+    ("$", "USD"),
+    ("usd", "USD"),
+    ("dollar", "USD"),
+    ("dollars", "USD"),
+    ("€", "EUR"),
+    ("eur", "EUR"),
+    ("euro", "EUR"),
+    ("euros", "EUR"),
+    ("£", "GBP"),
+    ("gbp", "GBP"),
+    ("¥", "CNY"),
+    ("元", "CNY"),
+    ("块", "CNY"),
+    ("rmb", "CNY"),
+    ("cny", "CNY"),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Quantity {
+    amount: f64,
+    unit: &'static str,
+    kind: UnitKind,
+}
+
+/// Parse a short phrase like "fed 80g royal canin at 8am" or "喂了豆豆 50g" into an
+/// activity draft.
+///
+/// The draft is for `pet_id` unless the text names another pet in `pets` by name or
+/// alias. `now` is the current local time; times of day and "yesterday" are resolved
+/// against it. Returns None when nothing in the text describes an activity.
+pub fn parse_quick_entry(
+    text: &str,
+    pet_id: i64,
+    pets: &[PetNames],
+    now: DateTime<FixedOffset>,
+) -> Option<QuickEntryDraft> {
+    let mut matches = Vec::new();
+    let (mentioned_pet, remaining) = find_pet_mention(text, pets);
+    let pet_id = match mentioned_pet {
+        Some((mentioned_id, name)) => {
+            matches.push(QuickEntryMatch {
+                kind: QuickEntryMatchKind::Pet,
+                text: name,
+            });
+            mentioned_id
+        }
+        None => pet_id,
+    };
+
+    let words: Vec<String> = remaining
+        .split_whitespace()
+        .flat_map(split_cjk)
+        .map(|word| {
+            word.trim_matches(|c: char| {
+                matches!(
+                    c,
+                    ',' | '.' | '!' | '?' | ';' | '，' | '。' | '！' | '？' | '；' | '、'
+                )
+            })
+            .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut used = vec![false; words.len()];
+
+    let mut activity: Option<(ActivityCategory, &str)> = None;
+    let mut quantities: Vec<Quantity> = Vec::new();
+    let mut cost: Option<(f64, &str)> = None;
+    let mut time_of_day: Option<NaiveTime> = None;
+    let mut days_back = 0;
+    let mut brand: Option<&str> = None;
+
+    let mut i = 0;
+    while i < words.len() {
+        let word = words[i].as_str();
+        let next = words.get(i + 1).map(String::as_str);
+
+        if let Some((names, display)) = KNOWN_BRANDS
+            .iter()
+            .find(|(names, _)| starts_with_words(&words[i..], names))
+        {
+            brand.get_or_insert(*display);
+            push_match(
+                &mut matches,
+                QuickEntryMatchKind::Brand,
+                &words[i..i + names.len()],
+            );
+            used[i..i + names.len()].fill(true);
+            i += names.len();
+            continue;
+        }
+
+        if let Some((amount, currency, consumed)) = parse_cost(word, next) {
+            cost.get_or_insert((amount, currency));
+            push_match(
+                &mut matches,
+                QuickEntryMatchKind::Cost,
+                &words[i..i + consumed],
+            );
+            used[i..i + consumed].fill(true);
+            i += consumed;
+            continue;
+        }
+
+        if let Some((quantity, consumed)) = parse_quantity(word, next) {
+            quantities.push(quantity);
+            push_match(
+                &mut matches,
+                QuickEntryMatchKind::Quantity,
+                &words[i..i + consumed],
+            );
+            used[i..i + consumed].fill(true);
+            i += consumed;
+            continue;
+        }
+
+        // Bare hours ("at 8") only count after "at"
+        let after_at = i > 0 && words[i - 1] == "at";
+        if let Some((time, consumed)) = parse_time(word, next, after_at) {
+            time_of_day.get_or_insert(time);
+            let start = if after_at { i - 1 } else { i };
+            push_match(
+                &mut matches,
+                QuickEntryMatchKind::Time,
+                &words[start..i + consumed],
+            );
+            used[start..i + consumed].fill(true);
+            i += consumed;
+            continue;
+        }
+
+        if YESTERDAY_WORDS.contains(&word) {
+            days_back = 1;
+            push_match(&mut matches, QuickEntryMatchKind::Time, &words[i..=i]);
+            used[i] = true;
+        } else if let Some((_, category, subcategory)) = ACTIVITY_KEYWORDS
+            .iter()
+            .find(|(keyword, ..)| *keyword == word)
+        {
+            // The first activity word wins; later ones are details ("paid $80 at the vet")
+            if activity.is_none() {
+                activity = Some((*category, *subcategory));
+            }
+            push_match(&mut matches, QuickEntryMatchKind::Activity, &words[i..=i]);
+            used[i] = true;
+        } else if FILLER_WORDS.contains(&word) {
+            used[i] = true;
+        }
+        i += 1;
+    }
+
+    // Without an activity word, guess from what was measured
+    let inferred = activity.is_none();
+    let (category, subcategory) = activity.or_else(|| {
+        if cost.is_some() {
+            Some((ActivityCategory::Expense, "Purchase"))
+        } else if brand.is_some() || quantities.iter().any(|q| q.kind == UnitKind::Volume) {
+            Some((ActivityCategory::Diet, "Feeding"))
+        } else if quantities
+            .iter()
+            .any(|q| matches!(q.kind, UnitKind::Duration | UnitKind::Distance))
+        {
+            Some((ActivityCategory::Lifestyle, "Walk"))
+        } else {
+            None
+        }
+    })?;
+
+    let mut blocks = ActivityBlocksBuilder::new();
+    blocks
+        .time(resolve_time(now, time_of_day, days_back))
+        .notes(text);
+
+    let mut detail_found = false;
+    let first_of = |kinds: &[UnitKind]| quantities.iter().find(|q| kinds.contains(&q.kind));
+    match category {
+        ActivityCategory::Diet => {
+            let portion = first_of(&[UnitKind::Mass, UnitKind::Volume]);
+            if portion.is_some() || brand.is_some() {
+                detail_found = blocks
+                    .portion(
+                        portion.map(|q| q.amount).unwrap_or(1.0),
+                        portion.map(|q| q.unit).unwrap_or("serving"),
+                        portion_type_for(subcategory),
+                        brand,
+                    )
+                    .is_ok();
+            }
+        }
+        ActivityCategory::Growth => {
+            if let Some(weight) = first_of(&[UnitKind::Mass]) {
+                detail_found = blocks.weight(weight.amount, weight.unit).is_ok();
+            }
+        }
+        ActivityCategory::Lifestyle => {
+            if let Some(duration) = first_of(&[UnitKind::Duration]) {
+                let minutes = match duration.unit {
+                    "h" => duration.amount * 60.0,
+                    _ => duration.amount,
+                };
+                detail_found = blocks.duration_minutes(minutes).is_ok();
+            }
+            if let Some(distance) = first_of(&[UnitKind::Distance]) {
+                detail_found |= blocks
+                    .distance(distance.amount, distance.unit, &subcategory.to_lowercase())
+                    .is_ok();
+            }
+        }
+        ActivityCategory::Health | ActivityCategory::Expense => {}
+    }
+    if let Some((amount, currency)) = cost {
+        if blocks.cost(amount, currency).is_ok() {
+            detail_found |= category == ActivityCategory::Expense;
+        }
+    }
+
+    let unrecognized: Vec<String> = words
+        .iter()
+        .zip(&used)
+        .filter(|(_, used)| !**used)
+        .map(|(word, _)| word.clone())
+        .collect();
+
+    let mut confidence = if inferred { 0.3 } else { 0.5 };
+    if detail_found {
+        confidence += 0.2;
+    }
+    if time_of_day.is_some() || days_back > 0 {
+        confidence += 0.1;
+    }
+    if !words.is_empty() {
+        confidence += 0.2 * (words.len() - unrecognized.len()) as f32 / words.len() as f32;
+    }
+
+    Some(QuickEntryDraft {
+        request: ActivityCreateRequest {
+            pet_id,
+            category,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(blocks.into_json()),
+            needs_review: true,
+        },
+        confidence: (confidence * 100.0).round() / 100.0,
+        matches,
+        unrecognized,
+    })
+}
+
+/// Find the first pet named in the text by name or alias, longest names first so
+/// "Little Max" wins over "Max". Names shared by several pets are ignored.
+/// Returns the pet with the name as written and the text with the name blanked out.
+fn find_pet_mention(text: &str, pets: &[PetNames]) -> (Option<(i64, String)>, String) {
+    let mut candidates: Vec<(String, i64)> = pets
+        .iter()
+        .flat_map(|pet| {
+            pet.names
+                .iter()
+                .map(|name| lowercase_chars(name.trim()).collect::<String>())
+                .filter(|name| !name.is_empty())
+                .map(move |name| (name, pet.pet_id))
+        })
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    let ambiguous: Vec<String> = candidates
+        .windows(2)
+        .filter(|pair| pair[0].0 == pair[1].0)
+        .map(|pair| pair[0].0.clone())
+        .collect();
+    candidates.retain(|(name, _)| !ambiguous.contains(name));
+    candidates.sort_by(|a, b| b.0.chars().count().cmp(&a.0.chars().count()));
+
+    // Lowercasing can change byte lengths, so match on characters
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = lowercase_chars(text).collect();
+    let is_word_char = |i: usize| {
+        lower
+            .get(i)
+            .is_some_and(|c| c.is_alphabetic() && !is_cjk(*c))
+    };
+
+    let mut found: Option<(usize, usize, i64)> = None;
+    for (name, pet_id) in &candidates {
+        let name: Vec<char> = name.chars().collect();
+        let start = (0..lower.len().saturating_sub(name.len() - 1)).find(|&start| {
+            lower[start..start + name.len()] == name[..]
+                // Latin names must stand alone: "max" isn't in "maximum"
+                && !(is_word_char(start) && start > 0 && is_word_char(start - 1))
+                && !(is_word_char(start + name.len() - 1) && is_word_char(start + name.len()))
+        });
+        if let Some(start) = start {
+            if found.is_none_or(|(first, ..)| start < first) {
+                found = Some((start, name.len(), *pet_id));
+            }
+        }
+    }
+
+    match found {
+        Some((start, len, pet_id)) => {
+            let name: String = chars[start..start + len].iter().collect();
+            let remaining: String = chars[..start]
+                .iter()
+                .chain(std::iter::once(&' '))
+                .chain(&chars[start + len..])
+                .collect();
+            (Some((pet_id, name)), remaining)
+        }
+        None => (None, text.to_string()),
+    }
+}
+
+/// Lowercase one character for one character, so positions stay aligned with the original
+fn lowercase_chars(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.chars().map(|c| c.to_lowercase().next().unwrap_or(c))
+}
+
+/// Chinese, Japanese and Korean characters, which are written without spaces
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7AF}'
+        | '\u{F900}'..='\u{FAFF}')
+}
+
+/// Split a whitespace-separated word where CJK text meets other text ("喂了50g")
+fn split_cjk(word: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut rest = word;
+    while let Some(first) = rest.chars().next() {
+        let end = if is_cjk(first) {
+            rest.find(|c: char| !is_cjk(c))
+        } else {
+            rest.find(is_cjk)
+        }
+        .unwrap_or(rest.len());
+        if is_cjk(first) {
+            parts.extend(segment_cjk(&rest[..end]));
+        } else {
+            parts.push(rest[..end].to_string());
+        }
+        rest = &rest[end..];
+    }
+    parts
+}
+
+/// Split a run of CJK text into known keywords, units and filler words, longest
+/// first; characters in between are kept together as unknown words
+fn segment_cjk(run: &str) -> Vec<String> {
+    let vocabulary = ACTIVITY_KEYWORDS
+        .iter()
+        .map(|(keyword, ..)| *keyword)
+        .chain(UNITS.iter().map(|(spelling, ..)| *spelling))
+        .chain(CURRENCIES.iter().map(|(spelling, _)| *spelling))
+        .chain(FILLER_WORDS.iter().copied())
+        .chain(YESTERDAY_WORDS.iter().copied())
+        .filter(|entry| entry.chars().any(is_cjk));
+
+    let mut parts = Vec::new();
+    let mut unknown = String::new();
+    let mut rest = run;
+    while let Some(c) = rest.chars().next() {
+        match vocabulary
+            .clone()
+            .filter(|entry| rest.starts_with(entry))
+            .max_by_key(|entry| entry.len())
+        {
+            Some(entry) => {
+                if !unknown.is_empty() {
+                    parts.push(std::mem::take(&mut unknown));
+                }
+                parts.push(entry.to_string());
+                rest = &rest[entry.len()..];
+            }
+            None => {
+                unknown.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !unknown.is_empty() {
+        parts.push(unknown);
+    }
+    parts
+}
+
+/// Whether `words` begins with the phrase `names`
+fn starts_with_words(words: &[String], names: &[&str]) -> bool {
+    words.len() >= names.len() && words.iter().zip(names).all(|(word, name)| word == name)
+}
+
+fn push_match(matches: &mut Vec<QuickEntryMatch>, kind: QuickEntryMatchKind, words: &[String]) {
+    matches.push(QuickEntryMatch {
+        kind,
+        text: words.join(" "),
+    });
+}
+
+/// Split a word into its leading number and the rest ("80g" -> (80.0, "g"))
+fn split_number(word: &str) -> Option<(f64, &str)> {
+    let end = word
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(word.len());
+    let amount = word[..end].parse::<f64>().ok()?;
+    Some((amount, &word[end..]))
+}
+
+fn lookup_unit(unit: &str) -> Option<(&'static str, UnitKind)> {
+    UNITS
+        .iter()
+        .find(|(spelling, ..)| *spelling == unit)
+        .map(|(_, normalized, kind)| (*normalized, *kind))
+}
+
+pub(crate) fn lookup_currency(word: &str) -> Option<&'static str> {
+    CURRENCIES
+        .iter()
+        .find(|(spelling, _)| *spelling == word)
+        .map(|(_, code)| *code)
+}
+
+/// "80g", "80 g", "1.5 cups"; returns the quantity and the number of words used
+fn parse_quantity(word: &str, next: Option<&str>) -> Option<(Quantity, usize)> {
+    let (amount, suffix) = split_number(word)?;
+    let (unit, consumed) = if suffix.is_empty() {
+        (lookup_unit(next?)?, 2)
+    } else {
+        (lookup_unit(suffix)?, 1)
+    };
+    Some((
+        Quantity {
+            amount,
+            unit: unit.0,
+            kind: unit.1,
+        },
+        consumed,
+    ))
+}
+
+/// "$25", "25$", "25 usd", "€12.50"; returns amount, currency and words used
+fn parse_cost(word: &str, next: Option<&str>) -> Option<(f64, &'static str, usize)> {
+    for symbol in ["$", "€", "£", "¥"] {
+        if let Some(rest) = word.strip_prefix(symbol) {
+            let (amount, tail) = split_number(rest)?;
+            return tail
+                .is_empty()
+                .then(|| (amount, lookup_currency(symbol).unwrap_or("USD"), 1));
+        }
+    }
+
+    let (amount, suffix) = split_number(word)?;
+    if suffix.is_empty() {
+        Some((amount, lookup_currency(next?)?, 2))
+    } else {
+        Some((amount, lookup_currency(suffix)?, 1))
+    }
+}
+
+/// "8am", "8 pm", "8:30pm", "20:15", "noon", or a bare hour after "at"
+fn parse_time(word: &str, next: Option<&str>, after_at: bool) -> Option<(NaiveTime, usize)> {
+    match word {
+        "noon" => return Some((NaiveTime::from_hms_opt(12, 0, 0)?, 1)),
+        "midnight" => return Some((NaiveTime::MIN, 1)),
+        _ => {}
+    }
+
+    let (clock, suffix, consumed) = if let Some(clock) = word
+        .strip_suffix("am")
+        .map(|c| (c, "am"))
+        .or_else(|| word.strip_suffix("pm").map(|c| (c, "pm")))
+    {
+        (clock.0, clock.1, 1)
+    } else if matches!(next, Some("am" | "pm")) {
+        (word, next.unwrap_or_default(), 2)
+    } else {
+        (word, "", 1)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None if !suffix.is_empty() || after_at => (clock.parse::<u32>().ok()?, 0),
+        None => return None,
+    };
+    let hour = match (suffix, hour) {
+        ("am", 12) => 0,
+        ("pm", h) if h < 12 => h + 12,
+        ("am" | "pm", h) if h > 12 => return None,
+        (_, h) => h,
+    };
+    Some((NaiveTime::from_hms_opt(hour, minute, 0)?, consumed))
+}
+
+/// ISO timestamp of the activity, like the frontend's `Date.toISOString()`
+fn resolve_time(
+    now: DateTime<FixedOffset>,
+    time_of_day: Option<NaiveTime>,
+    days_back: u64,
+) -> DateTime<Utc> {
+    let local = now.naive_local();
+    let date = local
+        .date()
+        .checked_sub_days(Days::new(days_back))
+        .unwrap_or(local.date());
+    let time = time_of_day.unwrap_or(local.time());
+
+    date.and_time(time)
+        .and_local_timezone(*now.offset())
+        .single()
+        .unwrap_or(now)
+        .with_timezone(&Utc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339("2025-06-10T14:30:00+02:00").unwrap()
+    }
+
+    fn block(draft: &QuickEntryDraft, key: &str) -> serde_json::Value {
+        draft.request.activity_data.as_ref().unwrap()[key].clone()
+    }
+
+    #[test]
+    fn test_parse_feeding_with_brand_and_time() {
+        let draft = parse_quick_entry("Fed 80g Royal Canin at 8am", 3, &[], now()).unwrap();
+
+        assert_eq!(draft.request.pet_id, 3);
+        assert_eq!(draft.request.category, ActivityCategory::Diet);
+        assert_eq!(draft.request.subcategory, "Feeding");
+        assert_eq!(
+            block(&draft, "portion"),
+            serde_json::json!({
+                "amount": 80.0,
+                "unit": "g",
+                "portionType": "meal",
+                "brand": "Royal Canin",
+                "product": null,
+            })
+        );
+        assert_eq!(block(&draft, "time")["date"], "2025-06-10T06:00:00.000Z");
+        assert_eq!(block(&draft, "notes"), "Fed 80g Royal Canin at 8am");
+        assert!(draft.unrecognized.is_empty());
+        assert_eq!(draft.confidence, 1.0);
+    }
+
+    #[test]
+    fn test_parse_weight_walk_and_cost() {
+        let weight = parse_quick_entry("weighed 4.2 kg yesterday", 1, &[], now()).unwrap();
+        assert_eq!(weight.request.subcategory, "Weight");
+        assert_eq!(block(&weight, "weight")["value"], "4.2");
+        assert_eq!(block(&weight, "time")["date"], "2025-06-09T12:30:00.000Z");
+
+        let walk = parse_quick_entry("walked 1.5 hours in the park", 1, &[], now()).unwrap();
+        assert_eq!(walk.request.category, ActivityCategory::Lifestyle);
+        assert_eq!(block(&walk, "timer")["duration"], 90.0);
+        assert_eq!(walk.unrecognized, vec!["park"]);
+
+        let long_walk = parse_quick_entry("walked 3 km in 40 minutes", 1, &[], now()).unwrap();
+        assert_eq!(block(&long_walk, "timer")["duration"], 40.0);
+        assert_eq!(
+            block(&long_walk, "distance"),
+            serde_json::json!({ "amount": 3.0, "unit": "km", "distanceType": "walk" })
+        );
+
+        let vet_bill = parse_quick_entry("paid $85.50 at the vet", 1, &[], now()).unwrap();
+        assert_eq!(vet_bill.request.category, ActivityCategory::Expense);
+        assert_eq!(
+            block(&vet_bill, "cost"),
+            serde_json::json!({ "amount": 85.5, "currency": "USD" })
+        );
+    }
+
+    #[test]
+    fn test_inferred_and_unknown_entries() {
+        let inferred = parse_quick_entry("2 cups kibble", 1, &[], now()).unwrap();
+        assert_eq!(inferred.request.category, ActivityCategory::Diet);
+        assert!(inferred.confidence < 0.8);
+        assert_eq!(inferred.unrecognized, vec!["kibble"]);
+
+        assert!(parse_quick_entry("hello there", 1, &[], now()).is_none());
+        assert!(parse_quick_entry("", 1, &[], now()).is_none());
+    }
+
+    #[test]
+    fn test_pet_named_by_alias() {
+        let pets = vec![
+            PetNames {
+                pet_id: 7,
+                names: vec!["Doudou".to_string(), "豆豆".to_string()],
+            },
+            PetNames {
+                pet_id: 8,
+                names: vec!["Max".to_string(), "宝宝".to_string()],
+            },
+            PetNames {
+                pet_id: 9,
+                names: vec!["Mimi".to_string(), "宝宝".to_string()],
+            },
+        ];
+
+        let draft = parse_quick_entry("喂了豆豆 50g", 1, &pets, now()).unwrap();
+        assert_eq!(draft.request.pet_id, 7);
+        assert_eq!(draft.request.subcategory, "Feeding");
+        assert_eq!(block(&draft, "portion")["amount"], 50.0);
+        assert_eq!(draft.matches[0].kind, QuickEntryMatchKind::Pet);
+        assert_eq!(draft.matches[0].text, "豆豆");
+        assert!(draft.unrecognized.is_empty());
+
+        let walk = parse_quick_entry("昨天遛Max30分钟", 1, &pets, now()).unwrap();
+        assert_eq!(walk.request.pet_id, 8);
+        assert_eq!(block(&walk, "timer")["duration"], 30.0);
+        assert_eq!(block(&walk, "time")["date"], "2025-06-09T12:30:00.000Z");
+
+        // Names inside other words and names shared by two pets don't pick a pet
+        let maximum = parse_quick_entry("fed the maximum 80g", 1, &pets, now()).unwrap();
+        assert_eq!(maximum.request.pet_id, 1);
+        let shared = parse_quick_entry("喂宝宝 30克", 1, &pets, now()).unwrap();
+        assert_eq!(shared.request.pet_id, 1);
+        assert_eq!(block(&shared, "portion")["unit"], "g");
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert_eq!(parse_time("8:30pm", None, false), Some((time(20, 30), 1)));
+        assert_eq!(parse_time("12", Some("am"), false), Some((time(0, 0), 2)));
+        assert_eq!(parse_time("20:15", None, false), Some((time(20, 15), 1)));
+        assert_eq!(parse_time("7", None, true), Some((time(7, 0), 1)));
+        assert_eq!(parse_time("7", None, false), None);
+        assert_eq!(parse_time("13pm", None, false), None);
+    }
+}
@@ -0,0 +1,622 @@
+//! Total and date suggestions for expense receipts.
+//!
+//! Text recognition runs in the frontend, as it does for vault documents; this reads
+//! the recognized text line by line. Receipts list many numbers, so every amount and
+//! date found is returned as a candidate with a confidence, and the user confirms
+//! which one to keep before the expense is saved.
+
+use crate::database::activity_blocks::ActivityBlocksBuilder;
+use crate::quick_entry::lookup_currency;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// Longest recognized receipt text that will be read
+pub const MAX_RECEIPT_TEXT_LENGTH: usize = 20_000;
+
+/// Most candidates returned of each kind
+const MAX_CANDIDATES: usize = 3;
+
+/// Confidence of an amount on a line without any label
+const UNLABELED_AMOUNT: f32 = 0.2;
+
+/// Lines naming the amount paid
+const TOTAL_LABELS: &[&str] = &[
+    "total",
+    "grand total",
+    "total due",
+    "amount due",
+    "balance due",
+    "合计",
+    "总计",
+    "实付",
+    "应付",
+];
+
+/// Lines naming an amount that may be the total
+const AMOUNT_LABELS: &[&str] = &["amount", "金额"];
+
+/// Lines with a part of the total
+const SUBTOTAL_LABELS: &[&str] = &["subtotal", "sub total", "小计"];
+
+/// Lines with an amount that isn't the total
+const OTHER_AMOUNT_LABELS: &[&str] = &[
+    "tax", "vat", "gst", "tip", "change", "cash", "tendered", "discount", "savings", "税", "找零",
+    "现金", "优惠", "折扣",
+];
+
+/// Words saying a total includes its tax, e.g. "Total incl. VAT"
+const INCLUSIVE_LABELS: &[&str] = &["incl", "including", "含税"];
+
+/// Lines naming the purchase date
+const DATE_LABELS: &[&str] = &["date", "dated", "日期"];
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// An amount found on the receipt
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReceiptAmount {
+    pub amount: f64,
+    /// ISO code, written on the line or the one most used on the receipt
+    pub currency: Option<String>,
+    /// How likely this is the total paid, 0.0..=1.0
+    pub confidence: f32,
+    /// Receipt line the amount was read from
+    pub line: String,
+}
+
+/// A date found on the receipt
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReceiptDate {
+    pub date: NaiveDate,
+    /// How likely this is the purchase date, 0.0..=1.0
+    pub confidence: f32,
+    /// Receipt line the date was read from
+    pub line: String,
+}
+
+/// Candidates read from a receipt, most likely first
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReceiptExtraction {
+    pub amounts: Vec<ReceiptAmount>,
+    pub dates: Vec<ReceiptDate>,
+    /// Cost and time blocks from the best candidates, to prefill the expense;
+    /// the cost block is left out when no currency was found
+    pub blocks: serde_json::Value,
+}
+
+/// How a line labels the amounts on it
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LineLabel {
+    Total,
+    Amount,
+    Subtotal,
+    Other,
+}
+
+impl LineLabel {
+    fn confidence(self) -> f32 {
+        match self {
+            LineLabel::Total => 0.85,
+            LineLabel::Amount => 0.6,
+            LineLabel::Subtotal => 0.15,
+            LineLabel::Other => 0.05,
+        }
+    }
+}
+
+/// Read the total and purchase date candidates from a receipt's recognized text.
+/// Dates after `today` are ignored.
+pub fn extract_receipt(text: &str, today: NaiveDate) -> ReceiptExtraction {
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let mut found: Vec<(f64, Option<&'static str>, Option<LineLabel>, &str)> = Vec::new();
+    let mut dates: Vec<ReceiptDate> = Vec::new();
+    // Receipts often print a label and its amount on separate lines
+    let mut pending_label = None;
+    for line in &lines {
+        let label = line_label(line);
+        let amounts = amounts_in_line(line);
+        if amounts.is_empty() {
+            pending_label = label;
+        } else {
+            let label = label.or(pending_label.take());
+            found.extend(
+                amounts
+                    .into_iter()
+                    .map(|(amount, currency)| (amount, currency, label, *line)),
+            );
+        }
+
+        let labeled = has_label(line, DATE_LABELS);
+        for (date, ambiguous) in dates_in_line(line) {
+            if date > today {
+                continue;
+            }
+            let mut confidence: f32 = if ambiguous { 0.4 } else { 0.6 };
+            if labeled {
+                confidence += 0.3;
+            }
+            if (today - date).num_days() > 365 {
+                confidence -= 0.2;
+            }
+            dates.push(ReceiptDate {
+                date,
+                confidence: round(confidence),
+                line: line.to_string(),
+            });
+        }
+    }
+
+    let amounts = rank_amounts(&found);
+    dates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    let mut seen = Vec::new();
+    dates.retain(|candidate| {
+        let first = !seen.contains(&candidate.date);
+        seen.push(candidate.date);
+        first
+    });
+    dates.truncate(MAX_CANDIDATES);
+
+    let mut blocks = ActivityBlocksBuilder::new();
+    if let Some(best) = amounts.first() {
+        if let Some(currency) = &best.currency {
+            let _ = blocks.cost(best.amount, currency);
+        }
+    }
+    if let Some(best) = dates.first() {
+        // Midday keeps the date the same in the user's time zone
+        if let Some(at) = best.date.and_hms_opt(12, 0, 0) {
+            blocks.time(at.and_utc());
+        }
+    }
+
+    ReceiptExtraction {
+        amounts,
+        dates,
+        blocks: blocks.into_json(),
+    }
+}
+
+/// Merge amounts found more than once and order them by how likely each is the total
+fn rank_amounts(
+    found: &[(f64, Option<&'static str>, Option<LineLabel>, &str)],
+) -> Vec<ReceiptAmount> {
+    let mut currency_counts: HashMap<&str, usize> = HashMap::new();
+    for (_, currency, _, _) in found {
+        if let Some(currency) = currency {
+            *currency_counts.entry(currency).or_default() += 1;
+        }
+    }
+    let receipt_currency = currency_counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+        .map(|(currency, _)| currency);
+    let largest = found.iter().map(|(amount, ..)| *amount).fold(0.0, f64::max);
+
+    let mut by_cents: HashMap<i64, ReceiptAmount> = HashMap::new();
+    for (amount, currency, label, line) in found {
+        let mut confidence = label.map_or(UNLABELED_AMOUNT, LineLabel::confidence);
+        if *amount == largest {
+            confidence += 0.1;
+        }
+        if currency.is_some() {
+            confidence += 0.05;
+        }
+
+        let candidate = ReceiptAmount {
+            amount: *amount,
+            currency: currency.or(receipt_currency).map(str::to_string),
+            confidence,
+            line: line.to_string(),
+        };
+        match by_cents.entry((amount * 100.0).round() as i64) {
+            Entry::Occupied(mut entry) => {
+                let existing = entry.get_mut();
+                // A total is often printed again next to the card payment
+                let repeated = *label != Some(LineLabel::Other);
+                if candidate.confidence > existing.confidence {
+                    *existing = ReceiptAmount {
+                        currency: candidate.currency.or(existing.currency.take()),
+                        ..candidate
+                    };
+                }
+                if repeated {
+                    existing.confidence += 0.05;
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(candidate);
+            }
+        }
+    }
+
+    let mut amounts: Vec<ReceiptAmount> = by_cents
+        .into_values()
+        .map(|candidate| ReceiptAmount {
+            confidence: round(candidate.confidence.min(1.0)),
+            ..candidate
+        })
+        .collect();
+    amounts.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| b.amount.total_cmp(&a.amount))
+    });
+    amounts.truncate(MAX_CANDIDATES);
+    amounts
+}
+
+fn round(confidence: f32) -> f32 {
+    (confidence * 100.0).round() / 100.0
+}
+
+/// Whether a line contains any of `labels`; ASCII labels must be whole words,
+/// so "subtotal" isn't read as "total"
+fn has_label(line: &str, labels: &[&str]) -> bool {
+    let lower = line.to_lowercase();
+    let words = format!(
+        " {} ",
+        lower
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    labels.iter().any(|label| {
+        if label.is_ascii() {
+            words.contains(&format!(" {label} "))
+        } else {
+            lower.contains(label)
+        }
+    })
+}
+
+fn line_label(line: &str) -> Option<LineLabel> {
+    if has_label(line, SUBTOTAL_LABELS) {
+        Some(LineLabel::Subtotal)
+    } else if has_label(line, OTHER_AMOUNT_LABELS)
+        && !(has_label(line, TOTAL_LABELS) && has_label(line, INCLUSIVE_LABELS))
+    {
+        Some(LineLabel::Other)
+    } else if has_label(line, TOTAL_LABELS) {
+        Some(LineLabel::Total)
+    } else if has_label(line, AMOUNT_LABELS) {
+        Some(LineLabel::Amount)
+    } else {
+        None
+    }
+}
+
+/// Money amounts on a line with the currency written next to them. Numbers count
+/// as money when they have cents or a currency; parts of dates, times, percentages
+/// and negative amounts are skipped.
+fn amounts_in_line(line: &str) -> Vec<(f64, Option<&'static str>)> {
+    let chars: Vec<char> = line
+        .chars()
+        .map(|c| if c == '￥' { '¥' } else { c })
+        .collect();
+    let is_number_char = |c: char| c.is_ascii_digit() || c == '.' || c == ',';
+
+    let mut amounts = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() || (i > 0 && is_number_char(chars[i - 1])) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_number_char(chars[i]) {
+            i += 1;
+        }
+        let mut end = i;
+        while !chars[end - 1].is_ascii_digit() {
+            end -= 1;
+        }
+
+        let before = start.checked_sub(1).map(|p| chars[p]);
+        let before_digit = start
+            .checked_sub(2)
+            .is_some_and(|p| chars[p].is_ascii_digit());
+        let after = chars.get(end).copied();
+        let after_digit = chars.get(end + 1).is_some_and(|c| c.is_ascii_digit());
+        if matches!(before, Some('-' | '−'))
+            || (matches!(before, Some('/' | ':')) && before_digit)
+            || (matches!(after, Some('/' | '-' | ':')) && after_digit)
+            || after == Some('%')
+        {
+            continue;
+        }
+
+        let run: String = chars[start..end].iter().collect();
+        let Some((amount, has_cents)) = parse_amount(&run) else {
+            continue;
+        };
+        let currency = currency_before(&chars[..start]).or_else(|| currency_after(&chars[end..]));
+        if (has_cents || currency.is_some()) && amount > 0.0 && amount < 1_000_000.0 {
+            amounts.push((amount, currency));
+        }
+    }
+    amounts
+}
+
+/// "1,234.56", "1.234,56", "12,50", "25"; returns the amount and whether it has cents
+fn parse_amount(run: &str) -> Option<(f64, bool)> {
+    let Some(last) = run.rfind(['.', ',']) else {
+        return Some((run.parse().ok()?, false));
+    };
+    let decimals = run.len() - last - 1;
+    let separator = &run[last..=last];
+    let mixed = run.contains('.') && run.contains(',');
+
+    let (whole, fraction) = if decimals == 2 && (mixed || run.matches(separator).count() == 1) {
+        (&run[..last], &run[last + 1..])
+    } else if decimals == 3 && !mixed {
+        (run, "")
+    } else {
+        return None;
+    };
+    let digits: String = whole.chars().filter(char::is_ascii_digit).collect();
+    let amount: f64 = format!(
+        "{digits}.{}",
+        if fraction.is_empty() { "0" } else { fraction }
+    )
+    .parse()
+    .ok()?;
+    Some((amount, !fraction.is_empty()))
+}
+
+fn currency_before(chars: &[char]) -> Option<&'static str> {
+    let end = chars.iter().rposition(|c| !c.is_whitespace())? + 1;
+    if let Some(currency) = lookup_currency(&chars[end - 1].to_string()) {
+        return Some(currency);
+    }
+    let start = chars[..end]
+        .iter()
+        .rposition(|c| !c.is_ascii_alphabetic())
+        .map_or(0, |p| p + 1);
+    let word: String = chars[start..end].iter().collect();
+    lookup_currency(&word.to_lowercase())
+}
+
+/// A currency written after an amount, unless it starts the next amount as in
+/// "1234 $43.82"
+fn currency_after(chars: &[char]) -> Option<&'static str> {
+    let start = chars.iter().position(|c| !c.is_whitespace())?;
+    let end = match chars[start..].iter().position(|c| !c.is_ascii_alphabetic()) {
+        Some(0) => start + 1,
+        Some(p) => start + p,
+        None => chars.len(),
+    };
+    let next_is_number = chars[end..]
+        .iter()
+        .find(|c| !c.is_whitespace())
+        .is_some_and(|c| c.is_ascii_digit());
+    if next_is_number {
+        return None;
+    }
+    let word: String = chars[start..end].iter().collect();
+    lookup_currency(&word.to_lowercase())
+}
+
+/// Dates written on a line, with whether day and month could be the other way round
+fn dates_in_line(line: &str) -> Vec<(NaiveDate, bool)> {
+    let words: Vec<&str> = line
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| ",;()[]".contains(c)))
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut dates = Vec::new();
+    for (i, word) in words.iter().enumerate() {
+        if word.contains('年') {
+            dates.extend(parse_cjk_date(word).map(|date| (date, false)));
+            continue;
+        }
+        // "2025-06-01", "01/06/2025 14:30", "05-Jun-2025"
+        let date_part: &str = word
+            .split(|c: char| !(c.is_ascii_alphanumeric() || "-/.".contains(c)))
+            .next()
+            .unwrap_or_default();
+        if let Some(separator) = date_part.chars().find(|c| "-/.".contains(*c)) {
+            let parts: Vec<&str> = date_part.trim_end_matches('.').split(separator).collect();
+            dates.extend(parse_date_parts(&parts, separator == '.'));
+        }
+        // "Jun 5, 2025", "5 June 2025"
+        if let Some(window) = words.get(i..i + 3) {
+            let parts: Vec<&str> = window.iter().map(|w| w.trim_end_matches('.')).collect();
+            if parts.iter().any(|part| month_number(part).is_some()) {
+                dates.extend(parse_date_parts(&parts, false));
+            }
+        }
+    }
+    dates
+}
+
+/// Day, month and year in one of the orders receipts print them. Day-first is
+/// assumed with dots, as in "01.06.2025"; otherwise a date like 01/06/2025 is
+/// returned both ways round and marked ambiguous.
+fn parse_date_parts(parts: &[&str], day_first: bool) -> Vec<(NaiveDate, bool)> {
+    let [a, b, c] = parts else {
+        return Vec::new();
+    };
+    let number = |part: &str| {
+        (!part.is_empty() && part.len() <= 4 && part.chars().all(|c| c.is_ascii_digit()))
+            .then(|| part.parse::<u32>().ok())
+            .flatten()
+    };
+    let year = |part: &str| match (part.len(), number(part)) {
+        (4, Some(year)) => Some(year as i32),
+        (2, Some(year)) => Some(2000 + year as i32),
+        _ => None,
+    };
+    let date = |y: i32, m: u32, d: u32| NaiveDate::from_ymd_opt(y, m, d);
+
+    if a.len() == 4 {
+        return match (year(a), month_number(b).or(number(b)), number(c)) {
+            (Some(y), Some(m), Some(d)) => date(y, m, d).map(|d| (d, false)).into_iter().collect(),
+            _ => Vec::new(),
+        };
+    }
+    let Some(y) = year(c) else {
+        return Vec::new();
+    };
+    match (month_number(a), month_number(b)) {
+        (Some(m), None) => {
+            return number(b)
+                .and_then(|d| date(y, m, d))
+                .map(|d| (d, false))
+                .into_iter()
+                .collect()
+        }
+        (None, Some(m)) => {
+            return number(a)
+                .and_then(|d| date(y, m, d))
+                .map(|d| (d, false))
+                .into_iter()
+                .collect()
+        }
+        _ => {}
+    }
+
+    let (Some(first), Some(second)) = (number(a), number(b)) else {
+        return Vec::new();
+    };
+    let month_first = date(y, first, second);
+    let day_first_date = date(y, second, first);
+    match (month_first, day_first_date) {
+        (Some(md), Some(dm)) if md == dm => vec![(md, false)],
+        (Some(_), Some(dm)) if day_first => vec![(dm, false)],
+        (Some(md), Some(dm)) => vec![(md, true), (dm, true)],
+        (Some(md), None) => vec![(md, false)],
+        (None, Some(dm)) => vec![(dm, false)],
+        (None, None) => Vec::new(),
+    }
+}
+
+/// "2025年6月1日"
+fn parse_cjk_date(word: &str) -> Option<NaiveDate> {
+    let (year, rest) = word.split_once('年')?;
+    let (month, rest) = rest.split_once('月')?;
+    let day: String = rest.chars().take_while(char::is_ascii_digit).collect();
+    let year: String = year
+        .chars()
+        .rev()
+        .take_while(char::is_ascii_digit)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+    if year.len() != 4 {
+        return None;
+    }
+    NaiveDate::from_ymd_opt(
+        year.parse().ok()?,
+        month.trim().parse().ok()?,
+        day.parse().ok()?,
+    )
+}
+
+/// "Jun", "Sept", "June"
+fn month_number(word: &str) -> Option<u32> {
+    let lower = word.to_lowercase();
+    if lower.len() < 3 || !lower.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|month| month.starts_with(&lower))
+        .map(|index| index as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 6, 10).unwrap()
+    }
+
+    #[test]
+    fn test_extract_store_receipt() {
+        let text = "HAPPY PAWS PET SUPPLY\n\
+            Tel 555-123-4567\n\
+            Date: 06/08/2025 14:32\n\
+            Kibble 2kg x1        $34.99\n\
+            Chew toy             $5.49\n\
+            Subtotal             $40.48\n\
+            Tax 8.25%            $3.34\n\
+            TOTAL\n\
+            $43.82\n\
+            VISA ****1234        $43.82\n\
+            CASH                 $50.00\n";
+        let extraction = extract_receipt(text, today());
+
+        let best = &extraction.amounts[0];
+        assert_eq!(best.amount, 43.82);
+        assert_eq!(best.currency.as_deref(), Some("USD"));
+        assert_eq!(best.line, "$43.82");
+        assert!(best.confidence > extraction.amounts[1].confidence + 0.3);
+
+        // 06/08 could be either way round, but August 6 hasn't come yet
+        let dates: Vec<NaiveDate> = extraction.dates.iter().map(|d| d.date).collect();
+        assert_eq!(dates, vec![NaiveDate::from_ymd_opt(2025, 6, 8).unwrap()]);
+        assert_eq!(
+            extraction.blocks["cost"],
+            serde_json::json!({ "amount": 43.82, "currency": "USD" })
+        );
+        assert_eq!(
+            extraction.blocks["time"]["date"],
+            "2025-06-08T12:00:00.000Z"
+        );
+    }
+
+    #[test]
+    fn test_extract_chinese_and_european_receipts() {
+        let text = "宠物医院\n日期：2025年5月20日\n疫苗 120.00元\n驱虫 80.00元\n合计：￥200.00\n现金 ￥200.00\n";
+        let extraction = extract_receipt(text, today());
+        assert_eq!(extraction.amounts[0].amount, 200.0);
+        assert_eq!(extraction.amounts[0].currency.as_deref(), Some("CNY"));
+        assert_eq!(
+            extraction.dates[0].date,
+            NaiveDate::from_ymd_opt(2025, 5, 20).unwrap()
+        );
+        assert!(extraction.dates[0].confidence > 0.8);
+
+        let text = "Tierarzt\n01.06.2025\nBehandlung 1.234,50 EUR\nSumme inkl. MwSt 1.234,50\n";
+        let extraction = extract_receipt(text, today());
+        assert_eq!(extraction.amounts[0].amount, 1234.5);
+        assert_eq!(extraction.amounts[0].currency.as_deref(), Some("EUR"));
+        assert_eq!(
+            extraction.dates[0].date,
+            NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()
+        );
+        assert_eq!(extraction.dates.len(), 1);
+    }
+
+    #[test]
+    fn test_nothing_found() {
+        let extraction = extract_receipt("Thank you for shopping\nItems: 3", today());
+        assert!(extraction.amounts.is_empty());
+        assert!(extraction.dates.is_empty());
+        assert_eq!(extraction.blocks, serde_json::json!({}));
+    }
+}
@@ -0,0 +1,226 @@
+use anyhow::Result;
+use chrono::{Days, Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on generated occurrences to guard against runaway rules
+const MAX_OCCURRENCES: usize = 1000;
+
+/// Recurrence frequency (subset of RFC 5545 FREQ values)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+impl std::fmt::Display for Frequency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Frequency::Daily => write!(f, "DAILY"),
+            Frequency::Weekly => write!(f, "WEEKLY"),
+            Frequency::Monthly => write!(f, "MONTHLY"),
+            Frequency::Yearly => write!(f, "YEARLY"),
+        }
+    }
+}
+
+impl std::str::FromStr for Frequency {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_uppercase().as_str() {
+            "DAILY" => Ok(Frequency::Daily),
+            "WEEKLY" => Ok(Frequency::Weekly),
+            "MONTHLY" => Ok(Frequency::Monthly),
+            "YEARLY" => Ok(Frequency::Yearly),
+            _ => Err(anyhow::anyhow!("Unsupported recurrence frequency: {}", s)),
+        }
+    }
+}
+
+/// Parsed recurrence rule, e.g. `FREQ=MONTHLY;INTERVAL=1;COUNT=12`
+///
+/// Supports the FREQ, INTERVAL, COUNT and UNTIL parts of an RFC 5545 RRULE.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    pub interval: u32,
+    pub count: Option<u32>,
+    pub until: Option<NaiveDate>,
+}
+
+impl RecurrenceRule {
+    /// Date of the n-th occurrence (0-based) for a series starting on `start`
+    pub fn nth_occurrence(&self, start: NaiveDate, n: u32) -> Option<NaiveDate> {
+        let steps = n.checked_mul(self.interval)?;
+        match self.freq {
+            Frequency::Daily => start.checked_add_days(Days::new(steps as u64)),
+            Frequency::Weekly => start.checked_add_days(Days::new(steps as u64 * 7)),
+            Frequency::Monthly => start.checked_add_months(Months::new(steps)),
+            Frequency::Yearly => start.checked_add_months(Months::new(steps.checked_mul(12)?)),
+        }
+    }
+
+    /// All occurrence dates in `[from, to]` for a series starting on `start`
+    pub fn occurrences_between(
+        &self,
+        start: NaiveDate,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<NaiveDate> {
+        let mut dates = Vec::new();
+        let mut n = 0u32;
+
+        while dates.len() < MAX_OCCURRENCES {
+            if self.count.is_some_and(|count| n >= count) {
+                break;
+            }
+
+            let date = match self.nth_occurrence(start, n) {
+                Some(date) => date,
+                None => break,
+            };
+
+            if date > to || self.until.is_some_and(|until| date > until) {
+                break;
+            }
+
+            if date >= from {
+                dates.push(date);
+            }
+            n += 1;
+        }
+
+        dates
+    }
+}
+
+impl std::fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FREQ={};INTERVAL={}", self.freq, self.interval)?;
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={count}")?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%d"))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::str::FromStr for RecurrenceRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let body = s.trim();
+        let body = body.strip_prefix("RRULE:").unwrap_or(body);
+
+        let mut freq = None;
+        let mut interval = 1u32;
+        let mut count = None;
+        let mut until = None;
+
+        for part in body.split(';').filter(|p| !p.trim().is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Invalid recurrence rule part: {}", part))?;
+
+            match key.trim().to_uppercase().as_str() {
+                "FREQ" => freq = Some(value.trim().parse::<Frequency>()?),
+                "INTERVAL" => {
+                    interval = value
+                        .trim()
+                        .parse()
+                        .map_err(|_| anyhow::anyhow!("Invalid INTERVAL: {}", value))?;
+                    if interval == 0 {
+                        return Err(anyhow::anyhow!("INTERVAL must be at least 1"));
+                    }
+                }
+                "COUNT" => {
+                    count = Some(
+                        value
+                            .trim()
+                            .parse()
+                            .map_err(|_| anyhow::anyhow!("Invalid COUNT: {}", value))?,
+                    )
+                }
+                "UNTIL" => {
+                    // Accept both date (20250131) and date-time (20250131T000000Z) forms
+                    let date_part = value.trim().get(..8).unwrap_or(value);
+                    until = Some(
+                        NaiveDate::parse_from_str(date_part, "%Y%m%d")
+                            .map_err(|_| anyhow::anyhow!("Invalid UNTIL: {}", value))?,
+                    );
+                }
+                other => {
+                    return Err(anyhow::anyhow!(
+                        "Unsupported recurrence rule part: {}",
+                        other
+                    ))
+                }
+            }
+        }
+
+        Ok(RecurrenceRule {
+            freq: freq.ok_or_else(|| anyhow::anyhow!("Recurrence rule requires FREQ"))?,
+            interval,
+            count,
+            until,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_parse_rrule() {
+        let rule: RecurrenceRule = "RRULE:FREQ=MONTHLY;INTERVAL=2;COUNT=6".parse().unwrap();
+        assert_eq!(rule.freq, Frequency::Monthly);
+        assert_eq!(rule.interval, 2);
+        assert_eq!(rule.count, Some(6));
+        assert_eq!(rule.to_string(), "FREQ=MONTHLY;INTERVAL=2;COUNT=6");
+
+        assert!("INTERVAL=2".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=HOURLY".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=DAILY;INTERVAL=0".parse::<RecurrenceRule>().is_err());
+    }
+
+    #[test]
+    fn test_monthly_occurrences_clamp_to_month_end() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY".parse().unwrap();
+        let dates =
+            rule.occurrences_between(date("2025-01-31"), date("2025-01-01"), date("2025-04-30"));
+        assert_eq!(
+            dates,
+            vec![
+                date("2025-01-31"),
+                date("2025-02-28"),
+                date("2025-03-31"),
+                date("2025-04-30")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_count_and_until_limit_occurrences() {
+        let rule: RecurrenceRule = "FREQ=WEEKLY;COUNT=3".parse().unwrap();
+        let dates =
+            rule.occurrences_between(date("2025-01-01"), date("2025-01-01"), date("2025-12-31"));
+        assert_eq!(dates.len(), 3);
+
+        let rule: RecurrenceRule = "FREQ=DAILY;UNTIL=20250105".parse().unwrap();
+        let dates =
+            rule.occurrences_between(date("2025-01-01"), date("2025-01-03"), date("2025-12-31"));
+        assert_eq!(
+            dates,
+            vec![date("2025-01-03"), date("2025-01-04"), date("2025-01-05")]
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use crate::errors::PetError;
+use serde::{Deserialize, Serialize};
+use sqlx::migrate::MigrateError;
+use std::path::{Path, PathBuf};
+
+/// Outcome of a start-up check, from best to worst
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum InitStatus {
+    Ok,
+    /// Something was wrong and has been fixed; the app can be used normally
+    Repaired,
+    /// Data can't be trusted or opened; the user should restore a backup
+    NeedsRestore,
+}
+
+/// One check run while initializing the app
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InitCheck {
+    pub name: String,
+    pub status: InitStatus,
+    pub message: String,
+}
+
+impl InitCheck {
+    pub fn new(name: &str, status: InitStatus, message: impl Into<String>) -> Self {
+        InitCheck {
+            name: name.to_string(),
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+/// What `initialize_app` found and did, for the start-up and recovery screens
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitReport {
+    /// Worst status of all checks
+    pub status: InitStatus,
+    pub database_path: String,
+    pub photo_dir: String,
+    /// The app was started read-only so damaged data can still be viewed and exported
+    pub read_only: bool,
+    pub checks: Vec<InitCheck>,
+}
+
+impl InitReport {
+    pub fn new(database_path: &Path, photo_dir: &Path) -> Self {
+        InitReport {
+            status: InitStatus::Ok,
+            database_path: database_path.display().to_string(),
+            photo_dir: photo_dir.display().to_string(),
+            read_only: false,
+            checks: Vec::new(),
+        }
+    }
+
+    /// Add a check, lowering the overall status when it is worse
+    pub fn record(&mut self, check: InitCheck) {
+        match check.status {
+            InitStatus::Ok => log::info!("Start-up check {}: {}", check.name, check.message),
+            _ => log::warn!(
+                "Start-up check {} {:?}: {}",
+                check.name,
+                check.status,
+                check.message
+            ),
+        }
+        self.status = self.status.max(check.status);
+        self.checks.push(check);
+    }
+}
+
+/// SQLite's write-ahead log next to the database file
+pub fn wal_path(database_path: &Path) -> PathBuf {
+    let mut path = database_path.as_os_str().to_owned();
+    path.push("-wal");
+    PathBuf::from(path)
+}
+
+/// Create a storage directory when it is missing. A missing directory next to an
+/// existing database means its files were lost, which is reported as repaired.
+pub fn ensure_directory(
+    name: &str,
+    dir: &Path,
+    database_existed: bool,
+) -> Result<InitCheck, PetError> {
+    if dir.is_dir() {
+        return Ok(InitCheck::new(name, InitStatus::Ok, "Directory present"));
+    }
+
+    std::fs::create_dir_all(dir)
+        .map_err(|e| PetError::file_system(format!("Failed to create {}: {e}", dir.display())))?;
+
+    Ok(if database_existed {
+        InitCheck::new(
+            name,
+            InitStatus::Repaired,
+            format!(
+                "{} was missing and has been recreated; files stored in it before are gone",
+                dir.display()
+            ),
+        )
+    } else {
+        InitCheck::new(name, InitStatus::Ok, "Directory created")
+    })
+}
+
+/// Explain why the database could not be opened
+pub fn classify_open_error(error: &anyhow::Error) -> InitCheck {
+    let message = match error.downcast_ref::<MigrateError>() {
+        Some(MigrateError::VersionMissing(version)) => format!(
+            "The database was upgraded by a newer version of the app (migration {version}); update the app or restore a backup"
+        ),
+        Some(MigrateError::VersionMismatch(version)) => {
+            format!("Migration {version} applied to the database differs from this version of the app")
+        }
+        Some(MigrateError::Dirty(version)) => {
+            format!("Migration {version} was interrupted and only partially applied")
+        }
+        Some(e) => format!("Database migration failed: {e}"),
+        None => format!("The database could not be opened: {error}"),
+    };
+    InitCheck::new("database", InitStatus::NeedsRestore, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::PetDatabase;
+
+    #[test]
+    fn test_missing_directory_is_repaired_for_existing_database() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let photos = dir.path().join("photos");
+
+        let first_run = ensure_directory("photo_directory", &photos, false).unwrap();
+        assert_eq!(first_run.status, InitStatus::Ok);
+        assert!(photos.is_dir());
+
+        std::fs::remove_dir(&photos).unwrap();
+        let lost = ensure_directory("photo_directory", &photos, true).unwrap();
+        assert_eq!(lost.status, InitStatus::Repaired);
+
+        let mut report = InitReport::new(&dir.path().join("pets.db"), &photos);
+        report.record(first_run);
+        report.record(lost);
+        assert_eq!(report.status, InitStatus::Repaired);
+        assert_eq!(
+            wal_path(&dir.path().join("pets.db")),
+            dir.path().join("pets.db-wal")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_database_from_newer_app_needs_restore() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db_path = dir.path().join("pets.db");
+
+        let db = PetDatabase::new(&db_path).await.unwrap();
+        assert!(db.quick_check().await.unwrap().is_empty());
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) VALUES (99990101000000, 'future', 1, x'00', 0)",
+        )
+        .execute(&db.pool)
+        .await
+        .unwrap();
+        db.pool.close().await;
+
+        let error = PetDatabase::new(&db_path).await.err().unwrap();
+        let check = classify_open_error(&error);
+        assert_eq!(check.status, InitStatus::NeedsRestore);
+        assert!(
+            check.message.contains("99990101000000"),
+            "{}",
+            check.message
+        );
+    }
+}
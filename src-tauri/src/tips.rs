@@ -0,0 +1,247 @@
+use crate::database::{ActivityCategory, PetSpecies};
+use serde::Serialize;
+
+/// A short piece of care guidance shown while editing an activity
+#[derive(Debug, Clone, Serialize)]
+pub struct CareTip {
+    pub key: &'static str,
+    pub title: &'static str,
+    pub body: &'static str,
+    pub category: ActivityCategory,
+    /// Subcategories the tip is about; empty means the whole category
+    pub subcategories: &'static [&'static str],
+    /// Species the tip applies to; None means any species
+    pub species: Option<PetSpecies>,
+}
+
+impl CareTip {
+    /// Whether the tip is relevant to an activity of `category`/`subcategory` for `species`
+    pub fn applies_to(
+        &self,
+        category: ActivityCategory,
+        subcategory: Option<&str>,
+        species: Option<&PetSpecies>,
+    ) -> bool {
+        self.category == category
+            && (self.subcategories.is_empty() || subcategory.is_some_and(|s| self.is_about(s)))
+            && self
+                .species
+                .as_ref()
+                .is_none_or(|tip_species| species.is_none_or(|s| s == tip_species))
+    }
+
+    fn is_about(&self, subcategory: &str) -> bool {
+        let subcategory = subcategory.trim();
+        self.subcategories
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(subcategory))
+    }
+}
+
+const fn tip(
+    key: &'static str,
+    title: &'static str,
+    body: &'static str,
+    category: ActivityCategory,
+    subcategories: &'static [&'static str],
+    species: Option<PetSpecies>,
+) -> CareTip {
+    CareTip {
+        key,
+        title,
+        body,
+        category,
+        subcategories,
+        species,
+    }
+}
+
+static TIPS: [CareTip; 14] = [
+    tip(
+        "post_vaccination_care",
+        "After a vaccination",
+        "Mild tiredness or a small lump at the injection site for a day or two is normal. \
+         Call the vet if there is vomiting, facial swelling or trouble breathing.",
+        ActivityCategory::Health,
+        &["Vaccination", "Veterinary"],
+        None,
+    ),
+    tip(
+        "medication_with_food",
+        "Giving medication",
+        "Give the full course even if your pet seems better, and check whether the dose \
+         should be given with food.",
+        ActivityCategory::Health,
+        &["Medication"],
+        None,
+    ),
+    tip(
+        "cat_pill_water",
+        "Pilling a cat",
+        "Follow a pill with a little water or a treat; dry pills can get stuck in a cat's \
+         esophagus.",
+        ActivityCategory::Health,
+        &["Medication"],
+        Some(PetSpecies::Cat),
+    ),
+    tip(
+        "symptom_warning_signs",
+        "When to call the vet",
+        "Seek care quickly for breathing difficulty, repeated vomiting, not eating for over \
+         24 hours, or a cat straining without passing urine.",
+        ActivityCategory::Health,
+        &["Symptom"],
+        None,
+    ),
+    tip(
+        "toxic_foods_dog",
+        "Foods dogs must avoid",
+        "Chocolate, grapes and raisins, onions, garlic, xylitol (in sugar-free gum) and \
+         macadamia nuts are toxic to dogs.",
+        ActivityCategory::Diet,
+        &[],
+        Some(PetSpecies::Dog),
+    ),
+    tip(
+        "toxic_foods_cat",
+        "Foods cats must avoid",
+        "Onions, garlic, chives, chocolate, grapes and raisins are toxic to cats, and lilies \
+         are dangerous even in small amounts.",
+        ActivityCategory::Diet,
+        &[],
+        Some(PetSpecies::Cat),
+    ),
+    tip(
+        "safe_treats_dog",
+        "Safe dog treats",
+        "Plain cooked chicken, carrots, apple slices without seeds and blueberries make good \
+         treats. Keep treats under 10% of daily calories.",
+        ActivityCategory::Diet,
+        &["Treat"],
+        Some(PetSpecies::Dog),
+    ),
+    tip(
+        "safe_treats_cat",
+        "Safe cat treats",
+        "Small pieces of plain cooked chicken or fish are fine. Most adult cats are lactose \
+         intolerant, so skip the milk. Keep treats under 10% of daily calories.",
+        ActivityCategory::Diet,
+        &["Treat"],
+        Some(PetSpecies::Cat),
+    ),
+    tip(
+        "food_transition",
+        "Changing food",
+        "Switch to a new food gradually over 7 to 10 days, mixing more of it in each day, \
+         to avoid an upset stomach.",
+        ActivityCategory::Diet,
+        &["Feeding"],
+        None,
+    ),
+    tip(
+        "fresh_water",
+        "Fresh water",
+        "Refresh water daily. Many cats drink more from a fountain or a bowl placed away from \
+         their food.",
+        ActivityCategory::Diet,
+        &["Water"],
+        None,
+    ),
+    tip(
+        "weigh_consistently",
+        "Consistent weigh-ins",
+        "Weigh at the same time of day on the same scale so trends reflect real changes.",
+        ActivityCategory::Growth,
+        &["Weight"],
+        None,
+    ),
+    tip(
+        "hot_pavement",
+        "Hot pavement",
+        "If the pavement is too hot to hold the back of your hand on for 7 seconds, it is too \
+         hot for paws. Walk early or late on hot days.",
+        ActivityCategory::Lifestyle,
+        &["Walk"],
+        Some(PetSpecies::Dog),
+    ),
+    tip(
+        "grooming_checks",
+        "Grooming check",
+        "Grooming is a good time to check for lumps, ticks, sore spots and overgrown nails.",
+        ActivityCategory::Lifestyle,
+        &["Grooming"],
+        None,
+    ),
+    tip(
+        "keep_receipts",
+        "Keep the receipt",
+        "Attach invoices to vet expenses; insurers usually need an itemized bill to process \
+         a claim.",
+        ActivityCategory::Expense,
+        &["Veterinary", "Insurance"],
+        None,
+    ),
+];
+
+/// All built-in care tips
+pub fn all_tips() -> &'static [CareTip] {
+    &TIPS
+}
+
+/// Tips relevant to an activity being edited, those about its subcategory first
+pub fn contextual_tips(
+    category: ActivityCategory,
+    subcategory: Option<&str>,
+    species: Option<&PetSpecies>,
+) -> Vec<&'static CareTip> {
+    let mut tips: Vec<&CareTip> = TIPS
+        .iter()
+        .filter(|tip| tip.applies_to(category, subcategory, species))
+        .collect();
+    // Stable sort keeps the curated order within each group
+    tips.sort_by_key(|tip| tip.subcategories.is_empty());
+    tips
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tip_keys_are_unique() {
+        let mut keys: Vec<_> = all_tips().iter().map(|t| t.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), all_tips().len());
+    }
+
+    #[test]
+    fn test_contextual_tips() {
+        let keys = |tips: Vec<&CareTip>| tips.iter().map(|t| t.key).collect::<Vec<_>>();
+
+        assert_eq!(
+            keys(contextual_tips(
+                ActivityCategory::Diet,
+                Some("treat"),
+                Some(&PetSpecies::Cat)
+            )),
+            vec!["safe_treats_cat", "toxic_foods_cat"]
+        );
+        assert_eq!(
+            keys(contextual_tips(
+                ActivityCategory::Health,
+                Some(" Vaccination "),
+                Some(&PetSpecies::Dog)
+            )),
+            vec!["post_vaccination_care"]
+        );
+
+        // Without a species, species-specific tips are included
+        let medication = contextual_tips(ActivityCategory::Health, Some("Medication"), None);
+        assert_eq!(medication.len(), 2);
+
+        // Subcategory tips need a subcategory
+        assert!(contextual_tips(ActivityCategory::Growth, None, None).is_empty());
+        assert!(contextual_tips(ActivityCategory::Expense, Some("Purchase"), None).is_empty());
+    }
+}
@@ -0,0 +1,347 @@
+use crate::database::activity_data::BlockData;
+use crate::database::{Activity, ActivityCategory, Pet};
+use crate::pdf::{self, Font, PageContent};
+use crate::privacy::PrivacySettings;
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Card size: ISO/IEC 7810 ID-1 (85.6 x 54 mm, a bank card) in PDF points,
+/// so the card fits a wallet or a vaccination card sleeve
+const CARD_WIDTH_PT: f32 = 242.65;
+const CARD_HEIGHT_PT: f32 = 153.07;
+const MARGIN_PT: f32 = 10.0;
+
+/// Vaccinations listed on one card; longer records continue on further cards
+pub const ROWS_PER_CARD: usize = 9;
+
+/// Longest vaccine name printed before it is cut short
+const MAX_VACCINE_NAME_CHARS: usize = 32;
+
+/// One vaccination as printed on the card
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VaccinationEntry {
+    pub activity_id: i64,
+    pub vaccine: String,
+    pub given_on: NaiveDate,
+    pub next_due_on: Option<NaiveDate>,
+    /// No due date was recorded; `next_due_on` assumes the usual yearly booster
+    pub next_due_estimated: bool,
+    pub batch_number: Option<String>,
+}
+
+/// A generated vaccination card
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VaccinationCard {
+    pub pet_id: i64,
+    /// Path of the PDF in the export directory
+    pub file_path: String,
+    /// Number of card-sized pages in the PDF
+    pub cards: usize,
+    pub entries: Vec<VaccinationEntry>,
+}
+
+/// Vaccinations among a pet's health activities, oldest first. The vaccine name and
+/// next due date come from the vaccination block, falling back to the title and the
+/// reminder date; without either date the next dose is expected a year later.
+/// Private activities and categories hidden in `privacy` are never printed.
+pub fn vaccination_entries(
+    activities: &[Activity],
+    privacy: &PrivacySettings,
+) -> Vec<VaccinationEntry> {
+    let mut entries: Vec<VaccinationEntry> = activities
+        .iter()
+        .filter(|a| a.category == ActivityCategory::Health && !a.needs_review && privacy.shares(a))
+        .filter_map(|activity| {
+            let data = activity.activity_data.as_ref();
+            let record = match data.and_then(|data| data.get("vaccination")) {
+                Some(BlockData::Other(value)) => Some(value),
+                _ => None,
+            };
+            if record.is_none() && !activity.subcategory.to_lowercase().contains("vaccin") {
+                return None;
+            }
+
+            let record_text = |key: &str| {
+                record
+                    .and_then(|value| value.get(key))
+                    .and_then(|v| v.as_str())
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .map(str::to_string)
+            };
+            let title = match data.and_then(|data| data.get("title")) {
+                Some(BlockData::Text(title)) if !title.trim().is_empty() => {
+                    Some(title.trim().to_string())
+                }
+                _ => None,
+            };
+            let reminder_date = match data.and_then(|data| data.get("reminder")) {
+                Some(BlockData::Other(reminder)) => reminder
+                    .get("reminderDate")
+                    .and_then(|v| v.as_str())
+                    .and_then(parse_date),
+                _ => None,
+            };
+
+            let given_on = activity.occurred_on();
+            let recorded_due = record_text("nextDueDate")
+                .as_deref()
+                .and_then(parse_date)
+                .or(reminder_date);
+            Some(VaccinationEntry {
+                activity_id: activity.id,
+                vaccine: record_text("vaccineName")
+                    .or(title)
+                    .unwrap_or_else(|| activity.subcategory.clone()),
+                given_on,
+                next_due_on: recorded_due.or_else(|| given_on.checked_add_months(Months::new(12))),
+                next_due_estimated: recorded_due.is_none(),
+                batch_number: record_text("batchNumber"),
+            })
+        })
+        .collect();
+
+    entries.sort_by_key(|entry| (entry.given_on, entry.activity_id));
+    entries
+}
+
+/// Number of cards needed to print `entries`
+pub fn card_count(entries: &[VaccinationEntry]) -> usize {
+    entries.len().div_ceil(ROWS_PER_CARD).max(1)
+}
+
+/// Render the vaccination record as a PDF of ID-1 sized pages, one per card
+pub fn render_card_pdf(pet: &Pet, entries: &[VaccinationEntry], printed_on: NaiveDate) -> Vec<u8> {
+    let mut description = pet.species.to_string();
+    if let Some(breed) = pet
+        .breed
+        .as_deref()
+        .map(str::trim)
+        .filter(|b| !b.is_empty())
+    {
+        description.push_str(", ");
+        description.push_str(breed);
+    }
+    let pet_line = format!(
+        "{} \u{b7} {description} \u{b7} Born {}",
+        pet.name.trim(),
+        pet.birth_date.format("%Y-%m-%d")
+    );
+    let any_estimated = entries.iter().any(|entry| entry.next_due_estimated);
+
+    let cards = card_count(entries);
+    let vaccine_x = MARGIN_PT;
+    let given_x = 138.0;
+    let due_x = 188.0;
+    let pages: Vec<PageContent> = (0..cards)
+        .map(|card| {
+            let mut page = PageContent::default();
+            let mut y = CARD_HEIGHT_PT - MARGIN_PT - 8.0;
+            page.text(Font::Bold, 9.0, MARGIN_PT, y, "VACCINATION RECORD");
+            y -= 11.0;
+            page.text(Font::Regular, 7.0, MARGIN_PT, y, &pet_line);
+            y -= 13.0;
+
+            page.text(Font::Bold, 6.5, vaccine_x, y, "Vaccine");
+            page.text(Font::Bold, 6.5, given_x, y, "Given");
+            page.text(Font::Bold, 6.5, due_x, y, "Next due");
+            page.rule(MARGIN_PT, CARD_WIDTH_PT - MARGIN_PT, y - 2.5);
+            y -= 10.0;
+
+            let rows = entries
+                .iter()
+                .skip(card * ROWS_PER_CARD)
+                .take(ROWS_PER_CARD);
+            for entry in rows {
+                page.text(Font::Regular, 6.5, vaccine_x, y, &shorten(&entry.vaccine));
+                page.text(
+                    Font::Regular,
+                    6.5,
+                    given_x,
+                    y,
+                    &entry.given_on.format("%Y-%m-%d").to_string(),
+                );
+                let due = match entry.next_due_on {
+                    Some(date) if entry.next_due_estimated => {
+                        format!("{}*", date.format("%Y-%m-%d"))
+                    }
+                    Some(date) => date.format("%Y-%m-%d").to_string(),
+                    None => "-".to_string(),
+                };
+                page.text(Font::Regular, 6.5, due_x, y, &due);
+                y -= 9.0;
+            }
+
+            let mut footer = format!("Printed {}", printed_on.format("%Y-%m-%d"));
+            if cards > 1 {
+                footer.push_str(&format!(" \u{b7} Card {} of {cards}", card + 1));
+            }
+            if any_estimated {
+                footer.push_str(" \u{b7} * yearly booster, not recorded");
+            }
+            page.text(Font::Regular, 5.0, MARGIN_PT, MARGIN_PT - 3.0, &footer);
+            page
+        })
+        .collect();
+
+    pdf::document(&pages, CARD_WIDTH_PT, CARD_HEIGHT_PT)
+}
+
+/// Vaccine name cut to fit its column
+fn shorten(name: &str) -> String {
+    if name.chars().count() <= MAX_VACCINE_NAME_CHARS {
+        return name.to_string();
+    }
+    let mut short: String = name.chars().take(MAX_VACCINE_NAME_CHARS - 3).collect();
+    short.push_str("...");
+    short
+}
+
+/// Date of an ISO 8601 date or datetime string
+fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.get(..10)?, "%Y-%m-%d").ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::activity_data::{ActivityData, ActivityDataExt};
+    use crate::database::{PetGender, PetSpecies, WeightUnit};
+    use chrono::Utc;
+
+    fn pet() -> Pet {
+        Pet {
+            id: 1,
+            name: "Mochi (Mo)".to_string(),
+            birth_date: NaiveDate::from_ymd_opt(2022, 4, 1).unwrap(),
+            species: PetSpecies::Dog,
+            gender: PetGender::Female,
+            breed: Some("Shiba Inu".to_string()),
+            color: None,
+            weight_kg: None,
+            photo_path: None,
+            notes: None,
+            display_order: 0,
+            is_archived: false,
+            is_memorial: false,
+            passed_away_on: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            display_weight: None,
+            display_unit: WeightUnit::Kg,
+        }
+    }
+
+    fn activity(id: i64, subcategory: &str, data: serde_json::Value) -> Activity {
+        Activity {
+            id,
+            pet_id: 1,
+            category: ActivityCategory::Health,
+            subcategory: subcategory.to_string(),
+            activity_data: Some(ActivityData::from_legacy_json(data)),
+            needs_review: false,
+            is_private: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_vaccination_entries() {
+        let time = |date: &str| serde_json::json!({ "date": date, "time": "", "timezone": "" });
+        let activities = vec![
+            activity(
+                1,
+                "Vaccination",
+                serde_json::json!({
+                    "time": time("2025-03-01T09:00:00.000Z"),
+                    "vaccination": {
+                        "vaccineName": "Rabies",
+                        "nextDueDate": "2028-03-01T00:00:00.000Z",
+                        "batchNumber": "RB-77"
+                    }
+                }),
+            ),
+            activity(
+                2,
+                "Vaccination",
+                serde_json::json!({
+                    "time": time("2024-06-10"),
+                    "title": "DHPP booster",
+                    "reminder": { "reminderDate": "2025-06-10", "type": "vaccination_due" }
+                }),
+            ),
+            activity(
+                3,
+                "Vaccination",
+                serde_json::json!({ "time": time("2023-01-31") }),
+            ),
+            activity(
+                4,
+                "Checkup",
+                serde_json::json!({ "time": time("2025-01-01") }),
+            ),
+        ];
+
+        let entries = vaccination_entries(&activities, &PrivacySettings::default());
+        assert_eq!(
+            entries.iter().map(|e| e.activity_id).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(entries[2].vaccine, "Rabies");
+        assert_eq!(entries[2].next_due_on, Some(date(2028, 3, 1)));
+        assert_eq!(entries[2].batch_number.as_deref(), Some("RB-77"));
+        assert_eq!(entries[1].vaccine, "DHPP booster");
+        assert_eq!(entries[1].next_due_on, Some(date(2025, 6, 10)));
+        assert!(!entries[1].next_due_estimated);
+        assert_eq!(entries[0].vaccine, "Vaccination");
+        assert_eq!(entries[0].next_due_on, Some(date(2024, 1, 31)));
+        assert!(entries[0].next_due_estimated);
+
+        let health_hidden = PrivacySettings {
+            hidden_categories: vec![ActivityCategory::Health],
+            ..Default::default()
+        };
+        assert!(vaccination_entries(&activities, &health_hidden).is_empty());
+    }
+
+    #[test]
+    fn test_card_pdf_layout() {
+        let entries: Vec<VaccinationEntry> = (0..ROWS_PER_CARD as i64 + 1)
+            .map(|i| VaccinationEntry {
+                activity_id: i,
+                vaccine: "Leptospirosis (L4) annual booster with extra long name".to_string(),
+                given_on: date(2024, 1, 1),
+                next_due_on: Some(date(2025, 1, 1)),
+                next_due_estimated: i == 0,
+                batch_number: None,
+            })
+            .collect();
+        assert_eq!(card_count(&entries), 2);
+        assert_eq!(card_count(&[]), 1);
+
+        let pdf = render_card_pdf(&pet(), &entries, date(2025, 6, 1));
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.starts_with("%PDF-1.4\n"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("(Mochi \\(Mo\\) \u{fffd} dog, Shiba Inu \u{fffd} Born 2022-04-01)"));
+        assert!(text.contains("(Leptospirosis \\(L4\\) annual boo...)"));
+        assert!(text.contains("(2025-01-01*)"));
+
+        // The cross-reference table points at the objects
+        let startxref: usize = text
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|offset| offset.parse().ok())
+            .unwrap();
+        let xref = std::str::from_utf8(&pdf[startxref..]).unwrap();
+        assert!(xref.starts_with("xref\n0 9\n"));
+        let first_object: usize = xref.lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(pdf[first_object..].starts_with(b"1 0 obj\n"));
+    }
+}
@@ -0,0 +1,262 @@
+use super::outcome::ValidationOutcome;
+use crate::database::activity_data::ActivityDataExt;
+use crate::database::{ActivityCategory, ActivityCreateRequest, ActivityData, Pet, PetSpecies};
+use crate::errors::{ActivityError, PetError};
+use chrono::NaiveDate;
+
+/// Oldest age (in years) accepted for a birth date before it is treated as a typo
+const MAX_PLAUSIBLE_AGE_YEARS: i64 = 40;
+
+/// Minimum age (in days) at which core vaccinations are usually given
+const MIN_VACCINATION_AGE_DAYS: i64 = 42;
+
+/// Life stage of a pet derived from its age
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifeStage {
+    Newborn,
+    Juvenile,
+    Adult,
+    Senior,
+}
+
+impl LifeStage {
+    /// Determine the life stage for a species at the given age in days
+    pub fn for_age(species: &PetSpecies, age_days: i64) -> Self {
+        let senior_from_years = match species {
+            PetSpecies::Cat => 11,
+            PetSpecies::Dog => 8,
+        };
+
+        if age_days < 56 {
+            LifeStage::Newborn
+        } else if age_days < 365 {
+            LifeStage::Juvenile
+        } else if age_days < senior_from_years * 365 {
+            LifeStage::Adult
+        } else {
+            LifeStage::Senior
+        }
+    }
+
+    /// Typical weight range in kg for this life stage
+    pub fn typical_weight_range(&self, species: &PetSpecies) -> (f32, f32) {
+        match (species, self) {
+            (PetSpecies::Cat, LifeStage::Newborn) => (0.05, 1.5),
+            (PetSpecies::Cat, LifeStage::Juvenile) => (0.3, 6.0),
+            (PetSpecies::Cat, _) => (1.5, 12.0),
+            (PetSpecies::Dog, LifeStage::Newborn) => (0.07, 8.0),
+            (PetSpecies::Dog, LifeStage::Juvenile) => (0.3, 70.0),
+            (PetSpecies::Dog, _) => (0.5, 100.0),
+        }
+    }
+
+    fn label(&self, species: &PetSpecies) -> &'static str {
+        match (species, self) {
+            (PetSpecies::Cat, LifeStage::Newborn | LifeStage::Juvenile) => "kitten",
+            (PetSpecies::Dog, LifeStage::Newborn | LifeStage::Juvenile) => "puppy",
+            (_, LifeStage::Adult) => "adult",
+            (_, LifeStage::Senior) => "senior",
+        }
+    }
+}
+
+/// Age of a pet in whole days on the given date
+pub fn age_in_days(birth_date: NaiveDate, on: NaiveDate) -> i64 {
+    (on - birth_date).num_days()
+}
+
+/// Validate pet fields that depend on birth date.
+/// Impossible dates are hard errors; unusual but possible values are returned as warnings.
+pub fn validate_pet_age_rules(
+    species: &PetSpecies,
+    birth_date: NaiveDate,
+    weight_kg: Option<f32>,
+    today: NaiveDate,
+) -> Result<ValidationOutcome, PetError> {
+    let mut outcome = ValidationOutcome::new();
+    let age_days = age_in_days(birth_date, today);
+
+    if age_days < 0 {
+        return Err(PetError::validation(
+            "birth_date",
+            "Birth date cannot be in the future",
+        ));
+    }
+
+    if age_days > MAX_PLAUSIBLE_AGE_YEARS * 365 {
+        return Err(PetError::validation(
+            "birth_date",
+            "Birth date is unrealistically far in the past",
+        ));
+    }
+
+    let typical_lifespan_years = match species {
+        PetSpecies::Cat => 25,
+        PetSpecies::Dog => 20,
+    };
+    if age_days > typical_lifespan_years * 365 {
+        outcome.warn(
+            "birth_date",
+            "AGE_EXCEEDS_LIFESPAN",
+            format!(
+                "Age is above the typical {species} lifespan of {typical_lifespan_years} years"
+            ),
+        );
+    }
+
+    if let Some(weight) = weight_kg {
+        outcome.merge(check_weight_for_age(species, age_days, weight, "weight_kg"));
+    }
+
+    Ok(outcome)
+}
+
+/// Validate an activity against the age of the pet it is recorded for.
+/// Activities dated before the pet was born are rejected.
+pub fn validate_activity_age_rules(
+    pet: &Pet,
+    request: &ActivityCreateRequest,
+    today: NaiveDate,
+) -> Result<ValidationOutcome, ActivityError> {
+    let mut outcome = ValidationOutcome::new();
+
+    let data: Option<ActivityData> = request
+        .activity_data
+        .clone()
+        .map(ActivityData::from_legacy_json);
+
+    let activity_date = data
+        .as_ref()
+        .and_then(|d| d.extract_activity_date())
+        .unwrap_or(today);
+
+    let age_days = age_in_days(pet.birth_date, activity_date);
+    if age_days < 0 {
+        return Err(ActivityError::date_out_of_range(format!(
+            "Activity date {activity_date} is before {}'s birth date {}",
+            pet.name, pet.birth_date
+        )));
+    }
+
+    if request.category == ActivityCategory::Health
+        && request.subcategory.to_lowercase().contains("vaccin")
+        && age_days < MIN_VACCINATION_AGE_DAYS
+    {
+        outcome.warn(
+            "subcategory",
+            "TOO_YOUNG_FOR_VACCINATION",
+            format!(
+                "Vaccinations are usually given from 6 weeks of age; pet is {age_days} days old"
+            ),
+        );
+    }
+
+    if let Some(weight) = data.as_ref().and_then(|d| d.extract_weight_kg()) {
+        outcome.merge(check_weight_for_age(
+            &pet.species,
+            age_days,
+            weight,
+            "weight",
+        ));
+    }
+
+    Ok(outcome)
+}
+
+/// Warn when a weight falls outside the typical range for the pet's life stage
+fn check_weight_for_age(
+    species: &PetSpecies,
+    age_days: i64,
+    weight_kg: f32,
+    field: &str,
+) -> ValidationOutcome {
+    let mut outcome = ValidationOutcome::new();
+    let stage = LifeStage::for_age(species, age_days);
+    let (min, max) = stage.typical_weight_range(species);
+
+    if weight_kg > max {
+        outcome.warn(
+            field,
+            "WEIGHT_ABOVE_AGE_RANGE",
+            format!(
+                "{weight_kg} kg is above the typical range for a {} {species} (max {max} kg)",
+                stage.label(species)
+            ),
+        );
+    } else if weight_kg > 0.0 && weight_kg < min {
+        outcome.warn(
+            field,
+            "WEIGHT_BELOW_AGE_RANGE",
+            format!(
+                "{weight_kg} kg is below the typical range for a {} {species} (min {min} kg)",
+                stage.label(species)
+            ),
+        );
+    }
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_future_birth_date_is_hard_error() {
+        let result = validate_pet_age_rules(
+            &PetSpecies::Cat,
+            date("2025-06-01"),
+            None,
+            date("2025-01-01"),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_heavy_kitten_produces_warning() {
+        let outcome = validate_pet_age_rules(
+            &PetSpecies::Cat,
+            date("2025-01-01"),
+            Some(8.0),
+            date("2025-04-01"),
+        )
+        .unwrap();
+
+        assert_eq!(outcome.warnings.len(), 1);
+        assert_eq!(outcome.warnings[0].code, "WEIGHT_ABOVE_AGE_RANGE");
+    }
+
+    #[test]
+    fn test_adult_weight_is_clean() {
+        let outcome = validate_pet_age_rules(
+            &PetSpecies::Dog,
+            date("2020-01-01"),
+            Some(25.0),
+            date("2025-01-01"),
+        )
+        .unwrap();
+
+        assert!(outcome.is_clean());
+    }
+
+    #[test]
+    fn test_life_stage_boundaries() {
+        assert_eq!(LifeStage::for_age(&PetSpecies::Cat, 10), LifeStage::Newborn);
+        assert_eq!(
+            LifeStage::for_age(&PetSpecies::Cat, 100),
+            LifeStage::Juvenile
+        );
+        assert_eq!(
+            LifeStage::for_age(&PetSpecies::Cat, 9 * 365),
+            LifeStage::Adult
+        );
+        assert_eq!(
+            LifeStage::for_age(&PetSpecies::Dog, 9 * 365),
+            LifeStage::Senior
+        );
+    }
+}
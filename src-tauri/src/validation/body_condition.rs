@@ -0,0 +1,59 @@
+use crate::database::activity_blocks::check_body_condition;
+use crate::database::activity_data::{ActivityData, BlockData};
+use crate::errors::ActivityError;
+
+/// Check the body condition blocks of incoming activity data: a whole score from
+/// 1 (emaciated) to 9 (obese)
+pub fn validate_body_condition_blocks(
+    activity_data: Option<&serde_json::Value>,
+) -> Result<(), ActivityError> {
+    let Some(json) = activity_data else {
+        return Ok(());
+    };
+    let data = ActivityData::from_legacy_json(json.clone());
+
+    for (key, block) in &data {
+        match block {
+            BlockData::BodyCondition { score } => check_body_condition(key, *score)?,
+            // Shaped like a body condition block but not readable as one, e.g. 4.5 or -1
+            BlockData::Other(value) if value.get("bodyConditionScore").is_some() => {
+                return Err(ActivityError::validation(
+                    key.as_str(),
+                    "Body condition must be a whole score from 1 to 9",
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_body_condition_block_validation() {
+        let check = |score: serde_json::Value| {
+            validate_body_condition_blocks(Some(&json!({
+                "notes": "Vet check",
+                "bcs": { "bodyConditionScore": score }
+            })))
+        };
+        assert!(check(json!(1)).is_ok());
+        assert!(check(json!(9)).is_ok());
+        assert!(validate_body_condition_blocks(None).is_ok());
+
+        for score in [
+            json!(0),
+            json!(10),
+            json!(4.5),
+            json!(-1),
+            json!(300),
+            json!("5"),
+        ] {
+            assert!(check(score.clone()).is_err(), "{score} should be rejected");
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use crate::database::activity_data::{ActivityData, ActivityDataExt, BlockData};
+use crate::database::{CustomBlockDefinition, CustomFieldDefinition, CustomFieldType};
+use crate::errors::ActivityError;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Longest block name or field key
+const MAX_NAME_LENGTH: usize = 40;
+
+/// Longest block or field label
+const MAX_LABEL_LENGTH: usize = 100;
+
+/// Most fields a custom block may have
+pub const MAX_CUSTOM_FIELDS: usize = 20;
+
+/// Longest text value when a text field sets no limit
+const DEFAULT_MAX_TEXT_LENGTH: usize = 1000;
+
+/// Check a custom block definition: a lowercase name and field keys (`a-z`, `0-9`,
+/// `_`), unique keys, ordered bounds and options for choice fields
+pub fn validate_custom_block_definition(
+    name: &str,
+    label: &str,
+    fields: &[CustomFieldDefinition],
+) -> Result<(), ActivityError> {
+    check_identifier("name", name)?;
+    check_label("label", label)?;
+    if fields.is_empty() || fields.len() > MAX_CUSTOM_FIELDS {
+        return Err(ActivityError::validation(
+            "fields",
+            &format!("A custom block needs 1 to {MAX_CUSTOM_FIELDS} fields"),
+        ));
+    }
+
+    let mut keys = HashSet::new();
+    for field in fields {
+        let path = format!("fields.{}", field.key);
+        check_identifier(&path, &field.key)?;
+        check_label(&path, &field.label)?;
+        if !keys.insert(field.key.as_str()) {
+            return Err(ActivityError::validation(
+                path,
+                "Field keys must be unique".to_string(),
+            ));
+        }
+        if field.min.is_some_and(|min| !min.is_finite())
+            || field.max.is_some_and(|max| !max.is_finite())
+            || field.min.zip(field.max).is_some_and(|(min, max)| min > max)
+        {
+            return Err(ActivityError::validation(
+                path,
+                "Bounds must be numbers with min at most max".to_string(),
+            ));
+        }
+        if field.max_length == Some(0) {
+            return Err(ActivityError::validation(
+                path,
+                "Maximum length must be at least 1".to_string(),
+            ));
+        }
+        if field.field_type == CustomFieldType::Choice
+            && field.options.iter().all(|option| option.trim().is_empty())
+        {
+            return Err(ActivityError::validation(
+                path,
+                "Choice fields need at least one option".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Check the custom block instances in incoming activity data against their
+/// definitions: the definition must exist, required fields must be set, and each
+/// value must have its field's type and stay within its bounds, length or options
+pub fn validate_custom_blocks(
+    activity_data: Option<&Value>,
+    definitions: &[CustomBlockDefinition],
+) -> Result<(), ActivityError> {
+    let Some(json) = activity_data else {
+        return Ok(());
+    };
+    let data = ActivityData::from_legacy_json(json.clone());
+
+    for (block_key, block) in &data {
+        let (custom_block, values) = match block {
+            BlockData::Custom {
+                custom_block,
+                values,
+            } => (custom_block, values),
+            // Shaped like an instance but not readable as one, e.g. values in a list
+            BlockData::Other(value) if value.get("customBlock").is_some() => {
+                return Err(ActivityError::validation(
+                    block_key.clone(),
+                    "A custom block needs a name and an object of values".to_string(),
+                ));
+            }
+            _ => continue,
+        };
+        let definition = definitions
+            .iter()
+            .find(|definition| definition.name == *custom_block)
+            .ok_or_else(|| {
+                ActivityError::validation(
+                    block_key.clone(),
+                    format!("Unknown custom block '{custom_block}'"),
+                )
+            })?;
+
+        if let Some(key) = values
+            .keys()
+            .find(|key| !definition.fields.iter().any(|field| field.key == **key))
+        {
+            return Err(ActivityError::validation(
+                format!("{block_key}.{key}"),
+                format!("'{}' has no field '{key}'", definition.label),
+            ));
+        }
+        for field in &definition.fields {
+            let path = format!("{block_key}.{}", field.key);
+            // Blank inputs count as not filled in
+            let value = values.get(&field.key).filter(|value| {
+                !matches!(value, Value::Null)
+                    && !matches!(value, Value::String(text) if text.trim().is_empty())
+            });
+            match value {
+                None if field.required => {
+                    return Err(ActivityError::validation(
+                        path,
+                        format!("{} is required", field.label),
+                    ));
+                }
+                None => {}
+                Some(value) => check_value(field, value)
+                    .map_err(|message| ActivityError::validation(path, message))?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check one value against its field; the error is the message to show
+fn check_value(field: &CustomFieldDefinition, value: &Value) -> Result<(), String> {
+    match field.field_type {
+        CustomFieldType::Text => {
+            let text = value
+                .as_str()
+                .ok_or_else(|| format!("{} must be text", field.label))?;
+            let limit = field.max_length.unwrap_or(DEFAULT_MAX_TEXT_LENGTH);
+            if text.chars().count() > limit {
+                return Err(format!(
+                    "{} cannot be longer than {limit} characters",
+                    field.label
+                ));
+            }
+        }
+        CustomFieldType::Number => {
+            // Number inputs may send their text
+            let number = match value {
+                Value::Number(number) => number.as_f64(),
+                Value::String(text) => text.trim().parse::<f64>().ok(),
+                _ => None,
+            }
+            .filter(|number| number.is_finite())
+            .ok_or_else(|| format!("{} must be a number", field.label))?;
+            let unit = field
+                .unit
+                .as_deref()
+                .map(|unit| format!(" {unit}"))
+                .unwrap_or_default();
+            if let Some(min) = field.min.filter(|min| number < *min) {
+                return Err(format!("{} must be at least {min}{unit}", field.label));
+            }
+            if let Some(max) = field.max.filter(|max| number > *max) {
+                return Err(format!("{} must be at most {max}{unit}", field.label));
+            }
+        }
+        CustomFieldType::Boolean => {
+            if !value.is_boolean() {
+                return Err(format!("{} must be true or false", field.label));
+            }
+        }
+        CustomFieldType::Date => {
+            let valid = value
+                .as_str()
+                .and_then(|text| text.get(..10))
+                .is_some_and(|day| chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d").is_ok());
+            if !valid {
+                return Err(format!("{} must be a date (YYYY-MM-DD)", field.label));
+            }
+        }
+        CustomFieldType::Choice => {
+            let choice = value.as_str().map(str::trim).unwrap_or_default();
+            if !field.options.iter().any(|option| option.trim() == choice) {
+                return Err(format!(
+                    "{} must be one of: {}",
+                    field.label,
+                    field.options.join(", ")
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_identifier(field: &str, value: &str) -> Result<(), ActivityError> {
+    let valid = value.len() <= MAX_NAME_LENGTH
+        && value.starts_with(|c: char| c.is_ascii_lowercase())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(ActivityError::validation(
+            field.to_string(),
+            format!(
+                "Use up to {MAX_NAME_LENGTH} lowercase letters, digits and underscores, starting with a letter"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+fn check_label(field: &str, label: &str) -> Result<(), ActivityError> {
+    let length = label.trim().chars().count();
+    if length == 0 || length > MAX_LABEL_LENGTH {
+        return Err(ActivityError::validation(
+            field.to_string(),
+            format!("Label must be 1 to {MAX_LABEL_LENGTH} characters"),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn field(key: &str, field_type: CustomFieldType) -> CustomFieldDefinition {
+        CustomFieldDefinition {
+            key: key.to_string(),
+            label: key.to_string(),
+            field_type,
+            required: false,
+            unit: None,
+            min: None,
+            max: None,
+            max_length: None,
+            options: Vec::new(),
+        }
+    }
+
+    fn insulin() -> CustomBlockDefinition {
+        CustomBlockDefinition {
+            id: 1,
+            name: "insulin".to_string(),
+            label: "Insulin".to_string(),
+            fields: vec![
+                CustomFieldDefinition {
+                    required: true,
+                    unit: Some("IU".to_string()),
+                    min: Some(0.0),
+                    max: Some(40.0),
+                    ..field("units", CustomFieldType::Number)
+                },
+                CustomFieldDefinition {
+                    options: vec!["left".to_string(), "right".to_string()],
+                    ..field("site", CustomFieldType::Choice)
+                },
+                CustomFieldDefinition {
+                    max_length: Some(20),
+                    ..field("remark", CustomFieldType::Text)
+                },
+                field("with_food", CustomFieldType::Boolean),
+            ],
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_custom_block_instances_follow_definition() {
+        let definitions = [insulin()];
+        let blocks = |values: Value| {
+            json!({
+                "notes": "Evening dose",
+                "insulin": { "customBlock": "insulin", "values": values }
+            })
+        };
+        let check = |values: Value| validate_custom_blocks(Some(&blocks(values)), &definitions);
+
+        assert!(check(json!({ "units": 4.5, "site": "left", "with_food": true })).is_ok());
+        assert!(check(json!({ "units": "12", "remark": "" })).is_ok());
+        assert!(validate_custom_blocks(None, &definitions).is_ok());
+
+        for values in [
+            json!({ "site": "left" }),
+            json!({ "units": " " }),
+            json!({ "units": 41 }),
+            json!({ "units": "lots" }),
+            json!({ "units": 2, "site": "neck" }),
+            json!({ "units": 2, "remark": "a remark far longer than twenty" }),
+            json!({ "units": 2, "with_food": "yes" }),
+            json!({ "units": 2, "dose": 3 }),
+        ] {
+            assert!(
+                check(values.clone()).is_err(),
+                "{values} should be rejected"
+            );
+        }
+
+        let unknown = json!({ "glucose": { "customBlock": "glucose", "values": {} } });
+        assert!(validate_custom_blocks(Some(&unknown), &definitions).is_err());
+        let malformed = json!({ "insulin": { "customBlock": "insulin", "values": [4] } });
+        assert!(validate_custom_blocks(Some(&malformed), &definitions).is_err());
+    }
+
+    #[test]
+    fn test_custom_block_definition_rules() {
+        let fields = insulin().fields;
+        assert!(validate_custom_block_definition("insulin", "Insulin", &fields).is_ok());
+        assert!(validate_custom_block_definition("Insulin", "Insulin", &fields).is_err());
+        assert!(validate_custom_block_definition("insulin", " ", &fields).is_err());
+        assert!(validate_custom_block_definition("insulin", "Insulin", &[]).is_err());
+
+        let duplicate = vec![fields[0].clone(), fields[0].clone()];
+        assert!(validate_custom_block_definition("insulin", "Insulin", &duplicate).is_err());
+        let bounds = vec![CustomFieldDefinition {
+            min: Some(5.0),
+            max: Some(1.0),
+            ..field("units", CustomFieldType::Number)
+        }];
+        assert!(validate_custom_block_definition("insulin", "Insulin", &bounds).is_err());
+        let no_options = vec![field("site", CustomFieldType::Choice)];
+        assert!(validate_custom_block_definition("insulin", "Insulin", &no_options).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::database::activity_data::{ActivityData, ActivityDataExt};
+use crate::database::ActivityCategory;
+use crate::errors::ActivityError;
+use chrono::{Months, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Settings key of the accepted activity date range
+pub const ACTIVITY_DATE_LIMITS_SETTING_KEY: &str = "activity_date_limits";
+
+/// Largest number of years a limit may be set to
+pub const MAX_DATE_LIMIT_YEARS: u32 = 100;
+
+/// How far from today an activity may be dated
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct DateLimit {
+    pub max_past_years: u32,
+    pub max_future_years: u32,
+}
+
+impl Default for DateLimit {
+    fn default() -> Self {
+        DateLimit {
+            max_past_years: 10,
+            max_future_years: 1,
+        }
+    }
+}
+
+/// Accepted activity dates, with overrides for categories that need a wider
+/// range (e.g. Health, to back-date an old adoption or vaccination record)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct ActivityDateLimits {
+    pub default: DateLimit,
+    pub overrides: HashMap<ActivityCategory, DateLimit>,
+}
+
+impl ActivityDateLimits {
+    /// Limit that applies to activities of `category`
+    pub fn for_category(&self, category: ActivityCategory) -> DateLimit {
+        self.overrides
+            .get(&category)
+            .copied()
+            .unwrap_or(self.default)
+    }
+
+    /// Check every limit is within `MAX_DATE_LIMIT_YEARS`
+    pub fn validate(&self) -> Result<(), ActivityError> {
+        let too_long = std::iter::once(&self.default)
+            .chain(self.overrides.values())
+            .any(|limit| {
+                limit.max_past_years > MAX_DATE_LIMIT_YEARS
+                    || limit.max_future_years > MAX_DATE_LIMIT_YEARS
+            });
+        if too_long {
+            return Err(ActivityError::validation(
+                "activity_date_limits",
+                &format!("Date limits can be at most {MAX_DATE_LIMIT_YEARS} years"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Reject an activity of `category` whose time block falls outside the
+    /// category's range around `today`. Activities without a date pass.
+    pub fn check(
+        &self,
+        category: ActivityCategory,
+        activity_data: Option<&serde_json::Value>,
+        today: NaiveDate,
+    ) -> Result<(), ActivityError> {
+        let Some(date) = activity_data
+            .map(|json| ActivityData::from_legacy_json(json.clone()))
+            .and_then(|data| data.extract_activity_date())
+        else {
+            return Ok(());
+        };
+
+        let limit = self.for_category(category);
+        let earliest = today - Months::new(limit.max_past_years * 12);
+        let latest = today + Months::new(limit.max_future_years * 12);
+        if date < earliest {
+            return Err(ActivityError::date_out_of_range(format!(
+                "Activity date {date} is more than {} years ago; {category} activities can be dated from {earliest}",
+                limit.max_past_years
+            )));
+        }
+        if date > latest {
+            return Err(ActivityError::date_out_of_range(format!(
+                "Activity date {date} is more than {} years ahead; {category} activities can be dated up to {latest}",
+                limit.max_future_years
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_overrides_widen_the_range() {
+        let today = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+        let dated = |date: &str| {
+            serde_json::json!({
+                "time": { "date": format!("{date}T09:00:00.000Z"), "time": "", "timezone": "" }
+            })
+        };
+        let adoption = dated("2013-05-20");
+
+        let mut limits = ActivityDateLimits::default();
+        assert!(limits
+            .check(ActivityCategory::Health, Some(&adoption), today)
+            .is_err());
+        assert!(limits
+            .check(ActivityCategory::Diet, Some(&dated("2026-07-01")), today)
+            .is_err());
+        assert!(limits
+            .check(ActivityCategory::Diet, Some(&dated("2015-06-01")), today)
+            .is_ok());
+        assert!(limits.check(ActivityCategory::Diet, None, today).is_ok());
+
+        limits.overrides.insert(
+            ActivityCategory::Health,
+            DateLimit {
+                max_past_years: 20,
+                max_future_years: 1,
+            },
+        );
+        assert!(limits
+            .check(ActivityCategory::Health, Some(&adoption), today)
+            .is_ok());
+        assert!(limits
+            .check(ActivityCategory::Lifestyle, Some(&adoption), today)
+            .is_err());
+
+        limits.default.max_past_years = MAX_DATE_LIMIT_YEARS + 1;
+        assert!(limits.validate().is_err());
+    }
+}
@@ -0,0 +1,80 @@
+use crate::database::activity_blocks::{MAX_SESSION_KM, MAX_SESSION_MINUTES};
+use crate::database::activity_data::{
+    distance_km, duration_minutes, ActivityData, ActivityDataExt, BlockData,
+};
+use crate::errors::ActivityError;
+
+/// Check the duration and distance blocks of incoming activity data: amounts must be
+/// non-negative, units convertible, and one session at most a day or 200 km long
+pub fn validate_exercise_blocks(
+    activity_data: Option<&serde_json::Value>,
+) -> Result<(), ActivityError> {
+    let Some(json) = activity_data else {
+        return Ok(());
+    };
+    let data = ActivityData::from_legacy_json(json.clone());
+
+    if let Some(BlockData::Duration { amount, unit, .. }) = data.get("duration") {
+        let minutes = checked_amount("duration", *amount, unit, duration_minutes)?;
+        if minutes > MAX_SESSION_MINUTES {
+            return Err(ActivityError::validation(
+                "duration",
+                "Duration cannot be longer than 24 hours",
+            ));
+        }
+    }
+    if let Some(BlockData::Distance { amount, unit, .. }) = data.get("distance") {
+        let km = checked_amount("distance", *amount, unit, distance_km)?;
+        if km > MAX_SESSION_KM {
+            return Err(ActivityError::validation(
+                "distance",
+                &format!("Distance cannot be longer than {MAX_SESSION_KM} km"),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Amount converted to the block's base unit
+fn checked_amount(
+    field: &str,
+    amount: f32,
+    unit: &str,
+    convert: fn(f32, &str) -> Option<f32>,
+) -> Result<f32, ActivityError> {
+    if !amount.is_finite() || amount < 0.0 {
+        return Err(ActivityError::validation(
+            field,
+            "Amount must be a non-negative number",
+        ));
+    }
+    convert(amount, unit)
+        .ok_or_else(|| ActivityError::validation(field, &format!("Unsupported unit '{unit}'")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exercise_block_validation() {
+        let blocks = |duration: serde_json::Value| {
+            serde_json::json!({
+                "duration": duration,
+                "distance": { "amount": 3, "unit": "km", "distanceType": "walk" }
+            })
+        };
+        let valid =
+            blocks(serde_json::json!({ "amount": 2, "unit": "hours", "durationType": "hike" }));
+        assert!(validate_exercise_blocks(Some(&valid)).is_ok());
+        assert!(validate_exercise_blocks(None).is_ok());
+
+        for duration in [
+            serde_json::json!({ "amount": 30, "unit": "laps", "durationType": "swim" }),
+            serde_json::json!({ "amount": -5, "unit": "min", "durationType": "walk" }),
+            serde_json::json!({ "amount": 30, "unit": "h", "durationType": "walk" }),
+        ] {
+            assert!(validate_exercise_blocks(Some(&blocks(duration))).is_err());
+        }
+    }
+}
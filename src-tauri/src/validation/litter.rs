@@ -0,0 +1,64 @@
+use crate::database::activity_blocks::check_litter;
+use crate::database::activity_data::{ActivityData, ActivityDataExt, BlockData};
+use crate::errors::ActivityError;
+
+/// Check the litter blocks of incoming activity data: a known litter type, a count
+/// of 1 to 20, and a 1-7 consistency score on stool only
+pub fn validate_litter_blocks(
+    activity_data: Option<&serde_json::Value>,
+) -> Result<(), ActivityError> {
+    let Some(json) = activity_data else {
+        return Ok(());
+    };
+    let data = ActivityData::from_legacy_json(json.clone());
+
+    for (key, block) in &data {
+        match block {
+            BlockData::Litter {
+                litter_type,
+                count,
+                consistency,
+            } => {
+                check_litter(key, litter_type, *count, *consistency)?;
+            }
+            // Shaped like a litter block but not readable as one, e.g. a count of 1.5
+            BlockData::Other(value) if value.get("litterType").is_some() => {
+                return Err(ActivityError::validation(
+                    key.as_str(),
+                    "Count and consistency must be whole numbers",
+                ));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_litter_block_validation() {
+        let check = |litter: serde_json::Value| {
+            validate_litter_blocks(Some(&json!({ "notes": "Morning scoop", "litter": litter })))
+        };
+        assert!(check(json!({ "litterType": "both", "count": 2, "consistency": 3 })).is_ok());
+        assert!(check(json!({ "litterType": "urine" })).is_ok());
+        assert!(validate_litter_blocks(None).is_ok());
+
+        for litter in [
+            json!({ "litterType": "vomit" }),
+            json!({ "litterType": "urine", "count": 0 }),
+            json!({ "litterType": "urine", "count": 1.5 }),
+            json!({ "litterType": "urine", "consistency": 4 }),
+            json!({ "litterType": "stool", "consistency": 9 }),
+        ] {
+            assert!(
+                check(litter.clone()).is_err(),
+                "{litter} should be rejected"
+            );
+        }
+    }
+}
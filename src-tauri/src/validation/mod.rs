@@ -1,3 +1,17 @@
+pub mod age;
+pub mod body_condition;
+pub mod custom_blocks;
+pub mod date_limits;
+pub mod exercise;
+pub mod litter;
+pub mod outcome;
 pub mod pet;
 
+pub use age::*;
+pub use body_condition::*;
+pub use custom_blocks::*;
+pub use date_limits::*;
+pub use exercise::*;
+pub use litter::*;
+pub use outcome::*;
 pub use pet::*;
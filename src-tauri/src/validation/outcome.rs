@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// A non-blocking validation finding.
+/// Warnings are returned to the caller alongside a successful operation,
+/// while hard errors abort the operation through the domain error types.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ValidationWarning {
+    pub field: String,
+    pub code: String,
+    pub message: String,
+}
+
+/// Collected warnings produced by soft validation rules
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ValidationOutcome {
+    pub warnings: Vec<ValidationWarning>,
+}
+
+impl ValidationOutcome {
+    /// Create an empty outcome
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new warning
+    pub fn warn(
+        &mut self,
+        field: impl Into<String>,
+        code: impl Into<String>,
+        message: impl Into<String>,
+    ) {
+        self.warnings.push(ValidationWarning {
+            field: field.into(),
+            code: code.into(),
+            message: message.into(),
+        });
+    }
+
+    /// Append all warnings from another outcome
+    pub fn merge(&mut self, other: ValidationOutcome) {
+        self.warnings.extend(other.warnings);
+    }
+
+    /// Check if no warnings were produced
+    pub fn is_clean(&self) -> bool {
+        self.warnings.is_empty()
+    }
+}
+
+/// Response wrapper that returns soft validation results alongside created or updated data.
+/// The wrapped value is flattened so existing fields stay at the top level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithValidation<T> {
+    #[serde(flatten)]
+    pub data: T,
+    pub validation: ValidationOutcome,
+}
+
+impl<T> WithValidation<T> {
+    pub fn new(data: T, validation: ValidationOutcome) -> Self {
+        WithValidation { data, validation }
+    }
+}
@@ -170,6 +170,40 @@ pub fn validate_photo_path(path: &str) -> Result<(), PetError> {
     Ok(())
 }
 
+/// Most alternate names a pet can have
+pub const MAX_PET_ALIASES: usize = 20;
+
+/// Validate a pet's list of alternate names
+pub fn validate_pet_aliases(aliases: &[String]) -> Result<(), PetError> {
+    if aliases.len() > MAX_PET_ALIASES {
+        return Err(PetError::validation(
+            "aliases".to_string(),
+            format!("A pet can have at most {MAX_PET_ALIASES} aliases"),
+        ));
+    }
+
+    for alias in aliases {
+        let trimmed = alias.trim();
+        if trimmed.is_empty() {
+            return Err(PetError::validation("aliases", "Alias cannot be empty"));
+        }
+        if trimmed.chars().count() > 100 {
+            return Err(PetError::validation(
+                "aliases",
+                "Alias cannot exceed 100 characters",
+            ));
+        }
+        if trimmed.chars().any(|c| c.is_control()) {
+            return Err(PetError::validation(
+                "aliases",
+                "Alias contains invalid characters",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Validate display order list for reordering
 pub fn validate_reorder_list(pet_ids: &[i64]) -> Result<(), PetError> {
     if pet_ids.is_empty() {
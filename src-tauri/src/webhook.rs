@@ -0,0 +1,319 @@
+use crate::errors::PetError;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Settings key the automation webhook is stored under
+pub const WEBHOOK_SETTINGS_KEY: &str = "webhook_settings";
+
+/// Event name sent with newly created activities
+pub const ACTIVITY_CREATED_EVENT: &str = "activity.created";
+
+/// Longest a single delivery may take, connecting included
+pub const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Deliveries are given up after this many failed attempts
+pub const MAX_DELIVERY_ATTEMPTS: i64 = 8;
+
+/// Wait before the first retry; doubled after every further failure
+const FIRST_RETRY_DELAY_SECS: i64 = 30;
+
+/// Longest wait between retries
+const MAX_RETRY_DELAY_SECS: i64 = 60 * 60;
+
+/// Local automation hook that receives new activities (e.g. Home Assistant)
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    /// `http://` URL on this machine or the local network
+    pub url: Option<String>,
+}
+
+impl WebhookSettings {
+    /// An enabled webhook needs a valid URL
+    pub fn validate(&self) -> Result<(), PetError> {
+        match &self.url {
+            Some(url) => WebhookEndpoint::parse(url).map(|_| ()),
+            None if self.enabled => Err(PetError::validation(
+                "url",
+                "A URL is required to enable the webhook",
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Endpoint to deliver to, None when the webhook is off
+    pub fn endpoint(&self) -> Option<WebhookEndpoint> {
+        self.url
+            .as_deref()
+            .filter(|_| self.enabled)
+            .and_then(|url| WebhookEndpoint::parse(url).ok())
+    }
+}
+
+/// Host, port and path of a plain-HTTP webhook URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebhookEndpoint {
+    pub host: String,
+    pub port: u16,
+    /// Path with query string, always starting with `/`
+    pub path: String,
+}
+
+impl WebhookEndpoint {
+    /// Parse an `http://host[:port][/path]` URL
+    pub fn parse(url: &str) -> Result<Self, PetError> {
+        let invalid = |message: &str| PetError::validation("url", message);
+
+        let rest = url
+            .trim()
+            .strip_prefix("http://")
+            .ok_or_else(|| invalid("Only http:// URLs on the local network are supported"))?;
+        let (authority, path) = match rest.find(['/', '?']) {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        if authority.contains('@') {
+            return Err(invalid("Credentials in the URL are not supported"));
+        }
+
+        let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+            // IPv6 literal, e.g. [::1]:8123
+            let (host, after) = bracketed
+                .split_once(']')
+                .ok_or_else(|| invalid("Invalid IPv6 address"))?;
+            (host, after.strip_prefix(':'))
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+        if host.is_empty() || host.contains(char::is_whitespace) {
+            return Err(invalid("The URL has no host"));
+        }
+        let port = match port {
+            Some(port) => port.parse().map_err(|_| invalid("Invalid port"))?,
+            None => 80,
+        };
+        let path = if path.starts_with('?') {
+            format!("/{path}")
+        } else {
+            path.to_string()
+        };
+        if path.contains(char::is_whitespace) {
+            return Err(invalid("The URL path can't contain spaces"));
+        }
+
+        Ok(WebhookEndpoint {
+            host: host.to_string(),
+            port,
+            path,
+        })
+    }
+
+    /// Value of the Host header
+    fn host_header(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        if self.port == 80 {
+            host
+        } else {
+            format!("{host}:{}", self.port)
+        }
+    }
+}
+
+/// Outcome of `test_webhook`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookTestResult {
+    pub success: bool,
+    /// HTTP status returned by the receiver
+    pub status: Option<u16>,
+    pub elapsed_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Addresses the webhook may be delivered to: this machine and private networks
+fn is_local_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => {
+            ip.is_loopback()
+                // Unique local (fc00::/7) and link-local (fe80::/10) ranges
+                || (ip.segments()[0] & 0xfe00) == 0xfc00
+                || (ip.segments()[0] & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// POST a JSON body and return the HTTP status code.
+/// Hosts that resolve outside the local network are refused.
+pub async fn post_json(endpoint: &WebhookEndpoint, body: &[u8]) -> Result<u16, String> {
+    tokio::time::timeout(WEBHOOK_TIMEOUT, send(endpoint, body))
+        .await
+        .map_err(|_| format!("No response within {} seconds", WEBHOOK_TIMEOUT.as_secs()))?
+}
+
+async fn send(endpoint: &WebhookEndpoint, body: &[u8]) -> Result<u16, String> {
+    let addresses: Vec<_> = tokio::net::lookup_host((endpoint.host.as_str(), endpoint.port))
+        .await
+        .map_err(|e| format!("Could not resolve {}: {e}", endpoint.host))?
+        .collect();
+    let address = addresses
+        .iter()
+        .find(|address| is_local_address(address.ip()))
+        .ok_or_else(|| format!("{} is not on the local network", endpoint.host))?;
+
+    let mut stream = TcpStream::connect(address)
+        .await
+        .map_err(|e| format!("Could not connect to {address}: {e}"))?;
+    let head = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nUser-Agent: paw-diary\r\nConnection: close\r\n\r\n",
+        endpoint.path,
+        endpoint.host_header(),
+        body.len()
+    );
+    stream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to send request: {e}"))?;
+    stream
+        .write_all(body)
+        .await
+        .map_err(|e| format!("Failed to send request: {e}"))?;
+
+    // Only the status line matters; the rest of the response is ignored
+    let mut response = Vec::new();
+    let mut buffer = [0u8; 512];
+    while !response.windows(2).any(|w| w == b"\r\n") {
+        let read = stream
+            .read(&mut buffer)
+            .await
+            .map_err(|e| format!("Failed to read response: {e}"))?;
+        if read == 0 || response.len() > 8 * 1024 {
+            break;
+        }
+        response.extend_from_slice(&buffer[..read]);
+    }
+    parse_status_line(&response).ok_or_else(|| "Invalid HTTP response".to_string())
+}
+
+fn parse_status_line(response: &[u8]) -> Option<u16> {
+    let line = response.split(|&b| b == b'\r').next()?;
+    let line = std::str::from_utf8(line).ok()?;
+    let mut parts = line.split(' ');
+    parts
+        .next()
+        .filter(|version| version.starts_with("HTTP/"))?;
+    parts.next()?.parse().ok()
+}
+
+/// Send a sample payload to check the receiver is reachable
+pub async fn test_endpoint(endpoint: &WebhookEndpoint) -> WebhookTestResult {
+    let body = serde_json::json!({
+        "event": "test",
+        "message": "Test message from Paw Diary",
+        "sent_at": chrono::Utc::now(),
+    });
+    let started = Instant::now();
+    let outcome = post_json(endpoint, body.to_string().as_bytes()).await;
+    let elapsed_ms = started.elapsed().as_millis() as u64;
+
+    match outcome {
+        Ok(status) => WebhookTestResult {
+            success: (200..300).contains(&status),
+            status: Some(status),
+            elapsed_ms,
+            error: (!(200..300).contains(&status))
+                .then(|| format!("The receiver answered with HTTP {status}")),
+        },
+        Err(error) => WebhookTestResult {
+            success: false,
+            status: None,
+            elapsed_ms,
+            error: Some(error),
+        },
+    }
+}
+
+/// Wait before retrying a delivery that has failed `attempts` times
+pub fn retry_delay(attempts: i64) -> chrono::Duration {
+    let exponent = attempts.saturating_sub(1).clamp(0, 20) as u32;
+    let seconds = FIRST_RETRY_DELAY_SECS
+        .saturating_mul(2i64.pow(exponent))
+        .min(MAX_RETRY_DELAY_SECS);
+    chrono::Duration::seconds(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_endpoint() {
+        let endpoint =
+            WebhookEndpoint::parse("http://homeassistant.local:8123/api/webhook/feed?x=1").unwrap();
+        assert_eq!(endpoint.host, "homeassistant.local");
+        assert_eq!(endpoint.port, 8123);
+        assert_eq!(endpoint.path, "/api/webhook/feed?x=1");
+        assert_eq!(endpoint.host_header(), "homeassistant.local:8123");
+
+        let endpoint = WebhookEndpoint::parse("http://[::1]").unwrap();
+        assert_eq!((endpoint.host.as_str(), endpoint.port), ("::1", 80));
+        assert_eq!(endpoint.path, "/");
+
+        assert!(WebhookEndpoint::parse("https://example.com/hook").is_err());
+        assert!(WebhookEndpoint::parse("http://user:pw@localhost/").is_err());
+        assert!(WebhookEndpoint::parse("http://localhost:99999/").is_err());
+        assert!(WebhookSettings {
+            enabled: true,
+            url: None
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off() {
+        assert_eq!(retry_delay(1).num_seconds(), 30);
+        assert_eq!(retry_delay(3).num_seconds(), 120);
+        assert_eq!(retry_delay(50).num_seconds(), MAX_RETRY_DELAY_SECS);
+        assert!(!is_local_address("8.8.8.8".parse().unwrap()));
+        assert!(is_local_address("192.168.1.20".parse().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_post_json_to_local_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).contains("\"ok\":true") {
+                let read = socket.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let endpoint = WebhookEndpoint::parse(&format!("http://127.0.0.1:{port}/feed")).unwrap();
+        let status = post_json(&endpoint, br#"{"ok":true}"#).await.unwrap();
+        assert_eq!(status, 204);
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /feed HTTP/1.1\r\n"));
+        assert!(request.contains("Content-Length: 11\r\n"));
+    }
+}
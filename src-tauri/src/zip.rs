@@ -0,0 +1,217 @@
+//! Minimal ZIP writer for the shared photo album.
+//!
+//! Entries are stored without compression: photos are already compressed, and
+//! deflating them again only costs time. Names are marked as UTF-8. ZIP64 isn't
+//! supported, so an archive and each entry stay under 4 GB and 65535 entries.
+
+use chrono::{Datelike, NaiveDateTime, Timelike};
+use std::io::{self, Write};
+
+const LOCAL_HEADER_SIGNATURE: u32 = 0x0403_4b50;
+const CENTRAL_HEADER_SIGNATURE: u32 = 0x0201_4b50;
+const END_OF_DIRECTORY_SIGNATURE: u32 = 0x0605_4b50;
+
+/// Version 2.0 of the format, needed for directories in names
+const VERSION: u16 = 20;
+
+/// General purpose flag marking names as UTF-8
+const UTF8_NAMES: u16 = 1 << 11;
+
+/// CRC-32 (IEEE) lookup table
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xEDB8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC-32 checksum as ZIP stores it
+pub fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0u32, |crc, &byte| {
+        CRC_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// An entry written so far, for the central directory
+struct CentralEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    time: u16,
+    date: u16,
+    offset: u32,
+}
+
+/// Writes a ZIP archive entry by entry
+pub struct ZipWriter<W: Write> {
+    out: W,
+    offset: u64,
+    entries: Vec<CentralEntry>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    pub fn new(out: W) -> Self {
+        ZipWriter {
+            out,
+            offset: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a file; `name` may contain `/` to put it in a folder
+    pub fn add(&mut self, name: &str, bytes: &[u8], modified: NaiveDateTime) -> io::Result<()> {
+        let size = u32::try_from(bytes.len())
+            .map_err(|_| too_large(&format!("{name} is larger than 4 GB")))?;
+        let offset =
+            u32::try_from(self.offset).map_err(|_| too_large("the album is larger than 4 GB"))?;
+        if self.entries.len() == usize::from(u16::MAX) {
+            return Err(too_large("the album has more than 65535 files"));
+        }
+        let name_length = u16::try_from(name.len())
+            .map_err(|_| too_large(&format!("the name {name} is too long")))?;
+        let (time, date) = dos_date_time(modified);
+        let crc = crc32(bytes);
+
+        let mut header = Vec::with_capacity(30 + name.len());
+        header.extend(LOCAL_HEADER_SIGNATURE.to_le_bytes());
+        header.extend(VERSION.to_le_bytes());
+        header.extend(UTF8_NAMES.to_le_bytes());
+        header.extend(0u16.to_le_bytes()); // stored
+        header.extend(time.to_le_bytes());
+        header.extend(date.to_le_bytes());
+        header.extend(crc.to_le_bytes());
+        header.extend(size.to_le_bytes()); // compressed size
+        header.extend(size.to_le_bytes());
+        header.extend(name_length.to_le_bytes());
+        header.extend(0u16.to_le_bytes()); // extra field length
+        header.extend(name.as_bytes());
+        self.out.write_all(&header)?;
+        self.out.write_all(bytes)?;
+        self.offset += (header.len() + bytes.len()) as u64;
+
+        self.entries.push(CentralEntry {
+            name: name.to_string(),
+            crc,
+            size,
+            time,
+            date,
+            offset,
+        });
+        Ok(())
+    }
+
+    /// Write the central directory and return the output
+    pub fn finish(mut self) -> io::Result<W> {
+        let directory_offset =
+            u32::try_from(self.offset).map_err(|_| too_large("the album is larger than 4 GB"))?;
+        let mut directory = Vec::new();
+        for entry in &self.entries {
+            directory.extend(CENTRAL_HEADER_SIGNATURE.to_le_bytes());
+            directory.extend(VERSION.to_le_bytes()); // made by
+            directory.extend(VERSION.to_le_bytes()); // needed to extract
+            directory.extend(UTF8_NAMES.to_le_bytes());
+            directory.extend(0u16.to_le_bytes()); // stored
+            directory.extend(entry.time.to_le_bytes());
+            directory.extend(entry.date.to_le_bytes());
+            directory.extend(entry.crc.to_le_bytes());
+            directory.extend(entry.size.to_le_bytes());
+            directory.extend(entry.size.to_le_bytes());
+            directory.extend((entry.name.len() as u16).to_le_bytes());
+            directory.extend([0u8; 12]); // extra, comment, disk, attributes
+            directory.extend(entry.offset.to_le_bytes());
+            directory.extend(entry.name.as_bytes());
+        }
+        let directory_size = u32::try_from(directory.len())
+            .map_err(|_| too_large("the album directory is larger than 4 GB"))?;
+        let count = self.entries.len() as u16;
+
+        directory.extend(END_OF_DIRECTORY_SIGNATURE.to_le_bytes());
+        directory.extend([0u8; 4]); // disk numbers
+        directory.extend(count.to_le_bytes());
+        directory.extend(count.to_le_bytes());
+        directory.extend(directory_size.to_le_bytes());
+        directory.extend(directory_offset.to_le_bytes());
+        directory.extend(0u16.to_le_bytes()); // comment length
+        self.out.write_all(&directory)?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+fn too_large(what: &str) -> io::Error {
+    io::Error::other(format!("Can't write the ZIP file: {what}"))
+}
+
+/// MS-DOS time and date, which can't go before 1980
+fn dos_date_time(at: NaiveDateTime) -> (u16, u16) {
+    if at.year() < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+    let time = (at.hour() << 11) | (at.minute() << 5) | (at.second() / 2);
+    let date = ((at.year() as u32 - 1980).min(127) << 9) | (at.month() << 5) | at.day();
+    (time as u16, date as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], at: usize) -> u16 {
+        u16::from_le_bytes([bytes[at], bytes[at + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_archive_layout() {
+        let at = NaiveDateTime::parse_from_str("2025-06-01 14:30:10", "%Y-%m-%d %H:%M:%S").unwrap();
+        let mut zip = ZipWriter::new(Vec::new());
+        zip.add("index.html", b"<html></html>", at).unwrap();
+        zip.add("photos/2025-06-01_散步.jpg", &[0xFF, 0xD8, 0xFF], at)
+            .unwrap();
+        let bytes = zip.finish().unwrap();
+
+        assert_eq!(u32_at(&bytes, 0), LOCAL_HEADER_SIGNATURE);
+        assert_eq!(u32_at(&bytes, 14), crc32(b"<html></html>"));
+        assert_eq!(u16_at(&bytes, 10), (14 << 11) | (30 << 5) | 5);
+        assert_eq!(u16_at(&bytes, 12), (45 << 9) | (6 << 5) | 1);
+
+        let end = bytes.len() - 22;
+        assert_eq!(u32_at(&bytes, end), END_OF_DIRECTORY_SIGNATURE);
+        assert_eq!(u16_at(&bytes, end + 10), 2);
+        let directory = u32_at(&bytes, end + 16) as usize;
+        assert_eq!(u32_at(&bytes, directory), CENTRAL_HEADER_SIGNATURE);
+        assert_eq!(directory + u32_at(&bytes, end + 12) as usize, end);
+
+        // The second entry's local header is where the directory says
+        let second = directory + 46 + "index.html".len();
+        let offset = u32_at(&bytes, second + 42) as usize;
+        assert_eq!(u32_at(&bytes, offset), LOCAL_HEADER_SIGNATURE);
+        let name_length = u16_at(&bytes, offset + 26) as usize;
+        assert_eq!(
+            std::str::from_utf8(&bytes[offset + 30..offset + 30 + name_length]).unwrap(),
+            "photos/2025-06-01_散步.jpg"
+        );
+    }
+}
@@ -5,6 +5,7 @@ import { Loader2 } from 'lucide-react';
 import { useAppState } from './hooks/useAppState';
 import { ToastProvider } from './components/ui/toast';
 import { HeaderProvider } from './components/header';
+import type { InitReport } from './lib/types';
 import { HomePage, AddPetPage, PetProfilePage, EditPetPage, ActivitiesListPage, ActivityEditorPage } from './pages';
 import './App.css';
 
@@ -66,8 +67,16 @@ function App() {
         actions.startInitialization();
 
         console.log('Calling initialize_app command...');
-        const result = await invoke('initialize_app');
-        console.log('initialize_app result:', result);
+        const report = await invoke<InitReport>('initialize_app');
+        console.log('initialize_app result:', report);
+
+        // The database could not be opened: nothing to show until it is restored
+        if (report.status === 'needs_restore' && !report.read_only) {
+          const problems = report.checks
+            .filter(check => check.status === 'needs_restore')
+            .map(check => check.message);
+          throw new Error(problems.join('\n') || 'Your data needs to be restored from a backup');
+        }
 
         actions.completeInitialization();
         console.log('Initialization successful');
@@ -111,12 +111,7 @@ const TimeBlock: React.FC<BlockProps<TimeBlockConfig>> = ({
       return 'Date and time cannot be in the future';
     }
     
-    // Check if date is too far in the past (more than 10 years)
-    const tenYearsAgo = new Date();
-    tenYearsAgo.setFullYear(tenYearsAgo.getFullYear() - 10);
-    if (date < tenYearsAgo) {
-      return 'Date cannot be more than 10 years ago';
-    }
+    // How far back a date may go is a setting, checked when the activity is saved
     
     return true;
   };
@@ -63,7 +63,7 @@ export function PetPhoto({ pet, size, className }: PetPhotoProps) {
   };
   
   // Determine photo source
-  const hasValidPhoto = pet.photo_path && !imageError;
+  const hasValidPhoto = pet.photoPath && !imageError;
   
   const photoClasses = cn(
     BASE_PHOTO_STYLES,
@@ -85,7 +85,7 @@ export function PetPhoto({ pet, size, className }: PetPhotoProps) {
             </div>
           )}
           <img
-            src={pet.photo_path}
+            src={pet.photoPath}
             alt={`${pet.name}'s profile photo`}
             className={cn(
               'h-full w-full object-cover',
@@ -74,7 +74,7 @@ export interface Pet {
   id: number;
   name: string;
   species: string;
-  photo_path?: string;
+  photoPath?: string;
 }
 
 export interface BreadcrumbItem {
@@ -22,7 +22,7 @@ interface PetCardProps {
  */
 export function PetCard({ pet, onClick, className }: PetCardProps) {
   // Use photo state hook to get the correct photo URL
-  const { photoUrl, isLoading } = usePhotoState(pet.photo_path);
+  const { photoUrl, isLoading } = usePhotoState(pet.photoPath);
 
   // Calculate age from birth date
   const calculateAge = (birthDate: string): string => {
@@ -44,7 +44,7 @@ export function PetCard({ pet, onClick, className }: PetCardProps) {
     return `${years}y ${months}m`;
   };
 
-  const age = calculateAge(pet.birth_date);
+  const age = calculateAge(pet.birthDate);
   const SpeciesIcon = pet.species === PetSpecies.Dog ? Dog : Cat;
 
   return (
@@ -70,7 +70,7 @@ export function PetCard({ pet, onClick, className }: PetCardProps) {
                     <SpeciesIcon className="w-14 h-14 text-orange-300" strokeWidth={1.5} />
                   </div>
                 </div>
-              ) : pet.photo_path && photoUrl ? (
+              ) : pet.photoPath && photoUrl ? (
                 <img
                   src={photoUrl}
                   alt={pet.name}
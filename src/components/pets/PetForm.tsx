@@ -61,12 +61,12 @@ export function PetForm({ pet, open, onOpenChange, onSubmit, isSubmitting = fals
     defaultValues: isEditing
       ? {
           name: pet?.name,
-          birth_date: pet?.birth_date,
+          birthDate: pet?.birthDate,
           species: pet?.species,
           gender: pet?.gender,
           breed: pet?.breed || '',
           color: pet?.color || '',
-          weight_kg: pet?.weight_kg || undefined,
+          weightKg: pet?.weightKg || undefined,
           notes: pet?.notes || '',
         }
       : {
@@ -84,10 +84,10 @@ export function PetForm({ pet, open, onOpenChange, onSubmit, isSubmitting = fals
 
   // Load existing photo for editing
   useEffect(() => {
-    if (isEditing && pet?.photo_path) {
+    if (isEditing && pet?.photoPath) {
       // Use custom photos:// protocol instead of asset://
       // This works on iOS where asset:// is restricted to bundled resources
-      setPhotoPreview(`photos://localhost/${pet.photo_path}`);
+      setPhotoPreview(`photos://localhost/${pet.photoPath}`);
     }
   }, [isEditing, pet]);
 
@@ -127,7 +127,7 @@ export function PetForm({ pet, open, onOpenChange, onSubmit, isSubmitting = fals
 
   const handleFormSubmit = async (data: PetFormData) => {
     try {
-      let photoPath = pet?.photo_path;
+      let photoPath = pet?.photoPath;
 
       // Upload new photo if one was selected
       if (photoFile) {
@@ -137,14 +137,14 @@ export function PetForm({ pet, open, onOpenChange, onSubmit, isSubmitting = fals
       // Prepare form data
       const formData = {
         name: data.name,
-        birth_date: data.birth_date,
+        birthDate: data.birthDate,
         species: data.species,
         gender: data.gender,
         breed: data.breed || undefined,
         color: data.color || undefined,
-        weight_kg: typeof data.weight_kg === 'number' ? data.weight_kg : undefined,
+        weightKg: typeof data.weightKg === 'number' ? data.weightKg : undefined,
         notes: data.notes || undefined,
-        photo_path: photoPath,
+        photoPath: photoPath,
       };
 
       await onSubmit(formData);
@@ -251,18 +251,18 @@ export function PetForm({ pet, open, onOpenChange, onSubmit, isSubmitting = fals
 
             {/* Birth Date */}
             <div className="space-y-2">
-              <Label htmlFor="birth_date">Birth Date *</Label>
+              <Label htmlFor="birthDate">Birth Date *</Label>
               <Input
-                id="birth_date"
+                id="birthDate"
                 type="date"
-                {...register('birth_date')}
+                {...register('birthDate')}
                 className={cn(
                   'max-w-full [&::-webkit-date-and-time-value]:text-left',
-                  errors.birth_date && 'border-red-500',
+                  errors.birthDate && 'border-red-500',
                 )}
               />
-              {errors.birth_date && (
-                <p className="text-sm text-red-600">{errors.birth_date.message}</p>
+              {errors.birthDate && (
+                <p className="text-sm text-red-600">{errors.birthDate.message}</p>
               )}
             </div>
 
@@ -355,19 +355,19 @@ export function PetForm({ pet, open, onOpenChange, onSubmit, isSubmitting = fals
 
             {/* Weight */}
             <div className="space-y-2 md:col-span-2">
-              <Label htmlFor="weight_kg">Weight (kg)</Label>
+              <Label htmlFor="weightKg">Weight (kg)</Label>
               <Input
-                id="weight_kg"
+                id="weightKg"
                 type="number"
                 step="0.01"
                 min="0"
                 max="200"
-                {...register('weight_kg', { valueAsNumber: true })}
+                {...register('weightKg', { valueAsNumber: true })}
                 placeholder="Enter weight in kg (optional)"
-                className={cn(errors.weight_kg && 'border-red-500')}
+                className={cn(errors.weightKg && 'border-red-500')}
               />
-              {errors.weight_kg && (
-                <p className="text-sm text-red-600">{errors.weight_kg.message}</p>
+              {errors.weightKg && (
+                <p className="text-sm text-red-600">{errors.weightKg.message}</p>
               )}
             </div>
           </div>
@@ -23,7 +23,7 @@ export function PetProfile({
   className,
   disableVerticalScroll = false,
 }: PetProfileProps) {
-  const age = calculateAge(pet.birth_date);
+  const age = calculateAge(pet.birthDate);
 
   // Fetch recent activities for this pet using hook
   const {
@@ -111,14 +111,14 @@ export function PetProfile({
                 Born
               </span>
               <span className="text-sm text-orange-900">
-                {new Date(pet.birth_date).toLocaleDateString()}
+                {new Date(pet.birthDate).toLocaleDateString()}
               </span>
             </div>
 
-            {pet.weight_kg && (
+            {pet.weightKg && (
               <div className="flex items-center justify-between">
                 <span className="text-sm font-medium text-orange-700">Weight</span>
-                <span className="text-sm text-orange-900">{pet.weight_kg} kg</span>
+                <span className="text-sm text-orange-900">{pet.weightKg} kg</span>
               </div>
             )}
           </CardContent>
@@ -36,7 +36,7 @@ export function PetProfileHeader({
   showEditButton = true,
   size = 'full',
 }: PetProfileHeaderProps) {
-  const age = calculateAge(pet.birth_date);
+  const age = calculateAge(pet.birthDate);
   const isCompact = size === 'compact';
 
   return (
@@ -101,12 +101,12 @@ export function PetProfileHeader({
               </div>
 
               {/* Weight Card */}
-              {pet.weight_kg && (
+              {pet.weightKg && (
                 <div className="group bg-gradient-to-br from-white to-orange-50/30 rounded-xl p-5 border border-orange-100/50 hover:shadow-md hover:-translate-y-0.5 transition-all duration-200">
                   <div className="flex flex-col items-center text-center gap-2.5">
                     <Scale className="w-7 h-7 text-orange-500 group-hover:scale-110 transition-transform" />
                     <div>
-                      <p className="text-xl font-bold text-orange-900 mb-0.5">{pet.weight_kg} kg</p>
+                      <p className="text-xl font-bold text-orange-900 mb-0.5">{pet.weightKg} kg</p>
                       <p className="text-xs font-semibold uppercase tracking-wider text-orange-600">Weight</p>
                     </div>
                   </div>
@@ -185,13 +185,13 @@ export function PetProfileHeader({
               </div>
 
               {/* Additional Details */}
-              {(age || pet.breed || pet.weight_kg) && (
+              {(age || pet.breed || pet.weightKg) && (
                 <div className="flex items-center gap-2.5 text-xs text-orange-500">
                   <span>{age}</span>
-                  {(pet.breed || pet.weight_kg) && <span className="text-orange-300">•</span>}
+                  {(pet.breed || pet.weightKg) && <span className="text-orange-300">•</span>}
                   {pet.breed && <span>{pet.breed}</span>}
-                  {pet.breed && pet.weight_kg && <span className="text-orange-300">•</span>}
-                  {pet.weight_kg && <span>{pet.weight_kg}kg</span>}
+                  {pet.breed && pet.weightKg && <span className="text-orange-300">•</span>}
+                  {pet.weightKg && <span>{pet.weightKg}kg</span>}
                 </div>
               )}
             </div>
@@ -17,7 +17,7 @@ export function PetProfilePhoto({
   className,
   showPlaceholder = true,
 }: PetProfilePhotoProps) {
-  const { photoUrl, isLoading } = usePhotoState(pet.photo_path);
+  const { photoUrl, isLoading } = usePhotoState(pet.photoPath);
   const [showSkeleton, setShowSkeleton] = useState(false);
 
   // Show skeleton after 200ms delay to prevent flash
@@ -82,7 +82,7 @@ export function PetProfilePhoto({
 
       {/* Main photo */}
       <div className="absolute inset-0 flex items-center justify-center">
-        {pet.photo_path || !showPlaceholder ? (
+        {pet.photoPath || !showPlaceholder ? (
           <img
             src={photoUrl}
             alt={`Photo of ${pet.name}, a ${pet.species.toLowerCase()}`}
@@ -116,7 +116,7 @@ export function PetProfilePhoto({
       </div>
 
       {/* Pet status indicator */}
-      {pet.is_archived && (
+      {pet.isArchived && (
         <div
           className="absolute top-4 left-4 bg-gray-800/80 backdrop-blur-sm text-white text-sm px-3 py-1 rounded-full"
           role="status"
@@ -81,10 +81,10 @@ export function useCreateActivity() {
       // Convert ActivityFormData to ActivityCreateRequest format expected by backend
       const result = await invoke('create_activity', {
         activityData: {
-          pet_id: data.petId,
+          petId: data.petId,
           category: data.activityData.category,
           subcategory: data.activityData.subcategory,
-          activity_data: data.activityData.blocks,
+          activityData: data.activityData.blocks,
         },
       });
       return result as Activity;
@@ -97,12 +97,12 @@ export function useCreateActivity() {
       if (previousActivities) {
         const tempActivity: Activity = {
           id: tempId,
-          pet_id: petId,
+          petId,
           category: activityData.category,
           subcategory: activityData.subcategory || '',
-          activity_data: activityData.blocks || {},
-          created_at: new Date().toISOString(),
-          updated_at: new Date().toISOString(),
+          activityData: activityData.blocks || {},
+          createdAt: new Date().toISOString(),
+          updatedAt: new Date().toISOString(),
         };
 
         queryClient.setQueryData<Activity[]>(activityKeys.list(petId), [
@@ -154,11 +154,11 @@ export function useUpdateActivity() {
       const updateData: {
         category?: string;
         subcategory?: string;
-        activity_data?: Record<string, ActivityBlockData>;
+        activityData?: Record<string, ActivityBlockData>;
       } = {};
       if (data.updates.category) updateData.category = data.updates.category;
       if (data.updates.subcategory) updateData.subcategory = data.updates.subcategory;
-      if (data.updates.blocks) updateData.activity_data = data.updates.blocks;
+      if (data.updates.blocks) updateData.activityData = data.updates.blocks;
 
       console.log('📤 Sending update request:', {
         activityId: data.activityId,
@@ -188,7 +188,7 @@ export function useUpdateActivity() {
           activityKeys.list(petId),
           previousActivities.map(activity =>
             activity.id === activityId
-              ? { ...activity, ...updates, updated_at: new Date().toISOString() }
+              ? { ...activity, ...updates, updatedAt: new Date().toISOString() }
               : activity,
           ),
         );
@@ -198,7 +198,7 @@ export function useUpdateActivity() {
         queryClient.setQueryData<Activity>(activityKeys.detail(activityId), {
           ...previousActivity,
           ...updates,
-          updated_at: new Date().toISOString(),
+          updatedAt: new Date().toISOString(),
         });
       }
 
@@ -215,7 +215,7 @@ export function useUpdateActivity() {
     },
     onSuccess: (updatedActivity, { petId }) => {
       console.log('✅ Update successful, received activity:', updatedActivity);
-      console.log('✅ Activity data field:', updatedActivity.activity_data);
+      console.log('✅ Activity data field:', updatedActivity.activityData);
 
       // Update caches with server response
       queryClient.setQueryData<Activity[]>(activityKeys.list(petId), (old = []) => {
@@ -243,9 +243,9 @@ export function usePetProfileNavigation(
     // Preload images for adjacent pets
     preloadIndexes.forEach(index => {
       const pet = pets[index];
-      if (pet?.photo_path) {
+      if (pet?.photoPath) {
         const img = new Image();
-        img.src = pet.photo_path;
+        img.src = pet.photoPath;
       }
     });
   }, [activePetIndex, activePet, pets, totalPets, enableWrapping, preloadAdjacent]);
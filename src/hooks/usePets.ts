@@ -47,7 +47,7 @@ export function usePets(includeArchived = false): UsePetsState & UsePetsActions
 
       // Auto-select first pet if no active pet is selected and pets exist
       if (!activePetId && fetchedPets.length > 0) {
-        const firstActivePet = fetchedPets.find(pet => !pet.is_archived);
+        const firstActivePet = fetchedPets.find(pet => !pet.isArchived);
         if (firstActivePet) {
           setActivePetId(firstActivePet.id);
           // console.log('Auto-selected first pet:', firstActivePet.name);
@@ -101,7 +101,7 @@ export function usePets(includeArchived = false): UsePetsState & UsePetsActions
 
   // Navigation functions
   const selectFirstPet = () => {
-    const firstActivePet = pets.find(pet => !pet.is_archived);
+    const firstActivePet = pets.find(pet => !pet.isArchived);
     if (firstActivePet) {
       setActivePetId(firstActivePet.id);
       console.log('Selected first pet:', firstActivePet.name);
@@ -109,7 +109,7 @@ export function usePets(includeArchived = false): UsePetsState & UsePetsActions
   };
 
   const selectPetByIndex = (index: number) => {
-    const activePets = pets.filter(pet => !pet.is_archived);
+    const activePets = pets.filter(pet => !pet.isArchived);
     if (index >= 0 && index < activePets.length) {
       const selectedPet = activePets[index];
       setActivePetId(selectedPet.id);
@@ -223,7 +223,7 @@ export function usePhotoCache(): UsePhotoCacheReturn {
  * Preloads only visible photos and implements priority-based loading
  */
 export function usePreloadPetPhotos(
-  pets: Array<{ photo_path?: string | null }>,
+  pets: Array<{ photoPath?: string | null }>,
   options: {
     maxPreload?: number;
     priorityCount?: number;
@@ -236,7 +236,7 @@ export function usePreloadPetPhotos(
 
   useEffect(() => {
     const validPaths = pets
-      .map(pet => pet.photo_path)
+      .map(pet => pet.photoPath)
       .filter((path): path is string => Boolean(path));
 
     if (validPaths.length === 0) return;
@@ -303,7 +303,7 @@ export function usePreloadPetPhotos(
  * Only preloads photos for pets currently in or near the viewport
  */
 export function useVisiblePetPhotos(
-  pets: Array<{ id: number; photo_path?: string | null }>,
+  pets: Array<{ id: number; photoPath?: string | null }>,
   containerRef: React.RefObject<HTMLElement>,
 ) {
   const { preloadPhoto } = usePhotoCache();
@@ -347,7 +347,7 @@ export function useVisiblePetPhotos(
   useEffect(() => {
     const visiblePets = pets.filter(pet => visiblePetIds.has(pet.id));
     const photosToPreload = visiblePets
-      .map(pet => pet.photo_path)
+      .map(pet => pet.photoPath)
       .filter((path): path is string => Boolean(path));
 
     photosToPreload.forEach(photoPath => {
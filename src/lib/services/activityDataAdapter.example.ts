@@ -173,7 +173,7 @@ export async function completeFlowExample() {
   console.log('Form data blocks:', formData.blocks);
   console.log('Typed data (backend auto-converts):', typedData);
 
-  // Step 4: Backend automatically updates Pet.weight_kg = 5.2 kg
+  // Step 4: Backend automatically updates Pet.weightKg = 5.2 kg
   // This happens in create_activity_with_side_effects() transaction
 
   return {
@@ -207,10 +207,10 @@ export async function completeFlowExample() {
  * const typedData = ActivityDataAdapter.toBackendFormat(blocks, subcategory);
  * await invoke('create_activity', {
  *   activityData: {
- *     pet_id: petId,
+ *     petId,
  *     category,
  *     subcategory,
- *     activity_data: typedData, // Send typed instead of blocks
+ *     activityData: typedData, // Send typed instead of blocks
  *   }
  * });
  * ```
@@ -16,46 +16,46 @@ export enum PetGender {
 export interface Pet {
   id: number;
   name: string;
-  birth_date: string; // ISO date string
+  birthDate: string; // ISO date string
   species: PetSpecies;
   gender: PetGender;
   breed?: string;
   color?: string;
-  weight_kg?: number;
-  photo_path?: string;
+  weightKg?: number;
+  photoPath?: string;
   notes?: string;
-  display_order: number;
-  is_archived: boolean;
-  created_at: string; // ISO datetime string
-  updated_at: string; // ISO datetime string
+  displayOrder: number;
+  isArchived: boolean;
+  createdAt: string; // ISO datetime string
+  updatedAt: string; // ISO datetime string
 }
 
 // Request interfaces for Tauri commands
 export interface PetCreateRequest {
   name: string;
-  birth_date: string; // ISO date string
+  birthDate: string; // ISO date string
   species: PetSpecies;
   gender: PetGender;
   breed?: string;
   color?: string;
-  weight_kg?: number;
-  photo_path?: string;
+  weightKg?: number;
+  photoPath?: string;
   notes?: string;
-  display_order?: number;
+  displayOrder?: number;
 }
 
 export interface PetUpdateRequest {
   name?: string;
-  birth_date?: string;
+  birthDate?: string;
   species?: PetSpecies;
   gender?: PetGender;
   breed?: string;
   color?: string;
-  weight_kg?: number;
-  photo_path?: string;
+  weightKg?: number;
+  photoPath?: string;
   notes?: string;
-  display_order?: number;
-  is_archived?: boolean;
+  displayOrder?: number;
+  isArchived?: boolean;
 }
 
 // Photo-related interfaces
@@ -84,6 +84,23 @@ export interface AppInfo {
   version: string;
 }
 
+// Start-up integrity report returned by initialize_app
+export type InitStatus = 'ok' | 'repaired' | 'needs_restore';
+
+export interface InitCheck {
+  name: string;
+  status: InitStatus;
+  message: string;
+}
+
+export interface InitReport {
+  status: InitStatus;
+  database_path: string;
+  photo_dir: string;
+  read_only: boolean;
+  checks: InitCheck[];
+}
+
 // Form schemas using Zod for validation
 export const petFormSchema = z.object({
   name: z
@@ -91,7 +108,7 @@ export const petFormSchema = z.object({
     .min(1, 'Pet name is required')
     .max(100, 'Pet name must be less than 100 characters')
     .trim(),
-  birth_date: z
+  birthDate: z
     .string()
     .min(1, 'Birth date is required')
     .refine(date => {
@@ -107,7 +124,7 @@ export const petFormSchema = z.object({
   }),
   breed: z.string().max(100, 'Breed must be less than 100 characters').optional(),
   color: z.string().max(50, 'Color must be less than 50 characters').optional(),
-  weight_kg: z
+  weightKg: z
     .number()
     .min(0.1, 'Weight must be at least 0.1 kg')
     .max(200, 'Weight must be less than 200 kg')
@@ -226,8 +243,8 @@ export interface AddPetPageState {
 
 // Utility types for common operations
 export type PetId = Pet['id'];
-export type PetWithoutTimestamps = Omit<Pet, 'created_at' | 'updated_at'>;
-export type RequiredPetFields = Required<Pick<Pet, 'name' | 'birth_date' | 'species' | 'gender'>>;
+export type PetWithoutTimestamps = Omit<Pet, 'createdAt' | 'updatedAt'>;
+export type RequiredPetFields = Required<Pick<Pet, 'name' | 'birthDate' | 'species' | 'gender'>>;
 
 // Constants for validation and UI
 export const SUPPORTED_IMAGE_FORMATS = [
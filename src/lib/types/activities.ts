@@ -454,12 +454,12 @@ export interface BlockProps<TConfig = unknown> {
 // Activity database record interface
 export interface ActivityRecord {
   id: number;
-  pet_id: number;
+  petId: number;
   category: string;
   subcategory: string;
-  activity_data: Record<string, ActivityBlockData>; // Contains blocks data as JSON
-  created_at: string;
-  updated_at: string;
+  activityData: Record<string, ActivityBlockData>; // Contains blocks data as JSON
+  createdAt: string;
+  updatedAt: string;
 }
 
 
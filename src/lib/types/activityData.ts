@@ -18,7 +18,7 @@ export type ActivityData =
 
 /**
  * Weight measurement activity (Growth category)
- * Automatically updates Pet.weight_kg when created
+ * Automatically updates Pet.weightKg when created
  */
 export interface WeightActivityData {
   type: 'Weight';
@@ -9,8 +9,8 @@ import {
  * Extract display title from activity data
  */
 export function getActivityTitle(activity: ActivityRecord): string {
-  // Try to get title from activity_data first, fallback to subcategory
-  const titleBlock = activity.activity_data?.title;
+  // Try to get title from activityData first, fallback to subcategory
+  const titleBlock = activity.activityData?.title;
   const title = (titleBlock && typeof titleBlock === 'object' && 'value' in titleBlock)
     ? titleBlock.value
     : titleBlock;
@@ -21,8 +21,8 @@ export function getActivityTitle(activity: ActivityRecord): string {
  * Extract display description from activity data
  */
 export function getActivityDescription(activity: ActivityRecord): string {
-  // Try to get description from activity_data
-  const notesBlock = activity.activity_data?.notes;
+  // Try to get description from activityData
+  const notesBlock = activity.activityData?.notes;
   const description = (notesBlock && typeof notesBlock === 'object' && 'value' in notesBlock)
     ? notesBlock.value
     : notesBlock;
@@ -33,8 +33,8 @@ export function getActivityDescription(activity: ActivityRecord): string {
  * Extract activity date from activity data
  */
 export function getActivityDate(activity: ActivityRecord): Date {
-  // Try to get date from activity_data first, fallback to created_at
-  const timeBlock = activity.activity_data?.time;
+  // Try to get date from activityData first, fallback to createdAt
+  const timeBlock = activity.activityData?.time;
 
   // Handle the database format: { date: "ISO string", time: "", timezone: "" }
   if (timeBlock && typeof timeBlock === 'object' && 'date' in timeBlock && typeof timeBlock.date === 'string') {
@@ -44,7 +44,7 @@ export function getActivityDate(activity: ActivityRecord): Date {
   // Fallback to other formats
   const activityDate = (timeBlock && typeof timeBlock === 'object' && 'value' in timeBlock)
     ? timeBlock.value
-    : timeBlock || activity.created_at;
+    : timeBlock || activity.createdAt;
   return new Date(activityDate as string | number | Date);
 }
 
@@ -179,7 +179,7 @@ function extractThumbnails(blocks: Record<string, ActivityBlockData>, limit = 3)
 function hasHealthFlag(activity: ActivityRecord): boolean {
   if (activity.category === 'Health') return true;
 
-  const healthData = activity.activity_data?.health;
+  const healthData = activity.activityData?.health;
   if (healthData && typeof healthData === 'object') {
     return ('urgent' in healthData && healthData.urgent === true) ||
            ('flag' in healthData && healthData.flag === true);
@@ -192,7 +192,7 @@ function hasHealthFlag(activity: ActivityRecord): boolean {
  * Determines if activity is pinned
  */
 function isPinned(activity: ActivityRecord): boolean {
-  const pinnedData = activity.activity_data?.pinned;
+  const pinnedData = activity.activityData?.pinned;
   return typeof pinnedData === 'boolean' && pinnedData === true;
 }
 
@@ -201,12 +201,12 @@ function isPinned(activity: ActivityRecord): boolean {
  */
 export function convertActivitiesToTimelineItems(activities: ActivityRecord[]): ActivityTimelineItem[] {
   return activities.map((activity): ActivityTimelineItem => {
-    // activity_data is the blocks Record itself, not nested under .blocks
-    const blocks = activity.activity_data || {};
+    // activityData is the blocks Record itself, not nested under .blocks
+    const blocks = activity.activityData || {};
 
     return {
       id: activity.id,
-      petId: activity.pet_id,
+      petId: activity.petId,
       category: activity.category as ActivityCategory,
       subcategory: activity.subcategory,
       title: getActivityTitle(activity),
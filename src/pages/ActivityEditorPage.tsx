@@ -140,10 +140,10 @@ function ActivityEditorPageContent({
   // Convert ActivityRecord to ActivityFormData if editing
   const initialData: Partial<ActivityFormData> | undefined = activity
     ? {
-        petId: activity.pet_id,
+        petId: activity.petId,
         category: activity.category as ActivityCategory, // Category conversion from database
         subcategory: activity.subcategory,
-        blocks: convertBlocksFromDatabase(activity.activity_data || {}),
+        blocks: convertBlocksFromDatabase(activity.activityData || {}),
       }
     : undefined;
 
@@ -130,14 +130,14 @@ export function AddPetPage() {
         // Prepare form data
         const formData: PetCreateRequest = {
           name: data.name,
-          birth_date: data.birth_date,
+          birthDate: data.birthDate,
           species: data.species,
           gender: data.gender,
           breed: data.breed || undefined,
           color: data.color || undefined,
-          weight_kg: typeof data.weight_kg === 'number' ? data.weight_kg : undefined,
+          weightKg: typeof data.weightKg === 'number' ? data.weightKg : undefined,
           notes: data.notes || undefined,
-          photo_path: photoPath,
+          photoPath: photoPath,
         };
 
         console.log('Creating new pet:', formData);
@@ -282,15 +282,15 @@ export function AddPetPage() {
 
                   {/* Birth Date */}
                   <div className="space-y-2">
-                    <Label htmlFor="birth_date">Birth Date *</Label>
+                    <Label htmlFor="birthDate">Birth Date *</Label>
                     <Input
-                      id="birth_date"
+                      id="birthDate"
                       type="date"
-                      {...register('birth_date')}
-                      className={cn(errors.birth_date && 'border-red-500')}
+                      {...register('birthDate')}
+                      className={cn(errors.birthDate && 'border-red-500')}
                     />
-                    {errors.birth_date && (
-                      <p className="text-sm text-red-600">{errors.birth_date.message}</p>
+                    {errors.birthDate && (
+                      <p className="text-sm text-red-600">{errors.birthDate.message}</p>
                     )}
                   </div>
 
@@ -394,19 +394,19 @@ export function AddPetPage() {
 
                   {/* Weight */}
                   <div className="space-y-2">
-                    <Label htmlFor="weight_kg">Weight (kg)</Label>
+                    <Label htmlFor="weightKg">Weight (kg)</Label>
                     <Input
-                      id="weight_kg"
+                      id="weightKg"
                       type="number"
                       step="0.1"
                       min="0"
                       max="200"
-                      {...register('weight_kg', { valueAsNumber: true })}
+                      {...register('weightKg', { valueAsNumber: true })}
                       placeholder="Enter weight in kg (optional)"
-                      className={cn(errors.weight_kg && 'border-red-500')}
+                      className={cn(errors.weightKg && 'border-red-500')}
                     />
-                    {errors.weight_kg && (
-                      <p className="text-sm text-red-600">{errors.weight_kg.message}</p>
+                    {errors.weightKg && (
+                      <p className="text-sm text-red-600">{errors.weightKg.message}</p>
                     )}
                   </div>
 
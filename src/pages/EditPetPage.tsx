@@ -65,7 +65,7 @@ export function EditPetPage() {
   const currentPet = petId ? pets.find(p => p.id === parseInt(petId, 10)) : null;
 
   // Use photo state hook to get the correct photo URL
-  const { photoUrl: existingPhotoUrl } = usePhotoState(currentPet?.photo_path);
+  const { photoUrl: existingPhotoUrl } = usePhotoState(currentPet?.photoPath);
 
   const {
     register,
@@ -81,9 +81,9 @@ export function EditPetPage() {
       species: PetSpecies.Dog,
       breed: '',
       gender: PetGender.Male,
-      birth_date: '',
+      birthDate: '',
       color: '',
-      weight_kg: undefined,
+      weightKg: undefined,
       notes: '',
     },
   });
@@ -96,9 +96,9 @@ export function EditPetPage() {
         species: currentPet.species,
         breed: currentPet.breed || '',
         gender: currentPet.gender,
-        birth_date: currentPet.birth_date,
+        birthDate: currentPet.birthDate,
         color: currentPet.color || '',
-        weight_kg: currentPet.weight_kg || undefined,
+        weightKg: currentPet.weightKg || undefined,
         notes: currentPet.notes || '',
       });
     }
@@ -156,7 +156,7 @@ export function EditPetPage() {
       setError(null);
 
       // Upload photo first if selected
-      let photoPath = currentPet.photo_path;
+      let photoPath = currentPet.photoPath;
       if (selectedPhotoFile) {
         photoPath = await uploadPhoto(selectedPhotoFile);
       }
@@ -167,11 +167,11 @@ export function EditPetPage() {
         species: data.species,
         breed: data.breed || undefined,
         gender: data.gender,
-        birth_date: data.birth_date,
+        birthDate: data.birthDate,
         color: data.color || undefined,
-        weight_kg: data.weight_kg || undefined,
+        weightKg: data.weightKg || undefined,
         notes: data.notes || undefined,
-        photo_path: photoPath || undefined,
+        photoPath: photoPath || undefined,
       };
 
       // Update pet
@@ -418,31 +418,31 @@ export function EditPetPage() {
 
                   {/* Birth Date */}
                   <div className="space-y-2">
-                    <Label htmlFor="birth_date">Birth Date *</Label>
+                    <Label htmlFor="birthDate">Birth Date *</Label>
                     <Input
-                      id="birth_date"
+                      id="birthDate"
                       type="date"
-                      {...register('birth_date')}
-                      className={cn(errors.birth_date && 'border-red-300 focus:border-red-500')}
+                      {...register('birthDate')}
+                      className={cn(errors.birthDate && 'border-red-300 focus:border-red-500')}
                     />
-                    {errors.birth_date && (
-                      <p className="text-sm text-red-600">{errors.birth_date.message}</p>
+                    {errors.birthDate && (
+                      <p className="text-sm text-red-600">{errors.birthDate.message}</p>
                     )}
                   </div>
 
                   {/* Weight */}
                   <div className="space-y-2">
-                    <Label htmlFor="weight_kg">Weight (kg)</Label>
+                    <Label htmlFor="weightKg">Weight (kg)</Label>
                     <Input
-                      id="weight_kg"
+                      id="weightKg"
                       type="number"
                       step="0.1"
                       placeholder="Enter weight"
-                      {...register('weight_kg', { valueAsNumber: true })}
-                      className={cn(errors.weight_kg && 'border-red-300 focus:border-red-500')}
+                      {...register('weightKg', { valueAsNumber: true })}
+                      className={cn(errors.weightKg && 'border-red-300 focus:border-red-500')}
                     />
-                    {errors.weight_kg && (
-                      <p className="text-sm text-red-600">{errors.weight_kg.message}</p>
+                    {errors.weightKg && (
+                      <p className="text-sm text-red-600">{errors.weightKg.message}</p>
                     )}
                   </div>
                 </div>
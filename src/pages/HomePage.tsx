@@ -35,7 +35,7 @@ export function HomePage() {
   };
 
   // Filter out archived pets
-  const activePets = pets.filter(p => !p.is_archived);
+  const activePets = pets.filter(p => !p.isArchived);
 
   // Loading state - show skeleton only during initial load
   if (isLoading) {